@@ -2,9 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::client::cover_traffic_stream::LoopCoverTrafficStream;
+use crate::client::events::{self, ClientEventSender};
 use crate::client::inbound_messages::{InputMessage, InputMessageReceiver, InputMessageSender};
 use crate::client::key_manager::KeyManager;
 use crate::client::mix_traffic::{BatchMixMessageSender, MixTrafficController};
+use crate::client::path_health::{
+    self, NodeScores, PathHealthProber, ReceivedProbesReceiver, ReceivedProbesSender,
+};
 use crate::client::real_messages_control;
 use crate::client::real_messages_control::RealMessagesController;
 use crate::client::received_buffer::{
@@ -15,6 +19,8 @@ use crate::client::replies::reply_controller::{ReplyControllerReceiver, ReplyCon
 use crate::client::replies::reply_storage::{
     CombinedReplyStorage, PersistentReplyStorage, ReplyStorageBackend, SentReplyKeys,
 };
+#[cfg(feature = "chain-topology")]
+use crate::client::topology_control::ChainQueryClient;
 use crate::client::topology_control::{
     TopologyAccessor, TopologyRefresher, TopologyRefresherConfig,
 };
@@ -29,12 +35,12 @@ use gateway_client::{
     AcknowledgementReceiver, AcknowledgementSender, GatewayClient, MixnetMessageReceiver,
     MixnetMessageSender,
 };
-use log::{debug, info};
+use log::{debug, info, warn};
 use nymsphinx::acknowledgements::AckKey;
 use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::addressing::nodes::NodeIdentity;
+use nymsphinx::chunking::RedundancyRatio;
 use std::sync::Arc;
-use std::time::Duration;
 use tap::TapFallible;
 use task::{TaskClient, TaskManager};
 use url::Url;
@@ -90,6 +96,10 @@ pub struct BaseClientBuilder<'a, B> {
 
     bandwidth_controller: Option<BandwidthController>,
     key_manager: KeyManager,
+    client_event_sender: Option<ClientEventSender>,
+
+    #[cfg(feature = "chain-topology")]
+    chain_topology_client: Option<ChainQueryClient>,
 }
 
 impl<'a, B> BaseClientBuilder<'a, B>
@@ -110,6 +120,9 @@ where
             bandwidth_controller,
             reply_storage_backend,
             key_manager,
+            client_event_sender: None,
+            #[cfg(feature = "chain-topology")]
+            chain_topology_client: None,
         }
     }
 
@@ -130,9 +143,28 @@ where
             reply_storage_backend,
             bandwidth_controller,
             key_manager,
+            client_event_sender: None,
+            #[cfg(feature = "chain-topology")]
+            chain_topology_client: None,
         }
     }
 
+    /// Makes the client announce message deliveries and changes in the gateway connection state
+    /// on the provided channel.
+    #[must_use]
+    pub fn with_event_sender(mut self, client_event_sender: ClientEventSender) -> Self {
+        self.client_event_sender = Some(client_event_sender);
+        self
+    }
+
+    /// Makes the mixnet contract one of the sources used for verifying the network topology.
+    #[cfg(feature = "chain-topology")]
+    #[must_use]
+    pub fn with_chain_topology_client(mut self, chain_topology_client: ChainQueryClient) -> Self {
+        self.chain_topology_client = Some(chain_topology_client);
+        self
+    }
+
     pub fn as_mix_recipient(&self) -> Recipient {
         Recipient::new(
             *self.key_manager.identity_keypair().public_key(),
@@ -145,29 +177,46 @@ where
 
     // future constantly pumping loop cover traffic at some specified average rate
     // the pumped traffic goes to the MixTrafficController
+    // the path health probes, if enabled, are sent in place of some of the cover messages
+    #[allow(clippy::too_many_arguments)]
     fn start_cover_traffic_stream(
         debug_config: &DebugConfig,
         ack_key: Arc<AckKey>,
         self_address: Recipient,
         topology_accessor: TopologyAccessor,
         mix_tx: BatchMixMessageSender,
+        probe_receiver: Option<ReceivedProbesReceiver>,
+        node_scores: NodeScores,
         shutdown: TaskClient,
     ) {
         info!("Starting loop cover traffic stream...");
 
         let mut stream = LoopCoverTrafficStream::new(
-            ack_key,
+            ack_key.clone(),
             debug_config.average_ack_delay,
             debug_config.average_packet_delay,
             debug_config.loop_cover_traffic_average_delay,
             mix_tx,
             self_address,
-            topology_accessor,
+            topology_accessor.clone(),
         );
 
-        if let Some(size) = debug_config.use_extended_packet_size {
-            log::debug!("Setting extended packet size: {:?}", size);
-            stream.set_custom_packet_size(size.into());
+        if let Some(probe_receiver) = probe_receiver {
+            info!("Enabling path health probing...");
+            let prober = PathHealthProber::new(
+                path_health::Config::new(debug_config, ack_key, self_address),
+                topology_accessor,
+                node_scores,
+            );
+            stream.set_path_health_prober(prober, probe_receiver);
+        }
+
+        let (packet_size, extended_packet_size) = debug_config.cover_packet_sizes();
+        log::debug!("Using packet size of {packet_size:?} for cover traffic");
+        stream.set_custom_packet_size(packet_size);
+        if let Some((size, ratio)) = extended_packet_size {
+            log::debug!("Using packet size of {size:?} for {ratio} of the cover traffic");
+            stream.set_extended_packet_size(size, ratio);
         }
 
         stream.start_with_shutdown(shutdown);
@@ -185,6 +234,7 @@ where
         reply_controller_receiver: ReplyControllerReceiver,
         lane_queue_lengths: LaneQueueLengths,
         client_connection_rx: ConnectionCommandReceiver,
+        client_event_sender: Option<ClientEventSender>,
         shutdown: TaskClient,
     ) {
         info!("Starting real traffic stream...");
@@ -200,6 +250,7 @@ where
             reply_controller_receiver,
             lane_queue_lengths,
             client_connection_rx,
+            client_event_sender,
         )
         .start_with_shutdown(shutdown);
     }
@@ -212,6 +263,7 @@ where
         mixnet_receiver: MixnetMessageReceiver,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        probe_sender: Option<ReceivedProbesSender>,
         shutdown: TaskClient,
     ) {
         info!("Starting received messages buffer controller...");
@@ -221,6 +273,7 @@ where
             mixnet_receiver,
             reply_key_storage,
             reply_controller_sender,
+            probe_sender,
         )
         .start_with_shutdown(shutdown)
     }
@@ -269,6 +322,15 @@ where
 
         gateway_client.set_disabled_credentials_mode(self.disabled_credentials);
 
+        if let Some(client_event_sender) = &self.client_event_sender {
+            let (connection_event_sender, connection_event_receiver) = mpsc::unbounded();
+            gateway_client.with_connection_event_sender(connection_event_sender);
+            spawn_future(events::forward_gateway_connection_events(
+                connection_event_receiver,
+                client_event_sender.clone(),
+            ));
+        }
+
         gateway_client
             .authenticate_and_start()
             .await
@@ -281,18 +343,47 @@ where
     // future responsible for periodically polling directory server and updating
     // the current global view of topology
     async fn start_topology_refresher(
-        nym_api_urls: Vec<Url>,
-        refresh_rate: Duration,
+        &mut self,
         topology_accessor: TopologyAccessor,
         shutdown: TaskClient,
     ) -> Result<(), ClientCoreError<B>> {
-        let topology_refresher_config = TopologyRefresherConfig::new(
-            nym_api_urls,
-            refresh_rate,
+        let mut topology_refresher_config = TopologyRefresherConfig::new(
+            self.nym_api_endpoints.clone(),
+            self.debug_config.topology_refresh_rate,
             env!("CARGO_PKG_VERSION").to_string(),
-        );
+        )
+        .with_topology_stream(!self.debug_config.disable_topology_stream);
+        if let Some(quorum) = self.debug_config.topology_verification_quorum {
+            topology_refresher_config = topology_refresher_config.with_verification_quorum(quorum);
+        }
+        if self.debug_config.verify_topology_signatures {
+            topology_refresher_config = topology_refresher_config
+                .with_signed_topology(self.debug_config.trusted_topology_signers.clone());
+        }
+
         let mut topology_refresher =
             TopologyRefresher::new(topology_refresher_config, topology_accessor);
+
+        #[cfg(feature = "chain-topology")]
+        if let Some(chain_topology_client) = self.chain_topology_client.take() {
+            topology_refresher.set_chain_client(chain_topology_client);
+        }
+
+        if let Some(quorum) = self.debug_config.topology_verification_quorum {
+            let sources = topology_refresher.available_topology_sources();
+            if self.debug_config.verify_topology_against_chain
+                && sources == self.nym_api_endpoints.len()
+            {
+                // the mixnet contract client has not been provided
+                log::warn!(
+                    "The mixnet contract is not available as a source of the network topology"
+                );
+            }
+            if quorum == 0 || quorum > sources {
+                return Err(ClientCoreError::UnreachableTopologyQuorum { quorum, sources });
+            }
+        }
+
         // before returning, block entire runtime to refresh the current network view so that any
         // components depending on topology would see a non-empty view
         info!("Obtaining initial network topology");
@@ -375,6 +466,19 @@ where
 
         let self_address = self.as_mix_recipient();
 
+        // channels for passing the path health probes that made it back to us.
+        // The probes are sent in place of loop cover messages, so they need the cover traffic
+        let (probe_sender, probe_receiver) = if self.debug_config.disable_path_health_probing {
+            (None, None)
+        } else if self.debug_config.disable_loop_cover_traffic_stream {
+            warn!("path health probing requires the loop cover traffic stream, so it's not going to be enabled");
+            (None, None)
+        } else {
+            let (probe_sender, probe_receiver) = mpsc::unbounded();
+            (Some(probe_sender), Some(probe_receiver))
+        };
+        let node_scores = NodeScores::new();
+
         // the components are started in very specific order. Unless you know what you are doing,
         // do not change that.
         let gateway_client = self
@@ -387,13 +491,8 @@ where
         )
         .await?;
 
-        Self::start_topology_refresher(
-            self.nym_api_endpoints.clone(),
-            self.debug_config.topology_refresh_rate,
-            shared_topology_accessor.clone(),
-            task_manager.subscribe(),
-        )
-        .await?;
+        self.start_topology_refresher(shared_topology_accessor.clone(), task_manager.subscribe())
+            .await?;
 
         Self::start_received_messages_buffer_controller(
             self.key_manager.encryption_keypair(),
@@ -401,6 +500,7 @@ where
             mixnet_messages_receiver,
             reply_storage.key_storage(),
             reply_controller_sender.clone(),
+            probe_sender,
             task_manager.subscribe(),
         );
 
@@ -430,6 +530,28 @@ where
             controller_config.set_custom_packet_size(size.into());
         }
 
+        let packet_size_buckets = self.debug_config.packet_size_buckets();
+        if !packet_size_buckets.is_empty() {
+            log::debug!("Enabling adaptive packet sizes: {:?}", packet_size_buckets);
+            controller_config.set_packet_size_buckets(packet_size_buckets);
+        }
+
+        let (cover_packet_size, extended_cover_packet_size) =
+            self.debug_config.cover_packet_sizes();
+        controller_config.set_cover_packet_sizes(cover_packet_size, extended_cover_packet_size);
+
+        if let Some(ratio) = self.debug_config.erasure_coding_redundancy {
+            log::debug!("Enabling erasure coding with redundancy ratio of {ratio}");
+            let redundancy = RedundancyRatio::new(ratio)
+                .map_err(|_| ClientCoreError::InvalidErasureCodingRedundancy(ratio))?;
+            controller_config.set_erasure_coding(redundancy);
+        }
+
+        if let Some(compression) = self.debug_config.message_compression {
+            log::debug!("Enabling message compression: {:?}", compression);
+            controller_config.set_compression(compression.into());
+        }
+
         Self::start_real_traffic_controller(
             controller_config,
             shared_topology_accessor.clone(),
@@ -441,6 +563,7 @@ where
             reply_controller_receiver,
             shared_lane_queue_lengths.clone(),
             client_connection_rx,
+            self.client_event_sender.take(),
             task_manager.subscribe(),
         );
 
@@ -451,6 +574,8 @@ where
                 self_address,
                 shared_topology_accessor,
                 sphinx_message_sender,
+                probe_receiver,
+                node_scores.clone(),
                 task_manager.subscribe(),
             );
         }
@@ -472,6 +597,7 @@ where
                 },
             },
             reply_controller_sender,
+            node_scores,
             task_manager,
         })
     }
@@ -484,5 +610,9 @@ pub struct BaseClient {
     // it feels very wrong to put this channel here, but I can't think of any other way of passing it to the native client
     pub reply_controller_sender: ReplyControllerSender,
 
+    /// Reliability of the mixnodes as determined by the path health probes.
+    /// It stays empty if the probing is disabled.
+    pub node_scores: NodeScores,
+
     pub task_manager: TaskManager,
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::client::mix_traffic::BatchMixMessageSender;
+use crate::client::path_health::{self, PathHealthProber, ReceivedProbesReceiver};
 use crate::client::topology_control::TopologyAccessor;
 use crate::spawn_future;
 use futures::task::{Context, Poll};
@@ -63,6 +64,16 @@ where
 
     /// Predefined packet size used for the loop cover messages.
     packet_size: PacketSize,
+
+    /// If set, the given fraction of the loop cover messages is going to use this (extended)
+    /// packet size instead, so that the cover traffic would resemble the real traffic.
+    extended_packet_size: Option<(PacketSize, f64)>,
+
+    /// If set, some of the loop cover messages are going to be replaced by its path health probes.
+    path_health_prober: Option<PathHealthProber>,
+
+    /// Channel used for receiving ids of the probes that made it back to us.
+    probe_receiver: Option<ReceivedProbesReceiver>,
 }
 
 impl<R> Stream for LoopCoverTrafficStream<R>
@@ -104,6 +115,19 @@ where
     }
 }
 
+/// Chooses the size of the next loop cover packet. Unless the extended packet size is set,
+/// alongside the fraction of the cover packets that should use it, it's always the predefined one.
+pub(crate) fn choose_cover_packet_size<R: Rng>(
+    rng: &mut R,
+    packet_size: PacketSize,
+    extended_packet_size: Option<(PacketSize, f64)>,
+) -> PacketSize {
+    match extended_packet_size {
+        Some((extended, ratio)) if rng.gen_bool(ratio) => extended,
+        _ => packet_size,
+    }
+}
+
 // obviously when we finally make shared rng that is on 'higher' level, this should become
 // generic `R`
 impl LoopCoverTrafficStream<OsRng> {
@@ -136,6 +160,9 @@ impl LoopCoverTrafficStream<OsRng> {
             rng,
             topology_access,
             packet_size: Default::default(),
+            extended_packet_size: None,
+            path_health_prober: None,
+            probe_receiver: None,
         }
     }
 
@@ -143,6 +170,19 @@ impl LoopCoverTrafficStream<OsRng> {
         self.packet_size = packet_size;
     }
 
+    pub fn set_extended_packet_size(&mut self, packet_size: PacketSize, ratio: f64) {
+        self.extended_packet_size = Some((packet_size, ratio));
+    }
+
+    pub(crate) fn set_path_health_prober(
+        &mut self,
+        prober: PathHealthProber,
+        probe_receiver: ReceivedProbesReceiver,
+    ) {
+        self.path_health_prober = Some(prober);
+        self.probe_receiver = Some(probe_receiver);
+    }
+
     fn set_next_delay(&mut self, amount: Duration) {
         #[cfg(not(target_arch = "wasm32"))]
         let next_delay = Box::pin(time::sleep(amount));
@@ -156,6 +196,12 @@ impl LoopCoverTrafficStream<OsRng> {
     async fn on_new_message(&mut self) {
         trace!("next cover message!");
 
+        // this has to happen before we obtain the topology permit as it might need to update
+        // the mix weights
+        if let Some(prober) = self.path_health_prober.as_mut() {
+            prober.expire_stale_probes().await;
+        }
+
         // TODO for way down the line: in very rare cases (during topology update) we might have
         // to wait a really tiny bit before actually obtaining the permit hence messing with our
         // poisson delay, but is it really a problem?
@@ -172,18 +218,37 @@ impl LoopCoverTrafficStream<OsRng> {
             }
         };
 
-        let cover_message = generate_loop_cover_packet(
-            &mut self.rng,
-            topology_ref,
-            &self.ack_key,
-            &self.our_full_destination,
-            self.average_ack_delay,
-            self.average_packet_delay,
-            self.packet_size,
-        )
-        .expect("Somehow failed to generate a loop cover message with a valid topology");
+        let packet_size =
+            choose_cover_packet_size(&mut self.rng, self.packet_size, self.extended_packet_size);
+
+        // if it's time for a path health probe, it takes the place of this cover message
+        let probe = self
+            .path_health_prober
+            .as_mut()
+            .and_then(|prober| prober.try_prepare_probe(&mut self.rng, topology_ref, packet_size));
+
+        let (probe_id, cover_message) = match probe {
+            Some((probe_id, probe)) => (Some(probe_id), probe),
+            None => (
+                None,
+                generate_loop_cover_packet(
+                    &mut self.rng,
+                    topology_ref,
+                    &self.ack_key,
+                    &self.our_full_destination,
+                    self.average_ack_delay,
+                    self.average_packet_delay,
+                    packet_size,
+                )
+                .expect("Somehow failed to generate a loop cover message with a valid topology"),
+            ),
+        };
 
         if let Err(err) = self.mix_tx.try_send(vec![cover_message]) {
+            // don't penalise the nodes for our own congestion
+            if let (Some(prober), Some(probe_id)) = (self.path_health_prober.as_mut(), probe_id) {
+                prober.abandon_probe(probe_id)
+            }
             match err {
                 TrySendError::Full(_) => {
                     // This isn't a problem, if the channel is full means we're already sending the
@@ -219,6 +284,8 @@ impl LoopCoverTrafficStream<OsRng> {
             sample_poisson_duration(&mut self.rng, self.average_cover_message_sending_delay);
         self.set_next_delay(sampled);
 
+        let mut probe_receiver = self.probe_receiver.take();
+
         spawn_future(async move {
             debug!("Started LoopCoverTrafficStream with graceful shutdown support");
 
@@ -236,6 +303,17 @@ impl LoopCoverTrafficStream<OsRng> {
                             break;
                         }
                     }
+                    probe_id = path_health::next_received_probe(&mut probe_receiver) => match probe_id {
+                        Some(probe_id) => {
+                            if let Some(prober) = self.path_health_prober.as_mut() {
+                                prober.on_probe_received(probe_id).await
+                            }
+                        }
+                        None => {
+                            log::trace!("LoopCoverTrafficStream: No longer receiving path health probes since channel closed");
+                            probe_receiver = None;
+                        }
+                    }
                 }
             }
             shutdown.recv_timeout().await;
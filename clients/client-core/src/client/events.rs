@@ -0,0 +1,38 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gateway_client::{GatewayConnectionEvent, GatewayConnectionEventReceiver};
+
+pub type ClientEventSender = mpsc::UnboundedSender<ClientEvent>;
+pub type ClientEventReceiver = mpsc::UnboundedReceiver<ClientEvent>;
+
+/// Identifier attached to an `InputMessage` in order to get notified once it has been delivered.
+pub type MessageId = u64;
+
+/// Events happening in the base client that the attached application might want to react to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientEvent {
+    /// All packets of the message with the specified id have been acknowledged by the recipient.
+    MessageDelivered(MessageId),
+
+    /// State of the connection with the gateway has changed.
+    GatewayConnection(GatewayConnectionEvent),
+}
+
+// forwards gateway-client specific events into the generic client event channel
+pub(crate) async fn forward_gateway_connection_events(
+    mut gateway_events: GatewayConnectionEventReceiver,
+    client_events: ClientEventSender,
+) {
+    while let Some(event) = gateway_events.next().await {
+        if client_events
+            .unbounded_send(ClientEvent::GatewayConnection(event))
+            .is_err()
+        {
+            log::debug!("the client event receiver has been dropped");
+            break;
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use crate::client::events::MessageId;
 use client_connections::TransmissionLane;
 use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nymsphinx::params::PacketSize;
 
 pub type InputMessageSender = tokio::sync::mpsc::Sender<InputMessage>;
 pub type InputMessageReceiver = tokio::sync::mpsc::Receiver<InputMessage>;
@@ -15,6 +17,22 @@ pub enum InputMessage {
         recipient: Recipient,
         data: Vec<u8>,
         lane: TransmissionLane,
+
+        /// Preferred size of the sphinx packets used for this message. It is mapped onto one of
+        /// the configured packet sizes. If not specified, the client is going to choose it on its own.
+        packet_size: Option<PacketSize>,
+
+        /// If specified, the client is going to emit `ClientEvent::MessageDelivered` with this id
+        /// once all packets of the message are acknowledged.
+        message_id: Option<MessageId>,
+
+        /// Whether the recipient is known to support compressed messages, in which case the message
+        /// is going to be compressed with the configured algorithm (if any).
+        compress: bool,
+
+        /// Whether the message should be protected with forward error correction (if the client
+        /// has the erasure coding redundancy configured). The recipient has to support it.
+        erasure_coding: bool,
     },
 
     /// Creates a message used for a duplex anonymous communication where the recipient
@@ -30,10 +48,28 @@ pub enum InputMessage {
         data: Vec<u8>,
         reply_surbs: u32,
         lane: TransmissionLane,
+
+        /// Preferred size of the sphinx packets used for this message. It is mapped onto one of
+        /// the configured packet sizes. If not specified, the client is going to choose it on its own.
+        packet_size: Option<PacketSize>,
+
+        /// If specified, the client is going to emit `ClientEvent::MessageDelivered` with this id
+        /// once all packets of the message are acknowledged.
+        message_id: Option<MessageId>,
+
+        /// Whether the recipient is known to support compressed messages, in which case the message
+        /// is going to be compressed with the configured algorithm (if any).
+        compress: bool,
+
+        /// Whether the message should be protected with forward error correction (if the client
+        /// has the erasure coding redundancy configured). The recipient has to support it.
+        erasure_coding: bool,
     },
 
     /// Attempt to use our internally received and stored `ReplySurb` to send the message back
     /// to specified recipient whilst not knowing its full identity (or even gateway).
+    /// Note that replies are always sent using the default packet size of the client
+    /// and their delivery is not tracked.
     ///
     /// Ends up with `NymMessage::Reply` variant
     Reply {
@@ -49,6 +85,10 @@ impl InputMessage {
             recipient,
             data,
             lane,
+            packet_size: None,
+            message_id: None,
+            compress: false,
+            erasure_coding: false,
         }
     }
 
@@ -63,6 +103,10 @@ impl InputMessage {
             data,
             reply_surbs,
             lane,
+            packet_size: None,
+            message_id: None,
+            compress: false,
+            erasure_coding: false,
         }
     }
 
@@ -77,4 +121,57 @@ impl InputMessage {
             lane,
         }
     }
+
+    /// Specifies the preferred size of the sphinx packets used for sending this message.
+    /// Sizes that are not among the configured ones are mapped onto the closest configured size.
+    /// It has no effect on replies.
+    #[must_use]
+    pub fn with_packet_size(mut self, size: PacketSize) -> Self {
+        match &mut self {
+            InputMessage::Regular { packet_size, .. }
+            | InputMessage::Anonymous { packet_size, .. } => *packet_size = Some(size),
+            InputMessage::Reply { .. } => {}
+        }
+        self
+    }
+
+    /// Declares that the recipient is able to read compressed messages, allowing the client
+    /// to compress this message with its configured algorithm. Clients that don't support
+    /// the compression fail to read such messages. It has no effect on replies.
+    #[must_use]
+    pub fn with_compression(mut self) -> Self {
+        match &mut self {
+            InputMessage::Regular { compress, .. } | InputMessage::Anonymous { compress, .. } => {
+                *compress = true
+            }
+            InputMessage::Reply { .. } => {}
+        }
+        self
+    }
+
+    /// Requests protecting this message with forward error correction, using the redundancy ratio
+    /// configured for the client, so that it could get reconstructed even if some of its packets
+    /// got lost. Clients that don't support the erasure coding fail to read such messages.
+    /// It has no effect on replies or if the client has no redundancy ratio configured.
+    #[must_use]
+    pub fn with_erasure_coding(mut self) -> Self {
+        match &mut self {
+            InputMessage::Regular { erasure_coding, .. }
+            | InputMessage::Anonymous { erasure_coding, .. } => *erasure_coding = true,
+            InputMessage::Reply { .. } => {}
+        }
+        self
+    }
+
+    /// Requests the client to emit `ClientEvent::MessageDelivered` with the provided id once
+    /// the message is delivered. It has no effect on replies.
+    #[must_use]
+    pub fn with_message_id(mut self, id: MessageId) -> Self {
+        match &mut self {
+            InputMessage::Regular { message_id, .. }
+            | InputMessage::Anonymous { message_id, .. } => *message_id = Some(id),
+            InputMessage::Reply { .. } => {}
+        }
+        self
+    }
 }
@@ -10,6 +10,29 @@ use rand::{CryptoRng, RngCore};
 use std::io;
 use std::sync::Arc;
 
+use store_cipher::StoreCipher;
+
+#[cfg(not(target_arch = "wasm32"))]
+use rand::rngs::OsRng;
+
+#[cfg(target_arch = "wasm32")]
+use pemstore::traits::{PemStorableKey, PemStorableKeyPair};
+#[cfg(target_arch = "wasm32")]
+use wasm_storage::{stores, BrowserStorage, StorageError};
+#[cfg(target_arch = "wasm32")]
+use zeroize::Zeroize;
+
+// names under which the keys are kept in the browser storage
+#[cfg(target_arch = "wasm32")]
+mod browser_key_names {
+    pub(super) const PRIVATE_IDENTITY_KEY: &str = "private_identity_key";
+    pub(super) const PUBLIC_IDENTITY_KEY: &str = "public_identity_key";
+    pub(super) const PRIVATE_ENCRYPTION_KEY: &str = "private_encryption_key";
+    pub(super) const PUBLIC_ENCRYPTION_KEY: &str = "public_encryption_key";
+    pub(super) const GATEWAY_SHARED_KEY: &str = "gateway_shared_key";
+    pub(super) const ACK_KEY: &str = "ack_key";
+}
+
 // Note: to support key rotation in the future, all keys will require adding an extra smart pointer,
 // most likely an AtomicCell, or if it doesn't work as I think it does, a Mutex. Although I think
 // AtomicCell includes a Mutex implicitly if the underlying type does not work atomically.
@@ -128,6 +151,155 @@ impl KeyManager {
         Ok(())
     }
 
+    /// Checks whether the stored keys have been encrypted with a passphrase.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stored_keys_encrypted(client_pathfinder: &ClientKeyPathfinder) -> io::Result<bool> {
+        Ok(pemstore::read_kdf_info(client_pathfinder.private_identity_key())?.is_some())
+    }
+
+    /// Loads previously stored keys from the disk, decrypting them with a key derived from
+    /// the provided passphrase.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_encrypted_keys(
+        client_pathfinder: &ClientKeyPathfinder,
+        passphrase: &[u8],
+    ) -> io::Result<Self> {
+        let kdf_info = pemstore::read_kdf_info(client_pathfinder.private_identity_key())?
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the stored keys are not encrypted",
+                )
+            })?;
+        let cipher = StoreCipher::new_with_kdf_info(passphrase, kdf_info)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+
+        let identity_keypair: identity::KeyPair = pemstore::load_keypair_encrypted(
+            &pemstore::KeyPairPath::new(
+                client_pathfinder.private_identity_key().to_owned(),
+                client_pathfinder.public_identity_key().to_owned(),
+            ),
+            &cipher,
+        )?;
+        let encryption_keypair: encryption::KeyPair = pemstore::load_keypair_encrypted(
+            &pemstore::KeyPairPath::new(
+                client_pathfinder.private_encryption_key().to_owned(),
+                client_pathfinder.public_encryption_key().to_owned(),
+            ),
+            &cipher,
+        )?;
+
+        let gateway_shared_key: SharedKeys =
+            pemstore::load_key_encrypted(client_pathfinder.gateway_shared_key(), &cipher)?;
+
+        let ack_key: AckKey = pemstore::load_key_encrypted(client_pathfinder.ack_key(), &cipher)?;
+
+        Ok(KeyManager {
+            identity_keypair: Arc::new(identity_keypair),
+            encryption_keypair: Arc::new(encryption_keypair),
+            gateway_shared_key: Some(Arc::new(gateway_shared_key)),
+            ack_key: Arc::new(ack_key),
+        })
+    }
+
+    /// Loads the stored keys that are about to be encrypted alongside the cipher they should be
+    /// encrypted with, which is derived from the provided passphrase. If a previous encryption
+    /// got interrupted, some of the keys might have been encrypted already, in which case they're
+    /// decrypted and the cipher is derived with the same parameters they were encrypted with.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_keys_for_encryption(
+        client_pathfinder: &ClientKeyPathfinder,
+        passphrase: &[u8],
+    ) -> io::Result<(Self, StoreCipher)> {
+        let mut existing_kdf_info = None;
+        for key_path in [
+            client_pathfinder.private_encryption_key(),
+            client_pathfinder.ack_key(),
+            client_pathfinder.gateway_shared_key(),
+            client_pathfinder.private_identity_key(),
+        ] {
+            if let Some(kdf_info) = pemstore::read_kdf_info(key_path)? {
+                existing_kdf_info = Some(kdf_info);
+                break;
+            }
+        }
+
+        let cipher = match existing_kdf_info {
+            Some(kdf_info) => StoreCipher::new_with_kdf_info(passphrase, kdf_info),
+            None => StoreCipher::new(passphrase, &mut OsRng),
+        }
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+
+        let identity_keypair: identity::KeyPair = pemstore::load_keypair_possibly_encrypted(
+            &pemstore::KeyPairPath::new(
+                client_pathfinder.private_identity_key().to_owned(),
+                client_pathfinder.public_identity_key().to_owned(),
+            ),
+            &cipher,
+        )?;
+        let encryption_keypair: encryption::KeyPair = pemstore::load_keypair_possibly_encrypted(
+            &pemstore::KeyPairPath::new(
+                client_pathfinder.private_encryption_key().to_owned(),
+                client_pathfinder.public_encryption_key().to_owned(),
+            ),
+            &cipher,
+        )?;
+
+        let gateway_shared_key: SharedKeys =
+            pemstore::load_key_possibly_encrypted(client_pathfinder.gateway_shared_key(), &cipher)?;
+
+        let ack_key: AckKey =
+            pemstore::load_key_possibly_encrypted(client_pathfinder.ack_key(), &cipher)?;
+
+        let key_manager = KeyManager {
+            identity_keypair: Arc::new(identity_keypair),
+            encryption_keypair: Arc::new(encryption_keypair),
+            gateway_shared_key: Some(Arc::new(gateway_shared_key)),
+            ack_key: Arc::new(ack_key),
+        };
+        Ok((key_manager, cipher))
+    }
+
+    /// Stores all available keys on the disk, encrypting all the secret ones with the provided cipher.
+    /// Each key is written to a temporary file first and then moved into place.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn store_encrypted_keys(
+        &self,
+        client_pathfinder: &ClientKeyPathfinder,
+        cipher: &StoreCipher,
+    ) -> io::Result<()> {
+        pemstore::store_keypair_encrypted(
+            self.encryption_keypair.as_ref(),
+            &pemstore::KeyPairPath::new(
+                client_pathfinder.private_encryption_key().to_owned(),
+                client_pathfinder.public_encryption_key().to_owned(),
+            ),
+            cipher,
+        )?;
+
+        pemstore::store_key_encrypted(self.ack_key.as_ref(), client_pathfinder.ack_key(), cipher)?;
+
+        match self.gateway_shared_key.as_ref() {
+            None => warn!("No gateway shared key available to store!"),
+            Some(gate_key) => pemstore::store_key_encrypted(
+                gate_key.as_ref(),
+                client_pathfinder.gateway_shared_key(),
+                cipher,
+            )?,
+        }
+
+        // the identity key is used to determine whether the store is encrypted,
+        // so it's written last
+        pemstore::store_keypair_encrypted(
+            self.identity_keypair.as_ref(),
+            &pemstore::KeyPairPath::new(
+                client_pathfinder.private_identity_key().to_owned(),
+                client_pathfinder.public_identity_key().to_owned(),
+            ),
+            cipher,
+        )
+    }
+
     /// Gets an atomically reference counted pointer to [`identity::KeyPair`].
     pub fn identity_keypair(&self) -> Arc<identity::KeyPair> {
         Arc::clone(&self.identity_keypair)
@@ -158,3 +330,159 @@ impl KeyManager {
         Arc::clone(&self.ack_key)
     }
 }
+
+#[cfg(target_arch = "wasm32")]
+impl KeyManager {
+    async fn load_browser_key<T: PemStorableKey>(
+        storage: &dyn BrowserStorage,
+        cipher: &StoreCipher,
+        name: &str,
+    ) -> Result<Option<T>, StorageError> {
+        match wasm_storage::read_encrypted(storage, cipher, stores::KEYS, name).await? {
+            None => Ok(None),
+            Some(mut raw) => {
+                let key = T::from_bytes(&raw).map(Some).map_err(|err| {
+                    StorageError::malformed_data(stores::KEYS, name, err.to_string())
+                });
+                raw.zeroize();
+                key
+            }
+        }
+    }
+
+    async fn store_browser_key<T, R>(
+        storage: &dyn BrowserStorage,
+        cipher: &StoreCipher,
+        name: &str,
+        key: &T,
+        rng: &mut R,
+    ) -> Result<(), StorageError>
+    where
+        T: PemStorableKey,
+        R: RngCore + CryptoRng,
+    {
+        let mut raw = key.to_bytes();
+        let res =
+            wasm_storage::write_encrypted(storage, cipher, stores::KEYS, name, &raw, rng).await;
+        raw.zeroize();
+        res
+    }
+
+    async fn load_browser_keypair<T: PemStorableKeyPair>(
+        storage: &dyn BrowserStorage,
+        cipher: &StoreCipher,
+        private_key_name: &str,
+        public_key_name: &str,
+    ) -> Result<Option<T>, StorageError> {
+        let private_key = Self::load_browser_key(storage, cipher, private_key_name).await?;
+        let public_key = Self::load_browser_key(storage, cipher, public_key_name).await?;
+        Ok(private_key
+            .zip(public_key)
+            .map(|(private_key, public_key)| T::from_keys(private_key, public_key)))
+    }
+
+    /// Loads previously stored keys from the browser storage, decrypting them with the provided cipher.
+    /// Returns `None` if the storage does not contain a full set of client keys.
+    pub async fn load_from_browser_storage(
+        storage: &dyn BrowserStorage,
+        cipher: &StoreCipher,
+    ) -> Result<Option<Self>, StorageError> {
+        use browser_key_names::*;
+
+        let Some(identity_keypair) = Self::load_browser_keypair::<identity::KeyPair>(
+            storage,
+            cipher,
+            PRIVATE_IDENTITY_KEY,
+            PUBLIC_IDENTITY_KEY,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+        let Some(encryption_keypair) = Self::load_browser_keypair::<encryption::KeyPair>(
+            storage,
+            cipher,
+            PRIVATE_ENCRYPTION_KEY,
+            PUBLIC_ENCRYPTION_KEY,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+        let Some(ack_key) = Self::load_browser_key::<AckKey>(storage, cipher, ACK_KEY).await?
+        else {
+            return Ok(None);
+        };
+
+        // the shared key might legitimately not exist yet if we haven't registered with any gateway
+        let gateway_shared_key =
+            Self::load_browser_key::<SharedKeys>(storage, cipher, GATEWAY_SHARED_KEY).await?;
+
+        Ok(Some(KeyManager {
+            identity_keypair: Arc::new(identity_keypair),
+            encryption_keypair: Arc::new(encryption_keypair),
+            gateway_shared_key: gateway_shared_key.map(Arc::new),
+            ack_key: Arc::new(ack_key),
+        }))
+    }
+
+    /// Stores all available keys in the browser storage, encrypting them with the provided cipher.
+    pub async fn store_in_browser_storage<R>(
+        &self,
+        storage: &dyn BrowserStorage,
+        cipher: &StoreCipher,
+        rng: &mut R,
+    ) -> Result<(), StorageError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        use browser_key_names::*;
+
+        let identity_keypair = self.identity_keypair.as_ref();
+        let encryption_keypair = self.encryption_keypair.as_ref();
+
+        Self::store_browser_key(
+            storage,
+            cipher,
+            PRIVATE_IDENTITY_KEY,
+            identity_keypair.private_key(),
+            rng,
+        )
+        .await?;
+        Self::store_browser_key(
+            storage,
+            cipher,
+            PUBLIC_IDENTITY_KEY,
+            identity_keypair.public_key(),
+            rng,
+        )
+        .await?;
+        Self::store_browser_key(
+            storage,
+            cipher,
+            PRIVATE_ENCRYPTION_KEY,
+            encryption_keypair.private_key(),
+            rng,
+        )
+        .await?;
+        Self::store_browser_key(
+            storage,
+            cipher,
+            PUBLIC_ENCRYPTION_KEY,
+            encryption_keypair.public_key(),
+            rng,
+        )
+        .await?;
+        Self::store_browser_key(storage, cipher, ACK_KEY, self.ack_key.as_ref(), rng).await?;
+
+        match self.gateway_shared_key.as_ref() {
+            None => warn!("No gateway shared key available to store!"),
+            Some(gate_key) => {
+                Self::store_browser_key(storage, cipher, GATEWAY_SHARED_KEY, gate_key.as_ref(), rng)
+                    .await?
+            }
+        }
+
+        Ok(())
+    }
+}
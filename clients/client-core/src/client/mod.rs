@@ -3,10 +3,14 @@
 
 pub mod base_client;
 pub mod cover_traffic_stream;
+pub mod events;
 pub(crate) mod helpers;
 pub mod inbound_messages;
 pub mod key_manager;
 pub mod mix_traffic;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod passphrase;
+pub mod path_health;
 pub mod real_messages_control;
 pub mod received_buffer;
 pub mod replies;
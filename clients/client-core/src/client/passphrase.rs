@@ -0,0 +1,86 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Obtaining the passphrase protecting the client secrets stored on the disk.
+
+use std::io;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// Environment variable the passphrase can be provided with.
+pub const PASSPHRASE_ENV_VAR: &str = "NYM_CLIENT_PASSPHRASE";
+
+fn read_passphrase_file(path: &Path) -> io::Result<Zeroizing<String>> {
+    let content = Zeroizing::new(std::fs::read_to_string(path)?);
+    // ignore the trailing newline most editors are going to append to the file
+    Ok(Zeroizing::new(
+        content.trim_end_matches(['\r', '\n']).to_string(),
+    ))
+}
+
+fn non_empty(passphrase: Zeroizing<String>) -> io::Result<Zeroizing<String>> {
+    if passphrase.is_empty() {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the provided passphrase is empty",
+        ))
+    } else {
+        Ok(passphrase)
+    }
+}
+
+/// Retrieves the passphrase of an existing store. In order of precedence, it is read from
+/// the provided keyfile, the `NYM_CLIENT_PASSPHRASE` environment variable
+/// or, finally, the user is prompted for it.
+pub fn read_passphrase(passphrase_file: Option<&Path>) -> io::Result<Zeroizing<String>> {
+    if let Some(path) = passphrase_file {
+        return non_empty(read_passphrase_file(path)?);
+    }
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV_VAR) {
+        return non_empty(Zeroizing::new(passphrase));
+    }
+    non_empty(Zeroizing::new(rpassword::prompt_password(
+        "Enter the passphrase of the client store: ",
+    )?))
+}
+
+/// Retrieves a new passphrase for encrypting the store. It is obtained the same way as in
+/// [`read_passphrase`], except that if the user gets prompted, they have to confirm it.
+pub fn read_new_passphrase(passphrase_file: Option<&Path>) -> io::Result<Zeroizing<String>> {
+    if passphrase_file.is_some() || std::env::var(PASSPHRASE_ENV_VAR).is_ok() {
+        return read_passphrase(passphrase_file);
+    }
+
+    let passphrase = non_empty(Zeroizing::new(rpassword::prompt_password(
+        "Enter the new passphrase of the client store: ",
+    )?))?;
+    let confirmation = Zeroizing::new(rpassword::prompt_password("Confirm the new passphrase: ")?);
+    if passphrase != confirmation {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the provided passphrases do not match",
+        ));
+    }
+    Ok(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn passphrase_file_ignores_trailing_newline() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "my passphrase").unwrap();
+
+        let passphrase = read_passphrase(Some(file.path())).unwrap();
+        assert_eq!(passphrase.as_str(), "my passphrase");
+    }
+
+    #[test]
+    fn empty_passphrase_file_is_rejected() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        assert!(read_passphrase(Some(file.path())).is_err());
+    }
+}
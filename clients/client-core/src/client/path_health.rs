@@ -0,0 +1,405 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::helpers::{get_time_now, Instant};
+use crate::client::topology_control::TopologyAccessor;
+use crate::config::DebugConfig;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use log::*;
+use nymsphinx::acknowledgements::AckKey;
+use nymsphinx::addressing::clients::Recipient;
+use nymsphinx::cover::{generate_loop_probe_packet, ProbeId};
+use nymsphinx::forwarding::packet::MixPacket;
+use nymsphinx::params::{PacketSize, DEFAULT_NUM_MIX_HOPS};
+use rand::seq::SliceRandom;
+use rand::{CryptoRng, Rng};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use topology::{mix, MixId, NymTopology};
+
+pub(crate) type ReceivedProbesSender = mpsc::UnboundedSender<ProbeId>;
+pub(crate) type ReceivedProbesReceiver = mpsc::UnboundedReceiver<ProbeId>;
+
+// how much the outcome of the latest probe affects the reliability of the node
+const RELIABILITY_SMOOTHING_FACTOR: f64 = 0.2;
+
+/// Summary of the health probes sent through particular mixnode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeScore {
+    /// Exponentially weighted moving average of the probe outcomes, within [0, 1]
+    /// (where 1 means all recent probes came back).
+    pub reliability: f64,
+
+    /// Number of probes sent through the node that either came back or have timed out.
+    pub resolved_probes: u64,
+
+    /// Number of probes sent through the node that came back.
+    pub successful_probes: u64,
+}
+
+impl NodeScore {
+    fn new() -> Self {
+        NodeScore {
+            reliability: 1.0,
+            resolved_probes: 0,
+            successful_probes: 0,
+        }
+    }
+
+    fn record_probe(&mut self, success: bool) {
+        let outcome = if success { 1.0 } else { 0.0 };
+        self.reliability = (1.0 - RELIABILITY_SMOOTHING_FACTOR) * self.reliability
+            + RELIABILITY_SMOOTHING_FACTOR * outcome;
+        self.resolved_probes += 1;
+        if success {
+            self.successful_probes += 1;
+        }
+    }
+}
+
+/// Scores of all mixnodes the health probes have been sent through.
+/// It can be cheaply cloned and queried at any point.
+#[derive(Debug, Clone, Default)]
+pub struct NodeScores(Arc<Mutex<HashMap<MixId, NodeScore>>>);
+
+impl NodeScores {
+    pub fn new() -> Self {
+        NodeScores::default()
+    }
+
+    pub fn get(&self, mix_id: MixId) -> Option<NodeScore> {
+        self.0.lock().unwrap().get(&mix_id).copied()
+    }
+
+    pub fn all(&self) -> HashMap<MixId, NodeScore> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn record_probe(&self, route: &[MixId], success: bool) {
+        // we can't tell which node has dropped the probe, so every node on the route is affected
+        let mut guard = self.0.lock().unwrap();
+        for mix_id in route {
+            guard
+                .entry(*mix_id)
+                .or_insert_with(NodeScore::new)
+                .record_probe(success)
+        }
+    }
+
+    /// Derives weights used for choosing the route nodes, so that the less reliable nodes
+    /// would be chosen less often and the ones below the threshold, not at all.
+    fn route_weights(&self, exclusion_threshold: f64) -> HashMap<MixId, f64> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(mix_id, score)| {
+                if score.reliability < exclusion_threshold {
+                    (*mix_id, 0.0)
+                } else {
+                    (*mix_id, score.reliability)
+                }
+            })
+            .collect()
+    }
+}
+
+// the probed route is chosen uniformly (as opposed to the weighted choice made for the actual
+// traffic) so that the excluded nodes would get a chance of recovering their scores
+fn sample_probe_route<R: Rng + ?Sized>(
+    rng: &mut R,
+    topology: &NymTopology,
+) -> Option<Vec<mix::Node>> {
+    (1..=DEFAULT_NUM_MIX_HOPS)
+        .map(|layer| topology.mixes().get(&layer)?.choose(rng).cloned())
+        .collect()
+}
+
+struct PendingProbe {
+    route: Vec<MixId>,
+    sent_at: Instant,
+}
+
+pub struct Config {
+    /// Key used to encrypt and decrypt content of an ACK packet.
+    ack_key: Arc<AckKey>,
+
+    /// Average delay an acknowledgement packet is going to get delay at a single mixnode.
+    average_ack_delay: Duration,
+
+    /// Average delay a data packet is going to get delay at a single mixnode.
+    average_packet_delay: Duration,
+
+    /// Minimum delay between sending subsequent probes.
+    probing_interval: Duration,
+
+    /// Time after which the probe that hasn't come back is assumed to have been lost.
+    probe_timeout: Duration,
+
+    /// Reliability below which nodes are no longer used for routing packets.
+    exclusion_threshold: f64,
+
+    /// Represents full address of this client.
+    self_address: Recipient,
+}
+
+impl Config {
+    pub fn new(debug_config: &DebugConfig, ack_key: Arc<AckKey>, self_address: Recipient) -> Self {
+        Config {
+            ack_key,
+            average_ack_delay: debug_config.average_ack_delay,
+            average_packet_delay: debug_config.average_packet_delay,
+            probing_interval: debug_config.path_health_probing_interval,
+            probe_timeout: debug_config.topology_resolution_timeout,
+            exclusion_threshold: debug_config.path_health_exclusion_threshold,
+            self_address,
+        }
+    }
+}
+
+/// Creates loop probe packets going through randomly sampled mix routes and based on whether
+/// they come back, keeps track of reliability of the nodes. Nodes that keep failing are excluded
+/// from the routes chosen for all the other traffic.
+///
+/// It never sends anything on its own. Instead, the probes are sent by `LoopCoverTrafficStream`
+/// in place of some of its loop cover messages, so that they'd follow the same Poisson process.
+pub struct PathHealthProber {
+    config: Config,
+
+    /// Accessor to the common instance of network topology.
+    topology_access: TopologyAccessor,
+
+    node_scores: NodeScores,
+    pending_probes: HashMap<ProbeId, PendingProbe>,
+    last_probe: Option<Instant>,
+}
+
+impl PathHealthProber {
+    pub(crate) fn new(
+        config: Config,
+        topology_access: TopologyAccessor,
+        node_scores: NodeScores,
+    ) -> Self {
+        PathHealthProber {
+            config,
+            topology_access,
+            node_scores,
+            pending_probes: HashMap::new(),
+            last_probe: None,
+        }
+    }
+
+    fn probe_due(&self) -> bool {
+        match self.last_probe {
+            None => true,
+            Some(last_probe) => get_time_now() - last_probe >= self.config.probing_interval,
+        }
+    }
+
+    /// If it's time for another probe, creates it so that it could be sent instead of
+    /// the loop cover message that was about to be sent.
+    pub(crate) fn try_prepare_probe<R>(
+        &mut self,
+        rng: &mut R,
+        topology: &NymTopology,
+        packet_size: PacketSize,
+    ) -> Option<(ProbeId, MixPacket)>
+    where
+        R: CryptoRng + Rng,
+    {
+        if !self.probe_due() {
+            return None;
+        }
+
+        let route = sample_probe_route(rng, topology)?;
+
+        // the id has to be unpredictable, as otherwise anyone could forge probe replies
+        // and make us believe arbitrary nodes are reliable
+        let probe_id: ProbeId = rng.gen();
+
+        let probe = match generate_loop_probe_packet(
+            rng,
+            topology,
+            &self.config.ack_key,
+            &self.config.self_address,
+            &route,
+            probe_id,
+            self.config.average_ack_delay,
+            self.config.average_packet_delay,
+            packet_size,
+        ) {
+            Ok(probe) => probe,
+            Err(err) => {
+                warn!("failed to create a path health probe - {err}");
+                return None;
+            }
+        };
+
+        let now = get_time_now();
+        self.last_probe = Some(now);
+        self.pending_probes.insert(
+            probe_id,
+            PendingProbe {
+                route: route.iter().map(|node| node.mix_id).collect(),
+                sent_at: now,
+            },
+        );
+
+        Some((probe_id, probe))
+    }
+
+    /// Forgets about the probe that didn't make it out of the client,
+    /// so that the nodes wouldn't get penalised for our own congestion.
+    pub(crate) fn abandon_probe(&mut self, probe_id: ProbeId) {
+        self.pending_probes.remove(&probe_id);
+    }
+
+    async fn update_route_weights(&self) {
+        let weights = self
+            .node_scores
+            .route_weights(self.config.exclusion_threshold);
+        self.topology_access.update_mix_weights(weights).await
+    }
+
+    pub(crate) async fn on_probe_received(&mut self, probe_id: ProbeId) {
+        // only the ids of probes that are still in flight count, anything else is either late
+        // or has been forged
+        let Some(probe) = self.pending_probes.remove(&probe_id) else {
+            debug!("received an unknown (or expired) path health probe {probe_id}");
+            return;
+        };
+
+        trace!(
+            "path health probe {probe_id} has come back through {:?}",
+            probe.route
+        );
+        self.node_scores.record_probe(&probe.route, true);
+        self.update_route_weights().await;
+    }
+
+    pub(crate) async fn expire_stale_probes(&mut self) {
+        let now = get_time_now();
+        let probe_timeout = self.config.probe_timeout;
+        let expired: Vec<_> = self
+            .pending_probes
+            .iter()
+            .filter(|(_, probe)| now - probe.sent_at > probe_timeout)
+            .map(|(id, _)| *id)
+            .collect();
+
+        if expired.is_empty() {
+            return;
+        }
+
+        for probe_id in expired {
+            // the unwrap is fine as we've just got the id from the map
+            let probe = self.pending_probes.remove(&probe_id).unwrap();
+            debug!(
+                "path health probe {probe_id} through {:?} has timed out",
+                probe.route
+            );
+            self.node_scores.record_probe(&probe.route, false);
+        }
+        self.update_route_weights().await;
+    }
+}
+
+/// Resolves to the id of the next probe that made it back to us.
+/// If there's no receiver, it never resolves.
+pub(crate) async fn next_received_probe(
+    probe_receiver: &mut Option<ReceivedProbesReceiver>,
+) -> Option<ProbeId> {
+    match probe_receiver {
+        Some(probe_receiver) => probe_receiver.next().await,
+        None => futures::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+    use rand::rngs::OsRng;
+
+    fn prober(node_scores: NodeScores) -> PathHealthProber {
+        let mut rng = OsRng;
+        let self_address = Recipient::new(
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+            *identity::KeyPair::new(&mut rng).public_key(),
+        );
+        PathHealthProber::new(
+            Config::new(
+                &DebugConfig::default(),
+                Arc::new(AckKey::new(&mut rng)),
+                self_address,
+            ),
+            TopologyAccessor::new(),
+            node_scores,
+        )
+    }
+
+    #[test]
+    fn only_replies_to_probes_in_flight_are_counted() {
+        let scores = NodeScores::new();
+        let mut prober = prober(scores.clone());
+        let probe_id: ProbeId = OsRng.gen();
+        prober.pending_probes.insert(
+            probe_id,
+            PendingProbe {
+                route: vec![1, 2, 3],
+                sent_at: get_time_now(),
+            },
+        );
+
+        futures::executor::block_on(async {
+            // forged (or very late) replies are ignored
+            prober.on_probe_received(probe_id.wrapping_add(1)).await;
+            assert!(scores.get(1).is_none());
+
+            prober.on_probe_received(probe_id).await;
+            assert_eq!(scores.get(1).unwrap().successful_probes, 1);
+
+            // and so are replayed ones
+            prober.on_probe_received(probe_id).await;
+            assert_eq!(scores.get(1).unwrap().successful_probes, 1);
+        })
+    }
+
+    #[test]
+    fn failing_nodes_get_excluded() {
+        let scores = NodeScores::new();
+        for _ in 0..5 {
+            scores.record_probe(&[1, 2, 3], true);
+            scores.record_probe(&[1, 2, 3], true);
+            scores.record_probe(&[4, 2, 3], false);
+        }
+
+        let weights = scores.route_weights(0.5);
+        assert!(weights[&1] > 0.99);
+        assert_eq!(weights[&4], 0.0);
+        // nodes sharing routes with the failing one get penalised, but are still used
+        assert!(weights[&2] > 0.5 && weights[&2] < 1.0);
+
+        let score = scores.get(2).unwrap();
+        assert_eq!(score.resolved_probes, 15);
+        assert_eq!(score.successful_probes, 10);
+        assert!(scores.get(5).is_none());
+    }
+
+    #[test]
+    fn nodes_recover_after_successful_probes() {
+        let scores = NodeScores::new();
+        for _ in 0..5 {
+            scores.record_probe(&[1], false);
+        }
+        assert_eq!(scores.route_weights(0.5)[&1], 0.0);
+
+        for _ in 0..5 {
+            scores.record_probe(&[1], true);
+        }
+        assert!(scores.route_weights(0.5)[&1] > 0.5);
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::PendingAcknowledgement;
+use crate::client::events::{ClientEvent, ClientEventSender, MessageId};
 use crate::client::real_messages_control::acknowledgement_control::RetransmissionRequestSender;
 use futures::channel::mpsc;
 use futures::StreamExt;
@@ -80,6 +81,54 @@ impl Config {
     }
 }
 
+/// Keeps track of the number of unacknowledged fragments of messages that have `MessageId` attached
+/// so that their delivery could be announced.
+struct DeliveryTracker {
+    outstanding_fragments: HashMap<MessageId, usize>,
+    event_sender: ClientEventSender,
+}
+
+impl DeliveryTracker {
+    fn new(event_sender: ClientEventSender) -> Self {
+        DeliveryTracker {
+            outstanding_fragments: HashMap::new(),
+            event_sender,
+        }
+    }
+
+    fn fragment_sent(&mut self, message_id: MessageId) {
+        *self.outstanding_fragments.entry(message_id).or_default() += 1;
+    }
+
+    fn fragment_acknowledged(&mut self, message_id: MessageId) {
+        let Some(outstanding) = self.outstanding_fragments.get_mut(&message_id) else {
+            debug!("received an ack for untracked message {message_id}");
+            return;
+        };
+
+        *outstanding -= 1;
+        if *outstanding == 0 {
+            self.outstanding_fragments.remove(&message_id);
+            trace!("message {message_id} has been delivered");
+            if self
+                .event_sender
+                .unbounded_send(ClientEvent::MessageDelivered(message_id))
+                .is_err()
+            {
+                debug!("the client event receiver has been dropped");
+            }
+        }
+    }
+}
+
+/// Keeps track of the acknowledged `Fragment`s of an erasure coded set, which can be reconstructed
+/// by the recipient as soon as any `data_fragments` of its `Fragment`s are delivered.
+struct ErasureCodedSetAcks {
+    required: u8,
+    acknowledged: u8,
+    fragments: Vec<FragmentIdentifier>,
+}
+
 pub(super) struct ActionController {
     /// Configurable parameters of the `ActionController`
     config: Config,
@@ -100,6 +149,13 @@ pub(super) struct ActionController {
 
     /// Channel for notifying `RetransmissionRequestListener` about expired acknowledgements.
     retransmission_sender: RetransmissionRequestSender,
+
+    /// If enabled, keeps track of messages whose delivery should be announced.
+    delivery_tracker: Option<DeliveryTracker>,
+
+    /// Acknowledgement state of the erasure coded sets with pending `Fragment`s, so that
+    /// the remaining ones would not get retransmitted once the set can already be reconstructed.
+    erasure_coded_sets: HashMap<i32, ErasureCodedSetAcks>,
 }
 
 impl ActionController {
@@ -114,14 +170,39 @@ impl ActionController {
             pending_acks_timers: NonExhaustiveDelayQueue::new(),
             incoming_actions,
             retransmission_sender,
+            delivery_tracker: None,
+            erasure_coded_sets: HashMap::new(),
         }
     }
 
+    pub(super) fn with_delivery_tracking(mut self, event_sender: ClientEventSender) -> Self {
+        self.delivery_tracker = Some(DeliveryTracker::new(event_sender));
+        self
+    }
+
     fn handle_insert(&mut self, pending_acks: Vec<PendingAcknowledgement>) {
         for pending_ack in pending_acks {
             let frag_id = pending_ack.message_chunk.fragment_identifier();
             trace!("{} is inserted", frag_id);
 
+            if let (Some(tracker), Some(message_id)) =
+                (&mut self.delivery_tracker, pending_ack.message_id)
+            {
+                tracker.fragment_sent(message_id)
+            }
+
+            if let Some(erasure_coding) = pending_ack.message_chunk.erasure_coding() {
+                self.erasure_coded_sets
+                    .entry(pending_ack.message_chunk.id())
+                    .or_insert_with(|| ErasureCodedSetAcks {
+                        required: erasure_coding.data_fragments,
+                        acknowledged: 0,
+                        fragments: Vec::new(),
+                    })
+                    .fragments
+                    .push(frag_id);
+            }
+
             if self
                 .pending_acks_data
                 .insert(frag_id, (Arc::new(pending_ack), None))
@@ -163,14 +244,56 @@ impl ActionController {
     fn handle_remove(&mut self, frag_id: FragmentIdentifier) {
         trace!("{} is getting removed", frag_id);
 
+        let Some(pending_ack) = self.remove_pending_ack(frag_id) else {
+            return;
+        };
+
+        if pending_ack.message_chunk.erasure_coding().is_some() {
+            self.erasure_coded_fragment_acknowledged(pending_ack.message_chunk.id())
+        }
+    }
+
+    // once enough fragments of an erasure coded set got acknowledged, the recipient is able to
+    // reconstruct it, so there's no point in retransmitting the rest of them
+    fn erasure_coded_fragment_acknowledged(&mut self, set_id: i32) {
+        let Some(set_acks) = self.erasure_coded_sets.get_mut(&set_id) else {
+            debug!("received an ack for untracked erasure coded set {set_id}");
+            return;
+        };
+
+        set_acks.acknowledged += 1;
+        if set_acks.acknowledged < set_acks.required {
+            return;
+        }
+
+        let set_acks = self.erasure_coded_sets.remove(&set_id).unwrap();
+        for frag_id in set_acks.fragments {
+            if self.pending_acks_data.contains_key(&frag_id) {
+                trace!("{frag_id} is no longer needed to reconstruct its set");
+                self.remove_pending_ack(frag_id);
+            }
+        }
+    }
+
+    fn remove_pending_ack(
+        &mut self,
+        frag_id: FragmentIdentifier,
+    ) -> Option<Arc<PendingAcknowledgement>> {
         match self.pending_acks_data.remove(&frag_id) {
             None => {
                 debug!(
                     "Tried to REMOVE pending ack that is already gone! - {}",
                     frag_id
                 );
+                None
             }
-            Some((_, queue_key)) => {
+            Some((pending_ack, queue_key)) => {
+                if let (Some(tracker), Some(message_id)) =
+                    (&mut self.delivery_tracker, pending_ack.message_id)
+                {
+                    tracker.fragment_acknowledged(message_id)
+                }
+
                 if let Some(queue_key) = queue_key {
                     // there are no possible checks here, we must GUARANTEE that we NEVER try
                     // to remove an entry that doesn't exist (and we MUST GUARANTEE that
@@ -185,6 +308,7 @@ impl ActionController {
                         frag_id
                     );
                 }
+                Some(pending_ack)
             }
         }
     }
@@ -282,3 +406,96 @@ impl ActionController {
         log::debug!("ActionController: Exiting");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+    use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
+    use nymsphinx::chunking::{split_into_erasure_coded_sets, RedundancyRatio};
+    use nymsphinx::params::PacketSize;
+    use rand::thread_rng;
+
+    #[test]
+    fn delivery_is_announced_once_all_fragments_are_acknowledged() {
+        let (event_sender, mut event_receiver) = mpsc::unbounded();
+        let mut tracker = DeliveryTracker::new(event_sender);
+
+        tracker.fragment_sent(1);
+        tracker.fragment_sent(1);
+        tracker.fragment_sent(2);
+
+        tracker.fragment_acknowledged(1);
+        assert!(event_receiver.try_next().is_err());
+
+        tracker.fragment_acknowledged(2);
+        assert_eq!(
+            event_receiver.try_next().unwrap(),
+            Some(ClientEvent::MessageDelivered(2))
+        );
+
+        tracker.fragment_acknowledged(1);
+        assert_eq!(
+            event_receiver.try_next().unwrap(),
+            Some(ClientEvent::MessageDelivered(1))
+        );
+
+        // duplicate acks must not result in repeated announcements
+        tracker.fragment_acknowledged(1);
+        assert!(event_receiver.try_next().is_err());
+        assert!(tracker.outstanding_fragments.is_empty());
+    }
+
+    #[test]
+    fn remaining_fragments_of_erasure_coded_set_are_dropped_once_enough_are_acknowledged() {
+        let mut rng = thread_rng();
+        let (retransmission_sender, _retransmission_receiver) = mpsc::unbounded();
+        let (_action_sender, action_receiver) = mpsc::unbounded();
+        let mut controller = ActionController::new(
+            Config::new(Duration::from_millis(1500), 1.5),
+            retransmission_sender,
+            action_receiver,
+        );
+
+        let packet_size = PacketSize::default();
+        let mut sets = split_into_erasure_coded_sets(
+            &mut rng,
+            &[42u8; 10000],
+            packet_size.plaintext_size(),
+            RedundancyRatio::new(0.5).unwrap(),
+        );
+        assert_eq!(sets.len(), 1);
+        let fragments = sets.pop().unwrap();
+        let data_fragments = fragments[0].erasure_coding().unwrap().data_fragments as usize;
+        assert!(fragments.len() > data_fragments);
+
+        let frag_ids: Vec<_> = fragments.iter().map(|f| f.fragment_identifier()).collect();
+        let tag = AnonymousSenderTag::new_random(&mut rng);
+        controller.handle_insert(
+            fragments
+                .into_iter()
+                .map(|fragment| {
+                    PendingAcknowledgement::new_anonymous(
+                        fragment,
+                        SphinxDelay::new_from_nanos(42),
+                        tag,
+                        false,
+                        packet_size,
+                    )
+                })
+                .collect(),
+        );
+
+        for frag_id in frag_ids.iter().skip(1).take(data_fragments - 1) {
+            controller.handle_remove(*frag_id);
+        }
+        assert_eq!(
+            controller.pending_acks_data.len(),
+            frag_ids.len() - data_fragments + 1
+        );
+
+        controller.handle_remove(frag_ids[frag_ids.len() - 1]);
+        assert!(controller.pending_acks_data.is_empty());
+        assert!(controller.erasure_coded_sets.is_empty());
+    }
+}
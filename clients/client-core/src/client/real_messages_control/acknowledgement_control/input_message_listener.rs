@@ -1,6 +1,7 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::events::MessageId;
 use crate::client::inbound_messages::{InputMessage, InputMessageReceiver};
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::replies::reply_controller::ReplyControllerSender;
@@ -8,6 +9,7 @@ use client_connections::TransmissionLane;
 use log::*;
 use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nymsphinx::params::PacketSize;
 use rand::{CryptoRng, Rng};
 
 /// Module responsible for dealing with the received messages: splitting them, creating acknowledgements,
@@ -52,31 +54,58 @@ where
             .send_reply(recipient_tag, data, lane)
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_plain_message(
         &mut self,
         recipient: Recipient,
         content: Vec<u8>,
         lane: TransmissionLane,
+        packet_size: Option<PacketSize>,
+        message_id: Option<MessageId>,
+        compress: bool,
+        erasure_coding: bool,
     ) {
         if let Err(err) = self
             .message_handler
-            .try_send_plain_message(recipient, content, lane)
+            .try_send_plain_message(
+                recipient,
+                content,
+                lane,
+                packet_size,
+                message_id,
+                compress,
+                erasure_coding,
+            )
             .await
         {
             warn!("failed to send a plain message - {err}")
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_repliable_message(
         &mut self,
         recipient: Recipient,
         content: Vec<u8>,
         reply_surbs: u32,
         lane: TransmissionLane,
+        packet_size: Option<PacketSize>,
+        message_id: Option<MessageId>,
+        compress: bool,
+        erasure_coding: bool,
     ) {
         if let Err(err) = self
             .message_handler
-            .try_send_message_with_reply_surbs(recipient, content, reply_surbs, lane)
+            .try_send_message_with_reply_surbs(
+                recipient,
+                content,
+                reply_surbs,
+                lane,
+                packet_size,
+                message_id,
+                compress,
+                erasure_coding,
+            )
             .await
         {
             warn!("failed to send a repliable message - {err}")
@@ -89,15 +118,43 @@ where
                 recipient,
                 data,
                 lane,
-            } => self.handle_plain_message(recipient, data, lane).await,
+                packet_size,
+                message_id,
+                compress,
+                erasure_coding,
+            } => {
+                self.handle_plain_message(
+                    recipient,
+                    data,
+                    lane,
+                    packet_size,
+                    message_id,
+                    compress,
+                    erasure_coding,
+                )
+                .await
+            }
             InputMessage::Anonymous {
                 recipient,
                 data,
                 reply_surbs,
                 lane,
+                packet_size,
+                message_id,
+                compress,
+                erasure_coding,
             } => {
-                self.handle_repliable_message(recipient, data, reply_surbs, lane)
-                    .await
+                self.handle_repliable_message(
+                    recipient,
+                    data,
+                    reply_surbs,
+                    lane,
+                    packet_size,
+                    message_id,
+                    compress,
+                    erasure_coding,
+                )
+                .await
             }
             InputMessage::Reply {
                 recipient_tag,
@@ -132,3 +189,172 @@ where
         log::debug!("InputMessageListener: Exiting");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::real_messages_control::message_handler::Config;
+    use crate::client::real_messages_control::Action;
+    use crate::client::replies::reply_controller::requests::new_control_channels;
+    use crate::client::replies::reply_storage::{SentReplyKeys, UsedSenderTags};
+    use crate::client::topology_control::TopologyAccessor;
+    use crypto::asymmetric::{encryption, identity};
+    use futures::channel::mpsc;
+    use mixnet_contract_common::Layer;
+    use nymsphinx::acknowledgements::AckKey;
+    use nymsphinx::chunking::RedundancyRatio;
+    use nymsphinx::compression::MessageCompression;
+    use rand::rngs::OsRng;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use topology::{gateway, mix, NymTopology};
+
+    const GATEWAY_IDENTITY: &str = "FioFa8nMmPpQnYi7JyojoTuwGLeyNS8BF4ChPr29zUML";
+
+    fn mix_node(mix_id: u32, layer: Layer, host: &str) -> mix::Node {
+        mix::Node {
+            mix_id,
+            owner: "N/A".to_string(),
+            host: host.parse().unwrap(),
+            mix_host: format!("{host}:1789").parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(
+                "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "B3GzG62aXAZNg14RoMCp3BhELNBrySLr2JqrwyfYFzRc",
+            )
+            .unwrap(),
+            layer,
+            version: "0.x.0".to_string(),
+        }
+    }
+
+    fn topology() -> NymTopology {
+        let mut mixes = HashMap::new();
+        mixes.insert(1, vec![mix_node(1, Layer::One, "1.1.1.1")]);
+        mixes.insert(2, vec![mix_node(2, Layer::Two, "2.2.2.2")]);
+        mixes.insert(3, vec![mix_node(3, Layer::Three, "3.3.3.3")]);
+
+        NymTopology::new(
+            mixes,
+            vec![gateway::Node {
+                owner: "N/A".to_string(),
+                stake: 123,
+                location: "unknown".to_string(),
+                host: "1.2.3.4".parse().unwrap(),
+                mix_host: "1.2.3.4:1789".parse().unwrap(),
+                clients_port: 9000,
+                identity_key: identity::PublicKey::from_base58_string(GATEWAY_IDENTITY).unwrap(),
+                sphinx_key: encryption::PublicKey::from_base58_string(
+                    "EB42xvMFMD5rUCstE2CDazgQQJ22zLv8SPm1Luxni44c",
+                )
+                .unwrap(),
+                version: "0.x.0".to_string(),
+            }],
+        )
+    }
+
+    fn client_address() -> Recipient {
+        let mut rng = OsRng;
+        Recipient::new(
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+            identity::PublicKey::from_base58_string(GATEWAY_IDENTITY).unwrap(),
+        )
+    }
+
+    // pushes the message through the listener, exactly as if it came from one of the clients,
+    // and returns the content of all fragments that got created for it
+    fn sent_fragments(message: InputMessage) -> Vec<Vec<u8>> {
+        futures::executor::block_on(async {
+            let topology_access = TopologyAccessor::new();
+            topology_access
+                .update_global_topology(Some(topology()))
+                .await;
+
+            let config = Config::new(
+                Arc::new(AckKey::new(&mut OsRng)),
+                client_address(),
+                Duration::from_millis(50),
+                Duration::from_millis(50),
+            )
+            .with_compression(Some(MessageCompression::Deflate))
+            .with_erasure_coding(Some(RedundancyRatio::new(1.0).unwrap()));
+
+            let (action_sender, mut action_receiver) = mpsc::unbounded();
+            let (real_message_sender, _real_message_receiver) = tokio::sync::mpsc::channel(10);
+            let (_input_sender, input_receiver) = tokio::sync::mpsc::channel(1);
+            let (reply_controller_sender, _reply_controller_receiver) = new_control_channels();
+
+            let message_handler = MessageHandler::new(
+                config,
+                OsRng,
+                action_sender,
+                real_message_sender,
+                topology_access,
+                SentReplyKeys::new(),
+                UsedSenderTags::new(),
+            );
+            let mut listener =
+                InputMessageListener::new(input_receiver, message_handler, reply_controller_sender);
+            listener.on_input_message(message).await;
+
+            match action_receiver.try_next().unwrap().unwrap() {
+                Action::InsertPending(pending) => pending
+                    .into_iter()
+                    .map(|ack| ack.fragment_data().into_bytes())
+                    .collect(),
+                _ => panic!("unexpected action"),
+            }
+        })
+    }
+
+    fn contains(fragments: &[Vec<u8>], needle: &[u8]) -> bool {
+        fragments.iter().any(|fragment| {
+            fragment
+                .windows(needle.len())
+                .any(|window| window == needle)
+        })
+    }
+
+    #[test]
+    fn messages_are_only_compressed_when_requested() {
+        let content = b"hello mixnet! ".repeat(100);
+        let recipient = client_address();
+
+        let plain = sent_fragments(InputMessage::new_regular(
+            recipient,
+            content.clone(),
+            TransmissionLane::General,
+        ));
+        assert!(contains(&plain, &content[..100]));
+
+        let compressed = sent_fragments(
+            InputMessage::new_regular(recipient, content.clone(), TransmissionLane::General)
+                .with_compression(),
+        );
+        assert!(!contains(&compressed, &content[..100]));
+    }
+
+    #[test]
+    fn messages_are_only_erasure_coded_when_requested() {
+        let content = b"hello mixnet!".to_vec();
+        let recipient = client_address();
+
+        let plain = sent_fragments(InputMessage::new_regular(
+            recipient,
+            content.clone(),
+            TransmissionLane::General,
+        ));
+        assert_eq!(plain.len(), 1);
+
+        // with the redundancy ratio of 1, the single data fragment gets accompanied by a parity one
+        let erasure_coded = sent_fragments(
+            InputMessage::new_regular(recipient, content, TransmissionLane::General)
+                .with_erasure_coding(),
+        );
+        assert_eq!(erasure_coded.len(), 2);
+    }
+}
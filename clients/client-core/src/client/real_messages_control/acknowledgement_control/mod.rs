@@ -7,6 +7,7 @@ use self::{
     retransmission_request_listener::RetransmissionRequestListener,
     sent_notification_listener::SentNotificationListener,
 };
+use crate::client::events::{ClientEventSender, MessageId};
 use crate::client::inbound_messages::InputMessageReceiver;
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::replies::reply_controller::ReplyControllerSender;
@@ -69,6 +70,13 @@ pub(crate) struct PendingAcknowledgement {
     message_chunk: Fragment,
     delay: SphinxDelay,
     destination: PacketDestination,
+
+    /// Size of the sphinx packet the `Fragment` was put in, so that it could be retransmitted
+    /// using the same size.
+    packet_size: PacketSize,
+
+    /// Id of the message this `Fragment` belongs to if its delivery is being tracked.
+    message_id: Option<MessageId>,
 }
 
 impl PendingAcknowledgement {
@@ -77,11 +85,14 @@ impl PendingAcknowledgement {
         message_chunk: Fragment,
         delay: SphinxDelay,
         recipient: Recipient,
+        packet_size: PacketSize,
     ) -> Self {
         PendingAcknowledgement {
             message_chunk,
             delay,
             destination: PacketDestination::KnownRecipient(recipient.into()),
+            packet_size,
+            message_id: None,
         }
     }
 
@@ -90,6 +101,7 @@ impl PendingAcknowledgement {
         delay: SphinxDelay,
         recipient_tag: AnonymousSenderTag,
         extra_surb_request: bool,
+        packet_size: PacketSize,
     ) -> Self {
         PendingAcknowledgement {
             message_chunk,
@@ -98,9 +110,16 @@ impl PendingAcknowledgement {
                 recipient_tag,
                 extra_surb_request,
             },
+            packet_size,
+            message_id: None,
         }
     }
 
+    pub(crate) fn with_message_id(mut self, message_id: Option<MessageId>) -> Self {
+        self.message_id = message_id;
+        self
+    }
+
     pub(crate) fn inner_fragment_identifier(&self) -> FragmentIdentifier {
         self.message_chunk.fragment_identifier()
     }
@@ -134,6 +153,9 @@ pub(super) struct AcknowledgementControllerConnectors {
 
     /// Channel used for receiving request by `ActionController` to deal with anything ack-related,
     ack_action_receiver: AckActionReceiver,
+
+    /// Optional channel used for announcing delivery of messages.
+    client_event_sender: Option<ClientEventSender>,
 }
 
 impl AcknowledgementControllerConnectors {
@@ -143,6 +165,7 @@ impl AcknowledgementControllerConnectors {
         ack_receiver: AcknowledgementReceiver,
         ack_action_sender: AckActionSender,
         ack_action_receiver: AckActionReceiver,
+        client_event_sender: Option<ClientEventSender>,
     ) -> Self {
         AcknowledgementControllerConnectors {
             input_receiver,
@@ -150,6 +173,7 @@ impl AcknowledgementControllerConnectors {
             ack_receiver,
             ack_action_sender,
             ack_action_receiver,
+            client_event_sender,
         }
     }
 }
@@ -207,11 +231,14 @@ where
 
         let action_config =
             action_controller::Config::new(config.ack_wait_addition, config.ack_wait_multiplier);
-        let action_controller = ActionController::new(
+        let mut action_controller = ActionController::new(
             action_config,
             retransmission_tx,
             connectors.ack_action_receiver,
         );
+        if let Some(client_event_sender) = connectors.client_event_sender {
+            action_controller = action_controller.with_delivery_tracking(client_event_sender);
+        }
 
         // will listen for any acks coming from the network
         let acknowledgement_listener = AcknowledgementListener::new(
@@ -14,6 +14,7 @@ use futures::StreamExt;
 use log::*;
 use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::chunking::fragment::Fragment;
+use nymsphinx::params::PacketSize;
 use nymsphinx::preparer::PreparedFragment;
 use rand::{CryptoRng, Rng};
 use std::sync::{Arc, Weak};
@@ -48,11 +49,12 @@ where
         &mut self,
         packet_recipient: Recipient,
         chunk_data: Fragment,
+        packet_size: PacketSize,
     ) -> Result<PreparedFragment, PreparationError> {
         debug!("retransmitting normal packet...");
 
         self.message_handler
-            .try_prepare_single_chunk_for_sending(packet_recipient, chunk_data)
+            .try_prepare_single_chunk_for_sending(packet_recipient, chunk_data, packet_size)
             .await
     }
 
@@ -85,6 +87,7 @@ where
                 self.prepare_normal_retransmission_chunk(
                     **recipient,
                     timed_out_ack.message_chunk.clone(),
+                    timed_out_ack.packet_size,
                 )
                 .await
             }
@@ -1,6 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::events::MessageId;
 use crate::client::real_messages_control::acknowledgement_control::PendingAcknowledgement;
 use crate::client::real_messages_control::real_traffic_stream::{
     BatchRealMessageSender, RealMessage,
@@ -15,6 +16,8 @@ use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::anonymous_replies::requests::{AnonymousSenderTag, RepliableMessage, ReplyMessage};
 use nymsphinx::anonymous_replies::{ReplySurb, SurbEncryptionKey};
 use nymsphinx::chunking::fragment::{Fragment, FragmentIdentifier};
+use nymsphinx::chunking::RedundancyRatio;
+use nymsphinx::compression::MessageCompression;
 use nymsphinx::message::NymMessage;
 use nymsphinx::params::{PacketSize, DEFAULT_NUM_MIX_HOPS};
 use nymsphinx::preparer::{MessagePreparer, PreparedFragment};
@@ -103,6 +106,17 @@ pub(crate) struct Config {
 
     /// Predefined packet size used for the encapsulated messages.
     packet_size: PacketSize,
+
+    /// If not empty, specifies the packet sizes out of which the most appropriate one is going
+    /// to be chosen for each (non-reply) message, based on its length.
+    packet_size_buckets: Vec<PacketSize>,
+
+    /// Optional redundancy ratio used for protecting the sent messages that opted into it
+    /// with forward error correction.
+    erasure_coding: Option<RedundancyRatio>,
+
+    /// Optional compression algorithm applied to the sent messages.
+    compression: Option<MessageCompression>,
 }
 
 impl Config {
@@ -119,6 +133,9 @@ impl Config {
             average_ack_delay,
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
             packet_size: PacketSize::default(),
+            packet_size_buckets: Vec::new(),
+            erasure_coding: None,
+            compression: None,
         }
     }
 
@@ -134,6 +151,35 @@ impl Config {
         self.packet_size = packet_size;
         self
     }
+
+    /// Allows choosing packet size for each message individually out of the provided sizes.
+    pub fn with_packet_size_buckets(mut self, packet_size_buckets: Vec<PacketSize>) -> Self {
+        self.packet_size_buckets = packet_size_buckets;
+        self
+    }
+
+    /// Allows protecting sent messages that opted into it with forward error correction.
+    pub fn with_erasure_coding(mut self, erasure_coding: Option<RedundancyRatio>) -> Self {
+        self.erasure_coding = erasure_coding;
+        self
+    }
+
+    /// Allows compressing sent messages whose recipients are known to support it.
+    pub fn with_compression(mut self, compression: Option<MessageCompression>) -> Self {
+        self.compression = compression;
+        self
+    }
+}
+
+/// Maps the requested packet size onto the smallest of the available sizes that is at least as big,
+/// or onto the biggest one if none of them is.
+fn closest_packet_size(requested: PacketSize, available: &[PacketSize]) -> Option<PacketSize> {
+    available
+        .iter()
+        .copied()
+        .filter(|size| size.size() >= requested.size())
+        .min_by_key(|size| size.size())
+        .or_else(|| available.iter().copied().max_by_key(|size| size.size()))
 }
 
 #[derive(Clone)]
@@ -170,7 +216,6 @@ where
             config.average_packet_delay,
             config.average_ack_delay,
         )
-        .with_custom_real_message_packet_size(config.packet_size)
         .with_mix_hops(config.num_mix_hops);
 
         MessageHandler {
@@ -211,6 +256,53 @@ where
         }
     }
 
+    fn determine_packet_size(
+        &self,
+        message: &NymMessage,
+        recipient: &Recipient,
+        topology: &NymTopology,
+        requested: Option<PacketSize>,
+    ) -> PacketSize {
+        // only the configured sizes can ever be used, as otherwise the packets wouldn't be
+        // hidden among the cover traffic
+        if self.config.packet_size_buckets.is_empty() {
+            if let Some(requested) = requested {
+                if requested != self.config.packet_size {
+                    warn!("{requested:?} packets are not among the configured packet sizes. Going to use {:?} instead", self.config.packet_size);
+                }
+            }
+            return self.config.packet_size;
+        }
+
+        // make sure the recipient's gateway is actually capable of receiving extended packets
+        let extended_supported = topology
+            .get_gateway(recipient.gateway())
+            .map(|gateway| gateway.supports_extended_packets())
+            .unwrap_or_default();
+
+        let candidates = self
+            .config
+            .packet_size_buckets
+            .iter()
+            .copied()
+            .filter(|size| extended_supported || !size.is_extended_size())
+            .collect::<Vec<_>>();
+
+        let chosen = match requested {
+            Some(requested) => {
+                let chosen = closest_packet_size(requested, &candidates);
+                if chosen != Some(requested) {
+                    warn!("{requested:?} packets are either not among the configured packet sizes or not supported by the gateway of {recipient}. Going to use {:?} instead", chosen.unwrap_or(self.config.packet_size));
+                }
+                chosen
+            }
+            None => self
+                .message_preparer
+                .optimal_packet_size(message, &candidates),
+        };
+        chosen.unwrap_or(self.config.packet_size)
+    }
+
     async fn generate_reply_surbs_with_keys(
         &mut self,
         amount: usize,
@@ -237,9 +329,11 @@ where
         reply_surb: ReplySurb,
         is_extra_surb_request: bool,
     ) -> Result<(), SurbWrappedPreparationError> {
-        let mut fragment = self
-            .message_preparer
-            .pad_and_split_message(NymMessage::new_reply(message));
+        let mut fragment = self.message_preparer.pad_and_split_message(
+            NymMessage::new_reply(message),
+            self.config.packet_size,
+            None,
+        );
         if fragment.len() > 1 {
             // well, it's not a single surb message
             return Err(SurbWrappedPreparationError {
@@ -259,8 +353,13 @@ where
         let real_messages =
             RealMessage::new(prepared_fragment.mix_packet, chunk.fragment_identifier());
         let delay = prepared_fragment.total_delay;
-        let pending_ack =
-            PendingAcknowledgement::new_anonymous(chunk, delay, target, is_extra_surb_request);
+        let pending_ack = PendingAcknowledgement::new_anonymous(
+            chunk,
+            delay,
+            target,
+            is_extra_surb_request,
+            self.config.packet_size,
+        );
 
         let lane = if is_extra_surb_request {
             TransmissionLane::ReplySurbRequest
@@ -289,10 +388,11 @@ where
 
     // // TODO: this will require additional argument to make it use different variant of `ReplyMessage`
     pub(crate) fn split_reply_message(&mut self, message: Vec<u8>) -> Vec<Fragment> {
-        self.message_preparer
-            .pad_and_split_message(NymMessage::new_reply(ReplyMessage::new_data_message(
-                message,
-            )))
+        self.message_preparer.pad_and_split_message(
+            NymMessage::new_reply(ReplyMessage::new_data_message(message)),
+            self.config.packet_size,
+            None,
+        )
     }
 
     pub(crate) async fn send_retransmission_reply_chunks(
@@ -349,7 +449,13 @@ where
 
             let real_message = RealMessage::new(prepared.mix_packet, prepared.fragment_identifier);
             let delay = prepared.total_delay;
-            let pending_ack = PendingAcknowledgement::new_anonymous(fragment, delay, target, false);
+            let pending_ack = PendingAcknowledgement::new_anonymous(
+                fragment,
+                delay,
+                target,
+                false,
+                self.config.packet_size,
+            );
 
             let entry = to_forward.entry(lane).or_default();
             entry.push(real_message);
@@ -364,22 +470,40 @@ where
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn try_send_plain_message(
         &mut self,
         recipient: Recipient,
         message: Vec<u8>,
         lane: TransmissionLane,
+        packet_size: Option<PacketSize>,
+        message_id: Option<MessageId>,
+        compress: bool,
+        erasure_coding: bool,
     ) -> Result<(), PreparationError> {
         let message = NymMessage::new_plain(message);
-        self.try_split_and_send_non_reply_message(message, recipient, lane)
-            .await
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            lane,
+            packet_size,
+            message_id,
+            compress,
+            erasure_coding,
+        )
+        .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn try_split_and_send_non_reply_message(
         &mut self,
         message: NymMessage,
         recipient: Recipient,
         lane: TransmissionLane,
+        packet_size: Option<PacketSize>,
+        message_id: Option<MessageId>,
+        compress: bool,
+        erasure_coding: bool,
     ) -> Result<(), PreparationError> {
         // TODO: I really dislike existence of this assertion, it implies code has to be re-organised
         debug_assert!(!matches!(message, NymMessage::Reply(_)));
@@ -388,7 +512,32 @@ where
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
-        let fragments = self.message_preparer.pad_and_split_message(message);
+        let packet_size = self.determine_packet_size(&message, &recipient, topology, packet_size);
+        // older clients can't read compressed messages, so only compress them for recipients
+        // that are known to support it
+        let compression = if compress {
+            self.config.compression
+        } else {
+            None
+        };
+        // similarly, erasure coded messages can only be reconstructed by the recipients
+        // that were explicitly declared as supporting it
+        let redundancy = if erasure_coding {
+            self.config.erasure_coding
+        } else {
+            None
+        };
+        let fragments = match redundancy {
+            Some(redundancy) => self.message_preparer.pad_and_split_erasure_coded_message(
+                message,
+                packet_size,
+                compression,
+                redundancy,
+            ),
+            None => self
+                .message_preparer
+                .pad_and_split_message(message, packet_size, compression),
+        };
 
         let mut pending_acks = Vec::with_capacity(fragments.len());
         let mut real_messages = Vec::with_capacity(fragments.len());
@@ -401,12 +550,15 @@ where
                 topology,
                 &self.config.ack_key,
                 &recipient,
+                packet_size,
             )?;
 
             let real_message =
                 RealMessage::new(prepared_fragment.mix_packet, fragment.fragment_identifier());
             let delay = prepared_fragment.total_delay;
-            let pending_ack = PendingAcknowledgement::new_known(fragment, delay, recipient);
+            let pending_ack =
+                PendingAcknowledgement::new_known(fragment, delay, recipient, packet_size)
+                    .with_message_id(message_id);
 
             real_messages.push(real_message);
             pending_acks.push(pending_ack);
@@ -436,6 +588,10 @@ where
             message,
             recipient,
             TransmissionLane::AdditionalReplySurbs,
+            None,
+            None,
+            false,
+            false,
         )
         .await?;
 
@@ -445,12 +601,17 @@ where
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn try_send_message_with_reply_surbs(
         &mut self,
         recipient: Recipient,
         message: Vec<u8>,
         num_reply_surbs: u32,
         lane: TransmissionLane,
+        packet_size: Option<PacketSize>,
+        message_id: Option<MessageId>,
+        compress: bool,
+        erasure_coding: bool,
     ) -> Result<(), SurbWrappedPreparationError> {
         let sender_tag = self.get_or_create_sender_tag(&recipient);
         let (reply_surbs, reply_keys) = self
@@ -460,8 +621,16 @@ where
         let message =
             NymMessage::new_repliable(RepliableMessage::new_data(message, sender_tag, reply_surbs));
 
-        self.try_split_and_send_non_reply_message(message, recipient, lane)
-            .await?;
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            lane,
+            packet_size,
+            message_id,
+            compress,
+            erasure_coding,
+        )
+        .await?;
 
         log::trace!("storing {} reply keys", reply_keys.len());
         self.reply_key_storage.insert_multiple(reply_keys);
@@ -473,13 +642,20 @@ where
         &mut self,
         recipient: Recipient,
         chunk: Fragment,
+        packet_size: PacketSize,
     ) -> Result<PreparedFragment, PreparationError> {
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
         let prepared_fragment = self
             .message_preparer
-            .prepare_chunk_for_sending(chunk, topology, &self.config.ack_key, &recipient)
+            .prepare_chunk_for_sending(
+                chunk,
+                topology,
+                &self.config.ack_key,
+                &recipient,
+                packet_size,
+            )
             .unwrap();
 
         Ok(prepared_fragment)
@@ -515,6 +691,7 @@ where
                         topology,
                         &self.config.ack_key,
                         reply_surb,
+                        self.config.packet_size,
                     )
                     .unwrap()
             })
@@ -534,7 +711,13 @@ where
 
         let prepared_fragment = self
             .message_preparer
-            .prepare_reply_chunk_for_sending(chunk, topology, &self.config.ack_key, reply_surb)
+            .prepare_reply_chunk_for_sending(
+                chunk,
+                topology,
+                &self.config.ack_key,
+                reply_surb,
+                self.config.packet_size,
+            )
             .unwrap();
 
         Ok(prepared_fragment)
@@ -564,3 +747,31 @@ where
             .expect("real message receiver task (OutQueueControl) has died");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requested_packet_sizes_are_mapped_onto_available_ones() {
+        let available = [PacketSize::RegularPacket, PacketSize::ExtendedPacket16];
+
+        assert_eq!(
+            closest_packet_size(PacketSize::ExtendedPacket16, &available),
+            Some(PacketSize::ExtendedPacket16)
+        );
+        assert_eq!(
+            closest_packet_size(PacketSize::ExtendedPacket8, &available),
+            Some(PacketSize::ExtendedPacket16)
+        );
+        assert_eq!(
+            closest_packet_size(PacketSize::ExtendedPacket32, &available),
+            Some(PacketSize::ExtendedPacket16)
+        );
+        assert_eq!(
+            closest_packet_size(PacketSize::RegularPacket, &available),
+            Some(PacketSize::RegularPacket)
+        );
+        assert_eq!(closest_packet_size(PacketSize::RegularPacket, &[]), None);
+    }
+}
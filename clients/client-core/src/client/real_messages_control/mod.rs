@@ -8,6 +8,7 @@
 use self::{
     acknowledgement_control::AcknowledgementController, real_traffic_stream::OutQueueControl,
 };
+use crate::client::events::ClientEventSender;
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::replies::reply_controller::{
     ReplyController, ReplyControllerReceiver, ReplyControllerSender,
@@ -27,6 +28,8 @@ use gateway_client::AcknowledgementReceiver;
 use log::*;
 use nymsphinx::acknowledgements::AckKey;
 use nymsphinx::addressing::clients::Recipient;
+use nymsphinx::chunking::RedundancyRatio;
+use nymsphinx::compression::MessageCompression;
 use nymsphinx::params::PacketSize;
 use rand::{rngs::OsRng, CryptoRng, Rng};
 use std::sync::Arc;
@@ -70,6 +73,24 @@ pub struct Config {
     /// Predefined packet size used for the encapsulated messages.
     packet_size: PacketSize,
 
+    /// If not empty, specifies the packet sizes out of which one is going to be chosen for each
+    /// sent message.
+    packet_size_buckets: Vec<PacketSize>,
+
+    /// Packet size used for the loop cover messages.
+    cover_packet_size: PacketSize,
+
+    /// If set, the given fraction of the loop cover messages is going to use this (extended)
+    /// packet size instead.
+    extended_cover_packet_size: Option<(PacketSize, f64)>,
+
+    /// Optional redundancy ratio used for protecting the sent messages that opted into it
+    /// with forward error correction.
+    erasure_coding: Option<RedundancyRatio>,
+
+    /// Optional compression algorithm applied to the sent messages.
+    compression: Option<MessageCompression>,
+
     /// Defines the minimum number of reply surbs the client would request.
     minimum_reply_surb_request_size: u32,
 
@@ -109,7 +130,8 @@ impl<'a> From<&'a Config> for real_traffic_stream::Config {
             cfg.average_message_sending_delay,
             cfg.disable_main_poisson_packet_distribution,
         )
-        .with_custom_cover_packet_size(cfg.packet_size)
+        .with_custom_cover_packet_size(cfg.cover_packet_size)
+        .with_extended_cover_packet_size(cfg.extended_cover_packet_size)
     }
 }
 
@@ -135,6 +157,9 @@ impl<'a> From<&'a Config> for message_handler::Config {
             cfg.average_ack_delay_duration,
         )
         .with_custom_packet_size(cfg.packet_size)
+        .with_packet_size_buckets(cfg.packet_size_buckets.clone())
+        .with_erasure_coding(cfg.erasure_coding)
+        .with_compression(cfg.compression)
     }
 }
 
@@ -148,6 +173,11 @@ impl Config {
             ack_key,
             self_recipient,
             packet_size: Default::default(),
+            packet_size_buckets: Vec::new(),
+            cover_packet_size: Default::default(),
+            extended_cover_packet_size: None,
+            erasure_coding: None,
+            compression: None,
             ack_wait_addition: base_client_debug_config.ack_wait_addition,
             ack_wait_multiplier: base_client_debug_config.ack_wait_multiplier,
             average_message_sending_delay: base_client_debug_config.message_sending_average_delay,
@@ -171,6 +201,27 @@ impl Config {
     pub fn set_custom_packet_size(&mut self, packet_size: PacketSize) {
         self.packet_size = packet_size;
     }
+
+    pub fn set_packet_size_buckets(&mut self, packet_size_buckets: Vec<PacketSize>) {
+        self.packet_size_buckets = packet_size_buckets;
+    }
+
+    pub fn set_cover_packet_sizes(
+        &mut self,
+        packet_size: PacketSize,
+        extended_packet_size: Option<(PacketSize, f64)>,
+    ) {
+        self.cover_packet_size = packet_size;
+        self.extended_cover_packet_size = extended_packet_size;
+    }
+
+    pub fn set_erasure_coding(&mut self, redundancy: RedundancyRatio) {
+        self.erasure_coding = Some(redundancy);
+    }
+
+    pub fn set_compression(&mut self, compression: MessageCompression) {
+        self.compression = Some(compression);
+    }
 }
 
 pub(crate) struct RealMessagesController<R>
@@ -198,6 +249,7 @@ impl RealMessagesController<OsRng> {
         reply_controller_receiver: ReplyControllerReceiver,
         lane_queue_lengths: LaneQueueLengths,
         client_connection_rx: ConnectionCommandReceiver,
+        client_event_sender: Option<ClientEventSender>,
     ) -> Self {
         let rng = OsRng;
 
@@ -211,6 +263,7 @@ impl RealMessagesController<OsRng> {
             ack_receiver,
             ack_action_tx.clone(),
             ack_action_rx,
+            client_event_sender,
         );
 
         // create all configs for the components
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use self::sending_delay_controller::SendingDelayController;
+use crate::client::cover_traffic_stream::choose_cover_packet_size;
 use crate::client::mix_traffic::BatchMixMessageSender;
 use crate::client::real_messages_control::acknowledgement_control::SentPacketNotificationSender;
 use crate::client::topology_control::TopologyAccessor;
@@ -56,6 +57,10 @@ pub(crate) struct Config {
 
     /// Predefined packet size used for the loop cover messages.
     cover_packet_size: PacketSize,
+
+    /// If set, the given fraction of the loop cover messages is going to use this (extended)
+    /// packet size instead, so that the cover traffic would resemble the real traffic.
+    extended_cover_packet_size: Option<(PacketSize, f64)>,
 }
 
 impl Config {
@@ -75,6 +80,7 @@ impl Config {
             average_message_sending_delay,
             disable_poisson_packet_distribution,
             cover_packet_size: Default::default(),
+            extended_cover_packet_size: None,
         }
     }
 
@@ -82,6 +88,14 @@ impl Config {
         self.cover_packet_size = packet_size;
         self
     }
+
+    pub fn with_extended_cover_packet_size(
+        mut self,
+        extended_packet_size: Option<(PacketSize, f64)>,
+    ) -> Self {
+        self.extended_cover_packet_size = extended_packet_size;
+        self
+    }
 }
 
 pub(crate) struct OutQueueControl<R>
@@ -233,6 +247,12 @@ where
                     }
                 };
 
+                let cover_packet_size = choose_cover_packet_size(
+                    &mut self.rng,
+                    self.config.cover_packet_size,
+                    self.config.extended_cover_packet_size,
+                );
+
                 (
                     generate_loop_cover_packet(
                         &mut self.rng,
@@ -241,7 +261,7 @@ where
                         &self.config.our_full_destination,
                         self.config.average_ack_delay,
                         self.config.average_packet_delay,
-                        self.config.cover_packet_size,
+                        cover_packet_size,
                     )
                     .expect(
                         "Somehow failed to generate a loop cover message with a valid topology",
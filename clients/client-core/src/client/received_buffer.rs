@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::path_health::ReceivedProbesSender;
 use crate::client::replies::reply_controller::ReplyControllerSender;
 use crate::client::replies::reply_storage::SentReplyKeys;
 use crate::spawn_future;
@@ -43,6 +44,9 @@ struct ReceivedMessagesBufferInner {
     // but perhaps it should be changed to include timestamps of when the message was reconstructed
     // and every now and then remove ids older than X
     recently_reconstructed: HashSet<i32>,
+
+    // channel for notifying the path health prober about its packets that made it back to us
+    probe_sender: Option<ReceivedProbesSender>,
 }
 
 impl ReceivedMessagesBufferInner {
@@ -52,6 +56,16 @@ impl ReceivedMessagesBufferInner {
             return None;
         }
 
+        if let Some(probe_id) = nymsphinx::cover::probe_id(fragment_data) {
+            trace!("The message was a loop probe message with id {probe_id}");
+            if let Some(probe_sender) = &self.probe_sender {
+                if probe_sender.unbounded_send(probe_id).is_err() {
+                    debug!("the path health prober has stopped receiving probes");
+                }
+            }
+            return None;
+        }
+
         let fragment = match self.message_receiver.recover_fragment(fragment_data) {
             Err(err) => {
                 warn!("failed to recover fragment from raw data: {err}. The whole underlying message might be corrupted and unrecoverable!");
@@ -141,6 +155,7 @@ impl ReceivedMessagesBuffer {
         local_encryption_keypair: Arc<encryption::KeyPair>,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        probe_sender: Option<ReceivedProbesSender>,
     ) -> Self {
         ReceivedMessagesBuffer {
             inner: Arc::new(Mutex::new(ReceivedMessagesBufferInner {
@@ -149,6 +164,7 @@ impl ReceivedMessagesBuffer {
                 message_receiver: MessageReceiver::new(),
                 message_sender: None,
                 recently_reconstructed: HashSet::new(),
+                probe_sender,
             })),
             reply_key_storage,
             reply_controller_sender,
@@ -472,11 +488,13 @@ impl ReceivedMessagesBufferController {
         mixnet_packet_receiver: MixnetMessageReceiver,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        probe_sender: Option<ReceivedProbesSender>,
     ) -> Self {
         let received_buffer = ReceivedMessagesBuffer::new(
             local_encryption_keypair,
             reply_key_storage,
             reply_controller_sender,
+            probe_sender,
         );
 
         ReceivedMessagesBufferController {
@@ -1,43 +1,381 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::client::replies::reply_storage::backend::Empty;
-use crate::client::replies::reply_storage::{CombinedReplyStorage, ReplyStorageBackend};
+use crate::client::replies::reply_storage::key_storage::UsedReplyKey;
+use crate::client::replies::reply_storage::surb_storage::ReceivedReplySurbs;
+use crate::client::replies::reply_storage::{
+    CombinedReplyStorage, ReceivedReplySurbsMap, ReplyStorageBackend, SentReplyKeys, UsedSenderTags,
+};
 use async_trait::async_trait;
+use log::{error, info};
+use nymsphinx::addressing::clients::RecipientBytes;
+use nymsphinx::anonymous_replies::encryption_key::EncryptionKeyDigest;
+use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nymsphinx::anonymous_replies::{ReplySurb, SurbEncryptionKey};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use wasm_storage::{stores, BrowserStorage, StorageError};
 
-// well, right now we don't have the browser storage : (
-// so we keep everything in memory
+const METADATA_KEY: &str = "metadata";
+const REPLY_KEYS_KEY: &str = "reply_keys";
+const SENDER_TAGS_KEY: &str = "sender_tags";
+const REPLY_SURBS_KEY: &str = "reply_surbs";
+const FLUSH_IN_PROGRESS_KEY: &str = "flush_in_progress";
+const CLIENT_IN_USE_KEY: &str = "client_in_use";
+const LAST_FLUSH_KEY: &str = "last_flush";
+
+#[derive(Serialize, Deserialize)]
+struct ReplySurbStorageMetadata {
+    min_reply_surb_threshold: usize,
+    max_reply_surb_threshold: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredReplyKey {
+    key_digest: Vec<u8>,
+    reply_key: Vec<u8>,
+    sent_at_timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredSenderTag {
+    recipient: Vec<u8>,
+    tag: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredReplySurbs {
+    tag: Vec<u8>,
+    surbs_last_received_at: i64,
+    surbs: Vec<Vec<u8>>,
+}
+
+fn corrupted(key: &str, details: impl Into<String>) -> StorageError {
+    StorageError::malformed_data(stores::REPLY_STORAGE, key, details)
+}
+
+fn parse_sender_tag(key: &str, raw: Vec<u8>) -> Result<AnonymousSenderTag, StorageError> {
+    raw.try_into()
+        .map(AnonymousSenderTag::from_bytes)
+        .map_err(|raw: Vec<u8>| {
+            corrupted(
+                key,
+                format!("sender tag has invalid length of {}", raw.len()),
+            )
+        })
+}
+
+/// Reply storage backend persisting the data in the browser storage (such as IndexedDB).
+/// If no storage is provided, all the data is only kept in memory.
 pub struct Backend {
-    empty: Empty,
+    min_surb_threshold: usize,
+    max_surb_threshold: usize,
+    storage: Option<Arc<dyn BrowserStorage>>,
 }
 
 impl Backend {
     pub fn new(min_surb_threshold: usize, max_surb_threshold: usize) -> Self {
         Backend {
-            empty: Empty {
-                min_surb_threshold,
-                max_surb_threshold,
-            },
+            min_surb_threshold,
+            max_surb_threshold,
+            storage: None,
+        }
+    }
+
+    pub fn new_persistent(
+        storage: Arc<dyn BrowserStorage>,
+        min_surb_threshold: usize,
+        max_surb_threshold: usize,
+    ) -> Self {
+        Backend {
+            min_surb_threshold,
+            max_surb_threshold,
+            storage: Some(storage),
+        }
+    }
+
+    async fn read_value<T: DeserializeOwned>(
+        storage: &dyn BrowserStorage,
+        key: &str,
+    ) -> Result<Option<T>, StorageError> {
+        match storage.read(stores::REPLY_STORAGE, key).await? {
+            None => Ok(None),
+            Some(raw) => serde_json::from_slice(&raw)
+                .map(Some)
+                .map_err(|err| corrupted(key, err.to_string())),
+        }
+    }
+
+    async fn write_value<T: Serialize>(
+        storage: &dyn BrowserStorage,
+        key: &str,
+        value: &T,
+    ) -> Result<(), StorageError> {
+        // serializing our own types into json can't fail
+        let raw = serde_json::to_vec(value).map_err(|err| corrupted(key, err.to_string()))?;
+        storage.write(stores::REPLY_STORAGE, key, &raw).await
+    }
+
+    async fn get_flag(storage: &dyn BrowserStorage, key: &str) -> Result<bool, StorageError> {
+        Ok(Self::read_value(storage, key).await?.unwrap_or_default())
+    }
+
+    async fn set_flag(
+        storage: &dyn BrowserStorage,
+        key: &str,
+        value: bool,
+    ) -> Result<(), StorageError> {
+        Self::write_value(storage, key, &value).await
+    }
+
+    async fn purge(storage: &dyn BrowserStorage, keys: &[&str]) -> Result<(), StorageError> {
+        for key in keys {
+            storage.remove(stores::REPLY_STORAGE, key).await?;
+        }
+        Ok(())
+    }
+
+    // follows the same invalidation rules as the on-disk storage of the native clients
+    async fn purge_untrusted_data(storage: &dyn BrowserStorage) -> Result<(), StorageError> {
+        if Self::get_flag(storage, FLUSH_IN_PROGRESS_KEY).await? {
+            error!("the previous reply data flush hasn't been completed - we can't trust any of the stored data. It shall get purged");
+            return Self::purge(storage, &[REPLY_SURBS_KEY, REPLY_KEYS_KEY, SENDER_TAGS_KEY]).await;
+        }
+
+        if Self::get_flag(storage, CLIENT_IN_USE_KEY).await? {
+            error!("the client hasn't undergone through graceful shutdown the last time it's gone down - we can't trust its reply surbs or stored encryption keys. They shall get purged");
+            Self::purge(storage, &[REPLY_SURBS_KEY, REPLY_KEYS_KEY]).await?;
+        }
+
+        let Some(last_flush) = Self::read_value::<i64>(storage, LAST_FLUSH_KEY).await? else {
+            return Ok(());
+        };
+        let last_flush = OffsetDateTime::from_unix_timestamp(last_flush)
+            .map_err(|err| corrupted(LAST_FLUSH_KEY, err.to_string()))?;
+
+        let since_last_flush = OffsetDateTime::now_utc() - last_flush;
+        if since_last_flush.whole_days() > 0 {
+            info!("it's been over {} days since we last used our data store. our reply surbs are already outdated - we're going to purge them now.", since_last_flush.whole_days());
+            Self::purge(storage, &[REPLY_SURBS_KEY]).await?;
+        }
+        if since_last_flush.whole_days() > 1 {
+            info!("it's been over {} days since we last used our data store. our reply keys are already outdated - we're going to purge them now.", since_last_flush.whole_days());
+            Self::purge(storage, &[REPLY_KEYS_KEY]).await?;
         }
+        if since_last_flush.whole_days() > 2 {
+            info!("it's been over {} days since we last used our data store. our used sender tags are already outdated - we're going to purge them now.", since_last_flush.whole_days());
+            Self::purge(storage, &[SENDER_TAGS_KEY]).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn load_reply_keys(storage: &dyn BrowserStorage) -> Result<SentReplyKeys, StorageError> {
+        let stored: Vec<StoredReplyKey> = Self::read_value(storage, REPLY_KEYS_KEY)
+            .await?
+            .unwrap_or_default();
+
+        // stop at the first instance of corruption. if even a single entry is malformed,
+        // something weird has happened and we can't trust the rest of the data
+        let raw = stored
+            .into_iter()
+            .map(|stored| -> Result<_, StorageError> {
+                let digest =
+                    EncryptionKeyDigest::from_exact_iter(stored.key_digest).ok_or_else(|| {
+                        corrupted(REPLY_KEYS_KEY, "reply key digest has invalid length")
+                    })?;
+                let reply_key = SurbEncryptionKey::try_from_bytes(&stored.reply_key)
+                    .map_err(|err| corrupted(REPLY_KEYS_KEY, err.to_string()))?;
+                Ok((
+                    digest,
+                    UsedReplyKey::new(reply_key, stored.sent_at_timestamp),
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(SentReplyKeys::from_raw(raw))
+    }
+
+    async fn load_sender_tags(
+        storage: &dyn BrowserStorage,
+    ) -> Result<UsedSenderTags, StorageError> {
+        let stored: Vec<StoredSenderTag> = Self::read_value(storage, SENDER_TAGS_KEY)
+            .await?
+            .unwrap_or_default();
+
+        let raw = stored
+            .into_iter()
+            .map(|stored| -> Result<_, StorageError> {
+                let recipient: RecipientBytes = stored
+                    .recipient
+                    .try_into()
+                    .map_err(|_| corrupted(SENDER_TAGS_KEY, "recipient has invalid length"))?;
+                Ok((recipient, parse_sender_tag(SENDER_TAGS_KEY, stored.tag)?))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(UsedSenderTags::from_raw(raw))
+    }
+
+    async fn load_reply_surbs(
+        storage: &dyn BrowserStorage,
+        metadata: ReplySurbStorageMetadata,
+    ) -> Result<ReceivedReplySurbsMap, StorageError> {
+        let stored: Vec<StoredReplySurbs> = Self::read_value(storage, REPLY_SURBS_KEY)
+            .await?
+            .unwrap_or_default();
+
+        let raw = stored
+            .into_iter()
+            .map(|stored| -> Result<_, StorageError> {
+                let tag = parse_sender_tag(REPLY_SURBS_KEY, stored.tag)?;
+                let surbs = stored
+                    .surbs
+                    .iter()
+                    .map(|surb| {
+                        ReplySurb::from_bytes(surb)
+                            .map_err(|err| corrupted(REPLY_SURBS_KEY, err.to_string()))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok((
+                    tag,
+                    ReceivedReplySurbs::new_retrieved(surbs, stored.surbs_last_received_at),
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(ReceivedReplySurbsMap::from_raw(
+            metadata.min_reply_surb_threshold,
+            metadata.max_reply_surb_threshold,
+            raw,
+        ))
+    }
+
+    async fn dump_storage(
+        storage: &dyn BrowserStorage,
+        data: &CombinedReplyStorage,
+    ) -> Result<(), StorageError> {
+        let reply_keys = data
+            .key_storage_ref()
+            .as_raw_iter()
+            .map(|map_ref| {
+                let (digest, key) = map_ref.pair();
+                StoredReplyKey {
+                    key_digest: digest.to_vec(),
+                    reply_key: (**key).to_bytes(),
+                    sent_at_timestamp: key.sent_at_timestamp,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let sender_tags = data
+            .tags_storage_ref()
+            .as_raw_iter()
+            .map(|map_ref| {
+                let (recipient, tag) = map_ref.pair();
+                StoredSenderTag {
+                    recipient: recipient.to_vec(),
+                    tag: tag.to_bytes().to_vec(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let surbs_ref = data.surbs_storage_ref();
+        let metadata = ReplySurbStorageMetadata {
+            min_reply_surb_threshold: surbs_ref.min_surb_threshold(),
+            max_reply_surb_threshold: surbs_ref.max_surb_threshold(),
+        };
+        let reply_surbs = surbs_ref
+            .as_raw_iter()
+            .map(|map_ref| {
+                let (tag, received) = map_ref.pair();
+                StoredReplySurbs {
+                    tag: tag.to_bytes().to_vec(),
+                    surbs_last_received_at: received.surbs_last_received_at(),
+                    surbs: received.surbs_ref().iter().map(|s| s.to_bytes()).collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Self::write_value(storage, METADATA_KEY, &metadata).await?;
+        Self::write_value(storage, REPLY_KEYS_KEY, &reply_keys).await?;
+        Self::write_value(storage, SENDER_TAGS_KEY, &sender_tags).await?;
+        Self::write_value(storage, REPLY_SURBS_KEY, &reply_surbs).await
     }
 }
 
-#[async_trait]
+#[async_trait(?Send)]
 impl ReplyStorageBackend for Backend {
-    type StorageError = <Empty as ReplyStorageBackend>::StorageError;
+    type StorageError = StorageError;
+
+    async fn start_storage_session(&self) -> Result<(), Self::StorageError> {
+        match &self.storage {
+            Some(storage) => Self::set_flag(storage.as_ref(), CLIENT_IN_USE_KEY, true).await,
+            None => Ok(()),
+        }
+    }
 
     async fn flush_surb_storage(
         &mut self,
         storage: &CombinedReplyStorage,
     ) -> Result<(), Self::StorageError> {
-        self.empty.flush_surb_storage(storage).await
+        let Some(backing) = &self.storage else {
+            return Ok(());
+        };
+        let backing = backing.as_ref();
+
+        Self::set_flag(backing, FLUSH_IN_PROGRESS_KEY, true).await?;
+        Self::dump_storage(backing, storage).await?;
+        Self::write_value(
+            backing,
+            LAST_FLUSH_KEY,
+            &OffsetDateTime::now_utc().unix_timestamp(),
+        )
+        .await?;
+        Self::set_flag(backing, FLUSH_IN_PROGRESS_KEY, false).await
     }
 
     async fn init_fresh(&mut self, fresh: &CombinedReplyStorage) -> Result<(), Self::StorageError> {
-        self.empty.init_fresh(fresh).await
+        let Some(storage) = &self.storage else {
+            return Ok(());
+        };
+
+        let surbs_ref = fresh.surbs_storage_ref();
+        let metadata = ReplySurbStorageMetadata {
+            min_reply_surb_threshold: surbs_ref.min_surb_threshold(),
+            max_reply_surb_threshold: surbs_ref.max_surb_threshold(),
+        };
+        Self::write_value(storage.as_ref(), METADATA_KEY, &metadata).await
     }
 
     async fn load_surb_storage(&self) -> Result<CombinedReplyStorage, Self::StorageError> {
-        self.empty.load_surb_storage().await
+        let fresh = || CombinedReplyStorage::new(self.min_surb_threshold, self.max_surb_threshold);
+
+        let Some(storage) = &self.storage else {
+            return Ok(fresh());
+        };
+        let storage = storage.as_ref();
+
+        let Some(metadata) = Self::read_value(storage, METADATA_KEY).await? else {
+            info!("there's no reply data in the browser storage - starting fresh");
+            return Ok(fresh());
+        };
+
+        Self::purge_untrusted_data(storage).await?;
+
+        let reply_keys = Self::load_reply_keys(storage).await?;
+        let tags = Self::load_sender_tags(storage).await?;
+        let reply_surbs = Self::load_reply_surbs(storage, metadata).await?;
+
+        Ok(CombinedReplyStorage::load(reply_keys, reply_surbs, tags))
+    }
+
+    async fn stop_storage_session(self) -> Result<(), Self::StorageError> {
+        match &self.storage {
+            Some(storage) => Self::set_flag(storage.as_ref(), CLIENT_IN_USE_KEY, false).await,
+            None => Ok(()),
+        }
     }
 }
@@ -25,7 +25,8 @@ pub struct Empty {
     max_surb_threshold: usize,
 }
 
-#[async_trait]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl ReplyStorageBackend for Empty {
     type StorageError = UndefinedError;
 
@@ -51,7 +52,9 @@ impl ReplyStorageBackend for Empty {
     }
 }
 
-#[async_trait]
+// browser storage futures are not `Send`, but it doesn't matter in the single-threaded wasm
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait ReplyStorageBackend: Sized {
     type StorageError: Error + 'static;
 
@@ -0,0 +1,777 @@
+// Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::topology_control::quorum::TopologyView;
+use crate::spawn_future;
+use futures::StreamExt;
+use log::*;
+use nymsphinx::addressing::clients::Recipient;
+use nymsphinx::params::DEFAULT_NUM_MIX_HOPS;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, RwLockReadGuard};
+use topology::{nym_topology_from_detailed, MixId, NymTopology, NymTopologyError};
+use url::Url;
+use validator_client::ValidatorClientError;
+
+#[cfg(not(target_arch = "wasm32"))]
+use nymsphinx::addressing::nodes::NodeIdentity;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+use validator_client::{
+    models::{TopologyDiff, TopologyUpdate, TOPOLOGY_STREAM_KEEPALIVE_INTERVAL},
+    nym_api::{error::NymAPIError, TopologyUpdateStream},
+};
+
+mod quorum;
+mod signed;
+
+// if nothing, not even a keepalive, has been received over the topology stream for this long,
+// the connection is assumed to be dead (for example after the nym-api went away without closing it)
+#[cfg(not(target_arch = "wasm32"))]
+const TOPOLOGY_STREAM_TIMEOUT: Duration =
+    Duration::from_secs(3 * TOPOLOGY_STREAM_KEEPALIVE_INTERVAL.as_secs());
+
+#[cfg(not(target_arch = "wasm32"))]
+enum TopologyStreamItem {
+    // `None` indicates the stream has been closed
+    Received(Option<Result<TopologyUpdate, NymAPIError>>),
+    TimedOut,
+}
+
+// topology stream is not available in the browser, so no item can ever be received
+#[cfg(target_arch = "wasm32")]
+type TopologyStreamItem = std::convert::Infallible;
+
+/// Client used for obtaining the network topology directly from the mixnet contract.
+#[cfg(feature = "chain-topology")]
+pub type ChainQueryClient = validator_client::Client<validator_client::nymd::QueryNymdClient>;
+
+/// Creates a client for querying the mixnet contract (as defined by the environment)
+/// through the specified nymd endpoint.
+#[cfg(feature = "chain-topology")]
+pub fn new_chain_query_client(nymd_url: Url) -> Result<ChainQueryClient, ValidatorClientError> {
+    let details = network_defaults::NymNetworkDetails::new_from_env();
+    let config =
+        validator_client::Config::try_from_nym_network_details(&details)?.with_nymd_url(nymd_url);
+    validator_client::Client::new_query(config)
+}
+
+// I'm extremely curious why compiler NEVER complained about lack of Debug here before
+#[derive(Debug)]
+pub struct TopologyAccessorInner {
+    topology: Option<NymTopology>,
+
+    // weights of the mixnodes as determined by the path health prober, they're kept separately
+    // so that they could be applied to any new topology obtained by the refresher
+    mix_weights: HashMap<MixId, f64>,
+}
+
+impl AsRef<Option<NymTopology>> for TopologyAccessorInner {
+    fn as_ref(&self) -> &Option<NymTopology> {
+        &self.topology
+    }
+}
+
+impl TopologyAccessorInner {
+    fn new() -> Self {
+        TopologyAccessorInner {
+            topology: None,
+            mix_weights: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, mut new: Option<NymTopology>) {
+        if let Some(topology) = new.as_mut() {
+            topology.set_mix_weights(self.mix_weights.clone())
+        }
+        self.topology = new;
+    }
+
+    fn update_mix_weights(&mut self, mix_weights: HashMap<MixId, f64>) {
+        if let Some(topology) = self.topology.as_mut() {
+            topology.set_mix_weights(mix_weights.clone())
+        }
+        self.mix_weights = mix_weights;
+    }
+}
+
+pub struct TopologyReadPermit<'a> {
+    permit: RwLockReadGuard<'a, TopologyAccessorInner>,
+}
+
+impl<'a> Deref for TopologyReadPermit<'a> {
+    type Target = TopologyAccessorInner;
+
+    fn deref(&self) -> &Self::Target {
+        &self.permit
+    }
+}
+
+impl<'a> TopologyReadPermit<'a> {
+    /// Using provided topology read permit, tries to get an immutable reference to the underlying
+    /// topology. For obvious reasons the lifetime of the topology reference is bound to the permit.
+    pub(super) fn try_get_valid_topology_ref(
+        &'a self,
+        ack_recipient: &Recipient,
+        packet_recipient: Option<&Recipient>,
+    ) -> Result<&'a NymTopology, NymTopologyError> {
+        // 1. Have we managed to get anything from the refresher, i.e. have the nym-api queries gone through?
+        let topology = self
+            .permit
+            .as_ref()
+            .as_ref()
+            .ok_or(NymTopologyError::EmptyNetworkTopology)?;
+
+        // 2. does it have any mixnode at all?
+        // 3. does it have any gateways at all?
+        // 4. does it have a mixnode on each layer?
+        topology.ensure_can_construct_path_through(DEFAULT_NUM_MIX_HOPS)?;
+
+        // 5. does it contain OUR gateway (so that we could create an ack packet)?
+        if !topology.gateway_exists(ack_recipient.gateway()) {
+            return Err(NymTopologyError::NonExistentGatewayError {
+                identity_key: ack_recipient.gateway().to_base58_string(),
+            });
+        }
+
+        // 6. for our target recipient, does it contain THEIR gateway (so that we could create
+        if let Some(recipient) = packet_recipient {
+            if !topology.gateway_exists(recipient.gateway()) {
+                return Err(NymTopologyError::NonExistentGatewayError {
+                    identity_key: recipient.gateway().to_base58_string(),
+                });
+            }
+        }
+
+        Ok(topology)
+    }
+}
+
+impl<'a> From<RwLockReadGuard<'a, TopologyAccessorInner>> for TopologyReadPermit<'a> {
+    fn from(read_permit: RwLockReadGuard<'a, TopologyAccessorInner>) -> Self {
+        TopologyReadPermit {
+            permit: read_permit,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TopologyAccessor {
+    // `RwLock` *seems to* be the better approach for this as write access is only requested every
+    // few seconds, while reads are needed every single packet generated.
+    // However, proper benchmarks will be needed to determine if `RwLock` is indeed a better
+    // approach than a `Mutex`
+    inner: Arc<RwLock<TopologyAccessorInner>>,
+}
+
+impl TopologyAccessor {
+    pub fn new() -> Self {
+        TopologyAccessor {
+            inner: Arc::new(RwLock::new(TopologyAccessorInner::new())),
+        }
+    }
+
+    pub async fn get_read_permit(&self) -> TopologyReadPermit<'_> {
+        self.inner.read().await.into()
+    }
+
+    pub(crate) async fn update_global_topology(&self, new_topology: Option<NymTopology>) {
+        self.inner.write().await.update(new_topology);
+    }
+
+    pub(crate) async fn update_mix_weights(&self, mix_weights: HashMap<MixId, f64>) {
+        self.inner.write().await.update_mix_weights(mix_weights);
+    }
+
+    // only used by the client at startup to get a slightly more reasonable error message
+    pub async fn ensure_is_routable(&self) -> Result<(), NymTopologyError> {
+        match &self.inner.read().await.topology {
+            None => Err(NymTopologyError::EmptyNetworkTopology),
+            Some(ref topology) => topology.ensure_can_construct_path_through(DEFAULT_NUM_MIX_HOPS),
+        }
+    }
+}
+
+impl Default for TopologyAccessor {
+    fn default() -> Self {
+        TopologyAccessor::new()
+    }
+}
+
+pub struct TopologyRefresherConfig {
+    nym_api_urls: Vec<Url>,
+    refresh_rate: Duration,
+    client_version: String,
+    verification_quorum: Option<usize>,
+    use_topology_stream: bool,
+    trusted_topology_signers: Option<Vec<String>>,
+}
+
+impl TopologyRefresherConfig {
+    pub fn new(nym_api_urls: Vec<Url>, refresh_rate: Duration, client_version: String) -> Self {
+        TopologyRefresherConfig {
+            nym_api_urls,
+            refresh_rate,
+            client_version,
+            verification_quorum: None,
+            use_topology_stream: false,
+            trusted_topology_signers: None,
+        }
+    }
+
+    /// Makes the refresher query all the nym-apis (and the mixnet contract, if available)
+    /// and only accept the topology if at least `quorum` of them agree on it.
+    #[must_use]
+    pub fn with_verification_quorum(mut self, quorum: usize) -> Self {
+        self.verification_quorum = Some(quorum);
+        self
+    }
+
+    /// Makes the refresher subscribe to the stream of topology updates of the nym-api
+    /// instead of polling it, whenever possible. It has no effect if the topology is verified
+    /// against multiple sources.
+    #[must_use]
+    pub fn with_topology_stream(mut self, use_topology_stream: bool) -> Self {
+        self.use_topology_stream = use_topology_stream;
+        self
+    }
+
+    /// Makes the refresher only accept topology documents signed by one of the trusted signers,
+    /// i.e. the ones specified here and the ones listed in the mixnet contract, if it's available.
+    /// It takes precedence over the verification quorum.
+    #[must_use]
+    pub fn with_signed_topology(mut self, trusted_signers: Vec<String>) -> Self {
+        self.trusted_topology_signers = Some(trusted_signers);
+        self
+    }
+}
+
+async fn fetch_nym_api_topology(
+    client: &validator_client::client::ApiClient,
+) -> Result<NymTopology, ValidatorClientError> {
+    let mixnodes = client.get_cached_active_mixnodes().await?;
+    let gateways = client.get_cached_gateways().await?;
+    Ok(nym_topology_from_detailed(mixnodes, gateways))
+}
+
+#[cfg(feature = "chain-topology")]
+async fn fetch_chain_topology(
+    client: &ChainQueryClient,
+) -> Result<NymTopology, ValidatorClientError> {
+    let active_set: std::collections::HashSet<_> = client
+        .get_all_nymd_rewarded_set_mixnodes()
+        .await?
+        .into_iter()
+        .filter(|(_, status)| status.is_active())
+        .map(|(mix_id, _)| mix_id)
+        .collect();
+
+    let mixnodes = client
+        .get_all_nymd_mixnodes_detailed()
+        .await?
+        .into_iter()
+        .filter(|details| active_set.contains(&details.bond_information.mix_id))
+        .collect();
+    let gateways = client.get_all_nymd_gateways().await?;
+
+    Ok(nym_topology_from_detailed(mixnodes, gateways))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn apply_topology_diff(topology: &mut NymTopology, diff: TopologyDiff) {
+    for mix_id in diff.removed_mixnodes {
+        topology.remove_mixnode(mix_id);
+    }
+    for details in diff.added_mixnodes {
+        // explicitly remove the old entry in case the updated one turned out to be malformed
+        topology.remove_mixnode(details.mix_id());
+        topology.insert_detailed_mixnode(details)
+    }
+    for identity in diff.removed_gateways {
+        match NodeIdentity::from_base58_string(&identity) {
+            Ok(identity) => {
+                topology.remove_gateway(&identity);
+            }
+            Err(err) => warn!("received malformed gateway identity {identity} - {err}"),
+        }
+    }
+    for bond in diff.added_gateways {
+        topology.insert_gateway_bond(bond)
+    }
+}
+
+pub struct TopologyRefresher {
+    validator_client: validator_client::client::ApiClient,
+    client_version: String,
+
+    nym_api_urls: Vec<Url>,
+    topology_accessor: TopologyAccessor,
+    refresh_rate: Duration,
+
+    currently_used_api: usize,
+    was_latest_valid: bool,
+
+    verification_quorum: Option<usize>,
+    #[cfg(feature = "chain-topology")]
+    chain_client: Option<ChainQueryClient>,
+
+    // if set, only the topology documents signed by those (or on-chain) signers are accepted
+    trusted_topology_signers: Option<HashSet<String>>,
+    // epoch of the most recently accepted signed topology, so that an older document
+    // couldn't be replayed to us
+    latest_signed_epoch: Option<u32>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    use_topology_stream: bool,
+    // the mutex is never locked, it's only there so that the refresher would remain `Sync`
+    #[cfg(not(target_arch = "wasm32"))]
+    topology_stream: Option<tokio::sync::Mutex<TopologyUpdateStream>>,
+
+    // time the last item has been received over the topology stream (or it got established)
+    #[cfg(not(target_arch = "wasm32"))]
+    last_stream_activity: Instant,
+
+    // the full topology as received through the stream, i.e. before any filtering,
+    // so that subsequent diffs could be applied to it
+    #[cfg(not(target_arch = "wasm32"))]
+    streamed_topology: Option<NymTopology>,
+}
+
+impl TopologyRefresher {
+    pub fn new(mut cfg: TopologyRefresherConfig, topology_accessor: TopologyAccessor) -> Self {
+        cfg.nym_api_urls.shuffle(&mut thread_rng());
+
+        #[cfg(target_arch = "wasm32")]
+        if cfg.use_topology_stream {
+            warn!("the topology stream is not available in the browser - the nym-api is going to be polled instead")
+        }
+
+        TopologyRefresher {
+            validator_client: validator_client::client::ApiClient::new(cfg.nym_api_urls[0].clone()),
+            client_version: cfg.client_version,
+            nym_api_urls: cfg.nym_api_urls,
+            topology_accessor,
+            refresh_rate: cfg.refresh_rate,
+            currently_used_api: 0,
+            was_latest_valid: true,
+            verification_quorum: cfg.verification_quorum,
+            #[cfg(feature = "chain-topology")]
+            chain_client: None,
+            trusted_topology_signers: cfg
+                .trusted_topology_signers
+                .map(|signers| signers.into_iter().collect()),
+            latest_signed_epoch: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            use_topology_stream: cfg.use_topology_stream,
+            #[cfg(not(target_arch = "wasm32"))]
+            topology_stream: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            last_stream_activity: Instant::now(),
+            #[cfg(not(target_arch = "wasm32"))]
+            streamed_topology: None,
+        }
+    }
+
+    /// Makes the mixnet contract one of the sources of the network topology
+    /// when it's being verified.
+    #[cfg(feature = "chain-topology")]
+    pub fn set_chain_client(&mut self, chain_client: ChainQueryClient) {
+        self.chain_client = Some(chain_client);
+    }
+
+    /// Returns the number of sources the topology can be obtained from when it's being verified.
+    pub fn available_topology_sources(&self) -> usize {
+        #[cfg(feature = "chain-topology")]
+        let chain_sources = usize::from(self.chain_client.is_some());
+
+        #[cfg(not(feature = "chain-topology"))]
+        let chain_sources = 0;
+
+        self.nym_api_urls.len() + chain_sources
+    }
+
+    fn use_next_nym_api(&mut self) {
+        if self.nym_api_urls.len() == 1 {
+            warn!("There's only a single nym API available - it won't be possible to use a different one");
+            return;
+        }
+
+        self.currently_used_api = (self.currently_used_api + 1) % self.nym_api_urls.len();
+        self.validator_client
+            .change_nym_api(self.nym_api_urls[self.currently_used_api].clone())
+    }
+
+    /// Verifies whether nodes a reasonably distributed among all mix layers.
+    ///
+    /// In ideal world we would have 33% nodes on layer 1, 33% on layer 2 and 33% on layer 3.
+    /// However, this is a rather unrealistic expectation, instead we check whether there exists
+    /// a layer with more than 66% of nodes or with fewer than 15% and if so, we trigger a failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `topology`: active topology constructed from validator api data
+    fn check_layer_distribution(&self, active_topology: &NymTopology) -> bool {
+        let mixes = active_topology.mixes();
+        let mixnodes_count = active_topology.num_mixnodes();
+
+        if active_topology.gateways().is_empty() {
+            return false;
+        }
+
+        // trivial check to see if have at least a single node on each layer (regardless of active set size)
+        if mixes.get(&1).is_none() || mixes.get(&2).is_none() || mixes.get(&3).is_none() {
+            return false;
+        }
+
+        let upper_bound = (mixnodes_count as f32 * 0.66) as usize;
+        let lower_bound = (mixnodes_count as f32 * 0.15) as usize;
+
+        let layer1 = mixes.get(&1).unwrap().len();
+        let layer2 = mixes.get(&2).unwrap().len();
+        let layer3 = mixes.get(&3).unwrap().len();
+
+        if layer1 < lower_bound || layer1 > upper_bound {
+            warn!(
+                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
+                mixnodes_count, layer1, layer2, layer3
+            );
+            return false;
+        }
+
+        if layer2 < lower_bound || layer2 > upper_bound {
+            warn!(
+                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
+                mixnodes_count, layer1, layer2, layer3
+            );
+            return false;
+        }
+
+        if layer3 < lower_bound || layer3 > upper_bound {
+            warn!(
+                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
+                mixnodes_count, layer1, layer2, layer3
+            );
+            return false;
+        }
+
+        true
+    }
+
+    #[cfg(feature = "chain-topology")]
+    async fn get_chain_topology_view(&self) -> Option<TopologyView> {
+        let chain_client = self.chain_client.as_ref()?;
+        match fetch_chain_topology(chain_client).await {
+            Ok(topology) => Some(TopologyView::new("the mixnet contract", topology)),
+            Err(err) => {
+                warn!("failed to get the network topology from the mixnet contract - {err}");
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "chain-topology"))]
+    async fn get_chain_topology_view(&self) -> Option<TopologyView> {
+        None
+    }
+
+    // obtains the topology from all the available sources and accepts it only if enough of them
+    // have agreed on it so that a single malicious (or stale) nym-api couldn't affect us
+    async fn get_verified_topology(&self, quorum: usize) -> Option<NymTopology> {
+        let api_responses =
+            futures::future::join_all(self.nym_api_urls.iter().map(|url| async move {
+                let client = validator_client::client::ApiClient::new(url.clone());
+                (url, fetch_nym_api_topology(&client).await)
+            }))
+            .await;
+
+        let mut views = Vec::with_capacity(api_responses.len() + 1);
+        for (url, response) in api_responses {
+            match response {
+                Ok(topology) => views.push(TopologyView::new(url.as_str(), topology)),
+                Err(err) => warn!("failed to get the network topology from {url} - {err}"),
+            }
+        }
+
+        if let Some(chain_view) = self.get_chain_topology_view().await {
+            views.push(chain_view)
+        }
+
+        match quorum::select_by_quorum(views, quorum) {
+            Ok(topology) => Some(topology),
+            Err(err) => {
+                error!("failed to obtain a verified network topology - {err}");
+                None
+            }
+        }
+    }
+
+    #[cfg(feature = "chain-topology")]
+    async fn get_chain_topology_signers(&self) -> Vec<String> {
+        let chain_client = match self.chain_client.as_ref() {
+            Some(chain_client) => chain_client,
+            None => return Vec::new(),
+        };
+        match chain_client.get_nymd_topology_signers().await {
+            Ok(signers) => signers,
+            Err(err) => {
+                warn!("failed to get the topology signers from the mixnet contract - {err}");
+                Vec::new()
+            }
+        }
+    }
+
+    #[cfg(not(feature = "chain-topology"))]
+    async fn get_chain_topology_signers(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    // obtains the topology document from the current nym-api and accepts it only if it has been
+    // signed by one of the trusted signers, so that it doesn't matter where it came from
+    async fn get_signed_topology(&mut self, configured: HashSet<String>) -> Option<NymTopology> {
+        let mut trusted_signers = configured;
+        trusted_signers.extend(self.get_chain_topology_signers().await);
+        if trusted_signers.is_empty() {
+            error!(
+                "there are no trusted topology signers - the network topology can't be verified"
+            );
+            return None;
+        }
+
+        let signed = match self.validator_client.get_signed_topology().await {
+            Ok(signed) => signed,
+            Err(err) => {
+                error!("failed to get the signed network topology - {err}");
+                return None;
+            }
+        };
+
+        match signed::verify_signed_topology(&signed, &trusted_signers, self.latest_signed_epoch) {
+            Ok(document) => {
+                self.latest_signed_epoch = Some(document.epoch_id);
+                Some(signed::topology_from_document(document))
+            }
+            Err(err) => {
+                error!(
+                    "rejected the network topology signed by {} - {err}",
+                    signed.signer
+                );
+                None
+            }
+        }
+    }
+
+    fn make_compatible(&self, topology: NymTopology) -> Option<NymTopology> {
+        let topology = topology.filter_system_version(&self.client_version);
+
+        if !self.check_layer_distribution(&topology) {
+            warn!("The current filtered active topology has extremely skewed layer distribution. It cannot be used.");
+            None
+        } else {
+            Some(topology)
+        }
+    }
+
+    async fn get_current_compatible_topology(&mut self) -> Option<NymTopology> {
+        // TODO: optimization for the future:
+        // only refresh mixnodes on timer and refresh gateways only when
+        // we have to send to a new, unknown, gateway
+
+        if let Some(trusted_signers) = self.trusted_topology_signers.clone() {
+            let topology = self.get_signed_topology(trusted_signers).await?;
+            return self.make_compatible(topology);
+        }
+
+        let topology = match self.verification_quorum {
+            Some(quorum) => self.get_verified_topology(quorum).await?,
+            None => match fetch_nym_api_topology(&self.validator_client).await {
+                Ok(topology) => topology,
+                Err(err) => {
+                    error!("failed to get the network topology - {err}");
+                    return None;
+                }
+            },
+        };
+
+        self.make_compatible(topology)
+    }
+
+    pub async fn refresh(&mut self) {
+        trace!("Refreshing the topology");
+        let new_topology = self.get_current_compatible_topology().await;
+
+        if new_topology.is_none() {
+            self.use_next_nym_api();
+        }
+
+        self.update_topology(new_topology).await
+    }
+
+    async fn update_topology(&mut self, new_topology: Option<NymTopology>) {
+        if new_topology.is_none() && self.was_latest_valid {
+            // if we failed to grab this topology, but the one before it was alright, let's assume
+            // validator had a tiny hiccup and use the old data
+            warn!("we're going to keep on using the old topology for this iteration");
+            self.was_latest_valid = false;
+            return;
+        } else if new_topology.is_some() {
+            self.was_latest_valid = true;
+        }
+
+        self.topology_accessor
+            .update_global_topology(new_topology)
+            .await;
+    }
+
+    pub async fn ensure_topology_is_routable(&self) -> Result<(), NymTopologyError> {
+        self.topology_accessor.ensure_is_routable().await
+    }
+
+    // a single nym-api can't be the only source of the topology if it's meant to be verified
+    // and the streamed diffs are not signed
+    #[cfg(not(target_arch = "wasm32"))]
+    fn should_use_topology_stream(&self) -> bool {
+        self.use_topology_stream
+            && self.verification_quorum.is_none()
+            && self.trusted_topology_signers.is_none()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn try_subscribe_to_topology_stream(&mut self) -> bool {
+        if !self.should_use_topology_stream() {
+            return false;
+        }
+
+        match self.validator_client.subscribe_to_topology_updates().await {
+            Ok(stream) => {
+                debug!(
+                    "subscribed to the topology updates of {}",
+                    self.nym_api_urls[self.currently_used_api]
+                );
+                self.topology_stream = Some(tokio::sync::Mutex::new(stream));
+                self.last_stream_activity = Instant::now();
+                self.streamed_topology = None;
+                true
+            }
+            Err(err) => {
+                warn!("failed to subscribe to the topology updates - {err}. Going to poll the nym-api instead");
+                false
+            }
+        }
+    }
+
+    async fn on_refresh_tick(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.topology_stream.is_some() || self.try_subscribe_to_topology_stream().await {
+            // the topology is going to be updated as soon as anything changes
+            return;
+        }
+
+        self.refresh().await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn next_topology_stream_item(&mut self) -> TopologyStreamItem {
+        let deadline =
+            tokio::time::Instant::from_std(self.last_stream_activity + TOPOLOGY_STREAM_TIMEOUT);
+        match self.topology_stream.as_mut() {
+            Some(stream) => {
+                match tokio::time::timeout_at(deadline, stream.get_mut().next()).await {
+                    Ok(item) => TopologyStreamItem::Received(item),
+                    Err(_) => TopologyStreamItem::TimedOut,
+                }
+            }
+            None => futures::future::pending().await,
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn next_topology_stream_item(&mut self) -> TopologyStreamItem {
+        futures::future::pending().await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn handle_topology_stream_item(&mut self, item: TopologyStreamItem) {
+        let update = match item {
+            TopologyStreamItem::Received(Some(Ok(update))) => update,
+            TopologyStreamItem::Received(Some(Err(err))) => {
+                warn!("the topology stream has failed - {err}. Going to poll the nym-api until we manage to resubscribe");
+                self.topology_stream = None;
+                return self.refresh().await;
+            }
+            TopologyStreamItem::Received(None) => {
+                warn!("the topology stream has been closed. Going to poll the nym-api until we manage to resubscribe");
+                self.topology_stream = None;
+                return self.refresh().await;
+            }
+            TopologyStreamItem::TimedOut => {
+                warn!("nothing has been received over the topology stream for {TOPOLOGY_STREAM_TIMEOUT:?}. Going to poll the nym-api until we manage to resubscribe");
+                self.topology_stream = None;
+                return self.refresh().await;
+            }
+        };
+        self.last_stream_activity = Instant::now();
+
+        let topology = match update {
+            TopologyUpdate::Snapshot { mixnodes, gateways } => {
+                nym_topology_from_detailed(mixnodes, gateways)
+            }
+            // keepalive, there's nothing to update
+            TopologyUpdate::Diff(diff) if diff.is_empty() => return,
+            TopologyUpdate::Diff(diff) => {
+                let Some(mut topology) = self.streamed_topology.take() else {
+                    warn!("received a topology diff before the snapshot - resubscribing");
+                    self.topology_stream = None;
+                    return;
+                };
+                apply_topology_diff(&mut topology, diff);
+                topology
+            }
+        };
+
+        let new_topology = self.make_compatible(topology.clone());
+        self.streamed_topology = Some(topology);
+        self.update_topology(new_topology).await
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn handle_topology_stream_item(&mut self, item: TopologyStreamItem) {
+        match item {}
+    }
+
+    pub fn start_with_shutdown(mut self, mut shutdown: task::TaskClient) {
+        spawn_future(async move {
+            debug!("Started TopologyRefresher with graceful shutdown support");
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let mut interval = tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(
+                self.refresh_rate,
+            ));
+
+            #[cfg(target_arch = "wasm32")]
+            let mut interval =
+                gloo_timers::future::IntervalStream::new(self.refresh_rate.as_millis() as u32);
+
+            while !shutdown.is_shutdown() {
+                tokio::select! {
+                    _ = interval.next() => {
+                        self.on_refresh_tick().await;
+                    },
+                    item = self.next_topology_stream_item() => {
+                        self.handle_topology_stream_item(item).await;
+                    },
+                    _ = shutdown.recv() => {
+                        log::trace!("TopologyRefresher: Received shutdown");
+                    },
+                }
+            }
+            shutdown.recv_timeout().await;
+            log::debug!("TopologyRefresher: Exiting");
+        })
+    }
+}
@@ -0,0 +1,365 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use log::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+use thiserror::Error;
+use topology::{MixId, MixLayer, NymTopology};
+
+#[derive(Debug, Error)]
+pub(crate) enum TopologyQuorumError {
+    #[error(
+        "only {available} topology sources have responded while {quorum} are required to agree"
+    )]
+    NotEnoughResponses { available: usize, quorum: usize },
+
+    #[error("at most {agreeing} topology sources have agreed on the network view while {quorum} are required")]
+    QuorumNotReached { agreeing: usize, quorum: usize },
+
+    #[error("{agreeing} topology sources have agreed on one network view, but the same number agreed on another one")]
+    ConflictingViews { agreeing: usize },
+}
+
+// all the information about a mixnode that has to be identical between different sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MixnodeEntry {
+    layer: MixLayer,
+    host: String,
+    identity_key: String,
+    sphinx_key: String,
+}
+
+// all the information about a gateway that has to be identical between different sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GatewayEntry {
+    host: String,
+    sphinx_key: String,
+}
+
+/// Canonical representation of the network view used for comparing topologies obtained
+/// from different sources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TopologyFingerprint {
+    mixnodes: BTreeMap<MixId, MixnodeEntry>,
+    gateways: BTreeMap<String, GatewayEntry>,
+}
+
+impl From<&NymTopology> for TopologyFingerprint {
+    fn from(topology: &NymTopology) -> Self {
+        let mixnodes = topology
+            .mixes()
+            .iter()
+            .flat_map(|(layer, nodes)| {
+                nodes.iter().map(|node| {
+                    (
+                        node.mix_id,
+                        MixnodeEntry {
+                            layer: *layer,
+                            host: node.host.to_string(),
+                            identity_key: node.identity_key.to_base58_string(),
+                            sphinx_key: node.sphinx_key.to_base58_string(),
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        let gateways = topology
+            .gateways()
+            .iter()
+            .map(|node| {
+                (
+                    node.identity_key.to_base58_string(),
+                    GatewayEntry {
+                        host: node.host.to_string(),
+                        sphinx_key: node.sphinx_key.to_base58_string(),
+                    },
+                )
+            })
+            .collect();
+
+        TopologyFingerprint { mixnodes, gateways }
+    }
+}
+
+/// Summary of the differences between the network view of some source and the accepted one.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct TopologyDisagreement {
+    pub(crate) missing_mixnodes: BTreeSet<MixId>,
+    pub(crate) unexpected_mixnodes: BTreeSet<MixId>,
+    pub(crate) altered_mixnodes: BTreeSet<MixId>,
+    pub(crate) missing_gateways: BTreeSet<String>,
+    pub(crate) unexpected_gateways: BTreeSet<String>,
+    pub(crate) altered_gateways: BTreeSet<String>,
+}
+
+impl TopologyDisagreement {
+    fn new(accepted: &TopologyFingerprint, other: &TopologyFingerprint) -> Self {
+        let mut disagreement = TopologyDisagreement::default();
+
+        for (mix_id, entry) in &accepted.mixnodes {
+            match other.mixnodes.get(mix_id) {
+                None => {
+                    disagreement.missing_mixnodes.insert(*mix_id);
+                }
+                Some(other_entry) if other_entry != entry => {
+                    disagreement.altered_mixnodes.insert(*mix_id);
+                }
+                _ => (),
+            }
+        }
+        for mix_id in other.mixnodes.keys() {
+            if !accepted.mixnodes.contains_key(mix_id) {
+                disagreement.unexpected_mixnodes.insert(*mix_id);
+            }
+        }
+
+        for (identity, entry) in &accepted.gateways {
+            match other.gateways.get(identity) {
+                None => {
+                    disagreement.missing_gateways.insert(identity.clone());
+                }
+                Some(other_entry) if other_entry != entry => {
+                    disagreement.altered_gateways.insert(identity.clone());
+                }
+                _ => (),
+            }
+        }
+        for identity in other.gateways.keys() {
+            if !accepted.gateways.contains_key(identity) {
+                disagreement.unexpected_gateways.insert(identity.clone());
+            }
+        }
+
+        disagreement
+    }
+}
+
+impl Display for TopologyDisagreement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "missing mixnodes: {:?}, unexpected mixnodes: {:?}, altered mixnodes: {:?}, \
+            missing gateways: {:?}, unexpected gateways: {:?}, altered gateways: {:?}",
+            self.missing_mixnodes,
+            self.unexpected_mixnodes,
+            self.altered_mixnodes,
+            self.missing_gateways,
+            self.unexpected_gateways,
+            self.altered_gateways
+        )
+    }
+}
+
+/// Network topology as reported by particular source, such as a nym-api or the mixnet contract.
+pub(crate) struct TopologyView {
+    source: String,
+    topology: NymTopology,
+}
+
+impl TopologyView {
+    pub(crate) fn new<S: Into<String>>(source: S, topology: NymTopology) -> Self {
+        TopologyView {
+            source: source.into(),
+            topology,
+        }
+    }
+}
+
+/// Chooses the network view that has been reported by the greatest number of sources,
+/// as long as at least `quorum` of them agree on it. All the sources that disagree with
+/// the chosen view are reported.
+pub(crate) fn select_by_quorum(
+    views: Vec<TopologyView>,
+    quorum: usize,
+) -> Result<NymTopology, TopologyQuorumError> {
+    if views.len() < quorum {
+        return Err(TopologyQuorumError::NotEnoughResponses {
+            available: views.len(),
+            quorum,
+        });
+    }
+
+    // group the views by their fingerprints; there's going to be just a handful of sources,
+    // so the quadratic complexity doesn't matter
+    let mut groups: Vec<(TopologyFingerprint, Vec<TopologyView>)> = Vec::new();
+    for view in views {
+        let fingerprint = TopologyFingerprint::from(&view.topology);
+        match groups.iter_mut().find(|(known, _)| known == &fingerprint) {
+            Some((_, group)) => group.push(view),
+            None => groups.push((fingerprint, vec![view])),
+        }
+    }
+    groups.sort_by_key(|(_, group)| std::cmp::Reverse(group.len()));
+
+    let mut groups = groups.into_iter().peekable();
+    // the unwrap is fine as there's at least `quorum` views, i.e. at least one group
+    // (quorum of 0 is rejected during client startup)
+    let (accepted_fingerprint, mut accepted_group) = groups.next().unwrap();
+    let is_tied = groups
+        .peek()
+        .map(|(_, group)| group.len() == accepted_group.len())
+        .unwrap_or_default();
+
+    for (fingerprint, group) in groups {
+        let disagreement = TopologyDisagreement::new(&accepted_fingerprint, &fingerprint);
+        for view in group {
+            warn!(
+                "the network topology reported by {} differs from the one reported by {} - {disagreement}",
+                view.source,
+                accepted_group
+                    .iter()
+                    .map(|view| view.source.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+        }
+    }
+
+    if accepted_group.len() < quorum {
+        return Err(TopologyQuorumError::QuorumNotReached {
+            agreeing: accepted_group.len(),
+            quorum,
+        });
+    }
+
+    if is_tied {
+        return Err(TopologyQuorumError::ConflictingViews {
+            agreeing: accepted_group.len(),
+        });
+    }
+
+    // the unwrap is fine as the group is never empty
+    Ok(accepted_group.pop().unwrap().topology)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::Layer;
+    use std::collections::HashMap;
+    use topology::mix;
+
+    fn mix_node(mix_id: MixId, host: &str) -> mix::Node {
+        mix::Node {
+            mix_id,
+            owner: "N/A".to_string(),
+            host: host.parse().unwrap(),
+            mix_host: format!("{host}:1789").parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(
+                "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
+            )
+            .unwrap(),
+            layer: Layer::One,
+            version: "0.x.0".to_string(),
+        }
+    }
+
+    fn topology(nodes: Vec<(MixLayer, mix::Node)>) -> NymTopology {
+        let mut mixes: HashMap<MixLayer, Vec<mix::Node>> = HashMap::new();
+        for (layer, node) in nodes {
+            mixes.entry(layer).or_default().push(node)
+        }
+        NymTopology::new(mixes, vec![])
+    }
+
+    fn honest_topology() -> NymTopology {
+        topology(vec![
+            (1, mix_node(1, "1.1.1.1")),
+            (2, mix_node(2, "2.2.2.2")),
+            (3, mix_node(3, "3.3.3.3")),
+        ])
+    }
+
+    #[test]
+    fn majority_view_is_accepted() {
+        let malicious = topology(vec![
+            (1, mix_node(1, "6.6.6.6")),
+            (2, mix_node(2, "2.2.2.2")),
+            (3, mix_node(4, "6.6.6.7")),
+        ]);
+
+        let views = vec![
+            TopologyView::new("api1", malicious),
+            TopologyView::new("api2", honest_topology()),
+            TopologyView::new("chain", honest_topology()),
+        ];
+
+        let accepted = select_by_quorum(views, 2).unwrap();
+        assert_eq!(
+            TopologyFingerprint::from(&accepted),
+            TopologyFingerprint::from(&honest_topology())
+        );
+    }
+
+    #[test]
+    fn topology_is_rejected_without_quorum() {
+        let stale = topology(vec![
+            (1, mix_node(1, "1.1.1.1")),
+            (2, mix_node(2, "2.2.2.2")),
+        ]);
+
+        let views = vec![
+            TopologyView::new("api1", stale),
+            TopologyView::new("api2", honest_topology()),
+        ];
+        assert!(matches!(
+            select_by_quorum(views, 2),
+            Err(TopologyQuorumError::QuorumNotReached {
+                agreeing: 1,
+                quorum: 2
+            })
+        ));
+
+        let views = vec![TopologyView::new("api1", honest_topology())];
+        assert!(matches!(
+            select_by_quorum(views, 2),
+            Err(TopologyQuorumError::NotEnoughResponses {
+                available: 1,
+                quorum: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn topology_is_rejected_if_views_are_tied() {
+        let other = topology(vec![(1, mix_node(1, "6.6.6.6"))]);
+
+        let views = vec![
+            TopologyView::new("api1", other.clone()),
+            TopologyView::new("api2", honest_topology()),
+            TopologyView::new("api3", other),
+            TopologyView::new("api4", honest_topology()),
+        ];
+        assert!(matches!(
+            select_by_quorum(views, 2),
+            Err(TopologyQuorumError::ConflictingViews { agreeing: 2 })
+        ));
+    }
+
+    #[test]
+    fn disagreements_are_described() {
+        let other = topology(vec![
+            (2, mix_node(1, "1.1.1.1")),
+            (2, mix_node(2, "2.2.2.2")),
+            (3, mix_node(4, "4.4.4.4")),
+        ]);
+
+        let disagreement = TopologyDisagreement::new(
+            &TopologyFingerprint::from(&honest_topology()),
+            &TopologyFingerprint::from(&other),
+        );
+
+        assert_eq!(disagreement.missing_mixnodes, [3].into_iter().collect());
+        assert_eq!(disagreement.unexpected_mixnodes, [4].into_iter().collect());
+        // node 1 is on a different layer
+        assert_eq!(disagreement.altered_mixnodes, [1].into_iter().collect());
+        assert!(disagreement.missing_gateways.is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::asymmetric::identity;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use topology::NymTopology;
+use validator_client::models::{SignedTopology, TopologyDocument};
+
+#[derive(Debug, Error)]
+pub(crate) enum TopologySignatureError {
+    #[error("the topology document has been signed by {signer}, which is not a trusted signer")]
+    UntrustedSigner { signer: String },
+
+    #[error("the identity key of the topology signer is malformed - {0}")]
+    MalformedSigner(#[source] identity::Ed25519RecoveryError),
+
+    #[error("the signature on the topology document is malformed - {0}")]
+    MalformedSignature(#[source] identity::Ed25519RecoveryError),
+
+    #[error("the signature on the topology document is invalid")]
+    InvalidSignature,
+
+    #[error("the signed topology document is malformed - {0}")]
+    MalformedDocument(#[from] serde_json::Error),
+
+    #[error("the topology document for epoch {received} is older than the already seen one for epoch {latest}")]
+    StaleDocument { received: u32, latest: u32 },
+}
+
+/// Ensures the topology document has been signed by one of the trusted signers and that it's not
+/// older than the most recent document we have already accepted.
+pub(crate) fn verify_signed_topology(
+    signed: &SignedTopology,
+    trusted_signers: &HashSet<String>,
+    latest_epoch: Option<u32>,
+) -> Result<TopologyDocument, TopologySignatureError> {
+    if !trusted_signers.contains(&signed.signer) {
+        return Err(TopologySignatureError::UntrustedSigner {
+            signer: signed.signer.clone(),
+        });
+    }
+
+    let signer = identity::PublicKey::from_base58_string(&signed.signer)
+        .map_err(TopologySignatureError::MalformedSigner)?;
+    let signature = identity::Signature::from_base58_string(&signed.signature)
+        .map_err(TopologySignatureError::MalformedSignature)?;
+    signer
+        .verify(signed.document.as_bytes(), &signature)
+        .map_err(|_| TopologySignatureError::InvalidSignature)?;
+
+    // only parse the document once we know it's authentic
+    let document: TopologyDocument = serde_json::from_str(&signed.document)?;
+    if let Some(latest) = latest_epoch {
+        if document.epoch_id < latest {
+            return Err(TopologySignatureError::StaleDocument {
+                received: document.epoch_id,
+                latest,
+            });
+        }
+    }
+
+    Ok(document)
+}
+
+/// Builds the network topology out of the active mixnodes and gateways of the document.
+pub(crate) fn topology_from_document(document: TopologyDocument) -> NymTopology {
+    let mut topology =
+        NymTopology::new(HashMap::new(), Vec::with_capacity(document.gateways.len()));
+    for mixnode in document.mixnodes {
+        if mixnode.status.is_active() {
+            topology.insert_mixnode_bond(mixnode.bond)
+        }
+    }
+    for bond in document.gateways {
+        topology.insert_gateway_bond(bond)
+    }
+    topology
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_document(keypair: &identity::KeyPair, epoch_id: u32) -> SignedTopology {
+        let document = serde_json::to_string(&TopologyDocument {
+            epoch_id,
+            mixnodes: vec![],
+            gateways: vec![],
+        })
+        .unwrap();
+
+        SignedTopology {
+            signer: keypair.public_key().to_base58_string(),
+            signature: keypair.private_key().sign_text(&document),
+            document,
+        }
+    }
+
+    #[test]
+    fn only_documents_signed_by_trusted_signers_are_accepted() {
+        let mut rng = rand::rngs::OsRng;
+        let trusted = identity::KeyPair::new(&mut rng);
+        let untrusted = identity::KeyPair::new(&mut rng);
+        let trusted_signers = [trusted.public_key().to_base58_string()]
+            .into_iter()
+            .collect();
+
+        let document =
+            verify_signed_topology(&signed_document(&trusted, 42), &trusted_signers, Some(41))
+                .unwrap();
+        assert_eq!(document.epoch_id, 42);
+
+        assert!(matches!(
+            verify_signed_topology(&signed_document(&untrusted, 42), &trusted_signers, None),
+            Err(TopologySignatureError::UntrustedSigner { .. })
+        ));
+
+        // signature made by someone else on behalf of the trusted signer
+        let mut forged = signed_document(&untrusted, 42);
+        forged.signer = trusted.public_key().to_base58_string();
+        assert!(matches!(
+            verify_signed_topology(&forged, &trusted_signers, None),
+            Err(TopologySignatureError::InvalidSignature)
+        ));
+
+        // document altered after it has been signed
+        let mut altered = signed_document(&trusted, 42);
+        altered.document = altered.document.replace("42", "43");
+        assert!(matches!(
+            verify_signed_topology(&altered, &trusted_signers, None),
+            Err(TopologySignatureError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn stale_documents_are_rejected() {
+        let trusted = identity::KeyPair::new(&mut rand::rngs::OsRng);
+        let trusted_signers = [trusted.public_key().to_base58_string()]
+            .into_iter()
+            .collect();
+
+        assert!(matches!(
+            verify_signed_topology(&signed_document(&trusted, 41), &trusted_signers, Some(42)),
+            Err(TopologySignatureError::StaleDocument {
+                received: 41,
+                latest: 42
+            })
+        ));
+    }
+}
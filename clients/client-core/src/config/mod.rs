@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use config::{NymConfig, DB_FILE_NAME};
+use nymsphinx::compression::MessageCompression;
 use nymsphinx::params::PacketSize;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
@@ -25,10 +26,11 @@ const DEFAULT_MESSAGE_STREAM_AVERAGE_DELAY: Duration = Duration::from_millis(20)
 const DEFAULT_AVERAGE_PACKET_DELAY: Duration = Duration::from_millis(50);
 const DEFAULT_TOPOLOGY_REFRESH_RATE: Duration = Duration::from_secs(5 * 60); // every 5min
 const DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT: Duration = Duration::from_millis(5_000);
-// Set this to a high value for now, so that we don't risk sporadic timeouts that might cause
-// bought bandwidth tokens to not have time to be spent; Once we remove the gateway from the
-// bandwidth bridging protocol, we can come back to a smaller timeout value
-const DEFAULT_GATEWAY_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_PATH_HEALTH_PROBING_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_PATH_HEALTH_EXCLUSION_THRESHOLD: f64 = 0.5;
+// gateways no longer redeem the bandwidth credentials on chain while the client is waiting,
+// so there's no need to wait for minutes for the response
+const DEFAULT_GATEWAY_RESPONSE_TIMEOUT: Duration = Duration::from_millis(1_500);
 
 // reply-surbs related:
 
@@ -310,6 +312,38 @@ impl<T> Config<T> {
         self.debug.topology_resolution_timeout
     }
 
+    pub fn get_topology_verification_quorum(&self) -> Option<usize> {
+        self.debug.topology_verification_quorum
+    }
+
+    pub fn get_verify_topology_against_chain(&self) -> bool {
+        self.debug.verify_topology_against_chain
+    }
+
+    pub fn get_disabled_topology_stream(&self) -> bool {
+        self.debug.disable_topology_stream
+    }
+
+    pub fn get_verify_topology_signatures(&self) -> bool {
+        self.debug.verify_topology_signatures
+    }
+
+    pub fn get_trusted_topology_signers(&self) -> &[String] {
+        &self.debug.trusted_topology_signers
+    }
+
+    pub fn get_disabled_path_health_probing(&self) -> bool {
+        self.debug.disable_path_health_probing
+    }
+
+    pub fn get_path_health_probing_interval(&self) -> Duration {
+        self.debug.path_health_probing_interval
+    }
+
+    pub fn get_path_health_exclusion_threshold(&self) -> f64 {
+        self.debug.path_health_exclusion_threshold
+    }
+
     pub fn get_disabled_loop_cover_traffic_stream(&self) -> bool {
         self.debug.disable_loop_cover_traffic_stream
     }
@@ -322,6 +356,18 @@ impl<T> Config<T> {
         self.debug.use_extended_packet_size
     }
 
+    pub fn get_use_adaptive_packet_size(&self) -> bool {
+        self.debug.use_adaptive_packet_size
+    }
+
+    pub fn get_erasure_coding_redundancy(&self) -> Option<f64> {
+        self.debug.erasure_coding_redundancy
+    }
+
+    pub fn get_message_compression(&self) -> Option<CompressionAlgorithm> {
+        self.debug.message_compression
+    }
+
     pub fn get_minimum_reply_surb_storage_threshold(&self) -> usize {
         self.debug.minimum_reply_surb_storage_threshold
     }
@@ -581,12 +627,51 @@ pub struct DebugConfig {
     #[serde(with = "humantime_serde")]
     pub topology_refresh_rate: Duration,
 
-    /// During topology refresh, test packets are sent through every single possible network
-    /// path. This timeout determines waiting period until it is decided that the packet
-    /// did not reach its destination.
+    /// Path health probes are periodically sent through randomly chosen network paths.
+    /// This timeout determines waiting period until it is decided that the probe
+    /// did not reach its destination and all the nodes on its path get penalised.
     #[serde(with = "humantime_serde")]
     pub topology_resolution_timeout: Duration,
 
+    /// If set, the network topology is obtained from all the configured nym-apis (and the mixnet
+    /// contract if `verify_topology_against_chain` is enabled) and it's only accepted if at least
+    /// this many sources agree on it. Otherwise a single nym-api is trusted.
+    pub topology_verification_quorum: Option<usize>,
+
+    /// Controls whether the rewarded set and the layer assignment should also be read directly
+    /// from the mixnet contract when verifying the network topology. It is not available in the browser.
+    pub verify_topology_against_chain: bool,
+
+    /// Controls whether the client should rely solely on periodically polling the nym-api
+    /// for the network topology rather than subscribing to its stream of topology updates.
+    /// The stream is never used when the topology is being verified or in the browser.
+    pub disable_topology_stream: bool,
+
+    /// Controls whether the client should only accept network topology documents signed by
+    /// one of the trusted nym-apis, i.e. the ones listed in `trusted_topology_signers`
+    /// and the ones registered in the mixnet contract if `verify_topology_against_chain` is enabled.
+    /// It takes precedence over the `topology_verification_quorum`.
+    pub verify_topology_signatures: bool,
+
+    /// Base58-encoded identity keys of the nym-apis whose signed topology documents are trusted.
+    pub trusted_topology_signers: Vec<String>,
+
+    /// Controls whether the client should stop probing the health of the network paths
+    /// and hence choose the mixnodes for its packets uniformly at random.
+    /// Note that the probes are sent in place of the loop cover messages, so probing is also
+    /// disabled whenever the loop cover traffic stream is.
+    pub disable_path_health_probing: bool,
+
+    /// The minimum delay between sending subsequent path health probes. The probes replace
+    /// the loop cover messages, so the actual delay depends on the cover traffic rate.
+    #[serde(with = "humantime_serde")]
+    pub path_health_probing_interval: Duration,
+
+    /// Reliability (within [0, 1], estimated from the recent probes) below which mixnodes
+    /// are excluded from the routes of sent packets. Nodes above it are chosen proportionally
+    /// to their reliability.
+    pub path_health_exclusion_threshold: f64,
+
     /// Controls whether the dedicated loop cover traffic stream should be enabled.
     /// (and sending packets, on average, every [Self::loop_cover_traffic_average_delay])
     pub disable_loop_cover_traffic_stream: bool,
@@ -598,6 +683,34 @@ pub struct DebugConfig {
     /// Controls whether the sent sphinx packet use a NON-DEFAULT bigger size.
     pub use_extended_packet_size: Option<ExtendedPacketSize>,
 
+    /// Controls whether the client should choose the sphinx packet size separately for each message,
+    /// based on its length, between the regular size and the extended size (as specified by
+    /// `use_extended_packet_size` or 32kB if not set). Only those two sizes are ever used
+    /// in order to not fragment the anonymity set. Replies are always sent using the default packet size.
+    pub use_adaptive_packet_size: bool,
+
+    /// Fraction (within [0, 1]) of the cover traffic sent using the extended packet size
+    /// when `use_adaptive_packet_size` is enabled. By default, all cover packets are of the regular size.
+    /// It should roughly match the share of extended packets in the real traffic of the client,
+    /// so that the cover traffic would resemble it.
+    pub extended_cover_packet_ratio: f64,
+
+    /// If set, the sent messages are going to be protected with forward error correction,
+    /// where the value specifies the ratio of parity to data packets, for example 0.25 means
+    /// an additional packet for every 4 data packets. This allows the recipient to reconstruct
+    /// the message without waiting for retransmissions of lost packets, at the cost of extra bandwidth.
+    /// It must be within (0, 1]. Since older clients can't reconstruct erasure coded messages,
+    /// only the messages whose recipients are declared as supporting it
+    /// (`InputMessage::with_erasure_coding`) are protected. Replies are never erasure coded.
+    pub erasure_coding_redundancy: Option<f64>,
+
+    /// If set, content of the sent messages is going to be compressed with the specified algorithm,
+    /// unless it would not have made them any smaller. This reduces the number of sphinx packets
+    /// required for text-heavy traffic. Since older clients can't read compressed messages,
+    /// only the messages whose recipients are declared as supporting it (`InputMessage::with_compression`)
+    /// are compressed. Replies are never compressed.
+    pub message_compression: Option<CompressionAlgorithm>,
+
     /// Defines the minimum number of reply surbs the client wants to keep in its storage at all times.
     /// It can only allow to go below that value if its to request additional reply surbs.
     pub minimum_reply_surb_storage_threshold: usize,
@@ -638,6 +751,49 @@ pub enum ExtendedPacketSize {
     Extended32,
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    Deflate,
+}
+
+impl DebugConfig {
+    /// Returns all packet sizes the client can choose from for each of its messages.
+    /// If the packet size is not chosen adaptively, it's empty.
+    pub fn packet_size_buckets(&self) -> Vec<PacketSize> {
+        if !self.use_adaptive_packet_size {
+            return Vec::new();
+        }
+
+        let extended = self
+            .use_extended_packet_size
+            .unwrap_or(ExtendedPacketSize::Extended32);
+
+        vec![PacketSize::RegularPacket, extended.into()]
+    }
+
+    /// Returns the packet size used for the cover traffic alongside the optional extended
+    /// packet size and the fraction of the cover traffic that should use it instead.
+    pub fn cover_packet_sizes(&self) -> (PacketSize, Option<(PacketSize, f64)>) {
+        let extended: Option<PacketSize> = self.use_extended_packet_size.map(Into::into);
+        if !self.use_adaptive_packet_size {
+            return (extended.unwrap_or_default(), None);
+        }
+
+        let ratio = self.extended_cover_packet_ratio.clamp(0.0, 1.0);
+        let extended_cover = if ratio > 0.0 {
+            Some((
+                extended.unwrap_or_else(|| ExtendedPacketSize::Extended32.into()),
+                ratio,
+            ))
+        } else {
+            None
+        };
+
+        (PacketSize::RegularPacket, extended_cover)
+    }
+}
+
 impl Default for DebugConfig {
     fn default() -> Self {
         DebugConfig {
@@ -650,9 +806,21 @@ impl Default for DebugConfig {
             gateway_response_timeout: DEFAULT_GATEWAY_RESPONSE_TIMEOUT,
             topology_refresh_rate: DEFAULT_TOPOLOGY_REFRESH_RATE,
             topology_resolution_timeout: DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT,
+            topology_verification_quorum: None,
+            verify_topology_against_chain: false,
+            disable_topology_stream: false,
+            verify_topology_signatures: false,
+            trusted_topology_signers: Vec::new(),
+            disable_path_health_probing: false,
+            path_health_probing_interval: DEFAULT_PATH_HEALTH_PROBING_INTERVAL,
+            path_health_exclusion_threshold: DEFAULT_PATH_HEALTH_EXCLUSION_THRESHOLD,
             disable_loop_cover_traffic_stream: false,
             disable_main_poisson_packet_distribution: false,
             use_extended_packet_size: None,
+            use_adaptive_packet_size: false,
+            extended_cover_packet_ratio: 0.0,
+            erasure_coding_redundancy: None,
+            message_compression: None,
             minimum_reply_surb_storage_threshold: DEFAULT_MINIMUM_REPLY_SURB_STORAGE_THRESHOLD,
             maximum_reply_surb_storage_threshold: DEFAULT_MAXIMUM_REPLY_SURB_STORAGE_THRESHOLD,
             minimum_reply_surb_request_size: DEFAULT_MINIMUM_REPLY_SURB_REQUEST_SIZE,
@@ -665,6 +833,14 @@ impl Default for DebugConfig {
     }
 }
 
+impl From<CompressionAlgorithm> for MessageCompression {
+    fn from(algorithm: CompressionAlgorithm) -> MessageCompression {
+        match algorithm {
+            CompressionAlgorithm::Deflate => MessageCompression::Deflate,
+        }
+    }
+}
+
 impl From<ExtendedPacketSize> for PacketSize {
     fn from(size: ExtendedPacketSize) -> PacketSize {
         match size {
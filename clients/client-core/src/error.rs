@@ -56,6 +56,12 @@ pub enum ClientCoreError<B: ReplyStorageBackend> {
 
     #[error("Unexpected exit")]
     UnexpectedExit,
+
+    #[error("The erasure coding redundancy ratio ({0}) is invalid. It must be within (0, 1]")]
+    InvalidErasureCodingRedundancy(f64),
+
+    #[error("The topology verification quorum of {quorum} can't be reached with {sources} available topology sources")]
+    UnreachableTopologyQuorum { quorum: usize, sources: usize },
 }
 
 /// Set of messages that the client can send to listeners via the task manager
@@ -1,14 +1,26 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::error::Result;
+use crate::error::{CredentialClientError, Result};
 use bip39::Mnemonic;
-use network_defaults::{NymNetworkDetails, VOUCHER_INFO};
+use coconut_bandwidth_contract_common::events::{DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_EXPIRY_EPOCH};
+use coconut_bandwidth_contract_common::spend_credential::SpendCredential;
+use coconut_interface::Credential;
+use network_defaults::NymNetworkDetails;
 use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 use url::Url;
+use validator_client::coconut::VerifyRefundBody;
 use validator_client::nymd;
-use validator_client::nymd::traits::CoconutBandwidthSigningClient;
-use validator_client::nymd::{Coin, Fee, NymdClient, SigningNymdClient};
+use validator_client::nymd::cosmwasm_client::logs::{find_attribute, BANDWIDTH_PROPOSAL_ID};
+use validator_client::nymd::traits::{
+    CoconutBandwidthQueryClient, CoconutBandwidthSigningClient, MultisigSigningClient,
+};
+use validator_client::nymd::{AccountId, Coin, Fee, NymdClient, SigningNymdClient};
+use validator_client::CoconutApiClient;
+
+const ONE_HOUR_SEC: u64 = 3600;
+const MAX_FEEGRANT_UNYM: u128 = 10000;
 
 pub(crate) struct Client {
     nymd_client: NymdClient<SigningNymdClient>,
@@ -31,25 +43,145 @@ impl Client {
         }
     }
 
+    /// Deposits the funds for a credential of the provided type, returning the hash of the deposit
+    /// transaction alongside the expiry epoch the contract assigned to the credential.
     pub async fn deposit(
         &self,
         amount: u64,
+        voucher_info: &str,
         verification_key: String,
         encryption_key: String,
         fee: Option<Fee>,
-    ) -> Result<String> {
+    ) -> Result<(String, u64)> {
         let amount = Coin::new(amount as u128, self.mix_denom_base.clone());
-        Ok(self
+        let res = self
             .nymd_client
             .deposit(
                 amount,
-                String::from(VOUCHER_INFO),
+                voucher_info.to_string(),
                 verification_key,
                 encryption_key,
                 fee,
             )
+            .await?;
+
+        let expiry_epoch = find_attribute(
+            &res.logs,
+            &format!("wasm-{}", DEPOSITED_FUNDS_EVENT_TYPE),
+            DEPOSIT_EXPIRY_EPOCH,
+        )
+        .and_then(|attr| attr.value.parse().ok())
+        .ok_or(CredentialClientError::MissingDepositExpiry)?;
+
+        Ok((res.transaction_hash.to_string(), expiry_epoch))
+    }
+
+    pub fn address(&self) -> &AccountId {
+        self.nymd_client.address()
+    }
+
+    /// Looks up the credential with the provided blinded serial number in the set of spent
+    /// credentials, which also holds the refunded ones.
+    pub async fn spent_credential(
+        &self,
+        blinded_serial_number: String,
+    ) -> Result<Option<SpendCredential>> {
+        Ok(self
+            .nymd_client
+            .get_spent_credential(blinded_serial_number)
             .await?
-            .transaction_hash
-            .to_string())
+            .spend_credential)
+    }
+
+    /// Claims the refund of the provided credential, gets the nym-apis to vote on it and
+    /// executes the refund proposal afterwards. As with the gateways redeeming credentials,
+    /// the fees of the votes are paid through allowances granted for the duration of the refund.
+    pub async fn refund(
+        &self,
+        credential: &Credential,
+        nym_api_clients: &[CoconutApiClient],
+    ) -> Result<()> {
+        let res = self
+            .nymd_client
+            .refund_credential(
+                Coin::new(
+                    credential.voucher_value().into(),
+                    self.mix_denom_base.clone(),
+                ),
+                credential.blinded_serial_number(),
+                credential.expiry_epoch(),
+                None,
+            )
+            .await?;
+        let proposal_id = find_attribute(&res.logs, "wasm", BANDWIDTH_PROPOSAL_ID)
+            .and_then(|attr| attr.value.parse::<u64>().ok())
+            .ok_or(CredentialClientError::MissingProposalId)?;
+
+        let mut granted = Vec::with_capacity(nym_api_clients.len());
+        let mut result = Ok(());
+        for client in nym_api_clients {
+            if let Err(err) = self.grant_allowance(&client.cosmos_address).await {
+                result = Err(err);
+                break;
+            }
+            granted.push(&client.cosmos_address);
+        }
+
+        if result.is_ok() {
+            let req =
+                VerifyRefundBody::new(credential.clone(), proposal_id, self.address().clone());
+            // the proposal passes as long as enough of the nym-apis have voted for it
+            for client in nym_api_clients {
+                match client.api_client.verify_refund(&req).await {
+                    Ok(res) if !res.verification_result => eprintln!(
+                        "{} didn't accept the refund",
+                        client.api_client.nym_api_client.current_url()
+                    ),
+                    Err(err) => eprintln!(
+                        "Failed to get the vote of {}: {err}",
+                        client.api_client.nym_api_client.current_url()
+                    ),
+                    _ => {}
+                }
+            }
+            result = self
+                .nymd_client
+                .execute_proposal(proposal_id, None)
+                .await
+                .map(|_| ())
+                .map_err(Into::into);
+        }
+
+        // Use a custom multiplier for revoke, as the default one (1.3) isn't enough
+        let revoke_fee = Some(Fee::Auto(Some(1.5)));
+        for grantee in granted {
+            if let Err(err) = self
+                .nymd_client
+                .revoke_allowance(
+                    grantee,
+                    "Cleanup the previous allowance for refunding funds".to_string(),
+                    revoke_fee.clone(),
+                )
+                .await
+            {
+                eprintln!("Failed to revoke the allowance of {grantee}: {err}");
+            }
+        }
+
+        result
+    }
+
+    async fn grant_allowance(&self, grantee: &AccountId) -> Result<()> {
+        self.nymd_client
+            .grant_allowance(
+                grantee,
+                vec![Coin::new(MAX_FEEGRANT_UNYM, self.mix_denom_base.clone())],
+                SystemTime::now().checked_add(Duration::from_secs(ONE_HOUR_SEC)),
+                vec![String::from("/cosmwasm.wasm.v1.MsgExecuteContract")],
+                "Create allowance to vote the refund of funds".to_string(),
+                None,
+            )
+            .await?;
+        Ok(())
     }
 }
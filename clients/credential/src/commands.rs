@@ -5,14 +5,25 @@ use clap::{Args, Subcommand};
 use completions::ArgShell;
 use rand::rngs::OsRng;
 use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use coconut_interface::{Base58, Parameters};
+use coconut_bandwidth_contract_common::expiry::{epoch_at, is_refundable};
+use coconut_bandwidth_contract_common::spend_credential::SpendCredentialStatus;
+use coconut_interface::{
+    is_divisible_value, Attribute, Base58, BlindSignRequest, Parameters, Signature,
+};
+use credential_storage::models::CoconutCredential;
 use credential_storage::storage::Storage;
 use credential_storage::PersistentStorage;
-use credentials::coconut::bandwidth::{BandwidthVoucher, TOTAL_ATTRIBUTES};
-use credentials::coconut::utils::obtain_aggregate_signature;
+use credentials::coconut::bandwidth::{
+    prepare_for_spending, BandwidthVoucher, TICKET_INDEX_BITS, TOTAL_ATTRIBUTES,
+};
+use credentials::coconut::utils::{
+    obtain_aggregate_signature, obtain_aggregate_verification_key, SignerRequestConfig,
+};
 use crypto::asymmetric::{encryption, identity};
-use network_defaults::{NymNetworkDetails, VOUCHER_INFO};
+use network_defaults::{NymNetworkDetails, DIVISIBLE_VOUCHER_INFO, VOUCHER_INFO};
+use validator_client::nymd::traits::DkgQueryClient;
 use validator_client::nymd::tx::Hash;
 use validator_client::{CoconutApiClient, Config};
 
@@ -25,6 +36,12 @@ pub(crate) enum Command {
     /// Run the binary
     Run(Run),
 
+    /// Obtain credentials for deposits whose signatures were never obtained
+    Recover(Recover),
+
+    /// Get back the deposits of the credentials that expired without ever being spent
+    Refund(Refund),
+
     /// Generate shell completions
     Completions(ArgShell),
 
@@ -49,65 +66,319 @@ pub(crate) struct Run {
     /// The amount of utokens the credential will hold
     #[clap(long)]
     pub(crate) amount: u64,
+
+    /// Obtain a divisible credential, spent at the gateways one ticket at a time.
+    /// The amount has to be a multiple of the number of tickets.
+    #[clap(long)]
+    pub(crate) divisible: bool,
+
+    /// Path to the file containing the passphrase of the encrypted credential storage.
+    /// If not provided, it is read from the `NYM_CLIENT_PASSPHRASE` environment variable, if set.
+    #[clap(long)]
+    pub(crate) passphrase_file: Option<std::path::PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) signer_args: SignerArgs,
+}
+
+#[derive(Args)]
+pub(crate) struct Recover {
+    /// Home directory of the client that is supposed to use the credential.
+    #[clap(long)]
+    pub(crate) client_home_directory: std::path::PathBuf,
+
+    /// Hash of the deposit transaction to recover the credential for.
+    /// If not provided, all pending deposits are going to be recovered.
+    #[clap(long)]
+    pub(crate) tx_hash: Option<String>,
+
+    /// Path to the file containing the passphrase of the encrypted credential storage.
+    /// If not provided, it is read from the `NYM_CLIENT_PASSPHRASE` environment variable, if set.
+    #[clap(long)]
+    pub(crate) passphrase_file: Option<std::path::PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) signer_args: SignerArgs,
+}
+
+#[derive(Args)]
+pub(crate) struct Refund {
+    /// Home directory of the client that holds the expired credentials.
+    #[clap(long)]
+    pub(crate) client_home_directory: std::path::PathBuf,
+
+    /// The nymd URL that should be used
+    #[clap(long)]
+    pub(crate) nymd_url: String,
+
+    /// A mnemonic for the account the deposits are going to be refunded to
+    #[clap(long)]
+    pub(crate) mnemonic: String,
+
+    /// Path to the file containing the passphrase of the encrypted credential storage.
+    /// If not provided, it is read from the `NYM_CLIENT_PASSPHRASE` environment variable, if set.
+    #[clap(long)]
+    pub(crate) passphrase_file: Option<std::path::PathBuf>,
+}
+
+#[derive(Args)]
+pub(crate) struct SignerArgs {
+    /// Maximum time, in seconds, to wait for a single signer to respond
+    #[clap(long, default_value_t = 10)]
+    pub(crate) signer_timeout: u64,
+
+    /// Number of times a signer that timed out or failed is going to be asked again
+    #[clap(long, default_value_t = 2)]
+    pub(crate) signer_retries: u32,
+
+    /// Delay, in milliseconds, between subsequent requests sent to the same signer
+    #[clap(long, default_value_t = 500)]
+    pub(crate) signer_retry_backoff: u64,
+}
+
+impl SignerArgs {
+    pub(crate) fn signer_request_config(&self) -> SignerRequestConfig {
+        SignerRequestConfig::default()
+            .with_request_timeout(Duration::from_secs(self.signer_timeout))
+            .with_max_retries(self.signer_retries)
+            .with_retry_backoff(Duration::from_millis(self.signer_retry_backoff))
+    }
 }
 
-pub(crate) async fn deposit(nymd_url: &str, mnemonic: &str, amount: u64) -> Result<State> {
+pub(crate) async fn deposit(
+    nymd_url: &str,
+    mnemonic: &str,
+    amount: u64,
+    divisible: bool,
+) -> Result<State> {
+    let voucher_info = if divisible {
+        // the nym-apis refuse to sign the vouchers that can't be split into equally valued tickets
+        if !is_divisible_value(amount, TICKET_INDEX_BITS) {
+            return Err(CredentialClientError::IndivisibleAmount {
+                amount,
+                tickets: 1 << TICKET_INDEX_BITS,
+            });
+        }
+        DIVISIBLE_VOUCHER_INFO
+    } else {
+        VOUCHER_INFO
+    };
     let mut rng = OsRng;
     let signing_keypair = KeyPair::from(identity::KeyPair::new(&mut rng));
     let encryption_keypair = KeyPair::from(encryption::KeyPair::new(&mut rng));
 
     let client = Client::new(nymd_url, mnemonic);
-    let tx_hash = client
+    let (tx_hash, expiry_epoch) = client
         .deposit(
             amount,
+            voucher_info,
             signing_keypair.public_key.clone(),
             encryption_keypair.public_key.clone(),
             None,
         )
         .await?;
 
+    let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
+    let voucher = BandwidthVoucher::new(
+        &params,
+        amount.to_string(),
+        voucher_info.to_string(),
+        expiry_epoch,
+        Hash::from_str(&tx_hash).map_err(|_| CredentialClientError::InvalidTxHash)?,
+        identity::PrivateKey::from_base58_string(&signing_keypair.private_key)?,
+        encryption::PrivateKey::from_base58_string(&encryption_keypair.private_key)?,
+    );
+    let private_attributes = voucher.get_private_attributes();
+
     let state = State {
         amount,
+        voucher_info: voucher_info.to_string(),
         tx_hash,
+        expiry_epoch,
         signing_keypair,
         encryption_keypair,
+        serial_number: private_attributes[0].to_bs58(),
+        binding_number: private_attributes[1].to_bs58(),
+        pedersen_commitments_openings: voucher
+            .pedersen_commitments_openings()
+            .iter()
+            .map(|opening| opening.to_bs58())
+            .collect(),
+        blind_sign_request: voucher.blind_sign_request().to_bs58(),
     };
 
     Ok(state)
 }
 
-pub(crate) async fn get_credential(state: &State, shared_storage: PersistentStorage) -> Result<()> {
+fn restore_voucher(state: &State) -> Result<BandwidthVoucher> {
+    let serial_number = Attribute::try_from_bs58(&state.serial_number)?;
+    let binding_number = Attribute::try_from_bs58(&state.binding_number)?;
+    let pedersen_commitments_openings = state
+        .pedersen_commitments_openings
+        .iter()
+        .map(Attribute::try_from_bs58)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let blind_sign_request = BlindSignRequest::try_from_bs58(&state.blind_sign_request)?;
+    let amount = state.amount.to_string();
+    let expiry_epoch = state.expiry_epoch.to_string();
+
+    Ok(BandwidthVoucher::new_with_blind_sign_req(
+        [serial_number, binding_number],
+        [
+            amount.as_str(),
+            state.voucher_info.as_str(),
+            expiry_epoch.as_str(),
+        ],
+        Hash::from_str(&state.tx_hash).map_err(|_| CredentialClientError::InvalidTxHash)?,
+        identity::PrivateKey::from_base58_string(&state.signing_keypair.private_key)?,
+        encryption::PrivateKey::from_base58_string(&state.encryption_keypair.private_key)?,
+        pedersen_commitments_openings,
+        blind_sign_request,
+    )
+    .with_use_request(true))
+}
+
+pub(crate) async fn get_credential(
+    state: &State,
+    shared_storage: PersistentStorage,
+    signer_config: &SignerRequestConfig,
+) -> Result<()> {
     let network_details = NymNetworkDetails::new_from_env();
     let config = Config::try_from_nym_network_details(&network_details)?;
     let client = validator_client::Client::new_query(config)?;
     let coconut_api_clients = CoconutApiClient::all_coconut_api_clients(&client).await?;
+    let threshold = client
+        .nymd
+        .get_current_epoch_threshold()
+        .await?
+        .ok_or(CredentialClientError::UnknownThreshold)?;
 
     let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
-    let bandwidth_credential_attributes = BandwidthVoucher::new(
-        &params,
-        state.amount.to_string(),
-        VOUCHER_INFO.to_string(),
-        Hash::from_str(&state.tx_hash).map_err(|_| CredentialClientError::InvalidTxHash)?,
-        identity::PrivateKey::from_base58_string(&state.signing_keypair.private_key)?,
-        encryption::PrivateKey::from_base58_string(&state.encryption_keypair.private_key)?,
-    );
+    let bandwidth_credential_attributes = restore_voucher(state)?;
 
-    let signature = obtain_aggregate_signature(
+    let aggregated = obtain_aggregate_signature(
         &params,
         &bandwidth_credential_attributes,
         &coconut_api_clients,
+        threshold,
+        signer_config,
     )
     .await?;
+    for skipped in &aggregated.skipped_signers {
+        println!("Skipped {skipped}");
+    }
+    let signature = aggregated.signature;
     println!("Signature: {:?}", signature.to_bs58());
     shared_storage
         .insert_coconut_credential(
             state.amount.to_string(),
-            VOUCHER_INFO.to_string(),
+            state.voucher_info.clone(),
             bandwidth_credential_attributes.get_private_attributes()[0].to_bs58(),
             bandwidth_credential_attributes.get_private_attributes()[1].to_bs58(),
             signature.to_bs58(),
+            state.expiry_epoch,
         )
         .await?;
 
     Ok(())
 }
+
+/// Refunds all the stored credentials that can no longer be spent at the gateways, but are still
+/// within their refund window. Credentials that have been spent in the meantime are skipped.
+pub(crate) async fn refund(
+    nymd_url: &str,
+    mnemonic: &str,
+    shared_storage: PersistentStorage,
+) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let current_epoch = epoch_at(now);
+    // the credentials are marked as consumed once they expire, so those have to be considered as well
+    let refundable = shared_storage
+        .get_all_coconut_credentials()
+        .await?
+        .into_iter()
+        .filter(|credential| {
+            credential.voucher_info == VOUCHER_INFO
+                && is_refundable(credential.expiry_epoch as u64, current_epoch)
+        })
+        .collect::<Vec<_>>();
+    if refundable.is_empty() {
+        println!("There are no credentials to refund");
+        return Ok(());
+    }
+
+    let network_details = NymNetworkDetails::new_from_env();
+    let config = Config::try_from_nym_network_details(&network_details)?;
+    let query_client = validator_client::Client::new_query(config)?;
+    let coconut_api_clients = CoconutApiClient::all_coconut_api_clients(&query_client).await?;
+    let verification_key = obtain_aggregate_verification_key(&coconut_api_clients).await?;
+    let client = Client::new(nymd_url, mnemonic);
+
+    let total = refundable.len();
+    let mut failed = 0;
+    // a single failing refund shouldn't prevent refunding the remaining credentials
+    for stored in refundable {
+        let refunded = async {
+            let credential = prepare_stored_for_spending(&stored, &verification_key)?;
+            match client
+                .spent_credential(credential.blinded_serial_number())
+                .await?
+            {
+                None => {
+                    client.refund(&credential, &coconut_api_clients).await?;
+                    println!(
+                        "Refunded {} for credential {}",
+                        stored.voucher_value, stored.id
+                    );
+                }
+                // the refund has been claimed before, but the nym-apis haven't passed it (yet)
+                Some(spent)
+                    if spent.status() == SpendCredentialStatus::InProgress
+                        && spent.gateway_cosmos_address().as_str() == client.address().as_ref() =>
+                {
+                    println!(
+                        "The refund of credential {} is still waiting for the votes of the nym-apis",
+                        stored.id
+                    );
+                    return Ok(());
+                }
+                Some(_) => println!("Credential {} has already been spent", stored.id),
+            }
+            shared_storage
+                .consume_coconut_credential(stored.id)
+                .await
+                .map_err(CredentialClientError::from)
+        }
+        .await;
+        if let Err(err) = refunded {
+            eprintln!("Failed to refund credential {}: {err}", stored.id);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(CredentialClientError::RefundFailure { failed, total });
+    }
+
+    Ok(())
+}
+
+fn prepare_stored_for_spending(
+    stored: &CoconutCredential,
+    verification_key: &coconut_interface::VerificationKey,
+) -> Result<coconut_interface::Credential> {
+    let voucher_value = u64::from_str(&stored.voucher_value)
+        .map_err(|_| credential_storage::error::StorageError::InconsistentData)?;
+
+    Ok(prepare_for_spending(
+        voucher_value,
+        stored.voucher_info.clone(),
+        stored.expiry_epoch as u64,
+        Attribute::try_from_bs58(&stored.serial_number)?,
+        Attribute::try_from_bs58(&stored.binding_number)?,
+        &Signature::try_from_bs58(&stored.signature)?,
+        verification_key,
+    )?)
+}
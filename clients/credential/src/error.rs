@@ -3,6 +3,7 @@
 
 use thiserror::Error;
 
+use coconut_interface::CoconutError;
 use credential_storage::error::StorageError;
 use credentials::error::Error as CredentialError;
 use crypto::asymmetric::encryption::KeyRecoveryError;
@@ -23,6 +24,12 @@ pub enum CredentialClientError {
     #[error("Credential error: {0}")]
     Credential(#[from] CredentialError),
 
+    #[error("Coconut error: {0}")]
+    Coconut(#[from] CoconutError),
+
+    #[error("The signing threshold for the current DKG epoch is not yet known")]
+    UnknownThreshold,
+
     #[error("The tx hash provided is not valid")]
     InvalidTxHash,
 
@@ -34,4 +41,31 @@ pub enum CredentialClientError {
 
     #[error("Could not use shared storage")]
     SharedStorageError(#[from] StorageError),
+
+    #[error("Failed to read the passphrase of the credential storage - {0}")]
+    Passphrase(std::io::Error),
+
+    #[error("Failed to access the deposit state - {0}")]
+    StateIoError(#[from] std::io::Error),
+
+    #[error("Malformed deposit state - {0}")]
+    MalformedState(#[from] serde_json::Error),
+
+    #[error("There is no pending deposit with tx hash {0}")]
+    UnknownDeposit(String),
+
+    #[error("Failed to recover credentials for {failed} out of {total} pending deposits")]
+    RecoveryFailure { failed: usize, total: usize },
+
+    #[error("The deposit transaction did not report the expiry of the credential")]
+    MissingDepositExpiry,
+
+    #[error("{amount} can't be split into {tickets} equally valued tickets")]
+    IndivisibleAmount { amount: u64, tickets: u64 },
+
+    #[error("The refund transaction did not report the id of the refund proposal")]
+    MissingProposalId,
+
+    #[error("Failed to refund {failed} out of {total} expired credentials")]
+    RefundFailure { failed: usize, total: usize },
 }
@@ -14,6 +14,7 @@ cfg_if::cfg_if! {
         use completions::fig_generate;
         use commands::*;
         use config::{DATA_DIR, DB_FILE_NAME};
+        use state::State;
 
         use clap::{CommandFactory, Parser};
 
@@ -36,11 +37,84 @@ cfg_if::cfg_if! {
 
             match args.command {
                 Command::Run(r) => {
-                    let db_path = r.client_home_directory.join(DATA_DIR).join(DB_FILE_NAME);
-                    let shared_storage = credential_storage::initialise_storage(db_path).await;
+                    let data_dir = r.client_home_directory.join(DATA_DIR);
+                    let passphrase =
+                        credential_storage::read_passphrase(r.passphrase_file.as_deref())
+                            .map_err(error::CredentialClientError::Passphrase)?;
+                    let shared_storage = credential_storage::initialise_storage_with_passphrase(
+                        data_dir.join(DB_FILE_NAME),
+                        passphrase.as_ref().map(|passphrase| passphrase.as_bytes()),
+                    )
+                    .await;
 
-                    let state = deposit(&r.nymd_url, &r.mnemonic, r.amount).await?;
-                    get_credential(&state, shared_storage).await?;
+                    let state = deposit(&r.nymd_url, &r.mnemonic, r.amount, r.divisible).await?;
+                    // keep the deposit keys around until the credential is stored,
+                    // so that it could still be obtained with `recover` if anything goes wrong
+                    state.save(&data_dir)?;
+                    let signer_config = r.signer_args.signer_request_config();
+                    get_credential(&state, shared_storage, &signer_config).await?;
+                    state.remove(&data_dir)?;
+                }
+                Command::Recover(r) => {
+                    let data_dir = r.client_home_directory.join(DATA_DIR);
+                    let passphrase =
+                        credential_storage::read_passphrase(r.passphrase_file.as_deref())
+                            .map_err(error::CredentialClientError::Passphrase)?;
+                    let shared_storage = credential_storage::initialise_storage_with_passphrase(
+                        data_dir.join(DB_FILE_NAME),
+                        passphrase.as_ref().map(|passphrase| passphrase.as_bytes()),
+                    )
+                    .await;
+
+                    let mut pending = State::load_pending(&data_dir)?;
+                    if let Some(tx_hash) = &r.tx_hash {
+                        pending.retain(|state| &state.tx_hash == tx_hash);
+                        if pending.is_empty() {
+                            return Err(error::CredentialClientError::UnknownDeposit(
+                                tx_hash.clone(),
+                            ));
+                        }
+                    }
+                    if pending.is_empty() {
+                        println!("There are no pending deposits to recover");
+                    }
+
+                    let signer_config = r.signer_args.signer_request_config();
+                    let total = pending.len();
+                    let mut failed = 0;
+                    // a single failing deposit shouldn't prevent recovering the remaining ones
+                    for state in pending {
+                        println!("Recovering credential for deposit {}", state.tx_hash);
+                        let recovered =
+                            get_credential(&state, shared_storage.clone(), &signer_config).await;
+                        let recovered = match recovered {
+                            Ok(_) => state.remove(&data_dir).map_err(Into::into),
+                            Err(err) => Err(err),
+                        };
+                        if let Err(err) = recovered {
+                            eprintln!(
+                                "Failed to recover credential for deposit {}: {err}",
+                                state.tx_hash
+                            );
+                            failed += 1;
+                        }
+                    }
+                    if failed > 0 {
+                        return Err(error::CredentialClientError::RecoveryFailure { failed, total });
+                    }
+                }
+                Command::Refund(r) => {
+                    let data_dir = r.client_home_directory.join(DATA_DIR);
+                    let passphrase =
+                        credential_storage::read_passphrase(r.passphrase_file.as_deref())
+                            .map_err(error::CredentialClientError::Passphrase)?;
+                    let shared_storage = credential_storage::initialise_storage_with_passphrase(
+                        data_dir.join(DB_FILE_NAME),
+                        passphrase.as_ref().map(|passphrase| passphrase.as_bytes()),
+                    )
+                    .await;
+
+                    refund(&r.nymd_url, &r.mnemonic, shared_storage).await?;
                 }
                 Command::Completions(c) => c.generate(&mut crate::Cli::into_app(), bin_name),
                 Command::GenerateFigSpec => fig_generate(&mut crate::Cli::into_app(), bin_name)
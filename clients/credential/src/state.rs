@@ -2,8 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use crypto::asymmetric::{encryption, identity};
+use network_defaults::VOUCHER_INFO;
+
+use crate::error::Result;
+
+/// Directory, within the client data directory, holding the state of deposits
+/// for which the credential has not yet been obtained.
+const PENDING_DEPOSITS_DIR: &str = "pending_deposits";
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct KeyPair {
@@ -32,7 +41,64 @@ impl From<encryption::KeyPair> for KeyPair {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct State {
     pub amount: u64,
+    // type of the voucher, i.e. whether it's going to be spent as a whole or as individual tickets
+    #[serde(default = "default_voucher_info")]
+    pub voucher_info: String,
     pub tx_hash: String,
+    // epoch after which the credential can no longer be spent, as assigned by the contract
+    #[serde(default)]
+    pub expiry_epoch: u64,
     pub signing_keypair: KeyPair,
     pub encryption_keypair: KeyPair,
+    // base58 encoded private attributes of the voucher
+    pub serial_number: String,
+    pub binding_number: String,
+    // base58 encoded openings of the commitments to the private attributes
+    pub pedersen_commitments_openings: Vec<String>,
+    // base58 encoded blind sign request, so that exactly the same one could be re-sent on recovery
+    pub blind_sign_request: String,
+}
+
+// deposits made before divisible vouchers were introduced are all regular bandwidth vouchers
+fn default_voucher_info() -> String {
+    VOUCHER_INFO.to_string()
+}
+
+impl State {
+    fn pending_deposits_dir(data_dir: &Path) -> PathBuf {
+        data_dir.join(PENDING_DEPOSITS_DIR)
+    }
+
+    fn path(&self, data_dir: &Path) -> PathBuf {
+        Self::pending_deposits_dir(data_dir).join(format!("{}.json", self.tx_hash))
+    }
+
+    /// Persists the state of the deposit, so that the credential could be recovered
+    /// in case obtaining the signatures fails.
+    pub(crate) fn save(&self, data_dir: &Path) -> Result<()> {
+        fs::create_dir_all(Self::pending_deposits_dir(data_dir))?;
+        fs::write(self.path(data_dir), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Removes the persisted state once the credential has been successfully obtained.
+    pub(crate) fn remove(&self, data_dir: &Path) -> Result<()> {
+        fs::remove_file(self.path(data_dir))?;
+        Ok(())
+    }
+
+    /// Loads the states of all deposits for which the credential has not yet been obtained.
+    pub(crate) fn load_pending(data_dir: &Path) -> Result<Vec<State>> {
+        let dir = Self::pending_deposits_dir(data_dir);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut states = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let content = fs::read(entry?.path())?;
+            states.push(serde_json::from_slice(&content)?);
+        }
+        Ok(states)
+    }
 }
@@ -93,6 +93,8 @@ async fn send_file_without_reply() {
         recipient,
         message: read_data,
         connection_id: Some(0),
+        compress: false,
+        erasure_coding: false,
     };
 
     println!("sending content of 'dummy_file' over the mix network...");
@@ -14,6 +14,7 @@ use client_core::client::inbound_messages::InputMessage;
 use client_core::client::key_manager::KeyManager;
 use client_core::client::received_buffer::{ReceivedBufferMessage, ReconstructedMessagesReceiver};
 use client_core::client::replies::reply_controller::requests::ReplyControllerSender;
+use client_core::client::topology_control::{new_chain_query_client, ChainQueryClient};
 use client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
 use futures::channel::mpsc;
 use gateway_client::bandwidth::BandwidthController;
@@ -22,6 +23,7 @@ use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nymsphinx::receiver::ReconstructedMessage;
 use task::TaskManager;
+use zeroize::Zeroizing;
 
 pub(crate) mod config;
 
@@ -32,20 +34,40 @@ pub struct SocketClient {
 
     /// KeyManager object containing smart pointers to all relevant keys used by the client.
     key_manager: KeyManager,
+
+    /// Passphrase protecting the client store, if it has been encrypted.
+    passphrase: Option<Zeroizing<String>>,
 }
 
 impl SocketClient {
     pub fn new(config: Config) -> Self {
+        Self::new_with_passphrase(config, None).expect("failed to load stored keys")
+    }
+
+    /// Creates the client, decrypting its stored keys and credentials with the provided passphrase.
+    /// If no passphrase is given, the store is assumed to be unencrypted.
+    pub fn new_with_passphrase(
+        config: Config,
+        passphrase: Option<Zeroizing<String>>,
+    ) -> Result<Self, ClientError> {
         let pathfinder = ClientKeyPathfinder::new_from_config(config.get_base());
-        let key_manager = KeyManager::load_keys(&pathfinder).expect("failed to load stored keys");
+        let key_manager = match &passphrase {
+            Some(passphrase) => KeyManager::load_encrypted_keys(&pathfinder, passphrase.as_bytes()),
+            None => KeyManager::load_keys(&pathfinder),
+        }
+        .map_err(|source| ClientError::FailedToLoadKeys { source })?;
 
-        SocketClient {
+        Ok(SocketClient {
             config,
             key_manager,
-        }
+            passphrase,
+        })
     }
 
-    async fn create_bandwidth_controller(config: &Config) -> BandwidthController {
+    async fn create_bandwidth_controller(
+        config: &Config,
+        passphrase: Option<&[u8]>,
+    ) -> BandwidthController {
         #[cfg(feature = "coconut")]
         let bandwidth_controller = {
             let details = network_defaults::NymNetworkDetails::new_from_env();
@@ -71,18 +93,39 @@ impl SocketClient {
                     .await
                     .expect("Could not query api clients");
             BandwidthController::new(
-                credential_storage::initialise_storage(config.get_base().get_database_path()).await,
+                credential_storage::initialise_storage_with_passphrase(
+                    config.get_base().get_database_path(),
+                    passphrase,
+                )
+                .await,
                 coconut_api_clients,
             )
         };
         #[cfg(not(feature = "coconut"))]
         let bandwidth_controller = BandwidthController::new(
-            credential_storage::initialise_storage(config.get_base().get_database_path()).await,
+            credential_storage::initialise_storage_with_passphrase(
+                config.get_base().get_database_path(),
+                passphrase,
+            )
+            .await,
         )
         .expect("Could not create bandwidth controller");
         bandwidth_controller
     }
 
+    fn create_chain_topology_client(config: &Config) -> Option<ChainQueryClient> {
+        if !config.get_debug_settings().verify_topology_against_chain {
+            return None;
+        }
+
+        let nymd_url = config
+            .get_base()
+            .get_validator_endpoints()
+            .pop()
+            .expect("No nymd validator endpoint provided");
+        Some(new_chain_query_client(nymd_url).expect("Could not construct query client"))
+    }
+
     fn start_websocket_listener(
         config: &Config,
         client_input: ClientInput,
@@ -136,10 +179,16 @@ impl SocketClient {
             return Err(ClientError::InvalidSocketMode);
         }
 
-        let base_builder = BaseClientBuilder::new_from_base_config(
+        let mut base_builder = BaseClientBuilder::new_from_base_config(
             self.config.get_base(),
             self.key_manager,
-            Some(Self::create_bandwidth_controller(&self.config).await),
+            Some(
+                Self::create_bandwidth_controller(
+                    &self.config,
+                    self.passphrase.as_ref().map(|p| p.as_bytes()),
+                )
+                .await,
+            ),
             non_wasm_helpers::setup_fs_reply_surb_backend(
                 self.config.get_base().get_reply_surb_database_path(),
                 self.config.get_debug_settings(),
@@ -147,6 +196,10 @@ impl SocketClient {
             .await?,
         );
 
+        if let Some(chain_client) = Self::create_chain_topology_client(&self.config) {
+            base_builder = base_builder.with_chain_topology_client(chain_client);
+        }
+
         let self_address = base_builder.as_mix_recipient();
         let mut started_client = base_builder.start_base().await?;
         let client_input = started_client.client_input.register_producer();
@@ -172,10 +225,16 @@ impl SocketClient {
             return Err(ClientError::InvalidSocketMode);
         }
 
-        let base_client = BaseClientBuilder::new_from_base_config(
+        let mut base_client = BaseClientBuilder::new_from_base_config(
             self.config.get_base(),
             self.key_manager,
-            Some(Self::create_bandwidth_controller(&self.config).await),
+            Some(
+                Self::create_bandwidth_controller(
+                    &self.config,
+                    self.passphrase.as_ref().map(|p| p.as_bytes()),
+                )
+                .await,
+            ),
             non_wasm_helpers::setup_fs_reply_surb_backend(
                 self.config.get_base().get_reply_surb_database_path(),
                 self.config.get_debug_settings(),
@@ -183,6 +242,10 @@ impl SocketClient {
             .await?,
         );
 
+        if let Some(chain_client) = Self::create_chain_topology_client(&self.config) {
+            base_client = base_client.with_chain_topology_client(chain_client);
+        }
+
         let mut started_client = base_client.start_base().await?;
         let client_input = started_client.client_input.register_producer();
         let client_output = started_client.client_output.register_consumer();
@@ -0,0 +1,62 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::config::Config;
+use crate::error::ClientError;
+use clap::Args;
+use client_core::client::key_manager::KeyManager;
+use client_core::client::passphrase::read_new_passphrase;
+use client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
+use config::NymConfig;
+use credential_storage::PersistentStorage;
+use log::*;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Args, Clone)]
+pub(crate) struct EncryptStore {
+    /// Id of the nym-mixnet-client whose store we want to encrypt.
+    #[clap(long)]
+    id: String,
+
+    /// Path to the file containing the new passphrase. If not provided, it is read from the
+    /// `NYM_CLIENT_PASSPHRASE` environment variable or prompted for.
+    #[clap(long)]
+    passphrase_file: Option<PathBuf>,
+}
+
+pub(crate) async fn execute(args: &EncryptStore) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let id = &args.id;
+
+    let config = match Config::load_from_file(Some(id)) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            error!("Failed to load config for {}. Are you sure you have run `init` before? (Error was: {err})", id);
+            return Err(Box::new(ClientError::FailedToLoadConfig(id.to_string())));
+        }
+    };
+
+    let pathfinder = ClientKeyPathfinder::new_from_config(config.get_base());
+    if KeyManager::stored_keys_encrypted(&pathfinder)? {
+        return Err(Box::new(ClientError::StoreAlreadyEncrypted));
+    }
+    let passphrase = read_new_passphrase(args.passphrase_file.as_deref())?;
+    // some of the keys might have been encrypted already if the previous attempt got interrupted
+    let (key_manager, cipher) =
+        KeyManager::load_keys_for_encryption(&pathfinder, passphrase.as_bytes())?;
+
+    // the credentials get encrypted in a single transaction, so do it before touching the keys.
+    // if they have been encrypted already, this only checks the passphrase matches
+    PersistentStorage::encrypt_existing(
+        config.get_base().get_database_path(),
+        passphrase.as_bytes(),
+    )
+    .await?;
+
+    // every key is atomically replaced with its encrypted variant, with the identity key,
+    // which marks the whole store as encrypted, being the last one
+    key_manager.store_encrypted_keys(&pathfinder, &cipher)?;
+
+    println!("Encrypted the stored keys and credentials of client {id}");
+    Ok(())
+}
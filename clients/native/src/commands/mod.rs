@@ -8,6 +8,7 @@ use clap::CommandFactory;
 use clap::{Parser, Subcommand};
 use completions::{fig_generate, ArgShell};
 
+pub(crate) mod encrypt_store;
 pub(crate) mod init;
 pub(crate) mod run;
 pub(crate) mod upgrade;
@@ -66,6 +67,8 @@ pub(crate) enum Commands {
     Run(run::Run),
     /// Try to upgrade the client
     Upgrade(upgrade::Upgrade),
+    /// Encrypt the stored keys and credentials of the client with a passphrase
+    EncryptStore(encrypt_store::EncryptStore),
 
     /// Generate shell completions
     Completions(ArgShell),
@@ -94,6 +97,7 @@ pub(crate) async fn execute(args: &Cli) -> Result<(), Box<dyn Error + Send + Syn
         Commands::Init(m) => init::execute(m).await?,
         Commands::Run(m) => run::execute(m).await?,
         Commands::Upgrade(m) => upgrade::execute(m),
+        Commands::EncryptStore(m) => encrypt_store::execute(m).await?,
         Commands::Completions(s) => s.generate(&mut Cli::into_app(), bin_name),
         Commands::GenerateFigSpec => fig_generate(&mut Cli::into_app(), bin_name),
     }
@@ -10,8 +10,12 @@ use crate::{
 };
 
 use clap::Args;
+use client_core::client::key_manager::KeyManager;
+use client_core::client::passphrase::read_passphrase;
+use client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
 use config::NymConfig;
 use log::*;
+use std::path::PathBuf;
 use version_checker::is_minor_version_compatible;
 
 #[derive(Args, Clone)]
@@ -50,6 +54,11 @@ pub(crate) struct Run {
     #[clap(long, hidden = true)]
     no_cover: bool,
 
+    /// Path to the file containing the passphrase of the encrypted client store. If not provided,
+    /// it is read from the `NYM_CLIENT_PASSPHRASE` environment variable or prompted for.
+    #[clap(long)]
+    passphrase_file: Option<PathBuf>,
+
     /// Set this client to work in a enabled credentials mode that would attempt to use gateway
     /// with bandwidth credential requirement.
     #[cfg(feature = "coconut")]
@@ -114,5 +123,14 @@ pub(crate) async fn execute(args: &Run) -> Result<(), Box<dyn Error + Send + Syn
         return Err(Box::new(ClientError::FailedLocalVersionCheck));
     }
 
-    SocketClient::new(config).run_socket_forever().await
+    let pathfinder = ClientKeyPathfinder::new_from_config(config.get_base());
+    let passphrase = if KeyManager::stored_keys_encrypted(&pathfinder)? {
+        Some(read_passphrase(args.passphrase_file.as_deref())?)
+    } else {
+        None
+    };
+
+    SocketClient::new_with_passphrase(config, passphrase)?
+        .run_socket_forever()
+        .await
 }
@@ -15,6 +15,12 @@ pub enum ClientError {
     #[error("Failed local version check, client and config mismatch")]
     FailedLocalVersionCheck,
 
+    #[error("The client store has already been encrypted")]
+    StoreAlreadyEncrypted,
+
+    #[error("Failed to load the stored keys, was the correct passphrase provided? - {source}")]
+    FailedToLoadKeys { source: std::io::Error },
+
     #[error("Attempted to start the client in invalid socket mode")]
     InvalidSocketMode,
 }
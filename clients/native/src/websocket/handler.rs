@@ -152,6 +152,8 @@ impl Handler {
         recipient: Recipient,
         message: Vec<u8>,
         connection_id: Option<u64>,
+        compress: bool,
+        erasure_coding: bool,
     ) -> Option<ServerResponse> {
         info!(
             "Attempting to send {:.2} kiB message to {recipient} on connection_id {connection_id:?}",
@@ -164,7 +166,13 @@ impl Handler {
         });
 
         // the ack control is now responsible for chunking, etc.
-        let input_msg = InputMessage::new_regular(recipient, message, lane);
+        let mut input_msg = InputMessage::new_regular(recipient, message, lane);
+        if compress {
+            input_msg = input_msg.with_compression();
+        }
+        if erasure_coding {
+            input_msg = input_msg.with_erasure_coding();
+        }
         self.msg_input
             .send(input_msg)
             .await
@@ -178,12 +186,15 @@ impl Handler {
         self.get_lane_queue_length(connection_id).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_send_anonymous(
         &mut self,
         recipient: Recipient,
         message: Vec<u8>,
         reply_surbs: u32,
         connection_id: Option<u64>,
+        compress: bool,
+        erasure_coding: bool,
     ) -> Option<ServerResponse> {
         info!(
             "Attempting to anonymously send {:.2} kiB message to {recipient} on connection_id {connection_id:?} while attaching {reply_surbs} replySURBs.",
@@ -195,7 +206,13 @@ impl Handler {
             TransmissionLane::ConnectionId(id)
         });
 
-        let input_msg = InputMessage::new_anonymous(recipient, message, reply_surbs, lane);
+        let mut input_msg = InputMessage::new_anonymous(recipient, message, reply_surbs, lane);
+        if compress {
+            input_msg = input_msg.with_compression();
+        }
+        if erasure_coding {
+            input_msg = input_msg.with_erasure_coding();
+        }
         self.msg_input
             .send(input_msg)
             .await
@@ -257,16 +274,30 @@ impl Handler {
                 recipient,
                 message,
                 connection_id,
-            } => self.handle_send(recipient, message, connection_id).await,
+                compress,
+                erasure_coding,
+            } => {
+                self.handle_send(recipient, message, connection_id, compress, erasure_coding)
+                    .await
+            }
 
             ClientRequest::SendAnonymous {
                 recipient,
                 message,
                 reply_surbs,
                 connection_id,
+                compress,
+                erasure_coding,
             } => {
-                self.handle_send_anonymous(recipient, message, reply_surbs, connection_id)
-                    .await
+                self.handle_send_anonymous(
+                    recipient,
+                    message,
+                    reply_surbs,
+                    connection_id,
+                    compress,
+                    erasure_coding,
+                )
+                .await
             }
 
             ClientRequest::Reply {
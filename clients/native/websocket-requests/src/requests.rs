@@ -11,6 +11,41 @@ use nymsphinx::anonymous_replies::requests::{AnonymousSenderTag, SENDER_TAG_SIZE
 use std::convert::{TryFrom, TryInto};
 use std::mem::size_of;
 
+// the send requests might be followed by a single byte of flags. It's optional so that
+// the requests created by older libraries, which don't know about it, would still be understood
+const COMPRESS_FLAG: u8 = 0b0000_0001;
+const ERASURE_CODING_FLAG: u8 = 0b0000_0010;
+
+fn send_flags(compress: bool, erasure_coding: bool) -> Option<u8> {
+    let mut flags = 0;
+    if compress {
+        flags |= COMPRESS_FLAG;
+    }
+    if erasure_coding {
+        flags |= ERASURE_CODING_FLAG;
+    }
+    (flags != 0).then_some(flags)
+}
+
+// splits what's left of a send request after its data length into the data itself
+// and the (possibly absent) flags
+fn split_data_and_flags(b: &[u8], data_len: u64) -> Result<(&[u8], u8), error::Error> {
+    if b.len() as u64 == data_len {
+        Ok((b, 0))
+    } else if b.len() as u64 == data_len + 1 {
+        Ok((&b[..b.len() - 1], b[b.len() - 1]))
+    } else {
+        Err(error::Error::new(
+            ErrorKind::MalformedRequest,
+            format!(
+                "data len has inconsistent length. specified: {} got: {}",
+                data_len,
+                b.len()
+            ),
+        ))
+    }
+}
+
 #[repr(u8)]
 enum ClientRequestTag {
     /// Value tag representing [`Send`] variant of the [`ClientRequest`]
@@ -58,10 +93,19 @@ pub enum ClientRequest {
     /// You're simply sending your `data` to specified `recipient` without any tagging.
     ///
     /// Ends up with `NymMessage::Plain` variant
+    ///
+    /// If `compress` is set, the message might get compressed with the algorithm configured
+    /// in the client. It must only be set if the recipient is known to be able to decompress it.
+    ///
+    /// Similarly, if `erasure_coding` is set, the message might get protected with forward error
+    /// correction using the redundancy ratio configured in the client. It must only be set
+    /// if the recipient is known to be able to reconstruct such messages.
     Send {
         recipient: Recipient,
         message: Vec<u8>,
         connection_id: Option<u64>,
+        compress: bool,
+        erasure_coding: bool,
     },
 
     /// Create a message used for a duplex anonymous communication where the recipient
@@ -72,11 +116,15 @@ pub enum ClientRequest {
     /// (and thus the recipient also knowing our sender tag).
     ///
     /// Ends up with `NymMessage::Repliable` variant
+    ///
+    /// `compress` and `erasure_coding` have the same meaning as in the `Send` variant.
     SendAnonymous {
         recipient: Recipient,
         message: Vec<u8>,
         reply_surbs: u32,
         connection_id: Option<u64>,
+        compress: bool,
+        erasure_coding: bool,
     },
 
     /// Attempt to use our internally received and stored `ReplySurb` to send the message back
@@ -99,8 +147,14 @@ pub enum ClientRequest {
 // we could have been parsing it directly TryFrom<WsMessage>, but we want to retain
 // information about whether it came from binary or text to send appropriate response back
 impl ClientRequest {
-    // SEND_REQUEST_TAG || recipient || conn_id || data_len || data
-    fn serialize_send(recipient: Recipient, data: Vec<u8>, connection_id: Option<u64>) -> Vec<u8> {
+    // SEND_REQUEST_TAG || recipient || conn_id || data_len || data || [flags]
+    fn serialize_send(
+        recipient: Recipient,
+        data: Vec<u8>,
+        connection_id: Option<u64>,
+        compress: bool,
+        erasure_coding: bool,
+    ) -> Vec<u8> {
         let data_len_bytes = (data.len() as u64).to_be_bytes();
         let conn_id_bytes = connection_id.unwrap_or(0).to_be_bytes();
 
@@ -109,10 +163,11 @@ impl ClientRequest {
             .chain(conn_id_bytes.into_iter())
             .chain(data_len_bytes.into_iter())
             .chain(data.into_iter())
+            .chain(send_flags(compress, erasure_coding))
             .collect()
     }
 
-    // SEND_REQUEST_TAG || recipient || conn_id || data_len || data
+    // SEND_REQUEST_TAG || recipient || conn_id || data_len || data || [flags]
     fn deserialize_send(b: &[u8]) -> Result<Self, error::Error> {
         // we need to have at least 1 (tag) + Recipient::LEN + 2*sizeof<u64> bytes
         if b.len() < 1 + Recipient::LEN + 2 * size_of::<u64>() {
@@ -150,31 +205,26 @@ impl ClientRequest {
         let data_len_bytes =
             &b[1 + Recipient::LEN + size_of::<u64>()..1 + Recipient::LEN + 2 * size_of::<u64>()];
         let data_len = u64::from_be_bytes(data_len_bytes.try_into().unwrap());
-        let data = &b[1 + Recipient::LEN + 2 * size_of::<u64>()..];
-        if data.len() as u64 != data_len {
-            return Err(error::Error::new(
-                ErrorKind::MalformedRequest,
-                format!(
-                    "data len has inconsistent length. specified: {} got: {}",
-                    data_len,
-                    data.len()
-                ),
-            ));
-        }
+        let (data, flags) =
+            split_data_and_flags(&b[1 + Recipient::LEN + 2 * size_of::<u64>()..], data_len)?;
 
         Ok(ClientRequest::Send {
             recipient,
             message: data.to_vec(),
             connection_id,
+            compress: flags & COMPRESS_FLAG != 0,
+            erasure_coding: flags & ERASURE_CODING_FLAG != 0,
         })
     }
 
-    // SEND_ANONYMOUS_REQUEST_TAG || reply_surbs || recipient || conn_id || data_len || data
+    // SEND_ANONYMOUS_REQUEST_TAG || reply_surbs || recipient || conn_id || data_len || data || [flags]
     fn serialize_send_anonymous(
         recipient: Recipient,
         data: Vec<u8>,
         reply_surbs: u32,
         connection_id: Option<u64>,
+        compress: bool,
+        erasure_coding: bool,
     ) -> Vec<u8> {
         let data_len_bytes = (data.len() as u64).to_be_bytes();
         let conn_id_bytes = connection_id.unwrap_or(0).to_be_bytes();
@@ -185,10 +235,11 @@ impl ClientRequest {
             .chain(conn_id_bytes.into_iter())
             .chain(data_len_bytes.into_iter())
             .chain(data.into_iter())
+            .chain(send_flags(compress, erasure_coding))
             .collect()
     }
 
-    // SEND_ANONYMOUS_REQUEST_TAG || reply_surbs || recipient || data_len || data
+    // SEND_ANONYMOUS_REQUEST_TAG || reply_surbs || recipient || conn_id || data_len || data || [flags]
     fn deserialize_send_anonymous(b: &[u8]) -> Result<Self, error::Error> {
         // we need to have at least 1 (tag) + sizeof<u32> (num surbs) + Recipient::LEN + 2 *sizeof<u64> bytes
         if b.len() < 1 + size_of::<u32>() + Recipient::LEN + 2 * size_of::<u64>() {
@@ -228,23 +279,16 @@ impl ClientRequest {
         let data_len_bytes =
             &b[5 + Recipient::LEN + size_of::<u64>()..5 + Recipient::LEN + 2 * size_of::<u64>()];
         let data_len = u64::from_be_bytes(data_len_bytes.try_into().unwrap());
-        let data = &b[5 + Recipient::LEN + 2 * size_of::<u64>()..];
-        if data.len() as u64 != data_len {
-            return Err(error::Error::new(
-                ErrorKind::MalformedRequest,
-                format!(
-                    "data len has inconsistent length. specified: {} got: {}",
-                    data_len,
-                    data.len()
-                ),
-            ));
-        }
+        let (data, flags) =
+            split_data_and_flags(&b[5 + Recipient::LEN + 2 * size_of::<u64>()..], data_len)?;
 
         Ok(ClientRequest::SendAnonymous {
             reply_surbs,
             recipient,
             message: data.to_vec(),
             connection_id,
+            compress: flags & COMPRESS_FLAG != 0,
+            erasure_coding: flags & ERASURE_CODING_FLAG != 0,
         })
     }
 
@@ -388,14 +432,25 @@ impl ClientRequest {
                 recipient,
                 message,
                 connection_id,
-            } => Self::serialize_send(recipient, message, connection_id),
+                compress,
+                erasure_coding,
+            } => Self::serialize_send(recipient, message, connection_id, compress, erasure_coding),
 
             ClientRequest::SendAnonymous {
                 recipient,
                 message,
                 reply_surbs,
                 connection_id,
-            } => Self::serialize_send_anonymous(recipient, message, reply_surbs, connection_id),
+                compress,
+                erasure_coding,
+            } => Self::serialize_send_anonymous(
+                recipient,
+                message,
+                reply_surbs,
+                connection_id,
+                compress,
+                erasure_coding,
+            ),
 
             ClientRequest::Reply {
                 message,
@@ -462,6 +517,8 @@ mod tests {
             recipient,
             message: b"foomp".to_vec(),
             connection_id: Some(42),
+            compress: false,
+            erasure_coding: false,
         };
 
         let bytes = send_request.serialize();
@@ -471,13 +528,61 @@ mod tests {
                 recipient,
                 message,
                 connection_id,
+                compress,
+                erasure_coding,
             } => {
                 assert_eq!(recipient.to_string(), recipient_string);
                 assert_eq!(message, b"foomp".to_vec());
-                assert_eq!(connection_id, Some(42))
+                assert_eq!(connection_id, Some(42));
+                assert!(!compress);
+                assert!(!erasure_coding)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn send_request_flags_are_optional() {
+        let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+
+        let without_flags = ClientRequest::Send {
+            recipient,
+            message: b"foomp".to_vec(),
+            connection_id: None,
+            compress: false,
+            erasure_coding: false,
+        }
+        .serialize();
+        let with_flags = ClientRequest::Send {
+            recipient,
+            message: b"foomp".to_vec(),
+            connection_id: None,
+            compress: true,
+            erasure_coding: false,
+        }
+        .serialize();
+
+        // requests without any flags are identical to the ones created by older libraries
+        assert_eq!(without_flags.len() + 1, with_flags.len());
+
+        match ClientRequest::deserialize(&with_flags).unwrap() {
+            ClientRequest::Send {
+                message,
+                compress,
+                erasure_coding,
+                ..
+            } => {
+                assert_eq!(message, b"foomp".to_vec());
+                assert!(compress);
+                assert!(!erasure_coding)
             }
             _ => unreachable!(),
         }
+
+        // but anything beyond the single flags byte is still rejected
+        let mut too_long = with_flags;
+        too_long.push(0);
+        assert!(ClientRequest::deserialize(&too_long).is_err());
     }
 
     #[test]
@@ -489,6 +594,8 @@ mod tests {
             message: b"foomp".to_vec(),
             reply_surbs: 666,
             connection_id: Some(42),
+            compress: true,
+            erasure_coding: true,
         };
 
         let bytes = send_anonymous_request.serialize();
@@ -499,11 +606,15 @@ mod tests {
                 message,
                 reply_surbs,
                 connection_id,
+                compress,
+                erasure_coding,
             } => {
                 assert_eq!(recipient, original_recipient);
                 assert_eq!(message, b"foomp".to_vec());
                 assert_eq!(connection_id, Some(42));
-                assert_eq!(reply_surbs, 666)
+                assert_eq!(reply_surbs, 666);
+                assert!(compress);
+                assert!(erasure_coding)
             }
             _ => unreachable!(),
         }
@@ -20,6 +20,10 @@ pub(super) enum ClientRequestText {
         message: String,
         recipient: String,
         connection_id: Option<u64>,
+        #[serde(default)]
+        compress: bool,
+        #[serde(default)]
+        erasure_coding: bool,
     },
     #[serde(rename_all = "camelCase")]
     SendAnonymous {
@@ -27,6 +31,10 @@ pub(super) enum ClientRequestText {
         message: String,
         reply_surbs: u32,
         connection_id: Option<u64>,
+        #[serde(default)]
+        compress: bool,
+        #[serde(default)]
+        erasure_coding: bool,
     },
     #[serde(rename_all = "camelCase")]
     Reply {
@@ -54,6 +62,8 @@ impl TryInto<ClientRequest> for ClientRequestText {
                 message,
                 recipient,
                 connection_id,
+                compress,
+                erasure_coding,
             } => {
                 let message_bytes = message.into_bytes();
                 let recipient = Recipient::try_from_base58_string(recipient).map_err(|err| {
@@ -64,6 +74,8 @@ impl TryInto<ClientRequest> for ClientRequestText {
                     message: message_bytes,
                     recipient,
                     connection_id,
+                    compress,
+                    erasure_coding,
                 })
             }
             ClientRequestText::SendAnonymous {
@@ -71,6 +83,8 @@ impl TryInto<ClientRequest> for ClientRequestText {
                 message,
                 reply_surbs,
                 connection_id,
+                compress,
+                erasure_coding,
             } => {
                 let message_bytes = message.into_bytes();
                 let recipient = Recipient::try_from_base58_string(recipient).map_err(|err| {
@@ -81,6 +95,8 @@ impl TryInto<ClientRequest> for ClientRequestText {
                     message: message_bytes,
                     reply_surbs,
                     connection_id,
+                    compress,
+                    erasure_coding,
                 })
             }
             ClientRequestText::SelfAddress => Ok(ClientRequest::SelfAddress),
@@ -116,6 +116,14 @@ impl Config {
         self.socks5.send_anonymously
     }
 
+    pub fn get_provider_supports_compression(&self) -> bool {
+        self.socks5.provider_supports_compression
+    }
+
+    pub fn get_provider_supports_erasure_coding(&self) -> bool {
+        self.socks5.provider_supports_erasure_coding
+    }
+
     pub fn get_listening_port(&self) -> u16 {
         self.socks5.listening_port
     }
@@ -145,6 +153,18 @@ pub struct Socks5 {
     /// Note that some service providers might not support this.
     #[serde(default)]
     send_anonymously: bool,
+
+    /// Specifies whether the service provider is able to decompress the messages it receives.
+    /// If so, the requests are going to be compressed with the algorithm set in the `debug` section,
+    /// as long as it is enabled there.
+    #[serde(default)]
+    provider_supports_compression: bool,
+
+    /// Specifies whether the service provider is able to reconstruct erasure coded messages.
+    /// If so, the requests are going to be protected with forward error correction using
+    /// the redundancy ratio set in the `debug` section, as long as it is set there.
+    #[serde(default)]
+    provider_supports_erasure_coding: bool,
 }
 
 impl Socks5 {
@@ -153,6 +173,8 @@ impl Socks5 {
             listening_port: DEFAULT_SOCKS5_LISTENING_PORT,
             provider_mix_address: provider_mix_address.into(),
             send_anonymously: false,
+            provider_supports_compression: false,
+            provider_supports_erasure_coding: false,
         }
     }
 }
@@ -163,6 +185,8 @@ impl Default for Socks5 {
             listening_port: DEFAULT_SOCKS5_LISTENING_PORT,
             provider_mix_address: "".into(),
             send_anonymously: false,
+            provider_supports_compression: false,
+            provider_supports_erasure_coding: false,
         }
     }
 }
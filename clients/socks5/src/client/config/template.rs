@@ -98,6 +98,16 @@ listening_port = {{ socks5.listening_port }}
 # Note that some service providers might not support this.
 send_anonymously = {{ socks5.send_anonymously }}
 
+# Specifies whether the service provider is able to decompress the messages it receives.
+# If so, the requests are going to be compressed with the algorithm set in the `debug` section,
+# as long as it is enabled there.
+provider_supports_compression = {{ socks5.provider_supports_compression }}
+
+# Specifies whether the service provider is able to reconstruct erasure coded messages.
+# If so, the requests are going to be protected with forward error correction using
+# the redundancy ratio set in the `debug` section, as long as it is set there.
+provider_supports_erasure_coding = {{ socks5.provider_supports_erasure_coding }}
+
 ##### logging configuration options #####
 
 [logging]
@@ -12,6 +12,7 @@ use client_core::client::base_client::{
     non_wasm_helpers, BaseClientBuilder, ClientInput, ClientOutput,
 };
 use client_core::client::key_manager::KeyManager;
+use client_core::client::topology_control::{new_chain_query_client, ChainQueryClient};
 use client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
 use futures::channel::mpsc;
 use futures::StreamExt;
@@ -20,6 +21,7 @@ use log::*;
 use nymsphinx::addressing::clients::Recipient;
 use std::error::Error;
 use task::{wait_for_signal_and_error, TaskClient, TaskManager};
+use zeroize::Zeroizing;
 
 pub mod config;
 
@@ -40,20 +42,40 @@ pub struct NymClient {
 
     /// KeyManager object containing smart pointers to all relevant keys used by the client.
     key_manager: KeyManager,
+
+    /// Passphrase protecting the client store, if it has been encrypted.
+    passphrase: Option<Zeroizing<String>>,
 }
 
 impl NymClient {
     pub fn new(config: Config) -> Self {
+        Self::new_with_passphrase(config, None).expect("failed to load stored keys")
+    }
+
+    /// Creates the client, decrypting its stored keys and credentials with the provided passphrase.
+    /// If no passphrase is given, the store is assumed to be unencrypted.
+    pub fn new_with_passphrase(
+        config: Config,
+        passphrase: Option<Zeroizing<String>>,
+    ) -> Result<Self, Socks5ClientError> {
         let pathfinder = ClientKeyPathfinder::new_from_config(config.get_base());
-        let key_manager = KeyManager::load_keys(&pathfinder).expect("failed to load stored keys");
+        let key_manager = match &passphrase {
+            Some(passphrase) => KeyManager::load_encrypted_keys(&pathfinder, passphrase.as_bytes()),
+            None => KeyManager::load_keys(&pathfinder),
+        }
+        .map_err(|source| Socks5ClientError::FailedToLoadKeys { source })?;
 
-        NymClient {
+        Ok(NymClient {
             config,
             key_manager,
-        }
+            passphrase,
+        })
     }
 
-    async fn create_bandwidth_controller(config: &Config) -> BandwidthController {
+    async fn create_bandwidth_controller(
+        config: &Config,
+        passphrase: Option<&[u8]>,
+    ) -> BandwidthController {
         #[cfg(feature = "coconut")]
         let bandwidth_controller = {
             let details = network_defaults::NymNetworkDetails::new_from_env();
@@ -79,18 +101,39 @@ impl NymClient {
                     .await
                     .expect("Could not query api clients");
             BandwidthController::new(
-                credential_storage::initialise_storage(config.get_base().get_database_path()).await,
+                credential_storage::initialise_storage_with_passphrase(
+                    config.get_base().get_database_path(),
+                    passphrase,
+                )
+                .await,
                 coconut_api_clients,
             )
         };
         #[cfg(not(feature = "coconut"))]
         let bandwidth_controller = BandwidthController::new(
-            credential_storage::initialise_storage(config.get_base().get_database_path()).await,
+            credential_storage::initialise_storage_with_passphrase(
+                config.get_base().get_database_path(),
+                passphrase,
+            )
+            .await,
         )
         .expect("Could not create bandwidth controller");
         bandwidth_controller
     }
 
+    fn create_chain_topology_client(config: &Config) -> Option<ChainQueryClient> {
+        if !config.get_debug_settings().verify_topology_against_chain {
+            return None;
+        }
+
+        let nymd_url = config
+            .get_base()
+            .get_validator_endpoints()
+            .pop()
+            .expect("No nymd validator endpoint provided");
+        Some(new_chain_query_client(nymd_url).expect("Could not construct query client"))
+    }
+
     fn start_socks5_listener(
         config: &Config,
         client_input: ClientInput,
@@ -123,6 +166,8 @@ impl NymClient {
                 config.get_send_anonymously(),
                 config.get_connection_start_surbs(),
                 config.get_per_request_surbs(),
+                config.get_provider_supports_compression(),
+                config.get_provider_supports_erasure_coding(),
             ),
             shutdown.clone(),
         );
@@ -203,10 +248,16 @@ impl NymClient {
     }
 
     pub async fn start(self) -> Result<TaskManager, Socks5ClientError> {
-        let base_builder = BaseClientBuilder::new_from_base_config(
+        let mut base_builder = BaseClientBuilder::new_from_base_config(
             self.config.get_base(),
             self.key_manager,
-            Some(Self::create_bandwidth_controller(&self.config).await),
+            Some(
+                Self::create_bandwidth_controller(
+                    &self.config,
+                    self.passphrase.as_ref().map(|p| p.as_bytes()),
+                )
+                .await,
+            ),
             non_wasm_helpers::setup_fs_reply_surb_backend(
                 self.config.get_base().get_reply_surb_database_path(),
                 self.config.get_debug_settings(),
@@ -214,6 +265,10 @@ impl NymClient {
             .await?,
         );
 
+        if let Some(chain_client) = Self::create_chain_topology_client(&self.config) {
+            base_builder = base_builder.with_chain_topology_client(chain_client);
+        }
+
         let self_address = base_builder.as_mix_recipient();
         let mut started_client = base_builder.start_base().await?;
         let client_input = started_client.client_input.register_producer();
@@ -10,6 +10,7 @@ use completions::{fig_generate, ArgShell};
 use config::parse_validators;
 
 pub mod init;
+pub(crate) mod encrypt_store;
 pub(crate) mod run;
 pub(crate) mod upgrade;
 
@@ -70,6 +71,9 @@ pub(crate) enum Commands {
     /// Try to upgrade the client
     Upgrade(upgrade::Upgrade),
 
+    /// Encrypt the stored keys and credentials of the client with a passphrase
+    EncryptStore(encrypt_store::EncryptStore),
+
     /// Generate shell completions
     Completions(ArgShell),
 
@@ -97,6 +101,7 @@ pub(crate) async fn execute(args: &Cli) -> Result<(), Box<dyn Error + Send + Syn
         Commands::Init(m) => init::execute(m).await?,
         Commands::Run(m) => run::execute(m).await?,
         Commands::Upgrade(m) => upgrade::execute(m),
+        Commands::EncryptStore(m) => encrypt_store::execute(m).await?,
         Commands::Completions(s) => s.generate(&mut Cli::into_app(), bin_name),
         Commands::GenerateFigSpec => fig_generate(&mut Cli::into_app(), bin_name),
     }
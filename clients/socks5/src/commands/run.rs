@@ -8,8 +8,12 @@ use crate::{
 };
 
 use clap::Args;
+use client_core::client::key_manager::KeyManager;
+use client_core::client::passphrase::read_passphrase;
+use client_core::config::persistence::key_pathfinder::ClientKeyPathfinder;
 use config::NymConfig;
 use log::*;
+use std::path::PathBuf;
 use version_checker::is_minor_version_compatible;
 
 #[derive(Args, Clone)]
@@ -60,6 +64,11 @@ pub(crate) struct Run {
     #[clap(long, hidden = true)]
     no_cover: bool,
 
+    /// Path to the file containing the passphrase of the encrypted client store. If not provided,
+    /// it is read from the `NYM_CLIENT_PASSPHRASE` environment variable or prompted for.
+    #[clap(long)]
+    passphrase_file: Option<PathBuf>,
+
     /// Set this client to work in a enabled credentials mode that would attempt to use gateway
     /// with bandwidth credential requirement.
     #[cfg(feature = "coconut")]
@@ -129,5 +138,14 @@ pub(crate) async fn execute(args: &Run) -> Result<(), Box<dyn std::error::Error
         return Err(Box::new(Socks5ClientError::FailedLocalVersionCheck));
     }
 
-    NymClient::new(config).run_forever().await
+    let pathfinder = ClientKeyPathfinder::new_from_config(config.get_base());
+    let passphrase = if KeyManager::stored_keys_encrypted(&pathfinder)? {
+        Some(read_passphrase(args.passphrase_file.as_deref())?)
+    } else {
+        None
+    };
+
+    NymClient::new_with_passphrase(config, passphrase)?
+        .run_forever()
+        .await
 }
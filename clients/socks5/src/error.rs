@@ -20,6 +20,12 @@ pub enum Socks5ClientError {
     #[error("Failed local version check, client and config mismatch")]
     FailedLocalVersionCheck,
 
+    #[error("The client store has already been encrypted")]
+    StoreAlreadyEncrypted,
+
+    #[error("Failed to load the stored keys, was the correct passphrase provided? - {source}")]
+    FailedToLoadKeys { source: std::io::Error },
+
     #[error("Fail to bind address")]
     FailToBindAddress,
 
@@ -131,6 +131,8 @@ pub(crate) struct Config {
     use_surbs_for_responses: bool,
     connection_start_surbs: u32,
     per_request_surbs: u32,
+    provider_supports_compression: bool,
+    provider_supports_erasure_coding: bool,
 }
 
 impl Config {
@@ -138,13 +140,29 @@ impl Config {
         use_surbs_for_responses: bool,
         connection_start_surbs: u32,
         per_request_surbs: u32,
+        provider_supports_compression: bool,
+        provider_supports_erasure_coding: bool,
     ) -> Self {
         Self {
             use_surbs_for_responses,
             connection_start_surbs,
             per_request_surbs,
+            provider_supports_compression,
+            provider_supports_erasure_coding,
         }
     }
+
+    // the client is only going to compress (or erasure code) the message
+    // if it has it enabled in its own config
+    fn prepare_message(&self, mut message: InputMessage) -> InputMessage {
+        if self.provider_supports_compression {
+            message = message.with_compression()
+        }
+        if self.provider_supports_erasure_coding {
+            message = message.with_erasure_coding()
+        }
+        message
+    }
 }
 
 /// A client connecting to the Socks proxy server, because
@@ -296,12 +314,12 @@ impl SocksClient {
         let req = Request::new_connect(self.connection_id, remote_address, None);
         let msg = Message::Request(req);
 
-        let input_message = InputMessage::new_anonymous(
+        let input_message = self.config.prepare_message(InputMessage::new_anonymous(
             self.service_provider,
             msg.into_bytes(),
             self.config.connection_start_surbs,
             TransmissionLane::ConnectionId(self.connection_id),
-        );
+        ));
         self.input_sender
             .send(input_message)
             .await
@@ -312,11 +330,11 @@ impl SocksClient {
         let req = Request::new_connect(self.connection_id, remote_address, Some(self.self_address));
         let msg = Message::Request(req);
 
-        let input_message = InputMessage::new_regular(
+        let input_message = self.config.prepare_message(InputMessage::new_regular(
             self.service_provider,
             msg.into_bytes(),
             TransmissionLane::ConnectionId(self.connection_id),
-        );
+        ));
         self.input_sender
             .send(input_message)
             .await
@@ -348,6 +366,7 @@ impl SocksClient {
 
         let connection_id = self.connection_id;
         let input_sender = self.input_sender.clone();
+        let config = self.config;
         let anonymous = self.config.use_surbs_for_responses;
         let per_request_surbs = self.config.per_request_surbs;
 
@@ -366,7 +385,7 @@ impl SocksClient {
             let provider_request = Request::new_send(conn_id, read_data, socket_closed);
             let provider_message = Message::Request(provider_request);
             let lane = TransmissionLane::ConnectionId(conn_id);
-            if anonymous {
+            let input_message = if anonymous {
                 InputMessage::new_anonymous(
                     recipient,
                     provider_message.into_bytes(),
@@ -375,7 +394,8 @@ impl SocksClient {
                 )
             } else {
                 InputMessage::new_regular(recipient, provider_message.into_bytes(), lane)
-            }
+            };
+            config.prepare_message(input_message)
         })
         .await
         .into_inner();
@@ -65,7 +65,17 @@ async function main() {
 
   console.log('Instantiating WASM client...');
   
+  // message deliveries and gateway connection changes
+  const onEventHandler = (event) => {
+    console.log('Client event', event);
+  };
+
+  // the client keys are kept in the browser storage encrypted with this passphrase
+  const storagePassphrase = 'my-super-secret-passphrase';
+
   let clientBuilder = new NymClientBuilder(config, onMessageHandler)
+    .with_event_handler(onEventHandler)
+    .with_storage_passphrase(storagePassphrase);
   console.log('Web worker creating WASM client...');
   let local_client = await clientBuilder.start_client();
   console.log('WASM client running!');
@@ -90,7 +100,10 @@ async function main() {
         case 'SendMessage': {
           const { message, recipient } = event.data.args;
           let uint8Array = new TextEncoder().encode(message);
-          await client.send_regular_message(uint8Array, recipient);
+          // don't let the queue of packets waiting to be sent grow indefinitely
+          await client.wait_for_queue_capacity(100);
+          const messageId = await client.send_regular_message(uint8Array, recipient);
+          console.log(`Sent message ${messageId}`);
         }
       }
     }
@@ -4,7 +4,9 @@
 // due to expansion of #[wasm_bindgen] macro on `Debug` Config struct
 #![allow(clippy::drop_non_drop)]
 
-use client_core::config::{DebugConfig as ConfigDebug, ExtendedPacketSize, GatewayEndpointConfig};
+use client_core::config::{
+    CompressionAlgorithm, DebugConfig as ConfigDebug, ExtendedPacketSize, GatewayEndpointConfig,
+};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use url::Url;
@@ -46,6 +48,12 @@ impl Config {
             debug: debug.map(Into::into).unwrap_or_default(),
         }
     }
+
+    /// Specifies whether the client should run without using bandwidth credentials.
+    /// Credentials are disabled by default.
+    pub fn set_disabled_credentials_mode(&mut self, disabled_credentials_mode: bool) {
+        self.disabled_credentials_mode = disabled_credentials_mode;
+    }
 }
 
 // just a helper structure to more easily pass through the JS boundary
@@ -91,11 +99,26 @@ pub struct Debug {
     /// to try to obtain a compatible network topology to send sphinx packets through.
     pub topology_refresh_rate_ms: u64,
 
-    /// During topology refresh, test packets are sent through every single possible network
-    /// path. This timeout determines waiting period until it is decided that the packet
-    /// did not reach its destination.
+    /// Path health probes are periodically sent through randomly chosen network paths.
+    /// This timeout determines waiting period until it is decided that the probe
+    /// did not reach its destination and all the nodes on its path get penalised.
     pub topology_resolution_timeout_ms: u64,
 
+    /// If set, the network topology is obtained from all the configured nym-apis and it's only
+    /// accepted if at least this many of them agree on it.
+    pub topology_verification_quorum: Option<usize>,
+
+    /// Controls whether the client should stop probing the health of the network paths
+    /// and hence choose the mixnodes for its packets uniformly at random.
+    pub disable_path_health_probing: bool,
+
+    /// The minimum delay between sending subsequent path health probes. The probes replace
+    /// the loop cover messages, so the actual delay depends on the cover traffic rate.
+    pub path_health_probing_interval_ms: u64,
+
+    /// Reliability (within [0, 1]) below which mixnodes are excluded from the routes of sent packets.
+    pub path_health_exclusion_threshold: f64,
+
     /// Controls whether the dedicated loop cover traffic stream should be enabled.
     /// (and sending packets, on average, every [Self::loop_cover_traffic_average_delay_ms])
     pub disable_loop_cover_traffic_stream: bool,
@@ -107,6 +130,22 @@ pub struct Debug {
     /// Controls whether the sent sphinx packet use the NON-DEFAULT bigger size.
     pub use_extended_packet_size: bool,
 
+    /// Controls whether the packet size should be chosen separately for each message
+    /// between the regular and the extended size.
+    pub use_adaptive_packet_size: bool,
+
+    /// Fraction of the cover traffic sent using the extended packet size
+    /// when the packet size is chosen adaptively.
+    pub extended_cover_packet_ratio: f64,
+
+    /// If set, the sent messages whose recipients are declared as supporting it are going to be
+    /// protected with forward error correction with the specified ratio of parity to data packets.
+    pub erasure_coding_redundancy: Option<f64>,
+
+    /// Controls whether content of the sent messages should get compressed (using deflate),
+    /// if their recipients are declared as supporting it.
+    pub use_message_compression: bool,
+
     /// Defines the minimum number of reply surbs the client wants to keep in its storage at all times.
     /// It can only allow to go below that value if its to request additional reply surbs.
     pub minimum_reply_surb_storage_threshold: usize,
@@ -143,6 +182,11 @@ impl From<Debug> for ConfigDebug {
             .use_extended_packet_size
             .then(|| ExtendedPacketSize::Extended32);
 
+        // deflate is currently the only supported compression algorithm
+        let message_compression = debug
+            .use_message_compression
+            .then(|| CompressionAlgorithm::Deflate);
+
         ConfigDebug {
             average_packet_delay: Duration::from_millis(debug.average_packet_delay_ms),
             average_ack_delay: Duration::from_millis(debug.average_ack_delay_ms),
@@ -159,10 +203,27 @@ impl From<Debug> for ConfigDebug {
             topology_resolution_timeout: Duration::from_millis(
                 debug.topology_resolution_timeout_ms,
             ),
+            topology_verification_quorum: debug.topology_verification_quorum,
+            // the mixnet contract can't be queried from the browser
+            verify_topology_against_chain: false,
+            // not available in the browser
+            disable_topology_stream: true,
+            // not yet exposed to the browser
+            verify_topology_signatures: false,
+            trusted_topology_signers: Vec::new(),
+            disable_path_health_probing: debug.disable_path_health_probing,
+            path_health_probing_interval: Duration::from_millis(
+                debug.path_health_probing_interval_ms,
+            ),
+            path_health_exclusion_threshold: debug.path_health_exclusion_threshold,
             disable_loop_cover_traffic_stream: debug.disable_loop_cover_traffic_stream,
             disable_main_poisson_packet_distribution: debug
                 .disable_main_poisson_packet_distribution,
             use_extended_packet_size,
+            use_adaptive_packet_size: debug.use_adaptive_packet_size,
+            extended_cover_packet_ratio: debug.extended_cover_packet_ratio,
+            erasure_coding_redundancy: debug.erasure_coding_redundancy,
+            message_compression,
             minimum_reply_surb_storage_threshold: debug.minimum_reply_surb_storage_threshold,
             maximum_reply_surb_storage_threshold: debug.maximum_reply_surb_storage_threshold,
             minimum_reply_surb_request_size: debug.minimum_reply_surb_request_size,
@@ -191,10 +252,18 @@ impl From<ConfigDebug> for Debug {
             gateway_response_timeout_ms: debug.gateway_response_timeout.as_millis() as u64,
             topology_refresh_rate_ms: debug.topology_refresh_rate.as_millis() as u64,
             topology_resolution_timeout_ms: debug.topology_resolution_timeout.as_millis() as u64,
+            topology_verification_quorum: debug.topology_verification_quorum,
+            disable_path_health_probing: debug.disable_path_health_probing,
+            path_health_probing_interval_ms: debug.path_health_probing_interval.as_millis() as u64,
+            path_health_exclusion_threshold: debug.path_health_exclusion_threshold,
             disable_loop_cover_traffic_stream: debug.disable_loop_cover_traffic_stream,
             disable_main_poisson_packet_distribution: debug
                 .disable_main_poisson_packet_distribution,
             use_extended_packet_size: debug.use_extended_packet_size.is_some(),
+            use_adaptive_packet_size: debug.use_adaptive_packet_size,
+            extended_cover_packet_ratio: debug.extended_cover_packet_ratio,
+            erasure_coding_redundancy: debug.erasure_coding_redundancy,
+            use_message_compression: debug.message_compression.is_some(),
             minimum_reply_surb_storage_threshold: debug.minimum_reply_surb_storage_threshold,
             maximum_reply_surb_storage_threshold: debug.maximum_reply_surb_storage_threshold,
             minimum_reply_surb_request_size: debug.minimum_reply_surb_request_size,
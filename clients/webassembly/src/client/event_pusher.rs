@@ -0,0 +1,76 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use client_core::client::events::{ClientEvent, ClientEventReceiver};
+use futures::StreamExt;
+use gateway_client::GatewayConnectionEvent;
+use serde::Serialize;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::spawn_local;
+use wasm_utils::console_error;
+
+// the shape of the event object passed to the JS callback, e.g. `{ kind: "MessageDelivered", messageId: 42 }`
+#[derive(Serialize)]
+#[serde(tag = "kind")]
+enum JsClientEvent {
+    #[serde(rename_all = "camelCase")]
+    MessageDelivered {
+        message_id: u32,
+    },
+    GatewayDisconnected,
+    GatewayReconnected,
+    GatewayReconnectionFailed,
+}
+
+impl From<ClientEvent> for JsClientEvent {
+    fn from(event: ClientEvent) -> Self {
+        match event {
+            // we're only ever assigning ids that fit in u32 so that they'd be representable in JS
+            ClientEvent::MessageDelivered(id) => JsClientEvent::MessageDelivered {
+                message_id: id as u32,
+            },
+            ClientEvent::GatewayConnection(GatewayConnectionEvent::Disconnected) => {
+                JsClientEvent::GatewayDisconnected
+            }
+            ClientEvent::GatewayConnection(GatewayConnectionEvent::Reconnected) => {
+                JsClientEvent::GatewayReconnected
+            }
+            ClientEvent::GatewayConnection(GatewayConnectionEvent::ReconnectionFailed) => {
+                JsClientEvent::GatewayReconnectionFailed
+            }
+        }
+    }
+}
+
+pub(crate) struct EventPusher {
+    event_receiver: ClientEventReceiver,
+    on_event: js_sys::Function,
+}
+
+impl EventPusher {
+    pub(crate) fn new(event_receiver: ClientEventReceiver, on_event: js_sys::Function) -> Self {
+        EventPusher {
+            event_receiver,
+            on_event,
+        }
+    }
+
+    pub(crate) fn start(mut self) {
+        spawn_local(async move {
+            let this = JsValue::null();
+
+            while let Some(event) = self.event_receiver.next().await {
+                let js_event = match serde_wasm_bindgen::to_value(&JsClientEvent::from(event)) {
+                    Ok(js_event) => js_event,
+                    Err(err) => {
+                        console_error!("failed to serialize {event:?} - {err}");
+                        continue;
+                    }
+                };
+                if let Err(err) = self.on_event.call1(&this, &js_event) {
+                    console_error!("the event handler has thrown an error - {err:?}");
+                }
+            }
+        })
+    }
+}
@@ -11,20 +11,36 @@ use wasm_bindgen_futures::future_to_promise;
 // defining helper trait as we could directly call the method on the wrapper
 pub(crate) trait InputSender {
     fn send_message(&self, message: InputMessage) -> Promise;
+
+    /// Sends the message and resolves the promise with the id that's going to be
+    /// announced once it gets delivered.
+    fn send_tracked_message(&self, message: InputMessage, message_id: u32) -> Promise;
+}
+
+fn send_and_resolve_with(
+    client_input: &Arc<ClientInput>,
+    message: InputMessage,
+    resolved: JsValue,
+) -> Promise {
+    let this = Arc::clone(client_input);
+    future_to_promise(async move {
+        match this.input_sender.send(message).await {
+            Ok(_) => Ok(resolved),
+            Err(_) => {
+                let js_error = js_sys::Error::new("InputMessageReceiver has stopped receiving!");
+                Err(JsValue::from(js_error))
+            }
+        }
+    })
 }
 
 impl InputSender for Arc<ClientInput> {
     fn send_message(&self, message: InputMessage) -> Promise {
-        let this = Arc::clone(self);
-        future_to_promise(async move {
-            match this.input_sender.send(message).await {
-                Ok(_) => Ok(JsValue::null()),
-                Err(_) => {
-                    let js_error =
-                        js_sys::Error::new("InputMessageReceiver has stopped receiving!");
-                    Err(JsValue::from(js_error))
-                }
-            }
-        })
+        send_and_resolve_with(self, message, JsValue::null())
+    }
+
+    fn send_tracked_message(&self, message: InputMessage, message_id: u32) -> Promise {
+        let message = message.with_message_id(message_id.into());
+        send_and_resolve_with(self, message, JsValue::from(message_id))
     }
 }
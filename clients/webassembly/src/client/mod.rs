@@ -2,86 +2,274 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use self::config::Config;
+use crate::client::event_pusher::EventPusher;
 use crate::client::helpers::InputSender;
 use crate::client::response_pusher::ResponsePusher;
-use client_connections::TransmissionLane;
+use client_connections::{LaneQueueLengths, TransmissionLane};
 use client_core::client::base_client::{BaseClientBuilder, ClientInput, ClientOutput};
 use client_core::client::replies::reply_storage::browser_backend;
 use client_core::client::{inbound_messages::InputMessage, key_manager::KeyManager};
+use client_core::config::GatewayEndpointConfig;
+use crypto::asymmetric::identity;
+use futures::channel::mpsc;
+use futures::future::{self, Either};
 use gateway_client::bandwidth::BandwidthController;
+use gateway_client::GatewayClient;
+use gloo_timers::future::TimeoutFuture;
 use js_sys::Promise;
 use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
 use rand::rngs::OsRng;
+use std::cell::Cell;
 use std::sync::Arc;
+use std::time::Duration;
 use task::TaskManager;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::future_to_promise;
-use wasm_utils::{console_error, console_log};
+use wasm_storage::{BrowserStorage, StoreCipher};
+use wasm_utils::{console_error, console_log, console_warn};
+
+#[cfg(feature = "coconut")]
+use coconut_interface::{Base58, VerificationKey};
+#[cfg(feature = "coconut")]
+use gateway_client::wasm_storage::PersistentStorage;
+#[cfg(feature = "coconut")]
+use validator_client::client::{ApiClient, CoconutApiClient};
 
 pub mod config;
+mod event_pusher;
 mod helpers;
 mod response_pusher;
+mod storage;
+
+// how often we're checking whether there's enough space in the lane when applying backpressure
+const LANE_POLLING_INTERVAL_MS: u32 = 100;
+
+// how long we're willing to wait for all the tasks to finish once shutdown is requested
+const SHUTDOWN_TIMEOUT_MS: u32 = 5000;
 
 #[wasm_bindgen]
 pub struct NymClient {
     self_address: String,
     client_input: Arc<ClientInput>,
+    lane_queue_lengths: LaneQueueLengths,
 
-    // even though we don't use graceful shutdowns, other components rely on existence of this struct
+    // ids assigned to sent messages in order to announce their delivery
+    next_message_id: Cell<u32>,
+
+    // other components rely on existence of this struct
     // and if it's dropped, everything will start going offline
-    _task_manager: TaskManager,
+    task_manager: TaskManager,
 }
 
 #[wasm_bindgen]
 pub struct NymClientBuilder {
     config: Config,
 
-    /// KeyManager object containing smart pointers to all relevant keys used by the client.
-    key_manager: KeyManager,
+    on_message: js_sys::Function,
+
+    on_event: Option<js_sys::Function>,
 
-    reply_surb_storage_backend: browser_backend::Backend,
+    /// Passphrase used for encrypting the client keys kept in the browser storage.
+    storage_passphrase: Option<String>,
 
-    on_message: js_sys::Function,
+    /// Signing authorities used for obtaining the verification key of the bandwidth credentials.
+    #[cfg(feature = "coconut")]
+    coconut_signers: Vec<CoconutApiClient>,
+}
 
-    // unimplemented:
-    bandwidth_controller: Option<BandwidthController>,
-    disabled_credentials: bool,
+fn js_error(error_msg: String) -> JsValue {
+    console_error!("{}", error_msg);
+    JsValue::from(js_sys::Error::new(&error_msg))
 }
 
 #[wasm_bindgen]
 impl NymClientBuilder {
     #[wasm_bindgen(constructor)]
     pub fn new(config: Config, on_message: js_sys::Function) -> Self {
-        //, key_manager: Option<KeyManager>) {
         NymClientBuilder {
-            reply_surb_storage_backend: Self::setup_reply_surb_storage_backend(&config),
             config,
-            key_manager: Self::setup_key_manager(),
             on_message,
-            bandwidth_controller: None,
-            disabled_credentials: true,
+            on_event: None,
+            storage_passphrase: None,
+            #[cfg(feature = "coconut")]
+            coconut_signers: Vec::new(),
         }
     }
 
-    // TODO: once we make keys persistent, we'll require some kind of `init` method to generate
-    // a prior shared keypair between the client and the gateway
+    /// Specifies the callback invoked with the client events, i.e. message deliveries
+    /// (`{ kind: "MessageDelivered", messageId }`) and changes in the state of the gateway connection
+    /// (`{ kind: "GatewayDisconnected" }`, `{ kind: "GatewayReconnected" }`
+    /// and `{ kind: "GatewayReconnectionFailed" }`).
+    pub fn with_event_handler(mut self, on_event: js_sys::Function) -> NymClientBuilder {
+        self.on_event = Some(on_event);
+        self
+    }
 
-    // perhaps this should be public?
-    fn setup_key_manager() -> KeyManager {
-        let mut rng = OsRng;
-        // for time being generate new keys each time...
-        console_log!("generated new set of keys");
-        KeyManager::new(&mut rng)
+    /// Specifies the passphrase used for encrypting the client keys kept in the browser storage.
+    /// If it's not provided, the keys are never persisted and a fresh identity is used on every start.
+    pub fn with_storage_passphrase(mut self, passphrase: String) -> NymClientBuilder {
+        self.storage_passphrase = Some(passphrase);
+        self
     }
 
-    // don't get too excited about the name, under the hood it's just a big fat placeholder
-    // with no persistence
-    fn setup_reply_surb_storage_backend(config: &Config) -> browser_backend::Backend {
-        browser_backend::Backend::new(
-            config.debug.minimum_reply_surb_storage_threshold,
-            config.debug.maximum_reply_surb_storage_threshold,
+    async fn register_with_gateway(
+        gateway_endpoint: &GatewayEndpointConfig,
+        key_manager: &mut KeyManager,
+        timeout: Duration,
+    ) -> Result<(), JsValue> {
+        let gateway_identity = identity::PublicKey::from_base58_string(
+            &gateway_endpoint.gateway_id,
         )
+        .map_err(|err| {
+            js_error(format!(
+                "the provided gateway identity is malformed - {err}"
+            ))
+        })?;
+
+        let mut gateway_client = GatewayClient::new_init(
+            gateway_endpoint.gateway_listener.clone(),
+            gateway_identity,
+            gateway_endpoint.gateway_owner.clone(),
+            key_manager.identity_keypair(),
+            timeout,
+        );
+        gateway_client
+            .establish_connection()
+            .await
+            .map_err(|err| js_error(format!("failed to connect to the gateway - {err}")))?;
+        let shared_key = gateway_client
+            .perform_initial_authentication()
+            .await
+            .map_err(|err| js_error(format!("failed to register with the gateway - {err}")))?;
+        key_manager.insert_gateway_shared_key(shared_key);
+        Ok(())
+    }
+
+    async fn storage_cipher(
+        &self,
+        storage: &dyn BrowserStorage,
+    ) -> Result<Option<StoreCipher>, JsValue> {
+        let Some(passphrase) = self.storage_passphrase.as_ref() else {
+            console_warn!("no storage passphrase has been provided - the client keys are not going to be persisted");
+            return Ok(None);
+        };
+
+        wasm_storage::storage_cipher(storage, passphrase.as_bytes(), &mut OsRng)
+            .await
+            .map(Some)
+            .map_err(|err| {
+                storage::storage_error("failed to derive the storage encryption key", err)
+            })
+    }
+
+    // loads the keys from the storage (or generates fresh ones if there aren't any)
+    // and makes sure we have a shared key with the gateway we're about to use.
+    // without the cipher, the keys are neither loaded nor stored, as they're never kept in plaintext
+    async fn setup_key_manager(
+        storage: &dyn BrowserStorage,
+        cipher: Option<&StoreCipher>,
+        config: &Config,
+    ) -> Result<KeyManager, JsValue> {
+        let Some(cipher) = cipher else {
+            console_log!("generated new set of ephemeral keys");
+            let mut key_manager = KeyManager::new(&mut OsRng);
+            Self::register_with_gateway(
+                &config.gateway_endpoint,
+                &mut key_manager,
+                config.debug.gateway_response_timeout,
+            )
+            .await?;
+            return Ok(key_manager);
+        };
+
+        let mut key_manager = match KeyManager::load_from_browser_storage(storage, cipher).await {
+            Ok(Some(key_manager)) => {
+                console_log!("loaded stored client keys");
+                key_manager
+            }
+            Ok(None) => {
+                console_log!("generated new set of keys");
+                KeyManager::new(&mut OsRng)
+            }
+            Err(err) => {
+                return Err(storage::storage_error(
+                    "failed to load the stored client keys",
+                    err,
+                ))
+            }
+        };
+
+        let registered_gateway = storage::registered_gateway(storage)
+            .await
+            .map_err(|err| storage::storage_error("failed to load the gateway details", err))?;
+        let gateway_id = &config.gateway_endpoint.gateway_id;
+        if !key_manager.gateway_key_set() || registered_gateway.as_ref() != Some(gateway_id) {
+            console_log!("registering with gateway {gateway_id}");
+            Self::register_with_gateway(
+                &config.gateway_endpoint,
+                &mut key_manager,
+                config.debug.gateway_response_timeout,
+            )
+            .await?;
+
+            key_manager
+                .store_in_browser_storage(storage, cipher, &mut OsRng)
+                .await
+                .map_err(|err| storage::storage_error("failed to store the client keys", err))?;
+            storage::store_registered_gateway(storage, gateway_id)
+                .await
+                .map_err(|err| {
+                    storage::storage_error("failed to store the gateway details", err)
+                })?;
+        }
+
+        Ok(key_manager)
+    }
+
+    // the credentials are never kept in plaintext, so they can only be used with the storage cipher
+    #[cfg(feature = "coconut")]
+    fn create_bandwidth_controller(
+        &self,
+        storage: Arc<dyn BrowserStorage>,
+        cipher: Option<StoreCipher>,
+    ) -> Result<Option<BandwidthController>, JsValue> {
+        if self.config.disabled_credentials_mode {
+            return Ok(None);
+        }
+        if self.coconut_signers.is_empty() {
+            return Err(js_error(
+                "at least a single coconut signer has to be specified in order to use bandwidth credentials"
+                    .to_string(),
+            ));
+        }
+        let Some(cipher) = cipher else {
+            return Err(js_error(
+                "the storage passphrase has to be specified in order to use bandwidth credentials"
+                    .to_string(),
+            ));
+        };
+
+        Ok(Some(BandwidthController::new(
+            PersistentStorage::new(storage, Arc::new(cipher)),
+            self.coconut_signers.clone(),
+        )))
+    }
+
+    #[cfg(not(feature = "coconut"))]
+    fn create_bandwidth_controller(
+        &self,
+        _storage: Arc<dyn BrowserStorage>,
+        _cipher: Option<StoreCipher>,
+    ) -> Result<Option<BandwidthController>, JsValue> {
+        if self.config.disabled_credentials_mode {
+            Ok(None)
+        } else {
+            Err(js_error(
+                "the client has been built without the support for bandwidth credentials"
+                    .to_string(),
+            ))
+        }
     }
 
     fn start_reconstructed_pusher(client_output: ClientOutput, on_message: js_sys::Function) {
@@ -92,41 +280,88 @@ impl NymClientBuilder {
         future_to_promise(async move {
             console_log!("Starting the wasm client");
 
-            let base_builder = BaseClientBuilder::new(
+            let storage = storage::open_client_storage(&self.config.id).await;
+            let cipher = self.storage_cipher(storage.as_ref()).await?;
+            let key_manager =
+                Self::setup_key_manager(storage.as_ref(), cipher.as_ref(), &self.config).await?;
+            let bandwidth_controller = self.create_bandwidth_controller(storage.clone(), cipher)?;
+            let reply_surb_storage_backend = browser_backend::Backend::new_persistent(
+                storage,
+                self.config.debug.minimum_reply_surb_storage_threshold,
+                self.config.debug.maximum_reply_surb_storage_threshold,
+            );
+
+            let mut base_builder = BaseClientBuilder::new(
                 &self.config.gateway_endpoint,
                 &self.config.debug,
-                self.key_manager,
-                self.bandwidth_controller,
-                self.reply_surb_storage_backend,
-                self.disabled_credentials,
+                key_manager,
+                bandwidth_controller,
+                reply_surb_storage_backend,
+                self.config.disabled_credentials_mode,
                 vec![self.config.nym_api_url.clone()],
             );
 
+            if let Some(on_event) = self.on_event {
+                let (event_sender, event_receiver) = mpsc::unbounded();
+                base_builder = base_builder.with_event_sender(event_sender);
+                EventPusher::new(event_receiver, on_event).start();
+            }
+
             let self_address = base_builder.as_mix_recipient().to_string();
             let mut started_client = match base_builder.start_base().await {
                 Ok(base_client) => base_client,
                 Err(err) => {
-                    let error_msg = format!("failed to start the base client components - {err}");
-                    console_error!("{}", error_msg);
-                    let js_error = js_sys::Error::new(&error_msg);
-                    return Err(JsValue::from(js_error));
+                    return Err(js_error(format!(
+                        "failed to start the base client components - {err}"
+                    )))
                 }
             };
 
             let client_input = started_client.client_input.register_producer();
             let client_output = started_client.client_output.register_consumer();
+            let lane_queue_lengths = client_output.shared_lane_queue_lengths.clone();
 
             Self::start_reconstructed_pusher(client_output, self.on_message);
 
             Ok(JsValue::from(NymClient {
                 self_address,
                 client_input: Arc::new(client_input),
-                _task_manager: started_client.task_manager,
+                lane_queue_lengths,
+                next_message_id: Cell::new(1),
+                task_manager: started_client.task_manager,
             }))
         })
     }
 }
 
+#[cfg(feature = "coconut")]
+#[wasm_bindgen]
+impl NymClientBuilder {
+    /// Adds a coconut signing authority, identified by its index in the signers set, whose
+    /// verification key share is going to be used for verifying the bandwidth credentials.
+    /// Note that credentials are only going to be used if they were explicitly enabled
+    /// in the config.
+    pub fn with_coconut_signer(
+        mut self,
+        api_url: String,
+        node_index: u64,
+        verification_key: String,
+    ) -> Result<NymClientBuilder, JsValue> {
+        let api_url = api_url
+            .parse()
+            .map_err(|err| js_error(format!("{api_url} is not a valid url - {err}")))?;
+        let verification_key = VerificationKey::try_from_bs58(&verification_key)
+            .map_err(|err| js_error(format!("the verification key is malformed - {err}")))?;
+
+        self.coconut_signers.push(CoconutApiClient {
+            api_client: ApiClient::new(api_url),
+            verification_key,
+            node_id: node_index,
+        });
+        Ok(self)
+    }
+}
+
 #[wasm_bindgen]
 impl NymClient {
     pub fn self_address(&self) -> String {
@@ -145,6 +380,12 @@ impl NymClient {
         }
     }
 
+    fn next_message_id(&self) -> u32 {
+        let id = self.next_message_id.get();
+        self.next_message_id.set(id.wrapping_add(1));
+        id
+    }
+
     fn parse_sender_tag(tag: &str) -> Result<AnonymousSenderTag, JsValue> {
         match AnonymousSenderTag::try_from_base58_string(tag) {
             Ok(tag) => Ok(tag),
@@ -161,7 +402,24 @@ impl NymClient {
     /// You're simply sending your `data` to specified `recipient` without any tagging.
     ///
     /// Ends up with `NymMessage::Plain` variant
-    pub fn send_regular_message(&self, message: Vec<u8>, recipient: String) -> Promise {
+    ///
+    /// The returned promise resolves to the id of the message that's going to be announced
+    /// in the `MessageDelivered` event.
+    ///
+    /// If `compress` is set and the client has `use_message_compression` enabled,
+    /// the message is going to be compressed. It must only be set if the recipient
+    /// is known to be able to decompress it.
+    ///
+    /// Similarly, if `erasure_coding` is set and the client has `erasure_coding_redundancy`
+    /// configured, the message is going to be protected with forward error correction.
+    /// It must only be set if the recipient is known to be able to reconstruct such messages.
+    pub fn send_regular_message(
+        &self,
+        message: Vec<u8>,
+        recipient: String,
+        compress: Option<bool>,
+        erasure_coding: Option<bool>,
+    ) -> Promise {
         console_log!(
             "Attempting to send {:.2} kiB message to {recipient}",
             message.len() as f64 / 1024.0
@@ -173,8 +431,15 @@ impl NymClient {
         };
         let lane = TransmissionLane::General;
 
-        let input_msg = InputMessage::new_regular(recipient, message, lane);
-        self.client_input.send_message(input_msg)
+        let mut input_msg = InputMessage::new_regular(recipient, message, lane);
+        if compress.unwrap_or_default() {
+            input_msg = input_msg.with_compression();
+        }
+        if erasure_coding.unwrap_or_default() {
+            input_msg = input_msg.with_erasure_coding();
+        }
+        self.client_input
+            .send_tracked_message(input_msg, self.next_message_id())
     }
 
     /// Creates a message used for a duplex anonymous communication where the recipient
@@ -185,11 +450,18 @@ impl NymClient {
     /// (and thus the recipient also knowing our sender tag).
     ///
     /// Ends up with `NymMessage::Repliable` variant
+    ///
+    /// The returned promise resolves to the id of the message that's going to be announced
+    /// in the `MessageDelivered` event.
+    ///
+    /// `compress` and `erasure_coding` have the same meaning as in `send_regular_message`.
     pub fn send_anonymous_message(
         &self,
         message: Vec<u8>,
         recipient: String,
         reply_surbs: u32,
+        compress: Option<bool>,
+        erasure_coding: Option<bool>,
     ) -> Promise {
         console_log!(
             "Attempting to anonymously send {:.2} kiB message to {recipient} while attaching {reply_surbs} replySURBs.",
@@ -202,8 +474,15 @@ impl NymClient {
         };
         let lane = TransmissionLane::General;
 
-        let input_msg = InputMessage::new_anonymous(recipient, message, reply_surbs, lane);
-        self.client_input.send_message(input_msg)
+        let mut input_msg = InputMessage::new_anonymous(recipient, message, reply_surbs, lane);
+        if compress.unwrap_or_default() {
+            input_msg = input_msg.with_compression();
+        }
+        if erasure_coding.unwrap_or_default() {
+            input_msg = input_msg.with_erasure_coding();
+        }
+        self.client_input
+            .send_tracked_message(input_msg, self.next_message_id())
     }
 
     /// Attempt to use our internally received and stored `ReplySurb` to send the message back
@@ -225,4 +504,53 @@ impl NymClient {
         let input_msg = InputMessage::new_reply(sender_tag, message, lane);
         self.client_input.send_message(input_msg)
     }
+
+    /// Returns the number of packets waiting to be sent out to the mix network.
+    pub fn queue_length(&self) -> u32 {
+        self.lane_queue_lengths
+            .get(&TransmissionLane::General)
+            .unwrap_or_default() as u32
+    }
+
+    /// Returns a promise that resolves once the number of packets waiting to be sent out
+    /// drops to (or below) the specified value. It should be awaited before sending more data
+    /// in order not to overwhelm the client.
+    pub fn wait_for_queue_capacity(&self, max_queue_length: u32) -> Promise {
+        let lane_queue_lengths = self.lane_queue_lengths.clone();
+        future_to_promise(async move {
+            while lane_queue_lengths
+                .get(&TransmissionLane::General)
+                .unwrap_or_default()
+                > max_queue_length as usize
+            {
+                TimeoutFuture::new(LANE_POLLING_INTERVAL_MS).await;
+            }
+            Ok(JsValue::null())
+        })
+    }
+
+    /// Gracefully stops all the client tasks, including flushing its data to the storage.
+    /// The client must not be used afterwards.
+    pub fn stop(self) -> Promise {
+        future_to_promise(async move {
+            let mut task_manager = self.task_manager;
+            if task_manager.signal_shutdown().is_err() {
+                // all tasks are already gone
+                return Ok(JsValue::null());
+            }
+
+            // input channel has to be dropped so that its listener could finish
+            drop(self.client_input);
+
+            let shutdown = Box::pin(task_manager.wait_for_shutdown());
+            let timeout = TimeoutFuture::new(SHUTDOWN_TIMEOUT_MS);
+            match future::select(shutdown, timeout).await {
+                Either::Left(_) => console_log!("the client has been stopped"),
+                Either::Right(_) => {
+                    console_warn!("timed out while waiting for the client tasks to finish")
+                }
+            }
+            Ok(JsValue::null())
+        })
+    }
 }
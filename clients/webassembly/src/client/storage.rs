@@ -0,0 +1,137 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use js_sys::{Promise, Uint8Array};
+use rand::rngs::OsRng;
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+use wasm_storage::{stores, BrowserStorage, InMemoryStorage, IndexedDbStorage, StorageError};
+use wasm_utils::{console_error, console_warn};
+
+// identity of the gateway the stored shared key has been derived with
+const REGISTERED_GATEWAY_KEY: &str = "registered_gateway_id";
+
+fn database_name(client_id: &str) -> String {
+    format!("nym-client-{client_id}")
+}
+
+pub(crate) fn storage_error(context: &str, err: StorageError) -> JsValue {
+    let error_msg = format!("{context} - {err}");
+    console_error!("{}", error_msg);
+    JsValue::from(js_sys::Error::new(&error_msg))
+}
+
+/// Opens the persistent storage of the client with the specified id. If IndexedDB is not
+/// available (for example in private browsing mode), the data is only going to be kept in memory.
+pub(crate) async fn open_client_storage(client_id: &str) -> Arc<dyn BrowserStorage> {
+    match IndexedDbStorage::open(&database_name(client_id)).await {
+        Ok(storage) => Arc::new(storage),
+        Err(err) => {
+            console_warn!("failed to open the IndexedDB storage - {err}. All the client data will be lost once it's shut down");
+            Arc::new(InMemoryStorage::new())
+        }
+    }
+}
+
+pub(crate) async fn registered_gateway(
+    storage: &dyn BrowserStorage,
+) -> Result<Option<String>, StorageError> {
+    Ok(storage
+        .read(stores::KEYS, REGISTERED_GATEWAY_KEY)
+        .await?
+        .map(|raw| String::from_utf8_lossy(&raw).into_owned()))
+}
+
+pub(crate) async fn store_registered_gateway(
+    storage: &dyn BrowserStorage,
+    gateway_id: &str,
+) -> Result<(), StorageError> {
+    storage
+        .write(stores::KEYS, REGISTERED_GATEWAY_KEY, gateway_id.as_bytes())
+        .await
+}
+
+/// Exports all the data (keys, reply SURBs and credentials) of the client with the specified id
+/// encrypted with the provided passphrase.
+#[wasm_bindgen]
+pub fn export_client_storage(client_id: String, passphrase: String) -> Promise {
+    future_to_promise(async move {
+        let storage = IndexedDbStorage::open(&database_name(&client_id))
+            .await
+            .map_err(|err| storage_error("failed to open the client storage", err))?;
+
+        let bundle =
+            wasm_storage::export_encrypted_bundle(&storage, passphrase.as_bytes(), &mut OsRng)
+                .await
+                .map_err(|err| storage_error("failed to export the client storage", err))?;
+
+        Ok(JsValue::from(Uint8Array::from(bundle.as_slice())))
+    })
+}
+
+/// Replaces all the data of the client with the specified id with the content of the bundle
+/// created with `export_client_storage`.
+/// Note: it must not be called while the client is running.
+#[wasm_bindgen]
+pub fn import_client_storage(client_id: String, bundle: Vec<u8>, passphrase: String) -> Promise {
+    future_to_promise(async move {
+        let storage = IndexedDbStorage::open(&database_name(&client_id))
+            .await
+            .map_err(|err| storage_error("failed to open the client storage", err))?;
+
+        wasm_storage::import_encrypted_bundle(&storage, &bundle, passphrase.as_bytes())
+            .await
+            .map_err(|err| storage_error("failed to import the client storage", err))?;
+
+        Ok(JsValue::null())
+    })
+}
+
+/// Stores a coconut bandwidth credential (for example, one acquired with the `credential` binary)
+/// in the storage of the client with the specified id, so that it could be used when connecting
+/// to the gateway. Its secret parts are encrypted with the key derived from the storage passphrase,
+/// so it must be the same one the client is started with.
+#[cfg(feature = "coconut")]
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn import_bandwidth_credential(
+    client_id: String,
+    storage_passphrase: String,
+    voucher_value: String,
+    voucher_info: String,
+    serial_number: String,
+    binding_number: String,
+    signature: String,
+    expiry_epoch: u64,
+) -> Promise {
+    use gateway_client::wasm_storage::{PersistentStorage, Storage};
+
+    future_to_promise(async move {
+        let storage = IndexedDbStorage::open(&database_name(&client_id))
+            .await
+            .map_err(|err| storage_error("failed to open the client storage", err))?;
+        let cipher =
+            wasm_storage::storage_cipher(&storage, storage_passphrase.as_bytes(), &mut OsRng)
+                .await
+                .map_err(|err| storage_error("failed to derive the storage encryption key", err))?;
+
+        PersistentStorage::new(Arc::new(storage), Arc::new(cipher))
+            .insert_coconut_credential(
+                voucher_value,
+                voucher_info,
+                serial_number,
+                binding_number,
+                signature,
+                expiry_epoch,
+            )
+            .await
+            .map_err(|err| {
+                let error_msg = format!("failed to store the bandwidth credential - {err}");
+                console_error!("{}", error_msg);
+                JsValue::from(js_sys::Error::new(&error_msg))
+            })?;
+
+        Ok(JsValue::null())
+    })
+}
@@ -14,16 +14,26 @@ use crate::wasm_storage::StorageError;
 #[cfg(all(not(target_arch = "wasm32"), feature = "coconut"))]
 use credential_storage::error::StorageError;
 
+#[cfg(feature = "coconut")]
+use coconut_bandwidth_contract_common::expiry::{epoch_at, is_spendable};
+#[cfg(feature = "coconut")]
+use log::warn;
 #[cfg(feature = "coconut")]
 use std::str::FromStr;
+#[cfg(all(not(target_arch = "wasm32"), feature = "coconut"))]
+use std::time::{SystemTime, UNIX_EPOCH};
 #[cfg(feature = "coconut")]
 use validator_client::client::CoconutApiClient;
+#[cfg(all(target_arch = "wasm32", feature = "coconut"))]
+use wasm_timer::{SystemTime, UNIX_EPOCH};
 #[cfg(feature = "coconut")]
 use {
-    coconut_interface::Base58,
+    coconut_interface::{Base58, Parameters, VerificationKey},
     credentials::coconut::{
-        bandwidth::prepare_for_spending, utils::obtain_aggregate_verification_key,
+        bandwidth::{prepare_for_spending, TICKET_INDEX_BITS, TOTAL_ATTRIBUTES},
+        utils::{obtain_aggregate_verification_key, prepare_ticket_for_spending},
     },
+    network_defaults::DIVISIBLE_VOUCHER_INFO,
 };
 
 // TODO: make it nicer for wasm (I don't want to touch it for this experiment)
@@ -33,6 +43,22 @@ use crate::wasm_storage::PersistentStorage;
 #[cfg(not(target_arch = "wasm32"))]
 use credential_storage::PersistentStorage;
 
+#[cfg(all(target_arch = "wasm32", feature = "coconut"))]
+use crate::wasm_storage::CoconutCredential as StoredCredential;
+#[cfg(all(not(target_arch = "wasm32"), feature = "coconut"))]
+use credential_storage::models::CoconutCredential as StoredCredential;
+
+/// Credential prepared to be shown to the gateway.
+#[cfg(feature = "coconut")]
+pub enum PreparedCredential {
+    /// Credential spent as a whole. The stored credential with the provided id has to be consumed
+    /// once the gateway has accepted it.
+    Whole(coconut_interface::Credential, i64),
+
+    /// Single ticket of a divisible credential. It has already been recorded as spent.
+    Ticket(coconut_interface::TicketCredential),
+}
+
 #[derive(Clone)]
 pub struct BandwidthController<St: Storage = PersistentStorage> {
     #[allow(dead_code)]
@@ -61,12 +87,22 @@ where
     #[cfg(feature = "coconut")]
     pub async fn prepare_coconut_credential(
         &self,
-    ) -> Result<(coconut_interface::Credential, i64), GatewayClientError> {
+    ) -> Result<PreparedCredential, GatewayClientError> {
         let verification_key = obtain_aggregate_verification_key(&self.coconut_api_clients).await?;
-        let bandwidth_credential = self.storage.get_next_coconut_credential().await?;
+        self.prepare_with_verification_key(&verification_key).await
+    }
+
+    // divisible credentials are spent one ticket at a time, the whole ones all at once
+    #[cfg(feature = "coconut")]
+    async fn prepare_with_verification_key(
+        &self,
+        verification_key: &VerificationKey,
+    ) -> Result<PreparedCredential, GatewayClientError> {
+        let bandwidth_credential = self.next_spendable_credential().await?;
         let voucher_value = u64::from_str(&bandwidth_credential.voucher_value)
             .map_err(|_| StorageError::InconsistentData)?;
         let voucher_info = bandwidth_credential.voucher_info.clone();
+        let expiry_epoch = bandwidth_credential.expiry_epoch as u64;
         let serial_number =
             coconut_interface::Attribute::try_from_bs58(bandwidth_credential.serial_number)?;
         let binding_number =
@@ -74,22 +110,248 @@ where
         let signature =
             coconut_interface::Signature::try_from_bs58(bandwidth_credential.signature)?;
 
+        if voucher_info == DIVISIBLE_VOUCHER_INFO {
+            let ticket_index = bandwidth_credential.spent_tickets as u64;
+            // the ticket is recorded as spent before it's ever shown, so that the same ticket
+            // would never be shown twice, even if the client crashed in between
+            self.storage
+                .consume_coconut_ticket(bandwidth_credential.id, ticket_index)
+                .await?;
+            if ticket_index + 1 == 1 << TICKET_INDEX_BITS {
+                self.storage
+                    .consume_coconut_credential(bandwidth_credential.id)
+                    .await?;
+            }
+
+            let params = Parameters::new(TOTAL_ATTRIBUTES)?;
+            return Ok(PreparedCredential::Ticket(prepare_ticket_for_spending(
+                &params,
+                voucher_value,
+                voucher_info,
+                expiry_epoch,
+                serial_number,
+                binding_number,
+                ticket_index,
+                &signature,
+                verification_key,
+            )?));
+        }
+
         // the below would only be executed once we know where we want to spend it (i.e. which gateway and stuff)
-        Ok((
+        Ok(PreparedCredential::Whole(
             prepare_for_spending(
                 voucher_value,
                 voucher_info,
+                expiry_epoch,
                 serial_number,
                 binding_number,
                 &signature,
-                &verification_key,
+                verification_key,
             )?,
             bandwidth_credential.id,
         ))
     }
 
+    // expired credentials are going to be rejected by the gateway anyway, so mark them as consumed
+    #[cfg(feature = "coconut")]
+    async fn next_spendable_credential(&self) -> Result<StoredCredential, GatewayClientError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let current_epoch = epoch_at(now);
+
+        loop {
+            let credential = self.storage.get_next_coconut_credential().await?;
+            // all the tickets might have been spent without the credential getting consumed
+            let exhausted = credential.voucher_info == DIVISIBLE_VOUCHER_INFO
+                && credential.spent_tickets as u64 >= 1 << TICKET_INDEX_BITS;
+            if exhausted {
+                self.storage
+                    .consume_coconut_credential(credential.id)
+                    .await?;
+                continue;
+            }
+            if is_spendable(credential.expiry_epoch as u64, current_epoch) {
+                return Ok(credential);
+            }
+            warn!(
+                "credential {} has expired at the end of epoch {} - it will not be used",
+                credential.id, credential.expiry_epoch
+            );
+            self.storage
+                .consume_coconut_credential(credential.id)
+                .await?;
+        }
+    }
+
     #[cfg(feature = "coconut")]
     pub async fn consume_credential(&self, id: i64) -> Result<(), GatewayClientError> {
         Ok(self.storage.consume_coconut_credential(id).await?)
     }
 }
+
+#[cfg(all(test, feature = "coconut", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use coconut_bandwidth_contract_common::expiry::deposit_expiry_epoch;
+    use coconut_interface::{blind_sign, hash_to_scalar, prepare_blind_sign, ttp_keygen};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct InMemStorage {
+        credentials: Arc<Mutex<Vec<StoredCredential>>>,
+    }
+
+    fn copy_credential(credential: &StoredCredential) -> StoredCredential {
+        StoredCredential {
+            id: credential.id,
+            voucher_value: credential.voucher_value.clone(),
+            voucher_info: credential.voucher_info.clone(),
+            serial_number: credential.serial_number.clone(),
+            binding_number: credential.binding_number.clone(),
+            signature: credential.signature.clone(),
+            consumed: credential.consumed,
+            expiry_epoch: credential.expiry_epoch,
+            spent_tickets: credential.spent_tickets,
+        }
+    }
+
+    #[async_trait]
+    impl Storage for InMemStorage {
+        async fn insert_coconut_credential(
+            &self,
+            voucher_value: String,
+            voucher_info: String,
+            serial_number: String,
+            binding_number: String,
+            signature: String,
+            expiry_epoch: u64,
+        ) -> Result<(), StorageError> {
+            let mut credentials = self.credentials.lock().unwrap();
+            let id = credentials.len() as i64 + 1;
+            credentials.push(StoredCredential {
+                id,
+                voucher_value,
+                voucher_info,
+                serial_number,
+                binding_number,
+                signature,
+                consumed: false,
+                expiry_epoch: expiry_epoch as i64,
+                spent_tickets: 0,
+            });
+            Ok(())
+        }
+
+        async fn get_next_coconut_credential(&self) -> Result<StoredCredential, StorageError> {
+            self.credentials
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|credential| !credential.consumed)
+                .map(copy_credential)
+                .ok_or(StorageError::InconsistentData)
+        }
+
+        async fn get_all_coconut_credentials(&self) -> Result<Vec<StoredCredential>, StorageError> {
+            Ok(self
+                .credentials
+                .lock()
+                .unwrap()
+                .iter()
+                .map(copy_credential)
+                .collect())
+        }
+
+        async fn consume_coconut_credential(&self, id: i64) -> Result<(), StorageError> {
+            let mut credentials = self.credentials.lock().unwrap();
+            credentials[id as usize - 1].consumed = true;
+            Ok(())
+        }
+
+        async fn consume_coconut_ticket(
+            &self,
+            id: i64,
+            ticket_index: u64,
+        ) -> Result<(), StorageError> {
+            let mut credentials = self.credentials.lock().unwrap();
+            credentials[id as usize - 1].spent_tickets = ticket_index as i64 + 1;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn divisible_credential_is_spent_one_ticket_at_a_time() {
+        let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
+        let key_pair = ttp_keygen(&params, 1, 1).unwrap().remove(0);
+        let verification_key = key_pair.verification_key();
+
+        let voucher_value = 256u64;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let expiry_epoch = deposit_expiry_epoch(now);
+        let serial_number = params.random_scalar();
+        let binding_number = params.random_scalar();
+        let private_attributes = vec![serial_number, binding_number];
+        let public_attributes = vec![
+            hash_to_scalar(voucher_value.to_string()),
+            hash_to_scalar(DIVISIBLE_VOUCHER_INFO),
+            hash_to_scalar(expiry_epoch.to_string()),
+        ];
+        let (commitments_openings, blind_sign_request) =
+            prepare_blind_sign(&params, &private_attributes, &public_attributes).unwrap();
+        let signature = blind_sign(
+            &params,
+            &key_pair.secret_key(),
+            &blind_sign_request,
+            &public_attributes,
+        )
+        .unwrap()
+        .unblind(
+            &params,
+            &verification_key,
+            &private_attributes,
+            &public_attributes,
+            &blind_sign_request.get_commitment_hash(),
+            &commitments_openings,
+        )
+        .unwrap();
+
+        let storage = InMemStorage::default();
+        storage
+            .insert_coconut_credential(
+                voucher_value.to_string(),
+                DIVISIBLE_VOUCHER_INFO.to_string(),
+                serial_number.to_bs58(),
+                binding_number.to_bs58(),
+                signature.to_bs58(),
+                expiry_epoch,
+            )
+            .await
+            .unwrap();
+        let controller = BandwidthController::new(storage.clone(), Vec::new());
+
+        let mut ticket_serial_numbers = Vec::new();
+        for _ in 0..2 {
+            let prepared = controller
+                .prepare_with_verification_key(&verification_key)
+                .await
+                .unwrap();
+            let PreparedCredential::Ticket(ticket) = prepared else {
+                panic!("divisible credential has been prepared to be spent as a whole");
+            };
+            assert!(ticket.verify(&verification_key, TICKET_INDEX_BITS));
+            assert_eq!(ticket.ticket_value(TICKET_INDEX_BITS), 1);
+            ticket_serial_numbers.push(ticket.ticket_serial_number());
+        }
+        assert_ne!(ticket_serial_numbers[0], ticket_serial_numbers[1]);
+
+        let stored = storage.get_next_coconut_credential().await.unwrap();
+        assert_eq!(stored.spent_tickets, 2);
+        assert!(!stored.consumed);
+    }
+}
@@ -26,7 +26,9 @@ use task::TaskClient;
 use tungstenite::protocol::Message;
 
 #[cfg(feature = "coconut")]
-use coconut_interface::Credential;
+use crate::bandwidth::PreparedCredential;
+#[cfg(feature = "coconut")]
+use coconut_interface::{Credential, TicketCredential};
 
 #[cfg(not(target_arch = "wasm32"))]
 use credential_storage::PersistentStorage;
@@ -43,6 +45,25 @@ use wasm_utils::websocket::JSWebsocket;
 const DEFAULT_RECONNECTION_ATTEMPTS: usize = 10;
 const DEFAULT_RECONNECTION_BACKOFF: Duration = Duration::from_secs(5);
 
+pub type GatewayConnectionEventSender =
+    futures::channel::mpsc::UnboundedSender<GatewayConnectionEvent>;
+pub type GatewayConnectionEventReceiver =
+    futures::channel::mpsc::UnboundedReceiver<GatewayConnectionEvent>;
+
+/// Changes in the state of the connection with the gateway that happened after
+/// the initial authentication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatewayConnectionEvent {
+    /// The connection with the gateway got closed and the client is going to attempt reconnection.
+    Disconnected,
+
+    /// The connection with the gateway got re-established.
+    Reconnected,
+
+    /// All the reconnection attempts have failed.
+    ReconnectionFailed,
+}
+
 pub struct GatewayClient {
     authenticated: bool,
     disabled_credentials_mode: bool,
@@ -65,6 +86,8 @@ pub struct GatewayClient {
     reconnection_attempts: usize,
     /// Delay between each subsequent reconnection attempt.
     reconnection_backoff: Duration,
+    /// Optional channel used for announcing disconnections and reconnections.
+    connection_event_sender: Option<GatewayConnectionEventSender>,
 
     /// Listen to shutdown messages.
     shutdown: TaskClient,
@@ -101,6 +124,7 @@ impl GatewayClient {
             should_reconnect_on_failure: true,
             reconnection_attempts: DEFAULT_RECONNECTION_ATTEMPTS,
             reconnection_backoff: DEFAULT_RECONNECTION_BACKOFF,
+            connection_event_sender: None,
             shutdown,
         }
     }
@@ -122,6 +146,19 @@ impl GatewayClient {
         self.reconnection_backoff = backoff
     }
 
+    pub fn with_connection_event_sender(&mut self, sender: GatewayConnectionEventSender) {
+        self.connection_event_sender = Some(sender)
+    }
+
+    fn notify_connection_event(&self, event: GatewayConnectionEvent) {
+        if let Some(sender) = &self.connection_event_sender {
+            // nobody might be listening anymore, which is fine
+            if sender.unbounded_send(event).is_err() {
+                debug!("failed to announce {event:?} as the receiver has been dropped");
+            }
+        }
+    }
+
     pub fn new_init(
         gateway_address: String,
         gateway_identity: identity::PublicKey,
@@ -154,6 +191,7 @@ impl GatewayClient {
             should_reconnect_on_failure: false,
             reconnection_attempts: DEFAULT_RECONNECTION_ATTEMPTS,
             reconnection_backoff: DEFAULT_RECONNECTION_BACKOFF,
+            connection_event_sender: None,
             shutdown,
         }
     }
@@ -227,11 +265,13 @@ impl GatewayClient {
     async fn attempt_reconnection(&mut self) -> Result<(), GatewayClientError> {
         info!("Attempting gateway reconnection...");
         self.authenticated = false;
+        self.notify_connection_event(GatewayConnectionEvent::Disconnected);
 
         for i in 1..self.reconnection_attempts {
             info!("attempt {}...", i);
             if self.authenticate_and_start().await.is_ok() {
                 info!("managed to reconnect!");
+                self.notify_connection_event(GatewayConnectionEvent::Reconnected);
                 return Ok(());
             }
 
@@ -252,6 +292,7 @@ impl GatewayClient {
         match self.authenticate_and_start().await {
             Ok(_) => {
                 info!("managed to reconnect!");
+                self.notify_connection_event(GatewayConnectionEvent::Reconnected);
                 Ok(())
             }
             Err(err) => {
@@ -259,6 +300,7 @@ impl GatewayClient {
                     "failed to reconnect after {} attempts",
                     self.reconnection_attempts
                 );
+                self.notify_connection_event(GatewayConnectionEvent::ReconnectionFailed);
                 Err(err)
             }
         }
@@ -556,6 +598,33 @@ impl GatewayClient {
         Ok(())
     }
 
+    /// Claims bandwidth worth a single ticket of a divisible credential.
+    /// Note that keeping track of which tickets have already been spent is up to the caller.
+    #[cfg(feature = "coconut")]
+    async fn claim_coconut_bandwidth_ticket(
+        &mut self,
+        ticket: TicketCredential,
+    ) -> Result<(), GatewayClientError> {
+        if !self.authenticated {
+            return Err(GatewayClientError::NotAuthenticated);
+        }
+        let Some(shared_key) = self.shared_key.as_ref() else {
+            return Err(GatewayClientError::NoSharedKeyAvailable);
+        };
+
+        let mut rng = OsRng;
+        let iv = IV::new_random(&mut rng);
+
+        let msg =
+            ClientControlRequest::new_enc_coconut_bandwidth_ticket(&ticket, shared_key, iv).into();
+        self.bandwidth_remaining = match self.send_websocket_message(msg).await? {
+            ServerResponse::Bandwidth { available_total } => Ok(available_total),
+            ServerResponse::Error { message } => Err(GatewayClientError::GatewayError(message)),
+            _ => Err(GatewayClientError::UnexpectedResponse),
+        }?;
+        Ok(())
+    }
+
     async fn try_claim_testnet_bandwidth(&mut self) -> Result<(), GatewayClientError> {
         let msg = ClientControlRequest::ClaimFreeTestnetBandwidth.into();
         self.bandwidth_remaining = match self.send_websocket_message(msg).await? {
@@ -587,7 +656,7 @@ impl GatewayClient {
         let _gateway_owner = self.gateway_owner.clone();
 
         #[cfg(feature = "coconut")]
-        let (credential, credential_id) = self
+        let prepared = self
             .bandwidth_controller
             .as_ref()
             .unwrap()
@@ -597,14 +666,17 @@ impl GatewayClient {
         return self.try_claim_testnet_bandwidth().await;
 
         #[cfg(feature = "coconut")]
-        {
-            self.claim_coconut_bandwidth(credential).await?;
-            self.bandwidth_controller
-                .as_ref()
-                .unwrap()
-                .consume_credential(credential_id)
-                .await?;
-            Ok(())
+        match prepared {
+            PreparedCredential::Whole(credential, credential_id) => {
+                self.claim_coconut_bandwidth(credential).await?;
+                self.bandwidth_controller
+                    .as_ref()
+                    .unwrap()
+                    .consume_credential(credential_id)
+                    .await?;
+                Ok(())
+            }
+            PreparedCredential::Ticket(ticket) => self.claim_coconut_bandwidth_ticket(ticket).await,
         }
     }
 
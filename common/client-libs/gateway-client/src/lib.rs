@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::GatewayClientError;
-pub use client::GatewayClient;
+pub use client::{
+    GatewayClient, GatewayConnectionEvent, GatewayConnectionEventReceiver,
+    GatewayConnectionEventSender,
+};
 use gateway_requests::registration::handshake::SharedKeys;
 use gateway_requests::BinaryResponse;
 use log::warn;
@@ -16,8 +19,8 @@ pub mod client;
 pub mod error;
 pub mod packet_router;
 pub mod socket_state;
-#[cfg(target_arch = "wasm32")]
-mod wasm_storage;
+#[cfg(any(target_arch = "wasm32", test))]
+pub mod wasm_storage;
 
 /// Helper method for reading from websocket stream. Helps to flatten the structure.
 pub(crate) fn cleanup_socket_message(
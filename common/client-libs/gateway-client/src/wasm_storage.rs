@@ -2,20 +2,122 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use thiserror::Error;
+use wasm_storage::{stores, BrowserStorage, EncryptedData, StoreCipher};
 
 #[derive(Error, Debug)]
 pub enum StorageError {
-    #[error("Wasm client is not yet supported")]
-    WasmNotSupported,
+    #[error("There are no unused coconut credentials in the storage")]
+    NoUnusedCredentials,
+
+    #[error("Coconut credential {id} does not exist")]
+    CredentialNotFound { id: i64 },
+
+    #[error("Browser storage failure - {0}")]
+    BrowserStorageError(#[from] wasm_storage::StorageError),
 
-    #[allow(dead_code)]
     #[error("Code shouldn't reach this point")]
     InconsistentData,
 }
 
+/// Coconut credentials storage kept in the browser storage (such as IndexedDB).
+/// The secret parts of the credentials are encrypted with the provided cipher.
 #[derive(Clone)]
-pub struct PersistentStorage {}
+pub struct PersistentStorage {
+    storage: Arc<dyn BrowserStorage>,
+    cipher: Arc<StoreCipher>,
+}
+
+impl PersistentStorage {
+    pub fn new(storage: Arc<dyn BrowserStorage>, cipher: Arc<StoreCipher>) -> Self {
+        PersistentStorage { storage, cipher }
+    }
+
+    fn encrypt_secrets(&self, secrets: &CredentialSecrets) -> Result<EncryptedData, StorageError> {
+        self.cipher
+            .encrypt_data(secrets, &mut OsRng)
+            .map_err(|err| wasm_storage::StorageError::from(err).into())
+    }
+
+    fn decrypt_secrets(
+        &self,
+        encrypted: &EncryptedData,
+    ) -> Result<CredentialSecrets, StorageError> {
+        self.cipher
+            .decrypt_data(encrypted)
+            .map_err(|err| wasm_storage::StorageError::from(err).into())
+    }
+
+    async fn stored_ids(&self) -> Result<Vec<i64>, StorageError> {
+        let mut ids = self
+            .storage
+            .keys(stores::CREDENTIALS)
+            .await?
+            .into_iter()
+            .map(|key| {
+                key.parse().map_err(|_| {
+                    wasm_storage::StorageError::malformed_data(
+                        stores::CREDENTIALS,
+                        &key,
+                        "the key is not a valid credential id",
+                    )
+                })
+            })
+            .collect::<Result<Vec<i64>, _>>()?;
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    async fn read_credential(&self, id: i64) -> Result<StoredCredential, StorageError> {
+        let key = id.to_string();
+        let raw = self
+            .storage
+            .read(stores::CREDENTIALS, &key)
+            .await?
+            .ok_or(StorageError::CredentialNotFound { id })?;
+
+        serde_json::from_slice(&raw).map_err(|err| {
+            wasm_storage::StorageError::malformed_data(stores::CREDENTIALS, &key, err.to_string())
+                .into()
+        })
+    }
+
+    async fn write_credential(
+        &self,
+        id: i64,
+        credential: &StoredCredential,
+    ) -> Result<(), StorageError> {
+        // serializing plain strings and bytes into json can't fail
+        let raw = serde_json::to_vec(credential).map_err(|_| StorageError::InconsistentData)?;
+        Ok(self
+            .storage
+            .write(stores::CREDENTIALS, &id.to_string(), &raw)
+            .await?)
+    }
+}
+
+// parts of the credential that would allow anyone to spend it
+#[derive(Serialize, Deserialize)]
+struct CredentialSecrets {
+    serial_number: String,
+    binding_number: String,
+    signature: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredCredential {
+    voucher_value: String,
+    voucher_info: String,
+    secrets: EncryptedData,
+    consumed: bool,
+    #[serde(default)]
+    expiry_epoch: u64,
+    #[serde(default)]
+    spent_tickets: u64,
+}
 
 pub struct CoconutCredential {
     pub id: i64,
@@ -24,9 +126,13 @@ pub struct CoconutCredential {
     pub serial_number: String,
     pub binding_number: String,
     pub signature: String,
+    // mirrors the sqlite model of the native storage
+    pub expiry_epoch: i64,
+    pub spent_tickets: i64,
 }
 
-#[async_trait]
+// note: browser storage futures are not `Send`, but it doesn't matter in the single-threaded wasm
+#[async_trait(?Send)]
 pub trait Storage: Send + Sync {
     async fn insert_coconut_credential(
         &self,
@@ -35,31 +141,133 @@ pub trait Storage: Send + Sync {
         serial_number: String,
         binding_number: String,
         signature: String,
+        expiry_epoch: u64,
     ) -> Result<(), StorageError>;
 
     async fn get_next_coconut_credential(&self) -> Result<CoconutCredential, StorageError>;
 
     async fn consume_coconut_credential(&self, id: i64) -> Result<(), StorageError>;
+
+    /// Records the ticket with the provided index of the specified divisible credential as spent,
+    /// along with all the tickets with lower indices.
+    async fn consume_coconut_ticket(&self, id: i64, ticket_index: u64) -> Result<(), StorageError>;
 }
 
-#[async_trait]
+#[async_trait(?Send)]
 impl Storage for PersistentStorage {
     async fn insert_coconut_credential(
         &self,
-        _voucher_value: String,
-        _voucher_info: String,
-        _serial_number: String,
-        _binding_number: String,
-        _signature: String,
+        voucher_value: String,
+        voucher_info: String,
+        serial_number: String,
+        binding_number: String,
+        signature: String,
+        expiry_epoch: u64,
     ) -> Result<(), StorageError> {
-        Err(StorageError::WasmNotSupported)
+        let secrets = self.encrypt_secrets(&CredentialSecrets {
+            serial_number,
+            binding_number,
+            signature,
+        })?;
+        let credential = StoredCredential {
+            voucher_value,
+            voucher_info,
+            secrets,
+            consumed: false,
+            expiry_epoch,
+            spent_tickets: 0,
+        };
+        // serializing plain strings and bytes into json can't fail
+        let raw = serde_json::to_vec(&credential).map_err(|_| StorageError::InconsistentData)?;
+
+        // let the storage assign the id (like the autoincrement of the native sqlite storage),
+        // so that concurrent inserts couldn't collide
+        self.storage.insert(stores::CREDENTIALS, &raw).await?;
+        Ok(())
     }
 
     async fn get_next_coconut_credential(&self) -> Result<CoconutCredential, StorageError> {
-        Err(StorageError::WasmNotSupported)
+        for id in self.stored_ids().await? {
+            let credential = self.read_credential(id).await?;
+            if !credential.consumed {
+                let secrets = self.decrypt_secrets(&credential.secrets)?;
+                return Ok(CoconutCredential {
+                    id,
+                    voucher_value: credential.voucher_value,
+                    voucher_info: credential.voucher_info,
+                    serial_number: secrets.serial_number,
+                    binding_number: secrets.binding_number,
+                    signature: secrets.signature,
+                    expiry_epoch: credential.expiry_epoch as i64,
+                    spent_tickets: credential.spent_tickets as i64,
+                });
+            }
+        }
+        Err(StorageError::NoUnusedCredentials)
     }
 
-    async fn consume_coconut_credential(&self, _id: i64) -> Result<(), StorageError> {
-        Err(StorageError::WasmNotSupported)
+    async fn consume_coconut_credential(&self, id: i64) -> Result<(), StorageError> {
+        let mut credential = self.read_credential(id).await?;
+        credential.consumed = true;
+        self.write_credential(id, &credential).await
+    }
+
+    async fn consume_coconut_ticket(&self, id: i64, ticket_index: u64) -> Result<(), StorageError> {
+        let mut credential = self.read_credential(id).await?;
+        credential.spent_tickets = ticket_index + 1;
+        self.write_credential(id, &credential).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+    use wasm_storage::InMemoryStorage;
+
+    const SERIAL_NUMBER: &str = "CuGhRJzAN1bMfrAtG1z1HRwk8GVF5UGHYPmvUfzxtTRh";
+    const BINDING_NUMBER: &str = "9TAPbXpAFGwVqJ5jakwCmsaLFy65QW6zKh7p2W7z9Qn8";
+    const SIGNATURE: &str = "3kZv1ciWQgfD8EjMyeWXMyjkg8m57DbLJf7nUqAtvBvU";
+
+    #[tokio::test]
+    async fn credential_secrets_are_not_stored_in_plaintext() {
+        let storage = Arc::new(InMemoryStorage::new());
+        let cipher =
+            wasm_storage::storage_cipher(storage.as_ref(), b"passphrase", &mut thread_rng())
+                .await
+                .unwrap();
+        let credentials = PersistentStorage::new(storage.clone(), Arc::new(cipher));
+
+        credentials
+            .insert_coconut_credential(
+                "1000".to_string(),
+                "BandwidthVoucher".to_string(),
+                SERIAL_NUMBER.to_string(),
+                BINDING_NUMBER.to_string(),
+                SIGNATURE.to_string(),
+                42,
+            )
+            .await
+            .unwrap();
+
+        let keys = storage.keys(stores::CREDENTIALS).await.unwrap();
+        assert_eq!(keys.len(), 1);
+        let raw = storage
+            .read(stores::CREDENTIALS, &keys[0])
+            .await
+            .unwrap()
+            .unwrap();
+        for secret in [SERIAL_NUMBER, BINDING_NUMBER, SIGNATURE] {
+            assert!(!raw
+                .windows(secret.len())
+                .any(|window| window == secret.as_bytes()));
+        }
+
+        let credential = credentials.get_next_coconut_credential().await.unwrap();
+        assert_eq!(credential.serial_number, SERIAL_NUMBER);
+        assert_eq!(credential.binding_number, BINDING_NUMBER);
+        assert_eq!(credential.signature, SIGNATURE);
+        assert_eq!(credential.voucher_value, "1000");
+        assert_eq!(credential.expiry_epoch, 42);
     }
 }
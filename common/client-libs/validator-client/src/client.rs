@@ -8,18 +8,24 @@ use mixnet_contract_common::mixnode::MixNodeDetails;
 use mixnet_contract_common::MixId;
 use mixnet_contract_common::{GatewayBond, IdentityKeyRef};
 use nym_api_requests::coconut::{
-    BlindSignRequestBody, BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse,
+    BatchVerifyCredentialBody, BatchVerifyCredentialResponse, BlindSignRequestBody,
+    BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse, VerifyRefundBody,
+    VerifyTicketBody,
 };
 use nym_api_requests::models::{
     GatewayCoreStatusResponse, MixnodeCoreStatusResponse, MixnodeStatusResponse,
-    RewardEstimationResponse, StakeSaturationResponse,
+    RewardEstimationResponse, SignedTopology, StakeSaturationResponse,
 };
 
 #[cfg(feature = "nymd-client")]
-use crate::nymd::traits::{DkgQueryClient, MixnetQueryClient, MultisigQueryClient};
+use crate::nymd::traits::{
+    CoconutBandwidthQueryClient, DkgQueryClient, MixnetQueryClient, MultisigQueryClient,
+};
 #[cfg(feature = "nymd-client")]
 use crate::nymd::{self, CosmWasmClient, NymdClient, QueryNymdClient, SigningNymdClient};
 #[cfg(feature = "nymd-client")]
+use coconut_bandwidth_contract_common::spend_credential::SpendCredential;
+#[cfg(feature = "nymd-client")]
 use coconut_dkg_common::{
     dealer::ContractDealing, types::DealerDetails, verification_key::ContractVKShare,
 };
@@ -58,6 +64,7 @@ pub struct Config {
     dealers_page_limit: Option<u32>,
     verification_key_page_limit: Option<u32>,
     proposals_page_limit: Option<u32>,
+    spent_credentials_page_limit: Option<u32>,
 }
 
 #[cfg(feature = "nymd-client")]
@@ -90,6 +97,7 @@ impl Config {
             dealers_page_limit: None,
             verification_key_page_limit: None,
             proposals_page_limit: None,
+            spent_credentials_page_limit: None,
         })
     }
 
@@ -125,6 +133,11 @@ impl Config {
         self.rewarded_set_page_limit = limit;
         self
     }
+
+    pub fn with_spent_credentials_page_limit(mut self, limit: Option<u32>) -> Config {
+        self.spent_credentials_page_limit = limit;
+        self
+    }
 }
 
 #[cfg(feature = "nymd-client")]
@@ -140,6 +153,7 @@ pub struct Client<C> {
     dealers_page_limit: Option<u32>,
     verification_key_page_limit: Option<u32>,
     proposals_page_limit: Option<u32>,
+    spent_credentials_page_limit: Option<u32>,
 
     // ideally they would have been read-only, but unfortunately rust doesn't have such features
     pub nym_api: nym_api::Client,
@@ -169,6 +183,7 @@ impl Client<SigningNymdClient> {
             dealers_page_limit: config.dealers_page_limit,
             verification_key_page_limit: config.verification_key_page_limit,
             proposals_page_limit: config.proposals_page_limit,
+            spent_credentials_page_limit: config.spent_credentials_page_limit,
             nym_api: nym_api_client,
             nymd: nymd_client,
         })
@@ -205,6 +220,7 @@ impl Client<QueryNymdClient> {
             dealers_page_limit: config.dealers_page_limit,
             verification_key_page_limit: config.verification_key_page_limit,
             proposals_page_limit: config.proposals_page_limit,
+            spent_credentials_page_limit: config.spent_credentials_page_limit,
             nym_api: nym_api_client,
             nymd: nymd_client,
         })
@@ -460,6 +476,13 @@ impl<C> Client<C> {
         Ok(gateways)
     }
 
+    pub async fn get_nymd_topology_signers(&self) -> Result<Vec<IdentityKey>, ValidatorClientError>
+    where
+        C: CosmWasmClient + Sync + Send,
+    {
+        Ok(self.nymd.get_topology_signers().await?.signers)
+    }
+
     pub async fn get_all_nymd_single_mixnode_delegations(
         &self,
         mix_id: MixId,
@@ -726,6 +749,63 @@ impl<C> Client<C> {
 
         Ok(proposals)
     }
+
+    pub async fn get_all_nymd_spent_credentials(
+        &self,
+    ) -> Result<Vec<SpendCredential>, ValidatorClientError>
+    where
+        C: CosmWasmClient + Sync + Send,
+    {
+        let mut spent_credentials = Vec::new();
+        let mut start_after = None;
+        loop {
+            let mut paged_response = self
+                .nymd
+                .get_all_spent_credentials_paged(
+                    start_after.take(),
+                    self.spent_credentials_page_limit,
+                )
+                .await?;
+            spent_credentials.append(&mut paged_response.spend_credentials);
+
+            if let Some(start_after_res) = paged_response.start_next_after {
+                start_after = Some(start_after_res)
+            } else {
+                break;
+            }
+        }
+
+        Ok(spent_credentials)
+    }
+
+    /// Retrieves all the credentials spent after the provided spend id, in the order in which they
+    /// got spent, along with the spend id of the last one of them (or the provided one if there
+    /// were no new credentials), so that it could be used for the next retrieval.
+    pub async fn get_nymd_spent_credentials_after(
+        &self,
+        spend_id: Option<u64>,
+    ) -> Result<(Vec<SpendCredential>, Option<u64>), ValidatorClientError>
+    where
+        C: CosmWasmClient + Sync + Send,
+    {
+        let mut spent_credentials = Vec::new();
+        let mut start_after = spend_id;
+        loop {
+            let mut paged_response = self
+                .nymd
+                .get_spent_credentials_after_paged(start_after, self.spent_credentials_page_limit)
+                .await?;
+            spent_credentials.append(&mut paged_response.spend_credentials);
+
+            if let Some(start_after_res) = paged_response.start_next_after {
+                start_after = Some(start_after_res)
+            } else {
+                break;
+            }
+        }
+
+        Ok((spent_credentials, start_after))
+    }
 }
 
 // validator-api wrappers
@@ -849,6 +929,17 @@ impl ApiClient {
         Ok(self.nym_api_client.get_active_mixnodes().await?)
     }
 
+    pub async fn get_signed_topology(&self) -> Result<SignedTopology, ValidatorClientError> {
+        Ok(self.nym_api_client.get_signed_topology().await?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn subscribe_to_topology_updates(
+        &self,
+    ) -> Result<nym_api::TopologyUpdateStream, ValidatorClientError> {
+        Ok(self.nym_api_client.subscribe_to_topology_updates().await?)
+    }
+
     pub async fn get_cached_rewarded_mixnodes(
         &self,
     ) -> Result<Vec<MixNodeDetails>, ValidatorClientError> {
@@ -919,6 +1010,17 @@ impl ApiClient {
         Ok(self.nym_api_client.blind_sign(request_body).await?)
     }
 
+    pub async fn blind_sign_credential(
+        &self,
+        credential_type: &str,
+        request_body: &BlindSignRequestBody,
+    ) -> Result<BlindedSignatureResponse, ValidatorClientError> {
+        Ok(self
+            .nym_api_client
+            .blind_sign_credential(credential_type, request_body)
+            .await?)
+    }
+
     pub async fn partial_bandwidth_credential(
         &self,
         request_body: &str,
@@ -938,4 +1040,31 @@ impl ApiClient {
             .verify_bandwidth_credential(request_body)
             .await?)
     }
+
+    pub async fn batch_verify_bandwidth_credentials(
+        &self,
+        request_body: &BatchVerifyCredentialBody,
+    ) -> Result<BatchVerifyCredentialResponse, ValidatorClientError> {
+        Ok(self
+            .nym_api_client
+            .batch_verify_bandwidth_credentials(request_body)
+            .await?)
+    }
+
+    pub async fn verify_bandwidth_ticket(
+        &self,
+        request_body: &VerifyTicketBody,
+    ) -> Result<VerifyCredentialResponse, ValidatorClientError> {
+        Ok(self
+            .nym_api_client
+            .verify_bandwidth_ticket(request_body)
+            .await?)
+    }
+
+    pub async fn verify_refund(
+        &self,
+        request_body: &VerifyRefundBody,
+    ) -> Result<VerifyCredentialResponse, ValidatorClientError> {
+        Ok(self.nym_api_client.verify_refund(request_body).await?)
+    }
 }
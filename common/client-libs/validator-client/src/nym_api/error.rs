@@ -9,9 +9,18 @@ pub enum NymAPIError {
         source: reqwest::Error,
     },
 
+    #[error("Failed to deserialize the response - {source}")]
+    MalformedResponse {
+        #[from]
+        source: serde_json::Error,
+    },
+
     #[error("Request failed with error message - {0}")]
     GenericRequestFailure(String),
 
+    #[error("The event stream contained a line (or an event) longer than {limit} bytes")]
+    EventStreamLineTooLong { limit: usize },
+
     #[error("The nym API has failed to resolve our request. It returned status code {status} and additional error message: {}", error.message())]
     ApiRequestFailure { status: u16, error: RequestError },
 }
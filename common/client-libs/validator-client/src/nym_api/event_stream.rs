@@ -0,0 +1,160 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Minimal decoder of the server-sent events streams, as described by
+//! <https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation>.
+//! Only the `data` field is of any interest to us, all the other ones are ignored.
+
+use crate::nym_api::error::NymAPIError;
+
+/// Maximum length of a single line, and of the data of a single event, the decoder is going to
+/// buffer. It's generous as the initial topology snapshot is sent as a single line, but it
+/// prevents a misbehaving server from making us buffer an endless line.
+pub(crate) const MAX_LINE_LENGTH: usize = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub(crate) struct EventStreamDecoder {
+    max_line_length: usize,
+
+    // bytes of the incomplete line received so far
+    line_buffer: Vec<u8>,
+
+    // data of the event that hasn't been terminated yet
+    data_buffer: Option<String>,
+}
+
+impl Default for EventStreamDecoder {
+    fn default() -> Self {
+        EventStreamDecoder::new(MAX_LINE_LENGTH)
+    }
+}
+
+impl EventStreamDecoder {
+    pub(crate) fn new(max_line_length: usize) -> Self {
+        EventStreamDecoder {
+            max_line_length,
+            line_buffer: Vec::new(),
+            data_buffer: None,
+        }
+    }
+
+    /// Processes the received chunk of bytes and returns data of all events it has completed.
+    /// It fails if a line or the data of an event exceeds the maximum length, in which case
+    /// the rest of the stream can't be decoded anymore.
+    pub(crate) fn decode(&mut self, chunk: &[u8]) -> Result<Vec<String>, NymAPIError> {
+        let mut events = Vec::new();
+        for byte in chunk {
+            if *byte != b'\n' {
+                if self.line_buffer.len() >= self.max_line_length {
+                    return Err(NymAPIError::EventStreamLineTooLong {
+                        limit: self.max_line_length,
+                    });
+                }
+                self.line_buffer.push(*byte);
+                continue;
+            }
+
+            let mut line = std::mem::take(&mut self.line_buffer);
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+
+            if line.is_empty() {
+                // blank line dispatches the event
+                if let Some(data) = self.data_buffer.take() {
+                    events.push(data)
+                }
+            } else {
+                self.process_line(&String::from_utf8_lossy(&line))?
+            }
+        }
+        Ok(events)
+    }
+
+    fn process_line(&mut self, line: &str) -> Result<(), NymAPIError> {
+        // lines starting with a colon are comments (such as heartbeats)
+        if line.starts_with(':') {
+            return Ok(());
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        if field == "data" {
+            match self.data_buffer.as_mut() {
+                Some(data) => {
+                    // multiline events could otherwise grow without bounds too
+                    if data.len() + 1 + value.len() > self.max_line_length {
+                        return Err(NymAPIError::EventStreamLineTooLong {
+                            limit: self.max_line_length,
+                        });
+                    }
+                    data.push('\n');
+                    data.push_str(value)
+                }
+                None => self.data_buffer = Some(value.to_string()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoding_events_split_between_chunks() {
+        let mut decoder = EventStreamDecoder::default();
+
+        assert!(decoder
+            .decode(b":heartbeat\n\ndata:{\"foo\"")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            decoder.decode(b": 42}\n\ndata: second\r\n\r\n").unwrap(),
+            vec!["{\"foo\": 42}".to_string(), "second".to_string()]
+        );
+    }
+
+    #[test]
+    fn decoding_multiline_events() {
+        let mut decoder = EventStreamDecoder::default();
+
+        assert_eq!(
+            decoder
+                .decode(b"event: update\ndata: first\ndata:second\n\n")
+                .unwrap(),
+            vec!["first\nsecond".to_string()]
+        );
+    }
+
+    #[test]
+    fn overly_long_lines_are_rejected() {
+        let mut decoder = EventStreamDecoder::new(16);
+
+        assert_eq!(
+            decoder.decode(b"data: 0123456789\n\n").unwrap(),
+            vec!["0123456789".to_string()]
+        );
+
+        // the line is rejected even if it's split between multiple chunks
+        assert!(decoder.decode(b"data: 0123456").unwrap().is_empty());
+        assert!(matches!(
+            decoder.decode(b"789abcdef\n\n"),
+            Err(NymAPIError::EventStreamLineTooLong { limit: 16 })
+        ));
+    }
+
+    #[test]
+    fn overly_long_multiline_events_are_rejected() {
+        let mut decoder = EventStreamDecoder::new(16);
+
+        assert!(matches!(
+            decoder.decode(b"data: 01234567\ndata: 89abcdef\n\n"),
+            Err(NymAPIError::EventStreamLineTooLong { limit: 16 })
+        ));
+    }
+}
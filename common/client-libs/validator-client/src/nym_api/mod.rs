@@ -2,24 +2,36 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::nym_api::error::NymAPIError;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::nym_api::event_stream::EventStreamDecoder;
 use crate::nym_api::routes::{CORE_STATUS_COUNT, SINCE_ARG};
+#[cfg(not(target_arch = "wasm32"))]
+use futures::{Stream, StreamExt};
 use mixnet_contract_common::mixnode::MixNodeDetails;
 use mixnet_contract_common::{GatewayBond, IdentityKeyRef, MixId};
 use nym_api_requests::coconut::{
-    BlindSignRequestBody, BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse,
+    BatchVerifyCredentialBody, BatchVerifyCredentialResponse, BlindSignRequestBody,
+    BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse, VerifyRefundBody,
+    VerifyTicketBody,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use nym_api_requests::models::TopologyUpdate;
 use nym_api_requests::models::{
     ComputeRewardEstParam, GatewayCoreStatusResponse, GatewayStatusReportResponse,
     GatewayUptimeHistoryResponse, InclusionProbabilityResponse, MixNodeBondAnnotated,
-    MixnodeCoreStatusResponse, MixnodeStatusReportResponse, MixnodeStatusResponse,
-    MixnodeUptimeHistoryResponse, RequestError, RewardEstimationResponse, StakeSaturationResponse,
-    UptimeResponse,
+    MixnodeCoreStatusResponse, MixnodeLatencyResponse, MixnodeStatusReportResponse,
+    MixnodeStatusResponse, MixnodeUptimeHistoryResponse, RequestError, RewardEstimationResponse,
+    SignedTopology, StakeSaturationResponse, UptimeResponse,
 };
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::pin::Pin;
 use url::Url;
 
 pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
+mod event_stream;
 pub mod routes;
 
 type PathSegments<'a> = &'a [&'a str];
@@ -27,6 +39,11 @@ type Params<'a, K, V> = &'a [(K, V)];
 
 const NO_PARAMS: Params<'_, &'_ str, &'_ str> = &[];
 
+/// Stream of the network topology updates published by a nym-api.
+#[cfg(not(target_arch = "wasm32"))]
+pub type TopologyUpdateStream =
+    Pin<Box<dyn Stream<Item = Result<TopologyUpdate, NymAPIError>> + Send>>;
+
 #[derive(Clone)]
 pub struct Client {
     url: Url,
@@ -157,6 +174,57 @@ impl Client {
         .await
     }
 
+    pub async fn get_signed_topology(&self) -> Result<SignedTopology, NymAPIError> {
+        self.query_nym_api(
+            &[routes::API_VERSION, routes::TOPOLOGY, routes::SIGNED],
+            NO_PARAMS,
+        )
+        .await
+    }
+
+    /// Subscribes to the stream of network topology updates, which starts with the snapshot
+    /// of the current topology followed by the diffs of any subsequent changes.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn subscribe_to_topology_updates(&self) -> Result<TopologyUpdateStream, NymAPIError> {
+        let res = self
+            .send_get_request(
+                &[routes::API_VERSION, routes::TOPOLOGY, routes::STREAM],
+                NO_PARAMS,
+            )
+            .await?;
+        if !res.status().is_success() {
+            return Err(NymAPIError::GenericRequestFailure(res.text().await?));
+        }
+
+        // the decoder is dropped once it fails, which terminates the stream right after the error
+        Ok(res
+            .bytes_stream()
+            .scan(Some(EventStreamDecoder::default()), |decoder, chunk| {
+                let Some(active_decoder) = decoder.as_mut() else {
+                    return futures::future::ready(None);
+                };
+                let updates = match chunk.map_err(NymAPIError::from) {
+                    Ok(chunk) => match active_decoder.decode(&chunk) {
+                        Ok(events) => events
+                            .into_iter()
+                            .map(|data| {
+                                serde_json::from_str::<TopologyUpdate>(&data)
+                                    .map_err(NymAPIError::from)
+                            })
+                            .collect(),
+                        Err(err) => {
+                            *decoder = None;
+                            vec![Err(err)]
+                        }
+                    },
+                    Err(err) => vec![Err(err)],
+                };
+                futures::future::ready(Some(updates))
+            })
+            .flat_map(futures::stream::iter)
+            .boxed())
+    }
+
     pub async fn get_active_mixnodes_detailed(
         &self,
     ) -> Result<Vec<MixNodeBondAnnotated>, NymAPIError> {
@@ -198,6 +266,23 @@ impl Client {
         .await
     }
 
+    pub async fn get_mixnode_latency(
+        &self,
+        mix_id: MixId,
+    ) -> Result<MixnodeLatencyResponse, NymAPIError> {
+        self.query_nym_api(
+            &[
+                routes::API_VERSION,
+                routes::STATUS,
+                routes::MIXNODE,
+                &mix_id.to_string(),
+                routes::LATENCY,
+            ],
+            NO_PARAMS,
+        )
+        .await
+    }
+
     pub async fn get_gateway_report(
         &self,
         identity: IdentityKeyRef<'_>,
@@ -449,6 +534,25 @@ impl Client {
         .await
     }
 
+    pub async fn blind_sign_credential(
+        &self,
+        credential_type: &str,
+        request_body: &BlindSignRequestBody,
+    ) -> Result<BlindedSignatureResponse, NymAPIError> {
+        self.post_nym_api(
+            &[
+                routes::API_VERSION,
+                routes::COCONUT_ROUTES,
+                routes::COCONUT_CREDENTIALS,
+                credential_type,
+                routes::COCONUT_BLIND_SIGN,
+            ],
+            NO_PARAMS,
+            request_body,
+        )
+        .await
+    }
+
     pub async fn partial_bandwidth_credential(
         &self,
         request_body: &str,
@@ -482,6 +586,57 @@ impl Client {
         )
         .await
     }
+
+    pub async fn batch_verify_bandwidth_credentials(
+        &self,
+        request_body: &BatchVerifyCredentialBody,
+    ) -> Result<BatchVerifyCredentialResponse, NymAPIError> {
+        self.post_nym_api(
+            &[
+                routes::API_VERSION,
+                routes::COCONUT_ROUTES,
+                routes::BANDWIDTH,
+                routes::COCONUT_BATCH_VERIFY_BANDWIDTH_CREDENTIALS,
+            ],
+            NO_PARAMS,
+            request_body,
+        )
+        .await
+    }
+
+    pub async fn verify_bandwidth_ticket(
+        &self,
+        request_body: &VerifyTicketBody,
+    ) -> Result<VerifyCredentialResponse, NymAPIError> {
+        self.post_nym_api(
+            &[
+                routes::API_VERSION,
+                routes::COCONUT_ROUTES,
+                routes::BANDWIDTH,
+                routes::COCONUT_VERIFY_BANDWIDTH_TICKET,
+            ],
+            NO_PARAMS,
+            request_body,
+        )
+        .await
+    }
+
+    pub async fn verify_refund(
+        &self,
+        request_body: &VerifyRefundBody,
+    ) -> Result<VerifyCredentialResponse, NymAPIError> {
+        self.post_nym_api(
+            &[
+                routes::API_VERSION,
+                routes::COCONUT_ROUTES,
+                routes::BANDWIDTH,
+                routes::COCONUT_VERIFY_REFUND,
+            ],
+            NO_PARAMS,
+            request_body,
+        )
+        .await
+    }
 }
 
 // utility function that should solve the double slash problem in validator API forever.
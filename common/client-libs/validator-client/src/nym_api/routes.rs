@@ -16,6 +16,13 @@ pub const BANDWIDTH: &str = "bandwidth";
 pub const COCONUT_BLIND_SIGN: &str = "blind-sign";
 pub const COCONUT_PARTIAL_BANDWIDTH_CREDENTIAL: &str = "partial-bandwidth-credential";
 pub const COCONUT_VERIFY_BANDWIDTH_CREDENTIAL: &str = "verify-bandwidth-credential";
+pub const COCONUT_BATCH_VERIFY_BANDWIDTH_CREDENTIALS: &str = "batch-verify-bandwidth-credentials";
+pub const COCONUT_VERIFY_BANDWIDTH_TICKET: &str = "verify-bandwidth-ticket";
+pub const COCONUT_VERIFY_REFUND: &str = "verify-refund";
+pub const COCONUT_ADMIN: &str = "admin";
+pub const COCONUT_CREDENTIALS: &str = "credentials";
+pub const COCONUT_ISSUANCE_TOTALS: &str = "issuance-totals";
+pub const COCONUT_ISSUANCE_LOG: &str = "issuance-log";
 
 pub const STATUS_ROUTES: &str = "status";
 pub const MIXNODE: &str = "mixnode";
@@ -27,8 +34,13 @@ pub const SINCE_ARG: &str = "since";
 pub const STATUS: &str = "status";
 pub const REPORT: &str = "report";
 pub const HISTORY: &str = "history";
+pub const LATENCY: &str = "latency";
 pub const REWARD_ESTIMATION: &str = "reward-estimation";
 pub const COMPUTE_REWARD_ESTIMATION: &str = "compute-reward-estimation";
 pub const AVG_UPTIME: &str = "avg_uptime";
 pub const STAKE_SATURATION: &str = "stake-saturation";
 pub const INCLUSION_CHANCE: &str = "inclusion-probability";
+
+pub const TOPOLOGY: &str = "topology";
+pub const STREAM: &str = "stream";
+pub const SIGNED: &str = "signed";
@@ -5,7 +5,9 @@ use crate::nymd::error::NymdError;
 use crate::nymd::{CosmWasmClient, NymdClient};
 
 use coconut_bandwidth_contract_common::msg::QueryMsg;
-use coconut_bandwidth_contract_common::spend_credential::SpendCredentialResponse;
+use coconut_bandwidth_contract_common::spend_credential::{
+    PagedSpendCredentialIdResponse, PagedSpendCredentialResponse, SpendCredentialResponse,
+};
 
 use async_trait::async_trait;
 
@@ -15,6 +17,16 @@ pub trait CoconutBandwidthQueryClient {
         &self,
         blinded_serial_number: String,
     ) -> Result<SpendCredentialResponse, NymdError>;
+    async fn get_all_spent_credentials_paged(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<PagedSpendCredentialResponse, NymdError>;
+    async fn get_spent_credentials_after_paged(
+        &self,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<PagedSpendCredentialIdResponse, NymdError>;
 }
 
 #[async_trait]
@@ -30,4 +42,24 @@ impl<C: CosmWasmClient + Sync + Send> CoconutBandwidthQueryClient for NymdClient
             .query_contract_smart(self.coconut_bandwidth_contract_address(), &request)
             .await
     }
+    async fn get_all_spent_credentials_paged(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<PagedSpendCredentialResponse, NymdError> {
+        let request = QueryMsg::GetAllSpentCredentials { limit, start_after };
+        self.client
+            .query_contract_smart(self.coconut_bandwidth_contract_address(), &request)
+            .await
+    }
+    async fn get_spent_credentials_after_paged(
+        &self,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<PagedSpendCredentialIdResponse, NymdError> {
+        let request = QueryMsg::GetSpentCredentialsAfter { limit, start_after };
+        self.client
+            .query_contract_smart(self.coconut_bandwidth_contract_address(), &request)
+            .await
+    }
 }
@@ -5,6 +5,7 @@ pub use crate::nymd::cosmwasm_client::signing_client::SigningCosmWasmClient;
 use crate::nymd::cosmwasm_client::types::ExecuteResult;
 use crate::nymd::error::NymdError;
 use crate::nymd::{Coin, Fee, NymdClient};
+use coconut_bandwidth_contract_common::refund::RefundCredentialData;
 use coconut_bandwidth_contract_common::spend_credential::SpendCredentialData;
 use coconut_bandwidth_contract_common::{deposit::DepositData, msg::ExecuteMsg};
 
@@ -25,6 +26,29 @@ pub trait CoconutBandwidthSigningClient {
         funds: Coin,
         blinded_serial_number: String,
         gateway_cosmos_address: String,
+        expiry_epoch: u64,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+    /// Spends multiple credentials within a single transaction. Each credential is described by
+    /// its value, its blinded serial number and its expiry epoch.
+    async fn spend_credentials(
+        &self,
+        credentials: Vec<(Coin, String, u64)>,
+        gateway_cosmos_address: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+    /// Removes the spent credentials that can no longer be redeemed.
+    async fn prune_spent_credentials(
+        &self,
+        limit: Option<u32>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError>;
+    /// Requests the refund of a credential that expired without being spent to the sender.
+    async fn refund_credential(
+        &self,
+        funds: Coin,
+        blinded_serial_number: String,
+        expiry_epoch: u64,
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NymdError>;
 }
@@ -59,6 +83,7 @@ impl<C: SigningCosmWasmClient + Sync + Send> CoconutBandwidthSigningClient for N
         funds: Coin,
         blinded_serial_number: String,
         gateway_cosmos_address: String,
+        expiry_epoch: u64,
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NymdError> {
         let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
@@ -67,6 +92,7 @@ impl<C: SigningCosmWasmClient + Sync + Send> CoconutBandwidthSigningClient for N
                 funds.into(),
                 blinded_serial_number,
                 gateway_cosmos_address,
+                expiry_epoch,
             ),
         };
         self.client
@@ -80,4 +106,79 @@ impl<C: SigningCosmWasmClient + Sync + Send> CoconutBandwidthSigningClient for N
             )
             .await
     }
+    async fn spend_credentials(
+        &self,
+        credentials: Vec<(Coin, String, u64)>,
+        gateway_cosmos_address: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let reqs = credentials
+            .into_iter()
+            .map(|(funds, blinded_serial_number, expiry_epoch)| {
+                (
+                    ExecuteMsg::SpendCredential {
+                        data: SpendCredentialData::new(
+                            funds.into(),
+                            blinded_serial_number,
+                            gateway_cosmos_address.clone(),
+                            expiry_epoch,
+                        ),
+                    },
+                    vec![],
+                )
+            })
+            .collect::<Vec<_>>();
+        self.client
+            .execute_multiple(
+                self.address(),
+                self.coconut_bandwidth_contract_address(),
+                reqs,
+                fee,
+                "CoconutBandwidth::SpendCredentials",
+            )
+            .await
+    }
+
+    async fn prune_spent_credentials(
+        &self,
+        limit: Option<u32>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::PruneSpentCredentials { limit };
+        self.client
+            .execute(
+                self.address(),
+                self.coconut_bandwidth_contract_address(),
+                &req,
+                fee,
+                "CoconutBandwidth::PruneSpentCredentials",
+                vec![],
+            )
+            .await
+    }
+
+    async fn refund_credential(
+        &self,
+        funds: Coin,
+        blinded_serial_number: String,
+        expiry_epoch: u64,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = ExecuteMsg::RefundCredential {
+            data: RefundCredentialData::new(funds.into(), blinded_serial_number, expiry_epoch),
+        };
+        self.client
+            .execute(
+                self.address(),
+                self.coconut_bandwidth_contract_address(),
+                &req,
+                fee,
+                "CoconutBandwidth::RefundCredential",
+                vec![],
+            )
+            .await
+    }
 }
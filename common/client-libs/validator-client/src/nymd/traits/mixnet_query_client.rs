@@ -12,6 +12,10 @@ use mixnet_contract_common::mixnode::{
     MixNodeDetails, MixnodeRewardingDetailsResponse, PagedMixnodesDetailsResponse,
     PagedUnbondedMixnodesResponse, StakeSaturationResponse, UnbondedMixnodeResponse,
 };
+use mixnet_contract_common::penalties::{
+    EvidenceId, EvidenceRecord, PagedEvidenceResponse, PagedExcludedMixnodesResponse,
+    PenaltyMonitorsResponse,
+};
 use mixnet_contract_common::reward_params::{Performance, RewardingParams};
 use mixnet_contract_common::rewarding::{
     EstimatedCurrentEpochRewardResponse, PendingRewardResponse,
@@ -19,11 +23,13 @@ use mixnet_contract_common::rewarding::{
 use mixnet_contract_common::{
     delegation, ContractBuildInformation, ContractState, ContractStateParams,
     CurrentIntervalResponse, EpochEventId, GatewayBondResponse, GatewayOwnershipResponse,
-    IdentityKey, IntervalEventId, LayerDistribution, MixId, MixOwnershipResponse,
-    MixnodeDetailsResponse, PagedAllDelegationsResponse, PagedDelegatorDelegationsResponse,
-    PagedFamiliesResponse, PagedGatewayResponse, PagedMembersResponse,
-    PagedMixNodeDelegationsResponse, PagedMixnodeBondsResponse, PagedRewardedSetResponse,
-    PendingEpochEventsResponse, PendingIntervalEventsResponse, QueryMsg as MixnetQueryMsg,
+    GatewayRewardingDetailsResponse, IdentityKey, IntervalEventId, LayerDistribution, MixId,
+    MixOwnershipResponse, MixnodeDetailsResponse, PagedAllDelegationsResponse,
+    PagedDelegatorDelegationsResponse, PagedDelegatorGatewayDelegationsResponse,
+    PagedFamiliesResponse, PagedGatewayDelegationsResponse, PagedGatewayResponse,
+    PagedMembersResponse, PagedMixNodeDelegationsResponse, PagedMixnodeBondsResponse,
+    PagedRewardedSetResponse, PendingEpochEventsResponse, PendingIntervalEventsResponse,
+    QueryMsg as MixnetQueryMsg, TopologySignersResponse,
 };
 use serde::Deserialize;
 
@@ -55,6 +61,11 @@ pub trait MixnetQueryClient {
             .await
     }
 
+    async fn get_topology_signers(&self) -> Result<TopologySignersResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetTopologySigners {})
+            .await
+    }
+
     async fn get_rewarding_parameters(&self) -> Result<RewardingParams, NymdError> {
         self.query_mixnet_contract(MixnetQueryMsg::GetRewardingParams {})
             .await
@@ -259,6 +270,36 @@ pub trait MixnetQueryClient {
         .await
     }
 
+    /// Gets list of all delegations towards particular gateway.
+    async fn get_gateway_delegations_paged(
+        &self,
+        gateway_identity: IdentityKey,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<PagedGatewayDelegationsResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetGatewayDelegations {
+            gateway_identity,
+            start_after,
+            limit,
+        })
+        .await
+    }
+
+    /// Gets list of all the gateways to which a particular address delegated.
+    async fn get_delegator_gateway_delegations_paged(
+        &self,
+        delegator: String,
+        start_after: Option<IdentityKey>,
+        limit: Option<u32>,
+    ) -> Result<PagedDelegatorGatewayDelegationsResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetDelegatorGatewayDelegations {
+            delegator,
+            start_after,
+            limit,
+        })
+        .await
+    }
+
     /// Checks value of delegation of given client towards particular mixnode.
     async fn get_delegation_details(
         &self,
@@ -347,6 +388,36 @@ pub trait MixnetQueryClient {
         .await
     }
 
+    async fn get_gateway_rewarding_details(
+        &self,
+        gateway_identity: IdentityKey,
+    ) -> Result<GatewayRewardingDetailsResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetGatewayRewardingDetails { gateway_identity })
+            .await
+    }
+
+    async fn get_pending_gateway_operator_reward(
+        &self,
+        gateway_identity: IdentityKey,
+    ) -> Result<PendingRewardResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetPendingGatewayOperatorReward {
+            gateway_identity,
+        })
+        .await
+    }
+
+    async fn get_pending_gateway_delegator_reward(
+        &self,
+        delegator: &AccountId,
+        gateway_identity: IdentityKey,
+    ) -> Result<PendingRewardResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetPendingGatewayDelegatorReward {
+            address: delegator.to_string(),
+            gateway_identity,
+        })
+        .await
+    }
+
     // interval-related
 
     async fn get_pending_epoch_events_paged(
@@ -390,6 +461,42 @@ pub trait MixnetQueryClient {
         })
         .await
     }
+
+    // penalty-related
+
+    async fn get_penalty_monitors(&self) -> Result<PenaltyMonitorsResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetPenaltyMonitors {})
+            .await
+    }
+
+    async fn get_misbehaviour_evidence(
+        &self,
+        evidence_id: EvidenceId,
+    ) -> Result<Option<EvidenceRecord>, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetMisbehaviourEvidence { evidence_id })
+            .await
+    }
+
+    async fn get_misbehaviour_evidence_paged(
+        &self,
+        start_after: Option<EvidenceId>,
+        limit: Option<u32>,
+    ) -> Result<PagedEvidenceResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetMisbehaviourEvidencePaged {
+            start_after,
+            limit,
+        })
+        .await
+    }
+
+    async fn get_excluded_mixnodes_paged(
+        &self,
+        start_after: Option<MixId>,
+        limit: Option<u32>,
+    ) -> Result<PagedExcludedMixnodesResponse, NymdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetExcludedMixnodes { start_after, limit })
+            .await
+    }
 }
 
 #[async_trait]
@@ -9,9 +9,11 @@ use crate::nymd::{Fee, NymdClient, SigningCosmWasmClient};
 use async_trait::async_trait;
 use cosmrs::AccountId;
 use mixnet_contract_common::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
+use mixnet_contract_common::penalties::{EvidenceId, MisbehaviourEvidence, Penalty};
 use mixnet_contract_common::reward_params::{IntervalRewardingParamsUpdate, Performance};
 use mixnet_contract_common::{
-    ContractStateParams, ExecuteMsg as MixnetExecuteMsg, Gateway, LayerAssignment, MixId, MixNode,
+    ContractStateParams, ExecuteMsg as MixnetExecuteMsg, Gateway, GatewayConfigUpdate, IdentityKey,
+    LayerAssignment, MixId, MixNode,
 };
 
 #[async_trait]
@@ -53,6 +55,32 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn add_topology_signer(
+        &self,
+        identity_key: IdentityKey,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::AddTopologySigner { identity_key },
+            vec![],
+        )
+        .await
+    }
+
+    async fn remove_topology_signer(
+        &self,
+        identity_key: IdentityKey,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RemoveTopologySigner { identity_key },
+            vec![],
+        )
+        .await
+    }
+
     async fn update_active_set_size(
         &self,
         active_set_size: u32,
@@ -490,6 +518,97 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn pledge_more_gateway(
+        &self,
+        additional_pledge: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::PledgeMoreGateway {},
+            vec![additional_pledge],
+        )
+        .await
+    }
+
+    async fn pledge_more_gateway_on_behalf(
+        &self,
+        owner: AccountId,
+        additional_pledge: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::PledgeMoreGatewayOnBehalf {
+                owner: owner.to_string(),
+            },
+            vec![additional_pledge],
+        )
+        .await
+    }
+
+    async fn decrease_gateway_pledge(
+        &self,
+        decrease_by: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::DecreaseGatewayPledge {
+                decrease_by: decrease_by.into(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn decrease_gateway_pledge_on_behalf(
+        &self,
+        owner: AccountId,
+        decrease_by: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::DecreaseGatewayPledgeOnBehalf {
+                owner: owner.to_string(),
+                decrease_by: decrease_by.into(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateGatewayConfig { new_config },
+            vec![],
+        )
+        .await
+    }
+
+    async fn update_gateway_config_on_behalf(
+        &self,
+        owner: AccountId,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateGatewayConfigOnBehalf {
+                new_config,
+                owner: owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
     // delegation-related:
 
     async fn delegate_to_mixnode(
@@ -554,6 +673,73 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn redelegate_from_mixnode(
+        &self,
+        from: MixId,
+        to: MixId,
+        amount: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RedelegateFromMixnode {
+                from,
+                to,
+                amount: amount.into(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn redelegate_from_mixnode_on_behalf(
+        &self,
+        delegate: AccountId,
+        from: MixId,
+        to: MixId,
+        amount: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RedelegateFromMixnodeOnBehalf {
+                from,
+                to,
+                amount: amount.into(),
+                delegate: delegate.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn delegate_to_gateway(
+        &self,
+        gateway_identity: IdentityKey,
+        amount: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::DelegateToGateway { gateway_identity },
+            vec![amount],
+        )
+        .await
+    }
+
+    async fn undelegate_from_gateway(
+        &self,
+        gateway_identity: IdentityKey,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UndelegateFromGateway { gateway_identity },
+            vec![],
+        )
+        .await
+    }
+
     // reward-related
 
     async fn reward_mixnode(
@@ -573,6 +759,23 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn reward_gateway(
+        &self,
+        gateway_identity: IdentityKey,
+        performance: Performance,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RewardGateway {
+                gateway_identity,
+                performance,
+            },
+            vec![],
+        )
+        .await
+    }
+
     async fn withdraw_operator_reward(&self, fee: Option<Fee>) -> Result<ExecuteResult, NymdError> {
         self.execute_mixnet_contract(fee, MixnetExecuteMsg::WithdrawOperatorReward {}, vec![])
             .await
@@ -622,6 +825,92 @@ pub trait MixnetSigningClient {
         )
         .await
     }
+
+    // penalty-related
+
+    async fn add_penalty_monitor(
+        &self,
+        identity_key: IdentityKey,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::AddPenaltyMonitor { identity_key },
+            vec![],
+        )
+        .await
+    }
+
+    async fn remove_penalty_monitor(
+        &self,
+        identity_key: IdentityKey,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RemovePenaltyMonitor { identity_key },
+            vec![],
+        )
+        .await
+    }
+
+    async fn submit_misbehaviour_evidence(
+        &self,
+        evidence: MisbehaviourEvidence,
+        monitor_identity: IdentityKey,
+        signature: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::SubmitMisbehaviourEvidence {
+                evidence,
+                monitor_identity,
+                signature,
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn apply_penalty(
+        &self,
+        evidence_id: EvidenceId,
+        penalty: Penalty,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::ApplyPenalty {
+                evidence_id,
+                penalty,
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn dismiss_evidence(
+        &self,
+        evidence_id: EvidenceId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::DismissEvidence { evidence_id },
+            vec![],
+        )
+        .await
+    }
+
+    async fn release_from_jail(
+        &self,
+        mix_id: MixId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NymdError> {
+        self.execute_mixnet_contract(fee, MixnetExecuteMsg::ReleaseFromJail { mix_id }, vec![])
+            .await
+    }
 }
 
 #[async_trait]
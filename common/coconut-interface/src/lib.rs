@@ -5,11 +5,44 @@ pub mod error;
 
 use getset::{CopyGetters, Getters};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use error::CoconutInterfaceError;
 
 pub use nymcoconut::*;
 
+/// Version of the serialization format of the credentials. Legacy credentials, issued before
+/// the expiry epoch became one of their attributes, are serialized without the version byte
+/// (their first byte, the most significant byte of `n_params`, is always 0).
+pub const CREDENTIAL_FORMAT_VERSION: u8 = 1;
+
+/// Number of attributes of the legacy credentials, i.e. the ones without the expiry epoch.
+pub const LEGACY_CREDENTIAL_ATTRIBUTES: u32 = 4;
+
+/// Checks whether the voucher value can be split into `2^index_bits` tickets of equal value,
+/// without any remainder that would be lost when spending them.
+pub fn is_divisible_value(voucher_value: u64, index_bits: u8) -> bool {
+    match 1u64.checked_shl(index_bits as u32) {
+        Some(tickets) => voucher_value % tickets == 0,
+        None => false,
+    }
+}
+
+fn voucher_public_attributes(
+    voucher_value: u64,
+    voucher_info: &str,
+    expiry_epoch: u64,
+) -> Vec<Attribute> {
+    vec![
+        voucher_value.to_string().as_bytes(),
+        voucher_info.as_bytes(),
+        expiry_epoch.to_string().as_bytes(),
+    ]
+    .iter()
+    .map(hash_to_scalar)
+    .collect()
+}
+
 #[derive(Debug, Serialize, Deserialize, Getters, CopyGetters, Clone, PartialEq, Eq)]
 pub struct Credential {
     #[getset(get = "pub")]
@@ -18,6 +51,7 @@ pub struct Credential {
     theta: Theta,
     voucher_value: u64,
     voucher_info: String,
+    expiry_epoch: u64,
 }
 impl Credential {
     pub fn new(
@@ -25,12 +59,20 @@ impl Credential {
         theta: Theta,
         voucher_value: u64,
         voucher_info: String,
+        expiry_epoch: u64,
     ) -> Credential {
+        // legacy credentials do not carry the expiry
+        let expiry_epoch = if n_params == LEGACY_CREDENTIAL_ATTRIBUTES {
+            0
+        } else {
+            expiry_epoch
+        };
         Credential {
             n_params,
             theta,
             voucher_value,
             voucher_info,
+            expiry_epoch,
         }
     }
 
@@ -51,38 +93,122 @@ impl Credential {
         self.voucher_value
     }
 
+    /// Type of the credential, e.g. `BandwidthVoucher`.
+    pub fn voucher_info(&self) -> &str {
+        &self.voucher_info
+    }
+
+    /// Epoch after which the credential can no longer be spent.
+    /// It is always 0 for the legacy credentials, which have been issued without it.
+    pub fn expiry_epoch(&self) -> u64 {
+        self.expiry_epoch
+    }
+
+    /// Checks whether the credential has been issued before the expiry epoch became one of its attributes.
+    pub fn is_legacy(&self) -> bool {
+        self.n_params == LEGACY_CREDENTIAL_ATTRIBUTES
+    }
+
+    fn public_attributes(&self) -> Vec<Attribute> {
+        let mut public_attributes =
+            voucher_public_attributes(self.voucher_value, &self.voucher_info, self.expiry_epoch);
+        if self.is_legacy() {
+            public_attributes.pop();
+        }
+        public_attributes
+    }
+
     pub fn verify(&self, verification_key: &VerificationKey) -> bool {
         let params = Parameters::new(self.n_params).unwrap();
-        let public_attributes = vec![
-            self.voucher_value.to_string().as_bytes(),
-            self.voucher_info.as_bytes(),
-        ]
-        .iter()
-        .map(hash_to_scalar)
-        .collect::<Vec<Attribute>>();
+        let public_attributes = self.public_attributes();
         nymcoconut::verify_credential(&params, verification_key, &self.theta, &public_attributes)
     }
 
+    /// Verifies all the provided credentials against the same verification key, sharing
+    /// the pairing computation between them.
+    /// Returns the verification result of each credential, in the order they were provided.
+    pub fn batch_verify(
+        credentials: &[Credential],
+        verification_key: &VerificationKey,
+    ) -> Vec<bool> {
+        let mut results = vec![false; credentials.len()];
+
+        // credentials can only be batched under the same parameters
+        let mut by_params: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, credential) in credentials.iter().enumerate() {
+            by_params.entry(credential.n_params).or_default().push(i);
+        }
+
+        for (n_params, indices) in by_params {
+            let params = match Parameters::new(n_params) {
+                Ok(params) => params,
+                Err(_) => continue,
+            };
+            let public_attributes: Vec<Vec<Attribute>> = indices
+                .iter()
+                .map(|&i| credentials[i].public_attributes())
+                .collect();
+            let batch: Vec<(&Theta, &[Attribute])> = indices
+                .iter()
+                .zip(public_attributes.iter())
+                .map(|(&i, attributes)| (&credentials[i].theta, attributes.as_slice()))
+                .collect();
+
+            let batch_results =
+                nymcoconut::batch_verify_credentials(&params, verification_key, &batch);
+            for (i, valid) in indices.into_iter().zip(batch_results) {
+                results[i] = valid;
+            }
+        }
+
+        results
+    }
+
+    /// Serializes the credential. Legacy credentials keep their original layout,
+    /// so that they could still be parsed by the gateways that do not know about the expiry.
     pub fn as_bytes(&self) -> Vec<u8> {
         let n_params_bytes = self.n_params.to_be_bytes();
         let theta_bytes = self.theta.to_bytes();
         let theta_bytes_len = theta_bytes.len();
         let voucher_value_bytes = self.voucher_value.to_be_bytes();
+        let expiry_epoch_bytes = self.expiry_epoch.to_be_bytes();
         let voucher_info_bytes = self.voucher_info.as_bytes();
         let voucher_info_len = voucher_info_bytes.len();
 
-        let mut bytes = Vec::with_capacity(28 + theta_bytes_len + voucher_info_len);
+        let mut bytes = Vec::with_capacity(29 + theta_bytes_len + voucher_info_len);
+        if !self.is_legacy() {
+            bytes.push(CREDENTIAL_FORMAT_VERSION);
+        }
         bytes.extend_from_slice(&n_params_bytes);
         bytes.extend_from_slice(&(theta_bytes_len as u64).to_be_bytes());
         bytes.extend_from_slice(&theta_bytes);
         bytes.extend_from_slice(&voucher_value_bytes);
+        if !self.is_legacy() {
+            bytes.extend_from_slice(&expiry_epoch_bytes);
+        }
         bytes.extend_from_slice(voucher_info_bytes);
 
         bytes
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, CoconutError> {
-        if bytes.len() < 28 {
+        let (legacy, bytes) = match bytes.first() {
+            Some(0) => (true, bytes),
+            Some(&CREDENTIAL_FORMAT_VERSION) => (false, &bytes[1..]),
+            Some(version) => {
+                return Err(CoconutError::Deserialization(format!(
+                    "Unsupported credential format version {version}"
+                )))
+            }
+            None => {
+                return Err(CoconutError::Deserialization(String::from(
+                    "To few bytes in credential",
+                )))
+            }
+        };
+        let expiry_epoch_len = if legacy { 0 } else { 8 };
+
+        if bytes.len() < 20 + expiry_epoch_len {
             return Err(CoconutError::Deserialization(String::from(
                 "To few bytes in credential",
             )));
@@ -92,18 +218,30 @@ impl Credential {
 
         four_byte.copy_from_slice(&bytes[..4]);
         let n_params = u32::from_be_bytes(four_byte);
-        eight_byte.copy_from_slice(&bytes[4..12]);
-        let theta_len = u64::from_be_bytes(eight_byte);
-        if bytes.len() < 28 + theta_len as usize {
-            return Err(CoconutError::Deserialization(String::from(
-                "To few bytes in credential",
+        if legacy != (n_params == LEGACY_CREDENTIAL_ATTRIBUTES) {
+            return Err(CoconutError::Deserialization(format!(
+                "Unexpected number of attributes ({n_params}) in credential"
             )));
         }
-        let theta = Theta::from_bytes(&bytes[12..12 + theta_len as usize])
+        eight_byte.copy_from_slice(&bytes[4..12]);
+        let theta_len = u64::from_be_bytes(eight_byte) as usize;
+        let voucher_info_start = theta_len
+            .checked_add(20 + expiry_epoch_len)
+            .filter(|&start| start <= bytes.len())
+            .ok_or_else(|| {
+                CoconutError::Deserialization(String::from("To few bytes in credential"))
+            })?;
+        let theta = Theta::from_bytes(&bytes[12..12 + theta_len])
             .map_err(|e| CoconutError::Deserialization(e.to_string()))?;
-        eight_byte.copy_from_slice(&bytes[12 + theta_len as usize..20 + theta_len as usize]);
+        eight_byte.copy_from_slice(&bytes[12 + theta_len..20 + theta_len]);
         let voucher_value = u64::from_be_bytes(eight_byte);
-        let voucher_info = String::from_utf8(bytes[20 + theta_len as usize..].to_vec())
+        let expiry_epoch = if legacy {
+            0
+        } else {
+            eight_byte.copy_from_slice(&bytes[20 + theta_len..28 + theta_len]);
+            u64::from_be_bytes(eight_byte)
+        };
+        let voucher_info = String::from_utf8(bytes[voucher_info_start..].to_vec())
             .map_err(|e| CoconutError::Deserialization(e.to_string()))?;
 
         Ok(Credential {
@@ -111,6 +249,7 @@ impl Credential {
             theta,
             voucher_value,
             voucher_info,
+            expiry_epoch,
         })
     }
 }
@@ -127,6 +266,152 @@ impl Bytable for Credential {
 
 impl Base58 for Credential {}
 
+/// A single ticket of a divisible credential, i.e. the credential shown as one of
+/// `2^index_bits` unlinkable tickets, each worth an equal part of the voucher value.
+#[derive(Debug, Serialize, Deserialize, Getters, CopyGetters, Clone, PartialEq, Eq)]
+pub struct TicketCredential {
+    #[getset(get = "pub")]
+    n_params: u32,
+    #[getset(get = "pub")]
+    theta: TicketTheta,
+    voucher_value: u64,
+    voucher_info: String,
+    expiry_epoch: u64,
+}
+
+impl TicketCredential {
+    pub fn new(
+        n_params: u32,
+        theta: TicketTheta,
+        voucher_value: u64,
+        voucher_info: String,
+        expiry_epoch: u64,
+    ) -> TicketCredential {
+        TicketCredential {
+            n_params,
+            theta,
+            voucher_value,
+            voucher_info,
+            expiry_epoch,
+        }
+    }
+
+    pub fn ticket_serial_number(&self) -> String {
+        self.theta.ticket_serial_number_bs58()
+    }
+
+    pub fn voucher_value(&self) -> u64 {
+        self.voucher_value
+    }
+
+    /// Type of the credential the ticket belongs to, e.g. `DivisibleBandwidthVoucher`.
+    pub fn voucher_info(&self) -> &str {
+        &self.voucher_info
+    }
+
+    /// Epoch after which the credential can no longer be spent.
+    pub fn expiry_epoch(&self) -> u64 {
+        self.expiry_epoch
+    }
+
+    /// Value of this single ticket, assuming the credential got split into `2^index_bits` tickets.
+    /// Note that tickets of credentials whose value can't be split evenly never verify.
+    pub fn ticket_value(&self, index_bits: u8) -> u64 {
+        self.voucher_value
+            .checked_shr(index_bits as u32)
+            .unwrap_or_default()
+    }
+
+    pub fn verify(&self, verification_key: &VerificationKey, index_bits: u8) -> bool {
+        // otherwise the remainder of the voucher value would be either lost or claimed twice
+        if !is_divisible_value(self.voucher_value, index_bits) {
+            return false;
+        }
+        let params = match Parameters::new(self.n_params) {
+            Ok(params) => params,
+            Err(_) => return false,
+        };
+        let public_attributes =
+            voucher_public_attributes(self.voucher_value, &self.voucher_info, self.expiry_epoch);
+        nymcoconut::verify_ticket(
+            &params,
+            verification_key,
+            &self.theta,
+            &public_attributes,
+            index_bits,
+        )
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let n_params_bytes = self.n_params.to_be_bytes();
+        let theta_bytes = self.theta.to_bytes();
+        let theta_bytes_len = theta_bytes.len();
+        let voucher_value_bytes = self.voucher_value.to_be_bytes();
+        let expiry_epoch_bytes = self.expiry_epoch.to_be_bytes();
+        let voucher_info_bytes = self.voucher_info.as_bytes();
+        let voucher_info_len = voucher_info_bytes.len();
+
+        let mut bytes = Vec::with_capacity(36 + theta_bytes_len + voucher_info_len);
+        bytes.extend_from_slice(&n_params_bytes);
+        bytes.extend_from_slice(&(theta_bytes_len as u64).to_be_bytes());
+        bytes.extend_from_slice(&theta_bytes);
+        bytes.extend_from_slice(&voucher_value_bytes);
+        bytes.extend_from_slice(&expiry_epoch_bytes);
+        bytes.extend_from_slice(voucher_info_bytes);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CoconutError> {
+        if bytes.len() < 36 {
+            return Err(CoconutError::Deserialization(String::from(
+                "To few bytes in ticket credential",
+            )));
+        }
+        let mut four_byte = [0u8; 4];
+        let mut eight_byte = [0u8; 8];
+
+        four_byte.copy_from_slice(&bytes[..4]);
+        let n_params = u32::from_be_bytes(four_byte);
+        eight_byte.copy_from_slice(&bytes[4..12]);
+        let theta_len = u64::from_be_bytes(eight_byte) as usize;
+        if (bytes.len() - 28) < theta_len {
+            return Err(CoconutError::Deserialization(format!(
+                "To few bytes in ticket credential: {} for a theta of {theta_len} bytes",
+                bytes.len()
+            )));
+        }
+        let theta = TicketTheta::from_bytes(&bytes[12..12 + theta_len])
+            .map_err(|e| CoconutError::Deserialization(e.to_string()))?;
+        eight_byte.copy_from_slice(&bytes[12 + theta_len..20 + theta_len]);
+        let voucher_value = u64::from_be_bytes(eight_byte);
+        eight_byte.copy_from_slice(&bytes[20 + theta_len..28 + theta_len]);
+        let expiry_epoch = u64::from_be_bytes(eight_byte);
+        let voucher_info = String::from_utf8(bytes[28 + theta_len..].to_vec())
+            .map_err(|e| CoconutError::Deserialization(e.to_string()))?;
+
+        Ok(TicketCredential {
+            n_params,
+            theta,
+            voucher_value,
+            voucher_info,
+            expiry_epoch,
+        })
+    }
+}
+
+impl Bytable for TicketCredential {
+    fn to_byte_vec(&self) -> Vec<u8> {
+        self.as_bytes()
+    }
+
+    fn try_from_byte_slice(slice: &[u8]) -> Result<Self, CoconutError> {
+        TicketCredential::from_bytes(slice)
+    }
+}
+
+impl Base58 for TicketCredential {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,11 +451,147 @@ mod tests {
             binding_number,
         )
         .unwrap();
-        let credential = Credential::new(4, theta, voucher_value, voucher_info);
+        let credential = Credential::new(5, theta.clone(), voucher_value, voucher_info, 19000);
 
         let serialized_credential = credential.as_bytes();
+        assert_eq!(serialized_credential[0], CREDENTIAL_FORMAT_VERSION);
         let deserialized_credential = Credential::from_bytes(&serialized_credential).unwrap();
 
         assert_eq!(credential, deserialized_credential);
+        assert!(Credential::from_bytes(&serialized_credential[..30]).is_err());
+
+        let mut unknown_version = serialized_credential;
+        unknown_version[0] = CREDENTIAL_FORMAT_VERSION + 1;
+        assert!(Credential::from_bytes(&unknown_version).is_err());
+    }
+
+    #[test]
+    fn legacy_credentials_keep_their_format() {
+        let params = Parameters::new(LEGACY_CREDENTIAL_ATTRIBUTES).unwrap();
+        let keypair = ttp_keygen(&params, 1, 1).unwrap().remove(0);
+        let verification_key = keypair.verification_key();
+        let voucher_value = 1000u64;
+        let voucher_info = String::from("BandwidthVoucher");
+
+        let serial_number = params.random_scalar();
+        let binding_number = params.random_scalar();
+        let mut public_attributes = voucher_public_attributes(voucher_value, &voucher_info, 0);
+        public_attributes.pop();
+        let private_attributes = vec![serial_number, binding_number];
+
+        let (commitments_openings, blind_sign_request) =
+            prepare_blind_sign(&params, &private_attributes, &public_attributes).unwrap();
+        let signature = blind_sign(
+            &params,
+            &keypair.secret_key(),
+            &blind_sign_request,
+            &public_attributes,
+        )
+        .unwrap()
+        .unblind(
+            &params,
+            &verification_key,
+            &private_attributes,
+            &public_attributes,
+            &blind_sign_request.get_commitment_hash(),
+            &commitments_openings,
+        )
+        .unwrap();
+        let theta = prove_bandwidth_credential(
+            &params,
+            &verification_key,
+            &signature,
+            serial_number,
+            binding_number,
+        )
+        .unwrap();
+        let theta_len = theta.to_bytes().len();
+        let credential = Credential::new(
+            LEGACY_CREDENTIAL_ATTRIBUTES,
+            theta,
+            voucher_value,
+            voucher_info.clone(),
+            19000,
+        );
+        assert!(credential.is_legacy());
+        assert_eq!(credential.expiry_epoch(), 0);
+
+        // n_params || theta_len || theta || voucher_value || voucher_info
+        let bytes = credential.as_bytes();
+        assert_eq!(bytes.len(), 20 + theta_len + voucher_info.len());
+        assert_eq!(bytes[..4], LEGACY_CREDENTIAL_ATTRIBUTES.to_be_bytes());
+
+        let deserialized_credential = Credential::from_bytes(&bytes).unwrap();
+        assert_eq!(credential, deserialized_credential);
+        assert!(deserialized_credential.verify(&verification_key));
+    }
+
+    #[test]
+    fn ticket_credential_roundtrip_and_value() {
+        let params = Parameters::new(5).unwrap();
+        let keypair = ttp_keygen(&params, 1, 1).unwrap().remove(0);
+        let verification_key = keypair.verification_key();
+        let voucher_value = 1024u64;
+        let voucher_info = String::from("BandwidthVoucher");
+        let expiry_epoch = 19000u64;
+
+        let serial_number = params.random_scalar();
+        let binding_number = params.random_scalar();
+        let public_attributes =
+            voucher_public_attributes(voucher_value, &voucher_info, expiry_epoch);
+        let private_attributes = vec![serial_number, binding_number];
+
+        let (commitments_openings, blind_sign_request) =
+            prepare_blind_sign(&params, &private_attributes, &public_attributes).unwrap();
+        let signature = blind_sign(
+            &params,
+            &keypair.secret_key(),
+            &blind_sign_request,
+            &public_attributes,
+        )
+        .unwrap()
+        .unblind(
+            &params,
+            &verification_key,
+            &private_attributes,
+            &public_attributes,
+            &blind_sign_request.get_commitment_hash(),
+            &commitments_openings,
+        )
+        .unwrap();
+
+        let theta = prove_ticket(
+            &params,
+            &verification_key,
+            &signature,
+            serial_number,
+            binding_number,
+            5,
+            4,
+        )
+        .unwrap();
+        let ticket = TicketCredential::new(5, theta, voucher_value, voucher_info, expiry_epoch);
+
+        let deserialized_ticket = TicketCredential::from_bytes(&ticket.as_bytes()).unwrap();
+        assert_eq!(ticket, deserialized_ticket);
+
+        assert!(deserialized_ticket.verify(&verification_key, 4));
+        assert!(!deserialized_ticket.verify(&verification_key, 3));
+        assert_eq!(deserialized_ticket.ticket_value(4), 64);
+
+        // the theta length must not point past the end of the bytes
+        let mut bytes = ticket.as_bytes();
+        bytes[4..12].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(TicketCredential::from_bytes(&bytes).is_err());
+        assert!(TicketCredential::from_bytes(&ticket.as_bytes()[..40]).is_err());
+    }
+
+    #[test]
+    fn only_evenly_divisible_values_can_be_split_into_tickets() {
+        assert!(is_divisible_value(1024, 4));
+        assert!(is_divisible_value(0, 8));
+        assert!(!is_divisible_value(1025, 4));
+        assert!(!is_divisible_value(255, 8));
+        assert!(!is_divisible_value(1024, 64));
     }
 }
@@ -0,0 +1,48 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use credential_storage::storage::Storage;
+use credential_storage::PersistentStorage;
+use log::warn;
+
+use crate::coconut::voucher_bandwidth;
+use crate::utils::show_error;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    #[clap(help = "Home directory of the client whose bandwidth is to be shown")]
+    pub client_home_directory: std::path::PathBuf,
+
+    #[clap(long)]
+    #[clap(
+        help = "Path to the file containing the passphrase of the encrypted credential storage. If not provided, it is read from the NYM_CLIENT_PASSPHRASE environment variable, if set"
+    )]
+    pub passphrase_file: Option<std::path::PathBuf>,
+}
+
+pub async fn show_available_bandwidth(_args: Args, storage: &PersistentStorage) {
+    match storage.get_all_coconut_credentials().await {
+        Ok(credentials) => {
+            let mut unspent = 0;
+            let mut total_bandwidth = 0u64;
+            for credential in credentials.iter().filter(|credential| !credential.consumed) {
+                match voucher_bandwidth(&credential.voucher_value) {
+                    Some(bandwidth) => {
+                        unspent += 1;
+                        total_bandwidth = total_bandwidth.saturating_add(bandwidth);
+                    }
+                    None => warn!(
+                        "credential {} has an invalid value of {}",
+                        credential.id, credential.voucher_value
+                    ),
+                }
+            }
+
+            println!("Unspent credentials: {unspent}");
+            println!("Available bandwidth: {total_bandwidth} bytes");
+        }
+        Err(e) => show_error(e),
+    }
+}
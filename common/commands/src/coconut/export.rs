@@ -0,0 +1,95 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use credential_storage::storage::Storage;
+use credential_storage::PersistentStorage;
+use log::warn;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use crate::coconut::ExportedCredential;
+use crate::utils::show_error;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    #[clap(help = "Home directory of the client whose credentials are to be exported")]
+    pub client_home_directory: std::path::PathBuf,
+
+    #[clap(long)]
+    #[clap(
+        help = "Path to the file containing the passphrase of the encrypted credential storage. If not provided, it is read from the NYM_CLIENT_PASSPHRASE environment variable, if set"
+    )]
+    pub passphrase_file: Option<std::path::PathBuf>,
+
+    #[clap(long)]
+    #[clap(
+        help = "File the credentials are going to be written to. Note that they're written in plaintext and can be spent by anyone with access to it"
+    )]
+    pub output: std::path::PathBuf,
+
+    #[clap(long)]
+    #[clap(
+        help = "Mark the exported credentials as consumed, so that this client would not attempt to spend them anymore"
+    )]
+    pub mark_consumed: bool,
+}
+
+// the exported credentials can be spent by anyone who gets hold of them,
+// so make sure only the owner of the file is able to read it
+fn write_owner_only(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    // the mode is only applied when the file gets created, so also adjust any existing one
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content)
+}
+
+pub async fn export_credentials(args: Args, storage: &PersistentStorage) {
+    let credentials = match storage.get_all_coconut_credentials().await {
+        Ok(credentials) => credentials,
+        Err(e) => return show_error(e),
+    };
+
+    let (ids, exported): (Vec<_>, Vec<_>) = credentials
+        .into_iter()
+        .filter(|credential| !credential.consumed)
+        .map(|credential| (credential.id, ExportedCredential::from(credential)))
+        .unzip();
+
+    let serialized = serde_json::to_string_pretty(&exported).expect("json formatting error");
+    if let Err(e) = write_owner_only(&args.output, serialized.as_bytes()) {
+        return show_error(e);
+    }
+    warn!(
+        "The credentials have been exported in plaintext - anyone with access to {} is able to spend them. Keep it safe and remove it once it's imported",
+        args.output.display()
+    );
+
+    if args.mark_consumed {
+        for id in ids {
+            if let Err(e) = storage.consume_coconut_credential(id).await {
+                return show_error(e);
+            }
+        }
+    }
+
+    println!(
+        "Exported {} credentials to {}",
+        exported.len(),
+        args.output.display()
+    );
+}
@@ -0,0 +1,58 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use credential_storage::storage::Storage;
+use credential_storage::PersistentStorage;
+use log::warn;
+
+use crate::coconut::ExportedCredential;
+use crate::utils::show_error;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    #[clap(help = "Home directory of the client the credentials are going to be imported into")]
+    pub client_home_directory: std::path::PathBuf,
+
+    #[clap(long)]
+    #[clap(
+        help = "Path to the file containing the passphrase of the encrypted credential storage. If not provided, it is read from the NYM_CLIENT_PASSPHRASE environment variable, if set"
+    )]
+    pub passphrase_file: Option<std::path::PathBuf>,
+
+    #[clap(long)]
+    #[clap(help = "File containing the previously exported credentials")]
+    pub input: std::path::PathBuf,
+}
+
+pub async fn import_credentials(args: Args, storage: &PersistentStorage) {
+    let credentials: Vec<ExportedCredential> = match std::fs::read_to_string(&args.input)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+    {
+        Ok(credentials) => credentials,
+        Err(e) => return show_error(e),
+    };
+
+    let mut imported = 0;
+    for credential in credentials {
+        // credentials with an already existing signature are rejected by the storage
+        match storage
+            .insert_coconut_credential(
+                credential.voucher_value,
+                credential.voucher_info,
+                credential.serial_number,
+                credential.binding_number,
+                credential.signature,
+                credential.expiry_epoch,
+            )
+            .await
+        {
+            Ok(_) => imported += 1,
+            Err(e) => warn!("failed to import a credential - {e}"),
+        }
+    }
+
+    println!("Imported {imported} credentials");
+}
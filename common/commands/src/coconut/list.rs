@@ -0,0 +1,63 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use comfy_table::Table;
+use credential_storage::storage::Storage;
+use credential_storage::PersistentStorage;
+
+use crate::coconut::voucher_bandwidth;
+use crate::utils::show_error;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    #[clap(help = "Home directory of the client whose credentials are to be listed")]
+    pub client_home_directory: std::path::PathBuf,
+
+    #[clap(long)]
+    #[clap(
+        help = "Path to the file containing the passphrase of the encrypted credential storage. If not provided, it is read from the NYM_CLIENT_PASSPHRASE environment variable, if set"
+    )]
+    pub passphrase_file: Option<std::path::PathBuf>,
+
+    #[clap(long)]
+    #[clap(help = "Also show the credentials that have already been spent")]
+    pub include_consumed: bool,
+}
+
+pub async fn list_credentials(args: Args, storage: &PersistentStorage) {
+    match storage.get_all_coconut_credentials().await {
+        Ok(credentials) => {
+            let mut table = Table::new();
+
+            table.set_header(vec![
+                "Id",
+                "Value",
+                "Bandwidth (bytes)",
+                "Info",
+                "Expiry epoch",
+                "Consumed",
+            ]);
+            for credential in credentials
+                .into_iter()
+                .filter(|credential| args.include_consumed || !credential.consumed)
+            {
+                let bandwidth = voucher_bandwidth(&credential.voucher_value)
+                    .map(|bandwidth| bandwidth.to_string())
+                    .unwrap_or_else(|| "invalid value".to_string());
+                table.add_row(vec![
+                    credential.id.to_string(),
+                    credential.voucher_value,
+                    bandwidth,
+                    credential.voucher_info,
+                    credential.expiry_epoch.to_string(),
+                    credential.consumed.to_string(),
+                ]);
+            }
+
+            println!("{table}");
+        }
+        Err(e) => show_error(e),
+    }
+}
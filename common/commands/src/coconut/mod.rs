@@ -1,4 +1,64 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-// TODO: add coconut commands here
+use clap::{Args, Subcommand};
+use credential_storage::models::CoconutCredential;
+use serde::{Deserialize, Serialize};
+
+pub mod balance;
+pub mod export;
+pub mod import;
+pub mod list;
+
+#[derive(Debug, Args)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_required = true)]
+pub struct Coconut {
+    #[clap(subcommand)]
+    pub command: Option<CoconutCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CoconutCommands {
+    /// Lists the bandwidth credentials stored by a client
+    List(crate::coconut::list::Args),
+    /// Shows the total bandwidth available in the unspent credentials of a client
+    Balance(crate::coconut::balance::Args),
+    /// Exports the unspent credentials of a client into a file
+    Export(crate::coconut::export::Args),
+    /// Imports previously exported credentials into the storage of a client
+    Import(crate::coconut::import::Args),
+}
+
+/// Format of the credentials moved between client data directories.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedCredential {
+    pub voucher_value: String,
+    pub voucher_info: String,
+    pub serial_number: String,
+    pub binding_number: String,
+    pub signature: String,
+    #[serde(default)]
+    pub expiry_epoch: u64,
+}
+
+impl From<CoconutCredential> for ExportedCredential {
+    fn from(credential: CoconutCredential) -> Self {
+        ExportedCredential {
+            voucher_value: credential.voucher_value,
+            voucher_info: credential.voucher_info,
+            serial_number: credential.serial_number,
+            binding_number: credential.binding_number,
+            signature: credential.signature,
+            expiry_epoch: credential.expiry_epoch as u64,
+        }
+    }
+}
+
+/// Amount of bandwidth, in bytes, represented by the provided voucher value.
+/// Returns `None` if the stored value is malformed.
+pub(crate) fn voucher_bandwidth(voucher_value: &str) -> Option<u64> {
+    voucher_value
+        .parse::<u64>()
+        .ok()
+        .map(|value| value.saturating_mul(network_defaults::BYTES_PER_UTOKEN))
+}
@@ -0,0 +1,32 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use mixnet_contract_common::{Coin, IdentityKey};
+use validator_client::nymd::traits::MixnetSigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    pub identity_key: IdentityKey,
+
+    #[clap(long)]
+    pub amount: u128,
+}
+
+pub async fn delegate_to_gateway(args: Args, client: SigningClient) {
+    let denom = client.current_chain_details().mix_denom.base.as_str();
+
+    info!("Starting delegation to gateway");
+
+    let coin = Coin::new(args.amount, denom);
+
+    let res = client
+        .delegate_to_gateway(args.identity_key, coin.into(), None)
+        .await
+        .expect("failed to delegate to gateway!");
+
+    info!("delegating to gateway: {:?}", res);
+}
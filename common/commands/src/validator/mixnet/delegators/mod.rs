@@ -5,10 +5,14 @@ use clap::{Args, Subcommand};
 
 pub mod rewards;
 
+pub mod delegate_to_gateway;
 pub mod delegate_to_mixnode;
 pub mod query_for_delegations;
+pub mod redelegate_from_mixnode;
+pub mod undelegate_from_gateway;
 pub mod undelegate_from_mixnode;
 pub mod vesting_delegate_to_mixnode;
+pub mod vesting_redelegate_from_mixnode;
 pub mod vesting_undelegate_from_mixnode;
 
 #[derive(Debug, Args)]
@@ -32,4 +36,12 @@ pub enum MixnetDelegatorsCommands {
     DelegateVesting(vesting_delegate_to_mixnode::Args),
     /// Undelegate from a mixnode (when originally using locked tokens)
     UndelegateVesting(vesting_undelegate_from_mixnode::Args),
+    /// Move stake from one mixnode to another at the end of the current epoch
+    Redelegate(redelegate_from_mixnode::Args),
+    /// Move stake from one mixnode to another (when originally using locked tokens)
+    RedelegateVesting(vesting_redelegate_from_mixnode::Args),
+    /// Delegate to a gateway
+    DelegateToGateway(delegate_to_gateway::Args),
+    /// Undelegate from a gateway
+    UndelegateFromGateway(undelegate_from_gateway::Args),
 }
@@ -0,0 +1,40 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use mixnet_contract_common::{Coin, MixId};
+use validator_client::nymd::traits::MixnetSigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// Id of the mixnode the stake is moved away from
+    #[clap(long)]
+    pub from: MixId,
+
+    /// Id of the mixnode the stake is moved to
+    #[clap(long)]
+    pub to: MixId,
+
+    #[clap(long)]
+    pub amount: u128,
+}
+
+pub async fn redelegate_from_mixnode(args: Args, client: SigningClient) {
+    let denom = client.current_chain_details().mix_denom.base.as_str();
+
+    info!(
+        "Starting redelegation from mixnode {} to mixnode {}",
+        args.from, args.to
+    );
+
+    let coin = Coin::new(args.amount, denom);
+
+    let res = client
+        .redelegate_from_mixnode(args.from, args.to, coin.into(), None)
+        .await
+        .expect("failed to redelegate between mixnodes!");
+
+    info!("redelegating between mixnodes: {:?}", res);
+}
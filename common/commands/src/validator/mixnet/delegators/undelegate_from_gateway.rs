@@ -0,0 +1,25 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use mixnet_contract_common::IdentityKey;
+use validator_client::nymd::traits::MixnetSigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    pub identity_key: IdentityKey,
+}
+
+pub async fn undelegate_from_gateway(args: Args, client: SigningClient) {
+    info!("removing stake from gateway");
+
+    let res = client
+        .undelegate_from_gateway(args.identity_key, None)
+        .await
+        .expect("failed to remove stake from gateway!");
+
+    info!("removing stake from gateway: {:?}", res)
+}
@@ -0,0 +1,32 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use mixnet_contract_common::Coin;
+use validator_client::nymd::traits::MixnetSigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(
+        long,
+        help = "amount to decrease the pledge by in current DENOMINATION (so it would be 'unym', rather than 'nym')"
+    )]
+    pub amount: u128,
+}
+
+pub async fn decrease_pledge(args: Args, client: SigningClient) {
+    let denom = client.current_chain_details().mix_denom.base.as_str();
+
+    info!("Starting gateway pledge decrease");
+
+    let coin = Coin::new(args.amount, denom);
+
+    let res = client
+        .decrease_gateway_pledge(coin.into(), None)
+        .await
+        .expect("failed to decrease the gateway pledge!");
+
+    info!("Pledge decrease result: {:?}", res)
+}
@@ -3,9 +3,15 @@
 
 use clap::{Args, Subcommand};
 
+pub mod settings;
+
 pub mod bond_gateway;
+pub mod decrease_pledge;
+pub mod pledge_more;
 pub mod unbond_gateway;
 pub mod vesting_bond_gateway;
+pub mod vesting_decrease_pledge;
+pub mod vesting_pledge_more;
 pub mod vesting_unbond_gateway;
 
 #[derive(Debug, Args)]
@@ -25,4 +31,14 @@ pub enum MixnetOperatorsGatewayCommands {
     VestingBond(vesting_bond_gateway::Args),
     /// Unbound from a gateway (when originally using locked tokens)
     VestingUnbound(vesting_unbond_gateway::Args),
+    /// Pledge more tokens towards a bonded gateway
+    PledgeMore(pledge_more::Args),
+    /// Pledge more locked tokens towards a gateway bonded with locked tokens
+    VestingPledgeMore(vesting_pledge_more::Args),
+    /// Decrease the amount of tokens pledged towards a bonded gateway
+    DecreasePledge(decrease_pledge::Args),
+    /// Decrease the amount of locked tokens pledged towards a gateway bonded with locked tokens
+    VestingDecreasePledge(vesting_decrease_pledge::Args),
+    /// Manage your gateway settings stored in the directory
+    Settings(settings::MixnetOperatorsGatewaySettings),
 }
@@ -0,0 +1,61 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use mixnet_contract_common::GatewayConfigUpdate;
+use validator_client::nymd::traits::MixnetQueryClient;
+use validator_client::nymd::VestingSigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    pub host: Option<String>,
+
+    #[clap(long)]
+    pub mix_port: Option<u16>,
+
+    #[clap(long)]
+    pub clients_port: Option<u16>,
+
+    #[clap(long)]
+    pub location: Option<String>,
+
+    #[clap(long)]
+    pub version: Option<String>,
+}
+
+pub async fn vesting_update_config(args: Args, client: SigningClient) {
+    info!("Update vesting gateway config!");
+
+    let current_bond = match client
+        .get_owned_gateway(client.address())
+        .await
+        .expect("failed to query the chain for gateway details")
+        .gateway
+    {
+        Some(bond) => bond,
+        None => {
+            log::warn!("this operator does not own a gateway to update");
+            return;
+        }
+    };
+
+    let update = GatewayConfigUpdate {
+        host: args.host.unwrap_or(current_bond.gateway.host),
+        mix_port: args.mix_port.unwrap_or(current_bond.gateway.mix_port),
+        clients_port: args
+            .clients_port
+            .unwrap_or(current_bond.gateway.clients_port),
+        location: args.location.unwrap_or(current_bond.gateway.location),
+        version: args.version.unwrap_or(current_bond.gateway.version),
+    };
+
+    let res = client
+        .vesting_update_gateway_config(update, None)
+        .await
+        .expect("updating vesting gateway config");
+
+    info!("vesting gateway config updated: {:?}", res)
+}
@@ -3,9 +3,12 @@
 
 // event types
 pub const DEPOSITED_FUNDS_EVENT_TYPE: &str = "deposited-funds";
+pub const PRUNED_SPENT_CREDENTIALS_EVENT_TYPE: &str = "pruned-spent-credentials";
 
 // attributes that are used in multiple places
 pub const DEPOSIT_VALUE: &str = "deposit-value";
 pub const DEPOSIT_INFO: &str = "deposit-info";
 pub const DEPOSIT_IDENTITY_KEY: &str = "deposit-identity-key";
 pub const DEPOSIT_ENCRYPTION_KEY: &str = "deposit-encryption-key";
+pub const DEPOSIT_EXPIRY_EPOCH: &str = "deposit-expiry-epoch";
+pub const PRUNED_COUNT: &str = "pruned-count";
@@ -0,0 +1,120 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Credential expiry is expressed in whole epochs rather than exact timestamps,
+//! so that the (public) expiry attribute of a credential does not reveal the time of its deposit.
+
+/// Length of a single expiry epoch.
+pub const EXPIRY_EPOCH_LENGTH_SECS: u64 = 24 * 60 * 60;
+
+/// Number of epochs, following the epoch of the deposit, during which the credential can be spent.
+pub const CREDENTIAL_VALIDITY_EPOCHS: u64 = 30;
+
+/// Number of epochs, following the expiry, during which gateways can still redeem
+/// the credentials they have accepted before the expiry.
+pub const REDEMPTION_GRACE_EPOCHS: u64 = 7;
+
+/// Number of epochs, following the redemption deadline, during which the owners of the credentials
+/// that have never been spent can get their deposits refunded.
+pub const REFUND_WINDOW_EPOCHS: u64 = 30;
+
+/// Expiry epoch of the legacy credentials, issued before the expiry became one of their attributes.
+pub const LEGACY_EXPIRY_EPOCH: u64 = 0;
+
+/// Last epoch (2023-06-30) during which the legacy credentials can still be spent.
+pub const LEGACY_CREDENTIAL_EXPIRY_EPOCH: u64 = 19538;
+
+/// Returns the expiry epoch the provided unix timestamp falls into.
+pub fn epoch_at(timestamp_secs: u64) -> u64 {
+    timestamp_secs / EXPIRY_EPOCH_LENGTH_SECS
+}
+
+/// Returns the expiry epoch of credentials issued for a deposit made at the provided unix timestamp.
+pub fn deposit_expiry_epoch(deposit_timestamp_secs: u64) -> u64 {
+    epoch_at(deposit_timestamp_secs) + CREDENTIAL_VALIDITY_EPOCHS
+}
+
+/// Returns the epoch of the deposit the credentials expiring at `expiry_epoch` were issued for.
+pub fn deposit_epoch(expiry_epoch: u64) -> u64 {
+    expiry_epoch.saturating_sub(CREDENTIAL_VALIDITY_EPOCHS)
+}
+
+/// Returns the epoch after which a credential expiring at `expiry_epoch` can no longer be spent,
+/// i.e. the expiry itself, unless it is a legacy credential.
+pub fn effective_expiry_epoch(expiry_epoch: u64) -> u64 {
+    if expiry_epoch == LEGACY_EXPIRY_EPOCH {
+        LEGACY_CREDENTIAL_EXPIRY_EPOCH
+    } else {
+        expiry_epoch
+    }
+}
+
+/// Checks whether a credential expiring at `expiry_epoch` can still be spent.
+pub fn is_spendable(expiry_epoch: u64, current_epoch: u64) -> bool {
+    current_epoch <= effective_expiry_epoch(expiry_epoch)
+}
+
+/// Last epoch during which a credential expiring at `expiry_epoch` can be redeemed on chain.
+pub fn redemption_deadline(expiry_epoch: u64) -> u64 {
+    effective_expiry_epoch(expiry_epoch) + REDEMPTION_GRACE_EPOCHS
+}
+
+/// Last epoch during which the deposit of an unspent credential expiring at `expiry_epoch`
+/// can be refunded.
+pub fn refund_deadline(expiry_epoch: u64) -> u64 {
+    redemption_deadline(expiry_epoch) + REFUND_WINDOW_EPOCHS
+}
+
+/// Checks whether the deposit of a credential expiring at `expiry_epoch` can be refunded
+/// (assuming it has never been spent). It's only possible once the gateways can no longer redeem
+/// the credential, so that the set of its spent credentials could not change anymore.
+pub fn is_refundable(expiry_epoch: u64, current_epoch: u64) -> bool {
+    current_epoch > redemption_deadline(expiry_epoch)
+        && current_epoch <= refund_deadline(expiry_epoch)
+}
+
+/// Checks whether the spent credential expiring at `expiry_epoch` no longer has to be remembered,
+/// i.e. it can neither be redeemed nor refunded anymore.
+pub fn is_prunable(expiry_epoch: u64, current_epoch: u64) -> bool {
+    current_epoch > refund_deadline(expiry_epoch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credential_lifecycle_periods_do_not_overlap() {
+        let expiry = deposit_expiry_epoch(1_571_797_419);
+        let deadline = redemption_deadline(expiry);
+        let last_refund_epoch = refund_deadline(expiry);
+
+        assert_eq!(deposit_epoch(expiry), epoch_at(1_571_797_419));
+
+        assert!(is_spendable(expiry, expiry));
+        assert!(!is_spendable(expiry, expiry + 1));
+        assert!(expiry < deadline);
+        assert!(!is_refundable(expiry, deadline));
+        assert!(is_refundable(expiry, deadline + 1));
+        assert!(is_refundable(expiry, last_refund_epoch));
+        assert!(!is_refundable(expiry, last_refund_epoch + 1));
+        assert!(!is_prunable(expiry, last_refund_epoch));
+        assert!(is_prunable(expiry, last_refund_epoch + 1));
+    }
+
+    #[test]
+    fn legacy_credentials_expire_at_fixed_epoch() {
+        assert!(is_spendable(
+            LEGACY_EXPIRY_EPOCH,
+            LEGACY_CREDENTIAL_EXPIRY_EPOCH
+        ));
+        assert!(!is_spendable(
+            LEGACY_EXPIRY_EPOCH,
+            LEGACY_CREDENTIAL_EXPIRY_EPOCH + 1
+        ));
+        assert_eq!(
+            redemption_deadline(LEGACY_EXPIRY_EPOCH),
+            redemption_deadline(LEGACY_CREDENTIAL_EXPIRY_EPOCH)
+        );
+    }
+}
@@ -1,5 +1,7 @@
 pub mod deposit;
 pub mod event_attributes;
 pub mod events;
+pub mod expiry;
 pub mod msg;
+pub mod refund;
 pub mod spend_credential;
@@ -5,7 +5,9 @@ use cosmwasm_std::Coin;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::{deposit::DepositData, spend_credential::SpendCredentialData};
+use crate::{
+    deposit::DepositData, refund::RefundCredentialData, spend_credential::SpendCredentialData,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -20,6 +22,9 @@ pub enum ExecuteMsg {
     DepositFunds { data: DepositData },
     SpendCredential { data: SpendCredentialData },
     ReleaseFunds { funds: Coin },
+    PruneSpentCredentials { limit: Option<u32> },
+    RefundCredential { data: RefundCredentialData },
+    RefundFunds { funds: Coin, recipient: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -32,6 +37,11 @@ pub enum QueryMsg {
         limit: Option<u32>,
         start_after: Option<String>,
     },
+    /// Pages through the spent credentials in the order in which they got spent.
+    GetSpentCredentialsAfter {
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
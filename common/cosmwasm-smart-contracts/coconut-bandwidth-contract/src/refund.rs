@@ -0,0 +1,124 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Refunds of the deposits of credentials that expired without ever being spent.
+//!
+//! A refund is a spend of the credential whose funds go back to its owner. It follows the same
+//! path as the spending done by the gateways: the serial number is put into the set of the spent
+//! credentials and the nym-apis vote on the release of the funds only after verifying the credential.
+//! Hence a credential can either be spent or refunded, but never both.
+
+use cosmwasm_std::{from_binary, to_binary, Coin, CosmosMsg, StdResult, WasmMsg};
+use multisig_contract_common::msg::ExecuteMsg as MultisigExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::msg::ExecuteMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RefundCredentialData {
+    funds: Coin,
+    blinded_serial_number: String,
+    expiry_epoch: u64,
+}
+
+impl RefundCredentialData {
+    pub fn new(funds: Coin, blinded_serial_number: String, expiry_epoch: u64) -> Self {
+        RefundCredentialData {
+            funds,
+            blinded_serial_number,
+            expiry_epoch,
+        }
+    }
+
+    pub fn funds(&self) -> &Coin {
+        &self.funds
+    }
+
+    pub fn blinded_serial_number(&self) -> &str {
+        &self.blinded_serial_number
+    }
+
+    pub fn expiry_epoch(&self) -> u64 {
+        self.expiry_epoch
+    }
+}
+
+/// Creates the multisig proposal for refunding the provided funds to the owner of the credential.
+pub fn to_refund_cosmos_msg(
+    funds: Coin,
+    blinded_serial_number: String,
+    recipient: String,
+    coconut_bandwidth_addr: String,
+    multisig_addr: String,
+) -> StdResult<CosmosMsg> {
+    let refund_funds_req = ExecuteMsg::RefundFunds { funds, recipient };
+    let refund_funds_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: coconut_bandwidth_addr,
+        msg: to_binary(&refund_funds_req)?,
+        funds: vec![],
+    });
+    let req = MultisigExecuteMsg::Propose {
+        title: String::from("Refund funds, as ordered by Coconut Bandwidth Contract"),
+        description: blinded_serial_number,
+        msgs: vec![refund_funds_msg],
+        latest: None,
+    };
+    let msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: multisig_addr,
+        msg: to_binary(&req)?,
+        funds: vec![],
+    });
+
+    Ok(msg)
+}
+
+/// Recovers the refunded funds and their recipient out of the messages of the proposal.
+pub fn refund_from_cosmos_msgs(msgs: Vec<CosmosMsg>) -> Option<(Coin, String)> {
+    if let Some(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: _,
+        msg,
+        funds: _,
+    })) = msgs.get(0)
+    {
+        if let Ok(ExecuteMsg::RefundFunds { funds, recipient }) = from_binary::<ExecuteMsg>(msg) {
+            return Some((funds, recipient));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spend_credential::funds_from_cosmos_msgs;
+
+    #[test]
+    fn refund_proposals_are_not_mistaken_for_releases() {
+        let funds = Coin::new(42, "unym");
+        let msg = to_refund_cosmos_msg(
+            funds.clone(),
+            "serial".to_string(),
+            "owner".to_string(),
+            "bandwidth".to_string(),
+            "multisig".to_string(),
+        )
+        .unwrap();
+
+        let proposed = match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                match from_binary::<MultisigExecuteMsg>(&msg).unwrap() {
+                    MultisigExecuteMsg::Propose { msgs, .. } => msgs,
+                    _ => panic!("not a proposal"),
+                }
+            }
+            _ => panic!("not a wasm message"),
+        };
+
+        assert!(funds_from_cosmos_msgs(proposed.clone()).is_none());
+        assert_eq!(
+            refund_from_cosmos_msgs(proposed),
+            Some((funds, "owner".to_string()))
+        );
+    }
+}
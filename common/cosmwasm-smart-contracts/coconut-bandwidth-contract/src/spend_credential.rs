@@ -13,14 +13,22 @@ pub struct SpendCredentialData {
     funds: Coin,
     blinded_serial_number: String,
     gateway_cosmos_address: String,
+    #[serde(default)]
+    expiry_epoch: u64,
 }
 
 impl SpendCredentialData {
-    pub fn new(funds: Coin, blinded_serial_number: String, gateway_cosmos_address: String) -> Self {
+    pub fn new(
+        funds: Coin,
+        blinded_serial_number: String,
+        gateway_cosmos_address: String,
+        expiry_epoch: u64,
+    ) -> Self {
         SpendCredentialData {
             funds,
             blinded_serial_number,
             gateway_cosmos_address,
+            expiry_epoch,
         }
     }
 
@@ -35,6 +43,10 @@ impl SpendCredentialData {
     pub fn gateway_cosmos_address(&self) -> &str {
         &self.gateway_cosmos_address
     }
+
+    pub fn expiry_epoch(&self) -> u64 {
+        self.expiry_epoch
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
@@ -49,22 +61,54 @@ pub struct SpendCredential {
     blinded_serial_number: String,
     gateway_cosmos_address: Addr,
     status: SpendCredentialStatus,
+    // entries created before the introduction of expiry are treated as long expired
+    #[serde(default)]
+    expiry_epoch: u64,
+    // position in the order in which the credentials got spent, used for the incremental synchronisation
+    #[serde(default)]
+    spend_id: Option<u64>,
 }
 
 impl SpendCredential {
-    pub fn new(funds: Coin, blinded_serial_number: String, gateway_cosmos_address: Addr) -> Self {
+    pub fn new(
+        funds: Coin,
+        blinded_serial_number: String,
+        gateway_cosmos_address: Addr,
+        expiry_epoch: u64,
+    ) -> Self {
         SpendCredential {
             funds,
             blinded_serial_number,
             gateway_cosmos_address,
             status: SpendCredentialStatus::InProgress,
+            expiry_epoch,
+            spend_id: None,
         }
     }
 
+    pub fn with_spend_id(mut self, spend_id: u64) -> Self {
+        self.spend_id = Some(spend_id);
+        self
+    }
+
     pub fn blinded_serial_number(&self) -> &str {
         &self.blinded_serial_number
     }
 
+    /// Address of the gateway that spent the credential or, for refunded credentials,
+    /// of the owner that got its deposit back.
+    pub fn gateway_cosmos_address(&self) -> &Addr {
+        &self.gateway_cosmos_address
+    }
+
+    pub fn expiry_epoch(&self) -> u64 {
+        self.expiry_epoch
+    }
+
+    pub fn spend_id(&self) -> Option<u64> {
+        self.spend_id
+    }
+
     pub fn status(&self) -> SpendCredentialStatus {
         self.status
     }
@@ -95,6 +139,27 @@ impl PagedSpendCredentialResponse {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct PagedSpendCredentialIdResponse {
+    pub spend_credentials: Vec<SpendCredential>,
+    pub per_page: usize,
+    pub start_next_after: Option<u64>,
+}
+
+impl PagedSpendCredentialIdResponse {
+    pub fn new(
+        spend_credentials: Vec<SpendCredential>,
+        per_page: usize,
+        start_next_after: Option<u64>,
+    ) -> Self {
+        PagedSpendCredentialIdResponse {
+            spend_credentials,
+            per_page,
+            start_next_after,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
 pub struct SpendCredentialResponse {
     pub spend_credential: Option<SpendCredential>,
@@ -1,6 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::penalties::EvidenceId;
 use crate::{IdentityKey, MixId};
 use cosmwasm_std::{Addr, Coin, Decimal};
 use thiserror::Error;
@@ -46,6 +47,28 @@ pub enum MixnetContractError {
     #[error("Gateway with this identity already exists. Its owner is {owner}")]
     DuplicateGateway { owner: Addr },
 
+    #[error("Gateway ({identity}) does not exist")]
+    GatewayBondNotFound { identity: IdentityKey },
+
+    #[error("Gateway {identity} already has a pending pledge change that will be resolved at the end of the current epoch")]
+    PendingGatewayPledgeChange { identity: IdentityKey },
+
+    #[error("Decreasing the gateway pledge ({current}) by {decrease_by} would bring it below the minimum of {minimum}")]
+    InvalidGatewayPledgeReduction {
+        current: Coin,
+        decrease_by: Coin,
+        minimum: Coin,
+    },
+
+    #[error("The provided gateway configuration is invalid: {reason}")]
+    InvalidGatewayConfig { reason: String },
+
+    #[error("Could not find any delegation information associated with gateway {identity} for {address}")]
+    NoGatewayDelegationFound {
+        identity: IdentityKey,
+        address: String,
+    },
+
     #[error("Unauthorized")]
     Unauthorized,
 
@@ -86,6 +109,12 @@ pub enum MixnetContractError {
         absolute_epoch_id: u32,
     },
 
+    #[error("Gateway {identity} has already been rewarded during the current rewarding epoch ({absolute_epoch_id})")]
+    GatewayAlreadyRewarded {
+        identity: IdentityKey,
+        absolute_epoch_id: u32,
+    },
+
     #[error("Mixnode {mix_id} hasn't been selected to the rewarding set in this epoch ({absolute_epoch_id})")]
     MixnodeNotInRewardedSet {
         mix_id: MixId,
@@ -110,6 +139,9 @@ pub enum MixnetContractError {
         proxy: Option<String>,
     },
 
+    #[error("Attempted to redelegate stake from mixnode {mix_id} back to itself")]
+    RedelegationToSameMixnode { mix_id: MixId },
+
     #[error("Provided message to update rewarding params did not contain any updates")]
     EmptyParamsChangeMsg,
 
@@ -167,6 +199,54 @@ pub enum MixnetContractError {
         member: IdentityKey,
     },
 
+    #[error("{identity_key} is already a topology signer")]
+    DuplicateTopologySigner { identity_key: IdentityKey },
+
+    #[error("{identity_key} is not a topology signer")]
+    NotATopologySigner { identity_key: IdentityKey },
+
+    #[error("{identity_key} is already a penalty monitor")]
+    DuplicatePenaltyMonitor { identity_key: IdentityKey },
+
+    #[error("{identity_key} is not a penalty monitor")]
+    NotAPenaltyMonitor { identity_key: IdentityKey },
+
+    #[error("Misbehaviour evidence with id {evidence_id} does not exist")]
+    EvidenceNotFound { evidence_id: EvidenceId },
+
+    #[error("Misbehaviour evidence with id {evidence_id} has already been resolved")]
+    EvidenceAlreadyResolved { evidence_id: EvidenceId },
+
+    #[error("Misbehaviour evidence with id {evidence_id} has expired before it got reviewed")]
+    EvidenceExpired { evidence_id: EvidenceId },
+
+    #[error("This misbehaviour evidence has already been submitted")]
+    DuplicateEvidence,
+
+    #[error("The provided misbehaviour evidence is malformed: {reason}")]
+    MalformedEvidence { reason: String },
+
+    #[error("The provided identity key ({provided}) does not match the one of mixnode {mix_id} ({expected})")]
+    EvidenceIdentityMismatch {
+        mix_id: MixId,
+        provided: IdentityKey,
+        expected: IdentityKey,
+    },
+
+    #[error(
+        "Mixnode {mix_id} can't be unbonded as there's unresolved misbehaviour evidence against it"
+    )]
+    MixnodeUnderInvestigation { mix_id: MixId },
+
+    #[error("Mixnode {mix_id} is not jailed")]
+    MixnodeNotJailed { mix_id: MixId },
+
+    #[error("Penalty exclusion must last for at least one epoch")]
+    ZeroEpochExclusion,
+
+    #[error("Slashed fraction of the pledge must be non-zero")]
+    ZeroSlashingFraction,
+
     #[error("Feature is not yet implemented")]
     NotImplemented,
 }
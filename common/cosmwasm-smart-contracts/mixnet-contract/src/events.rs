@@ -1,7 +1,9 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::gateway::GatewayConfigUpdate;
 use crate::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
+use crate::penalties::{EvidenceId, Penalty};
 use crate::reward_params::{IntervalRewardParams, IntervalRewardingParamsUpdate};
 use crate::rewarding::RewardDistribution;
 use crate::{BlockHeight, ContractStateParams, IdentityKeyRef, Interval, Layer, MixId};
@@ -16,12 +18,23 @@ pub enum MixnetEventType {
     PledgeIncrease,
     GatewayBonding,
     GatewayUnbonding,
+    GatewayConfigUpdate,
+    PendingGatewayPledgeIncrease,
+    GatewayPledgeIncrease,
+    PendingGatewayPledgeDecrease,
+    GatewayPledgeDecrease,
+    PendingGatewayDelegation,
+    PendingGatewayUndelegation,
+    GatewayDelegation,
+    GatewayDelegationOnUnbonded,
+    GatewayUndelegation,
     PendingMixnodeUnbonding,
     MixnodeUnbonding,
     MixnodeConfigUpdate,
     PendingMixnodeCostParamsUpdate,
     MixnodeCostParamsUpdate,
     MixnodeRewarding,
+    GatewayRewarding,
     WithdrawDelegatorReward,
     WithdrawOperatorReward,
     PendingActiveSetUpdate,
@@ -33,8 +46,20 @@ pub enum MixnetEventType {
     Delegation,
     DelegationOnUnbonding,
     Undelegation,
+    PendingRedelegation,
+    Redelegation,
+    RedelegationToUnbondedNode,
     ContractSettingsUpdate,
     RewardingValidatorUpdate,
+    TopologySignerAddition,
+    TopologySignerRemoval,
+    PenaltyMonitorAddition,
+    PenaltyMonitorRemoval,
+    MisbehaviourEvidenceSubmission,
+    PenaltyApplication,
+    EvidenceDismissal,
+    JailRelease,
+    OperatorPledgeSlashing,
     AdvanceEpoch,
     ExecutePendingEpochEvents,
     ExecutePendingIntervalEvents,
@@ -57,12 +82,23 @@ impl ToString for MixnetEventType {
             MixnetEventType::PledgeIncrease => "pledge_increase",
             MixnetEventType::GatewayBonding => "gateway_bonding",
             MixnetEventType::GatewayUnbonding => "gateway_unbonding",
+            MixnetEventType::GatewayConfigUpdate => "gateway_config_update",
+            MixnetEventType::PendingGatewayPledgeIncrease => "pending_gateway_pledge_increase",
+            MixnetEventType::GatewayPledgeIncrease => "gateway_pledge_increase",
+            MixnetEventType::PendingGatewayPledgeDecrease => "pending_gateway_pledge_decrease",
+            MixnetEventType::GatewayPledgeDecrease => "gateway_pledge_decrease",
+            MixnetEventType::PendingGatewayDelegation => "pending_gateway_delegation",
+            MixnetEventType::PendingGatewayUndelegation => "pending_gateway_undelegation",
+            MixnetEventType::GatewayDelegation => "gateway_delegation",
+            MixnetEventType::GatewayDelegationOnUnbonded => "delegation_on_unbonded_gateway",
+            MixnetEventType::GatewayUndelegation => "gateway_undelegation",
             MixnetEventType::PendingMixnodeUnbonding => "pending_mixnode_unbonding",
             MixnetEventType::MixnodeConfigUpdate => "mixnode_config_update",
             MixnetEventType::MixnodeUnbonding => "mixnode_unbonding",
             MixnetEventType::PendingMixnodeCostParamsUpdate => "pending_mixnode_cost_params_update",
             MixnetEventType::MixnodeCostParamsUpdate => "mixnode_cost_params_update",
             MixnetEventType::MixnodeRewarding => "mix_rewarding",
+            MixnetEventType::GatewayRewarding => "gateway_rewarding",
             MixnetEventType::WithdrawDelegatorReward => "withdraw_delegator_reward",
             MixnetEventType::WithdrawOperatorReward => "withdraw_operator_reward",
             MixnetEventType::PendingActiveSetUpdate => "pending_active_set_update",
@@ -75,8 +111,20 @@ impl ToString for MixnetEventType {
             MixnetEventType::PendingUndelegation => "pending_undelegation",
             MixnetEventType::Delegation => "delegation",
             MixnetEventType::Undelegation => "undelegation",
+            MixnetEventType::PendingRedelegation => "pending_redelegation",
+            MixnetEventType::Redelegation => "redelegation",
+            MixnetEventType::RedelegationToUnbondedNode => "redelegation_to_unbonded_node",
             MixnetEventType::ContractSettingsUpdate => "settings_update",
             MixnetEventType::RewardingValidatorUpdate => "rewarding_validator_address_update",
+            MixnetEventType::TopologySignerAddition => "topology_signer_addition",
+            MixnetEventType::TopologySignerRemoval => "topology_signer_removal",
+            MixnetEventType::PenaltyMonitorAddition => "penalty_monitor_addition",
+            MixnetEventType::PenaltyMonitorRemoval => "penalty_monitor_removal",
+            MixnetEventType::MisbehaviourEvidenceSubmission => "misbehaviour_evidence_submission",
+            MixnetEventType::PenaltyApplication => "penalty_application",
+            MixnetEventType::EvidenceDismissal => "evidence_dismissal",
+            MixnetEventType::JailRelease => "jail_release",
+            MixnetEventType::OperatorPledgeSlashing => "operator_pledge_slashing",
             MixnetEventType::AdvanceEpoch => "advance_epoch",
             MixnetEventType::ExecutePendingEpochEvents => "execute_pending_epoch_events",
             MixnetEventType::ExecutePendingIntervalEvents => "execute_pending_interval_events",
@@ -100,6 +148,7 @@ pub const PROXY_KEY: &str = "proxy";
 // delegation/undelegation
 pub const DELEGATOR_KEY: &str = "delegator";
 pub const DELEGATION_TARGET_KEY: &str = "delegation_target";
+pub const REDELEGATION_SOURCE_KEY: &str = "redelegation_source";
 pub const UNIT_REWARD_KEY: &str = "unit_reward";
 
 // bonding/unbonding
@@ -120,6 +169,7 @@ pub const OLD_REWARDING_VALIDATOR_ADDRESS_KEY: &str = "old_rewarding_validator_a
 pub const NEW_REWARDING_VALIDATOR_ADDRESS_KEY: &str = "new_rewarding_validator_address";
 
 pub const UPDATED_MIXNODE_CONFIG_KEY: &str = "updated_mixnode_config";
+pub const UPDATED_GATEWAY_CONFIG_KEY: &str = "updated_gateway_config";
 pub const UPDATED_MIXNODE_COST_PARAMS_KEY: &str = "updated_mixnode_cost_params";
 
 // rewarding
@@ -135,6 +185,13 @@ pub const PRIOR_UNIT_REWARD_KEY: &str = "prior_unit_reward";
 pub const NO_REWARD_REASON_KEY: &str = "no_reward_reason";
 pub const BOND_NOT_FOUND_VALUE: &str = "bond_not_found";
 pub const ZERO_PERFORMANCE_VALUE: &str = "zero_performance";
+pub const EXCLUDED_FROM_REWARDED_SET_VALUE: &str = "excluded_from_rewarded_set";
+
+// penalties
+pub const EVIDENCE_ID_KEY: &str = "evidence_id";
+pub const MONITOR_KEY: &str = "monitor";
+pub const PENALTY_KEY: &str = "penalty";
+pub const SLASHED_AMOUNT_KEY: &str = "slashed_amount";
 
 // rewarded set update
 pub const ACTIVE_SET_SIZE_KEY: &str = "active_set_size";
@@ -290,6 +347,53 @@ pub fn new_pending_undelegation_event(
         .add_attribute(MIX_ID_KEY, mix_id.to_string())
 }
 
+pub fn new_pending_redelegation_event(
+    delegator: &Addr,
+    proxy: &Option<Addr>,
+    amount: &Coin,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+) -> Event {
+    Event::new(MixnetEventType::PendingRedelegation)
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+        .add_attribute(REDELEGATION_SOURCE_KEY, from_mix_id.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, to_mix_id.to_string())
+}
+
+pub fn new_redelegation_event(
+    created_at: BlockHeight,
+    delegator: &Addr,
+    proxy: &Option<Addr>,
+    moved_amount: &Coin,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    target_unit_reward: Decimal,
+) -> Event {
+    Event::new(MixnetEventType::Redelegation)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(AMOUNT_KEY, moved_amount.to_string())
+        .add_attribute(REDELEGATION_SOURCE_KEY, from_mix_id.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, to_mix_id.to_string())
+        .add_attribute(UNIT_REWARD_KEY, target_unit_reward.to_string())
+}
+
+pub fn new_redelegation_to_unbonded_node_event(
+    delegator: &Addr,
+    proxy: &Option<Addr>,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+) -> Event {
+    Event::new(MixnetEventType::RedelegationToUnbondedNode)
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(REDELEGATION_SOURCE_KEY, from_mix_id.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, to_mix_id.to_string())
+}
+
 pub fn new_gateway_bonding_event(
     owner: &Addr,
     proxy: &Option<Addr>,
@@ -316,6 +420,114 @@ pub fn new_gateway_unbonding_event(
         .add_attribute(AMOUNT_KEY, amount.to_string())
 }
 
+pub fn new_gateway_config_update_event(
+    owner: &Addr,
+    proxy: &Option<Addr>,
+    identity: IdentityKeyRef<'_>,
+    update: &GatewayConfigUpdate,
+) -> Event {
+    Event::new(MixnetEventType::GatewayConfigUpdate)
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(OWNER_KEY, owner)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(UPDATED_GATEWAY_CONFIG_KEY, update.to_inline_json())
+}
+
+pub fn new_pending_gateway_pledge_increase_event(
+    identity: IdentityKeyRef<'_>,
+    amount: &Coin,
+) -> Event {
+    Event::new(MixnetEventType::PendingGatewayPledgeIncrease)
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+}
+
+pub fn new_gateway_pledge_increase_event(
+    created_at: BlockHeight,
+    identity: IdentityKeyRef<'_>,
+    amount: &Coin,
+) -> Event {
+    Event::new(MixnetEventType::GatewayPledgeIncrease)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+}
+
+pub fn new_pending_gateway_pledge_decrease_event(
+    identity: IdentityKeyRef<'_>,
+    amount: &Coin,
+) -> Event {
+    Event::new(MixnetEventType::PendingGatewayPledgeDecrease)
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+}
+
+pub fn new_gateway_pledge_decrease_event(
+    created_at: BlockHeight,
+    identity: IdentityKeyRef<'_>,
+    amount: &Coin,
+) -> Event {
+    Event::new(MixnetEventType::GatewayPledgeDecrease)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+}
+
+pub fn new_pending_gateway_delegation_event(
+    delegator: &Addr,
+    amount: &Coin,
+    identity: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::PendingGatewayDelegation)
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, identity)
+}
+
+pub fn new_gateway_delegation_event(
+    created_at: BlockHeight,
+    delegator: &Addr,
+    amount: &Coin,
+    identity: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::GatewayDelegation)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, identity)
+}
+
+pub fn new_delegation_on_unbonded_gateway_event(
+    delegator: &Addr,
+    identity: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::GatewayDelegationOnUnbonded)
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_attribute(DELEGATION_TARGET_KEY, identity)
+}
+
+pub fn new_pending_gateway_undelegation_event(
+    delegator: &Addr,
+    identity: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::PendingGatewayUndelegation)
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_attribute(DELEGATION_TARGET_KEY, identity)
+}
+
+pub fn new_gateway_undelegation_event(
+    created_at: BlockHeight,
+    delegator: &Addr,
+    amount: &Coin,
+    identity: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::GatewayUndelegation)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, identity)
+}
+
 pub fn new_mixnode_bonding_event(
     owner: &Addr,
     proxy: &Option<Addr>,
@@ -409,6 +621,69 @@ pub fn new_rewarding_validator_address_update_event(old: Addr, new: Addr) -> Eve
         .add_attribute(NEW_REWARDING_VALIDATOR_ADDRESS_KEY, new)
 }
 
+pub fn new_topology_signer_addition_event(identity_key: IdentityKeyRef<'_>) -> Event {
+    Event::new(MixnetEventType::TopologySignerAddition)
+        .add_attribute(NODE_IDENTITY_KEY, identity_key)
+}
+
+pub fn new_topology_signer_removal_event(identity_key: IdentityKeyRef<'_>) -> Event {
+    Event::new(MixnetEventType::TopologySignerRemoval)
+        .add_attribute(NODE_IDENTITY_KEY, identity_key)
+}
+
+pub fn new_penalty_monitor_addition_event(identity_key: IdentityKeyRef<'_>) -> Event {
+    Event::new(MixnetEventType::PenaltyMonitorAddition)
+        .add_attribute(NODE_IDENTITY_KEY, identity_key)
+}
+
+pub fn new_penalty_monitor_removal_event(identity_key: IdentityKeyRef<'_>) -> Event {
+    Event::new(MixnetEventType::PenaltyMonitorRemoval)
+        .add_attribute(NODE_IDENTITY_KEY, identity_key)
+}
+
+pub fn new_misbehaviour_evidence_submission_event(
+    evidence_id: EvidenceId,
+    mix_id: MixId,
+    monitor: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::MisbehaviourEvidenceSubmission)
+        .add_attribute(EVIDENCE_ID_KEY, evidence_id.to_string())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(MONITOR_KEY, monitor)
+}
+
+pub fn new_penalty_application_event(
+    evidence_id: EvidenceId,
+    mix_id: MixId,
+    penalty: &Penalty,
+) -> Event {
+    Event::new(MixnetEventType::PenaltyApplication)
+        .add_attribute(EVIDENCE_ID_KEY, evidence_id.to_string())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(PENALTY_KEY, format!("{:?}", penalty))
+}
+
+pub fn new_evidence_dismissal_event(evidence_id: EvidenceId, mix_id: MixId) -> Event {
+    Event::new(MixnetEventType::EvidenceDismissal)
+        .add_attribute(EVIDENCE_ID_KEY, evidence_id.to_string())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+}
+
+pub fn new_jail_release_event(mix_id: MixId) -> Event {
+    Event::new(MixnetEventType::JailRelease).add_attribute(MIX_ID_KEY, mix_id.to_string())
+}
+
+pub fn new_operator_pledge_slashing_event(
+    created_at: BlockHeight,
+    mix_id: MixId,
+    slashed_amount: &Coin,
+) -> Event {
+    Event::new(MixnetEventType::OperatorPledgeSlashing)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(SLASHED_AMOUNT_KEY, slashed_amount.to_string())
+}
+
 pub fn new_settings_update_event(
     old_params: &ContractStateParams,
     new_params: &ContractStateParams,
@@ -475,6 +750,16 @@ pub fn new_zero_uptime_mix_operator_rewarding_event(interval: Interval, mix_id:
         .add_attribute(NO_REWARD_REASON_KEY, ZERO_PERFORMANCE_VALUE)
 }
 
+pub fn new_excluded_mix_operator_rewarding_event(interval: Interval, mix_id: MixId) -> Event {
+    Event::new(MixnetEventType::MixnodeRewarding)
+        .add_attribute(
+            INTERVAL_KEY,
+            interval.current_epoch_absolute_id().to_string(),
+        )
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(NO_REWARD_REASON_KEY, EXCLUDED_FROM_REWARDED_SET_VALUE)
+}
+
 pub fn new_mix_rewarding_event(
     interval: Interval,
     mix_id: MixId,
@@ -500,6 +785,57 @@ pub fn new_mix_rewarding_event(
         )
 }
 
+pub fn new_not_found_gateway_rewarding_event(
+    interval: Interval,
+    gateway_identity: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::GatewayRewarding)
+        .add_attribute(
+            INTERVAL_KEY,
+            interval.current_epoch_absolute_id().to_string(),
+        )
+        .add_attribute(NODE_IDENTITY_KEY, gateway_identity)
+        .add_attribute(NO_REWARD_REASON_KEY, BOND_NOT_FOUND_VALUE)
+}
+
+pub fn new_zero_uptime_gateway_rewarding_event(
+    interval: Interval,
+    gateway_identity: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::GatewayRewarding)
+        .add_attribute(
+            INTERVAL_KEY,
+            interval.current_epoch_absolute_id().to_string(),
+        )
+        .add_attribute(NODE_IDENTITY_KEY, gateway_identity)
+        .add_attribute(NO_REWARD_REASON_KEY, ZERO_PERFORMANCE_VALUE)
+}
+
+pub fn new_gateway_rewarding_event(
+    interval: Interval,
+    gateway_identity: IdentityKeyRef<'_>,
+    reward_distribution: RewardDistribution,
+    prior_delegates: Decimal,
+    prior_unit_reward: Decimal,
+) -> Event {
+    Event::new(MixnetEventType::GatewayRewarding)
+        .add_attribute(
+            INTERVAL_KEY,
+            interval.current_epoch_absolute_id().to_string(),
+        )
+        .add_attribute(PRIOR_DELEGATES_KEY, prior_delegates.to_string())
+        .add_attribute(PRIOR_UNIT_REWARD_KEY, prior_unit_reward.to_string())
+        .add_attribute(NODE_IDENTITY_KEY, gateway_identity)
+        .add_attribute(
+            OPERATOR_REWARD_KEY,
+            reward_distribution.operator.to_string(),
+        )
+        .add_attribute(
+            DELEGATES_REWARD_KEY,
+            reward_distribution.delegates.to_string(),
+        )
+}
+
 pub fn new_advance_epoch_event(interval: Interval, rewarded_nodes: u32) -> Event {
     Event::new(MixnetEventType::AdvanceEpoch)
         .add_attribute(
@@ -4,8 +4,14 @@
 // due to code generated by JsonSchema
 #![allow(clippy::field_reassign_with_default)]
 
-use crate::{IdentityKey, SphinxKey};
-use cosmwasm_std::{Addr, Coin};
+use crate::constants::{TOKEN_SUPPLY, UNIT_DELEGATION_BASE};
+use crate::error::MixnetContractError;
+use crate::helpers::IntoBaseDecimal;
+use crate::reward_params::{GatewayRewardParams, Performance};
+use crate::rewarding::helpers::truncate_reward;
+use crate::rewarding::RewardDistribution;
+use crate::{EpochId, IdentityKey, SphinxKey};
+use cosmwasm_std::{Addr, Coin, Decimal, StdResult, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -145,6 +151,345 @@ pub struct GatewayBondResponse {
     pub gateway: Option<GatewayBond>,
 }
 
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export_to = "ts-packages/types/src/types/rust/GatewayConfigUpdate.ts")
+)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct GatewayConfigUpdate {
+    pub host: String,
+    pub mix_port: u16,
+    pub clients_port: u16,
+    pub location: String,
+    pub version: String,
+}
+
+impl GatewayConfigUpdate {
+    pub fn to_inline_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "serialisation failure".into())
+    }
+}
+
+/// Tokens delegated towards particular gateway.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct GatewayDelegation {
+    /// Address of the owner of this delegation.
+    pub owner: Addr,
+
+    /// Identity of the gateway this delegation was performed against.
+    pub gateway_identity: IdentityKey,
+
+    /// Value of the "unit delegation" associated with the gateway at the time of delegation.
+    #[serde(default)]
+    pub cumulative_reward_ratio: Decimal,
+
+    /// Total amount of tokens delegated towards the gateway.
+    pub amount: Coin,
+
+    /// Block height where this delegation was last modified.
+    pub height: u64,
+}
+
+impl GatewayDelegation {
+    pub fn new(
+        owner: Addr,
+        gateway_identity: IdentityKey,
+        cumulative_reward_ratio: Decimal,
+        amount: Coin,
+        height: u64,
+    ) -> Self {
+        GatewayDelegation {
+            owner,
+            gateway_identity,
+            cumulative_reward_ratio,
+            amount,
+            height,
+        }
+    }
+
+    pub fn dec_amount(&self) -> StdResult<Decimal> {
+        self.amount.amount.into_base_decimal()
+    }
+}
+
+/// Rewarding state of a gateway. It follows the same "unit delegation" approach as the mixnodes,
+/// but since gateways are not part of the rewarded set, the reward only depends on their performance
+/// and the saturation of their stake.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct GatewayRewarding {
+    /// Total pledge and compounded reward earned by the gateway operator.
+    pub operator: Decimal,
+
+    /// Total delegation and compounded reward earned by all gateway delegators.
+    pub delegates: Decimal,
+
+    /// Cumulative reward earned by the "unit delegation" since the gateway has started being rewarded.
+    pub total_unit_reward: Decimal,
+
+    /// Value of the theoretical "unit delegation" that has delegated to this gateway at the very beginning.
+    pub unit_delegation: Decimal,
+
+    /// Marks the epoch when this gateway was last rewarded so that we wouldn't accidentally attempt
+    /// to reward it multiple times in the same epoch. It's set to [`NEVER_REWARDED_EPOCH`]
+    /// until the gateway receives its first reward.
+    pub last_rewarded_epoch: EpochId,
+
+    pub unique_delegations: u32,
+}
+
+/// Value of `last_rewarded_epoch` of gateways that have never been rewarded.
+/// Unlike mixnodes, which have to wait to get into the rewarded set, all bonded gateways
+/// get rewarded at the end of every epoch, including the one they have bonded in.
+pub const NEVER_REWARDED_EPOCH: EpochId = EpochId::MAX;
+
+impl GatewayRewarding {
+    pub fn initialise_new(initial_pledge: &Coin) -> Result<Self, MixnetContractError> {
+        assert!(
+            initial_pledge.amount <= TOKEN_SUPPLY,
+            "pledge cannot be larger than the token supply"
+        );
+
+        Ok(GatewayRewarding {
+            operator: initial_pledge.amount.into_base_decimal()?,
+            delegates: Decimal::zero(),
+            total_unit_reward: Decimal::zero(),
+            unit_delegation: UNIT_DELEGATION_BASE,
+            last_rewarded_epoch: NEVER_REWARDED_EPOCH,
+            unique_delegations: 0,
+        })
+    }
+
+    /// Determines whether the gateway is still bonded. Similarly to the mixnodes, if there are no tokens left
+    /// associated with the operator, the details only exist for the purposes of calculating rewards
+    /// for delegators that have not yet removed their tokens.
+    pub fn still_bonded(&self) -> bool {
+        self.operator != Decimal::zero()
+    }
+
+    pub fn node_bond(&self) -> Decimal {
+        self.operator + self.delegates
+    }
+
+    pub fn operator_pledge_with_reward(&self, denom: impl Into<String>) -> Coin {
+        truncate_reward(self.operator, denom)
+    }
+
+    pub fn pending_operator_reward(&self, pledge: &Coin) -> Coin {
+        let reward_with_pledge = truncate_reward(self.operator, &pledge.denom);
+        Coin {
+            denom: reward_with_pledge.denom,
+            amount: reward_with_pledge.amount.saturating_sub(pledge.amount),
+        }
+    }
+
+    pub fn pending_delegator_reward(&self, delegation: &GatewayDelegation) -> StdResult<Coin> {
+        let delegator_reward = self.determine_delegation_reward(delegation)?;
+        Ok(truncate_reward(delegator_reward, &delegation.amount.denom))
+    }
+
+    /// Saturation over all the tokens staked over this gateway.
+    pub fn bond_saturation(&self, params: &GatewayRewardParams) -> Decimal {
+        // make sure our saturation is never greater than 1
+        if self.node_bond() > params.stake_saturation_point {
+            Decimal::one()
+        } else {
+            self.node_bond() / params.stake_saturation_point
+        }
+    }
+
+    pub fn node_reward(&self, params: &GatewayRewardParams, performance: Performance) -> Decimal {
+        if !params.rewarding_enabled() {
+            return Decimal::zero();
+        }
+
+        params.epoch_reward_budget * performance.value() * self.bond_saturation(params)
+    }
+
+    pub fn determine_reward_split(
+        &self,
+        node_reward: Decimal,
+        params: &GatewayRewardParams,
+    ) -> RewardDistribution {
+        if node_reward.is_zero() {
+            return RewardDistribution::default();
+        }
+
+        let profit_margin = params.profit_margin.value();
+        let operator_share = self.operator / self.node_bond();
+
+        let operator =
+            node_reward * (profit_margin + (Decimal::one() - profit_margin) * operator_share);
+        let delegates = node_reward - operator;
+
+        RewardDistribution {
+            operator,
+            delegates,
+        }
+    }
+
+    pub fn calculate_epoch_reward(
+        &self,
+        params: &GatewayRewardParams,
+        performance: Performance,
+    ) -> RewardDistribution {
+        let node_reward = self.node_reward(params, performance);
+        self.determine_reward_split(node_reward, params)
+    }
+
+    pub fn distribute_rewards(
+        &mut self,
+        distribution: RewardDistribution,
+        absolute_epoch_id: EpochId,
+    ) {
+        let unit_delegation_reward = distribution.delegates
+            * self.delegator_share(self.unit_delegation + self.total_unit_reward);
+
+        self.operator += distribution.operator;
+        self.delegates += distribution.delegates;
+
+        self.total_unit_reward += unit_delegation_reward;
+        self.last_rewarded_epoch = absolute_epoch_id;
+    }
+
+    pub fn determine_delegation_reward(
+        &self,
+        delegation: &GatewayDelegation,
+    ) -> StdResult<Decimal> {
+        let starting_ratio = delegation.cumulative_reward_ratio;
+        let ending_ratio = self.full_reward_ratio();
+        let adjust = starting_ratio + self.unit_delegation;
+
+        Ok((ending_ratio - starting_ratio) * delegation.dec_amount()? / adjust)
+    }
+
+    pub fn increase_operator_uint128(
+        &mut self,
+        amount: Uint128,
+    ) -> Result<(), MixnetContractError> {
+        self.operator += amount.into_base_decimal()?;
+        Ok(())
+    }
+
+    pub fn decrease_operator_uint128(
+        &mut self,
+        amount: Uint128,
+    ) -> Result<(), MixnetContractError> {
+        let amount_decimal = amount.into_base_decimal()?;
+        if self.operator < amount_decimal {
+            return Err(MixnetContractError::OverflowDecimalSubtraction {
+                minuend: self.operator,
+                subtrahend: amount_decimal,
+            });
+        }
+        self.operator -= amount_decimal;
+        Ok(())
+    }
+
+    // this updates `unique_delegations` field
+    pub fn add_base_delegation(&mut self, amount: Uint128) -> Result<(), MixnetContractError> {
+        self.delegates += amount.into_base_decimal()?;
+        self.unique_delegations += 1;
+        Ok(())
+    }
+
+    pub fn decrease_delegates_decimal(
+        &mut self,
+        amount: Decimal,
+    ) -> Result<(), MixnetContractError> {
+        if self.delegates < amount {
+            return Err(MixnetContractError::OverflowDecimalSubtraction {
+                minuend: self.delegates,
+                subtrahend: amount,
+            });
+        }
+
+        self.delegates -= amount;
+        Ok(())
+    }
+
+    // this updates `unique_delegations` field
+    pub fn remove_delegation_decimal(
+        &mut self,
+        amount: Decimal,
+    ) -> Result<(), MixnetContractError> {
+        self.decrease_delegates_decimal(amount)?;
+        if self.unique_delegations == 0 {
+            return Err(MixnetContractError::OverflowSubtraction {
+                minuend: 0,
+                subtrahend: 1,
+            });
+        }
+        self.unique_delegations -= 1;
+
+        // if this was last delegation, move all leftover decimal tokens to the operator
+        // (this is literally in the order of a millionth of a micronym)
+        if self.unique_delegations == 0 {
+            self.operator += self.delegates;
+            self.delegates = Decimal::zero();
+        }
+        Ok(())
+    }
+
+    pub fn undelegate(
+        &mut self,
+        delegation: &GatewayDelegation,
+    ) -> Result<Coin, MixnetContractError> {
+        let reward = self.determine_delegation_reward(delegation)?;
+        let full_amount = reward + delegation.dec_amount()?;
+        self.remove_delegation_decimal(full_amount)?;
+        Ok(truncate_reward(full_amount, &delegation.amount.denom))
+    }
+
+    pub fn full_reward_ratio(&self) -> Decimal {
+        self.total_unit_reward
+    }
+
+    pub fn delegator_share(&self, amount: Decimal) -> Decimal {
+        if self.delegates.is_zero() {
+            Decimal::zero()
+        } else {
+            amount / self.delegates
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct GatewayRewardingDetailsResponse {
+    pub identity: IdentityKey,
+    pub rewarding_details: Option<GatewayRewarding>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct PagedGatewayDelegationsResponse {
+    pub delegations: Vec<GatewayDelegation>,
+    pub start_next_after: Option<String>,
+}
+
+impl PagedGatewayDelegationsResponse {
+    pub fn new(delegations: Vec<GatewayDelegation>, start_next_after: Option<String>) -> Self {
+        PagedGatewayDelegationsResponse {
+            delegations,
+            start_next_after,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct PagedDelegatorGatewayDelegationsResponse {
+    pub delegations: Vec<GatewayDelegation>,
+    pub start_next_after: Option<IdentityKey>,
+}
+
+impl PagedDelegatorGatewayDelegationsResponse {
+    pub fn new(delegations: Vec<GatewayDelegation>, start_next_after: Option<IdentityKey>) -> Self {
+        PagedDelegatorGatewayDelegationsResponse {
+            delegations,
+            start_next_after,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -14,6 +14,7 @@ pub mod helpers;
 mod interval;
 pub mod mixnode;
 mod msg;
+pub mod penalties;
 pub mod pending_events;
 pub mod reward_params;
 pub mod rewarding;
@@ -26,7 +27,10 @@ pub use delegation::{
     PagedMixNodeDelegationsResponse,
 };
 pub use gateway::{
-    Gateway, GatewayBond, GatewayBondResponse, GatewayOwnershipResponse, PagedGatewayResponse,
+    Gateway, GatewayBond, GatewayBondResponse, GatewayConfigUpdate, GatewayDelegation,
+    GatewayOwnershipResponse, GatewayRewarding, GatewayRewardingDetailsResponse,
+    PagedDelegatorGatewayDelegationsResponse, PagedGatewayDelegationsResponse,
+    PagedGatewayResponse,
 };
 pub use interval::{
     CurrentIntervalResponse, Interval, PendingEpochEventsResponse, PendingIntervalEventsResponse,
@@ -41,5 +45,7 @@ pub use pending_events::{
     PendingEpochEvent, PendingEpochEventData, PendingEpochEventKind, PendingIntervalEvent,
     PendingIntervalEventData, PendingIntervalEventKind,
 };
-pub use reward_params::{IntervalRewardParams, IntervalRewardingParamsUpdate, RewardingParams};
+pub use reward_params::{
+    GatewayRewardParams, IntervalRewardParams, IntervalRewardingParamsUpdate, RewardingParams,
+};
 pub use types::*;
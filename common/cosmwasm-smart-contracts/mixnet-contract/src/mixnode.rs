@@ -144,7 +144,7 @@ impl MixNodeRewarding {
         let initial_dec = original_pledge.amount.into_base_decimal()?;
         if initial_dec > self.operator {
             panic!(
-                "seems slashing has occurred while it has not been implemented nor accounted for!"
+                "the operator stake fell below its original pledge. slashing must always reduce both of them!"
             )
         }
         Ok(self.operator - initial_dec)
@@ -166,7 +166,7 @@ impl MixNodeRewarding {
         let initial_dec = original_pledge.amount.into_base_decimal()?;
         if initial_dec > self.operator {
             panic!(
-                "seems slashing has occurred while it has not been implemented nor accounted for!"
+                "the operator stake fell below its original pledge. slashing must always reduce both of them!"
             )
         }
         let diff = self.operator - initial_dec;
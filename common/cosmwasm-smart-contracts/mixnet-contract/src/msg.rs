@@ -5,12 +5,14 @@ use crate::delegation::OwnerProxySubKey;
 use crate::error::MixnetContractError;
 use crate::helpers::IntoBaseDecimal;
 use crate::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
+use crate::penalties::{EvidenceId, MisbehaviourEvidence, Penalty};
 use crate::reward_params::{
-    IntervalRewardParams, IntervalRewardingParamsUpdate, Performance, RewardingParams,
+    GatewayRewardParams, IntervalRewardParams, IntervalRewardingParamsUpdate, Performance,
+    RewardingParams,
 };
 use crate::{delegation, ContractStateParams, Layer, LayerAssignment, MixId, Percent};
-use crate::{Gateway, IdentityKey, MixNode};
-use cosmwasm_std::Decimal;
+use crate::{Gateway, GatewayConfigUpdate, IdentityKey, MixNode};
+use cosmwasm_std::{Coin, Decimal};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -40,6 +42,13 @@ pub struct InitialRewardingParams {
 
     pub rewarded_set_size: u32,
     pub active_set_size: u32,
+
+    #[serde(default)]
+    pub gateway_interval_pool_emission: Percent,
+    #[serde(default)]
+    pub nominal_gateways: u32,
+    #[serde(default)]
+    pub gateway_profit_margin: Percent,
 }
 
 impl InitialRewardingParams {
@@ -52,6 +61,14 @@ impl InitialRewardingParams {
             * self.interval_pool_emission;
         let stake_saturation_point =
             self.initial_staking_supply / self.rewarded_set_size.into_base_decimal()?;
+        let gateway = GatewayRewardParams::new(
+            self.gateway_interval_pool_emission,
+            self.nominal_gateways,
+            self.gateway_profit_margin,
+            self.initial_reward_pool,
+            self.initial_staking_supply,
+            epochs_in_interval,
+        )?;
 
         Ok(RewardingParams {
             interval: IntervalRewardParams {
@@ -66,6 +83,7 @@ impl InitialRewardingParams {
             },
             rewarded_set_size: self.rewarded_set_size,
             active_set_size: self.active_set_size,
+            gateway,
         })
     }
 }
@@ -125,6 +143,12 @@ pub enum ExecuteMsg {
     UpdateContractStateParams {
         updated_parameters: ContractStateParams,
     },
+    AddTopologySigner {
+        identity_key: IdentityKey,
+    },
+    RemoveTopologySigner {
+        identity_key: IdentityKey,
+    },
     UpdateActiveSetSize {
         active_set_size: u32,
         force_immediately: bool,
@@ -196,6 +220,24 @@ pub enum ExecuteMsg {
     UnbondGatewayOnBehalf {
         owner: String,
     },
+    PledgeMoreGateway {},
+    PledgeMoreGatewayOnBehalf {
+        owner: String,
+    },
+    DecreaseGatewayPledge {
+        decrease_by: Coin,
+    },
+    DecreaseGatewayPledgeOnBehalf {
+        owner: String,
+        decrease_by: Coin,
+    },
+    UpdateGatewayConfig {
+        new_config: GatewayConfigUpdate,
+    },
+    UpdateGatewayConfigOnBehalf {
+        new_config: GatewayConfigUpdate,
+        owner: String,
+    },
 
     // delegation-related:
     DelegateToMixnode {
@@ -212,12 +254,33 @@ pub enum ExecuteMsg {
         mix_id: MixId,
         delegate: String,
     },
+    RedelegateFromMixnode {
+        from: MixId,
+        to: MixId,
+        amount: Coin,
+    },
+    RedelegateFromMixnodeOnBehalf {
+        from: MixId,
+        to: MixId,
+        amount: Coin,
+        delegate: String,
+    },
+    DelegateToGateway {
+        gateway_identity: IdentityKey,
+    },
+    UndelegateFromGateway {
+        gateway_identity: IdentityKey,
+    },
 
     // reward-related
     RewardMixnode {
         mix_id: MixId,
         performance: Performance,
     },
+    RewardGateway {
+        gateway_identity: IdentityKey,
+        performance: Performance,
+    },
     WithdrawOperatorReward {},
     WithdrawOperatorRewardOnBehalf {
         owner: String,
@@ -230,6 +293,30 @@ pub enum ExecuteMsg {
         owner: String,
     },
 
+    // penalty-related
+    AddPenaltyMonitor {
+        identity_key: IdentityKey,
+    },
+    RemovePenaltyMonitor {
+        identity_key: IdentityKey,
+    },
+    /// Anyone can relay the evidence as long as it has been signed by one of the penalty monitors
+    SubmitMisbehaviourEvidence {
+        evidence: MisbehaviourEvidence,
+        monitor_identity: IdentityKey,
+        signature: String,
+    },
+    ApplyPenalty {
+        evidence_id: EvidenceId,
+        penalty: Penalty,
+    },
+    DismissEvidence {
+        evidence_id: EvidenceId,
+    },
+    ReleaseFromJail {
+        mix_id: MixId,
+    },
+
     // testing-only
     #[cfg(feature = "contract-testing")]
     TestingResolveAllPendingEvents {
@@ -269,6 +356,12 @@ impl ExecuteMsg {
             ExecuteMsg::UpdateContractStateParams { .. } => {
                 "updating mixnet state parameters".into()
             }
+            ExecuteMsg::AddTopologySigner { identity_key } => {
+                format!("adding {} as a topology signer", identity_key)
+            }
+            ExecuteMsg::RemoveTopologySigner { identity_key } => {
+                format!("removing {} from topology signers", identity_key)
+            }
             ExecuteMsg::UpdateActiveSetSize {
                 active_set_size,
                 force_immediately,
@@ -316,6 +409,20 @@ impl ExecuteMsg {
             }
             ExecuteMsg::UnbondGateway { .. } => "unbonding gateway".into(),
             ExecuteMsg::UnbondGatewayOnBehalf { .. } => "unbonding gateway on behalf".into(),
+            ExecuteMsg::PledgeMoreGateway {} => "pledging additional tokens to gateway".into(),
+            ExecuteMsg::PledgeMoreGatewayOnBehalf { .. } => {
+                "pledging additional tokens to gateway on behalf".into()
+            }
+            ExecuteMsg::DecreaseGatewayPledge { decrease_by } => {
+                format!("decreasing gateway pledge by {}", decrease_by)
+            }
+            ExecuteMsg::DecreaseGatewayPledgeOnBehalf { decrease_by, .. } => {
+                format!("decreasing gateway pledge by {} on behalf", decrease_by)
+            }
+            ExecuteMsg::UpdateGatewayConfig { .. } => "updating gateway configuration".into(),
+            ExecuteMsg::UpdateGatewayConfigOnBehalf { .. } => {
+                "updating gateway configuration on behalf".into()
+            }
             ExecuteMsg::DelegateToMixnode { mix_id } => format!("delegating to mixnode {}", mix_id),
             ExecuteMsg::DelegateToMixnodeOnBehalf { mix_id, .. } => {
                 format!("delegating to mixnode {} on behalf", mix_id)
@@ -326,6 +433,24 @@ impl ExecuteMsg {
             ExecuteMsg::UndelegateFromMixnodeOnBehalf { mix_id, .. } => {
                 format!("removing delegation from mixnode {} on behalf", mix_id)
             }
+            ExecuteMsg::RedelegateFromMixnode { from, to, amount } => {
+                format!(
+                    "redelegating {} from mixnode {} to mixnode {}",
+                    amount, from, to
+                )
+            }
+            ExecuteMsg::RedelegateFromMixnodeOnBehalf {
+                from, to, amount, ..
+            } => format!(
+                "redelegating {} from mixnode {} to mixnode {} on behalf",
+                amount, from, to
+            ),
+            ExecuteMsg::DelegateToGateway { gateway_identity } => {
+                format!("delegating to gateway {}", gateway_identity)
+            }
+            ExecuteMsg::UndelegateFromGateway { gateway_identity } => {
+                format!("removing delegation from gateway {}", gateway_identity)
+            }
             ExecuteMsg::RewardMixnode {
                 mix_id,
                 performance,
@@ -333,6 +458,13 @@ impl ExecuteMsg {
                 "rewarding mixnode {} for performance {}",
                 mix_id, performance
             ),
+            ExecuteMsg::RewardGateway {
+                gateway_identity,
+                performance,
+            } => format!(
+                "rewarding gateway {} for performance {}",
+                gateway_identity, performance
+            ),
             ExecuteMsg::WithdrawOperatorReward { .. } => "withdrawing operator reward".into(),
             ExecuteMsg::WithdrawOperatorRewardOnBehalf { .. } => {
                 "withdrawing operator reward on behalf".into()
@@ -344,6 +476,25 @@ impl ExecuteMsg {
                 "withdrawing delegator reward from mixnode {} on behalf",
                 mix_id
             ),
+            ExecuteMsg::AddPenaltyMonitor { identity_key } => {
+                format!("adding {} as a penalty monitor", identity_key)
+            }
+            ExecuteMsg::RemovePenaltyMonitor { identity_key } => {
+                format!("removing {} from penalty monitors", identity_key)
+            }
+            ExecuteMsg::SubmitMisbehaviourEvidence { evidence, .. } => format!(
+                "submitting misbehaviour evidence against mixnode {}",
+                evidence.mix_id
+            ),
+            ExecuteMsg::ApplyPenalty { evidence_id, .. } => {
+                format!("applying penalty for evidence {}", evidence_id)
+            }
+            ExecuteMsg::DismissEvidence { evidence_id } => {
+                format!("dismissing evidence {}", evidence_id)
+            }
+            ExecuteMsg::ReleaseFromJail { mix_id } => {
+                format!("releasing mixnode {} from jail", mix_id)
+            }
             #[cfg(feature = "contract-testing")]
             ExecuteMsg::TestingResolveAllPendingEvents { .. } => {
                 "resolving all pending events".into()
@@ -381,6 +532,7 @@ pub enum QueryMsg {
     GetRewardingValidatorAddress {},
     GetStateParams {},
     GetState {},
+    GetTopologySigners {},
     GetRewardingParams {},
     GetCurrentIntervalDetails {},
     GetRewardedSet {
@@ -470,6 +622,20 @@ pub enum QueryMsg {
         start_after: Option<delegation::StorageKey>,
         limit: Option<u32>,
     },
+    // gets all [paged] delegations associated with particular gateway
+    GetGatewayDelegations {
+        gateway_identity: IdentityKey,
+        // since `start_after` is user-provided input, we can't use `Addr` as we
+        // can't guarantee it's validated.
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // gets all [paged] gateway delegations associated with particular delegator
+    GetDelegatorGatewayDelegations {
+        delegator: String,
+        start_after: Option<IdentityKey>,
+        limit: Option<u32>,
+    },
 
     // rewards related
     GetPendingOperatorReward {
@@ -494,6 +660,16 @@ pub enum QueryMsg {
         proxy: Option<String>,
         estimated_performance: Performance,
     },
+    GetGatewayRewardingDetails {
+        gateway_identity: IdentityKey,
+    },
+    GetPendingGatewayOperatorReward {
+        gateway_identity: IdentityKey,
+    },
+    GetPendingGatewayDelegatorReward {
+        address: String,
+        gateway_identity: IdentityKey,
+    },
 
     // interval-related
     GetPendingEpochEvents {
@@ -504,6 +680,20 @@ pub enum QueryMsg {
         limit: Option<u32>,
         start_after: Option<u32>,
     },
+
+    // penalty-related
+    GetPenaltyMonitors {},
+    GetMisbehaviourEvidence {
+        evidence_id: EvidenceId,
+    },
+    GetMisbehaviourEvidencePaged {
+        limit: Option<u32>,
+        start_after: Option<EvidenceId>,
+    },
+    GetExcludedMixnodes {
+        limit: Option<u32>,
+        start_after: Option<MixId>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
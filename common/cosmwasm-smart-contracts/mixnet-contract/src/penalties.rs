@@ -0,0 +1,187 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{BlockHeight, EpochId, IdentityKey, MixId, Percent};
+use cosmwasm_std::StdResult;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub type EvidenceId = u32;
+
+/// Specific type of misbehaviour a monitor has observed.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MisbehaviourKind {
+    /// The node has been measured with zero performance in every monitor run
+    /// between (and including) the specified absolute epochs.
+    PersistentZeroPerformance {
+        first_epoch: EpochId,
+        last_epoch: EpochId,
+        monitor_runs: u32,
+    },
+
+    /// The identity key of the node has been observed being announced by a different host.
+    DuplicateIdentityKey { observed_host: String },
+}
+
+/// Evidence of misbehaviour of particular mixnode as signed by one of the authorised monitors.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, JsonSchema)]
+pub struct MisbehaviourEvidence {
+    /// Identifier chosen by the monitor that must be unique among all the evidence it has ever signed.
+    /// The contract remembers it even after the evidence gets resolved so that it couldn't be replayed.
+    pub nonce: u64,
+
+    pub mix_id: MixId,
+
+    /// Identity key of the node at the time of observing the misbehaviour.
+    pub identity_key: IdentityKey,
+
+    pub kind: MisbehaviourKind,
+}
+
+impl MisbehaviourEvidence {
+    /// Bytes the monitor is expected to sign with its ed25519 identity key.
+    pub fn signable_bytes(&self) -> StdResult<Vec<u8>> {
+        cosmwasm_std::to_vec(self)
+    }
+}
+
+/// Penalty that might get applied to a mixnode after its misbehaviour has been proven.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Penalty {
+    /// The node is excluded from the rewarded set until it's explicitly released from jail.
+    Jail,
+
+    /// The node is excluded from the rewarded set for the specified number of epochs.
+    ExcludeFromRewardedSet { epochs: u32 },
+
+    /// The specified portion of the operator pledge is taken away at the end of the current epoch.
+    /// Note that delegations towards the node are never affected.
+    SlashPledge { fraction: Percent },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceStatus {
+    /// The evidence is waiting to be reviewed.
+    Pending,
+
+    /// The evidence has been accepted and the specified penalty got applied.
+    Penalised { penalty: Penalty },
+
+    /// The evidence has been reviewed and rejected.
+    Dismissed,
+
+    /// The evidence has not been reviewed before its expiry.
+    Expired,
+}
+
+impl EvidenceStatus {
+    pub fn is_pending(&self) -> bool {
+        matches!(self, EvidenceStatus::Pending)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, JsonSchema)]
+pub struct EvidenceRecord {
+    pub id: EvidenceId,
+    pub evidence: MisbehaviourEvidence,
+
+    /// Identity key of the monitor that has signed the evidence.
+    pub monitor: IdentityKey,
+    pub signature: String,
+
+    pub submitted_at: BlockHeight,
+
+    /// Height at which the evidence expires if it has not been reviewed by then.
+    pub expires_at: BlockHeight,
+    pub status: EvidenceStatus,
+}
+
+impl EvidenceRecord {
+    pub fn is_expired(&self, current_height: BlockHeight) -> bool {
+        current_height >= self.expires_at
+    }
+}
+
+/// Reason for the mixnode not being allowed in the rewarded set.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardedSetExclusion {
+    /// The node has been jailed during the specified absolute epoch and it has not been released since.
+    Jailed { since_epoch: EpochId },
+
+    /// The node is excluded up until (but not including) the specified absolute epoch.
+    Until { epoch: EpochId },
+}
+
+impl RewardedSetExclusion {
+    pub fn is_active(&self, absolute_epoch_id: EpochId) -> bool {
+        match self {
+            RewardedSetExclusion::Jailed { .. } => true,
+            RewardedSetExclusion::Until { epoch } => absolute_epoch_id < *epoch,
+        }
+    }
+}
+
+/// Identity keys of the monitors trusted to sign misbehaviour evidence.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, JsonSchema)]
+pub struct PenaltyMonitorsResponse {
+    pub monitors: Vec<IdentityKey>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, JsonSchema)]
+pub struct PagedEvidenceResponse {
+    pub evidence: Vec<EvidenceRecord>,
+    pub start_next_after: Option<EvidenceId>,
+}
+
+impl PagedEvidenceResponse {
+    pub fn new(evidence: Vec<EvidenceRecord>, start_next_after: Option<EvidenceId>) -> Self {
+        PagedEvidenceResponse {
+            evidence,
+            start_next_after,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, JsonSchema)]
+pub struct ExcludedMixnode {
+    pub mix_id: MixId,
+    pub exclusion: RewardedSetExclusion,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, JsonSchema)]
+pub struct PagedExcludedMixnodesResponse {
+    pub nodes: Vec<ExcludedMixnode>,
+    pub start_next_after: Option<MixId>,
+}
+
+impl PagedExcludedMixnodesResponse {
+    pub fn new(nodes: Vec<ExcludedMixnode>, start_next_after: Option<MixId>) -> Self {
+        PagedExcludedMixnodesResponse {
+            nodes,
+            start_next_after,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exclusion_activity() {
+        let jailed = RewardedSetExclusion::Jailed { since_epoch: 10 };
+        assert!(jailed.is_active(5));
+        assert!(jailed.is_active(10));
+        assert!(jailed.is_active(1000));
+
+        let temporary = RewardedSetExclusion::Until { epoch: 10 };
+        assert!(temporary.is_active(5));
+        assert!(temporary.is_active(9));
+        assert!(!temporary.is_active(10));
+        assert!(!temporary.is_active(11));
+    }
+}
@@ -3,7 +3,7 @@
 
 use crate::mixnode::MixNodeCostParams;
 use crate::reward_params::IntervalRewardingParamsUpdate;
-use crate::{BlockHeight, EpochEventId, IntervalEventId, MixId};
+use crate::{BlockHeight, EpochEventId, IdentityKey, IntervalEventId, MixId, Percent};
 use cosmwasm_std::{Addr, Coin};
 use serde::{Deserialize, Serialize};
 
@@ -34,6 +34,15 @@ pub enum PendingEpochEventKind {
         mix_id: MixId,
         proxy: Option<Addr>,
     },
+    // the amount might include the rewards accumulated on the source node, i.e. it's not bounded by
+    // the original delegation
+    Redelegate {
+        owner: Addr,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        proxy: Option<Addr>,
+    },
     PledgeMore {
         mix_id: MixId,
         amount: Coin,
@@ -41,9 +50,31 @@ pub enum PendingEpochEventKind {
     UnbondMixnode {
         mix_id: MixId,
     },
+    // the result of an approved penalty; only ever touches the operator's share of the stake
+    SlashOperatorPledge {
+        mix_id: MixId,
+        fraction: Percent,
+    },
     UpdateActiveSetSize {
         new_size: u32,
     },
+    PledgeMoreGateway {
+        identity: IdentityKey,
+        amount: Coin,
+    },
+    DecreaseGatewayPledge {
+        identity: IdentityKey,
+        decrease_by: Coin,
+    },
+    DelegateToGateway {
+        owner: Addr,
+        gateway_identity: IdentityKey,
+        amount: Coin,
+    },
+    UndelegateFromGateway {
+        owner: Addr,
+        gateway_identity: IdentityKey,
+    },
 }
 
 impl PendingEpochEventKind {
@@ -74,6 +74,92 @@ impl IntervalRewardParams {
     }
 }
 
+/// Parameters required for rewarding the gateways. Unlike mixnodes, gateways are not part of the rewarded set,
+/// so every bonded gateway is eligible for rewards proportional to its performance and stake.
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export_to = "ts-packages/types/src/types/rust/GatewayRewardParams.ts")
+)]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, PartialOrd, Serialize, JsonSchema,
+)]
+pub struct GatewayRewardParams {
+    // default: 0% (i.e. gateway rewarding is disabled)
+    /// Current maximum interval pool emission towards the gateways.
+    /// Assuming the nominal number of gateways are fully saturated and have 100% performance,
+    /// this % of the reward pool would get distributed in rewards to all gateway operators and their delegators.
+    /// Note that it's on top of the emission for the mixnodes.
+    #[cfg_attr(feature = "generate-ts", ts(type = "string"))]
+    pub interval_pool_emission: Percent,
+
+    /// Number of gateways the gateway reward budget is designed to be distributed between.
+    pub nominal_gateways: u32,
+
+    /// Portion of the gateway profit claimed by the operator before the rest is split
+    /// proportionally to the stake between the operator and the delegators.
+    #[cfg_attr(feature = "generate-ts", ts(type = "string"))]
+    pub profit_margin: Percent,
+
+    // computed values
+    /// Current value of the computed reward budget per epoch, per gateway.
+    /// It is expected to be constant throughout the interval.
+    #[cfg_attr(feature = "generate-ts", ts(type = "string"))]
+    pub epoch_reward_budget: Decimal,
+
+    /// Current value of the gateway stake saturation point.
+    /// It is expected to be constant throughout the interval.
+    #[cfg_attr(feature = "generate-ts", ts(type = "string"))]
+    pub stake_saturation_point: Decimal,
+}
+
+impl GatewayRewardParams {
+    pub fn new(
+        interval_pool_emission: Percent,
+        nominal_gateways: u32,
+        profit_margin: Percent,
+        reward_pool: Decimal,
+        staking_supply: Decimal,
+        epochs_in_interval: u32,
+    ) -> Result<Self, MixnetContractError> {
+        let mut params = GatewayRewardParams {
+            interval_pool_emission,
+            nominal_gateways,
+            profit_margin,
+            epoch_reward_budget: Decimal::zero(),
+            stake_saturation_point: Decimal::zero(),
+        };
+        params.recompute(reward_pool, staking_supply, epochs_in_interval)?;
+        Ok(params)
+    }
+
+    /// Determines whether gateways are going to receive any rewards at all.
+    pub fn rewarding_enabled(&self) -> bool {
+        !self.epoch_reward_budget.is_zero() && !self.stake_saturation_point.is_zero()
+    }
+
+    pub fn recompute(
+        &mut self,
+        reward_pool: Decimal,
+        staking_supply: Decimal,
+        epochs_in_interval: u32,
+    ) -> Result<(), MixnetContractError> {
+        // no gateways are expected, so there's nothing to distribute
+        if self.nominal_gateways == 0 {
+            self.epoch_reward_budget = Decimal::zero();
+            self.stake_saturation_point = Decimal::zero();
+            return Ok(());
+        }
+
+        let nominal_gateways = self.nominal_gateways.into_base_decimal()?;
+        self.epoch_reward_budget = reward_pool / epochs_in_interval.into_base_decimal()?
+            * self.interval_pool_emission
+            / nominal_gateways;
+        self.stake_saturation_point = staking_supply / nominal_gateways;
+        Ok(())
+    }
+}
+
 #[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
 #[cfg_attr(
     feature = "generate-ts",
@@ -90,6 +176,10 @@ pub struct RewardingParams {
     // very strongly related to each other.
     pub rewarded_set_size: u32,
     pub active_set_size: u32,
+
+    /// Parameters used for rewarding the gateways.
+    #[serde(default)]
+    pub gateway: GatewayRewardParams,
 }
 
 impl RewardingParams {
@@ -135,10 +225,20 @@ impl RewardingParams {
         // the unwrap here is fine as we're guaranteed an `u32` is going to fit in a Decimal
         // with 0 decimal places
         #[allow(clippy::unwrap_used)]
-        let new_epochs_in_interval = new_epochs_in_interval.into_base_decimal().unwrap();
+        let epochs_in_interval = new_epochs_in_interval.into_base_decimal().unwrap();
+
+        self.interval.epoch_reward_budget =
+            self.interval.reward_pool / epochs_in_interval * self.interval.interval_pool_emission;
 
-        self.interval.epoch_reward_budget = self.interval.reward_pool / new_epochs_in_interval
-            * self.interval.interval_pool_emission;
+        // and for the same reason this unwrap is also fine
+        #[allow(clippy::unwrap_used)]
+        self.gateway
+            .recompute(
+                self.interval.reward_pool,
+                self.interval.staking_supply,
+                new_epochs_in_interval,
+            )
+            .unwrap();
     }
 
     pub fn try_change_active_set_size(
@@ -168,14 +268,17 @@ impl RewardingParams {
 
         let mut recompute_epoch_budget = false;
         let mut recompute_saturation_point = false;
+        let mut recompute_gateway_params = false;
 
         if let Some(reward_pool) = updates.reward_pool {
             recompute_epoch_budget = true;
+            recompute_gateway_params = true;
             self.interval.reward_pool = reward_pool;
         }
 
         if let Some(staking_supply) = updates.staking_supply {
             recompute_saturation_point = true;
+            recompute_gateway_params = true;
             self.interval.staking_supply = staking_supply;
         }
 
@@ -208,6 +311,20 @@ impl RewardingParams {
             self.rewarded_set_size = rewarded_set_size;
         }
 
+        if let Some(gateway_interval_pool_emission) = updates.gateway_interval_pool_emission {
+            recompute_gateway_params = true;
+            self.gateway.interval_pool_emission = gateway_interval_pool_emission;
+        }
+
+        if let Some(nominal_gateways) = updates.nominal_gateways {
+            recompute_gateway_params = true;
+            self.gateway.nominal_gateways = nominal_gateways;
+        }
+
+        if let Some(gateway_profit_margin) = updates.gateway_profit_margin {
+            self.gateway.profit_margin = gateway_profit_margin;
+        }
+
         if recompute_epoch_budget {
             self.interval.epoch_reward_budget = self.interval.reward_pool
                 / epochs_in_interval.into_base_decimal()?
@@ -219,6 +336,14 @@ impl RewardingParams {
                 self.interval.staking_supply / self.rewarded_set_size.into_base_decimal()?
         }
 
+        if recompute_gateway_params {
+            self.gateway.recompute(
+                self.interval.reward_pool,
+                self.interval.staking_supply,
+                epochs_in_interval,
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -267,6 +392,14 @@ pub struct IntervalRewardingParamsUpdate {
     pub interval_pool_emission: Option<Percent>,
 
     pub rewarded_set_size: Option<u32>,
+
+    #[cfg_attr(feature = "generate-ts", ts(type = "string | null"))]
+    pub gateway_interval_pool_emission: Option<Percent>,
+
+    pub nominal_gateways: Option<u32>,
+
+    #[cfg_attr(feature = "generate-ts", ts(type = "string | null"))]
+    pub gateway_profit_margin: Option<Percent>,
 }
 
 impl IntervalRewardingParamsUpdate {
@@ -279,6 +412,9 @@ impl IntervalRewardingParamsUpdate {
             || self.active_set_work_factor.is_some()
             || self.interval_pool_emission.is_some()
             || self.rewarded_set_size.is_some()
+            || self.gateway_interval_pool_emission.is_some()
+            || self.nominal_gateways.is_some()
+            || self.gateway_profit_margin.is_some()
     }
 
     pub fn to_inline_json(&self) -> String {
@@ -3,19 +3,23 @@
 
 use crate::error::MixnetContractError;
 use crate::helpers::IntoBaseDecimal;
-use crate::reward_params::NodeRewardParams;
+use crate::reward_params::{NodeRewardParams, Performance};
+use crate::rewarding::simulator::simulated_gateway::SimulatedGateway;
 use crate::rewarding::simulator::simulated_node::SimulatedNode;
 use crate::rewarding::RewardDistribution;
 use crate::{
-    Delegation, Interval, IntervalRewardParams, MixId, MixNodeCostParams, RewardingParams,
+    Delegation, IdentityKey, Interval, IntervalRewardParams, MixId, MixNodeCostParams,
+    RewardingParams,
 };
 use cosmwasm_std::{Coin, Decimal};
 use std::collections::BTreeMap;
 
+pub mod simulated_gateway;
 pub mod simulated_node;
 
 pub struct Simulator {
     pub nodes: BTreeMap<MixId, SimulatedNode>,
+    pub gateways: BTreeMap<IdentityKey, SimulatedGateway>,
     pub system_rewarding_params: RewardingParams,
     pub interval: Interval,
 
@@ -27,6 +31,7 @@ impl Simulator {
     pub fn new(system_rewarding_params: RewardingParams, interval: Interval) -> Self {
         Simulator {
             nodes: Default::default(),
+            gateways: Default::default(),
             system_rewarding_params,
             interval,
             next_mix_id: 0,
@@ -56,6 +61,13 @@ impl Simulator {
                     .rewarded_set_size
                     .into_base_decimal()?;
 
+            let mut gateway = self.system_rewarding_params.gateway;
+            gateway.recompute(
+                reward_pool,
+                staking_supply,
+                self.interval.epochs_in_interval(),
+            )?;
+
             let updated_params = RewardingParams {
                 interval: IntervalRewardParams {
                     reward_pool,
@@ -69,6 +81,7 @@ impl Simulator {
                 },
                 rewarded_set_size: self.system_rewarding_params.rewarded_set_size,
                 active_set_size: self.system_rewarding_params.active_set_size,
+                gateway,
             };
 
             self.system_rewarding_params = updated_params;
@@ -128,6 +141,44 @@ impl Simulator {
         node.undelegate(delegator)
     }
 
+    pub fn bond_gateway<S: Into<IdentityKey>>(
+        &mut self,
+        identity: S,
+        pledge: Coin,
+    ) -> Result<(), MixnetContractError> {
+        let identity = identity.into();
+        let gateway = SimulatedGateway::new(identity.clone(), &pledge)?;
+        self.gateways.insert(identity, gateway);
+        Ok(())
+    }
+
+    pub fn delegate_to_gateway<S: Into<String>>(
+        &mut self,
+        delegator: S,
+        delegation: Coin,
+        identity: &str,
+    ) -> Result<(), MixnetContractError> {
+        let gateway = self.gateways.get_mut(identity).ok_or_else(|| {
+            MixnetContractError::GatewayBondNotFound {
+                identity: identity.to_string(),
+            }
+        })?;
+        gateway.delegate(delegator, delegation)
+    }
+
+    pub fn undelegate_from_gateway<S: Into<String>>(
+        &mut self,
+        delegator: S,
+        identity: &str,
+    ) -> Result<(Coin, Coin), MixnetContractError> {
+        let gateway = self.gateways.get_mut(identity).ok_or_else(|| {
+            MixnetContractError::GatewayBondNotFound {
+                identity: identity.to_string(),
+            }
+        })?;
+        gateway.undelegate(delegator)
+    }
+
     pub fn simulate_epoch_single_node(
         &mut self,
         params: NodeRewardParams,
@@ -150,6 +201,23 @@ impl Simulator {
         &mut self,
         node_params: &BTreeMap<MixId, NodeRewardParams>,
     ) -> Result<BTreeMap<MixId, RewardDistribution>, MixnetContractError> {
+        let (dist, _) = self.simulate_epoch_with_gateways(node_params, &BTreeMap::new())?;
+        Ok(dist)
+    }
+
+    // gateways without provided performance are treated as if they had 0 performance
+    #[allow(clippy::type_complexity)]
+    pub fn simulate_epoch_with_gateways(
+        &mut self,
+        node_params: &BTreeMap<MixId, NodeRewardParams>,
+        gateway_performance: &BTreeMap<IdentityKey, Performance>,
+    ) -> Result<
+        (
+            BTreeMap<MixId, RewardDistribution>,
+            BTreeMap<IdentityKey, RewardDistribution>,
+        ),
+        MixnetContractError,
+    > {
         let mut params_keys = node_params.keys().copied().collect::<Vec<_>>();
         params_keys.sort_unstable();
         let mut node_keys = self.nodes.keys().copied().collect::<Vec<_>>();
@@ -177,8 +245,28 @@ impl Simulator {
             dist.insert(*mix_id, reward_distribution);
         }
 
+        let mut gateway_dist = BTreeMap::new();
+
+        for (identity, gateway) in self.gateways.iter_mut() {
+            let performance = gateway_performance
+                .get(identity)
+                .copied()
+                .unwrap_or_default();
+            let reward_distribution = gateway
+                .rewarding_details
+                .calculate_epoch_reward(&self.system_rewarding_params.gateway, performance);
+            gateway.rewarding_details.distribute_rewards(
+                reward_distribution,
+                self.interval.current_epoch_absolute_id(),
+            );
+            self.pending_reward_pool_emission += reward_distribution.operator;
+            self.pending_reward_pool_emission += reward_distribution.delegates;
+
+            gateway_dist.insert(identity.clone(), reward_distribution);
+        }
+
         self.advance_epoch()?;
-        Ok(dist)
+        Ok((dist, gateway_dist))
     }
 
     pub fn determine_delegation_reward(
@@ -265,6 +353,7 @@ mod tests {
                 },
                 rewarded_set_size,
                 active_set_size,
+                gateway: Default::default(),
             };
 
             let interval = Interval::init_interval(
@@ -629,6 +718,7 @@ mod tests {
             },
             rewarded_set_size,
             active_set_size,
+            gateway: Default::default(),
         };
 
         let interval = Interval::init_interval(
@@ -870,4 +960,146 @@ mod tests {
         compare_decimals(node.operator, expected_n9_pledge, epsilon);
         compare_decimals(node.delegates, expected_n9_delegated, epsilon);
     }
+
+    #[cfg(test)]
+    mod gateway_rewarding {
+        use super::*;
+        use crate::reward_params::GatewayRewardParams;
+        use crate::rewarding::helpers::truncate_reward_amount;
+        use cosmwasm_std::coin;
+
+        const GATEWAY: &str = "gateway-identity";
+
+        fn gateway_simulator(gateway_params: GatewayRewardParams) -> Simulator {
+            let epochs_in_interval = 720u32;
+            let reward_pool = 250_000_000_000_000u128;
+            let staking_supply = 100_000_000_000_000u128;
+            let interval_pool_emission = Percent::from_percentage_value(2).unwrap();
+
+            let rewarding_params = RewardingParams {
+                interval: IntervalRewardParams {
+                    reward_pool: Decimal::from_atomics(reward_pool, 0).unwrap(),
+                    staking_supply: Decimal::from_atomics(staking_supply, 0).unwrap(),
+                    staking_supply_scale_factor: Percent::hundred(),
+                    epoch_reward_budget: interval_pool_emission
+                        * Decimal::from_ratio(reward_pool, epochs_in_interval),
+                    stake_saturation_point: Decimal::from_ratio(staking_supply, 240u32),
+                    sybil_resistance: Percent::from_percentage_value(30).unwrap(),
+                    active_set_work_factor: Decimal::percent(1000),
+                    interval_pool_emission,
+                },
+                rewarded_set_size: 240,
+                active_set_size: 100,
+                gateway: gateway_params,
+            };
+
+            let interval = Interval::init_interval(
+                epochs_in_interval,
+                Duration::from_secs(60 * 60),
+                &mock_env(),
+            );
+
+            let mut simulator = Simulator::new(rewarding_params, interval);
+            simulator
+                .bond_gateway(GATEWAY, coin(10000_000000, "unym"))
+                .unwrap();
+            simulator
+        }
+
+        fn enabled_params() -> GatewayRewardParams {
+            GatewayRewardParams::new(
+                Percent::from_percentage_value(1).unwrap(),
+                50,
+                Percent::from_percentage_value(10).unwrap(),
+                Decimal::from_atomics(250_000_000_000_000u128, 0).unwrap(),
+                Decimal::from_atomics(100_000_000_000_000u128, 0).unwrap(),
+                720,
+            )
+            .unwrap()
+        }
+
+        fn full_performance() -> BTreeMap<IdentityKey, Performance> {
+            let mut performance = BTreeMap::new();
+            performance.insert(GATEWAY.to_string(), Percent::hundred());
+            performance
+        }
+
+        #[test]
+        fn is_disabled_by_default() {
+            let mut simulator = gateway_simulator(GatewayRewardParams::default());
+            simulator
+                .delegate_to_gateway("alice", coin(10000_000000, "unym"), GATEWAY)
+                .unwrap();
+
+            let (_, rewards) = simulator
+                .simulate_epoch_with_gateways(&BTreeMap::new(), &full_performance())
+                .unwrap();
+            assert_eq!(rewards[GATEWAY], RewardDistribution::default());
+        }
+
+        #[test]
+        fn gateways_without_performance_are_not_rewarded() {
+            let mut simulator = gateway_simulator(enabled_params());
+            let (_, rewards) = simulator
+                .simulate_epoch_with_gateways(&BTreeMap::new(), &BTreeMap::new())
+                .unwrap();
+            assert_eq!(rewards[GATEWAY], RewardDistribution::default());
+        }
+
+        #[test]
+        fn single_delegation_at_genesis() {
+            let mut simulator = gateway_simulator(enabled_params());
+            simulator
+                .delegate_to_gateway("alice", coin(10000_000000, "unym"), GATEWAY)
+                .unwrap();
+
+            let (_, rewards) = simulator
+                .simulate_epoch_with_gateways(&BTreeMap::new(), &full_performance())
+                .unwrap();
+
+            // budget of 69444444.(4) with the bond saturation of 0.01,
+            // 10% of which goes to the operator before splitting the rest equally
+            compare_decimals(
+                rewards[GATEWAY].operator,
+                "381944.444444444444".parse().unwrap(),
+                None,
+            );
+            compare_decimals(rewards[GATEWAY].delegates, "312500".parse().unwrap(), None);
+        }
+
+        #[test]
+        fn delegation_and_undelegation() {
+            let mut simulator = gateway_simulator(enabled_params());
+            let (_, rewards1) = simulator
+                .simulate_epoch_with_gateways(&BTreeMap::new(), &full_performance())
+                .unwrap();
+            assert_eq!(rewards1[GATEWAY].delegates, Decimal::zero());
+
+            simulator
+                .delegate_to_gateway("alice", coin(18000_000000, "unym"), GATEWAY)
+                .unwrap();
+
+            let mut operator_rewards = rewards1[GATEWAY].operator;
+            let mut delegator_rewards = Decimal::zero();
+            for _ in 0..10 {
+                let (_, rewards) = simulator
+                    .simulate_epoch_with_gateways(&BTreeMap::new(), &full_performance())
+                    .unwrap();
+                operator_rewards += rewards[GATEWAY].operator;
+                delegator_rewards += rewards[GATEWAY].delegates;
+            }
+
+            let (delegation, reward) = simulator.undelegate_from_gateway("alice", GATEWAY).unwrap();
+            assert_eq!(delegation.amount.u128(), 18000_000000);
+            assert_eq!(reward.amount, truncate_reward_amount(delegator_rewards));
+
+            let gateway = &simulator.gateways[GATEWAY];
+            compare_decimals(
+                gateway.rewarding_details.operator,
+                Decimal::from_atomics(10000_000000u128, 0).unwrap() + operator_rewards,
+                None,
+            );
+            assert_eq!(Decimal::zero(), gateway.rewarding_details.delegates);
+        }
+    }
 }
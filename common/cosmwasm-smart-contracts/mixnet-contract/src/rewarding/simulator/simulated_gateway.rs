@@ -0,0 +1,69 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::MixnetContractError;
+use crate::rewarding::helpers::truncate_reward;
+use crate::{GatewayDelegation, GatewayRewarding, IdentityKey};
+use cosmwasm_std::{Addr, Coin};
+use std::collections::HashMap;
+
+pub struct SimulatedGateway {
+    pub identity: IdentityKey,
+    pub rewarding_details: GatewayRewarding,
+    pub delegations: HashMap<String, GatewayDelegation>,
+}
+
+impl SimulatedGateway {
+    pub fn new(identity: IdentityKey, initial_pledge: &Coin) -> Result<Self, MixnetContractError> {
+        Ok(SimulatedGateway {
+            identity,
+            rewarding_details: GatewayRewarding::initialise_new(initial_pledge)?,
+            delegations: HashMap::new(),
+        })
+    }
+
+    pub fn delegate<S: Into<String>>(
+        &mut self,
+        delegator: S,
+        delegation: Coin,
+    ) -> Result<(), MixnetContractError> {
+        self.rewarding_details
+            .add_base_delegation(delegation.amount)?;
+
+        let delegator = delegator.into();
+        let delegation = GatewayDelegation::new(
+            Addr::unchecked(&delegator),
+            self.identity.clone(),
+            self.rewarding_details.full_reward_ratio(),
+            delegation,
+            42,
+        );
+
+        self.delegations.insert(delegator, delegation);
+        Ok(())
+    }
+
+    pub fn undelegate<S: Into<String>>(
+        &mut self,
+        delegator: S,
+    ) -> Result<(Coin, Coin), MixnetContractError> {
+        let delegator = delegator.into();
+        let delegation = self.delegations.remove(&delegator).ok_or(
+            MixnetContractError::NoGatewayDelegationFound {
+                identity: self.identity.clone(),
+                address: delegator,
+            },
+        )?;
+
+        let reward = self
+            .rewarding_details
+            .determine_delegation_reward(&delegation)?;
+        self.rewarding_details
+            .remove_delegation_decimal(delegation.dec_amount()? + reward)?;
+
+        let reward_denom = &delegation.amount.denom;
+        let truncated_reward = truncate_reward(reward, reward_denom);
+
+        Ok((delegation.amount, truncated_reward))
+    }
+}
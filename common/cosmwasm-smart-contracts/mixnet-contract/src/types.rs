@@ -143,6 +143,12 @@ pub struct ContractStateParams {
     pub minimum_gateway_pledge: Coin,
 }
 
+/// Identity keys of the nym-apis trusted to sign the network topology documents.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+pub struct TopologySignersResponse {
+    pub signers: Vec<IdentityKey>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
 pub struct PagedRewardedSetResponse {
     pub nodes: Vec<(MixId, RewardedSetNodeStatus)>,
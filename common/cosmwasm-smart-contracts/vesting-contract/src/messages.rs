@@ -1,7 +1,7 @@
 use cosmwasm_std::{Coin, Timestamp};
 use mixnet_contract_common::{
     mixnode::{MixNodeConfigUpdate, MixNodeCostParams},
-    Gateway, IdentityKey, MixId, MixNode,
+    Gateway, GatewayConfigUpdate, IdentityKey, MixId, MixNode,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -100,6 +100,11 @@ pub enum ExecuteMsg {
     UndelegateFromMixnode {
         mix_id: MixId,
     },
+    RedelegateFromMixnode {
+        from: MixId,
+        to: MixId,
+        amount: Coin,
+    },
     CreateAccount {
         owner_address: String,
         staking_address: Option<String>,
@@ -114,6 +119,12 @@ pub enum ExecuteMsg {
         mix_id: MixId,
         amount: Coin,
     },
+    TrackRedelegation {
+        owner: String,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+    },
     BondMixnode {
         mix_node: MixNode,
         cost_params: MixNodeCostParams,
@@ -133,11 +144,24 @@ pub enum ExecuteMsg {
         owner_signature: String,
         amount: Coin,
     },
+    PledgeMoreGateway {
+        amount: Coin,
+    },
+    DecreaseGatewayPledge {
+        amount: Coin,
+    },
+    UpdateGatewayConfig {
+        new_config: GatewayConfigUpdate,
+    },
     UnbondGateway {},
     TrackUnbondGateway {
         owner: String,
         amount: Coin,
     },
+    TrackDecreaseGatewayPledge {
+        owner: String,
+        amount: Coin,
+    },
     TransferOwnership {
         to_address: String,
     },
@@ -167,16 +191,24 @@ impl ExecuteMsg {
             ExecuteMsg::UpdateMixnetAddress { .. } => "VestingExecuteMsg::UpdateMixnetAddress",
             ExecuteMsg::DelegateToMixnode { .. } => "VestingExecuteMsg::DelegateToMixnode",
             ExecuteMsg::UndelegateFromMixnode { .. } => "VestingExecuteMsg::UndelegateFromMixnode",
+            ExecuteMsg::RedelegateFromMixnode { .. } => "VestingExecuteMsg::RedelegateFromMixnode",
             ExecuteMsg::CreateAccount { .. } => "VestingExecuteMsg::CreateAccount",
             ExecuteMsg::WithdrawVestedCoins { .. } => "VestingExecuteMsg::WithdrawVestedCoins",
             ExecuteMsg::TrackUndelegation { .. } => "VestingExecuteMsg::TrackUndelegation",
+            ExecuteMsg::TrackRedelegation { .. } => "VestingExecuteMsg::TrackRedelegation",
             ExecuteMsg::BondMixnode { .. } => "VestingExecuteMsg::BondMixnode",
             ExecuteMsg::PledgeMore { .. } => "VestingExecuteMsg::PledgeMore",
             ExecuteMsg::UnbondMixnode { .. } => "VestingExecuteMsg::UnbondMixnode",
             ExecuteMsg::TrackUnbondMixnode { .. } => "VestingExecuteMsg::TrackUnbondMixnode",
             ExecuteMsg::BondGateway { .. } => "VestingExecuteMsg::BondGateway",
+            ExecuteMsg::PledgeMoreGateway { .. } => "VestingExecuteMsg::PledgeMoreGateway",
+            ExecuteMsg::DecreaseGatewayPledge { .. } => "VestingExecuteMsg::DecreaseGatewayPledge",
+            ExecuteMsg::UpdateGatewayConfig { .. } => "VestingExecuteMsg::UpdateGatewayConfig",
             ExecuteMsg::UnbondGateway { .. } => "VestingExecuteMsg::UnbondGateway",
             ExecuteMsg::TrackUnbondGateway { .. } => "VestingExecuteMsg::TrackUnbondGateway",
+            ExecuteMsg::TrackDecreaseGatewayPledge { .. } => {
+                "VestingExecuteMsg::TrackDecreaseGatewayPledge"
+            }
             ExecuteMsg::TransferOwnership { .. } => "VestingExecuteMsg::TransferOwnership",
             ExecuteMsg::UpdateStakingAddress { .. } => "VestingExecuteMsg::UpdateStakingAddress",
             ExecuteMsg::UpdateLockedPledgeCap { .. } => "VestingExecuteMsg::UpdateLockedPledgeCap",
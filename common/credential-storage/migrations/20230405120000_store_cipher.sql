@@ -0,0 +1,12 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- presence of the (single) row implies the secret columns of the credentials are encrypted
+-- with a key derived from the user passphrase using the stored (non-secret) kdf information
+CREATE TABLE store_cipher
+(
+    id       INTEGER NOT NULL PRIMARY KEY CHECK (id = 0),
+    kdf_info TEXT    NOT NULL
+);
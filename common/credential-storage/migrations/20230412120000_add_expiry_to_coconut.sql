@@ -0,0 +1,7 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- credentials obtained before the introduction of expiry are marked with epoch 0
+ALTER TABLE coconut_credentials ADD COLUMN expiry_epoch INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- tickets of the divisible credentials are spent in order, so the count doubles as the index of the next one
+ALTER TABLE coconut_credentials ADD COLUMN spent_tickets INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,76 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+#[derive(Clone)]
+pub(crate) struct StoreCipherManager {
+    connection_pool: sqlx::SqlitePool,
+}
+
+impl StoreCipherManager {
+    /// Creates new instance of the `StoreCipherManager` with the provided sqlite connection pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_pool`: database connection pool to use.
+    pub(crate) fn new(connection_pool: sqlx::SqlitePool) -> Self {
+        StoreCipherManager { connection_pool }
+    }
+
+    /// Retrieves the serialized key derivation information, if the storage is encrypted.
+    pub(crate) async fn get_kdf_info(&self) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query!("SELECT kdf_info FROM store_cipher WHERE id = 0")
+            .fetch_optional(&self.connection_pool)
+            .await?;
+        Ok(row.map(|row| row.kdf_info))
+    }
+
+    /// Stores the serialized key derivation information of a freshly created encrypted storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `kdf_info`: serialized key derivation information.
+    pub(crate) async fn set_kdf_info(&self, kdf_info: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO store_cipher(id, kdf_info) VALUES (0, ?)",
+            kdf_info
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Replaces the secrets of all the credentials with their encrypted variants and stores
+    /// the key derivation information. Everything happens within a single transaction so the
+    /// storage is never left partially encrypted.
+    ///
+    /// # Arguments
+    ///
+    /// * `encrypted_credentials`: tuples of (id, serial number, binding number, signature),
+    ///   with all of the secrets already encrypted.
+    /// * `kdf_info`: serialized key derivation information.
+    pub(crate) async fn encrypt_credentials(
+        &self,
+        encrypted_credentials: Vec<(i64, String, String, String)>,
+        kdf_info: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+        for (id, serial_number, binding_number, signature) in encrypted_credentials {
+            sqlx::query!(
+                "UPDATE coconut_credentials SET serial_number = ?, binding_number = ?, signature = ? WHERE id = ?",
+                serial_number,
+                binding_number,
+                signature,
+                id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        sqlx::query!(
+            "INSERT INTO store_cipher(id, kdf_info) VALUES (0, ?)",
+            kdf_info
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await
+    }
+}
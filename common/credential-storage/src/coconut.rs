@@ -27,6 +27,7 @@ impl CoconutCredentialManager {
     /// * `serial_number`: Base58 representation of the serial number attribute.
     /// * `binding_number`: Base58 representation of the binding number attribute.
     /// * `signature`: Coconut credential in the form of a signature.
+    /// * `expiry_epoch`: Epoch after which the credential can no longer be spent.
     pub(crate) async fn insert_coconut_credential(
         &self,
         voucher_value: String,
@@ -34,10 +35,11 @@ impl CoconutCredentialManager {
         serial_number: String,
         binding_number: String,
         signature: String,
+        expiry_epoch: i64,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "INSERT INTO coconut_credentials(voucher_value, voucher_info, serial_number, binding_number, signature, consumed) VALUES (?, ?, ?, ?, ?, ?)",
-            voucher_value, voucher_info, serial_number, binding_number, signature, false
+            "INSERT INTO coconut_credentials(voucher_value, voucher_info, serial_number, binding_number, signature, consumed, expiry_epoch) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            voucher_value, voucher_info, serial_number, binding_number, signature, false, expiry_epoch
         )
         .execute(&self.connection_pool)
         .await?;
@@ -56,6 +58,39 @@ impl CoconutCredentialManager {
         .await
     }
 
+    /// Retrieves all stored credentials, including the already consumed ones.
+    pub(crate) async fn get_all_coconut_credentials(
+        &self,
+    ) -> Result<Vec<CoconutCredential>, sqlx::Error> {
+        sqlx::query_as!(
+            CoconutCredential,
+            "SELECT * FROM coconut_credentials ORDER BY id"
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Records the number of tickets of the specified divisible credential that have been spent.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: Database id.
+    /// * `spent_tickets`: Number of the spent tickets.
+    pub(crate) async fn set_spent_tickets(
+        &self,
+        id: i64,
+        spent_tickets: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE coconut_credentials SET spent_tickets = ? WHERE id = ?",
+            spent_tickets,
+            id
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
     /// Consumes in the database the specified credential.
     ///
     /// # Arguments
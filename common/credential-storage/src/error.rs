@@ -13,4 +13,16 @@ pub enum StorageError {
 
     #[error("Inconsistent data in database")]
     InconsistentData,
+
+    #[error("The storage is encrypted - a passphrase is required to open it")]
+    PassphraseRequired,
+
+    #[error("The storage contains unencrypted credentials - encrypt it before opening it with a passphrase")]
+    UnencryptedStorage,
+
+    #[error("Failed to encrypt or decrypt the stored data - {0}")]
+    CipherError(#[from] store_cipher::StoreCipherError),
+
+    #[error("The stored encrypted data is malformed - {0}")]
+    MalformedEncryptedData(String),
 }
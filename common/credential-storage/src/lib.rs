@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::cipher::StoreCipherManager;
 use crate::coconut::CoconutCredentialManager;
 use crate::error::StorageError;
 use crate::storage::Storage;
@@ -10,27 +11,33 @@ use crate::storage::Storage;
 use crate::models::CoconutCredential;
 use async_trait::async_trait;
 use log::{debug, error};
+use rand::rngs::OsRng;
 use sqlx::ConnectOptions;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use store_cipher::{EncryptedData, KdfInfo, StoreCipher};
 
+mod cipher;
 mod coconut;
 pub mod error;
-mod models;
+pub mod models;
 pub mod storage;
 
 // note that clone here is fine as upon cloning the same underlying pool will be used
 #[derive(Clone)]
 pub struct PersistentStorage {
     coconut_credential_manager: CoconutCredentialManager,
+    store_cipher_manager: StoreCipherManager,
+
+    /// If set, the secret parts of the credentials (i.e. the private attributes and the signature)
+    /// are encrypted with it before being written to the database.
+    cipher: Option<Arc<StoreCipher>>,
 }
 
 impl PersistentStorage {
-    /// Initialises `PersistentStorage` using the provided path.
-    ///
-    /// # Arguments
-    ///
-    /// * `database_path`: path to the database.
-    pub async fn init<P: AsRef<Path> + Send>(database_path: P) -> Result<Self, StorageError> {
+    async fn connect<P: AsRef<Path> + Send>(
+        database_path: P,
+    ) -> Result<sqlx::SqlitePool, StorageError> {
         debug!(
             "Attempting to connect to database {:?}",
             database_path.as_ref().as_os_str()
@@ -55,12 +62,171 @@ impl PersistentStorage {
             return Err(err.into());
         }
 
-        Ok(PersistentStorage {
+        Ok(connection_pool)
+    }
+
+    fn new(connection_pool: sqlx::SqlitePool, cipher: Option<StoreCipher>) -> Self {
+        PersistentStorage {
             coconut_credential_manager: CoconutCredentialManager::new(connection_pool.clone()),
-        })
+            store_cipher_manager: StoreCipherManager::new(connection_pool),
+            cipher: cipher.map(Arc::new),
+        }
+    }
+
+    /// Initialises unencrypted `PersistentStorage` using the provided path.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_path`: path to the database.
+    pub async fn init<P: AsRef<Path> + Send>(database_path: P) -> Result<Self, StorageError> {
+        let connection_pool = Self::connect(database_path).await?;
+        let storage = Self::new(connection_pool, None);
+
+        if storage.store_cipher_manager.get_kdf_info().await?.is_some() {
+            return Err(StorageError::PassphraseRequired);
+        }
+
+        Ok(storage)
+    }
+
+    /// Initialises `PersistentStorage` encrypting the credential secrets with a key derived from
+    /// the provided passphrase. If the storage does not exist yet, it is created as encrypted.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_path`: path to the database.
+    /// * `passphrase`: passphrase used to derive the encryption key.
+    pub async fn init_with_passphrase<P: AsRef<Path> + Send>(
+        database_path: P,
+        passphrase: &[u8],
+    ) -> Result<Self, StorageError> {
+        let connection_pool = Self::connect(database_path).await?;
+        let mut storage = Self::new(connection_pool, None);
+
+        let cipher = match storage.store_cipher_manager.get_kdf_info().await? {
+            Some(kdf_info) => {
+                let kdf_info: KdfInfo = serde_json::from_str(&kdf_info)
+                    .map_err(|err| StorageError::MalformedEncryptedData(err.to_string()))?;
+                StoreCipher::new_with_kdf_info(passphrase, kdf_info)?
+            }
+            None => {
+                if !storage
+                    .coconut_credential_manager
+                    .get_all_coconut_credentials()
+                    .await?
+                    .is_empty()
+                {
+                    return Err(StorageError::UnencryptedStorage);
+                }
+                let cipher = StoreCipher::new(passphrase, &mut OsRng)?;
+                storage
+                    .store_cipher_manager
+                    .set_kdf_info(&serialize_kdf_info(&cipher))
+                    .await?;
+                cipher
+            }
+        };
+
+        storage.cipher = Some(Arc::new(cipher));
+        Ok(storage)
+    }
+
+    /// Encrypts the credential secrets of an existing unencrypted storage with a key derived from
+    /// the provided passphrase and returns the storage opened in the encrypted mode.
+    /// If the storage has already been encrypted, for example by an interrupted run of the client
+    /// store encryption, it is opened with the passphrase instead, as long as it can decrypt
+    /// all of the stored credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_path`: path to the database.
+    /// * `passphrase`: passphrase used to derive the encryption key.
+    pub async fn encrypt_existing<P: AsRef<Path> + Send>(
+        database_path: P,
+        passphrase: &[u8],
+    ) -> Result<Self, StorageError> {
+        let connection_pool = Self::connect(database_path).await?;
+        let mut storage = Self::new(connection_pool, None);
+
+        // the credentials are encrypted in the same transaction the kdf info is stored in,
+        // so if it's present, there's nothing left to encrypt
+        if let Some(kdf_info) = storage.store_cipher_manager.get_kdf_info().await? {
+            let kdf_info: KdfInfo = serde_json::from_str(&kdf_info)
+                .map_err(|err| StorageError::MalformedEncryptedData(err.to_string()))?;
+            storage.cipher = Some(Arc::new(StoreCipher::new_with_kdf_info(
+                passphrase, kdf_info,
+            )?));
+            // make sure it's the passphrase the storage has been encrypted with
+            storage.get_all_coconut_credentials().await?;
+            return Ok(storage);
+        }
+
+        let cipher = StoreCipher::new(passphrase, &mut OsRng)?;
+        let mut encrypted_credentials = Vec::new();
+        for credential in storage
+            .coconut_credential_manager
+            .get_all_coconut_credentials()
+            .await?
+        {
+            encrypted_credentials.push((
+                credential.id,
+                encrypt_value(&cipher, &credential.serial_number)?,
+                encrypt_value(&cipher, &credential.binding_number)?,
+                encrypt_value(&cipher, &credential.signature)?,
+            ));
+        }
+
+        storage
+            .store_cipher_manager
+            .encrypt_credentials(encrypted_credentials, &serialize_kdf_info(&cipher))
+            .await?;
+
+        storage.cipher = Some(Arc::new(cipher));
+        Ok(storage)
+    }
+
+    fn encrypt_secret(&self, value: String) -> Result<String, StorageError> {
+        match &self.cipher {
+            None => Ok(value),
+            Some(cipher) => encrypt_value(cipher, &value),
+        }
+    }
+
+    fn decrypt_secret(&self, value: String) -> Result<String, StorageError> {
+        match &self.cipher {
+            None => Ok(value),
+            Some(cipher) => {
+                let encrypted: EncryptedData = serde_json::from_str(&value)
+                    .map_err(|err| StorageError::MalformedEncryptedData(err.to_string()))?;
+                let plaintext = cipher.decrypt_data_unchecked(&encrypted)?;
+                String::from_utf8(plaintext)
+                    .map_err(|err| StorageError::MalformedEncryptedData(err.to_string()))
+            }
+        }
+    }
+
+    fn decrypt_credential(
+        &self,
+        mut credential: CoconutCredential,
+    ) -> Result<CoconutCredential, StorageError> {
+        credential.serial_number = self.decrypt_secret(credential.serial_number)?;
+        credential.binding_number = self.decrypt_secret(credential.binding_number)?;
+        credential.signature = self.decrypt_secret(credential.signature)?;
+        Ok(credential)
     }
 }
 
+fn serialize_kdf_info(cipher: &StoreCipher) -> String {
+    // serializing plain struct into json can't fail
+    serde_json::to_string(&cipher.kdf_info()).unwrap()
+}
+
+fn encrypt_value(cipher: &StoreCipher, value: &str) -> Result<String, StorageError> {
+    let encrypted = cipher.encrypt_data_ref(value.as_bytes(), &mut OsRng)?;
+    // serializing plain struct into json can't fail
+    Ok(serde_json::to_string(&encrypted).unwrap())
+}
+
 #[async_trait]
 impl Storage for PersistentStorage {
     async fn insert_coconut_credential(
@@ -70,14 +236,16 @@ impl Storage for PersistentStorage {
         serial_number: String,
         binding_number: String,
         signature: String,
+        expiry_epoch: u64,
     ) -> Result<(), StorageError> {
         self.coconut_credential_manager
             .insert_coconut_credential(
                 voucher_value,
                 voucher_info,
-                serial_number,
-                binding_number,
-                signature,
+                self.encrypt_secret(serial_number)?,
+                self.encrypt_secret(binding_number)?,
+                self.encrypt_secret(signature)?,
+                expiry_epoch as i64,
             )
             .await?;
 
@@ -90,7 +258,16 @@ impl Storage for PersistentStorage {
             .get_next_coconut_credential()
             .await?;
 
-        Ok(credential)
+        self.decrypt_credential(credential)
+    }
+
+    async fn get_all_coconut_credentials(&self) -> Result<Vec<CoconutCredential>, StorageError> {
+        self.coconut_credential_manager
+            .get_all_coconut_credentials()
+            .await?
+            .into_iter()
+            .map(|credential| self.decrypt_credential(credential))
+            .collect()
     }
 
     async fn consume_coconut_credential(&self, id: i64) -> Result<(), StorageError> {
@@ -100,6 +277,40 @@ impl Storage for PersistentStorage {
 
         Ok(())
     }
+
+    async fn consume_coconut_ticket(&self, id: i64, ticket_index: u64) -> Result<(), StorageError> {
+        self.coconut_credential_manager
+            .set_spent_tickets(id, ticket_index as i64 + 1)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Environment variable the passphrase of an encrypted storage can be provided with.
+/// It is the same one the clients read the passphrase of their whole store from.
+pub const PASSPHRASE_ENV_VAR: &str = "NYM_CLIENT_PASSPHRASE";
+
+/// Reads the passphrase of the storage from the provided file or, if no file is given, from the
+/// `NYM_CLIENT_PASSPHRASE` environment variable. Returns `None` if the passphrase is provided by neither.
+pub fn read_passphrase(passphrase_file: Option<&Path>) -> std::io::Result<Option<String>> {
+    let passphrase = match passphrase_file {
+        // ignore the trailing newline most editors are going to append to the file
+        Some(path) => std::fs::read_to_string(path)?
+            .trim_end_matches(['\r', '\n'])
+            .to_string(),
+        None => match std::env::var(PASSPHRASE_ENV_VAR) {
+            Ok(passphrase) => passphrase,
+            Err(_) => return Ok(None),
+        },
+    };
+    if passphrase.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "the provided passphrase is empty",
+        ));
+    }
+    Ok(Some(passphrase))
 }
 
 pub async fn initialise_storage(path: PathBuf) -> PersistentStorage {
@@ -108,3 +319,18 @@ pub async fn initialise_storage(path: PathBuf) -> PersistentStorage {
         Ok(storage) => storage,
     }
 }
+
+/// Initialises the storage, encrypted with the provided passphrase if one is given.
+pub async fn initialise_storage_with_passphrase(
+    path: PathBuf,
+    passphrase: Option<&[u8]>,
+) -> PersistentStorage {
+    let storage = match passphrase {
+        None => PersistentStorage::init(path).await,
+        Some(passphrase) => PersistentStorage::init_with_passphrase(path, passphrase).await,
+    };
+    match storage {
+        Err(err) => panic!("failed to initialise credential storage - {err}"),
+        Ok(storage) => storage,
+    }
+}
@@ -10,4 +10,6 @@ pub struct CoconutCredential {
     pub binding_number: String,
     pub signature: String,
     pub consumed: bool,
+    pub expiry_epoch: i64,
+    pub spent_tickets: i64,
 }
@@ -13,6 +13,7 @@ pub trait Storage: Send + Sync {
     /// # Arguments
     ///
     /// * `signature`: Coconut credential in the form of a signature.
+    /// * `expiry_epoch`: Epoch after which the credential can no longer be spent.
     async fn insert_coconut_credential(
         &self,
         voucher_value: String,
@@ -20,15 +21,28 @@ pub trait Storage: Send + Sync {
         serial_number: String,
         binding_number: String,
         signature: String,
+        expiry_epoch: u64,
     ) -> Result<(), StorageError>;
 
     /// Tries to retrieve one of the stored, unused credentials.
     async fn get_next_coconut_credential(&self) -> Result<CoconutCredential, StorageError>;
 
+    /// Retrieves all stored credentials, including the already consumed ones.
+    async fn get_all_coconut_credentials(&self) -> Result<Vec<CoconutCredential>, StorageError>;
+
     /// Marks as consumed in the database the specified credential.
     ///
     /// # Arguments
     ///
     /// * `id`: Id of the credential to be consumed.
     async fn consume_coconut_credential(&self, id: i64) -> Result<(), StorageError>;
+
+    /// Records the ticket with the provided index of the specified divisible credential as spent.
+    /// Tickets are spent in order, so all the tickets with lower indices are considered spent as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: Id of the divisible credential.
+    /// * `ticket_index`: Index of the spent ticket.
+    async fn consume_coconut_ticket(&self, id: i64, ticket_index: u64) -> Result<(), StorageError>;
 }
@@ -6,9 +6,10 @@
 // right now this has no double-spending protection, spender binding, etc
 // it's the simplest possible case
 
+use coconut_bandwidth_contract_common::expiry::LEGACY_EXPIRY_EPOCH;
 use coconut_interface::{
     hash_to_scalar, prepare_blind_sign, Attribute, BlindSignRequest, Credential, Parameters,
-    PrivateAttribute, PublicAttribute, Signature, VerificationKey,
+    PrivateAttribute, PublicAttribute, Signature, VerificationKey, LEGACY_CREDENTIAL_ATTRIBUTES,
 };
 use crypto::asymmetric::{encryption, identity};
 
@@ -17,10 +18,23 @@ use cosmrs::tx::Hash;
 use super::utils::prepare_credential_for_spending;
 use crate::error::Error;
 
-pub const PUBLIC_ATTRIBUTES: u32 = 2;
+pub const PUBLIC_ATTRIBUTES: u32 = 3;
 pub const PRIVATE_ATTRIBUTES: u32 = 2;
 pub const TOTAL_ATTRIBUTES: u32 = PUBLIC_ATTRIBUTES + PRIVATE_ATTRIBUTES;
 
+/// Returns the number of attributes the credential expiring at `expiry_epoch` has been issued with,
+/// as the legacy credentials have been issued without the expiry.
+pub fn credential_attributes(expiry_epoch: u64) -> u32 {
+    if expiry_epoch == LEGACY_EXPIRY_EPOCH {
+        LEGACY_CREDENTIAL_ATTRIBUTES
+    } else {
+        TOTAL_ATTRIBUTES
+    }
+}
+
+/// Number of bits of the ticket index, i.e. a credential can be spent as `2^TICKET_INDEX_BITS` tickets.
+pub const TICKET_INDEX_BITS: u8 = 8;
+
 pub struct BandwidthVoucher {
     // a random secret value generated by the client used for double-spending detection
     serial_number: PrivateAttribute,
@@ -34,6 +48,10 @@ pub struct BandwidthVoucher {
     voucher_info: PublicAttribute,
     // the plain text information
     voucher_info_plain: String,
+    // the epoch after which the voucher can no longer be spent
+    expiry_epoch: PublicAttribute,
+    // the plain text expiry epoch
+    expiry_epoch_plain: String,
     // the hash of the deposit transaction
     tx_hash: Hash,
     // base58 encoded private key ensuring the depositer requested these attributes
@@ -57,10 +75,13 @@ impl BandwidthVoucher {
     ) -> Self {
         let voucher_value = public_attributes_plain[0];
         let voucher_info = public_attributes_plain[1];
+        let expiry_epoch = public_attributes_plain[2];
         let voucher_value_plain = voucher_value.to_string();
         let voucher_info_plain = voucher_info.to_string();
+        let expiry_epoch_plain = expiry_epoch.to_string();
         let voucher_value = hash_to_scalar(voucher_value.as_bytes());
         let voucher_info = hash_to_scalar(voucher_info.as_bytes());
+        let expiry_epoch = hash_to_scalar(expiry_epoch.as_bytes());
 
         BandwidthVoucher {
             serial_number: private_attributes[0],
@@ -69,6 +90,8 @@ impl BandwidthVoucher {
             voucher_value_plain,
             voucher_info,
             voucher_info_plain,
+            expiry_epoch,
+            expiry_epoch_plain,
             tx_hash,
             signing_key,
             encryption_key,
@@ -81,6 +104,7 @@ impl BandwidthVoucher {
         params: &Parameters,
         voucher_value: String,
         voucher_info: String,
+        expiry_epoch: u64,
         tx_hash: Hash,
         signing_key: identity::PrivateKey,
         encryption_key: encryption::PrivateKey,
@@ -89,12 +113,14 @@ impl BandwidthVoucher {
         let binding_number = params.random_scalar();
         let voucher_value_plain = voucher_value.clone();
         let voucher_info_plain = voucher_info.clone();
+        let expiry_epoch_plain = expiry_epoch.to_string();
         let voucher_value = hash_to_scalar(voucher_value.as_bytes());
         let voucher_info = hash_to_scalar(voucher_info.as_bytes());
+        let expiry_epoch = hash_to_scalar(expiry_epoch_plain.as_bytes());
         let (pedersen_commitments_openings, blind_sign_request) = prepare_blind_sign(
             params,
             &[serial_number, binding_number],
-            &[voucher_value, voucher_info],
+            &[voucher_value, voucher_info, expiry_epoch],
         )
         .unwrap();
         BandwidthVoucher {
@@ -104,6 +130,8 @@ impl BandwidthVoucher {
             voucher_value_plain,
             voucher_info,
             voucher_info_plain,
+            expiry_epoch,
+            expiry_epoch_plain,
             tx_hash,
             signing_key,
             encryption_key,
@@ -113,20 +141,36 @@ impl BandwidthVoucher {
         }
     }
 
+    /// Sets whether the blind sign request should be (re-)sent to the signers, as opposed to only
+    /// querying them for partial signatures they have already issued for this deposit.
+    /// Since the signers will return the previously issued signatures for an already seen
+    /// deposit, re-sending the same request is safe.
+    #[must_use]
+    pub fn with_use_request(mut self, use_request: bool) -> Self {
+        self.use_request = use_request;
+        self
+    }
+
     /// Check if the plain values correspond to the PublicAttributes
     pub fn verify_against_plain(values: &[PublicAttribute], plain_values: &[String]) -> bool {
-        values.len() == 2
-            && plain_values.len() == 2
-            && values[0] == hash_to_scalar(&plain_values[0])
-            && values[1] == hash_to_scalar(&plain_values[1])
+        values.len() == PUBLIC_ATTRIBUTES as usize
+            && plain_values.len() == PUBLIC_ATTRIBUTES as usize
+            && values
+                .iter()
+                .zip(plain_values.iter())
+                .all(|(value, plain_value)| *value == hash_to_scalar(plain_value))
     }
 
     pub fn tx_hash(&self) -> &Hash {
         &self.tx_hash
     }
 
+    pub fn voucher_info(&self) -> &str {
+        &self.voucher_info_plain
+    }
+
     pub fn get_public_attributes(&self) -> Vec<PublicAttribute> {
-        vec![self.voucher_value, self.voucher_info]
+        vec![self.voucher_value, self.voucher_info, self.expiry_epoch]
     }
 
     pub fn encryption_key(&self) -> &encryption::PrivateKey {
@@ -149,6 +193,7 @@ impl BandwidthVoucher {
         vec![
             self.voucher_value_plain.clone(),
             self.voucher_info_plain.clone(),
+            self.expiry_epoch_plain.clone(),
         ]
     }
 
@@ -166,17 +211,19 @@ impl BandwidthVoucher {
 pub fn prepare_for_spending(
     voucher_value: u64,
     voucher_info: String,
+    expiry_epoch: u64,
     serial_number: PrivateAttribute,
     binding_number: PrivateAttribute,
     signature: &Signature,
     verification_key: &VerificationKey,
 ) -> Result<Credential, Error> {
-    let params = Parameters::new(TOTAL_ATTRIBUTES)?;
+    let params = Parameters::new(credential_attributes(expiry_epoch))?;
 
     prepare_credential_for_spending(
         &params,
         voucher_value,
         voucher_info,
+        expiry_epoch,
         serial_number,
         binding_number,
         signature,
@@ -191,12 +238,13 @@ mod test {
 
     #[test]
     fn voucher_consistency() {
-        let params = Parameters::new(4).unwrap();
+        let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
         let mut rng = OsRng;
         let voucher = BandwidthVoucher::new(
             &params,
             "1234".to_string(),
             "voucher info".to_string(),
+            19000,
             Hash::new([0; 32]),
             identity::PrivateKey::from_base58_string(
                 identity::KeyPair::new(&mut rng)
@@ -221,22 +269,44 @@ mod test {
             &voucher.get_public_attributes(),
             &[
                 voucher.get_public_attributes_plain()[0].clone(),
-                String::new()
+                String::new(),
+                voucher.get_public_attributes_plain()[2].clone(),
             ]
         ));
         assert!(!BandwidthVoucher::verify_against_plain(
             &voucher.get_public_attributes(),
             &[
                 String::new(),
-                voucher.get_public_attributes_plain()[1].clone()
+                voucher.get_public_attributes_plain()[1].clone(),
+                voucher.get_public_attributes_plain()[2].clone(),
             ]
         ));
         assert!(!BandwidthVoucher::verify_against_plain(
-            &[voucher.get_public_attributes()[0], Attribute::one()],
+            &voucher.get_public_attributes(),
+            &[
+                voucher.get_public_attributes_plain()[0].clone(),
+                voucher.get_public_attributes_plain()[1].clone(),
+                "19001".to_string(),
+            ]
+        ));
+        assert!(!BandwidthVoucher::verify_against_plain(
+            &voucher.get_public_attributes()[..2],
+            &voucher.get_public_attributes_plain()[..2]
+        ));
+        assert!(!BandwidthVoucher::verify_against_plain(
+            &[
+                voucher.get_public_attributes()[0],
+                Attribute::one(),
+                voucher.get_public_attributes()[2]
+            ],
             &voucher.get_public_attributes_plain()
         ));
         assert!(!BandwidthVoucher::verify_against_plain(
-            &[Attribute::one(), voucher.get_public_attributes()[1]],
+            &[
+                Attribute::one(),
+                voucher.get_public_attributes()[1],
+                voucher.get_public_attributes()[2]
+            ],
             &voucher.get_public_attributes_plain()
         ));
         assert!(BandwidthVoucher::verify_against_plain(
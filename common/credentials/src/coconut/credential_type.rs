@@ -0,0 +1,313 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types of credentials the nym-apis can issue. Apart from the bandwidth vouchers spent at the gateways,
+//! credentials can grant anonymous access to other services, e.g. premium exits of the network requesters.
+//!
+//! Each type defines the schema of its attributes along with the policy the signers apply
+//! to the requested public attributes, while [`verify_service_credential`] lets the services
+//! check the credentials they have received over the mixnet.
+
+use coconut_bandwidth_contract_common::events::{DEPOSIT_EXPIRY_EPOCH, DEPOSIT_VALUE};
+use coconut_bandwidth_contract_common::expiry::is_spendable;
+use coconut_interface::{
+    hash_to_scalar, is_divisible_value, Credential, PublicAttribute, VerificationKey,
+};
+use network_defaults::{DIVISIBLE_VOUCHER_INFO, SERVICE_ACCESS_INFO, VOUCHER_INFO};
+
+use crate::coconut::bandwidth::{PRIVATE_ATTRIBUTES, TICKET_INDEX_BITS};
+use crate::error::Error;
+
+/// Policy the signers check the plain value of a public attribute against before issuing a credential.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicAttributePolicy {
+    /// The value has to match the given attribute of the deposit event.
+    Deposit(&'static str),
+
+    /// The value has to be the name of the credential type and the deposit has to have been made for it.
+    CredentialType,
+
+    /// The value is chosen freely by the requester.
+    Unrestricted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicAttributeSpec {
+    pub name: &'static str,
+    pub policy: PublicAttributePolicy,
+}
+
+pub trait CredentialType: Send + Sync {
+    /// Name of the credential type. It keys the issuance routes of the nym-apis
+    /// and it is embedded in the credentials as their voucher info.
+    fn name(&self) -> &'static str;
+
+    /// Public attributes of the credential, in the order they are signed.
+    fn public_attributes(&self) -> &'static [PublicAttributeSpec];
+
+    /// Number of private attributes, the first two of which are always
+    /// the serial number and the binding number.
+    fn private_attributes(&self) -> u32 {
+        PRIVATE_ATTRIBUTES
+    }
+
+    fn total_attributes(&self) -> u32 {
+        self.private_attributes() + self.public_attributes().len() as u32
+    }
+
+    /// Check if credentials of this type can be issued for the deposited value.
+    fn accepts_value(&self, _value: u64) -> bool {
+        true
+    }
+
+    /// Check if the plain values correspond to the public attributes of this credential type.
+    fn verify_against_plain(&self, values: &[PublicAttribute], plain_values: &[String]) -> bool {
+        let expected = self.public_attributes().len();
+        values.len() == expected
+            && plain_values.len() == expected
+            && values
+                .iter()
+                .zip(plain_values.iter())
+                .all(|(value, plain_value)| *value == hash_to_scalar(plain_value))
+    }
+}
+
+// `coconut_interface::Credential` carries the value, the info and the expiry epoch,
+// so all the types spent as one share that layout of public attributes
+const VALUE: PublicAttributeSpec = PublicAttributeSpec {
+    name: "voucher_value",
+    policy: PublicAttributePolicy::Deposit(DEPOSIT_VALUE),
+};
+
+const EXPIRY_EPOCH: PublicAttributeSpec = PublicAttributeSpec {
+    name: "expiry_epoch",
+    policy: PublicAttributePolicy::Deposit(DEPOSIT_EXPIRY_EPOCH),
+};
+
+/// Bandwidth vouchers, spent at the gateways.
+#[derive(Debug, Clone, Copy)]
+pub struct Bandwidth;
+
+impl CredentialType for Bandwidth {
+    fn name(&self) -> &'static str {
+        VOUCHER_INFO
+    }
+
+    fn public_attributes(&self) -> &'static [PublicAttributeSpec] {
+        const ATTRIBUTES: &[PublicAttributeSpec] = &[
+            VALUE,
+            PublicAttributeSpec {
+                name: "voucher_info",
+                policy: PublicAttributePolicy::CredentialType,
+            },
+            EXPIRY_EPOCH,
+        ];
+        ATTRIBUTES
+    }
+}
+
+/// Bandwidth vouchers that the gateways only accept as `2^TICKET_INDEX_BITS` individual tickets.
+/// Being divisible is part of the signed voucher info, so the same voucher can never be spent
+/// both as a whole and as tickets.
+#[derive(Debug, Clone, Copy)]
+pub struct DivisibleBandwidth;
+
+impl CredentialType for DivisibleBandwidth {
+    fn name(&self) -> &'static str {
+        DIVISIBLE_VOUCHER_INFO
+    }
+
+    fn public_attributes(&self) -> &'static [PublicAttributeSpec] {
+        const ATTRIBUTES: &[PublicAttributeSpec] = &[
+            VALUE,
+            PublicAttributeSpec {
+                name: "voucher_info",
+                policy: PublicAttributePolicy::CredentialType,
+            },
+            EXPIRY_EPOCH,
+        ];
+        ATTRIBUTES
+    }
+
+    fn accepts_value(&self, value: u64) -> bool {
+        is_divisible_value(value, TICKET_INDEX_BITS)
+    }
+}
+
+/// Credentials granting access to the services gated behind them, e.g. premium exits.
+#[derive(Debug, Clone, Copy)]
+pub struct ServiceAccess;
+
+impl CredentialType for ServiceAccess {
+    fn name(&self) -> &'static str {
+        SERVICE_ACCESS_INFO
+    }
+
+    fn public_attributes(&self) -> &'static [PublicAttributeSpec] {
+        const ATTRIBUTES: &[PublicAttributeSpec] = &[
+            VALUE,
+            PublicAttributeSpec {
+                name: "voucher_info",
+                policy: PublicAttributePolicy::CredentialType,
+            },
+            EXPIRY_EPOCH,
+        ];
+        ATTRIBUTES
+    }
+}
+
+/// All the credential types the nym-apis are able to issue.
+pub const CREDENTIAL_TYPES: &[&dyn CredentialType] =
+    &[&Bandwidth, &DivisibleBandwidth, &ServiceAccess];
+
+/// Returns the credential type with the provided name, if it is known.
+pub fn credential_type(name: &str) -> Option<&'static dyn CredentialType> {
+    CREDENTIAL_TYPES
+        .iter()
+        .find(|credential_type| credential_type.name() == name)
+        .copied()
+}
+
+/// Checks whether the credential, received by a service provider, is a valid, unexpired credential
+/// of the given type. Detecting double spending, e.g. by remembering the blinded serial numbers,
+/// and checking the value of the credential against the price of the service are left to the service.
+pub fn verify_service_credential(
+    credential_type: &dyn CredentialType,
+    credential: &Credential,
+    verification_key: &VerificationKey,
+    current_epoch: u64,
+) -> Result<(), Error> {
+    if credential.voucher_info() != credential_type.name() {
+        return Err(Error::UnexpectedCredentialType {
+            expected: credential_type.name().to_string(),
+            received: credential.voucher_info().to_string(),
+        });
+    }
+    if *credential.n_params() != credential_type.total_attributes() {
+        return Err(Error::InvalidCredential);
+    }
+    if !is_spendable(credential.expiry_epoch(), current_epoch) {
+        return Err(Error::ExpiredCredential {
+            expiry_epoch: credential.expiry_epoch(),
+        });
+    }
+    if !credential.verify(verification_key) {
+        return Err(Error::InvalidCredential);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coconut::bandwidth::{BandwidthVoucher, TOTAL_ATTRIBUTES};
+    use crate::coconut::utils::prepare_credential_for_spending;
+    use coconut_interface::{blind_sign, ttp_keygen, Parameters};
+    use cosmrs::tx::Hash;
+    use crypto::asymmetric::{encryption, identity};
+    use rand::rngs::OsRng;
+
+    fn issue_credential(voucher_info: &str, expiry_epoch: u64) -> (Credential, VerificationKey) {
+        let params = Parameters::new(TOTAL_ATTRIBUTES).unwrap();
+        let mut rng = OsRng;
+        let key_pair = ttp_keygen(&params, 1, 1).unwrap().remove(0);
+        let voucher = BandwidthVoucher::new(
+            &params,
+            "1234".to_string(),
+            voucher_info.to_string(),
+            expiry_epoch,
+            Hash::new([0; 32]),
+            identity::PrivateKey::from_base58_string(
+                identity::KeyPair::new(&mut rng)
+                    .private_key()
+                    .to_base58_string(),
+            )
+            .unwrap(),
+            encryption::PrivateKey::from_bytes(
+                &encryption::KeyPair::new(&mut rng).private_key().to_bytes(),
+            )
+            .unwrap(),
+        );
+        let blinded_signature = blind_sign(
+            &params,
+            &key_pair.secret_key(),
+            voucher.blind_sign_request(),
+            &voucher.get_public_attributes(),
+        )
+        .unwrap();
+        let private_attributes = voucher.get_private_attributes();
+        let signature = blinded_signature
+            .unblind(
+                &params,
+                &key_pair.verification_key(),
+                &private_attributes,
+                &voucher.get_public_attributes(),
+                &voucher.blind_sign_request().get_commitment_hash(),
+                voucher.pedersen_commitments_openings(),
+            )
+            .unwrap();
+        let credential = prepare_credential_for_spending(
+            &params,
+            1234,
+            voucher_info.to_string(),
+            expiry_epoch,
+            private_attributes[0],
+            private_attributes[1],
+            &signature,
+            &key_pair.verification_key(),
+        )
+        .unwrap();
+
+        (credential, key_pair.verification_key())
+    }
+
+    #[test]
+    fn credential_types_are_looked_up_by_name() {
+        assert_eq!(credential_type(VOUCHER_INFO).unwrap().name(), VOUCHER_INFO);
+        assert_eq!(
+            credential_type(DIVISIBLE_VOUCHER_INFO).unwrap().name(),
+            DIVISIBLE_VOUCHER_INFO
+        );
+        assert_eq!(
+            credential_type(SERVICE_ACCESS_INFO).unwrap().name(),
+            SERVICE_ACCESS_INFO
+        );
+        assert!(credential_type("PremiumVoucher").is_none());
+
+        assert!(Bandwidth.accepts_value(1234));
+        assert!(DivisibleBandwidth.accepts_value(25600));
+        assert!(!DivisibleBandwidth.accepts_value(1234));
+
+        // the issuers' keys are generated for a fixed number of attributes
+        for credential_type in CREDENTIAL_TYPES {
+            assert_eq!(credential_type.total_attributes(), TOTAL_ATTRIBUTES);
+        }
+    }
+
+    #[test]
+    fn service_credentials_are_verified() {
+        let (credential, verification_key) = issue_credential(SERVICE_ACCESS_INFO, 19000);
+
+        assert!(
+            verify_service_credential(&ServiceAccess, &credential, &verification_key, 19000)
+                .is_ok()
+        );
+        assert!(matches!(
+            verify_service_credential(&ServiceAccess, &credential, &verification_key, 19001),
+            Err(Error::ExpiredCredential {
+                expiry_epoch: 19000
+            })
+        ));
+        assert!(matches!(
+            verify_service_credential(&Bandwidth, &credential, &verification_key, 19000),
+            Err(Error::UnexpectedCredentialType { .. })
+        ));
+
+        let (_, other_verification_key) = issue_credential(SERVICE_ACCESS_INFO, 19000);
+        assert!(matches!(
+            verify_service_credential(&ServiceAccess, &credential, &other_verification_key, 19000),
+            Err(Error::InvalidCredential)
+        ));
+    }
+}
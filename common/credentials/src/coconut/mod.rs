@@ -2,5 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod bandwidth;
+pub mod credential_type;
 pub mod params;
 pub mod utils;
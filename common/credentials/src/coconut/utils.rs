@@ -2,19 +2,99 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use coconut_interface::{
-    aggregate_signature_shares, aggregate_verification_keys, prove_bandwidth_credential, Attribute,
-    BlindedSignature, Credential, Parameters, Signature, SignatureShare, VerificationKey,
+    aggregate_signature_shares, aggregate_verification_keys, prove_bandwidth_credential,
+    prove_ticket, Attribute, BlindedSignature, Credential, Parameters, Signature, SignatureShare,
+    TicketCredential, VerificationKey,
 };
 use crypto::asymmetric::encryption::PublicKey;
 use crypto::shared_key::recompute_shared_key;
 use crypto::symmetric::stream_cipher;
+use futures::stream::{FuturesUnordered, StreamExt};
+use log::{debug, warn};
 use nym_api_requests::coconut::BlindSignRequestBody;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::time::Duration;
 use validator_client::client::CoconutApiClient;
 
-use crate::coconut::bandwidth::{BandwidthVoucher, PRIVATE_ATTRIBUTES, PUBLIC_ATTRIBUTES};
+use crate::coconut::bandwidth::{
+    credential_attributes, BandwidthVoucher, PRIVATE_ATTRIBUTES, PUBLIC_ATTRIBUTES,
+    TICKET_INDEX_BITS,
+};
+use crate::coconut::credential_type::{Bandwidth, CredentialType};
 use crate::coconut::params::{NymApiCredentialEncryptionAlgorithm, NymApiCredentialHkdfAlgorithm};
 use crate::error::Error;
 
+const DEFAULT_SIGNER_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_SIGNER_MAX_RETRIES: u32 = 2;
+const DEFAULT_SIGNER_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Controls how long we are willing to wait for each individual signer
+/// and how many times we are going to ask it again before giving up on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignerRequestConfig {
+    /// Maximum duration of a single partial signature request.
+    pub request_timeout: Duration,
+
+    /// Number of additional attempts made after a request has timed out or failed
+    /// due to a network (or api) error.
+    pub max_retries: u32,
+
+    /// Delay between subsequent attempts made against the same signer.
+    pub retry_backoff: Duration,
+}
+
+impl Default for SignerRequestConfig {
+    fn default() -> Self {
+        SignerRequestConfig {
+            request_timeout: DEFAULT_SIGNER_REQUEST_TIMEOUT,
+            max_retries: DEFAULT_SIGNER_MAX_RETRIES,
+            retry_backoff: DEFAULT_SIGNER_RETRY_BACKOFF,
+        }
+    }
+}
+
+impl SignerRequestConfig {
+    #[must_use]
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    #[must_use]
+    pub fn with_retry_backoff(mut self, retry_backoff: Duration) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+}
+
+/// Signer whose partial signature did not contribute to the aggregated credential.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedSigner {
+    pub node_id: u64,
+    pub reason: String,
+}
+
+impl Display for SkippedSigner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "signer {} ({})", self.node_id, self.reason)
+    }
+}
+
+#[derive(Debug)]
+pub struct AggregatedSignature {
+    pub signature: Signature,
+
+    /// Signers that failed to provide a valid share before the threshold has been reached.
+    pub skipped_signers: Vec<SkippedSigner>,
+}
+
 pub async fn obtain_aggregate_verification_key(
     api_clients: &[CoconutApiClient],
 ) -> Result<VerificationKey, Error> {
@@ -54,7 +134,15 @@ async fn obtain_partial_credential(
             public_attributes_plain,
             (public_attributes.len() + private_attributes.len()) as u32,
         );
-        client.blind_sign(&blind_sign_request_body).await?
+        // bandwidth vouchers keep using their dedicated route, so that they can still be obtained
+        // from the signers that predate the credential types
+        if attributes.voucher_info() == Bandwidth.name() {
+            client.blind_sign(&blind_sign_request_body).await?
+        } else {
+            client
+                .blind_sign_credential(attributes.voucher_info(), &blind_sign_request_body)
+                .await?
+        }
     } else {
         client
             .partial_bandwidth_credential(&attributes.tx_hash().to_string())
@@ -88,37 +176,130 @@ async fn obtain_partial_credential(
     Ok(unblinded_signature)
 }
 
+// only failures that might go away on their own are worth retrying;
+// an invalid share is going to be just as invalid the second time around
+fn is_retryable(err: &Error) -> bool {
+    matches!(err, Error::ValidatorClientError(_))
+}
+
+async fn obtain_partial_credential_with_retries(
+    params: &Parameters,
+    attributes: &BandwidthVoucher,
+    coconut_api_client: &CoconutApiClient,
+    config: &SignerRequestConfig,
+) -> Result<Signature, String> {
+    let mut attempt = 0;
+    loop {
+        let res = tokio::time::timeout(
+            config.request_timeout,
+            obtain_partial_credential(
+                params,
+                attributes,
+                &coconut_api_client.api_client,
+                &coconut_api_client.verification_key,
+            ),
+        )
+        .await;
+
+        let reason = match res {
+            Ok(Ok(signature)) => return Ok(signature),
+            Ok(Err(err)) if !is_retryable(&err) => return Err(err.to_string()),
+            Ok(Err(err)) => err.to_string(),
+            Err(_) => format!("request timed out after {:?}", config.request_timeout),
+        };
+
+        if attempt >= config.max_retries {
+            return Err(format!("{reason} (after {} attempts)", attempt + 1));
+        }
+        attempt += 1;
+        debug!(
+            "signer {} failed with: {reason}. Retrying (attempt {attempt} out of {})",
+            coconut_api_client.node_id, config.max_retries
+        );
+        tokio::time::sleep(config.retry_backoff).await;
+    }
+}
+
+/// Polls all the provided signer requests concurrently and returns as soon as `threshold`
+/// of them have succeeded (or once all of them have completed). Requests that are still
+/// in flight at that point are dropped.
+async fn collect_until_threshold<F, T>(
+    requests: impl IntoIterator<Item = F>,
+    threshold: usize,
+) -> (Vec<T>, Vec<SkippedSigner>)
+where
+    F: Future<Output = (u64, Result<T, String>)>,
+{
+    let mut pending: FuturesUnordered<F> = requests.into_iter().collect();
+    let mut obtained = Vec::with_capacity(threshold);
+    let mut skipped = Vec::new();
+
+    while obtained.len() < threshold {
+        match pending.next().await {
+            Some((_, Ok(res))) => obtained.push(res),
+            Some((node_id, Err(reason))) => {
+                warn!("skipping signer {node_id}: {reason}");
+                skipped.push(SkippedSigner { node_id, reason })
+            }
+            None => break,
+        }
+    }
+
+    (obtained, skipped)
+}
+
+/// Concurrently requests partial signatures from all the provided signers and aggregates
+/// them as soon as `threshold` valid shares have been obtained.
 pub async fn obtain_aggregate_signature(
     params: &Parameters,
     attributes: &BandwidthVoucher,
     coconut_api_clients: &[CoconutApiClient],
-) -> Result<Signature, Error> {
+    threshold: u64,
+    config: &SignerRequestConfig,
+) -> Result<AggregatedSignature, Error> {
     if coconut_api_clients.is_empty() {
         return Err(Error::NoValidatorsAvailable);
     }
+    let available = coconut_api_clients.len() as u64;
+    if available < threshold {
+        return Err(Error::InsufficientSigners {
+            available,
+            threshold,
+        });
+    }
     let public_attributes = attributes.get_public_attributes();
     let private_attributes = attributes.get_private_attributes();
 
-    let mut shares = Vec::with_capacity(coconut_api_clients.len());
-    let validators_partial_vks: Vec<_> = coconut_api_clients
-        .iter()
-        .map(|api_client| api_client.verification_key.clone())
-        .collect();
-    let indices: Vec<_> = coconut_api_clients
+    let requests = coconut_api_clients
         .iter()
-        .map(|api_client| api_client.node_id)
-        .collect();
+        .map(|coconut_api_client| async move {
+            let res = obtain_partial_credential_with_retries(
+                params,
+                attributes,
+                coconut_api_client,
+                config,
+            )
+            .await
+            .map(|signature| (signature, coconut_api_client));
+            (coconut_api_client.node_id, res)
+        });
 
-    for coconut_api_client in coconut_api_clients.iter() {
-        let signature = obtain_partial_credential(
-            params,
-            attributes,
-            &coconut_api_client.api_client,
-            &coconut_api_client.verification_key,
-        )
-        .await?;
-        let share = SignatureShare::new(signature, coconut_api_client.node_id);
-        shares.push(share)
+    let (obtained, skipped_signers) = collect_until_threshold(requests, threshold as usize).await;
+    if (obtained.len() as u64) < threshold {
+        return Err(Error::ThresholdNotReached {
+            threshold,
+            obtained: obtained.len() as u64,
+            skipped: skipped_signers,
+        });
+    }
+
+    let mut shares = Vec::with_capacity(obtained.len());
+    let mut validators_partial_vks = Vec::with_capacity(obtained.len());
+    let mut indices = Vec::with_capacity(obtained.len());
+    for (signature, coconut_api_client) in obtained {
+        shares.push(SignatureShare::new(signature, coconut_api_client.node_id));
+        validators_partial_vks.push(coconut_api_client.verification_key.clone());
+        indices.push(coconut_api_client.node_id);
     }
 
     let mut attributes = Vec::with_capacity(private_attributes.len() + public_attributes.len());
@@ -128,19 +309,21 @@ pub async fn obtain_aggregate_signature(
     let verification_key =
         aggregate_verification_keys(&validators_partial_vks, Some(indices.as_ref()))?;
 
-    Ok(aggregate_signature_shares(
-        params,
-        &verification_key,
-        &attributes,
-        &shares,
-    )?)
+    let signature = aggregate_signature_shares(params, &verification_key, &attributes, &shares)?;
+
+    Ok(AggregatedSignature {
+        signature,
+        skipped_signers,
+    })
 }
 
 // TODO: better type flow
+#[allow(clippy::too_many_arguments)]
 pub fn prepare_credential_for_spending(
     params: &Parameters,
     voucher_value: u64,
     voucher_info: String,
+    expiry_epoch: u64,
     serial_number: Attribute,
     binding_number: Attribute,
     signature: &Signature,
@@ -155,9 +338,116 @@ pub fn prepare_credential_for_spending(
     )?;
 
     Ok(Credential::new(
+        credential_attributes(expiry_epoch),
+        theta,
+        voucher_value,
+        voucher_info,
+        expiry_epoch,
+    ))
+}
+
+/// Shows the credential as the ticket with the provided index,
+/// i.e. one of `2^TICKET_INDEX_BITS` equally valued tickets.
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_ticket_for_spending(
+    params: &Parameters,
+    voucher_value: u64,
+    voucher_info: String,
+    expiry_epoch: u64,
+    serial_number: Attribute,
+    binding_number: Attribute,
+    ticket_index: u64,
+    signature: &Signature,
+    verification_key: &VerificationKey,
+) -> Result<TicketCredential, Error> {
+    let theta = prove_ticket(
+        params,
+        verification_key,
+        signature,
+        serial_number,
+        binding_number,
+        ticket_index,
+        TICKET_INDEX_BITS,
+    )?;
+
+    Ok(TicketCredential::new(
         PUBLIC_ATTRIBUTES + PRIVATE_ATTRIBUTES,
         theta,
         voucher_value,
         voucher_info,
+        expiry_epoch,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer(
+        node_id: u64,
+        delay_ms: u64,
+        res: Result<u64, &'static str>,
+    ) -> impl Future<Output = (u64, Result<u64, String>)> {
+        async move {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            (node_id, res.map_err(|err| err.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn collection_stops_once_threshold_is_reached() {
+        let requests = vec![
+            signer(1, 5, Ok(1)),
+            signer(2, 10, Ok(2)),
+            signer(3, 10_000, Ok(3)),
+        ];
+
+        let (obtained, skipped) = collect_until_threshold(requests, 2).await;
+        assert_eq!(obtained, vec![1, 2]);
+        assert!(skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_signers_are_skipped_and_reported() {
+        let requests = vec![
+            signer(1, 5, Err("invalid share")),
+            signer(2, 10, Ok(2)),
+            signer(3, 15, Ok(3)),
+        ];
+
+        let (obtained, skipped) = collect_until_threshold(requests, 2).await;
+        assert_eq!(obtained, vec![2, 3]);
+        assert_eq!(
+            skipped,
+            vec![SkippedSigner {
+                node_id: 1,
+                reason: "invalid share".to_string()
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn collection_finishes_when_signers_run_out() {
+        let requests = vec![
+            signer(1, 5, Err("timed out")),
+            signer(2, 10, Ok(2)),
+            signer(3, 15, Err("invalid share")),
+        ];
+
+        let (obtained, skipped) = collect_until_threshold(requests, 2).await;
+        assert_eq!(obtained, vec![2]);
+        assert_eq!(skipped.len(), 2);
+    }
+
+    #[test]
+    fn signer_request_config_builders() {
+        let config = SignerRequestConfig::default()
+            .with_request_timeout(Duration::from_secs(3))
+            .with_max_retries(5)
+            .with_retry_backoff(Duration::from_millis(100));
+
+        assert_eq!(config.request_timeout, Duration::from_secs(3));
+        assert_eq!(config.max_retries, 5);
+        assert_eq!(config.retry_backoff, Duration::from_millis(100));
+    }
+}
@@ -16,6 +16,19 @@ pub enum Error {
     #[error("Could not contact any validator")]
     NoValidatorsAvailable,
 
+    #[error(
+        "There are only {available} signers available while the signing threshold is {threshold}"
+    )]
+    InsufficientSigners { available: u64, threshold: u64 },
+
+    #[cfg(feature = "coconut")]
+    #[error("Obtained only {obtained} valid signature shares while the signing threshold is {threshold}. Skipped signers: {}", format_skipped_signers(.skipped))]
+    ThresholdNotReached {
+        threshold: u64,
+        obtained: u64,
+        skipped: Vec<crate::coconut::utils::SkippedSigner>,
+    },
+
     #[cfg(feature = "coconut")]
     #[error("Ran into a coconut error - {0}")]
     CoconutError(#[from] CoconutError),
@@ -31,4 +44,22 @@ pub enum Error {
 
     #[error("Could not parse the key - {0}")]
     ParsePublicKey(#[from] KeyRecoveryError),
+
+    #[error("Expected a credential of type {expected}, received {received} instead")]
+    UnexpectedCredentialType { expected: String, received: String },
+
+    #[error("The credential expired at epoch {expiry_epoch}")]
+    ExpiredCredential { expiry_epoch: u64 },
+
+    #[error("The credential failed to verify")]
+    InvalidCredential,
+}
+
+#[cfg(feature = "coconut")]
+fn format_skipped_signers(skipped: &[crate::coconut::utils::SkippedSigner]) -> String {
+    skipped
+        .iter()
+        .map(|signer| signer.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
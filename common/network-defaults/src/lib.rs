@@ -331,6 +331,10 @@ pub const UTOKENS_TO_BURN: u64 = TOKENS_TO_BURN * 1000000;
 pub const BANDWIDTH_VALUE: u64 = UTOKENS_TO_BURN * BYTES_PER_UTOKEN;
 
 pub const VOUCHER_INFO: &str = "BandwidthVoucher";
+/// Credentials granting access to services, e.g. premium exits, rather than gateway bandwidth
+pub const SERVICE_ACCESS_INFO: &str = "ServiceAccess";
+/// Bandwidth vouchers that can only be spent at the gateways as individual tickets
+pub const DIVISIBLE_VOUCHER_INFO: &str = "DivisibleBandwidthVoucher";
 
 pub const ETH_MIN_BLOCK_DEPTH: usize = 7;
 
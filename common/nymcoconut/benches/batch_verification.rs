@@ -0,0 +1,129 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use nymcoconut::{
+    aggregate_signature_shares, aggregate_verification_keys, batch_verify_credentials, blind_sign,
+    prepare_blind_sign, prove_bandwidth_credential, setup, ttp_keygen, verify_credential,
+    Attribute, KeyPair, Parameters, SignatureShare, Theta, VerificationKey,
+};
+use std::time::Duration;
+
+const NUM_AUTHORITIES: u64 = 10;
+const THRESHOLD: u64 = 7;
+const NUM_PUBLIC_ATTRS: usize = 2;
+const BATCH_SIZES: [usize; 5] = [1, 2, 8, 32, 128];
+
+fn issue_credential(
+    params: &Parameters,
+    keypairs: &[KeyPair],
+    verification_key: &VerificationKey,
+) -> (Theta, Vec<Attribute>) {
+    let public_attributes = params.n_random_scalars(NUM_PUBLIC_ATTRS);
+    let serial_number = params.random_scalar();
+    let binding_number = params.random_scalar();
+    let private_attributes = vec![serial_number, binding_number];
+
+    let (pedersen_commitments_openings, blind_sign_request) =
+        prepare_blind_sign(params, &private_attributes, &public_attributes).unwrap();
+
+    let signature_shares: Vec<SignatureShare> = keypairs
+        .iter()
+        .enumerate()
+        .map(|(idx, keypair)| {
+            let signature = blind_sign(
+                params,
+                &keypair.secret_key(),
+                &blind_sign_request,
+                &public_attributes,
+            )
+            .unwrap()
+            .unblind(
+                params,
+                &keypair.verification_key(),
+                &private_attributes,
+                &public_attributes,
+                &blind_sign_request.get_commitment_hash(),
+                &pedersen_commitments_openings,
+            )
+            .unwrap();
+            SignatureShare::new(signature, (idx + 1) as u64)
+        })
+        .collect();
+
+    let mut attributes = private_attributes;
+    attributes.extend_from_slice(&public_attributes);
+    let signature =
+        aggregate_signature_shares(params, verification_key, &attributes, &signature_shares)
+            .unwrap();
+
+    let theta = prove_bandwidth_credential(
+        params,
+        verification_key,
+        &signature,
+        serial_number,
+        binding_number,
+    )
+    .unwrap();
+
+    (theta, public_attributes)
+}
+
+fn bench_batch_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("benchmark-batch-verification");
+    group.measurement_time(Duration::from_secs(30));
+
+    let params = setup(NUM_PUBLIC_ATTRS as u32 + 2).unwrap();
+    let keypairs = ttp_keygen(&params, THRESHOLD, NUM_AUTHORITIES).unwrap();
+    let verification_keys: Vec<VerificationKey> = keypairs
+        .iter()
+        .map(|keypair| keypair.verification_key())
+        .collect();
+    let indices: Vec<u64> = (1..=NUM_AUTHORITIES).collect();
+    let verification_key = aggregate_verification_keys(&verification_keys, Some(&indices)).unwrap();
+
+    let max_batch_size = *BATCH_SIZES.iter().max().unwrap();
+    let credentials: Vec<_> = (0..max_batch_size)
+        .map(|_| issue_credential(&params, &keypairs, &verification_key))
+        .collect();
+
+    for batch_size in BATCH_SIZES {
+        let batch: Vec<(&Theta, &[Attribute])> = credentials
+            .iter()
+            .take(batch_size)
+            .map(|(theta, public_attributes)| (theta, public_attributes.as_slice()))
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("[Verifier] individual_verify_credential", batch_size),
+            &batch,
+            |b, batch| {
+                b.iter(|| {
+                    for (theta, public_attributes) in batch {
+                        assert!(verify_credential(
+                            &params,
+                            &verification_key,
+                            theta,
+                            public_attributes
+                        ))
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("[Verifier] batch_verify_credentials", batch_size),
+            &batch,
+            |b, batch| {
+                b.iter(|| {
+                    assert!(batch_verify_credentials(&params, &verification_key, batch)
+                        .into_iter()
+                        .all(|valid| valid))
+                })
+            },
+        );
+    }
+}
+
+criterion_group!(benches, bench_batch_verification);
+criterion_main!(benches);
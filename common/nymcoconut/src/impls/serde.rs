@@ -1,7 +1,8 @@
 use crate::elgamal::PrivateKey;
 use crate::scheme::SecretKey;
 use crate::{
-    Base58, BlindSignRequest, BlindedSignature, PublicKey, Signature, Theta, VerificationKey,
+    Base58, BlindSignRequest, BlindedSignature, PublicKey, Signature, Theta, TicketTheta,
+    VerificationKey,
 };
 use serde::de::Unexpected;
 use serde::{de::Error, de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
@@ -54,3 +55,4 @@ impl_serde!(BlindSignRequest, V5);
 impl_serde!(BlindedSignature, V6);
 impl_serde!(Signature, V7);
 impl_serde!(Theta, V8);
+impl_serde!(TicketTheta, V9);
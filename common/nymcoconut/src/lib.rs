@@ -21,6 +21,11 @@ pub use scheme::keygen::SecretKey;
 pub use scheme::keygen::VerificationKey;
 pub use scheme::setup::setup;
 pub use scheme::setup::Parameters;
+pub use scheme::ticket::prove_ticket;
+pub use scheme::ticket::verify_ticket;
+pub use scheme::ticket::TicketTheta;
+pub use scheme::ticket::MAX_TICKET_INDEX_BITS;
+pub use scheme::verification::batch_verify_credentials;
 pub use scheme::verification::check_vk_pairing;
 pub use scheme::verification::prove_bandwidth_credential;
 pub use scheme::verification::verify_credential;
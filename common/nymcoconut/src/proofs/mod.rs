@@ -503,6 +503,371 @@ impl ProofKappaZeta {
     }
 }
 
+/// Second generator of G1 used for the (Pedersen) commitments to the ticket index.
+pub(crate) fn ticket_index_generator() -> G1Projective {
+    hash_g1("ticket_index_commitment")
+}
+
+// CDS-style OR proof that a commitment opens to either 0 or 1:
+// Cj = oj * h (if the bit is 0) or Cj - g1 = oj * h (if the bit is 1)
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+struct ProofBit {
+    // challenge of the "0" branch, the challenge of the "1" branch is implied
+    // as the two have to add up to the challenge of the whole proof
+    challenge_zero: Scalar,
+    response_zero: Scalar,
+    response_one: Scalar,
+}
+
+// the secrets needed for constructing `ProofBit` after the challenge has been computed
+struct BitWitness {
+    bit: bool,
+    witness: Scalar,
+    simulated_challenge: Scalar,
+    simulated_response: Scalar,
+}
+
+/// Proof of possession of a valid credential on (serial number `s`, binding number `b`) alongside
+/// the correctness of the ticket serial number `zeta = (s + i)^-1 * g2`, where `i` is a hidden
+/// index for which it's shown, via its bit decomposition, that `0 <= i < 2^k`.
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub struct ProofTicket {
+    // c
+    challenge: Scalar,
+
+    // responses
+    response_blinder: Scalar,
+    response_serial_number: Scalar,
+    response_binding_number: Scalar,
+    response_index: Scalar,
+    response_index_opening: Scalar,
+
+    bit_proofs: Vec<ProofBit>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn ticket_challenge(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    kappa: &G2Projective,
+    zeta: &G2Projective,
+    index_commitments: &[G1Projective],
+    commitment_kappa: &G2Projective,
+    commitment_zeta: &G2Projective,
+    commitment_index: &G1Projective,
+    commitments_bits: &[(G1Projective, G1Projective)],
+) -> Scalar {
+    let h = ticket_index_generator();
+    let beta_bytes = verification_key
+        .beta_g2
+        .iter()
+        .map(|beta_i| beta_i.to_bytes())
+        .collect::<Vec<_>>();
+    let index_commitments_bytes = index_commitments
+        .iter()
+        .map(|cm| cm.to_bytes())
+        .collect::<Vec<_>>();
+    let commitments_bits_bytes = commitments_bits
+        .iter()
+        .flat_map(|(t0, t1)| [t0.to_bytes(), t1.to_bytes()])
+        .collect::<Vec<_>>();
+
+    compute_challenge::<ChallengeDigest, _, _>(
+        std::iter::once(params.gen1().to_bytes().as_ref())
+            .chain(std::iter::once(h.to_bytes().as_ref()))
+            .chain(std::iter::once(params.gen2().to_bytes().as_ref()))
+            .chain(std::iter::once(kappa.to_bytes().as_ref()))
+            .chain(std::iter::once(zeta.to_bytes().as_ref()))
+            .chain(std::iter::once(verification_key.alpha.to_bytes().as_ref()))
+            .chain(beta_bytes.iter().map(|b| b.as_ref()))
+            .chain(index_commitments_bytes.iter().map(|cm| cm.as_ref()))
+            .chain(std::iter::once(commitment_kappa.to_bytes().as_ref()))
+            .chain(std::iter::once(commitment_zeta.to_bytes().as_ref()))
+            .chain(std::iter::once(commitment_index.to_bytes().as_ref()))
+            .chain(commitments_bits_bytes.iter().map(|cm| cm.as_ref())),
+    )
+}
+
+// C = sum(2^j * Cj) = i * g1 + o * h
+fn combine_index_commitments(index_commitments: &[G1Projective]) -> G1Projective {
+    index_commitments
+        .iter()
+        .rev()
+        .fold(G1Projective::identity(), |acc, cm| acc.double() + cm)
+}
+
+impl ProofTicket {
+    /// Construct non-interactive zero-knowledge proof of correctness of the ticket using
+    /// the Fiat-Shamir heuristic.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn construct(
+        params: &Parameters,
+        verification_key: &VerificationKey,
+        serial_number: &Attribute,
+        binding_number: &Attribute,
+        blinding_factor: &Scalar,
+        index: u64,
+        index_openings: &[Scalar],
+        blinded_message: &G2Projective,
+        ticket_serial_number: &G2Projective,
+        index_commitments: &[G1Projective],
+    ) -> Self {
+        // note: this is only called from `prove_ticket` that already checks whether the index
+        // fits in the specified number of bits and thus index_openings.len() == index_commitments.len()
+        let g1 = params.gen1();
+        let h = ticket_index_generator();
+
+        // create the witnesses
+        let witness_blinder = params.random_scalar();
+        let witness_serial_number = params.random_scalar();
+        let witness_binding_number = params.random_scalar();
+        let witness_index = params.random_scalar();
+        let witness_index_opening = params.random_scalar();
+        let witness_attributes = vec![witness_serial_number, witness_binding_number];
+
+        // witnesses commitments
+        // Aw = g2 * wt + alpha + beta[0] * wm[0] + ... + beta[i] * wm[i]
+        let commitment_kappa = params.gen2() * witness_blinder
+            + verification_key.alpha
+            + witness_attributes
+                .iter()
+                .zip(verification_key.beta_g2.iter())
+                .map(|(wm_i, beta_i)| beta_i * wm_i)
+                .sum::<G2Projective>();
+
+        // (s + i) * zeta = g2
+        let commitment_zeta = ticket_serial_number * (witness_serial_number + witness_index);
+
+        // C = i * g1 + o * h
+        let commitment_index = g1 * witness_index + h * witness_index_opening;
+
+        // for each bit we produce the real commitment for the actual value of the bit
+        // and simulate the other branch
+        let mut bit_witnesses = Vec::with_capacity(index_commitments.len());
+        let mut commitments_bits = Vec::with_capacity(index_commitments.len());
+        for (j, index_commitment) in index_commitments.iter().enumerate() {
+            let bit = (index >> j) & 1 == 1;
+            let witness = params.random_scalar();
+            let simulated_challenge = params.random_scalar();
+            let simulated_response = params.random_scalar();
+
+            let real = h * witness;
+            let commitments = if bit {
+                let simulated = h * simulated_response + index_commitment * simulated_challenge;
+                (simulated, real)
+            } else {
+                let simulated =
+                    h * simulated_response + (index_commitment - g1) * simulated_challenge;
+                (real, simulated)
+            };
+
+            commitments_bits.push(commitments);
+            bit_witnesses.push(BitWitness {
+                bit,
+                witness,
+                simulated_challenge,
+                simulated_response,
+            })
+        }
+
+        let challenge = ticket_challenge(
+            params,
+            verification_key,
+            blinded_message,
+            ticket_serial_number,
+            index_commitments,
+            &commitment_kappa,
+            &commitment_zeta,
+            &commitment_index,
+            &commitments_bits,
+        );
+
+        // responses
+        let index_opening = index_openings
+            .iter()
+            .rev()
+            .fold(Scalar::zero(), |acc, o_j| acc.double() + o_j);
+
+        let response_blinder = produce_response(&witness_blinder, &challenge, blinding_factor);
+        let response_serial_number =
+            produce_response(&witness_serial_number, &challenge, serial_number);
+        let response_binding_number =
+            produce_response(&witness_binding_number, &challenge, binding_number);
+        let response_index = produce_response(&witness_index, &challenge, &Scalar::from(index));
+        let response_index_opening =
+            produce_response(&witness_index_opening, &challenge, &index_opening);
+
+        let bit_proofs = bit_witnesses
+            .into_iter()
+            .zip(index_openings.iter())
+            .map(|(bit_witness, opening)| {
+                let real_challenge = challenge - bit_witness.simulated_challenge;
+                let real_response =
+                    produce_response(&bit_witness.witness, &real_challenge, opening);
+                if bit_witness.bit {
+                    ProofBit {
+                        challenge_zero: bit_witness.simulated_challenge,
+                        response_zero: bit_witness.simulated_response,
+                        response_one: real_response,
+                    }
+                } else {
+                    ProofBit {
+                        challenge_zero: real_challenge,
+                        response_zero: real_response,
+                        response_one: bit_witness.simulated_response,
+                    }
+                }
+            })
+            .collect();
+
+        ProofTicket {
+            challenge,
+            response_blinder,
+            response_serial_number,
+            response_binding_number,
+            response_index,
+            response_index_opening,
+            bit_proofs,
+        }
+    }
+
+    pub(crate) fn private_attributes_len(&self) -> usize {
+        2
+    }
+
+    pub(crate) fn index_bits(&self) -> usize {
+        self.bit_proofs.len()
+    }
+
+    pub(crate) fn verify(
+        &self,
+        params: &Parameters,
+        verification_key: &VerificationKey,
+        kappa: &G2Projective,
+        zeta: &G2Projective,
+        index_commitments: &[G1Projective],
+    ) -> bool {
+        if index_commitments.len() != self.bit_proofs.len() {
+            return false;
+        }
+
+        let g1 = params.gen1();
+        let h = ticket_index_generator();
+
+        let response_attributes = vec![self.response_serial_number, self.response_binding_number];
+        // re-compute witnesses commitments
+        // Aw = (c * kappa) + (rt * g2) + ((1 - c) * alpha) + (rm[0] * beta[0]) + ... + (rm[i] * beta[i])
+        let commitment_kappa = kappa * self.challenge
+            + params.gen2() * self.response_blinder
+            + verification_key.alpha * (Scalar::one() - self.challenge)
+            + response_attributes
+                .iter()
+                .zip(verification_key.beta_g2.iter())
+                .map(|(priv_attr, beta_i)| beta_i * priv_attr)
+                .sum::<G2Projective>();
+
+        let commitment_zeta = zeta * (self.response_serial_number + self.response_index)
+            + params.gen2() * self.challenge;
+
+        let commitment_index = combine_index_commitments(index_commitments) * self.challenge
+            + g1 * self.response_index
+            + h * self.response_index_opening;
+
+        let commitments_bits = self
+            .bit_proofs
+            .iter()
+            .zip(index_commitments.iter())
+            .map(|(bit_proof, index_commitment)| {
+                let challenge_one = self.challenge - bit_proof.challenge_zero;
+                (
+                    h * bit_proof.response_zero + index_commitment * bit_proof.challenge_zero,
+                    h * bit_proof.response_one + (index_commitment - g1) * challenge_one,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let challenge = ticket_challenge(
+            params,
+            verification_key,
+            kappa,
+            zeta,
+            index_commitments,
+            &commitment_kappa,
+            &commitment_zeta,
+            &commitment_index,
+            &commitments_bits,
+        );
+
+        challenge == self.challenge
+    }
+
+    // challenge || response blinder || response serial number || response binding number ||
+    // response index || response index opening || (challenge zero || response zero || response one) ...
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity((6 + 3 * self.bit_proofs.len()) * 32);
+
+        bytes.extend_from_slice(&self.challenge.to_bytes());
+        bytes.extend_from_slice(&self.response_blinder.to_bytes());
+        bytes.extend_from_slice(&self.response_serial_number.to_bytes());
+        bytes.extend_from_slice(&self.response_binding_number.to_bytes());
+        bytes.extend_from_slice(&self.response_index.to_bytes());
+        bytes.extend_from_slice(&self.response_index_opening.to_bytes());
+
+        for bit_proof in &self.bit_proofs {
+            bytes.extend_from_slice(&bit_proof.challenge_zero.to_bytes());
+            bytes.extend_from_slice(&bit_proof.response_zero.to_bytes());
+            bytes.extend_from_slice(&bit_proof.response_one.to_bytes());
+        }
+
+        bytes
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 32 * 6 {
+            return Err(CoconutError::DeserializationMinLength {
+                min: 32 * 6,
+                actual: bytes.len(),
+            });
+        }
+        if (bytes.len() - 32 * 6) % (32 * 3) != 0 {
+            return Err(CoconutError::DeserializationInvalidLength {
+                actual: bytes.len(),
+                modulus_target: bytes.len() - 32 * 6,
+                modulus: 32 * 3,
+                object: "ticket proof".to_string(),
+                target: 32 * 6,
+            });
+        }
+
+        let scalars = try_deserialize_scalar_vec(
+            (bytes.len() / 32) as u64,
+            bytes,
+            CoconutError::Deserialization("failed to deserialize the ticket proof".to_string()),
+        )?;
+
+        let bit_proofs = scalars[6..]
+            .chunks_exact(3)
+            .map(|chunk| ProofBit {
+                challenge_zero: chunk[0],
+                response_zero: chunk[1],
+                response_one: chunk[2],
+            })
+            .collect();
+
+        Ok(ProofTicket {
+            challenge: scalars[0],
+            response_blinder: scalars[1],
+            response_serial_number: scalars[2],
+            response_binding_number: scalars[3],
+            response_index: scalars[4],
+            response_index_opening: scalars[5],
+            bit_proofs,
+        })
+    }
+}
+
 // proof builder:
 // - commitment
 // - challenge
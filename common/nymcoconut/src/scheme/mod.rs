@@ -23,6 +23,7 @@ pub mod double_use;
 pub mod issuance;
 pub mod keygen;
 pub mod setup;
+pub mod ticket;
 pub mod verification;
 
 pub type SignerIndex = u64;
@@ -0,0 +1,452 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Divisible credentials.
+//!
+//! A single credential on (serial number `s`, binding number `b`) can be shown as up to `2^k`
+//! mutually unlinkable tickets. The ticket with (hidden) index `i` reveals the ticket serial number
+//! `zeta_i = (s + i)^-1 * g2` (i.e. the Dodis-Yampolskiy PRF of the index keyed with `s`),
+//! so showing the same ticket twice reveals the same serial number, while tickets with different
+//! indices can't be linked with each other. The index itself is committed to bit-by-bit so that
+//! it could be proven that `0 <= i < 2^k` without revealing it.
+
+use std::convert::TryFrom;
+use std::convert::TryInto;
+
+use bls12_381::{G1Projective, G2Prepared, G2Projective, Scalar};
+use group::{Curve, Group};
+
+use crate::error::{CoconutError, Result};
+use crate::proofs::{ticket_index_generator, ProofTicket};
+use crate::scheme::double_use::BlindedSerialNumber;
+use crate::scheme::setup::Parameters;
+use crate::scheme::verification::{check_bilinear_pairing, compute_kappa};
+use crate::scheme::Signature;
+use crate::scheme::VerificationKey;
+use crate::traits::{Base58, Bytable};
+use crate::utils::{try_deserialize_g1_projective, try_deserialize_g2_projective};
+use crate::Attribute;
+
+/// Maximum supported number of bits of the ticket index.
+pub const MAX_TICKET_INDEX_BITS: u8 = 32;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TicketTheta {
+    // blinded_message (kappa)
+    pub blinded_message: G2Projective,
+    // serial number of this particular ticket (zeta_i)
+    pub ticket_serial_number: G2Projective,
+    // sigma
+    pub credential: Signature,
+    // commitments to the bits of the ticket index
+    pub index_commitments: Vec<G1Projective>,
+    // pi_t
+    pub pi_t: ProofTicket,
+}
+
+impl TryFrom<&[u8]> for TicketTheta {
+    type Error = CoconutError;
+
+    fn try_from(bytes: &[u8]) -> Result<TicketTheta> {
+        if bytes.len() < 289 {
+            return Err(CoconutError::DeserializationMinLength {
+                min: 289,
+                actual: bytes.len(),
+            });
+        }
+
+        let blinded_message_bytes = bytes[..96].try_into().unwrap();
+        let blinded_message = try_deserialize_g2_projective(
+            &blinded_message_bytes,
+            CoconutError::Deserialization(
+                "failed to deserialize the blinded message (kappa)".to_string(),
+            ),
+        )?;
+
+        let ticket_serial_number_bytes = bytes[96..192].try_into().unwrap();
+        let ticket_serial_number = try_deserialize_g2_projective(
+            &ticket_serial_number_bytes,
+            CoconutError::Deserialization(
+                "failed to deserialize the ticket serial number (zeta)".to_string(),
+            ),
+        )?;
+        let credential = Signature::try_from(&bytes[192..288])?;
+
+        let index_bits = bytes[288] as usize;
+        let commitments_end = 289 + index_bits * 48;
+        if bytes.len() < commitments_end {
+            return Err(CoconutError::DeserializationMinLength {
+                min: commitments_end,
+                actual: bytes.len(),
+            });
+        }
+        let index_commitments = bytes[289..commitments_end]
+            .chunks_exact(48)
+            .map(|chunk| {
+                try_deserialize_g1_projective(
+                    chunk.try_into().unwrap(),
+                    CoconutError::Deserialization(
+                        "failed to deserialize the ticket index commitment".to_string(),
+                    ),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let pi_t = ProofTicket::from_bytes(&bytes[commitments_end..])?;
+
+        Ok(TicketTheta {
+            blinded_message,
+            ticket_serial_number,
+            credential,
+            index_commitments,
+            pi_t,
+        })
+    }
+}
+
+impl TicketTheta {
+    fn verify_proof(&self, params: &Parameters, verification_key: &VerificationKey) -> bool {
+        self.pi_t.verify(
+            params,
+            verification_key,
+            &self.blinded_message,
+            &self.ticket_serial_number,
+            &self.index_commitments,
+        )
+    }
+
+    /// Number of bits of the ticket index, i.e. the ticket was shown as one of `2^index_bits` tickets.
+    pub fn index_bits(&self) -> u8 {
+        self.index_commitments.len() as u8
+    }
+
+    // blinded message (kappa) || ticket serial number (zeta) || credential || index bits ||
+    // index commitments || pi_t
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let blinded_message_bytes = self.blinded_message.to_affine().to_compressed();
+        let ticket_serial_number_bytes = self.ticket_serial_number.to_affine().to_compressed();
+        let credential_bytes = self.credential.to_bytes();
+        let proof_bytes = self.pi_t.to_bytes();
+
+        let mut bytes =
+            Vec::with_capacity(289 + self.index_commitments.len() * 48 + proof_bytes.len());
+        bytes.extend_from_slice(&blinded_message_bytes);
+        bytes.extend_from_slice(&ticket_serial_number_bytes);
+        bytes.extend_from_slice(&credential_bytes);
+        bytes.push(self.index_bits());
+        for index_commitment in &self.index_commitments {
+            bytes.extend_from_slice(&index_commitment.to_affine().to_compressed());
+        }
+        bytes.extend_from_slice(&proof_bytes);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<TicketTheta> {
+        TicketTheta::try_from(bytes)
+    }
+
+    pub fn ticket_serial_number_bs58(&self) -> String {
+        let ticket_serial_number = BlindedSerialNumber {
+            inner: self.ticket_serial_number,
+        };
+        ticket_serial_number.to_bs58()
+    }
+}
+
+impl Bytable for TicketTheta {
+    fn to_byte_vec(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    fn try_from_byte_slice(slice: &[u8]) -> Result<Self> {
+        TicketTheta::try_from(slice)
+    }
+}
+
+impl Base58 for TicketTheta {}
+
+pub fn compute_ticket_serial_number(
+    params: &Parameters,
+    serial_number: Attribute,
+    index: u64,
+) -> Result<G2Projective> {
+    let exponent: Option<Scalar> = (serial_number + Scalar::from(index)).invert().into();
+    let exponent = exponent.ok_or_else(|| {
+        CoconutError::Verification(
+            "can't derive the ticket serial number for the provided index".to_string(),
+        )
+    })?;
+    Ok(params.gen2() * exponent)
+}
+
+/// Shows the credential as the ticket with the provided index out of `2^index_bits` tickets.
+pub fn prove_ticket(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    signature: &Signature,
+    serial_number: Attribute,
+    binding_number: Attribute,
+    index: u64,
+    index_bits: u8,
+) -> Result<TicketTheta> {
+    if verification_key.beta_g2.len() < 2 {
+        return Err(
+            CoconutError::Verification(
+                format!("Tried to prove a credential for higher than supported by the provided verification key number of attributes (max: {}, requested: 2)",
+                        verification_key.beta_g2.len()
+                )));
+    }
+
+    if index_bits == 0 || index_bits > MAX_TICKET_INDEX_BITS {
+        return Err(CoconutError::Verification(format!(
+            "the number of bits of the ticket index has to be between 1 and {MAX_TICKET_INDEX_BITS}, got {index_bits}"
+        )));
+    }
+
+    if index >> index_bits != 0 {
+        return Err(CoconutError::Verification(format!(
+            "ticket index {index} does not fit in {index_bits} bits"
+        )));
+    }
+
+    // Randomize the signature
+    let (signature_prime, sign_blinding_factor) = signature.randomise(params);
+
+    let private_attributes = vec![serial_number, binding_number];
+    let blinded_message = compute_kappa(
+        params,
+        verification_key,
+        &private_attributes,
+        sign_blinding_factor,
+    );
+
+    let ticket_serial_number = compute_ticket_serial_number(params, serial_number, index)?;
+
+    // commit to each bit of the index individually
+    let h = ticket_index_generator();
+    let index_openings = params.n_random_scalars(index_bits as usize);
+    let index_commitments = index_openings
+        .iter()
+        .enumerate()
+        .map(|(j, opening)| {
+            let commitment = h * opening;
+            if (index >> j) & 1 == 1 {
+                commitment + params.gen1()
+            } else {
+                commitment
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let pi_t = ProofTicket::construct(
+        params,
+        verification_key,
+        &serial_number,
+        &binding_number,
+        &sign_blinding_factor,
+        index,
+        &index_openings,
+        &blinded_message,
+        &ticket_serial_number,
+        &index_commitments,
+    );
+
+    Ok(TicketTheta {
+        blinded_message,
+        ticket_serial_number,
+        credential: signature_prime,
+        index_commitments,
+        pi_t,
+    })
+}
+
+/// Verifies the ticket shown as one of `2^index_bits` tickets. Note that the number of bits has
+/// to be agreed upon in advance as otherwise the user could split the credential into arbitrary
+/// number of tickets.
+pub fn verify_ticket(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    theta: &TicketTheta,
+    public_attributes: &[Attribute],
+    index_bits: u8,
+) -> bool {
+    if theta.index_bits() != index_bits || theta.pi_t.index_bits() != index_bits as usize {
+        return false;
+    }
+
+    if public_attributes.len() + theta.pi_t.private_attributes_len()
+        > verification_key.beta_g2.len()
+    {
+        return false;
+    }
+
+    if !theta.verify_proof(params, verification_key) {
+        return false;
+    }
+
+    let kappa = theta.blinded_message
+        + public_attributes
+            .iter()
+            .zip(
+                verification_key
+                    .beta_g2
+                    .iter()
+                    .skip(theta.pi_t.private_attributes_len()),
+            )
+            .map(|(pub_attr, beta_i)| beta_i * pub_attr)
+            .sum::<G2Projective>();
+
+    check_bilinear_pairing(
+        &theta.credential.0.to_affine(),
+        &G2Prepared::from(kappa.to_affine()),
+        &(theta.credential.1).to_affine(),
+        params.prepared_miller_g2(),
+    ) && !bool::from(theta.credential.0.is_identity())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scheme::issuance::sign;
+    use crate::scheme::keygen::keygen;
+    use crate::scheme::setup::setup;
+
+    use super::*;
+
+    #[test]
+    fn tickets_of_valid_credential_verify() {
+        let mut params = setup(4).unwrap();
+        let keypair = keygen(&params);
+        let vk = keypair.verification_key();
+
+        let serial_number = params.random_scalar();
+        let binding_number = params.random_scalar();
+        let public_attributes = params.n_random_scalars(2);
+
+        let mut attributes = vec![serial_number, binding_number];
+        attributes.extend_from_slice(&public_attributes);
+        let signature = sign(&mut params, &keypair.secret_key(), &attributes).unwrap();
+
+        let ticket = |index| {
+            prove_ticket(
+                &params,
+                &vk,
+                &signature,
+                serial_number,
+                binding_number,
+                index,
+                3,
+            )
+            .unwrap()
+        };
+
+        let first = ticket(0);
+        let last = ticket(7);
+        let first_again = ticket(0);
+
+        assert!(verify_ticket(&params, &vk, &first, &public_attributes, 3));
+        assert!(verify_ticket(&params, &vk, &last, &public_attributes, 3));
+        assert!(verify_ticket(
+            &params,
+            &vk,
+            &first_again,
+            &public_attributes,
+            3
+        ));
+
+        // different tickets have different serial numbers,
+        // but showing the same ticket again reveals the same one
+        assert_ne!(
+            first.ticket_serial_number_bs58(),
+            last.ticket_serial_number_bs58()
+        );
+        assert_eq!(
+            first.ticket_serial_number_bs58(),
+            first_again.ticket_serial_number_bs58()
+        );
+
+        // the number of tickets is fixed by the verifier
+        assert!(!verify_ticket(&params, &vk, &first, &public_attributes, 4));
+
+        // and the public attributes have to match
+        let other_attributes = params.n_random_scalars(2);
+        assert!(!verify_ticket(&params, &vk, &first, &other_attributes, 3));
+    }
+
+    fn random_ticket(params: &Parameters, index: u64, index_bits: u8) -> TicketTheta {
+        let keypair = keygen(params);
+        let r = params.random_scalar();
+        let s = params.random_scalar();
+
+        let signature = Signature(params.gen1() * r, params.gen1() * s);
+        let serial_number = params.random_scalar();
+        let binding_number = params.random_scalar();
+
+        prove_ticket(
+            params,
+            &keypair.verification_key(),
+            &signature,
+            serial_number,
+            binding_number,
+            index,
+            index_bits,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn ticket_theta_bytes_roundtrip() {
+        let params = setup(4).unwrap();
+        let theta = random_ticket(&params, 5, 4);
+
+        let bytes = theta.to_bytes();
+        assert_eq!(TicketTheta::try_from(bytes.as_slice()).unwrap(), theta);
+
+        // truncated right after the index commitments, i.e. within the proof
+        let commitments_end = 289 + 4 * 48;
+        assert!(TicketTheta::try_from(&bytes[..commitments_end + 32]).is_err());
+        assert!(TicketTheta::try_from(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn index_has_to_fit_in_the_specified_bits() {
+        let params = setup(4).unwrap();
+        let keypair = keygen(&params);
+        let signature = Signature(params.gen1() * params.random_scalar(), params.gen1().into());
+
+        let serial_number = params.random_scalar();
+        let binding_number = params.random_scalar();
+        let vk = keypair.verification_key();
+
+        assert!(prove_ticket(
+            &params,
+            &vk,
+            &signature,
+            serial_number,
+            binding_number,
+            16,
+            4
+        )
+        .is_err());
+        assert!(prove_ticket(
+            &params,
+            &vk,
+            &signature,
+            serial_number,
+            binding_number,
+            15,
+            4
+        )
+        .is_ok());
+        assert!(prove_ticket(
+            &params,
+            &vk,
+            &signature,
+            serial_number,
+            binding_number,
+            0,
+            0
+        )
+        .is_err());
+    }
+}
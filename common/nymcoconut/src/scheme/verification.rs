@@ -5,7 +5,7 @@ use core::ops::Neg;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 
-use bls12_381::{multi_miller_loop, G1Affine, G2Prepared, G2Projective, Scalar};
+use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Prepared, G2Projective, Scalar};
 use group::{Curve, Group};
 
 use crate::error::{CoconutError, Result};
@@ -245,7 +245,8 @@ pub fn check_vk_pairing(
     true
 }
 
-pub fn verify_credential(
+// the part of the credential verification that does not involve any pairings
+fn check_credential_preconditions(
     params: &Parameters,
     verification_key: &VerificationKey,
     theta: &Theta,
@@ -257,11 +258,19 @@ pub fn verify_credential(
         return false;
     }
 
-    if !theta.verify_proof(params, verification_key) {
+    if bool::from(theta.credential.0.is_identity()) {
         return false;
     }
 
-    let kappa = if public_attributes.is_empty() {
+    theta.verify_proof(params, verification_key)
+}
+
+fn credential_kappa(
+    verification_key: &VerificationKey,
+    theta: &Theta,
+    public_attributes: &[Attribute],
+) -> G2Projective {
+    if public_attributes.is_empty() {
         theta.blinded_message
     } else {
         let signed_public_attributes = public_attributes
@@ -276,14 +285,108 @@ pub fn verify_credential(
             .sum::<G2Projective>();
 
         theta.blinded_message + signed_public_attributes
-    };
+    }
+}
 
+fn check_credential_pairing(
+    params: &Parameters,
+    credential: &Signature,
+    kappa: &G2Projective,
+) -> bool {
     check_bilinear_pairing(
-        &theta.credential.0.to_affine(),
+        &credential.0.to_affine(),
         &G2Prepared::from(kappa.to_affine()),
-        &(theta.credential.1).to_affine(),
+        &credential.1.to_affine(),
         params.prepared_miller_g2(),
-    ) && !bool::from(theta.credential.0.is_identity())
+    )
+}
+
+pub fn verify_credential(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    theta: &Theta,
+    public_attributes: &[Attribute],
+) -> bool {
+    if !check_credential_preconditions(params, verification_key, theta, public_attributes) {
+        return false;
+    }
+
+    let kappa = credential_kappa(verification_key, theta, public_attributes);
+    check_credential_pairing(params, &theta.credential, &kappa)
+}
+
+// Checks e(h_i, kappa_i) == e(s_i, g2) for all the provided credentials at once by checking
+// prod_i e(r_i * h_i, kappa_i) * e(-sum_i(r_i * s_i), g2) == 1 for random, secret r_i.
+// If any of the individual equations does not hold, the combined one only holds
+// with negligible probability.
+fn batch_check_credential_pairings(
+    params: &Parameters,
+    credentials: &[(&Signature, G2Projective)],
+) -> bool {
+    let blinding_factors = params.n_random_scalars(credentials.len());
+
+    let mut blinded_s = G1Projective::identity();
+    let mut terms = Vec::with_capacity(credentials.len() + 1);
+    for ((credential, kappa), r) in credentials.iter().zip(blinding_factors.iter()) {
+        terms.push((
+            (credential.0 * r).to_affine(),
+            G2Prepared::from(kappa.to_affine()),
+        ));
+        blinded_s += credential.1 * r;
+    }
+    let blinded_s = blinded_s.neg().to_affine();
+
+    let mut miller_terms: Vec<(&G1Affine, &G2Prepared)> =
+        terms.iter().map(|(h, kappa)| (h, kappa)).collect();
+    miller_terms.push((&blinded_s, params.prepared_miller_g2()));
+
+    multi_miller_loop(&miller_terms)
+        .final_exponentiation()
+        .is_identity()
+        .into()
+}
+
+/// Verifies multiple credentials issued under the same verification key, sharing a single
+/// multi-pairing between all of them.
+///
+/// Returns the verification result of each credential, in the order they were provided.
+/// If the combined check fails, every credential is re-checked individually
+/// so that only the invalid ones are rejected.
+pub fn batch_verify_credentials(
+    params: &Parameters,
+    verification_key: &VerificationKey,
+    credentials: &[(&Theta, &[Attribute])],
+) -> Vec<bool> {
+    let mut results: Vec<bool> = credentials
+        .iter()
+        .map(|(theta, public_attributes)| {
+            check_credential_preconditions(params, verification_key, theta, public_attributes)
+        })
+        .collect();
+
+    let candidates: Vec<(usize, (&Signature, G2Projective))> = credentials
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| results[*i])
+        .map(|(i, (theta, public_attributes))| {
+            let kappa = credential_kappa(verification_key, theta, public_attributes);
+            (i, (&theta.credential, kappa))
+        })
+        .collect();
+
+    // there's nothing to gain from batching a single credential
+    if candidates.len() > 1 {
+        let batch: Vec<_> = candidates.iter().map(|(_, entry)| *entry).collect();
+        if batch_check_credential_pairings(params, &batch) {
+            return results;
+        }
+    }
+
+    for (i, (credential, kappa)) in candidates {
+        results[i] = check_credential_pairing(params, credential, &kappa);
+    }
+
+    results
 }
 
 // Used in tests only
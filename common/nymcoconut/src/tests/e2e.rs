@@ -3,8 +3,8 @@
 
 use crate::tests::helpers::tests::generate_dkg_keys;
 use crate::{
-    aggregate_verification_keys, setup, tests::helpers::*, ttp_keygen, verify_credential,
-    CoconutError, VerificationKey,
+    aggregate_verification_keys, batch_verify_credentials, setup, tests::helpers::*, ttp_keygen,
+    verify_credential, Attribute, CoconutError, Signature, Theta, VerificationKey,
 };
 
 #[test]
@@ -81,3 +81,60 @@ fn dkg() -> Result<(), CoconutError> {
 
     Ok(())
 }
+
+#[test]
+fn batch_verification() -> Result<(), CoconutError> {
+    let params = setup(5)?;
+    let node_indices = vec![15u64, 248, 33521];
+
+    let coconut_keypairs = ttp_keygen(&params, 2, 3)?;
+    let verification_keys: Vec<VerificationKey> = coconut_keypairs
+        .iter()
+        .map(|keypair| keypair.verification_key())
+        .collect();
+    let verification_key = aggregate_verification_keys(&verification_keys, Some(&node_indices))?;
+
+    let mut credentials = Vec::new();
+    for _ in 0..4 {
+        let public_attributes = params.n_random_scalars(2);
+        let theta = theta_from_keys_and_attributes(
+            &params,
+            &coconut_keypairs,
+            &node_indices,
+            &public_attributes,
+        )?;
+        credentials.push((theta, public_attributes));
+    }
+
+    let batch: Vec<(&Theta, &[Attribute])> = credentials
+        .iter()
+        .map(|(theta, attributes)| (theta, attributes.as_slice()))
+        .collect();
+    assert!(batch_verify_credentials(&params, &verification_key, &[]).is_empty());
+    assert_eq!(
+        batch_verify_credentials(&params, &verification_key, &batch),
+        vec![true; 4]
+    );
+
+    // wrong public attributes
+    let wrong_attributes = params.n_random_scalars(2);
+    let mut batch = batch;
+    batch[1].1 = wrong_attributes.as_slice();
+
+    // credential that does not verify under the key
+    let (theta, attributes) = &credentials[3];
+    let mut forged = Theta::try_from(theta.to_bytes().as_slice())?;
+    forged.credential = Signature(forged.credential.0, forged.credential.0);
+    batch[3] = (&forged, attributes.as_slice());
+
+    let results = batch_verify_credentials(&params, &verification_key, &batch);
+    assert_eq!(results, vec![true, false, true, false]);
+    for ((theta, attributes), result) in batch.iter().zip(results) {
+        assert_eq!(
+            verify_credential(&params, &verification_key, theta, attributes),
+            result
+        );
+    }
+
+    Ok(())
+}
@@ -169,6 +169,11 @@ impl RepliableMessage {
             .collect()
     }
 
+    /// Length of the message after it gets serialized with [`Self::into_bytes`].
+    pub fn serialized_size(&self, num_mix_hops: u8) -> usize {
+        SENDER_TAG_SIZE + 1 + self.content.serialized_size(num_mix_hops)
+    }
+
     pub fn try_from_bytes(
         bytes: &[u8],
         num_mix_hops: u8,
@@ -300,6 +305,25 @@ impl RepliableMessageContent {
         }
     }
 
+    fn serialized_size(&self, num_mix_hops: u8) -> usize {
+        let surbs_size = |num_surbs: usize| {
+            mem::size_of::<u32>() + num_surbs * ReplySurb::serialized_len(num_mix_hops)
+        };
+
+        match self {
+            RepliableMessageContent::Data {
+                message,
+                reply_surbs,
+            } => surbs_size(reply_surbs.len()) + message.len(),
+            RepliableMessageContent::AdditionalSurbs { reply_surbs } => {
+                surbs_size(reply_surbs.len())
+            }
+            RepliableMessageContent::Heartbeat {
+                additional_reply_surbs,
+            } => surbs_size(additional_reply_surbs.len()),
+        }
+    }
+
     fn try_from_bytes(
         bytes: &[u8],
         num_mix_hops: u8,
@@ -382,6 +406,11 @@ impl ReplyMessage {
             .collect()
     }
 
+    /// Length of the message after it gets serialized with [`Self::into_bytes`].
+    pub fn serialized_size(&self) -> usize {
+        1 + self.content.serialized_size()
+    }
+
     pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, InvalidReplyRequestError> {
         if bytes.is_empty() {
             return Err(InvalidReplyRequestError::RequestTooShortToDeserialize);
@@ -435,6 +464,13 @@ impl ReplyMessageContent {
         }
     }
 
+    fn serialized_size(&self) -> usize {
+        match self {
+            ReplyMessageContent::Data { message } => message.len(),
+            ReplyMessageContent::SurbRequest { .. } => Recipient::LEN + mem::size_of::<u32>(),
+        }
+    }
+
     fn try_from_bytes(
         bytes: &[u8],
         tag: ReplyMessageContentTag,
@@ -0,0 +1,339 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ChunkingError;
+
+// Optional forward error correction of `FragmentSet`s is achieved with a systematic
+// Reed-Solomon erasure code over GF(2^8).
+//
+// The encoding matrix consists of an identity matrix stacked on top of a Cauchy matrix.
+// The identity part means the data `Fragment`s are sent unchanged, while the Cauchy part
+// produces the parity `Fragment`s. Since every square submatrix of a Cauchy matrix is
+// invertible, *any* `k` out of the `n` `Fragment`s of the set are sufficient to recover
+// the `k` data `Fragment`s.
+//
+// Note that the Cauchy construction requires `n` distinct field elements, however, that bound
+// is already implied by the set being able to hold at most `u8::max_value()` `Fragment`s.
+
+/// Irreducible polynomial x^8 + x^4 + x^3 + x^2 + 1 used for the field arithmetic.
+const GF_POLYNOMIAL: u16 = 0x11d;
+
+struct GaloisTables {
+    /// Powers of the generator. The table is duplicated so that the sum of two logarithms
+    /// would not need to get reduced modulo 255.
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+
+const fn generate_tables() -> GaloisTables {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= GF_POLYNOMIAL;
+        }
+        i += 1;
+    }
+    while i < 512 {
+        exp[i] = exp[i - 255];
+        i += 1;
+    }
+
+    GaloisTables { exp, log }
+}
+
+static TABLES: GaloisTables = generate_tables();
+
+fn gf_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    TABLES.exp[TABLES.log[a as usize] as usize + TABLES.log[b as usize] as usize]
+}
+
+fn gf_inv(a: u8) -> u8 {
+    debug_assert_ne!(a, 0);
+    TABLES.exp[255 - TABLES.log[a as usize] as usize]
+}
+
+/// Coefficient of the encoding matrix in the given row and column.
+fn encoding_coefficient(row: usize, column: usize, data_fragments: usize) -> u8 {
+    if row < data_fragments {
+        (row == column) as u8
+    } else {
+        // the Cauchy matrix is built using x_i = row and y_j = column. They are guaranteed to be
+        // distinct as row >= data_fragments > column. Also recall that addition in GF(2^8) is a xor.
+        gf_inv((row as u8) ^ (column as u8))
+    }
+}
+
+/// Inverts the provided square matrix using Gauss-Jordan elimination.
+/// Returns `None` if the matrix is singular.
+fn invert_matrix(mut matrix: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+    let n = matrix.len();
+    let mut inverse: Vec<Vec<u8>> = (0..n)
+        .map(|i| (0..n).map(|j| (i == j) as u8).collect())
+        .collect();
+
+    for column in 0..n {
+        let pivot = (column..n).find(|&row| matrix[row][column] != 0)?;
+        matrix.swap(column, pivot);
+        inverse.swap(column, pivot);
+
+        let pivot_inv = gf_inv(matrix[column][column]);
+        for value in matrix[column].iter_mut() {
+            *value = gf_mul(*value, pivot_inv);
+        }
+        for value in inverse[column].iter_mut() {
+            *value = gf_mul(*value, pivot_inv);
+        }
+
+        let pivot_row = matrix[column].clone();
+        let pivot_inverse_row = inverse[column].clone();
+        for row in 0..n {
+            let factor = matrix[row][column];
+            if row == column || factor == 0 {
+                continue;
+            }
+            for (value, pivot_value) in matrix[row].iter_mut().zip(&pivot_row) {
+                *value ^= gf_mul(factor, *pivot_value);
+            }
+            for (value, pivot_value) in inverse[row].iter_mut().zip(&pivot_inverse_row) {
+                *value ^= gf_mul(factor, *pivot_value);
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+/// Ratio of parity `Fragment`s to data `Fragment`s produced for each erasure coded `FragmentSet`.
+///
+/// For example ratio of 0.25 means that for every 4 data `Fragment`s an additional parity
+/// `Fragment` is going to be created, so that the set could be reconstructed
+/// even if any single one of those 5 got lost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RedundancyRatio(f64);
+
+impl RedundancyRatio {
+    /// Upper bound on the ratio to prevent misconfigurations that would result in more than
+    /// doubling the amount of sent data.
+    pub const MAX: f64 = 1.0;
+
+    pub fn new(ratio: f64) -> Result<Self, ChunkingError> {
+        if ratio > 0.0 && ratio <= Self::MAX {
+            Ok(RedundancyRatio(ratio))
+        } else {
+            Err(ChunkingError::InvalidRedundancyRatio)
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Number of parity `Fragment`s to create for the given number of data `Fragment`s.
+    /// Note that at least a single parity `Fragment` is always going to be created.
+    pub fn parity_fragments(&self, data_fragments: usize) -> usize {
+        (data_fragments as f64 * self.0).ceil() as usize
+    }
+
+    /// Maximum number of data `Fragment`s that can be put in a single set so that
+    /// the total number of `Fragment`s, including parity, would not exceed `u8::max_value()`.
+    pub fn max_data_fragments(&self) -> usize {
+        (1..=u8::max_value() as usize)
+            .rev()
+            .find(|&data| data + self.parity_fragments(data) <= u8::max_value() as usize)
+            // with the ratio being bounded by `MAX`, there will always be a valid value
+            .unwrap_or(1)
+    }
+}
+
+/// Produces `parity_fragments` parity payloads for the provided data payloads.
+/// All data payloads must be of the same length.
+pub(crate) fn encode(data: &[Vec<u8>], parity_fragments: usize) -> Vec<Vec<u8>> {
+    let data_fragments = data.len();
+    debug_assert!(data_fragments + parity_fragments <= u8::max_value() as usize);
+
+    let payload_len = data
+        .first()
+        .map(|payload| payload.len())
+        .unwrap_or_default();
+    debug_assert!(data.iter().all(|payload| payload.len() == payload_len));
+
+    (data_fragments..data_fragments + parity_fragments)
+        .map(|row| {
+            let mut parity = vec![0u8; payload_len];
+            for (column, payload) in data.iter().enumerate() {
+                let coefficient = encoding_coefficient(row, column, data_fragments);
+                for (parity_byte, data_byte) in parity.iter_mut().zip(payload) {
+                    *parity_byte ^= gf_mul(coefficient, *data_byte);
+                }
+            }
+            parity
+        })
+        .collect()
+}
+
+/// Recovers all `data_fragments` original data payloads given at least that many payloads,
+/// identified by their (0-indexed) position in the set, out of the encoded set.
+pub(crate) fn reconstruct(
+    mut received: Vec<(usize, Vec<u8>)>,
+    data_fragments: usize,
+) -> Result<Vec<Vec<u8>>, ChunkingError> {
+    if received.len() < data_fragments {
+        return Err(ChunkingError::InsufficientErasureCodedFragments {
+            received: received.len(),
+            required: data_fragments,
+        });
+    }
+
+    // prefer data payloads as if all of them are present, no decoding is required
+    received.sort_by_key(|(position, _)| *position);
+    received.truncate(data_fragments);
+
+    let payload_len = received[0].1.len();
+    if let Some((_, malformed)) = received
+        .iter()
+        .find(|(_, payload)| payload.len() != payload_len)
+    {
+        return Err(ChunkingError::InvalidPayloadLengthError {
+            received: malformed.len(),
+            expected: payload_len,
+        });
+    }
+
+    if received
+        .iter()
+        .enumerate()
+        .all(|(i, (position, _))| i == *position)
+    {
+        return Ok(received.into_iter().map(|(_, payload)| payload).collect());
+    }
+
+    let decoding_matrix = received
+        .iter()
+        .map(|(position, _)| {
+            (0..data_fragments)
+                .map(|column| encoding_coefficient(*position, column, data_fragments))
+                .collect()
+        })
+        .collect();
+
+    // this can only fail if we received duplicate positions
+    let inverse = invert_matrix(decoding_matrix).ok_or(ChunkingError::MalformedErasureCodedSet)?;
+
+    Ok(inverse
+        .iter()
+        .map(|coefficients| {
+            let mut data = vec![0u8; payload_len];
+            for (coefficient, (_, payload)) in coefficients.iter().zip(&received) {
+                for (data_byte, received_byte) in data.iter_mut().zip(payload) {
+                    *data_byte ^= gf_mul(*coefficient, *received_byte);
+                }
+            }
+            data
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, RngCore};
+
+    fn random_payloads(count: usize, len: usize) -> Vec<Vec<u8>> {
+        let mut rng = thread_rng();
+        (0..count)
+            .map(|_| {
+                let mut payload = vec![0u8; len];
+                rng.fill_bytes(&mut payload);
+                payload
+            })
+            .collect()
+    }
+
+    #[test]
+    fn field_inverse_is_correct() {
+        for a in 1..=u8::max_value() {
+            assert_eq!(1, gf_mul(a, gf_inv(a)))
+        }
+    }
+
+    #[test]
+    fn redundancy_ratio_must_be_within_bounds() {
+        assert!(RedundancyRatio::new(0.0).is_err());
+        assert!(RedundancyRatio::new(-0.5).is_err());
+        assert!(RedundancyRatio::new(1.5).is_err());
+        assert!(RedundancyRatio::new(f64::NAN).is_err());
+        assert!(RedundancyRatio::new(0.1).is_ok());
+        assert!(RedundancyRatio::new(1.0).is_ok());
+    }
+
+    #[test]
+    fn max_data_fragments_never_overflows_the_set() {
+        for ratio in [0.01, 0.1, 0.25, 0.5, 1.0] {
+            let ratio = RedundancyRatio::new(ratio).unwrap();
+            let data = ratio.max_data_fragments();
+            assert!(data + ratio.parity_fragments(data) <= u8::max_value() as usize);
+            assert!(data + 1 + ratio.parity_fragments(data + 1) > u8::max_value() as usize);
+        }
+    }
+
+    #[test]
+    fn data_is_recovered_from_any_sufficient_subset() {
+        let data = random_payloads(10, 100);
+        let parity = encode(&data, 4);
+        assert_eq!(parity.len(), 4);
+
+        let all: Vec<_> = data
+            .iter()
+            .chain(parity.iter())
+            .cloned()
+            .enumerate()
+            .collect();
+
+        // only data
+        let received = all[..10].to_vec();
+        assert_eq!(data, reconstruct(received, 10).unwrap());
+
+        // lose first 4 data payloads
+        let received = all[4..].to_vec();
+        assert_eq!(data, reconstruct(received, 10).unwrap());
+
+        // lose some scattered payloads
+        let received = all
+            .iter()
+            .filter(|(position, _)| ![1, 5, 9, 12].contains(position))
+            .cloned()
+            .collect();
+        assert_eq!(data, reconstruct(received, 10).unwrap());
+    }
+
+    #[test]
+    fn reconstruction_fails_with_too_few_payloads() {
+        let data = random_payloads(10, 100);
+        let parity = encode(&data, 2);
+
+        let received: Vec<_> = data
+            .into_iter()
+            .chain(parity)
+            .enumerate()
+            .skip(3)
+            .collect();
+
+        assert_eq!(
+            reconstruct(received, 10),
+            Err(ChunkingError::InsufficientErasureCodedFragments {
+                received: 9,
+                required: 10
+            })
+        )
+    }
+}
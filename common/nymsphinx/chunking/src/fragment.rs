@@ -34,6 +34,22 @@ pub const UNLINKED_FRAGMENTED_HEADER_LEN: usize = 7;
 /// `Fragment` in a `FragmentSet`.
 pub const LINKED_FRAGMENTED_HEADER_LEN: usize = 10;
 
+/// If the `FragmentSet` was protected with forward error correction, each of its `Fragment`s
+/// has to indicate the number of data `Fragment`s in the set (1 byte) and the length of the
+/// payload in the final data `Fragment` before it got padded for encoding (2 bytes).
+/// This is on top of the 7 bytes of the unlinked header where the final byte is used to
+/// represent erasure coding flags rather than the lack of linking.
+pub const ERASURE_CODED_FRAGMENT_HEADER_LEN: usize = 10;
+
+/// Since any `k` out of `n` `Fragment`s of an erasure coded `FragmentSet` might be used for
+/// the reconstruction, *every* `Fragment` has to carry the ids of the linked sets, if applicable.
+/// Each of the links takes 4 bytes.
+pub const ERASURE_CODED_LINK_LEN: usize = 4;
+
+const ERASURE_CODING_FLAG: u8 = 0b0000_0001;
+const ERASURE_CODING_PREVIOUS_LINK_FLAG: u8 = 0b0000_0010;
+const ERASURE_CODING_NEXT_LINK_FLAG: u8 = 0b0000_0100;
+
 /// Maximum size of payload of each fragment is always the maximum amount of plaintext data
 /// we can put into a sphinx packet minus length of respective fragment header.
 pub const fn unlinked_fragment_payload_max_len(max_plaintext_size: usize) -> usize {
@@ -46,6 +62,13 @@ pub const fn linked_fragment_payload_max_len(max_plaintext_size: usize) -> usize
     max_plaintext_size - LINKED_FRAGMENTED_HEADER_LEN
 }
 
+/// Size of payload of each fragment in an erasure coded set. Unlike the regular fragments,
+/// it is always constant for the particular set as the encoding requires payloads of equal lengths.
+/// It only depends on the number of sets the set is linked to.
+pub const fn erasure_coded_fragment_payload_len(max_plaintext_size: usize, links: usize) -> usize {
+    max_plaintext_size - ERASURE_CODED_FRAGMENT_HEADER_LEN - links * ERASURE_CODED_LINK_LEN
+}
+
 // TODO: should this be defined in this module or in `cover`? I can see arguments for both options...
 /// A special `FragmentIdentifier` that is not valid in all cases unless if it's used in a loop
 /// cover message.
@@ -104,6 +127,19 @@ impl FragmentIdentifier {
     }
 }
 
+/// Metadata attached to every `Fragment` of a `FragmentSet` protected with forward error correction.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ErasureCodingInfo {
+    /// Number of data `Fragment`s in the set, i.e. the minimum number of `Fragment`s
+    /// required to reconstruct it. They always occupy the first positions in the set,
+    /// with parity `Fragment`s following afterwards.
+    pub data_fragments: u8,
+
+    /// Length of the payload contained in the final data `Fragment` before it got zero-padded
+    /// to the common length required by the encoding.
+    pub last_data_fragment_len: u16,
+}
+
 /// The basic unit of division of underlying bytes message sent through the mix network.
 /// Each `Fragment` after being marshaled is guaranteed to fit into a single sphinx packet.
 /// The `Fragment` itself consists of part, or whole of, message to be sent as well as additional
@@ -191,6 +227,40 @@ impl Fragment {
         })
     }
 
+    /// Tries to encapsulate provided payload and metadata into a `Fragment` belonging to an
+    /// erasure coded `FragmentSet`. Unlike regular `Fragment`s, the payload must always have
+    /// the exact length as defined by `erasure_coded_fragment_payload_len`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn try_new_erasure_coded(
+        payload: Vec<u8>,
+        id: i32,
+        total_fragments: u8,
+        current_fragment: u8,
+        previous_fragments_set_id: Option<i32>,
+        next_fragments_set_id: Option<i32>,
+        erasure_coding: ErasureCodingInfo,
+        max_plaintext_size: usize,
+    ) -> Result<Self, ChunkingError> {
+        let header = FragmentHeader::try_new_erasure_coded(
+            id,
+            total_fragments,
+            current_fragment,
+            previous_fragments_set_id,
+            next_fragments_set_id,
+            erasure_coding,
+        )?;
+
+        let expected = erasure_coded_fragment_payload_len(max_plaintext_size, header.links());
+        if payload.len() != expected {
+            return Err(ChunkingError::InvalidPayloadLengthError {
+                received: payload.len(),
+                expected,
+            });
+        }
+
+        Ok(Fragment { header, payload })
+    }
+
     /// Convert this `Fragment` into vector of bytes which can be put into a sphinx packet.
     pub fn into_bytes(self) -> Vec<u8> {
         self.header
@@ -239,6 +309,12 @@ impl Fragment {
         self.header.next_fragments_set_id
     }
 
+    /// Extracts erasure coding metadata if the `FragmentSet` this `Fragment` belongs to
+    /// was protected with forward error correction.
+    pub fn erasure_coding(&self) -> Option<ErasureCodingInfo> {
+        self.header.erasure_coding
+    }
+
     /// Consumes `self` to obtain payload (i.e. part of original message) associated with this
     /// `Fragment`.
     pub(crate) fn extract_payload(self) -> Vec<u8> {
@@ -284,6 +360,15 @@ impl Fragment {
 /// where the set is linked to either preceding data (TF == 1) or proceeding data (TF == CF == 255)
 /// '1'bit || 31-bit ID || 1-byte TF || 1 byte CF || '1'bit || 31-bit LID
 ///
+/// Finally, if the set was protected with forward error correction, each of its fragments
+/// has the following 10 to 18 byte long header:
+/// '1'bit || 31-bit ID || 1-byte TF || 1 byte CF || 1-byte EC flags || 1-byte DF ||
+/// 2-byte LDL || (optional) '1'bit || 31-bit previous LID || (optional) '1'bit || 31-bit next LID
+/// where EC flags always have the lowest bit set (and the highest bit cleared to distinguish it
+/// from the linked header), followed by two bits indicating the presence of the previous and next
+/// linked ids respectively, DF is the number of data fragments in the set and LDL is the
+/// length of the final data fragment before padding.
+///
 /// And hence for messages larger than `max_plaintext_size` but small enough
 /// to avoid set division (which happens if message has to be fragmented into more than 255 fragments)
 /// there is 7 bytes of overhead inside each sphinx packet sent
@@ -313,6 +398,11 @@ pub(crate) struct FragmentHeader {
     /// Optional ID of next `FragmentSet` into which the original message was split.
     /// Note, this option is only valid of `current_fragment == total_fragments == u8::max_value()`
     next_fragments_set_id: Option<i32>,
+
+    /// Optional erasure coding metadata present if the set was protected with forward error correction.
+    /// Note that in that case, the restrictions on the linked set ids no longer apply as
+    /// every `Fragment` in the set carries them.
+    erasure_coding: Option<ErasureCodingInfo>,
 }
 
 impl FragmentHeader {
@@ -355,9 +445,129 @@ impl FragmentHeader {
             current_fragment,
             previous_fragments_set_id,
             next_fragments_set_id,
+            erasure_coding: None,
         })
     }
 
+    /// Tries to create a new `FragmentHeader` for a `Fragment` belonging to an erasure coded set.
+    /// Apart from the checks performed for the regular headers, it is also verified that the
+    /// number of data `Fragment`s is consistent with the total number of `Fragment`s.
+    fn try_new_erasure_coded(
+        id: i32,
+        total_fragments: u8,
+        current_fragment: u8,
+        previous_fragments_set_id: Option<i32>,
+        next_fragments_set_id: Option<i32>,
+        erasure_coding: ErasureCodingInfo,
+    ) -> Result<Self, ChunkingError> {
+        if id <= 0 {
+            return Err(ChunkingError::MalformedHeaderError);
+        }
+        if total_fragments == 0 || current_fragment == 0 || current_fragment > total_fragments {
+            return Err(ChunkingError::MalformedHeaderError);
+        }
+        if erasure_coding.data_fragments == 0 || erasure_coding.data_fragments > total_fragments {
+            return Err(ChunkingError::MalformedHeaderError);
+        }
+        for linked_id in previous_fragments_set_id
+            .iter()
+            .chain(next_fragments_set_id.iter())
+        {
+            if *linked_id <= 0 || *linked_id == id {
+                return Err(ChunkingError::MalformedHeaderError);
+            }
+        }
+
+        Ok(FragmentHeader {
+            id,
+            total_fragments,
+            current_fragment,
+            previous_fragments_set_id,
+            next_fragments_set_id,
+            erasure_coding: Some(erasure_coding),
+        })
+    }
+
+    /// Number of other sets this header explicitly links to.
+    fn links(&self) -> usize {
+        self.previous_fragments_set_id.is_some() as usize
+            + self.next_fragments_set_id.is_some() as usize
+    }
+
+    /// Tries to recover the remaining part of an erasure coded `FragmentHeader`, i.e. everything
+    /// following the erasure coding flags byte.
+    fn try_erasure_coded_from_bytes(
+        b: &[u8],
+        id: i32,
+        total_fragments: u8,
+        current_fragment: u8,
+    ) -> Result<(Self, usize), ChunkingError> {
+        let flags = b[6];
+        if flags & ERASURE_CODING_FLAG == 0
+            || flags
+                & !(ERASURE_CODING_FLAG
+                    | ERASURE_CODING_PREVIOUS_LINK_FLAG
+                    | ERASURE_CODING_NEXT_LINK_FLAG)
+                != 0
+        {
+            return Err(ChunkingError::MalformedHeaderError);
+        }
+
+        let has_previous = flags & ERASURE_CODING_PREVIOUS_LINK_FLAG != 0;
+        let has_next = flags & ERASURE_CODING_NEXT_LINK_FLAG != 0;
+        let expected = ERASURE_CODED_FRAGMENT_HEADER_LEN
+            + (has_previous as usize + has_next as usize) * ERASURE_CODED_LINK_LEN;
+        if b.len() < expected {
+            return Err(ChunkingError::TooShortFragmentHeader {
+                received: b.len(),
+                expected,
+            });
+        }
+
+        let erasure_coding = ErasureCodingInfo {
+            data_fragments: b[7],
+            last_data_fragment_len: u16::from_be_bytes([b[8], b[9]]),
+        };
+
+        let mut offset = ERASURE_CODED_FRAGMENT_HEADER_LEN;
+        let mut read_linked_id = || {
+            let flagged_linked_id = i32::from_be_bytes(
+                b[offset..offset + ERASURE_CODED_LINK_LEN]
+                    .try_into()
+                    .unwrap(),
+            );
+            offset += ERASURE_CODED_LINK_LEN;
+            // sanity check for the linked flag
+            if ((flagged_linked_id >> 31) & 1) == 0 {
+                return Err(ChunkingError::MalformedHeaderError);
+            }
+            Ok(flagged_linked_id & !(1 << 31))
+        };
+
+        let previous_fragments_set_id = if has_previous {
+            Some(read_linked_id()?)
+        } else {
+            None
+        };
+        let next_fragments_set_id = if has_next {
+            Some(read_linked_id()?)
+        } else {
+            None
+        };
+
+        Ok((
+            Self::try_new_erasure_coded(
+                id,
+                total_fragments,
+                current_fragment,
+                previous_fragments_set_id,
+                next_fragments_set_id,
+                erasure_coding,
+            )?,
+            expected,
+        ))
+    }
+
     /// Tries to recover `FragmentHeader` from slice of bytes extracted from received sphinx packet.
     /// If successful, returns `Self` and number of bytes used, as those can differ based on the
     /// type of header (unlinked or linked).
@@ -383,6 +593,12 @@ impl FragmentHeader {
             return Err(ChunkingError::MalformedHeaderError);
         }
 
+        // erasure coded headers have their flags byte set to a non-zero value that's smaller
+        // than the linking flag
+        if b[6] != 0 && b[6] & (1 << 7) == 0 {
+            return Self::try_erasure_coded_from_bytes(b, id, total_fragments, current_fragment);
+        }
+
         let mut previous_fragments_set_id = None;
         let mut next_fragments_set_id = None;
 
@@ -439,6 +655,28 @@ impl FragmentHeader {
             .chain(std::iter::once(self.total_fragments))
             .chain(std::iter::once(self.current_fragment));
 
+        if let Some(erasure_coding) = self.erasure_coding {
+            let mut flags = ERASURE_CODING_FLAG;
+            if self.previous_fragments_set_id.is_some() {
+                flags |= ERASURE_CODING_PREVIOUS_LINK_FLAG
+            }
+            if self.next_fragments_set_id.is_some() {
+                flags |= ERASURE_CODING_NEXT_LINK_FLAG
+            }
+
+            return bytes_prefix_iter
+                .chain(std::iter::once(flags))
+                .chain(std::iter::once(erasure_coding.data_fragments))
+                .chain(erasure_coding.last_data_fragment_len.to_be_bytes())
+                .chain(
+                    self.previous_fragments_set_id
+                        .iter()
+                        .chain(self.next_fragments_set_id.iter())
+                        .flat_map(|linked_id| (linked_id | (1 << 31)).to_be_bytes()),
+                )
+                .collect();
+        }
+
         let is_linked =
             self.previous_fragments_set_id.is_some() || self.next_fragments_set_id.is_some();
         if is_linked {
@@ -1020,6 +1258,7 @@ mod fragment_header {
                 current_fragment: 11,
                 previous_fragments_set_id: None,
                 next_fragments_set_id: None,
+                erasure_coding: None,
             };
             let header_bytes = header.to_bytes();
             assert!(FragmentHeader::try_from_bytes(&header_bytes).is_err());
@@ -1034,6 +1273,7 @@ mod fragment_header {
                 current_fragment: 0,
                 previous_fragments_set_id: None,
                 next_fragments_set_id: None,
+                erasure_coding: None,
             };
             let header_bytes = header.to_bytes();
             assert!(FragmentHeader::try_from_bytes(&header_bytes).is_err());
@@ -1135,4 +1375,107 @@ mod fragment_header {
             assert_eq!(LINKED_FRAGMENTED_HEADER_LEN, bytes_used);
         }
     }
+
+    #[cfg(test)]
+    mod erasure_coded_payload {
+        use super::*;
+
+        fn erasure_coding() -> ErasureCodingInfo {
+            ErasureCodingInfo {
+                data_fragments: 8,
+                last_data_fragment_len: 1234,
+            }
+        }
+
+        #[test]
+        fn can_be_converted_to_and_from_bytes_with_any_combination_of_links() {
+            for (previous, next, links) in [
+                (None, None, 0),
+                (Some(1234), None, 1),
+                (None, Some(4321), 1),
+                (Some(1234), Some(4321), 2),
+            ] {
+                let header = FragmentHeader::try_new_erasure_coded(
+                    12345,
+                    10,
+                    9,
+                    previous,
+                    next,
+                    erasure_coding(),
+                )
+                .unwrap();
+
+                let mut header_bytes = header.to_bytes();
+                let expected_len =
+                    ERASURE_CODED_FRAGMENT_HEADER_LEN + links * ERASURE_CODED_LINK_LEN;
+                assert_eq!(expected_len, header_bytes.len());
+
+                header_bytes.append(vec![1, 2, 3, 4, 5].as_mut());
+                let (recovered_header, bytes_used) =
+                    FragmentHeader::try_from_bytes(&header_bytes).unwrap();
+                assert_eq!(header, recovered_header);
+                assert_eq!(expected_len, bytes_used);
+            }
+        }
+
+        #[test]
+        fn retrieval_from_bytes_fails_for_insufficient_number_of_bytes_provided() {
+            let header = FragmentHeader::try_new_erasure_coded(
+                12345,
+                10,
+                9,
+                Some(1234),
+                Some(4321),
+                erasure_coding(),
+            )
+            .unwrap();
+
+            let header_bytes = header.to_bytes();
+            let header_bytes = &header_bytes[..header_bytes.len() - 1];
+            assert!(FragmentHeader::try_from_bytes(header_bytes).is_err())
+        }
+
+        #[test]
+        fn retrieval_from_bytes_fails_for_unknown_flags() {
+            let header =
+                FragmentHeader::try_new_erasure_coded(12345, 10, 9, None, None, erasure_coding())
+                    .unwrap();
+
+            let mut header_bytes = header.to_bytes();
+            header_bytes[6] |= 1 << 4;
+            assert!(FragmentHeader::try_from_bytes(&header_bytes).is_err())
+        }
+
+        #[test]
+        fn creation_of_header_fails_for_invalid_number_of_data_fragments() {
+            let mut info = erasure_coding();
+            info.data_fragments = 0;
+            assert!(FragmentHeader::try_new_erasure_coded(12345, 10, 9, None, None, info).is_err());
+
+            info.data_fragments = 11;
+            assert!(FragmentHeader::try_new_erasure_coded(12345, 10, 9, None, None, info).is_err());
+        }
+
+        #[test]
+        fn cannot_be_linked_to_itself() {
+            assert!(FragmentHeader::try_new_erasure_coded(
+                12345,
+                10,
+                9,
+                Some(12345),
+                None,
+                erasure_coding()
+            )
+            .is_err());
+            assert!(FragmentHeader::try_new_erasure_coded(
+                12345,
+                10,
+                9,
+                None,
+                Some(12345),
+                erasure_coding()
+            )
+            .is_err());
+        }
+    }
 }
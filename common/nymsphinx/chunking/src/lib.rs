@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::fragment::{linked_fragment_payload_max_len, unlinked_fragment_payload_max_len};
-pub use set::split_into_sets;
+pub use erasure::RedundancyRatio;
+pub use set::{split_into_erasure_coded_sets, split_into_sets};
 use thiserror::Error;
 
 // Future consideration: currently in a lot of places, the payloads have randomised content
@@ -16,6 +17,7 @@ use thiserror::Error;
 // they should definitely be revisited.
 // For instance there are not tests for the cases when we are padding the message
 
+pub mod erasure;
 pub mod fragment;
 pub mod reconstruction;
 pub mod set;
@@ -66,6 +68,15 @@ pub enum ChunkingError {
 
     #[error("Received fragment identifier ({received}) is not a valid value!")]
     MalformedFragmentIdentifier { received: i32 },
+
+    #[error("The redundancy ratio must be within (0, {}]", RedundancyRatio::MAX)]
+    InvalidRedundancyRatio,
+
+    #[error("Received too few erasure coded fragments to reconstruct the set. Got {received}, required {required}")]
+    InsufficientErasureCodedFragments { received: usize, required: usize },
+
+    #[error("The erasure coded set contained duplicate or self-contradicting fragments")]
+    MalformedErasureCodedSet,
 }
 
 /// Returns number of fragments the message will be split to as well as number of available
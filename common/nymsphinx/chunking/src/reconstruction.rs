@@ -1,9 +1,14 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
-use crate::fragment::Fragment;
+use crate::erasure;
+use crate::fragment::{ErasureCodingInfo, Fragment};
 use crate::ChunkingError;
 use log::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Maximum number of ids of already reconstructed sets remembered by the `MessageReconstructor`
+/// in order to drop any of their `Fragment`s that might still arrive afterwards.
+const MAX_REMEMBERED_RECONSTRUCTED_SETS: usize = 10_000;
 
 // TODO: perhaps a more sophisticated approach with writing to disk periodically in case
 // we're receiving fast & furious in uncompressed 4K - we don't want to keep that in memory;
@@ -31,6 +36,11 @@ struct ReconstructionBuffer {
     /// appropriately resized and all missing fragments are set to a `None`, thus keeping
     /// everything in order the whole time, allowing for O(1) insertions and O(n) reconstruction.
     fragments: Vec<Option<Fragment>>,
+
+    /// If the set was protected with forward error correction, it contains the erasure coding
+    /// metadata shared by all of its `Fragment`s. In that case the set is complete as soon as
+    /// any `data_fragments` of its `Fragment`s are received.
+    erasure_coding: Option<ErasureCodingInfo>,
 }
 
 /// Type alias representing fully reconstructed message - its original data and list of all
@@ -56,6 +66,16 @@ impl ReconstructionBuffer {
             previous_fragments_set_id: None,
             next_fragments_set_id: None,
             fragments: fragments_buffer,
+            erasure_coding: None,
+        }
+    }
+
+    /// Initialises new instance of a `ReconstructionBuffer` for an erasure coded set with given size,
+    /// i.e. total number of data and parity `Fragment`s in the set.
+    fn new_erasure_coded(size: u8, erasure_coding: ErasureCodingInfo) -> Self {
+        ReconstructionBuffer {
+            erasure_coding: Some(erasure_coding),
+            ..Self::new(size)
         }
     }
 
@@ -66,6 +86,10 @@ impl ReconstructionBuffer {
         // if the set is complete.
         debug_assert!(self.is_complete);
 
+        if let Some(erasure_coding) = self.erasure_coding {
+            return Self::reconstruct_erasure_coded_set_data(self.fragments, erasure_coding);
+        }
+
         self.fragments
             .into_iter()
             .map(|fragment| fragment.unwrap().extract_payload())
@@ -73,12 +97,60 @@ impl ReconstructionBuffer {
             .collect()
     }
 
+    /// Decodes the data of the erasure coded set given any sufficient subset of its `Fragment`s
+    /// and strips the zero-padding of its final data `Fragment`.
+    fn reconstruct_erasure_coded_set_data(
+        fragments: Vec<Option<Fragment>>,
+        erasure_coding: ErasureCodingInfo,
+    ) -> Vec<u8> {
+        let data_fragments = erasure_coding.data_fragments as usize;
+        let received = fragments
+            .into_iter()
+            .enumerate()
+            .filter_map(|(position, fragment)| {
+                fragment.map(|fragment| (position, fragment.extract_payload()))
+            })
+            .collect();
+
+        // all fragments got checked for consistency upon insertion and we know we have enough
+        // of them, so the decoding cannot possibly fail
+        let data = erasure::reconstruct(received, data_fragments)
+            .expect("failed to decode a complete erasure coded set");
+
+        let payload_len = data[0].len();
+        let set_data_len = (data_fragments - 1) * payload_len
+            + usize::min(payload_len, erasure_coding.last_data_fragment_len as usize);
+
+        let mut set_data: Vec<_> = data.into_iter().flatten().collect();
+        set_data.truncate(set_data_len);
+        set_data
+    }
+
+    /// Checks whether the `Fragment` of an erasure coded set is consistent with the `Fragment`s
+    /// already present in the buffer, as otherwise it would not be possible to decode the set.
+    fn is_consistent_erasure_coded_fragment(&self, fragment: &Fragment) -> bool {
+        if fragment.erasure_coding() != self.erasure_coding
+            || fragment.total_fragments() as usize != self.fragments.len()
+        {
+            return false;
+        }
+
+        self.fragments
+            .iter()
+            .flatten()
+            .all(|present| present.payload_size() == fragment.payload_size())
+    }
+
     // TODO: check what's the performance impact of this, and if it's too big, keep track of number
     // of received fragments instead rather than checking whole vector, but then
     // we might have false positives if somehow we receive a duplicate
     /// Checks if `self` is done receiving `Fragment` data by checking if there are still
     /// any `None` elements in the `fragments` vector.
     fn is_done_receiving(&self) -> bool {
+        if let Some(erasure_coding) = self.erasure_coding {
+            return self.fragments.iter().flatten().count()
+                >= erasure_coding.data_fragments as usize;
+        }
         !self.fragments.contains(&None)
     }
 
@@ -104,6 +176,17 @@ impl ReconstructionBuffer {
             }
         });
 
+        if (self.erasure_coding.is_some() || fragment.erasure_coding().is_some())
+            && !self.is_consistent_erasure_coded_fragment(&fragment)
+        {
+            warn!(
+                "received erasure coded fragment inconsistent with the rest of its set! - frag - {} (set id: {}). It is going to be dropped",
+                fragment.current_fragment(),
+                fragment.id()
+            );
+            return;
+        }
+
         let fragment_index = fragment.current_fragment() as usize - 1;
         if self.fragments[fragment_index].is_some() {
             // TODO: what to do in that case? give up on the message? overwrite it? panic?
@@ -116,6 +199,35 @@ impl ReconstructionBuffer {
             );
         }
         self.fragments[fragment_index] = Some(fragment);
+        if self.is_complete {
+            // this might happen if we received more than the minimum number of fragments
+            // of an erasure coded set
+            return;
+        }
+
+        if self.erasure_coding.is_some() {
+            // every fragment of an erasure coded set carries the links and we cannot rely on
+            // the first or the last one being present
+            if self.is_done_receiving() {
+                let (previous, next) = self
+                    .fragments
+                    .iter()
+                    .flatten()
+                    .next()
+                    .map(|present| {
+                        (
+                            present.previous_fragments_set_id(),
+                            present.next_fragments_set_id(),
+                        )
+                    })
+                    .unwrap_or_default();
+                self.is_complete = true;
+                self.previous_fragments_set_id = previous;
+                self.next_fragments_set_id = next;
+            }
+            return;
+        }
+
         if self.is_done_receiving() {
             self.is_complete = true;
             self.previous_fragments_set_id = self.fragments[0]
@@ -147,6 +259,15 @@ pub struct MessageReconstructor {
     // maximum sized sets but without one of required fragments. All of the received
     // data will be kept on the heap indefinitely in the current implementation.
     reconstructed_sets: HashMap<i32, ReconstructionBuffer>,
+
+    /// Ids of the sets that were already used to reconstruct messages. `Fragment`s of such sets
+    /// might still arrive afterwards, for example the remaining ones of erasure coded sets or
+    /// retransmissions of `Fragment`s whose acks got lost, and they must not create new buffers.
+    completed_sets: HashSet<i32>,
+
+    /// Order in which the `completed_sets` were inserted, so that the oldest ones could be
+    /// forgotten once `MAX_REMEMBERED_RECONSTRUCTED_SETS` is reached.
+    completed_sets_order: VecDeque<i32>,
 }
 
 impl MessageReconstructor {
@@ -243,6 +364,19 @@ impl MessageReconstructor {
             .reconstruct_set_data()
     }
 
+    /// Remembers that the set of given `id` was already used to reconstruct a message.
+    fn mark_set_as_completed(&mut self, id: i32) {
+        if !self.completed_sets.insert(id) {
+            return;
+        }
+        self.completed_sets_order.push_back(id);
+        if self.completed_sets_order.len() > MAX_REMEMBERED_RECONSTRUCTED_SETS {
+            if let Some(oldest) = self.completed_sets_order.pop_front() {
+                self.completed_sets.remove(&oldest);
+            }
+        }
+    }
+
     // Future consideration: perhaps for long messages, rather than return whole data allocated
     // on the heap, return file handle with the saved content?
     /// Given id of *any* one of the sets into which message was divided,
@@ -260,21 +394,41 @@ impl MessageReconstructor {
             .flat_map(|payload| payload.into_iter())
             .collect();
 
+        for &id in &set_id_sequence {
+            self.mark_set_as_completed(id)
+        }
+
         (message_content, set_id_sequence)
     }
 
     /// Given recovered `Fragment`, tries to insert it into an appropriate `ReconstructionBuffer`.
-    /// If a buffer does not exist, a new instance is created.
+    /// If a buffer does not exist, a new instance is created, unless the set was already used
+    /// to reconstruct a message, in which case the `Fragment` is dropped.
     /// If it was last remaining `Fragment` for the original message, the message is reconstructed
     /// and returned alongside all (if applicable) set ids used in the message.
     pub fn insert_new_fragment(&mut self, fragment: Fragment) -> Option<ReconstructedMessage> {
         let set_id = fragment.id();
         let set_len = fragment.total_fragments();
+        let erasure_coding = fragment.erasure_coding();
+
+        if self.completed_sets.contains(&set_id) {
+            debug!(
+                "received fragment {} of an already reconstructed set (id: {}). It is going to be dropped",
+                fragment.current_fragment(),
+                set_id
+            );
+            return None;
+        }
 
         let buf = self
             .reconstructed_sets
             .entry(set_id)
-            .or_insert_with(|| ReconstructionBuffer::new(set_len));
+            .or_insert_with(|| match erasure_coding {
+                Some(erasure_coding) => {
+                    ReconstructionBuffer::new_erasure_coded(set_len, erasure_coding)
+                }
+                None => ReconstructionBuffer::new(set_len),
+            });
 
         buf.insert_fragment(fragment);
         if self.is_message_fully_received(set_id) {
@@ -865,6 +1019,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: None,
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
 
@@ -875,6 +1030,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: None,
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
 
@@ -1002,6 +1158,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: Some(1234),
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
 
@@ -1012,6 +1169,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(12345),
                 next_fragments_set_id: Some(123),
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
 
@@ -1022,6 +1180,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(1234),
                 next_fragments_set_id: Some(12),
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
 
@@ -1032,6 +1191,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(123),
                 next_fragments_set_id: None,
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
 
@@ -1079,6 +1239,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: Some(1234),
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
         reconstructor.reconstructed_sets.insert(
@@ -1088,6 +1249,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(12345),
                 next_fragments_set_id: None,
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
         assert_eq!(reconstructor.previous_linked_set_id(12345), None);
@@ -1135,6 +1297,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: None,
                 next_fragments_set_id: Some(1234),
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
         reconstructor.reconstructed_sets.insert(
@@ -1144,6 +1307,7 @@ mod message_reconstructor {
                 previous_fragments_set_id: Some(12345),
                 next_fragments_set_id: None,
                 fragments: vec![],
+                erasure_coding: None,
             },
         );
         assert_eq!(reconstructor.next_linked_set_id(12345), Some(1234));
@@ -1847,4 +2011,176 @@ mod message_reconstruction {
             }
         }
     }
+
+    #[cfg(test)]
+    mod erasure_coded_split {
+        use super::*;
+        use crate::set::max_erasure_coded_set_payload_length;
+        use crate::RedundancyRatio;
+
+        fn redundancy() -> RedundancyRatio {
+            RedundancyRatio::new(0.25).unwrap()
+        }
+
+        #[test]
+        fn it_reconstructs_message_with_parity_fragments_missing() {
+            let mut rng = thread_rng();
+
+            let mut message = vec![0u8; 12345];
+            rng.fill_bytes(&mut message);
+
+            let fragments: Vec<_> = crate::split_into_erasure_coded_sets(
+                &mut rng,
+                &message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                redundancy(),
+            )
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .map(|x| x.into_bytes())
+            .collect();
+
+            let data_fragments = Fragment::try_from_bytes(&fragments[0])
+                .unwrap()
+                .erasure_coding()
+                .unwrap()
+                .data_fragments as usize;
+
+            let mut message_reconstructor = MessageReconstructor::default();
+            for (i, fragment) in fragments.into_iter().take(data_fragments).enumerate() {
+                let result = message_reconstructor
+                    .insert_new_fragment(message_reconstructor.recover_fragment(fragment).unwrap());
+                if i == data_fragments - 1 {
+                    assert_eq!(result.unwrap().0, message);
+                } else {
+                    assert!(result.is_none());
+                }
+            }
+        }
+
+        #[test]
+        fn it_reconstructs_message_split_into_multiple_sets_with_data_fragments_missing() {
+            let mut rng = thread_rng();
+
+            let mut message = vec![
+                0u8;
+                2 * max_erasure_coded_set_payload_length(
+                    AVAILABLE_PLAINTEXT_SIZE,
+                    1,
+                    redundancy()
+                ) + 12345
+            ];
+            rng.fill_bytes(&mut message);
+
+            let sets = crate::split_into_erasure_coded_sets(
+                &mut rng,
+                &message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                redundancy(),
+            );
+            assert_eq!(sets.len(), 3);
+
+            // in every set lose as many fragments as we can afford, including the first and last ones
+            let mut fragments: Vec<_> = sets
+                .into_iter()
+                .flat_map(|set| {
+                    let data_fragments = set[0].erasure_coding().unwrap().data_fragments as usize;
+                    let lost = set.len() - data_fragments;
+                    set.into_iter()
+                        .enumerate()
+                        .filter(move |(i, _)| *i >= lost)
+                        .map(|(_, fragment)| fragment.into_bytes())
+                })
+                .collect();
+            fragments.shuffle(&mut rng);
+
+            let mut message_reconstructor = MessageReconstructor::default();
+            let mut reconstructed = None;
+            for fragment in fragments {
+                if let Some(msg) = message_reconstructor
+                    .insert_new_fragment(message_reconstructor.recover_fragment(fragment).unwrap())
+                {
+                    assert!(reconstructed.is_none());
+                    reconstructed = Some(msg)
+                }
+            }
+
+            let (reconstructed_message, used_sets) = reconstructed.unwrap();
+            assert_eq!(reconstructed_message, message);
+            assert_eq!(used_sets.len(), 3);
+        }
+
+        #[test]
+        fn it_drops_remaining_fragments_of_reconstructed_message() {
+            let mut rng = thread_rng();
+
+            let mut message = vec![0u8; 12345];
+            rng.fill_bytes(&mut message);
+
+            let fragments: Vec<_> = crate::split_into_erasure_coded_sets(
+                &mut rng,
+                &message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                redundancy(),
+            )
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .map(|x| x.into_bytes())
+            .collect();
+
+            let data_fragments = Fragment::try_from_bytes(&fragments[0])
+                .unwrap()
+                .erasure_coding()
+                .unwrap()
+                .data_fragments as usize;
+            assert!(fragments.len() > data_fragments);
+
+            let mut message_reconstructor = MessageReconstructor::default();
+            let mut reconstructed = None;
+            for fragment in fragments {
+                if let Some(msg) = message_reconstructor
+                    .insert_new_fragment(message_reconstructor.recover_fragment(fragment).unwrap())
+                {
+                    assert!(reconstructed.is_none());
+                    reconstructed = Some(msg)
+                }
+            }
+
+            assert_eq!(reconstructed.unwrap().0, message);
+            assert!(message_reconstructor.reconstructed_sets.is_empty());
+        }
+
+        #[test]
+        fn it_does_not_reconstruct_message_with_too_many_fragments_missing() {
+            let mut rng = thread_rng();
+
+            let mut message = vec![0u8; 12345];
+            rng.fill_bytes(&mut message);
+
+            let mut fragments: Vec<_> = crate::split_into_erasure_coded_sets(
+                &mut rng,
+                &message,
+                AVAILABLE_PLAINTEXT_SIZE,
+                redundancy(),
+            )
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .map(|x| x.into_bytes())
+            .collect();
+
+            let data_fragments = Fragment::try_from_bytes(&fragments[0])
+                .unwrap()
+                .erasure_coding()
+                .unwrap()
+                .data_fragments as usize;
+            fragments.truncate(data_fragments - 1);
+
+            let mut message_reconstructor = MessageReconstructor::default();
+            for fragment in fragments {
+                assert!(message_reconstructor
+                    .insert_new_fragment(message_reconstructor.recover_fragment(fragment).unwrap())
+                    .is_none());
+            }
+        }
+    }
 }
@@ -1,9 +1,11 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::erasure::{self, RedundancyRatio};
 use crate::fragment::{
-    linked_fragment_payload_max_len, unlinked_fragment_payload_max_len, Fragment,
-    LINKED_FRAGMENTED_HEADER_LEN, UNLINKED_FRAGMENTED_HEADER_LEN,
+    erasure_coded_fragment_payload_len, linked_fragment_payload_max_len,
+    unlinked_fragment_payload_max_len, ErasureCodingInfo, Fragment, LINKED_FRAGMENTED_HEADER_LEN,
+    UNLINKED_FRAGMENTED_HEADER_LEN,
 };
 use rand::Rng;
 
@@ -35,6 +37,18 @@ pub const fn two_way_linked_set_payload_length(max_plaintext_size: usize) -> usi
         - 2 * (LINKED_FRAGMENTED_HEADER_LEN - UNLINKED_FRAGMENTED_HEADER_LEN)
 }
 
+/// If the set is protected with forward error correction, its payload length is bounded by
+/// the maximum number of data `Fragment`s, so that together with the parity `Fragment`s
+/// it would still fit in a single set, multiplied by their (constant) length, which in turn
+/// depends on the number of sets it is linked to.
+pub fn max_erasure_coded_set_payload_length(
+    max_plaintext_size: usize,
+    links: usize,
+    redundancy: RedundancyRatio,
+) -> usize {
+    redundancy.max_data_fragments() * erasure_coded_fragment_payload_len(max_plaintext_size, links)
+}
+
 /// `FragmentSet` is an ordered collection of 1 to 255 `Fragment`s, each with the same ID
 /// that can be used to produce original message, assuming no linking took place.
 ///
@@ -206,6 +220,85 @@ pub(crate) fn total_number_of_sets(message_len: usize, max_plaintext_size: usize
     }
 }
 
+/// Based on total message length, determines the number of erasure coded sets into which it is going to be split.
+pub(crate) fn total_number_of_erasure_coded_sets(
+    message_len: usize,
+    max_plaintext_size: usize,
+    redundancy: RedundancyRatio,
+) -> usize {
+    let unlinked = max_erasure_coded_set_payload_length(max_plaintext_size, 0, redundancy);
+    let one_way_linked = max_erasure_coded_set_payload_length(max_plaintext_size, 1, redundancy);
+    let two_way_linked = max_erasure_coded_set_payload_length(max_plaintext_size, 2, redundancy);
+
+    if message_len <= unlinked {
+        1
+    } else if message_len <= 2 * one_way_linked {
+        2
+    } else {
+        let len_without_edges = message_len - 2 * one_way_linked;
+        // every set in between edges must be two way linked
+        (len_without_edges as f64 / two_way_linked as f64).ceil() as usize + 2
+    }
+}
+
+/// Splits part of the underlying message into data `Fragment`s of equal length (zero-padding the
+/// final one if required) and appends parity `Fragment`s computed according to the
+/// provided redundancy ratio. The byte slice of the message *must* fit into a single erasure coded set.
+fn prepare_erasure_coded_fragment_set(
+    message: &[u8],
+    id: i32,
+    previous_link_id: Option<i32>,
+    next_link_id: Option<i32>,
+    redundancy: RedundancyRatio,
+    max_plaintext_size: usize,
+) -> FragmentSet {
+    let links = previous_link_id.is_some() as usize + next_link_id.is_some() as usize;
+    let payload_len = erasure_coded_fragment_payload_len(max_plaintext_size, links);
+
+    // even an empty message has to produce a single data fragment
+    let data_fragments = usize::max(
+        1,
+        (message.len() as f64 / payload_len as f64).ceil() as usize,
+    );
+    let parity_fragments = redundancy.parity_fragments(data_fragments);
+    let total_fragments = data_fragments + parity_fragments;
+    debug_assert!(total_fragments <= u8::max_value() as usize);
+
+    let data: Vec<_> = (0..data_fragments)
+        .map(|i| {
+            let lb = usize::min(message.len(), i * payload_len);
+            let ub = usize::min(message.len(), (i + 1) * payload_len);
+            let mut payload = message[lb..ub].to_vec();
+            payload.resize(payload_len, 0);
+            payload
+        })
+        .collect();
+
+    let erasure_coding = ErasureCodingInfo {
+        data_fragments: data_fragments as u8,
+        last_data_fragment_len: (message.len() - (data_fragments - 1) * payload_len) as u16,
+    };
+    let parity = erasure::encode(&data, parity_fragments);
+
+    data.into_iter()
+        .chain(parity)
+        .enumerate()
+        .map(|(i, payload)| {
+            Fragment::try_new_erasure_coded(
+                payload,
+                id,
+                total_fragments as u8,
+                (i + 1) as u8,
+                previous_link_id,
+                next_link_id,
+                erasure_coding,
+                max_plaintext_size,
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
 /// Given part of the underlying message as well id of the set as well as its potential linked sets,
 /// correctly delegates to appropriate set constructor.
 fn prepare_fragment_set(
@@ -293,6 +386,51 @@ pub fn split_into_sets<R: Rng>(
     }
 }
 
+/// Entry point for splitting whole message into possibly multiple erasure coded [`Set`]s.
+/// Each of the produced sets can be reconstructed from *any* `k` out of its `n` `Fragment`s,
+/// where `k` is the number of data `Fragment`s in the set and `n - k` is the number of parity
+/// `Fragment`s as determined by the provided redundancy ratio.
+pub fn split_into_erasure_coded_sets<R: Rng>(
+    rng: &mut R,
+    message: &[u8],
+    max_plaintext_size: usize,
+    redundancy: RedundancyRatio,
+) -> Vec<FragmentSet> {
+    let num_of_sets =
+        total_number_of_erasure_coded_sets(message.len(), max_plaintext_size, redundancy);
+
+    // pre-generate all ids for the sets
+    let set_ids: Vec<_> = std::iter::repeat(())
+        .map(|_| generate_set_id(rng))
+        .take(num_of_sets)
+        .collect();
+
+    let mut sets = Vec::with_capacity(num_of_sets);
+    let mut lb = 0;
+    for i in 0..num_of_sets {
+        let previous_link_id = if i == 0 { None } else { Some(set_ids[i - 1]) };
+        let next_link_id = set_ids.get(i + 1).copied();
+        let links = previous_link_id.is_some() as usize + next_link_id.is_some() as usize;
+
+        let ub = usize::min(
+            message.len(),
+            lb + max_erasure_coded_set_payload_length(max_plaintext_size, links, redundancy),
+        );
+
+        sets.push(prepare_erasure_coded_fragment_set(
+            &message[lb..ub],
+            set_ids[i],
+            previous_link_id,
+            next_link_id,
+            redundancy,
+            max_plaintext_size,
+        ));
+        lb = ub;
+    }
+
+    sets
+}
+
 // reason for top level tests module is to be able to use the helper functions to verify sets payloads
 #[cfg(test)]
 mod tests {
@@ -797,6 +935,80 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod splitting_into_erasure_coded_sets {
+        use super::*;
+        use rand::{thread_rng, RngCore};
+
+        fn redundancy() -> RedundancyRatio {
+            RedundancyRatio::new(0.25).unwrap()
+        }
+
+        #[test]
+        fn creates_single_set_with_expected_number_of_parity_fragments() {
+            let mut rng = thread_rng();
+            let payload_len = erasure_coded_fragment_payload_len(max_plaintext_size(), 0);
+            let mut message = vec![0u8; 8 * payload_len - 42];
+            rng.fill_bytes(&mut message);
+
+            let sets = split_into_erasure_coded_sets(
+                &mut rng,
+                &message,
+                max_plaintext_size(),
+                redundancy(),
+            );
+            assert_eq!(1, sets.len());
+            assert_eq!(10, sets[0].len());
+
+            for (i, fragment) in sets[0].iter().enumerate() {
+                let erasure_coding = fragment.erasure_coding().unwrap();
+                assert_eq!(8, erasure_coding.data_fragments);
+                assert_eq!(
+                    payload_len as u16 - 42,
+                    erasure_coding.last_data_fragment_len
+                );
+                assert_eq!(payload_len, fragment.payload_size());
+                assert_eq!(10, fragment.total_fragments());
+                assert_eq!(i as u8 + 1, fragment.current_fragment());
+                assert!(fragment.previous_fragments_set_id().is_none());
+                assert!(fragment.next_fragments_set_id().is_none());
+            }
+        }
+
+        #[test]
+        fn links_every_fragment_of_multiple_sets() {
+            let mut rng = thread_rng();
+            let mut message =
+                vec![
+                    0u8;
+                    2 * max_erasure_coded_set_payload_length(max_plaintext_size(), 1, redundancy())
+                        + 2345
+                ];
+            rng.fill_bytes(&mut message);
+
+            let sets = split_into_erasure_coded_sets(
+                &mut rng,
+                &message,
+                max_plaintext_size(),
+                redundancy(),
+            );
+            assert_eq!(3, sets.len());
+
+            for (i, set) in sets.iter().enumerate() {
+                let previous = if i == 0 {
+                    None
+                } else {
+                    Some(sets[i - 1][0].id())
+                };
+                let next = sets.get(i + 1).map(|next_set| next_set[0].id());
+                for fragment in set {
+                    assert_eq!(previous, fragment.previous_fragments_set_id());
+                    assert_eq!(next, fragment.next_fragments_set_id());
+                }
+            }
+        }
+    }
+
     #[cfg(test)]
     mod helpers {
         use super::*;
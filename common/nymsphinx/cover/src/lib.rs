@@ -14,14 +14,18 @@ use nymsphinx_params::{
     PacketEncryptionAlgorithm, PacketHkdfAlgorithm, PacketMode, DEFAULT_NUM_MIX_HOPS,
 };
 use nymsphinx_types::builder::SphinxPacketBuilder;
-use nymsphinx_types::{delays, Error as SphinxError};
+use nymsphinx_types::{delays, Error as SphinxError, Node as SphinxNode};
 use rand::{CryptoRng, RngCore};
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use std::time;
 use thiserror::Error;
-use topology::{NymTopology, NymTopologyError};
+use topology::{mix, NymTopology, NymTopologyError};
 
 pub const LOOP_COVER_MESSAGE_PAYLOAD: &[u8] = b"The cake is a lie!";
+pub const LOOP_PROBE_MESSAGE_PREFIX: &[u8] = b"Is anybody out there?";
+
+/// Identifier of a loop probe packet used for checking whether particular mixnet path works.
+pub type ProbeId = u64;
 
 #[derive(Debug, Error)]
 pub enum CoverMessageError {
@@ -61,6 +65,86 @@ pub fn generate_loop_cover_packet<R>(
     average_packet_delay: time::Duration,
     packet_size: PacketSize,
 ) -> Result<MixPacket, CoverMessageError>
+where
+    R: RngCore + CryptoRng,
+{
+    let route =
+        topology.random_route_to_gateway(rng, DEFAULT_NUM_MIX_HOPS, full_address.gateway())?;
+
+    generate_loop_packet(
+        rng,
+        topology,
+        ack_key,
+        full_address,
+        route,
+        LOOP_COVER_MESSAGE_PAYLOAD,
+        average_ack_delay,
+        average_packet_delay,
+        packet_size,
+    )
+}
+
+/// Generates a loop packet going through exactly the specified mixnodes (and then our own gateway)
+/// that is going to be recognised by `probe_id` once it gets back to us.
+/// From the point of view of the mixnet it's indistinguishable from a loop cover message.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_loop_probe_packet<R>(
+    rng: &mut R,
+    topology: &NymTopology,
+    ack_key: &AckKey,
+    full_address: &Recipient,
+    probed_mixes: &[mix::Node],
+    probe_id: ProbeId,
+    average_ack_delay: time::Duration,
+    average_packet_delay: time::Duration,
+    packet_size: PacketSize,
+) -> Result<MixPacket, CoverMessageError>
+where
+    R: RngCore + CryptoRng,
+{
+    let gateway = topology.get_gateway(full_address.gateway()).ok_or(
+        NymTopologyError::NonExistentGatewayError {
+            identity_key: full_address.gateway().to_base58_string(),
+        },
+    )?;
+
+    let route = probed_mixes
+        .iter()
+        .map(SphinxNode::from)
+        .chain(std::iter::once(gateway.into()))
+        .collect();
+
+    let content: Vec<_> = LOOP_PROBE_MESSAGE_PREFIX
+        .iter()
+        .copied()
+        .chain(probe_id.to_be_bytes())
+        .collect();
+
+    generate_loop_packet(
+        rng,
+        topology,
+        ack_key,
+        full_address,
+        route,
+        &content,
+        average_ack_delay,
+        average_packet_delay,
+        packet_size,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn generate_loop_packet<R>(
+    rng: &mut R,
+    topology: &NymTopology,
+    ack_key: &AckKey,
+    full_address: &Recipient,
+    route: Vec<SphinxNode>,
+    content: &[u8],
+    average_ack_delay: time::Duration,
+    average_packet_delay: time::Duration,
+    packet_size: PacketSize,
+) -> Result<MixPacket, CoverMessageError>
 where
     R: RngCore + CryptoRng,
 {
@@ -81,7 +165,7 @@ where
     let public_key_bytes = ephemeral_keypair.public_key().to_bytes();
     let cover_size = packet_size.plaintext_size() - public_key_bytes.len() - ack_bytes.len();
 
-    let mut cover_content: Vec<_> = LOOP_COVER_MESSAGE_PAYLOAD
+    let mut cover_content: Vec<_> = content
         .iter()
         .cloned()
         .chain(std::iter::once(1))
@@ -105,8 +189,6 @@ where
         .chain(cover_content.into_iter())
         .collect();
 
-    let route =
-        topology.random_route_to_gateway(rng, DEFAULT_NUM_MIX_HOPS, full_address.gateway())?;
     let delays = delays::generate_from_average_duration(route.len(), average_packet_delay);
     let destination = full_address.as_sphinx_destination();
 
@@ -122,6 +204,15 @@ where
     Ok(MixPacket::new(first_hop_address, packet, PacketMode::Mix))
 }
 
+/// Helper function used to determine if given message represents a loop probe message
+/// and if so, returns its id.
+pub fn probe_id(data: &[u8]) -> Option<ProbeId> {
+    let id_bytes = data.strip_prefix(LOOP_PROBE_MESSAGE_PREFIX)?;
+    let id_bytes = id_bytes.get(..std::mem::size_of::<ProbeId>())?;
+    // the unwrap is fine as we've just taken the slice of the exact required length
+    Some(ProbeId::from_be_bytes(id_bytes.try_into().unwrap()))
+}
+
 /// Helper function used to determine if given message represents a loop cover message.
 // It kinda seems like there must exist "prefix" or "starts_with" method for bytes
 // or something, but I couldn't find anything
@@ -181,4 +272,31 @@ mod tests {
         let empty = Vec::new();
         assert!(!is_cover(&empty))
     }
+
+    #[test]
+    fn probe_id_is_recovered_from_padded_content() {
+        let input: Vec<_> = LOOP_PROBE_MESSAGE_PREFIX
+            .iter()
+            .copied()
+            .chain(1234u64.to_be_bytes())
+            .chain(std::iter::once(1))
+            .chain(std::iter::repeat(0).take(100))
+            .collect();
+        assert_eq!(probe_id(&input), Some(1234));
+        assert!(!is_cover(&input))
+    }
+
+    #[test]
+    fn probe_id_is_not_recovered_from_other_messages() {
+        assert!(probe_id(LOOP_COVER_MESSAGE_PAYLOAD).is_none());
+        assert!(probe_id(&[]).is_none());
+
+        // the id is cut short
+        let input: Vec<_> = LOOP_PROBE_MESSAGE_PREFIX
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(42).take(4))
+            .collect();
+        assert!(probe_id(&input).is_none())
+    }
 }
@@ -0,0 +1,139 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+/// Upper bound on the size of a decompressed message. It protects the recipient
+/// from so-called decompression bombs, i.e. tiny messages that expand to gigabytes of data.
+pub const MAX_DECOMPRESSED_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    #[error("{received} is not a valid message compression algorithm")]
+    UnknownAlgorithm { received: u8 },
+
+    #[error("Failed to decompress the message - {0}")]
+    MalformedCompressedData(#[from] std::io::Error),
+
+    #[error("The decompressed message exceeds the maximum allowed size of {limit} bytes")]
+    DecompressedMessageTooLarge { limit: usize },
+}
+
+/// Compression algorithm applied to the content of a `NymMessage` before it gets padded
+/// and split into sphinx packets.
+///
+/// Note that the recipient must understand the algorithm in order to recover the message,
+/// so it should only be enabled if the other party is known to support it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MessageCompression {
+    Deflate = 1,
+}
+
+impl TryFrom<u8> for MessageCompression {
+    type Error = CompressionError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            _ if value == (MessageCompression::Deflate as u8) => Ok(Self::Deflate),
+            val => Err(CompressionError::UnknownAlgorithm { received: val }),
+        }
+    }
+}
+
+impl MessageCompression {
+    /// Attempts to compress the provided data.
+    /// Returns `None` if compression would not have made the data any smaller.
+    pub fn compress(&self, data: &[u8]) -> Option<Vec<u8>> {
+        let compressed = match self {
+            MessageCompression::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                // writing to a `Vec` can't fail
+                encoder.write_all(data).ok()?;
+                encoder.finish().ok()?
+            }
+        };
+
+        (compressed.len() < data.len()).then_some(compressed)
+    }
+
+    /// Decompresses the provided data, failing if the result would exceed `max_size` bytes.
+    pub fn decompress(&self, data: &[u8], max_size: usize) -> Result<Vec<u8>, CompressionError> {
+        let decoder = match self {
+            MessageCompression::Deflate => DeflateDecoder::new(data),
+        };
+
+        // read at most a single byte more than the limit so that we'd know whether it got exceeded
+        // without having to ever allocate the entire decompressed content
+        let mut decompressed = Vec::new();
+        decoder
+            .take(max_size as u64 + 1)
+            .read_to_end(&mut decompressed)?;
+
+        if decompressed.len() > max_size {
+            return Err(CompressionError::DecompressedMessageTooLarge { limit: max_size });
+        }
+
+        Ok(decompressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, RngCore};
+
+    #[test]
+    fn compressible_data_round_trips() {
+        let data = b"{\"jsonrpc\":\"2.0\",\"method\":\"foo\"}".repeat(100);
+
+        let compressed = MessageCompression::Deflate.compress(&data).unwrap();
+        assert!(compressed.len() < data.len());
+
+        let decompressed = MessageCompression::Deflate
+            .decompress(&compressed, MAX_DECOMPRESSED_MESSAGE_SIZE)
+            .unwrap();
+        assert_eq!(data, decompressed);
+    }
+
+    #[test]
+    fn incompressible_data_is_not_compressed() {
+        let mut data = vec![0u8; 1000];
+        thread_rng().fill_bytes(&mut data);
+
+        assert!(MessageCompression::Deflate.compress(&data).is_none());
+        assert!(MessageCompression::Deflate.compress(&[]).is_none());
+    }
+
+    #[test]
+    fn decompression_is_bounded() {
+        let data = vec![0u8; 100_000];
+        let compressed = MessageCompression::Deflate.compress(&data).unwrap();
+
+        assert!(MessageCompression::Deflate
+            .decompress(&compressed, 100_000)
+            .is_ok());
+        assert!(matches!(
+            MessageCompression::Deflate.decompress(&compressed, 99_999),
+            Err(CompressionError::DecompressedMessageTooLarge { limit: 99_999 })
+        ));
+    }
+
+    #[test]
+    fn malformed_data_fails_to_decompress() {
+        assert!(MessageCompression::Deflate
+            .decompress(&[0xff; 32], MAX_DECOMPRESSED_MESSAGE_SIZE)
+            .is_err());
+    }
+
+    #[test]
+    fn unknown_algorithm_is_rejected() {
+        assert!(MessageCompression::try_from(1).is_ok());
+        assert!(MessageCompression::try_from(0).is_err());
+        assert!(MessageCompression::try_from(15).is_err());
+    }
+}
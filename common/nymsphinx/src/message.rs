@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::chunking;
+use crate::compression::{CompressionError, MessageCompression};
 use crypto::asymmetric::encryption;
 use crypto::Digest;
 use nymsphinx_addressing::clients::Recipient;
@@ -11,6 +12,7 @@ use nymsphinx_anonymous_replies::requests::{
     ReplyMessageContent,
 };
 use nymsphinx_chunking::fragment::Fragment;
+use nymsphinx_chunking::RedundancyRatio;
 use nymsphinx_params::{PacketSize, ReplySurbKeyDigestAlgorithm};
 use rand::Rng;
 use std::fmt::{Display, Formatter};
@@ -29,8 +31,16 @@ pub enum NymMessageError {
 
     #[error("Received empty message for deserialization")]
     EmptyMessage,
+
+    #[error(transparent)]
+    CompressionError(#[from] CompressionError),
 }
 
+// the message type tag only occupies the lower 4 bits of the first byte of the message,
+// while the upper 4 bits indicate the compression algorithm used (if any)
+const MESSAGE_TYPE_MASK: u8 = 0x0F;
+const COMPRESSION_TAG_SHIFT: u8 = 4;
+
 #[repr(u8)]
 enum NymMessageType {
     Plain = 0,
@@ -135,29 +145,63 @@ impl NymMessage {
         }
     }
 
+    /// Length of the message after serialization, before any compression or padding is applied.
+    pub fn serialized_size(&self, num_mix_hops: u8) -> usize {
+        let inner_size = match self {
+            NymMessage::Plain(msg) => msg.len(),
+            NymMessage::Repliable(msg) => msg.serialized_size(num_mix_hops),
+            NymMessage::Reply(msg) => msg.serialized_size(),
+        };
+
+        1 + inner_size
+    }
+
     // the message is in the format of:
-    // typ || msg
-    fn into_bytes(self) -> Vec<u8> {
-        let typ = self.typ();
+    // compression_tag || typ || msg
+    // where compression_tag and typ share the same byte and msg might have been compressed
+    fn into_bytes(self, compression: Option<MessageCompression>) -> Vec<u8> {
+        let typ = self.typ() as u8;
+        let inner_bytes = self.inner_bytes();
+
+        // if compression doesn't make the message any smaller, just send it as it is
+        if let Some(compression) = compression {
+            if let Some(compressed) = compression.compress(&inner_bytes) {
+                let tag = typ | ((compression as u8) << COMPRESSION_TAG_SHIFT);
+                return std::iter::once(tag).chain(compressed).collect();
+            }
+        }
 
-        std::iter::once(typ as u8)
-            .chain(self.inner_bytes())
-            .collect()
+        std::iter::once(typ).chain(inner_bytes).collect()
     }
 
-    fn try_from_bytes(bytes: &[u8], num_mix_hops: u8) -> Result<Self, NymMessageError> {
+    fn try_from_bytes(
+        bytes: &[u8],
+        num_mix_hops: u8,
+        max_decompressed_size: usize,
+    ) -> Result<Self, NymMessageError> {
         if bytes.is_empty() {
             return Err(NymMessageError::EmptyMessage);
         }
 
-        let typ_tag = NymMessageType::try_from(bytes[0])?;
+        let typ_tag = NymMessageType::try_from(bytes[0] & MESSAGE_TYPE_MASK)?;
+        let compression_tag = bytes[0] >> COMPRESSION_TAG_SHIFT;
+
+        let decompressed;
+        let inner_bytes = if compression_tag == 0 {
+            &bytes[1..]
+        } else {
+            decompressed = MessageCompression::try_from(compression_tag)?
+                .decompress(&bytes[1..], max_decompressed_size)?;
+            &decompressed
+        };
+
         match typ_tag {
-            NymMessageType::Plain => Ok(NymMessage::Plain(bytes[1..].to_vec())),
+            NymMessageType::Plain => Ok(NymMessage::Plain(inner_bytes.to_vec())),
             NymMessageType::Repliable => Ok(NymMessage::Repliable(
-                RepliableMessage::try_from_bytes(&bytes[1..], num_mix_hops)?,
+                RepliableMessage::try_from_bytes(inner_bytes, num_mix_hops)?,
             )),
             NymMessageType::Reply => Ok(NymMessage::Reply(ReplyMessage::try_from_bytes(
-                &bytes[1..],
+                inner_bytes,
             )?)),
         }
     }
@@ -182,8 +226,13 @@ impl NymMessage {
 
     /// Pads the message so that after it gets chunked, it will occupy exactly N sphinx packets.
     /// Produces new_message = message || 1 || 0000....
-    pub fn pad_to_full_packet_lengths(self, plaintext_per_packet: usize) -> PaddedMessage {
-        let bytes = self.into_bytes();
+    /// If compression is specified, it is applied to the message before the padding.
+    pub fn pad_to_full_packet_lengths(
+        self,
+        plaintext_per_packet: usize,
+        compression: Option<MessageCompression>,
+    ) -> PaddedMessage {
+        let bytes = self.into_bytes(compression);
 
         // 1 is added as there will always have to be at least a single byte of padding (1) added
         // to be able to later distinguish the actual padding from the underlying message
@@ -197,6 +246,15 @@ impl NymMessage {
             .collect::<Vec<_>>()
             .into()
     }
+
+    /// Pads the message only with the mandatory marker, i.e. produces new_message = message || 1.
+    /// It is meant to be used for erasure coded messages as in that case the final data fragment
+    /// of each set is always zero-padded to the full length during the encoding anyway.
+    pub fn pad_for_erasure_coding(self, compression: Option<MessageCompression>) -> PaddedMessage {
+        let mut bytes = self.into_bytes(compression);
+        bytes.push(1u8);
+        bytes.into()
+    }
 }
 
 pub struct PaddedMessage(Vec<u8>);
@@ -219,12 +277,31 @@ impl PaddedMessage {
             .collect()
     }
 
-    // reverse of NymMessage::pad_to_full_packet_lengths
-    pub fn remove_padding(self, num_mix_hops: u8) -> Result<NymMessage, NymMessageError> {
+    /// Splits the padded message into erasure coded [`Fragment`]s, so that the recipient
+    /// could reconstruct it even if some of them got lost on the way.
+    pub fn split_into_erasure_coded_fragments<R: Rng>(
+        self,
+        rng: &mut R,
+        plaintext_per_packet: usize,
+        redundancy: RedundancyRatio,
+    ) -> Vec<Fragment> {
+        chunking::split_into_erasure_coded_sets(rng, &self.0, plaintext_per_packet, redundancy)
+            .into_iter()
+            .flat_map(|fragment_set| fragment_set.into_iter())
+            .collect()
+    }
+
+    // reverse of NymMessage::pad_to_full_packet_lengths (and NymMessage::pad_for_erasure_coding)
+    // the size of the message after decompression (if it was compressed) is bounded by `max_decompressed_size`
+    pub fn remove_padding(
+        self,
+        num_mix_hops: u8,
+        max_decompressed_size: usize,
+    ) -> Result<NymMessage, NymMessageError> {
         // we are looking for first occurrence of 1 in the tail and we get its index
         if let Some(padding_end) = self.0.iter().rposition(|b| *b == 1) {
             // and now we only take bytes until that point (but not including it)
-            NymMessage::try_from_bytes(&self.0[..padding_end], num_mix_hops)
+            NymMessage::try_from_bytes(&self.0[..padding_end], num_mix_hops, max_decompressed_size)
         } else {
             Err(NymMessageError::InvalidMessagePadding)
         }
@@ -236,3 +313,74 @@ impl From<Vec<u8>> for PaddedMessage {
         PaddedMessage(bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::MAX_DECOMPRESSED_MESSAGE_SIZE;
+    use nymsphinx_params::DEFAULT_NUM_MIX_HOPS;
+
+    fn recover_plain(padded: PaddedMessage, max_decompressed_size: usize) -> Vec<u8> {
+        match padded
+            .remove_padding(DEFAULT_NUM_MIX_HOPS, max_decompressed_size)
+            .unwrap()
+        {
+            NymMessage::Plain(data) => data,
+            _ => panic!("unexpected message type"),
+        }
+    }
+
+    #[test]
+    fn serialized_size_matches_actual_length() {
+        let message = NymMessage::new_plain(vec![42; 1234]);
+        let expected = message.serialized_size(DEFAULT_NUM_MIX_HOPS);
+        assert_eq!(expected, message.into_bytes(None).len());
+
+        let message = NymMessage::new_reply(ReplyMessage::new_data_message(vec![42; 1234]));
+        let expected = message.serialized_size(DEFAULT_NUM_MIX_HOPS);
+        assert_eq!(expected, message.into_bytes(None).len());
+    }
+
+    #[test]
+    fn compressed_message_round_trips() {
+        let data = b"hello world! ".repeat(1000);
+        let compressed = NymMessage::new_plain(data.clone())
+            .pad_to_full_packet_lengths(1000, Some(MessageCompression::Deflate));
+        let uncompressed =
+            NymMessage::new_plain(data.clone()).pad_to_full_packet_lengths(1000, None);
+
+        assert!(compressed.0.len() < uncompressed.0.len());
+        assert_eq!(
+            data,
+            recover_plain(compressed, MAX_DECOMPRESSED_MESSAGE_SIZE)
+        );
+        assert_eq!(
+            data,
+            recover_plain(uncompressed, MAX_DECOMPRESSED_MESSAGE_SIZE)
+        );
+    }
+
+    #[test]
+    fn compression_is_skipped_if_it_does_not_shrink_the_message() {
+        let data = vec![42];
+        let padded = NymMessage::new_plain(data.clone())
+            .pad_for_erasure_coding(Some(MessageCompression::Deflate));
+
+        // type tag || data || padding marker
+        assert_eq!(padded.0, vec![NymMessageType::Plain as u8, 42, 1]);
+        assert_eq!(data, recover_plain(padded, MAX_DECOMPRESSED_MESSAGE_SIZE));
+    }
+
+    #[test]
+    fn decompression_limit_is_enforced() {
+        let padded = NymMessage::new_plain(vec![0; 10000])
+            .pad_to_full_packet_lengths(1000, Some(MessageCompression::Deflate));
+
+        assert!(matches!(
+            padded.remove_padding(DEFAULT_NUM_MIX_HOPS, 1000),
+            Err(NymMessageError::CompressionError(
+                CompressionError::DecompressedMessageTooLarge { .. }
+            ))
+        ))
+    }
+}
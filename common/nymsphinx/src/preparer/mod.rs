@@ -1,6 +1,8 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::chunking;
+use crate::compression::MessageCompression;
 use crate::message::NymMessage;
 use crate::NymsphinxPayloadBuilder;
 use nymsphinx_acknowledgements::surb_ack::SurbAck;
@@ -9,6 +11,7 @@ use nymsphinx_addressing::clients::Recipient;
 use nymsphinx_addressing::nodes::NymNodeRoutingAddress;
 use nymsphinx_anonymous_replies::reply_surb::ReplySurb;
 use nymsphinx_chunking::fragment::{Fragment, FragmentIdentifier};
+use nymsphinx_chunking::RedundancyRatio;
 use nymsphinx_forwarding::packet::MixPacket;
 use nymsphinx_params::packet_sizes::PacketSize;
 use nymsphinx_params::DEFAULT_NUM_MIX_HOPS;
@@ -27,6 +30,10 @@ pub struct PreparedFragment {
     /// until receiving the acknowledgement included inside of it.
     pub total_delay: Delay,
 
+    /// Indicates the delay introduced by the mixnodes on the forward route only, i.e. without
+    /// the delay of the acknowledgement packet.
+    pub forward_delay: Delay,
+
     /// Indicates all data required to serialize and forward the data. It contains the actual
     /// address of the node to which the message should be sent, the actual 'chunk' of the message
     /// going through the mix network and also the 'mode' of the packet, i.e. VPN or Mix.
@@ -45,9 +52,6 @@ pub struct MessagePreparer<R> {
     /// Instance of a cryptographically secure random number generator.
     rng: R,
 
-    /// Size of the target [`SphinxPacket`] into which the underlying is going to get split.
-    packet_size: PacketSize,
-
     /// Address of this client which also represent an address to which all acknowledgements
     /// and surb-based are going to be sent.
     sender_address: Recipient,
@@ -75,7 +79,6 @@ where
     ) -> Self {
         MessagePreparer {
             rng,
-            packet_size: Default::default(),
             sender_address,
             average_packet_delay,
             average_ack_delay,
@@ -89,12 +92,6 @@ where
         self
     }
 
-    /// Allows setting non-default size of the sphinx packets sent out.
-    pub fn with_custom_real_message_packet_size(mut self, packet_size: PacketSize) -> Self {
-        self.packet_size = packet_size;
-        self
-    }
-
     /// Overwrites existing sender address with the provided value.
     pub fn set_sender_address(&mut self, sender_address: Recipient) {
         self.sender_address = sender_address;
@@ -135,6 +132,7 @@ where
         topology: &NymTopology,
         ack_key: &AckKey,
         reply_surb: ReplySurb,
+        packet_size: PacketSize,
     ) -> Result<PreparedFragment, NymTopologyError> {
         // this is not going to be accurate by any means. but that's the best estimation we can do
         let expected_forward_delay = Delay::new_from_millis(
@@ -153,7 +151,7 @@ where
         // the unwrap here is fine as the failures can only originate from attempting to use invalid payload lenghts
         // and we just very carefully constructed a (presumably) valid one
         let (sphinx_packet, first_hop_address) = reply_surb
-            .apply_surb(packet_payload, Some(self.packet_size))
+            .apply_surb(packet_payload, Some(packet_size))
             .unwrap();
 
         Ok(PreparedFragment {
@@ -161,6 +159,7 @@ where
             // well as the total delay of the ack packet.
             // we don't know the delays inside the reply surbs so we use best-effort estimation from our poisson distribution
             total_delay: expected_forward_delay + ack_delay,
+            forward_delay: expected_forward_delay,
             mix_packet: MixPacket::new(first_hop_address, sphinx_packet, Default::default()),
             fragment_identifier,
         })
@@ -189,6 +188,7 @@ where
         topology: &NymTopology,
         ack_key: &AckKey,
         packet_recipient: &Recipient,
+        packet_size: PacketSize,
     ) -> Result<PreparedFragment, NymTopologyError> {
         let fragment_identifier = fragment.fragment_identifier();
 
@@ -213,7 +213,7 @@ where
         // create the actual sphinx packet here. With valid route and correct payload size,
         // there's absolutely no reason for this call to fail.
         let sphinx_packet = SphinxPacketBuilder::new()
-            .with_payload_size(self.packet_size.payload_size())
+            .with_payload_size(packet_size.payload_size())
             .build_packet(packet_payload, &route, &destination, &delays)
             .unwrap();
 
@@ -221,11 +221,14 @@ where
         let first_hop_address =
             NymNodeRoutingAddress::try_from(route.first().unwrap().address).unwrap();
 
+        // note that the last hop of the packet is a gateway that does not do any delays
+        let forward_delay = delays.iter().take(delays.len() - 1).sum::<Delay>();
+
         Ok(PreparedFragment {
             // the round-trip delay is the sum of delays of all hops on the forward route as
             // well as the total delay of the ack packet.
-            // note that the last hop of the packet is a gateway that does not do any delays
-            total_delay: delays.iter().take(delays.len() - 1).sum::<Delay>() + ack_delay,
+            total_delay: forward_delay + ack_delay,
+            forward_delay,
             mix_packet: MixPacket::new(first_hop_address, sphinx_packet, Default::default()),
             fragment_identifier,
         })
@@ -248,13 +251,67 @@ where
         )
     }
 
-    pub fn pad_and_split_message(&mut self, message: NymMessage) -> Vec<Fragment> {
-        let plaintext_per_packet = message.available_plaintext_per_packet(self.packet_size);
+    /// Out of the provided candidate packet sizes, chooses the one that would result in the fewest
+    /// bytes being put on the wire when sending the given message. In case of a tie,
+    /// the bigger packet size is preferred as fewer packets would have to be created.
+    ///
+    /// Note that it's only an estimation as it ignores the potential compression and erasure coding.
+    pub fn optimal_packet_size(
+        &self,
+        message: &NymMessage,
+        candidates: &[PacketSize],
+    ) -> Option<PacketSize> {
+        // 1 is added for the mandatory padding byte
+        let message_len = message.serialized_size(self.num_mix_hops) + 1;
+
+        candidates
+            .iter()
+            .map(|&packet_size| {
+                let plaintext_per_packet = message.available_plaintext_per_packet(packet_size);
+                let (packets, _) =
+                    chunking::number_of_required_fragments(message_len, plaintext_per_packet);
+                (packet_size, packets * packet_size.size())
+            })
+            .min_by(|(size_a, bytes_a), (size_b, bytes_b)| {
+                bytes_a
+                    .cmp(bytes_b)
+                    .then_with(|| size_b.size().cmp(&size_a.size()))
+            })
+            .map(|(packet_size, _)| packet_size)
+    }
+
+    /// Pads the message and splits it into [`Fragment`]s that fit in sphinx packets of the specified size.
+    /// If compression is specified, the content of the message is compressed with the given algorithm
+    /// (assuming it would actually make it smaller). Note that the recipient has to support it.
+    pub fn pad_and_split_message(
+        &mut self,
+        message: NymMessage,
+        packet_size: PacketSize,
+        compression: Option<MessageCompression>,
+    ) -> Vec<Fragment> {
+        let plaintext_per_packet = message.available_plaintext_per_packet(packet_size);
 
         message
-            .pad_to_full_packet_lengths(plaintext_per_packet)
+            .pad_to_full_packet_lengths(plaintext_per_packet, compression)
             .split_into_fragments(&mut self.rng, plaintext_per_packet)
     }
+
+    /// Pads the message and splits it into erasure coded [`Fragment`]s that fit in sphinx packets
+    /// of the specified size, with the given ratio of parity to data fragments, so that the recipient
+    /// can reconstruct it from a subset of them. Note that the recipient has to support it.
+    pub fn pad_and_split_erasure_coded_message(
+        &mut self,
+        message: NymMessage,
+        packet_size: PacketSize,
+        compression: Option<MessageCompression>,
+        redundancy: RedundancyRatio,
+    ) -> Vec<Fragment> {
+        let plaintext_per_packet = message.available_plaintext_per_packet(packet_size);
+
+        message
+            .pad_for_erasure_coding(compression)
+            .split_into_erasure_coded_fragments(&mut self.rng, plaintext_per_packet, redundancy)
+    }
 }
 
 /*
@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::compression::MAX_DECOMPRESSED_MESSAGE_SIZE;
 use crate::message::{NymMessage, NymMessageError, PaddedMessage, PlainMessage};
 use crypto::aes::cipher::{KeyIvInit, StreamCipher};
 use crypto::asymmetric::encryption;
@@ -159,7 +160,9 @@ impl MessageReceiver {
         fragment: Fragment,
     ) -> Result<Option<(NymMessage, Vec<i32>)>, MessageRecoveryError> {
         if let Some((message, used_sets)) = self.reconstructor.insert_new_fragment(fragment) {
-            match PaddedMessage::new_reconstructed(message).remove_padding(self.num_mix_hops) {
+            match PaddedMessage::new_reconstructed(message)
+                .remove_padding(self.num_mix_hops, MAX_DECOMPRESSED_MESSAGE_SIZE)
+            {
                 Ok(message) => Ok(Some((message, used_sets))),
                 Err(err) => Err(MessageRecoveryError::MalformedReconstructedMessage {
                     source: err,
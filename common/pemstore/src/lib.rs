@@ -3,12 +3,27 @@
 
 use crate::traits::{PemStorableKey, PemStorableKeyPair};
 use pem::{self, Pem};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use store_cipher::{EncryptedData, KdfInfo, StoreCipher};
+use zeroize::Zeroize;
 
 pub mod traits;
 
+// prefix of the pem tag of the keys encrypted with a `StoreCipher`, e.g. "ENCRYPTED ED25519 PRIVATE KEY"
+const ENCRYPTED_TAG_PREFIX: &str = "ENCRYPTED ";
+
+/// Contents of the pem file holding an encrypted key. Alongside the ciphertext it includes
+/// the information required to re-derive the encryption key from the passphrase.
+#[derive(Serialize, Deserialize)]
+struct EncryptedPemKey {
+    kdf_info: KdfInfo,
+    data: EncryptedData,
+}
+
 pub struct KeyPairPath {
     private_key_path: PathBuf,
     public_key_path: PathBuf,
@@ -46,6 +61,13 @@ where
 {
     let key_pem = read_pem_file(path)?;
 
+    if key_pem.tag.starts_with(ENCRYPTED_TAG_PREFIX) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "the key is encrypted - a passphrase is required to load it",
+        ));
+    }
+
     if T::pem_type() != key_pem.tag {
         return Err(io::Error::new(
             io::ErrorKind::Other,
@@ -68,6 +90,126 @@ where
     write_pem_file(path, key.to_bytes(), T::pem_type())
 }
 
+/// Loads keypair whose private key has been encrypted with [`store_keypair_encrypted`].
+/// Note that the public key is not secret and thus stored in plaintext.
+pub fn load_keypair_encrypted<T>(paths: &KeyPairPath, cipher: &StoreCipher) -> io::Result<T>
+where
+    T: PemStorableKeyPair,
+{
+    let private = load_key_encrypted::<T::PrivatePemKey>(&paths.private_key_path, cipher)?;
+    let public = load_key::<T::PublicPemKey>(&paths.public_key_path)?;
+    Ok(T::from_keys(private, public))
+}
+
+/// Stores the keypair encrypting its private key with the provided cipher.
+pub fn store_keypair_encrypted<T>(
+    keypair: &T,
+    paths: &KeyPairPath,
+    cipher: &StoreCipher,
+) -> io::Result<()>
+where
+    T: PemStorableKeyPair,
+{
+    store_key(keypair.public_key(), &paths.public_key_path)?;
+    store_key_encrypted(keypair.private_key(), &paths.private_key_path, cipher)
+}
+
+/// Loads key that has been encrypted with [`store_key_encrypted`].
+pub fn load_key_encrypted<T>(path: &Path, cipher: &StoreCipher) -> io::Result<T>
+where
+    T: PemStorableKey,
+{
+    let key_pem = read_pem_file(path)?;
+
+    if key_pem.tag.strip_prefix(ENCRYPTED_TAG_PREFIX) != Some(T::pem_type()) {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "unexpected encrypted key pem tag",
+        ));
+    }
+
+    let encrypted: EncryptedPemKey = serde_json::from_slice(&key_pem.contents)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if encrypted.kdf_info != cipher.kdf_info() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the key has been encrypted with a different key derivation setup",
+        ));
+    }
+
+    let mut plaintext = cipher
+        .decrypt_data_unchecked(&encrypted.data)
+        .map_err(|err| io::Error::new(io::ErrorKind::PermissionDenied, err.to_string()))?;
+    let key = T::from_bytes(&plaintext);
+    plaintext.zeroize();
+
+    key.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+}
+
+/// Stores the key encrypted with the provided cipher.
+pub fn store_key_encrypted<T>(key: &T, path: &Path, cipher: &StoreCipher) -> io::Result<()>
+where
+    T: PemStorableKey,
+{
+    let mut plaintext = key.to_bytes();
+    let data = cipher.encrypt_data_ref(&plaintext, &mut OsRng);
+    plaintext.zeroize();
+
+    let encrypted = EncryptedPemKey {
+        kdf_info: cipher.kdf_info(),
+        data: data.map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?,
+    };
+    let contents = serde_json::to_vec(&encrypted)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    write_pem_file(
+        path,
+        contents,
+        &format!("{ENCRYPTED_TAG_PREFIX}{}", T::pem_type()),
+    )
+}
+
+/// Loads key that might have been stored either in plaintext or encrypted with the provided cipher,
+/// for example when resuming an interrupted encryption of the keys.
+pub fn load_key_possibly_encrypted<T>(path: &Path, cipher: &StoreCipher) -> io::Result<T>
+where
+    T: PemStorableKey,
+{
+    if read_kdf_info(path)?.is_some() {
+        load_key_encrypted(path, cipher)
+    } else {
+        load_key(path)
+    }
+}
+
+/// Loads keypair whose private key might have been stored either in plaintext or encrypted
+/// with the provided cipher. Note that the public key is always stored in plaintext.
+pub fn load_keypair_possibly_encrypted<T>(
+    paths: &KeyPairPath,
+    cipher: &StoreCipher,
+) -> io::Result<T>
+where
+    T: PemStorableKeyPair,
+{
+    let private = load_key_possibly_encrypted::<T::PrivatePemKey>(&paths.private_key_path, cipher)?;
+    let public = load_key::<T::PublicPemKey>(&paths.public_key_path)?;
+    Ok(T::from_keys(private, public))
+}
+
+/// Retrieves the key derivation information of an encrypted key,
+/// so that the cipher could be recreated from the passphrase.
+/// Returns `None` if the key has been stored in plaintext.
+pub fn read_kdf_info(path: &Path) -> io::Result<Option<KdfInfo>> {
+    let key_pem = read_pem_file(path)?;
+    if !key_pem.tag.starts_with(ENCRYPTED_TAG_PREFIX) {
+        return Ok(None);
+    }
+
+    let encrypted: EncryptedPemKey = serde_json::from_slice(&key_pem.contents)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(Some(encrypted.kdf_info))
+}
+
 fn read_pem_file(filepath: &Path) -> io::Result<Pem> {
     let mut pem_bytes = File::open(filepath)?;
     let mut buf = Vec::new();
@@ -75,6 +217,15 @@ fn read_pem_file(filepath: &Path) -> io::Result<Pem> {
     pem::parse(&buf).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
 }
 
+// path of the temporary file the key is written to before it gets moved in place of the target
+fn temporary_path(filepath: &Path) -> PathBuf {
+    let mut file_name = filepath.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    filepath.with_file_name(file_name)
+}
+
+// the key is first written to a temporary file that is then renamed into place,
+// so that an interrupted write would never leave a partially written (or missing) key behind
 fn write_pem_file(filepath: &Path, data: Vec<u8>, tag: &str) -> io::Result<()> {
     // ensure the whole directory structure exists
     if let Some(parent_dir) = filepath.parent() {
@@ -86,7 +237,8 @@ fn write_pem_file(filepath: &Path, data: Vec<u8>, tag: &str) -> io::Result<()> {
     };
     let key = pem::encode(&pem);
 
-    let mut file = File::create(filepath)?;
+    let temporary_path = temporary_path(filepath);
+    let mut file = File::create(&temporary_path)?;
     file.write_all(key.as_bytes())?;
 
     // note: this is only supported on unix (on different systems, like Windows, it will just
@@ -101,8 +253,108 @@ fn write_pem_file(filepath: &Path, data: Vec<u8>, tag: &str) -> io::Result<()> {
 
         let mut permissions = file.metadata()?.permissions();
         permissions.set_mode(0o600);
-        fs::set_permissions(filepath, permissions)?;
+        fs::set_permissions(&temporary_path, permissions)?;
     }
 
-    Ok(())
+    file.sync_all()?;
+    std::fs::rename(&temporary_path, filepath)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store_cipher::{Argon2Params, ARGON2_SALT_SIZE};
+
+    struct DummyKey(Vec<u8>);
+
+    impl PemStorableKey for DummyKey {
+        type Error = io::Error;
+
+        fn pem_type() -> &'static str {
+            "DUMMY KEY"
+        }
+
+        fn to_bytes(&self) -> Vec<u8> {
+            self.0.clone()
+        }
+
+        fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error> {
+            Ok(DummyKey(bytes.to_vec()))
+        }
+    }
+
+    fn test_cipher(passphrase: &[u8]) -> StoreCipher {
+        let kdf_info = KdfInfo::Argon2id {
+            params: Argon2Params {
+                m_cost: 64,
+                t_cost: 1,
+                p_cost: 1,
+            },
+            kdf_salt: [1u8; ARGON2_SALT_SIZE],
+        };
+        StoreCipher::new_with_kdf_info(passphrase, kdf_info).unwrap()
+    }
+
+    #[test]
+    fn encrypted_key_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+        let cipher = test_cipher(b"passphrase");
+
+        store_key_encrypted(&DummyKey(vec![1, 2, 3]), &path, &cipher).unwrap();
+        assert_eq!(read_kdf_info(&path).unwrap(), Some(cipher.kdf_info()));
+
+        let loaded: DummyKey = load_key_encrypted(&path, &cipher).unwrap();
+        assert_eq!(loaded.0, vec![1, 2, 3]);
+
+        // the key is no longer readable without the passphrase
+        assert!(load_key::<DummyKey>(&path).is_err());
+        assert!(load_key_encrypted::<DummyKey>(&path, &test_cipher(b"other")).is_err());
+    }
+
+    #[test]
+    fn plaintext_keys_have_no_kdf_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+
+        store_key(&DummyKey(vec![1, 2, 3]), &path).unwrap();
+        assert_eq!(read_kdf_info(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn keys_are_replaced_without_leaving_temporary_files_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+        let cipher = test_cipher(b"passphrase");
+
+        store_key(&DummyKey(vec![1, 2, 3]), &path).unwrap();
+        store_key_encrypted(&DummyKey(vec![4, 5, 6]), &path, &cipher).unwrap();
+
+        let loaded: DummyKey = load_key_encrypted(&path, &cipher).unwrap();
+        assert_eq!(loaded.0, vec![4, 5, 6]);
+        assert!(!temporary_path(&path).exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn keys_are_loaded_regardless_of_whether_they_got_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let plaintext_path = dir.path().join("plaintext.pem");
+        let encrypted_path = dir.path().join("encrypted.pem");
+        let cipher = test_cipher(b"passphrase");
+
+        store_key(&DummyKey(vec![1, 2, 3]), &plaintext_path).unwrap();
+        store_key_encrypted(&DummyKey(vec![4, 5, 6]), &encrypted_path, &cipher).unwrap();
+
+        let plaintext: DummyKey = load_key_possibly_encrypted(&plaintext_path, &cipher).unwrap();
+        assert_eq!(plaintext.0, vec![1, 2, 3]);
+        let encrypted: DummyKey = load_key_possibly_encrypted(&encrypted_path, &cipher).unwrap();
+        assert_eq!(encrypted.0, vec![4, 5, 6]);
+
+        // but the already encrypted keys still require the right passphrase
+        assert!(
+            load_key_possibly_encrypted::<DummyKey>(&encrypted_path, &test_cipher(b"other"))
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,270 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{CryptoRng, RngCore};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::Zeroize;
+
+pub const ARGON2_SALT_SIZE: usize = 16;
+pub const AES256GCM_NONCE_SIZE: usize = 12;
+const CIPHER_KEY_SIZE: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum StoreCipherError {
+    #[error("failed to derive the encryption key from the provided passphrase - {0}")]
+    KeyDerivationFailure(argon2::Error),
+
+    #[error("failed to encrypt the provided data")]
+    EncryptionFailure,
+
+    #[error("failed to decrypt the provided data. Perhaps the passphrase is incorrect?")]
+    DecryptionFailure,
+
+    #[error("failed to (de)serialize the data - {source}")]
+    SerializationFailure {
+        #[from]
+        source: serde_json::Error,
+    },
+}
+
+/// Parameters of the Argon2id key derivation function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Argon2Params {
+    /// Memory cost expressed in KiB.
+    pub m_cost: u32,
+
+    /// Number of iterations.
+    pub t_cost: u32,
+
+    /// Degree of parallelism.
+    pub p_cost: u32,
+}
+
+impl Default for Argon2Params {
+    // the OWASP recommended minimum configuration for Argon2id
+    fn default() -> Self {
+        Argon2Params {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+/// Information required to re-derive the same encryption key out of the passphrase.
+/// It is not secret and is expected to be stored alongside the encrypted data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KdfInfo {
+    Argon2id {
+        params: Argon2Params,
+        kdf_salt: [u8; ARGON2_SALT_SIZE],
+    },
+}
+
+impl KdfInfo {
+    pub fn new_with_default_params<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut kdf_salt = [0u8; ARGON2_SALT_SIZE];
+        rng.fill_bytes(&mut kdf_salt);
+
+        KdfInfo::Argon2id {
+            params: Argon2Params::default(),
+            kdf_salt,
+        }
+    }
+
+    fn derive_key(&self, passphrase: &[u8]) -> Result<[u8; CIPHER_KEY_SIZE], StoreCipherError> {
+        match self {
+            KdfInfo::Argon2id { params, kdf_salt } => {
+                let params = Params::new(
+                    params.m_cost,
+                    params.t_cost,
+                    params.p_cost,
+                    Some(CIPHER_KEY_SIZE),
+                )
+                .map_err(StoreCipherError::KeyDerivationFailure)?;
+
+                let mut key = [0u8; CIPHER_KEY_SIZE];
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(passphrase, kdf_salt, &mut key)
+                    .map_err(StoreCipherError::KeyDerivationFailure)?;
+                Ok(key)
+            }
+        }
+    }
+}
+
+/// Data encrypted with AES256-GCM alongside the nonce used for the encryption.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedData {
+    pub nonce: [u8; AES256GCM_NONCE_SIZE],
+    pub ciphertext: Vec<u8>,
+}
+
+/// Cipher for encrypting data at rest with a key derived out of a user-provided passphrase.
+pub struct StoreCipher {
+    kdf_info: KdfInfo,
+    key: [u8; CIPHER_KEY_SIZE],
+}
+
+impl StoreCipher {
+    /// Creates new instance of the [`StoreCipher`] using fresh salt and the default
+    /// key derivation parameters.
+    pub fn new<R>(passphrase: &[u8], rng: &mut R) -> Result<Self, StoreCipherError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        Self::new_with_kdf_info(passphrase, KdfInfo::new_with_default_params(rng))
+    }
+
+    /// Recovers the [`StoreCipher`] using previously used key derivation information.
+    pub fn new_with_kdf_info(
+        passphrase: &[u8],
+        kdf_info: KdfInfo,
+    ) -> Result<Self, StoreCipherError> {
+        Ok(StoreCipher {
+            key: kdf_info.derive_key(passphrase)?,
+            kdf_info,
+        })
+    }
+
+    pub fn kdf_info(&self) -> KdfInfo {
+        self.kdf_info
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::from_slice(&self.key))
+    }
+
+    pub fn encrypt_data_ref<R>(
+        &self,
+        data: &[u8],
+        rng: &mut R,
+    ) -> Result<EncryptedData, StoreCipherError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut nonce = [0u8; AES256GCM_NONCE_SIZE];
+        rng.fill_bytes(&mut nonce);
+
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), data)
+            .map_err(|_| StoreCipherError::EncryptionFailure)?;
+
+        Ok(EncryptedData { nonce, ciphertext })
+    }
+
+    pub fn encrypt_data<T, R>(
+        &self,
+        data: &T,
+        rng: &mut R,
+    ) -> Result<EncryptedData, StoreCipherError>
+    where
+        T: Serialize,
+        R: RngCore + CryptoRng,
+    {
+        let mut serialized = serde_json::to_vec(data)?;
+        let encrypted = self.encrypt_data_ref(&serialized, rng);
+        serialized.zeroize();
+        encrypted
+    }
+
+    pub fn decrypt_data_unchecked(
+        &self,
+        data: &EncryptedData,
+    ) -> Result<Vec<u8>, StoreCipherError> {
+        self.cipher()
+            .decrypt(Nonce::from_slice(&data.nonce), data.ciphertext.as_ref())
+            .map_err(|_| StoreCipherError::DecryptionFailure)
+    }
+
+    pub fn decrypt_data<T>(&self, data: &EncryptedData) -> Result<T, StoreCipherError>
+    where
+        T: DeserializeOwned,
+    {
+        let mut plaintext = self.decrypt_data_unchecked(data)?;
+        let deserialized = serde_json::from_slice(&plaintext);
+        plaintext.zeroize();
+        Ok(deserialized?)
+    }
+}
+
+impl Drop for StoreCipher {
+    fn drop(&mut self) {
+        self.key.zeroize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    // make the tests run in a reasonable time
+    fn weak_kdf_info() -> KdfInfo {
+        KdfInfo::Argon2id {
+            params: Argon2Params {
+                m_cost: 64,
+                t_cost: 1,
+                p_cost: 1,
+            },
+            kdf_salt: [42u8; ARGON2_SALT_SIZE],
+        }
+    }
+
+    #[test]
+    fn data_round_trips() {
+        let mut rng = thread_rng();
+        let cipher = StoreCipher::new_with_kdf_info(b"my-passphrase", weak_kdf_info()).unwrap();
+
+        let data = vec![1u8, 2, 3, 4, 5];
+        let encrypted = cipher.encrypt_data(&data, &mut rng).unwrap();
+        let decrypted: Vec<u8> = cipher.decrypt_data(&encrypted).unwrap();
+        assert_eq!(data, decrypted);
+
+        // the same key is derived given the same passphrase and kdf info
+        let recovered =
+            StoreCipher::new_with_kdf_info(b"my-passphrase", cipher.kdf_info()).unwrap();
+        let decrypted: Vec<u8> = recovered.decrypt_data(&encrypted).unwrap();
+        assert_eq!(data, decrypted);
+    }
+
+    #[test]
+    fn decryption_fails_with_invalid_passphrase() {
+        let mut rng = thread_rng();
+        let cipher = StoreCipher::new_with_kdf_info(b"my-passphrase", weak_kdf_info()).unwrap();
+        let encrypted = cipher.encrypt_data_ref(b"foomp", &mut rng).unwrap();
+
+        let other = StoreCipher::new_with_kdf_info(b"other-passphrase", weak_kdf_info()).unwrap();
+        assert!(matches!(
+            other.decrypt_data_unchecked(&encrypted),
+            Err(StoreCipherError::DecryptionFailure)
+        ));
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let mut rng = thread_rng();
+        let cipher = StoreCipher::new_with_kdf_info(b"my-passphrase", weak_kdf_info()).unwrap();
+        let mut encrypted = cipher.encrypt_data_ref(b"foomp", &mut rng).unwrap();
+        encrypted.ciphertext[0] ^= 1;
+
+        assert!(cipher.decrypt_data_unchecked(&encrypted).is_err());
+    }
+
+    #[test]
+    fn fresh_ciphers_use_distinct_salts() {
+        let mut rng = thread_rng();
+        let first = KdfInfo::new_with_default_params(&mut rng);
+        let second = KdfInfo::new_with_default_params(&mut rng);
+        assert_ne!(first, second);
+    }
+}
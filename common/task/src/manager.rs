@@ -163,9 +163,13 @@ impl TaskManager {
             drop(notify_rx);
         }
 
-        // in wasm we'll never get our shutdown anyway...
+        // there are no signals in the browser, so it's up to the caller to decide
+        // how long it's willing to wait
         #[cfg(target_arch = "wasm32")]
-        futures::future::pending::<()>().await;
+        {
+            self.notify_tx.closed().await;
+            log::info!("All registered tasks succesfully shutdown");
+        }
 
         #[cfg(not(target_arch = "wasm32"))]
         tokio::select! {
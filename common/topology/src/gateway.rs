@@ -12,6 +12,9 @@ use std::io;
 use std::net::SocketAddr;
 use thiserror::Error;
 
+/// Minimum version of the gateway that is capable of receiving packets of all extended sizes.
+pub const EXTENDED_PACKETS_MIN_VERSION: &str = "1.1.0";
+
 #[derive(Error, Debug)]
 pub enum GatewayConversionError {
     #[error("gateway identity key was malformed - {0}")]
@@ -53,6 +56,18 @@ impl Node {
     pub fn clients_address(&self) -> String {
         format!("ws://{}:{}", self.host, self.clients_port)
     }
+
+    /// Checks whether this gateway is recent enough to be able to receive packets
+    /// of all extended sizes.
+    pub fn supports_extended_packets(&self) -> bool {
+        match (
+            version_checker::parse_version(&self.version),
+            version_checker::parse_version(EXTENDED_PACKETS_MIN_VERSION),
+        ) {
+            (Ok(version), Ok(min_version)) => version >= min_version,
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for Node {
@@ -4,7 +4,7 @@
 use crate::filter::VersionFilterable;
 use log::warn;
 use mixnet_contract_common::mixnode::MixNodeDetails;
-use mixnet_contract_common::GatewayBond;
+use mixnet_contract_common::{GatewayBond, MixNodeBond};
 use nymsphinx_addressing::nodes::NodeIdentity;
 use nymsphinx_types::Node as SphinxNode;
 use rand::Rng;
@@ -20,6 +20,8 @@ pub mod filter;
 pub mod gateway;
 pub mod mix;
 
+pub use mixnet_contract_common::MixId;
+
 #[derive(Debug, Clone, Error)]
 pub enum NymTopologyError {
     #[error("The provided network topology is empty - there are no mixnodes and no gateways on it - the network request(s) probably failed")]
@@ -85,11 +87,19 @@ pub type MixLayer = u8;
 pub struct NymTopology {
     mixes: HashMap<MixLayer, Vec<mix::Node>>,
     gateways: Vec<gateway::Node>,
+
+    // relative weights used when choosing mixnodes for routes. Nodes without an explicit entry
+    // have the weight of 1.0 and if it's empty, the nodes are chosen uniformly.
+    mix_weights: HashMap<MixId, f64>,
 }
 
 impl NymTopology {
     pub fn new(mixes: HashMap<MixLayer, Vec<mix::Node>>, gateways: Vec<gateway::Node>) -> Self {
-        NymTopology { mixes, gateways }
+        NymTopology {
+            mixes,
+            gateways,
+            mix_weights: HashMap::new(),
+        }
     }
 
     pub fn mixes(&self) -> &HashMap<MixLayer, Vec<mix::Node>> {
@@ -118,7 +128,7 @@ impl NymTopology {
         &self.gateways
     }
 
-    fn get_gateway(&self, gateway_identity: &NodeIdentity) -> Option<&gateway::Node> {
+    pub fn get_gateway(&self, gateway_identity: &NodeIdentity) -> Option<&gateway::Node> {
         self.gateways
             .iter()
             .find(|gateway| gateway.identity() == gateway_identity)
@@ -132,8 +142,42 @@ impl NymTopology {
         self.gateways = gateways
     }
 
+    /// Sets the relative weights of mixnodes that are going to be used for choosing them for
+    /// packet routes. Nodes with weight of 0 are never chosen, unless every node on given layer
+    /// has such weight. Nodes not present in the map are assigned the weight of 1.0.
+    pub fn set_mix_weights(&mut self, mix_weights: HashMap<MixId, f64>) {
+        self.mix_weights = mix_weights
+    }
+
+    pub fn mix_weight(&self, mix_id: MixId) -> f64 {
+        self.mix_weights.get(&mix_id).copied().unwrap_or(1.0)
+    }
+
+    fn choose_layer_mix<'a, R>(
+        &self,
+        rng: &mut R,
+        layer_mixes: &'a [mix::Node],
+    ) -> Option<&'a mix::Node>
+    where
+        R: Rng + ?Sized,
+    {
+        use rand::seq::SliceRandom;
+
+        if self.mix_weights.is_empty() {
+            return layer_mixes.choose(rng);
+        }
+
+        // if all nodes on the layer got excluded, fallback to choosing between them uniformly,
+        // it's better to use a potentially unreliable route than not being able to send anything
+        layer_mixes
+            .choose_weighted(rng, |node| self.mix_weight(node.mix_id))
+            .ok()
+            .or_else(|| layer_mixes.choose(rng))
+    }
+
     /// Returns a vec of size of `num_mix_hops` of mixnodes, such that each subsequent node is on
-    /// next layer, starting from layer 1
+    /// next layer, starting from layer 1. The nodes are chosen according to their weights
+    /// (if any were set).
     pub fn random_mix_route<R>(
         &self,
         rng: &mut R,
@@ -143,8 +187,6 @@ impl NymTopology {
         // I don't think there's a need for this RNG to be crypto-secure
         R: Rng + ?Sized,
     {
-        use rand::seq::SliceRandom;
-
         if self.mixes.len() < num_mix_hops as usize {
             return Err(NymTopologyError::InvalidNumberOfHopsError {
                 available: self.mixes.len(),
@@ -163,8 +205,8 @@ impl NymTopology {
 
             // choose a random mix from the above list
             // this can return a 'None' only if slice is empty
-            let random_mix = layer_mixes
-                .choose(rng)
+            let random_mix = self
+                .choose_layer_mix(rng, layer_mixes)
                 .ok_or(NymTopologyError::EmptyMixLayer { layer })?;
             route.push(random_mix.into());
         }
@@ -197,6 +239,75 @@ impl NymTopology {
             .collect())
     }
 
+    /// Inserts the mixnode into the specified layer, replacing any node with the same `mix_id`
+    /// that might have already existed in the topology (regardless of its layer).
+    pub fn insert_mixnode(&mut self, layer: MixLayer, mixnode: mix::Node) {
+        self.remove_mixnode(mixnode.mix_id);
+        self.mixes.entry(layer).or_default().push(mixnode)
+    }
+
+    /// Converts the mixnode details into a topology node and inserts it into the topology.
+    /// Nodes on invalid layers and malformed nodes are ignored.
+    pub fn insert_detailed_mixnode(&mut self, details: MixNodeDetails) {
+        self.insert_mixnode_bond(details.bond_information)
+    }
+
+    /// Converts the mixnode bond into a topology node and inserts it into the topology.
+    /// Nodes on invalid layers and malformed nodes are ignored.
+    pub fn insert_mixnode_bond(&mut self, bond: MixNodeBond) {
+        let layer = bond.layer as MixLayer;
+        if layer == 0 || layer > 3 {
+            warn!(
+                "{} says it's on invalid layer {}!",
+                bond.mix_node.identity_key, layer
+            );
+            return;
+        }
+        let mix_id = bond.mix_id;
+        let mix_identity = bond.mix_node.identity_key.clone();
+
+        match bond.try_into() {
+            Ok(mix) => self.insert_mixnode(layer, mix),
+            Err(err) => warn!("Mix {} / {} is malformed - {err}", mix_id, mix_identity),
+        }
+    }
+
+    /// Removes the mixnode with the specified `mix_id` from the topology, if it existed.
+    pub fn remove_mixnode(&mut self, mix_id: MixId) -> Option<mix::Node> {
+        for layer_mixes in self.mixes.values_mut() {
+            if let Some(index) = layer_mixes.iter().position(|node| node.mix_id == mix_id) {
+                return Some(layer_mixes.remove(index));
+            }
+        }
+        None
+    }
+
+    /// Inserts the gateway into the topology, replacing any gateway with the same identity
+    /// that might have already existed.
+    pub fn insert_gateway(&mut self, gateway: gateway::Node) {
+        self.remove_gateway(gateway.identity());
+        self.gateways.push(gateway)
+    }
+
+    /// Converts the gateway bond into a topology node and inserts it into the topology.
+    /// Malformed gateways are ignored.
+    pub fn insert_gateway_bond(&mut self, bond: GatewayBond) {
+        let gate_id = bond.gateway.identity_key.clone();
+        match bond.try_into() {
+            Ok(gate) => self.insert_gateway(gate),
+            Err(err) => warn!("Gateway {} is malformed - {err}", gate_id),
+        }
+    }
+
+    /// Removes the gateway with the specified identity from the topology, if it existed.
+    pub fn remove_gateway(&mut self, gateway_identity: &NodeIdentity) -> Option<gateway::Node> {
+        let index = self
+            .gateways
+            .iter()
+            .position(|gateway| gateway.identity() == gateway_identity)?;
+        Some(self.gateways.remove(index))
+    }
+
     /// Overwrites the existing nodes in the specified layer
     pub fn set_mixes_in_layer(&mut self, layer: u8, mixes: Vec<mix::Node>) {
         self.mixes.insert(layer, mixes);
@@ -248,6 +359,7 @@ impl NymTopology {
         NymTopology {
             mixes: self.mixes.filter_by_version(expected_mix_version),
             gateways: self.gateways.clone(),
+            mix_weights: self.mix_weights.clone(),
         }
     }
 }
@@ -256,45 +368,14 @@ pub fn nym_topology_from_detailed(
     mix_details: Vec<MixNodeDetails>,
     gateway_bonds: Vec<GatewayBond>,
 ) -> NymTopology {
-    let mut mixes = HashMap::new();
-    for bond in mix_details
-        .into_iter()
-        .map(|details| details.bond_information)
-    {
-        let layer = bond.layer as MixLayer;
-        if layer == 0 || layer > 3 {
-            warn!(
-                "{} says it's on invalid layer {}!",
-                bond.mix_node.identity_key, layer
-            );
-            continue;
-        }
-        let mix_id = bond.mix_id;
-        let mix_identity = bond.mix_node.identity_key.clone();
-
-        let layer_entry = mixes.entry(layer).or_insert_with(Vec::new);
-        match bond.try_into() {
-            Ok(mix) => layer_entry.push(mix),
-            Err(err) => {
-                warn!("Mix {} / {} is malformed - {err}", mix_id, mix_identity);
-                continue;
-            }
-        }
+    let mut topology = NymTopology::new(HashMap::new(), Vec::with_capacity(gateway_bonds.len()));
+    for details in mix_details {
+        topology.insert_detailed_mixnode(details)
     }
-
-    let mut gateways = Vec::with_capacity(gateway_bonds.len());
-    for bond in gateway_bonds.into_iter() {
-        let gate_id = bond.gateway.identity_key.clone();
-        match bond.try_into() {
-            Ok(gate) => gateways.push(gate),
-            Err(err) => {
-                warn!("Gateway {} is malformed - {err}", gate_id);
-                continue;
-            }
-        }
+    for bond in gateway_bonds {
+        topology.insert_gateway_bond(bond)
     }
-
-    NymTopology::new(mixes, gateways)
+    topology
 }
 
 #[cfg(test)]
@@ -359,3 +440,138 @@ mod converting_mixes_to_vec {
         }
     }
 }
+
+#[cfg(test)]
+mod choosing_weighted_routes {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::Layer;
+    use rand::rngs::OsRng;
+
+    fn mix_node(mix_id: MixId, layer: Layer) -> mix::Node {
+        mix::Node {
+            mix_id,
+            owner: "N/A".to_string(),
+            host: "3.3.3.3".parse().unwrap(),
+            mix_host: "3.3.3.3:1789".parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(
+                "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
+            )
+            .unwrap(),
+            layer,
+            version: "0.x.0".to_string(),
+        }
+    }
+
+    fn test_topology() -> NymTopology {
+        let mut mixes = HashMap::new();
+        mixes.insert(1, vec![mix_node(1, Layer::One), mix_node(2, Layer::One)]);
+        mixes.insert(2, vec![mix_node(3, Layer::Two), mix_node(4, Layer::Two)]);
+        mixes.insert(
+            3,
+            vec![mix_node(5, Layer::Three), mix_node(6, Layer::Three)],
+        );
+        NymTopology::new(mixes, vec![])
+    }
+
+    #[test]
+    fn nodes_with_zero_weight_are_never_chosen() {
+        let mut topology = test_topology();
+        topology.set_mix_weights([(1, 0.0), (4, 0.0), (5, 0.0)].into_iter().collect());
+
+        let mut rng = OsRng;
+        for _ in 0..50 {
+            let chosen: Vec<_> = (1..=3)
+                .map(|layer| {
+                    topology
+                        .choose_layer_mix(&mut rng, &topology.mixes[&layer])
+                        .unwrap()
+                        .mix_id
+                })
+                .collect();
+            assert_eq!(chosen, vec![2, 3, 6]);
+        }
+
+        // and the actual routes can still be constructed
+        assert_eq!(topology.random_mix_route(&mut rng, 3).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn excluded_layer_falls_back_to_uniform_choice() {
+        let mut topology = test_topology();
+        topology.set_mix_weights([(1, 0.0), (2, 0.0)].into_iter().collect());
+
+        let mut rng = OsRng;
+        let chosen = topology
+            .choose_layer_mix(&mut rng, &topology.mixes[&1])
+            .unwrap();
+        assert!([1, 2].contains(&chosen.mix_id));
+    }
+
+    #[test]
+    fn weights_are_preserved_when_filtering_versions() {
+        let mut topology = test_topology();
+        topology.set_mix_weights([(3, 0.25)].into_iter().collect());
+
+        let filtered = topology.filter_node_versions("0.x.0");
+        assert_eq!(filtered.mix_weight(3), 0.25);
+        assert_eq!(filtered.mix_weight(1), 1.0);
+    }
+}
+
+#[cfg(test)]
+mod updating_topology {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::Layer;
+
+    fn mix_node(mix_id: MixId, host: &str) -> mix::Node {
+        mix::Node {
+            mix_id,
+            owner: "N/A".to_string(),
+            host: host.parse().unwrap(),
+            mix_host: format!("{host}:1789").parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(
+                "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
+            )
+            .unwrap(),
+            layer: Layer::One,
+            version: "0.x.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn inserting_mixnode_replaces_existing_entry() {
+        let mut topology = NymTopology::new(HashMap::new(), vec![]);
+        topology.insert_mixnode(1, mix_node(1, "1.1.1.1"));
+        topology.insert_mixnode(1, mix_node(2, "2.2.2.2"));
+
+        // the node got moved to a different layer and changed its host
+        topology.insert_mixnode(2, mix_node(1, "3.3.3.3"));
+
+        assert_eq!(topology.num_mixnodes(), 2);
+        assert_eq!(topology.mixes()[&1].len(), 1);
+        assert_eq!(topology.mixes()[&2][0].mix_id, 1);
+        assert_eq!(topology.mixes()[&2][0].host.to_string(), "3.3.3.3");
+    }
+
+    #[test]
+    fn removing_mixnodes() {
+        let mut topology = NymTopology::new(HashMap::new(), vec![]);
+        topology.insert_mixnode(1, mix_node(1, "1.1.1.1"));
+        topology.insert_mixnode(2, mix_node(2, "2.2.2.2"));
+
+        assert_eq!(topology.remove_mixnode(2).unwrap().mix_id, 2);
+        assert!(topology.remove_mixnode(2).is_none());
+        assert!(topology.remove_mixnode(42).is_none());
+        assert_eq!(topology.num_mixnodes(), 1);
+    }
+}
@@ -5,11 +5,11 @@ use crate::currency::{DecCoin, RegisteredCoins};
 use crate::error::TypesError;
 use crate::mixnode::MixNodeCostParams;
 use mixnet_contract_common::{
-    BlockHeight, EpochEventId, IntervalEventId, IntervalRewardingParamsUpdate, MixId,
+    BlockHeight, EpochEventId, IdentityKey, IntervalEventId, IntervalRewardingParamsUpdate, MixId,
     PendingEpochEvent as MixnetContractPendingEpochEvent,
     PendingEpochEventKind as MixnetContractPendingEpochEventKind,
     PendingIntervalEvent as MixnetContractPendingIntervalEvent,
-    PendingIntervalEventKind as MixnetContractPendingIntervalEventKind,
+    PendingIntervalEventKind as MixnetContractPendingIntervalEventKind, Percent,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -57,6 +57,13 @@ pub enum PendingEpochEventData {
         mix_id: MixId,
         proxy: Option<String>,
     },
+    Redelegate {
+        owner: String,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: DecCoin,
+        proxy: Option<String>,
+    },
     PledgeMore {
         mix_id: MixId,
         amount: DecCoin,
@@ -64,9 +71,31 @@ pub enum PendingEpochEventData {
     UnbondMixnode {
         mix_id: MixId,
     },
+    SlashOperatorPledge {
+        mix_id: MixId,
+        #[cfg_attr(feature = "generate-ts", ts(type = "string"))]
+        fraction: Percent,
+    },
     UpdateActiveSetSize {
         new_size: u32,
     },
+    PledgeMoreGateway {
+        identity: IdentityKey,
+        amount: DecCoin,
+    },
+    DecreaseGatewayPledge {
+        identity: IdentityKey,
+        decrease_by: DecCoin,
+    },
+    DelegateToGateway {
+        owner: String,
+        gateway_identity: IdentityKey,
+        amount: DecCoin,
+    },
+    UndelegateFromGateway {
+        owner: String,
+        gateway_identity: IdentityKey,
+    },
 }
 
 impl PendingEpochEventData {
@@ -95,6 +124,19 @@ impl PendingEpochEventData {
                 mix_id,
                 proxy: proxy.map(|p| p.into_string()),
             }),
+            MixnetContractPendingEpochEventKind::Redelegate {
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+                proxy,
+            } => Ok(PendingEpochEventData::Redelegate {
+                owner: owner.into_string(),
+                from_mix_id,
+                to_mix_id,
+                amount: reg.attempt_convert_to_display_dec_coin(amount.into())?,
+                proxy: proxy.map(|p| p.into_string()),
+            }),
             MixnetContractPendingEpochEventKind::PledgeMore { mix_id, amount } => {
                 Ok(PendingEpochEventData::PledgeMore {
                     mix_id,
@@ -104,9 +146,41 @@ impl PendingEpochEventData {
             MixnetContractPendingEpochEventKind::UnbondMixnode { mix_id } => {
                 Ok(PendingEpochEventData::UnbondMixnode { mix_id })
             }
+            MixnetContractPendingEpochEventKind::SlashOperatorPledge { mix_id, fraction } => {
+                Ok(PendingEpochEventData::SlashOperatorPledge { mix_id, fraction })
+            }
             MixnetContractPendingEpochEventKind::UpdateActiveSetSize { new_size } => {
                 Ok(PendingEpochEventData::UpdateActiveSetSize { new_size })
             }
+            MixnetContractPendingEpochEventKind::PledgeMoreGateway { identity, amount } => {
+                Ok(PendingEpochEventData::PledgeMoreGateway {
+                    identity,
+                    amount: reg.attempt_convert_to_display_dec_coin(amount.into())?,
+                })
+            }
+            MixnetContractPendingEpochEventKind::DecreaseGatewayPledge {
+                identity,
+                decrease_by,
+            } => Ok(PendingEpochEventData::DecreaseGatewayPledge {
+                identity,
+                decrease_by: reg.attempt_convert_to_display_dec_coin(decrease_by.into())?,
+            }),
+            MixnetContractPendingEpochEventKind::DelegateToGateway {
+                owner,
+                gateway_identity,
+                amount,
+            } => Ok(PendingEpochEventData::DelegateToGateway {
+                owner: owner.into_string(),
+                gateway_identity,
+                amount: reg.attempt_convert_to_display_dec_coin(amount.into())?,
+            }),
+            MixnetContractPendingEpochEventKind::UndelegateFromGateway {
+                owner,
+                gateway_identity,
+            } => Ok(PendingEpochEventData::UndelegateFromGateway {
+                owner: owner.into_string(),
+                gateway_identity,
+            }),
         }
     }
 }
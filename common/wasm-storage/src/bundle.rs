@@ -0,0 +1,192 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Passphrase-protected export and import of the entire client storage,
+//! so that the same identity could be moved between browsers or backed up.
+
+use crate::{ensure_known_store, stores, BrowserStorage, StorageError};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use store_cipher::{EncryptedData, KdfInfo, StoreCipher};
+
+pub const CURRENT_BUNDLE_VERSION: u8 = 1;
+
+// store name => (key => value)
+type BundleContent = BTreeMap<String, BTreeMap<String, Vec<u8>>>;
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedBundle {
+    version: u8,
+    kdf_info: KdfInfo,
+    data: EncryptedData,
+}
+
+/// Exports content of all the stores and encrypts it with a key derived from the provided passphrase.
+pub async fn export_encrypted_bundle<S, R>(
+    storage: &S,
+    passphrase: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, StorageError>
+where
+    S: BrowserStorage + ?Sized,
+    R: RngCore + CryptoRng,
+{
+    let mut content = BundleContent::new();
+    for store in stores::ALL {
+        let mut store_content = BTreeMap::new();
+        for key in storage.keys(store).await? {
+            if let Some(value) = storage.read(store, &key).await? {
+                store_content.insert(key, value);
+            }
+        }
+        content.insert(store.to_string(), store_content);
+    }
+
+    let cipher = StoreCipher::new(passphrase, rng)?;
+    let bundle = EncryptedBundle {
+        version: CURRENT_BUNDLE_VERSION,
+        kdf_info: cipher.kdf_info(),
+        data: cipher.encrypt_data(&content, rng)?,
+    };
+
+    Ok(serde_json::to_vec(&bundle)?)
+}
+
+/// Decrypts the bundle created with [`export_encrypted_bundle`] and replaces the content
+/// of all the stores with the imported data.
+pub async fn import_encrypted_bundle<S>(
+    storage: &S,
+    bundle: &[u8],
+    passphrase: &[u8],
+) -> Result<(), StorageError>
+where
+    S: BrowserStorage + ?Sized,
+{
+    let bundle: EncryptedBundle = serde_json::from_slice(bundle)?;
+    if bundle.version > CURRENT_BUNDLE_VERSION {
+        return Err(StorageError::UnsupportedBundleVersion {
+            received: bundle.version,
+            supported: CURRENT_BUNDLE_VERSION,
+        });
+    }
+
+    let cipher = StoreCipher::new_with_kdf_info(passphrase, bundle.kdf_info)?;
+    let content: BundleContent = cipher.decrypt_data(&bundle.data)?;
+
+    // make sure the bundle is fully valid before we start overwriting anything
+    for store in content.keys() {
+        ensure_known_store(store)?;
+    }
+
+    for (store, store_content) in content {
+        storage.clear(&store).await?;
+        for (key, value) in store_content {
+            storage.write(&store, &key, &value).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryStorage;
+    use rand::thread_rng;
+
+    async fn populated_storage() -> InMemoryStorage {
+        let storage = InMemoryStorage::new();
+        storage
+            .write(stores::KEYS, "identity", &[1, 2, 3])
+            .await
+            .unwrap();
+        storage
+            .write(stores::KEYS, "gateway_shared_key", &[4, 5, 6])
+            .await
+            .unwrap();
+        storage
+            .write(stores::REPLY_STORAGE, "reply_surbs", &[7; 100])
+            .await
+            .unwrap();
+        storage
+            .write(stores::CREDENTIALS, "1", &[8, 9])
+            .await
+            .unwrap();
+        storage
+    }
+
+    #[tokio::test]
+    async fn bundle_round_trips() {
+        let mut rng = thread_rng();
+        let storage = populated_storage().await;
+        let bundle = export_encrypted_bundle(&storage, b"passphrase", &mut rng)
+            .await
+            .unwrap();
+
+        let fresh = InMemoryStorage::new();
+        // this entry should get removed during the import
+        fresh.write(stores::CREDENTIALS, "2", &[42]).await.unwrap();
+        import_encrypted_bundle(&fresh, &bundle, b"passphrase")
+            .await
+            .unwrap();
+
+        for store in stores::ALL {
+            let keys = storage.keys(store).await.unwrap();
+            assert_eq!(keys, fresh.keys(store).await.unwrap());
+            for key in keys {
+                assert_eq!(
+                    storage.read(store, &key).await.unwrap(),
+                    fresh.read(store, &key).await.unwrap()
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn bundle_does_not_leak_plaintext() {
+        let mut rng = thread_rng();
+        let storage = InMemoryStorage::new();
+        storage
+            .write(stores::KEYS, "identity", b"super-secret-key")
+            .await
+            .unwrap();
+
+        let bundle = export_encrypted_bundle(&storage, b"passphrase", &mut rng)
+            .await
+            .unwrap();
+        let bundle_str = String::from_utf8_lossy(&bundle);
+        assert!(!bundle_str.contains("identity"));
+        assert!(!bundle_str.contains(stores::KEYS));
+    }
+
+    #[tokio::test]
+    async fn import_with_invalid_passphrase_leaves_storage_untouched() {
+        let mut rng = thread_rng();
+        let storage = populated_storage().await;
+        let bundle = export_encrypted_bundle(&storage, b"passphrase", &mut rng)
+            .await
+            .unwrap();
+
+        let other = InMemoryStorage::new();
+        other.write(stores::KEYS, "identity", &[42]).await.unwrap();
+        assert!(
+            import_encrypted_bundle(&other, &bundle, b"wrong-passphrase")
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            other.read(stores::KEYS, "identity").await.unwrap(),
+            Some(vec![42])
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_bundle_is_rejected() {
+        let storage = InMemoryStorage::new();
+        assert!(matches!(
+            import_encrypted_bundle(&storage, b"definitely not a bundle", b"passphrase").await,
+            Err(StorageError::MalformedBundle { .. })
+        ));
+    }
+}
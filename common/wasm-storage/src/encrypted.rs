@@ -0,0 +1,156 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encryption at rest of the values kept in the browser storage with a key derived out of
+//! a user-provided passphrase.
+
+use crate::{stores, BrowserStorage, StorageError};
+use rand::{CryptoRng, RngCore};
+use store_cipher::{EncryptedData, KdfInfo, StoreCipher};
+
+// the parameters used for deriving the encryption key are not secret,
+// so they're kept in plaintext alongside the data they protect
+pub const STORAGE_KDF_INFO_KEY: &str = "storage_kdf_info";
+
+/// Derives the cipher used for encrypting the stored values out of the provided passphrase.
+/// The key derivation parameters are generated on the first use and persisted in the storage,
+/// so that the same passphrase would always result in the same key.
+pub async fn storage_cipher<S, R>(
+    storage: &S,
+    passphrase: &[u8],
+    rng: &mut R,
+) -> Result<StoreCipher, StorageError>
+where
+    S: BrowserStorage + ?Sized,
+    R: RngCore + CryptoRng,
+{
+    match storage.read(stores::KEYS, STORAGE_KDF_INFO_KEY).await? {
+        Some(raw) => {
+            let kdf_info: KdfInfo = serde_json::from_slice(&raw).map_err(|err| {
+                StorageError::malformed_data(stores::KEYS, STORAGE_KDF_INFO_KEY, err.to_string())
+            })?;
+            Ok(StoreCipher::new_with_kdf_info(passphrase, kdf_info)?)
+        }
+        None => {
+            let cipher = StoreCipher::new(passphrase, rng)?;
+            let raw = serde_json::to_vec(&cipher.kdf_info())?;
+            storage
+                .write(stores::KEYS, STORAGE_KDF_INFO_KEY, &raw)
+                .await?;
+            Ok(cipher)
+        }
+    }
+}
+
+/// Retrieves and decrypts the value stored under the provided key, if it exists.
+pub async fn read_encrypted<S>(
+    storage: &S,
+    cipher: &StoreCipher,
+    store: &str,
+    key: &str,
+) -> Result<Option<Vec<u8>>, StorageError>
+where
+    S: BrowserStorage + ?Sized,
+{
+    let Some(raw) = storage.read(store, key).await? else {
+        return Ok(None);
+    };
+    let encrypted: EncryptedData = serde_json::from_slice(&raw)
+        .map_err(|err| StorageError::malformed_data(store, key, err.to_string()))?;
+    Ok(Some(cipher.decrypt_data_unchecked(&encrypted)?))
+}
+
+/// Encrypts the value and stores it under the provided key, overwriting any existing entry.
+pub async fn write_encrypted<S, R>(
+    storage: &S,
+    cipher: &StoreCipher,
+    store: &str,
+    key: &str,
+    value: &[u8],
+    rng: &mut R,
+) -> Result<(), StorageError>
+where
+    S: BrowserStorage + ?Sized,
+    R: RngCore + CryptoRng,
+{
+    let encrypted = cipher.encrypt_data_ref(value, rng)?;
+    let raw = serde_json::to_vec(&encrypted)?;
+    storage.write(store, key, &raw).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryStorage;
+    use rand::thread_rng;
+
+    #[tokio::test]
+    async fn values_are_not_stored_in_plaintext() {
+        let mut rng = thread_rng();
+        let storage = InMemoryStorage::new();
+        let cipher = storage_cipher(&storage, b"passphrase", &mut rng)
+            .await
+            .unwrap();
+
+        let secret = b"super-secret-key";
+        write_encrypted(
+            &storage,
+            &cipher,
+            stores::KEYS,
+            "identity",
+            secret,
+            &mut rng,
+        )
+        .await
+        .unwrap();
+
+        let raw = storage
+            .read(stores::KEYS, "identity")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!raw.windows(secret.len()).any(|window| window == secret));
+
+        let decrypted = read_encrypted(&storage, &cipher, stores::KEYS, "identity")
+            .await
+            .unwrap();
+        assert_eq!(decrypted, Some(secret.to_vec()));
+    }
+
+    #[tokio::test]
+    async fn the_same_key_is_rederived_from_the_stored_parameters() {
+        let mut rng = thread_rng();
+        let storage = InMemoryStorage::new();
+        let cipher = storage_cipher(&storage, b"passphrase", &mut rng)
+            .await
+            .unwrap();
+        write_encrypted(
+            &storage,
+            &cipher,
+            stores::KEYS,
+            "identity",
+            &[1, 2, 3],
+            &mut rng,
+        )
+        .await
+        .unwrap();
+
+        let recovered = storage_cipher(&storage, b"passphrase", &mut rng)
+            .await
+            .unwrap();
+        assert_eq!(
+            read_encrypted(&storage, &recovered, stores::KEYS, "identity")
+                .await
+                .unwrap(),
+            Some(vec![1, 2, 3])
+        );
+
+        let other = storage_cipher(&storage, b"wrong-passphrase", &mut rng)
+            .await
+            .unwrap();
+        assert!(matches!(
+            read_encrypted(&storage, &other, stores::KEYS, "identity").await,
+            Err(StorageError::CipherError(_))
+        ));
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use store_cipher::StoreCipherError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("IndexedDB is not available in this environment")]
+    IndexedDbUnavailable,
+
+    #[error("IndexedDB operation has failed - {details}")]
+    IndexedDbFailure { details: String },
+
+    #[error("'{name}' is not a known object store")]
+    UnknownStore { name: String },
+
+    #[error("'{name}' does not generate the keys of its entries")]
+    NotAutoIncremented { name: String },
+
+    #[error("the data stored under '{key}' in '{store}' is malformed - {details}")]
+    MalformedData {
+        store: String,
+        key: String,
+        details: String,
+    },
+
+    #[error("the storage bundle has version {received} while only up to {supported} is supported")]
+    UnsupportedBundleVersion { received: u8, supported: u8 },
+
+    #[error("failed to (de)serialize the storage bundle - {source}")]
+    MalformedBundle {
+        #[from]
+        source: serde_json::Error,
+    },
+
+    #[error(transparent)]
+    CipherError(#[from] StoreCipherError),
+}
+
+impl StorageError {
+    pub fn malformed_data<S: Into<String>>(store: &str, key: &str, details: S) -> Self {
+        StorageError::MalformedData {
+            store: store.to_string(),
+            key: key.to_string(),
+            details: details.into(),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl From<wasm_bindgen::JsValue> for StorageError {
+    fn from(value: wasm_bindgen::JsValue) -> Self {
+        StorageError::IndexedDbFailure {
+            details: format!("{value:?}"),
+        }
+    }
+}
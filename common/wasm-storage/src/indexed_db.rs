@@ -0,0 +1,184 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ensure_auto_incremented_store, ensure_known_store, stores, BrowserStorage, StorageError,
+};
+use async_trait::async_trait;
+use js_sys::{Array, Promise, Uint8Array};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Event, IdbDatabase, IdbFactory, IdbObjectStore, IdbObjectStoreParameters, IdbOpenDbRequest,
+    IdbRequest, IdbTransactionMode,
+};
+
+// bump it whenever new object store is introduced
+const DATABASE_VERSION: u32 = 1;
+
+/// Storage backed by the browser's IndexedDB.
+pub struct IndexedDbStorage {
+    db: IdbDatabase,
+}
+
+// SAFETY: wasm32-unknown-unknown is single-threaded so the underlying `JsValue` can't ever be
+// accessed from multiple threads. We need those bounds as the storage is shared with components
+// that are also compiled for native targets.
+unsafe impl Send for IndexedDbStorage {}
+unsafe impl Sync for IndexedDbStorage {}
+
+// waits for either `success` or `error` event of the request and returns its result
+async fn resolve_request(request: &IdbRequest) -> Result<JsValue, StorageError> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    let outcome = JsFuture::from(promise).await;
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+
+    if outcome.is_err() {
+        let details = match request.error() {
+            Ok(Some(err)) => format!("{}: {}", err.name(), err.message()),
+            _ => "unknown error".to_string(),
+        };
+        return Err(StorageError::IndexedDbFailure { details });
+    }
+
+    Ok(request.result()?)
+}
+
+// entries of the auto-incremented stores are keyed by the numbers generated by IndexedDB
+fn js_key(store: &str, key: &str) -> Result<JsValue, StorageError> {
+    if stores::AUTO_INCREMENTED.contains(&store) {
+        let id: u64 = key.parse().map_err(|_| {
+            StorageError::malformed_data(store, key, "the key is not a valid generated id")
+        })?;
+        Ok(JsValue::from_f64(id as f64))
+    } else {
+        Ok(key.into())
+    }
+}
+
+impl IndexedDbStorage {
+    /// Opens (or creates if it doesn't exist) the database with the provided name.
+    pub async fn open(name: &str) -> Result<Self, StorageError> {
+        // rather than going through `window`, we grab the global object directly
+        // so that the storage could also be used from within web workers
+        let factory: IdbFactory = js_sys::Reflect::get(&js_sys::global(), &"indexedDB".into())
+            .ok()
+            .and_then(|factory| factory.dyn_into().ok())
+            .ok_or(StorageError::IndexedDbUnavailable)?;
+
+        let open_request: IdbOpenDbRequest = factory.open_with_u32(name, DATABASE_VERSION)?;
+
+        let on_upgrade_needed = Closure::once(move |event: Event| {
+            let Some(db) = event
+                .target()
+                .and_then(|target| target.dyn_into::<IdbOpenDbRequest>().ok())
+                .and_then(|request| request.result().ok())
+                .and_then(|result| result.dyn_into::<IdbDatabase>().ok())
+            else {
+                return;
+            };
+
+            let existing = db.object_store_names();
+            for store in stores::ALL {
+                if !existing.contains(store) {
+                    // if this fails, it will be reported as an error of the open request itself
+                    if stores::AUTO_INCREMENTED.contains(&store) {
+                        let mut params = IdbObjectStoreParameters::new();
+                        params.auto_increment(true);
+                        let _ = db.create_object_store_with_optional_parameters(store, &params);
+                    } else {
+                        let _ = db.create_object_store(store);
+                    }
+                }
+            }
+        });
+        open_request.set_onupgradeneeded(Some(on_upgrade_needed.as_ref().unchecked_ref()));
+
+        let db = resolve_request(&open_request).await?;
+        open_request.set_onupgradeneeded(None);
+
+        Ok(IndexedDbStorage { db: db.dyn_into()? })
+    }
+
+    fn object_store(&self, store: &str, writable: bool) -> Result<IdbObjectStore, StorageError> {
+        ensure_known_store(store)?;
+        let mode = if writable {
+            IdbTransactionMode::Readwrite
+        } else {
+            IdbTransactionMode::Readonly
+        };
+
+        let transaction = self.db.transaction_with_str_and_mode(store, mode)?;
+        Ok(transaction.object_store(store)?)
+    }
+}
+
+#[async_trait(?Send)]
+impl BrowserStorage for IndexedDbStorage {
+    async fn read(&self, store: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        let request = self.object_store(store, false)?.get(&js_key(store, key)?)?;
+        let value = resolve_request(&request).await?;
+        if value.is_undefined() {
+            return Ok(None);
+        }
+
+        let bytes: Uint8Array = value.dyn_into().map_err(|_| {
+            StorageError::malformed_data(store, key, "the value is not a byte array")
+        })?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    async fn write(&self, store: &str, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        let value = Uint8Array::from(value);
+        let request = self
+            .object_store(store, true)?
+            .put_with_key(&value, &js_key(store, key)?)?;
+        resolve_request(&request).await.map(|_| ())
+    }
+
+    async fn insert(&self, store: &str, value: &[u8]) -> Result<u64, StorageError> {
+        ensure_auto_incremented_store(store)?;
+        let value = Uint8Array::from(value);
+        // the key generator is advanced within the same transaction as the write,
+        // so concurrent inserts (say, from another tab) can't ever end up with the same id
+        let request = self.object_store(store, true)?.add(&value)?;
+        let id = resolve_request(&request).await?;
+        id.as_f64()
+            .map(|id| id as u64)
+            .ok_or_else(|| StorageError::IndexedDbFailure {
+                details: format!("{id:?} is not a valid generated id"),
+            })
+    }
+
+    async fn remove(&self, store: &str, key: &str) -> Result<(), StorageError> {
+        let request = self
+            .object_store(store, true)?
+            .delete(&js_key(store, key)?)?;
+        resolve_request(&request).await.map(|_| ())
+    }
+
+    async fn keys(&self, store: &str) -> Result<Vec<String>, StorageError> {
+        let request = self.object_store(store, false)?.get_all_keys()?;
+        let keys: Array = resolve_request(&request).await?.dyn_into()?;
+
+        keys.iter()
+            .map(|key| {
+                key.as_string()
+                    .or_else(|| key.as_f64().map(|id| (id as u64).to_string()))
+                    .ok_or_else(|| StorageError::IndexedDbFailure {
+                        details: format!("{key:?} is not a valid key"),
+                    })
+            })
+            .collect()
+    }
+
+    async fn clear(&self, store: &str) -> Result<(), StorageError> {
+        let request = self.object_store(store, true)?.clear()?;
+        resolve_request(&request).await.map(|_| ())
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Persistent storage for the nym clients running inside a web browser.
+//!
+//! All the data is kept as raw bytes inside named object stores, so the consumers
+//! (such as the key manager or the reply storage backend) decide on their own encoding.
+//! Anything secret should go through [`write_encrypted`] so that it wouldn't be kept in plaintext.
+
+use async_trait::async_trait;
+
+pub use crate::bundle::{export_encrypted_bundle, import_encrypted_bundle};
+pub use crate::encrypted::{read_encrypted, storage_cipher, write_encrypted};
+pub use crate::error::StorageError;
+#[cfg(target_arch = "wasm32")]
+pub use crate::indexed_db::IndexedDbStorage;
+pub use crate::memory::InMemoryStorage;
+pub use store_cipher::{EncryptedData, StoreCipher};
+
+pub mod bundle;
+pub mod encrypted;
+pub mod error;
+#[cfg(target_arch = "wasm32")]
+pub mod indexed_db;
+pub mod memory;
+
+/// Names of all object stores used by the browser clients.
+pub mod stores {
+    /// Identity, encryption and ack keys alongside the gateway shared key.
+    pub const KEYS: &str = "keys";
+
+    /// Reply SURBs, reply keys and used sender tags.
+    pub const REPLY_STORAGE: &str = "reply_storage";
+
+    /// Coconut bandwidth credentials.
+    pub const CREDENTIALS: &str = "coconut_credentials";
+
+    pub const ALL: [&str; 3] = [KEYS, REPLY_STORAGE, CREDENTIALS];
+
+    /// Stores whose entries are keyed by ids generated by the storage itself (see [`BrowserStorage::insert`]).
+    ///
+    /// [`BrowserStorage::insert`]: crate::BrowserStorage::insert
+    pub const AUTO_INCREMENTED: [&str; 1] = [CREDENTIALS];
+}
+
+// note: futures returned by IndexedDB are not `Send`, but since wasm is single-threaded,
+// it doesn't really matter there
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait BrowserStorage: Send + Sync {
+    /// Retrieves the value stored under the provided key, if it exists.
+    async fn read(&self, store: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Stores the value under the provided key, overwriting any existing entry.
+    async fn write(&self, store: &str, key: &str, value: &[u8]) -> Result<(), StorageError>;
+
+    /// Stores the value under a fresh id generated by the storage and returns it.
+    /// Only available for the [`stores::AUTO_INCREMENTED`] stores. The ids are increasing
+    /// and they are never reused, even if the entries get removed.
+    async fn insert(&self, store: &str, value: &[u8]) -> Result<u64, StorageError>;
+
+    /// Removes the value stored under the provided key. It is not an error if it didn't exist.
+    async fn remove(&self, store: &str, key: &str) -> Result<(), StorageError>;
+
+    /// Gets all keys present in the specified store.
+    async fn keys(&self, store: &str) -> Result<Vec<String>, StorageError>;
+
+    /// Removes all entries from the specified store.
+    async fn clear(&self, store: &str) -> Result<(), StorageError>;
+}
+
+fn ensure_auto_incremented_store(store: &str) -> Result<(), StorageError> {
+    ensure_known_store(store)?;
+    if stores::AUTO_INCREMENTED.contains(&store) {
+        Ok(())
+    } else {
+        Err(StorageError::NotAutoIncremented {
+            name: store.to_string(),
+        })
+    }
+}
+
+fn ensure_known_store(store: &str) -> Result<(), StorageError> {
+    if stores::ALL.contains(&store) {
+        Ok(())
+    } else {
+        Err(StorageError::UnknownStore {
+            name: store.to_string(),
+        })
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{ensure_auto_incremented_store, ensure_known_store, BrowserStorage, StorageError};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+type StoreContent = BTreeMap<String, Vec<u8>>;
+
+/// Storage keeping everything in memory. It's used whenever IndexedDB is not available
+/// (for example in private browsing mode) and for testing.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryStorage {
+    inner: Arc<Mutex<HashMap<String, StoreContent>>>,
+
+    // mimics the IndexedDB key generators, which are not reset when the entries are removed
+    last_generated_ids: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl InMemoryStorage {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl BrowserStorage for InMemoryStorage {
+    async fn read(&self, store: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        ensure_known_store(store)?;
+        let guard = self.inner.lock().expect("storage mutex got poisoned");
+        Ok(guard
+            .get(store)
+            .and_then(|content| content.get(key))
+            .cloned())
+    }
+
+    async fn write(&self, store: &str, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        ensure_known_store(store)?;
+        let mut guard = self.inner.lock().expect("storage mutex got poisoned");
+        guard
+            .entry(store.to_string())
+            .or_default()
+            .insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+
+    async fn insert(&self, store: &str, value: &[u8]) -> Result<u64, StorageError> {
+        ensure_auto_incremented_store(store)?;
+        let mut guard = self.inner.lock().expect("storage mutex got poisoned");
+        let mut last_ids = self
+            .last_generated_ids
+            .lock()
+            .expect("storage mutex got poisoned");
+
+        let content = guard.entry(store.to_string()).or_default();
+        let last_id = last_ids.entry(store.to_string()).or_default();
+
+        // explicitly written numeric keys also advance the generator, as they do in IndexedDB
+        let highest_existing = content
+            .keys()
+            .filter_map(|key| key.parse::<u64>().ok())
+            .max()
+            .unwrap_or_default();
+        let id = (*last_id).max(highest_existing) + 1;
+        *last_id = id;
+
+        content.insert(id.to_string(), value.to_vec());
+        Ok(id)
+    }
+
+    async fn remove(&self, store: &str, key: &str) -> Result<(), StorageError> {
+        ensure_known_store(store)?;
+        let mut guard = self.inner.lock().expect("storage mutex got poisoned");
+        if let Some(content) = guard.get_mut(store) {
+            content.remove(key);
+        }
+        Ok(())
+    }
+
+    async fn keys(&self, store: &str) -> Result<Vec<String>, StorageError> {
+        ensure_known_store(store)?;
+        let guard = self.inner.lock().expect("storage mutex got poisoned");
+        Ok(guard
+            .get(store)
+            .map(|content| content.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn clear(&self, store: &str) -> Result<(), StorageError> {
+        ensure_known_store(store)?;
+        let mut guard = self.inner.lock().expect("storage mutex got poisoned");
+        guard.remove(store);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stores;
+
+    #[tokio::test]
+    async fn generated_ids_are_never_reused() {
+        let storage = InMemoryStorage::new();
+        assert_eq!(storage.insert(stores::CREDENTIALS, &[1]).await.unwrap(), 1);
+        assert_eq!(storage.insert(stores::CREDENTIALS, &[2]).await.unwrap(), 2);
+
+        storage.remove(stores::CREDENTIALS, "2").await.unwrap();
+        assert_eq!(storage.insert(stores::CREDENTIALS, &[3]).await.unwrap(), 3);
+
+        storage.clear(stores::CREDENTIALS).await.unwrap();
+        storage
+            .write(stores::CREDENTIALS, "42", &[42])
+            .await
+            .unwrap();
+        assert_eq!(storage.insert(stores::CREDENTIALS, &[4]).await.unwrap(), 43);
+    }
+
+    #[tokio::test]
+    async fn only_auto_incremented_stores_generate_ids() {
+        let storage = InMemoryStorage::new();
+        assert!(matches!(
+            storage.insert(stores::KEYS, &[1]).await,
+            Err(StorageError::NotAutoIncremented { .. })
+        ));
+    }
+}
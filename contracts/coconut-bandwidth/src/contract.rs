@@ -2,14 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
 };
 
 use coconut_bandwidth_contract_common::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 use crate::error::ContractError;
-use crate::queries::{query_all_spent_credentials_paged, query_spent_credential};
+use crate::queries::{
+    query_all_spent_credentials_paged, query_spent_credential, query_spent_credentials_after,
+};
 use crate::state::{Config, ADMIN, CONFIG};
+use crate::storage;
 use crate::transactions;
 
 /// Instantiate the contract.
@@ -53,6 +56,15 @@ pub fn execute(
             transactions::spend_credential(deps, env, info, data)
         }
         ExecuteMsg::ReleaseFunds { funds } => transactions::release_funds(deps, env, info, funds),
+        ExecuteMsg::PruneSpentCredentials { limit } => {
+            transactions::prune_spent_credentials(deps, env, info, limit)
+        }
+        ExecuteMsg::RefundCredential { data } => {
+            transactions::refund_credential(deps, env, info, data)
+        }
+        ExecuteMsg::RefundFunds { funds, recipient } => {
+            transactions::refund_funds(deps, env, info, funds, recipient)
+        }
     }
 }
 
@@ -62,6 +74,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetAllSpentCredentials { limit, start_after } => to_binary(
             &query_all_spent_credentials_paged(deps, start_after, limit)?,
         ),
+        QueryMsg::GetSpentCredentialsAfter { limit, start_after } => {
+            to_binary(&query_spent_credentials_after(deps, start_after, limit)?)
+        }
         QueryMsg::GetSpentCredential {
             blinded_serial_number,
         } => to_binary(&query_spent_credential(deps, blinded_serial_number)?),
@@ -69,7 +84,25 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 }
 
 #[entry_point]
-pub fn migrate(_deps: DepsMut<'_>, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut<'_>, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // re-save the spent credentials stored before the introduction of their expiry,
+    // so that they'd get included in the (new) expiry index and could be pruned,
+    // assigning spend ids to the ones that don't have them yet
+    let spent_credentials = storage::spent_credentials()
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (blinded_serial_number, spent_credential) in spent_credentials {
+        if spent_credential.spend_id().is_some() {
+            storage::spent_credentials().save(
+                deps.storage,
+                &blinded_serial_number,
+                &spent_credential,
+            )?;
+        } else {
+            storage::save_spent_credential(deps.storage, spent_credential)?;
+        }
+    }
+
     Ok(Default::default())
 }
 
@@ -29,6 +29,12 @@ pub enum ContractError {
     #[error("Credential already spent or in process of spending")]
     DuplicateBlindedSerialNumber,
 
+    #[error("Credential expired at epoch {expiry_epoch} and can no longer be redeemed")]
+    ExpiredCredential { expiry_epoch: u64 },
+
+    #[error("Credential expiring at epoch {expiry_epoch} can't be refunded at this point")]
+    NotRefundable { expiry_epoch: u64 },
+
     #[error(transparent)]
     Admin(#[from] AdminError),
 }
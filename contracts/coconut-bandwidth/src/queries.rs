@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use coconut_bandwidth_contract_common::spend_credential::{
-    PagedSpendCredentialResponse, SpendCredential, SpendCredentialResponse,
+    PagedSpendCredentialIdResponse, PagedSpendCredentialResponse, SpendCredential,
+    SpendCredentialResponse,
 };
 use cosmwasm_std::{Deps, Order, StdResult};
 use cw_storage_plus::Bound;
@@ -37,6 +38,38 @@ pub(crate) fn query_all_spent_credentials_paged(
     ))
 }
 
+pub(crate) fn query_spent_credentials_after(
+    deps: Deps<'_>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PagedSpendCredentialIdResponse> {
+    let limit = limit
+        .unwrap_or(SPEND_CREDENTIAL_PAGE_DEFAULT_LIMIT)
+        .min(SPEND_CREDENTIAL_PAGE_MAX_LIMIT) as usize;
+
+    let start = start_after.map(Bound::exclusive);
+
+    let spend_ids = storage::SPEND_IDS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let start_next_after = spend_ids.last().map(|(spend_id, _)| *spend_id);
+
+    let spend_credentials = spend_ids
+        .into_iter()
+        .map(|(_, blinded_serial_number)| {
+            storage::spent_credentials().load(deps.storage, &blinded_serial_number)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PagedSpendCredentialIdResponse::new(
+        spend_credentials,
+        limit,
+        start_next_after,
+    ))
+}
+
 pub(crate) fn query_spent_credential(
     deps: Deps<'_>,
     blinded_serial_number: String,
@@ -175,4 +208,43 @@ pub(crate) mod tests {
         // now we have 2 pages, with 2 results on the second page
         assert_eq!(2, page2.spend_credentials.len());
     }
+
+    #[test]
+    fn spent_credentials_are_retrieved_in_spend_order() {
+        let mut deps = init_contract();
+        let env = mock_env();
+        let info = mock_info("requester", &[]);
+        for serial_number in ["c", "a", "b"] {
+            let data = spend_credential_data_fixture(serial_number);
+            spend_credential(deps.as_mut(), env.clone(), info.clone(), data).unwrap();
+        }
+
+        let page1 = query_spent_credentials_after(deps.as_ref(), None, Some(2)).unwrap();
+        let serial_numbers = page1
+            .spend_credentials
+            .iter()
+            .map(|spent| spent.blinded_serial_number())
+            .collect::<Vec<_>>();
+        assert_eq!(serial_numbers, vec!["c", "a"]);
+        assert_eq!(page1.start_next_after, Some(2));
+
+        // credentials spent later on are picked up after the cursor, regardless of their serial number
+        let data = spend_credential_data_fixture("0");
+        spend_credential(deps.as_mut(), env, info, data).unwrap();
+
+        let page2 =
+            query_spent_credentials_after(deps.as_ref(), page1.start_next_after, Some(2)).unwrap();
+        let serial_numbers = page2
+            .spend_credentials
+            .iter()
+            .map(|spent| spent.blinded_serial_number())
+            .collect::<Vec<_>>();
+        assert_eq!(serial_numbers, vec!["b", "0"]);
+        assert_eq!(page2.start_next_after, Some(4));
+
+        let page3 =
+            query_spent_credentials_after(deps.as_ref(), page2.start_next_after, Some(2)).unwrap();
+        assert!(page3.spend_credentials.is_empty());
+        assert_eq!(page3.start_next_after, None);
+    }
 }
@@ -2,25 +2,40 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use coconut_bandwidth_contract_common::spend_credential::SpendCredential;
-use cw_storage_plus::{Index, IndexList, IndexedMap, UniqueIndex};
+use cosmwasm_std::{StdResult, Storage};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
 
 // storage prefixes
 const SPEND_CREDENTIAL_PK_NAMESPACE: &str = "sc";
 const SPEND_CREDENTIAL_BLINDED_SERIAL_NO_IDX_NAMESPACE: &str = "scn";
+const SPEND_CREDENTIAL_EXPIRY_IDX_NAMESPACE: &str = "sce";
+const SPEND_ID_COUNTER_NAMESPACE: &str = "sic";
+const SPEND_IDS_NAMESPACE: &str = "sid";
+
+// spend id => blinded serial number, in the order in which the credentials got spent
+pub(crate) const SPEND_IDS: Map<'_, u64, String> = Map::new(SPEND_IDS_NAMESPACE);
+pub(crate) const SPEND_ID_COUNTER: Item<'_, u64> = Item::new(SPEND_ID_COUNTER_NAMESPACE);
 
 // paged retrieval limits for all queries and transactions
 pub(crate) const SPEND_CREDENTIAL_PAGE_MAX_LIMIT: u32 = 75;
 pub(crate) const SPEND_CREDENTIAL_PAGE_DEFAULT_LIMIT: u32 = 50;
 
+// limits on the number of spent credentials removed in a single pruning transaction
+pub(crate) const SPEND_CREDENTIAL_PRUNE_MAX_LIMIT: u32 = 200;
+pub(crate) const SPEND_CREDENTIAL_PRUNE_DEFAULT_LIMIT: u32 = 100;
+
 pub(crate) struct SpendCredentialIndex<'a> {
     pub(crate) blinded_serial_number: UniqueIndex<'a, String, SpendCredential>,
+
+    pub(crate) expiry_epoch: MultiIndex<'a, u64, SpendCredential, String>,
 }
 
 // IndexList is just boilerplate code for fetching a struct's indexes
 // note that from my understanding this will be converted into a macro at some point in the future
 impl<'a> IndexList<SpendCredential> for SpendCredentialIndex<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<SpendCredential>> + '_> {
-        let v: Vec<&dyn Index<SpendCredential>> = vec![&self.blinded_serial_number];
+        let v: Vec<&dyn Index<SpendCredential>> =
+            vec![&self.blinded_serial_number, &self.expiry_epoch];
         Box::new(v.into_iter())
     }
 }
@@ -33,10 +48,36 @@ pub(crate) fn spent_credentials<'a>(
             |d| d.blinded_serial_number().to_string(),
             SPEND_CREDENTIAL_BLINDED_SERIAL_NO_IDX_NAMESPACE,
         ),
+        expiry_epoch: MultiIndex::new(
+            |d| d.expiry_epoch(),
+            SPEND_CREDENTIAL_PK_NAMESPACE,
+            SPEND_CREDENTIAL_EXPIRY_IDX_NAMESPACE,
+        ),
     };
     IndexedMap::new(SPEND_CREDENTIAL_PK_NAMESPACE, indexes)
 }
 
+pub(crate) fn next_spend_id(store: &mut dyn Storage) -> StdResult<u64> {
+    let id = SPEND_ID_COUNTER.may_load(store)?.unwrap_or_default() + 1;
+    SPEND_ID_COUNTER.save(store, &id)?;
+    Ok(id)
+}
+
+// saves the spent credential along with a newly assigned spend id
+pub(crate) fn save_spent_credential(
+    store: &mut dyn Storage,
+    spent_credential: SpendCredential,
+) -> StdResult<()> {
+    let spend_id = next_spend_id(store)?;
+    let blinded_serial_number = spent_credential.blinded_serial_number().to_string();
+    SPEND_IDS.save(store, spend_id, &blinded_serial_number)?;
+    spent_credentials().save(
+        store,
+        &blinded_serial_number,
+        &spent_credential.with_spend_id(spend_id),
+    )
+}
+
 // currently not used outside tests
 #[cfg(test)]
 mod tests {
@@ -89,6 +130,7 @@ mod tests {
             funds.clone(),
             blind_serial_number.to_string(),
             gateway_cosmos_address.clone(),
+            42,
         );
         spend_credential.mark_as_spent();
 
@@ -1,16 +1,25 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use coconut_bandwidth_contract_common::expiry::deposit_expiry_epoch;
+use coconut_bandwidth_contract_common::refund::RefundCredentialData;
 use coconut_bandwidth_contract_common::spend_credential::{SpendCredential, SpendCredentialData};
+use cosmwasm_std::testing::mock_env;
 use cosmwasm_std::{Addr, Coin};
 
 pub const TEST_MIX_DENOM: &str = "unym";
 
+/// Expiry epoch of credentials obtained for deposits made at the time of the mock environment.
+pub fn expiry_epoch_fixture() -> u64 {
+    deposit_expiry_epoch(mock_env().block.time.seconds())
+}
+
 pub fn spend_credential_fixture(blinded_serial_number: &str) -> SpendCredential {
     SpendCredential::new(
         Coin::new(100, TEST_MIX_DENOM),
         blinded_serial_number.to_string(),
         Addr::unchecked("gateway_owner_addr"),
+        expiry_epoch_fixture(),
     )
 }
 
@@ -19,5 +28,14 @@ pub fn spend_credential_data_fixture(blinded_serial_number: &str) -> SpendCreden
         Coin::new(100, TEST_MIX_DENOM),
         blinded_serial_number.to_string(),
         "gateway_owner_addr".to_string(),
+        expiry_epoch_fixture(),
+    )
+}
+
+pub fn refund_credential_data_fixture(blinded_serial_number: &str) -> RefundCredentialData {
+    RefundCredentialData::new(
+        Coin::new(100, TEST_MIX_DENOM),
+        blinded_serial_number.to_string(),
+        expiry_epoch_fixture(),
     )
 }
@@ -1,24 +1,31 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use coconut_bandwidth_contract_common::refund::{to_refund_cosmos_msg, RefundCredentialData};
 use coconut_bandwidth_contract_common::spend_credential::{
     to_cosmos_msg, SpendCredential, SpendCredentialData,
 };
-use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, Event, MessageInfo, Response};
+use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult};
 
 use crate::error::ContractError;
 use crate::state::{ADMIN, CONFIG};
-use crate::storage;
+use crate::storage::{
+    self, SPEND_CREDENTIAL_PRUNE_DEFAULT_LIMIT, SPEND_CREDENTIAL_PRUNE_MAX_LIMIT,
+};
 
 use coconut_bandwidth_contract_common::deposit::DepositData;
 use coconut_bandwidth_contract_common::events::{
-    DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_IDENTITY_KEY, DEPOSIT_INFO,
-    DEPOSIT_VALUE,
+    DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_EXPIRY_EPOCH, DEPOSIT_IDENTITY_KEY,
+    DEPOSIT_INFO, DEPOSIT_VALUE, PRUNED_COUNT, PRUNED_SPENT_CREDENTIALS_EVENT_TYPE,
+};
+use coconut_bandwidth_contract_common::expiry::{
+    deposit_expiry_epoch, effective_expiry_epoch, epoch_at, is_prunable, is_refundable,
+    redemption_deadline,
 };
 
 pub(crate) fn deposit_funds(
     deps: DepsMut<'_>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     data: DepositData,
 ) -> Result<Response, ContractError> {
@@ -34,11 +41,13 @@ pub(crate) fn deposit_funds(
     }
 
     let voucher_value = info.funds.last().unwrap();
+    let expiry_epoch = deposit_expiry_epoch(env.block.time.seconds());
     let event = Event::new(DEPOSITED_FUNDS_EVENT_TYPE)
         .add_attribute(DEPOSIT_VALUE, voucher_value.amount)
         .add_attribute(DEPOSIT_INFO, data.deposit_info())
         .add_attribute(DEPOSIT_IDENTITY_KEY, data.identity_key())
-        .add_attribute(DEPOSIT_ENCRYPTION_KEY, data.encryption_key());
+        .add_attribute(DEPOSIT_ENCRYPTION_KEY, data.encryption_key())
+        .add_attribute(DEPOSIT_EXPIRY_EPOCH, expiry_epoch.to_string());
 
     Ok(Response::new().add_event(event))
 }
@@ -53,19 +62,27 @@ pub(crate) fn spend_credential(
     if data.funds().denom != mix_denom {
         return Err(ContractError::WrongDenom { mix_denom });
     }
+    // the expiry is declared by the gateway, the nym-apis refuse to release the funds
+    // if it does not match the expiry the credential has been issued with
+    if epoch_at(env.block.time.seconds()) > redemption_deadline(data.expiry_epoch()) {
+        return Err(ContractError::ExpiredCredential {
+            expiry_epoch: data.expiry_epoch(),
+        });
+    }
     if storage::spent_credentials().has(deps.storage, data.blinded_serial_number()) {
         return Err(ContractError::DuplicateBlindedSerialNumber);
     }
     let cfg = CONFIG.load(deps.storage)?;
 
     let gateway_cosmos_address = deps.api.addr_validate(data.gateway_cosmos_address())?;
-    storage::spent_credentials().save(
+    // legacy credentials are remembered with their actual expiry, so that they would get pruned in order
+    storage::save_spent_credential(
         deps.storage,
-        data.blinded_serial_number(),
-        &SpendCredential::new(
+        SpendCredential::new(
             data.funds().to_owned(),
             data.blinded_serial_number().to_owned(),
             gateway_cosmos_address,
+            effective_expiry_epoch(data.expiry_epoch()),
         ),
     )?;
 
@@ -108,14 +125,134 @@ pub(crate) fn release_funds(
     Ok(response)
 }
 
+pub(crate) fn refund_credential(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    data: RefundCredentialData,
+) -> Result<Response, ContractError> {
+    let mix_denom = CONFIG.load(deps.storage)?.mix_denom;
+    if data.funds().denom != mix_denom {
+        return Err(ContractError::WrongDenom { mix_denom });
+    }
+    // as with spending, the nym-apis refuse to refund the funds if the declared expiry
+    // does not match the one the credential has been issued with
+    if !is_refundable(data.expiry_epoch(), epoch_at(env.block.time.seconds())) {
+        return Err(ContractError::NotRefundable {
+            expiry_epoch: data.expiry_epoch(),
+        });
+    }
+    // refunded credentials are put into the same set as the spent ones, so that a credential
+    // could only ever be either spent or refunded
+    if storage::spent_credentials().has(deps.storage, data.blinded_serial_number()) {
+        return Err(ContractError::DuplicateBlindedSerialNumber);
+    }
+    let cfg = CONFIG.load(deps.storage)?;
+
+    storage::save_spent_credential(
+        deps.storage,
+        SpendCredential::new(
+            data.funds().to_owned(),
+            data.blinded_serial_number().to_owned(),
+            info.sender.clone(),
+            effective_expiry_epoch(data.expiry_epoch()),
+        ),
+    )?;
+
+    let msg = to_refund_cosmos_msg(
+        data.funds().clone(),
+        data.blinded_serial_number().to_string(),
+        info.sender.into_string(),
+        env.contract.address.into_string(),
+        cfg.multisig_addr.into_string(),
+    )?;
+
+    Ok(Response::new().add_message(msg))
+}
+
+pub(crate) fn refund_funds(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    funds: Coin,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let mix_denom = CONFIG.load(deps.storage)?.mix_denom;
+    if funds.denom != mix_denom {
+        return Err(ContractError::WrongDenom { mix_denom });
+    }
+    let current_balance = deps
+        .querier
+        .query_balance(env.contract.address, mix_denom)?;
+    if funds.amount > current_balance.amount {
+        return Err(ContractError::NotEnoughFunds);
+    }
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let return_tokens = BankMsg::Send {
+        to_address: recipient.into_string(),
+        amount: vec![funds],
+    };
+
+    Ok(Response::new().add_message(return_tokens))
+}
+
+pub(crate) fn prune_spent_credentials(
+    deps: DepsMut<'_>,
+    env: Env,
+    _info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit
+        .unwrap_or(SPEND_CREDENTIAL_PRUNE_DEFAULT_LIMIT)
+        .min(SPEND_CREDENTIAL_PRUNE_MAX_LIMIT) as usize;
+    let current_epoch = epoch_at(env.block.time.seconds());
+
+    // the index is ordered by the expiry, so we can stop at the first entry that has to be kept
+    let prunable = storage::spent_credentials()
+        .idx
+        .expiry_epoch
+        .range(deps.storage, None, None, Order::Ascending)
+        .take_while(|res| match res {
+            Ok((_, spent)) => is_prunable(spent.expiry_epoch(), current_epoch),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|res| {
+            res.map(|(blinded_serial_number, spent)| (blinded_serial_number, spent.spend_id()))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (blinded_serial_number, spend_id) in &prunable {
+        storage::spent_credentials().remove(deps.storage, blinded_serial_number)?;
+        if let Some(spend_id) = spend_id {
+            storage::SPEND_IDS.remove(deps.storage, *spend_id);
+        }
+    }
+
+    let event = Event::new(PRUNED_SPENT_CREDENTIALS_EVENT_TYPE)
+        .add_attribute(PRUNED_COUNT, prunable.len().to_string());
+
+    Ok(Response::new().add_event(event))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::support::tests::fixtures::spend_credential_data_fixture;
+    use crate::support::tests::fixtures::{
+        expiry_epoch_fixture, refund_credential_data_fixture, spend_credential_data_fixture,
+        TEST_MIX_DENOM,
+    };
     use crate::support::tests::helpers::{self, MULTISIG_CONTRACT, POOL_CONTRACT};
+    use coconut_bandwidth_contract_common::expiry::{
+        refund_deadline, EXPIRY_EPOCH_LENGTH_SECS, LEGACY_CREDENTIAL_EXPIRY_EPOCH,
+        LEGACY_EXPIRY_EPOCH,
+    };
     use coconut_bandwidth_contract_common::msg::ExecuteMsg;
+    use coconut_bandwidth_contract_common::refund::refund_from_cosmos_msgs;
     use cosmwasm_std::testing::{mock_env, mock_info};
-    use cosmwasm_std::{from_binary, Coin, CosmosMsg, WasmMsg};
+    use cosmwasm_std::{from_binary, Coin, CosmosMsg, Timestamp, WasmMsg};
     use cw_controllers::AdminError;
     use multisig_contract_common::msg::ExecuteMsg as MultisigExecuteMsg;
 
@@ -183,7 +320,7 @@ mod tests {
         assert_eq!(events.len(), 1);
 
         let event = events[0];
-        assert_eq!(event.attributes.len(), 4);
+        assert_eq!(event.attributes.len(), 5);
 
         let deposit_attr = event
             .attributes
@@ -212,6 +349,13 @@ mod tests {
             .find(|attr| attr.key == DEPOSIT_ENCRYPTION_KEY)
             .unwrap();
         assert_eq!(encryption_key_attr.value, encryption_key);
+
+        let expiry_epoch_attr = event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == DEPOSIT_EXPIRY_EPOCH)
+            .unwrap();
+        assert_eq!(expiry_epoch_attr.value, expiry_epoch_fixture().to_string());
     }
 
     #[test]
@@ -337,6 +481,7 @@ mod tests {
             Coin::new(1, "invalid_denom".to_string()),
             String::new(),
             String::new(),
+            expiry_epoch_fixture(),
         );
         let ret = spend_credential(deps.as_mut(), env.clone(), info.clone(), invalid_data);
         assert_eq!(
@@ -350,6 +495,7 @@ mod tests {
             Coin::new(1, crate::support::tests::fixtures::TEST_MIX_DENOM),
             String::new(),
             "Blinded Serial Number".to_string(),
+            expiry_epoch_fixture(),
         );
         let ret = spend_credential(deps.as_mut(), env.clone(), info.clone(), invalid_data);
         assert_eq!(
@@ -371,4 +517,241 @@ mod tests {
             ContractError::DuplicateBlindedSerialNumber
         );
     }
+
+    fn env_at_epoch(epoch: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(epoch * EXPIRY_EPOCH_LENGTH_SECS);
+        env
+    }
+
+    #[test]
+    fn expired_credential_cannot_be_spent() {
+        let mut deps = helpers::init_contract();
+        let info = mock_info("requester", &[]);
+        let data = spend_credential_data_fixture("blinded_serial_number");
+        let deadline = redemption_deadline(data.expiry_epoch());
+
+        assert_eq!(
+            spend_credential(
+                deps.as_mut(),
+                env_at_epoch(deadline + 1),
+                info.clone(),
+                data.clone()
+            ),
+            Err(ContractError::ExpiredCredential {
+                expiry_epoch: data.expiry_epoch()
+            })
+        );
+        // credentials accepted by the gateway before the expiry can still be redeemed
+        spend_credential(deps.as_mut(), env_at_epoch(deadline), info, data).unwrap();
+    }
+
+    #[test]
+    fn legacy_credential_is_stored_with_its_actual_expiry() {
+        let mut deps = helpers::init_contract();
+        let data = SpendCredentialData::new(
+            Coin::new(100, TEST_MIX_DENOM),
+            "blinded_serial_number".to_string(),
+            "gateway_owner_addr".to_string(),
+            LEGACY_EXPIRY_EPOCH,
+        );
+        spend_credential(
+            deps.as_mut(),
+            env_at_epoch(LEGACY_CREDENTIAL_EXPIRY_EPOCH),
+            mock_info("requester", &[]),
+            data,
+        )
+        .unwrap();
+
+        let spent = storage::spent_credentials()
+            .load(deps.as_ref().storage, "blinded_serial_number")
+            .unwrap();
+        assert_eq!(spent.expiry_epoch(), LEGACY_CREDENTIAL_EXPIRY_EPOCH);
+    }
+
+    #[test]
+    fn only_unredeemable_entries_are_pruned() {
+        let mut deps = helpers::init_contract();
+        let info = mock_info("requester", &[]);
+        let expiry_epoch = expiry_epoch_fixture();
+        for (n, expiry) in [expiry_epoch, expiry_epoch + 1, expiry_epoch]
+            .iter()
+            .enumerate()
+        {
+            let data = SpendCredentialData::new(
+                Coin::new(100, TEST_MIX_DENOM),
+                format!("blinded_serial_number{n}"),
+                "gateway_owner_addr".to_string(),
+                *expiry,
+            );
+            spend_credential(deps.as_mut(), mock_env(), info.clone(), data).unwrap();
+        }
+
+        // they still have to be kept while they could be refunded
+        let env = env_at_epoch(refund_deadline(expiry_epoch));
+        let res = prune_spent_credentials(deps.as_mut(), env, info.clone(), None).unwrap();
+        assert_eq!(res.events[0].attributes[0].value, "0");
+
+        let env = env_at_epoch(refund_deadline(expiry_epoch) + 1);
+        let res =
+            prune_spent_credentials(deps.as_mut(), env.clone(), info.clone(), Some(1)).unwrap();
+        assert_eq!(res.events[0].attributes[0].value, "1");
+        let res = prune_spent_credentials(deps.as_mut(), env, info, None).unwrap();
+        assert_eq!(res.events[0].attributes[0].value, "1");
+
+        let remaining = storage::spent_credentials()
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .map(|res| res.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, vec!["blinded_serial_number1".to_string()]);
+
+        let remaining_spend_ids = storage::SPEND_IDS
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .map(|res| res.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            remaining_spend_ids,
+            vec![(2, "blinded_serial_number1".to_string())]
+        );
+    }
+
+    #[test]
+    fn refunds_are_only_possible_within_refund_window() {
+        let mut deps = helpers::init_contract();
+        let info = mock_info("owner", &[]);
+        let data = refund_credential_data_fixture("blinded_serial_number");
+        let deadline = redemption_deadline(data.expiry_epoch());
+        let not_refundable = Err(ContractError::NotRefundable {
+            expiry_epoch: data.expiry_epoch(),
+        });
+
+        // gateways might still redeem the credential
+        assert_eq!(
+            refund_credential(
+                deps.as_mut(),
+                env_at_epoch(deadline),
+                info.clone(),
+                data.clone()
+            ),
+            not_refundable
+        );
+        // the spent entries might have already been pruned
+        assert_eq!(
+            refund_credential(
+                deps.as_mut(),
+                env_at_epoch(refund_deadline(data.expiry_epoch()) + 1),
+                info.clone(),
+                data.clone()
+            ),
+            not_refundable
+        );
+
+        let res = refund_credential(
+            deps.as_mut(),
+            env_at_epoch(deadline + 1),
+            info,
+            data.clone(),
+        )
+        .unwrap();
+        if let CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) = &res.messages[0].msg
+        {
+            assert_eq!(contract_addr, MULTISIG_CONTRACT);
+            let multisig_msg: MultisigExecuteMsg = from_binary(msg).unwrap();
+            if let MultisigExecuteMsg::Propose {
+                description, msgs, ..
+            } = multisig_msg
+            {
+                assert_eq!(description, data.blinded_serial_number());
+                assert_eq!(
+                    refund_from_cosmos_msgs(msgs),
+                    Some((data.funds().clone(), "owner".to_string()))
+                );
+            } else {
+                panic!("Could not extract proposal from binary blob");
+            }
+        } else {
+            panic!("Wasm execute message not found");
+        }
+    }
+
+    #[test]
+    fn credential_cannot_be_both_spent_and_refunded() {
+        let mut deps = helpers::init_contract();
+        let spent = spend_credential_data_fixture("spent");
+        let deadline = redemption_deadline(spent.expiry_epoch());
+        spend_credential(
+            deps.as_mut(),
+            env_at_epoch(deadline),
+            mock_info("gateway", &[]),
+            spent,
+        )
+        .unwrap();
+
+        assert_eq!(
+            refund_credential(
+                deps.as_mut(),
+                env_at_epoch(deadline + 1),
+                mock_info("owner", &[]),
+                refund_credential_data_fixture("spent")
+            ),
+            Err(ContractError::DuplicateBlindedSerialNumber)
+        );
+
+        // and it can't be refunded twice either
+        let refunded = refund_credential_data_fixture("refunded");
+        refund_credential(
+            deps.as_mut(),
+            env_at_epoch(deadline + 1),
+            mock_info("owner", &[]),
+            refunded.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            refund_credential(
+                deps.as_mut(),
+                env_at_epoch(deadline + 1),
+                mock_info("owner", &[]),
+                refunded
+            ),
+            Err(ContractError::DuplicateBlindedSerialNumber)
+        );
+    }
+
+    #[test]
+    fn refunded_funds_go_to_the_owner() {
+        let mut deps = helpers::init_contract();
+        let env = mock_env();
+        let coin = Coin::new(1, TEST_MIX_DENOM);
+        deps.querier
+            .update_balance(env.contract.address.clone(), vec![coin.clone()]);
+
+        assert_eq!(
+            refund_funds(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("owner", &[]),
+                coin.clone(),
+                "owner".to_string()
+            ),
+            Err(ContractError::Admin(AdminError::NotAdmin {}))
+        );
+
+        let res = refund_funds(
+            deps.as_mut(),
+            env,
+            mock_info(MULTISIG_CONTRACT, &[]),
+            coin.clone(),
+            "owner".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".to_string(),
+                amount: vec![coin]
+            })
+        );
+    }
 }
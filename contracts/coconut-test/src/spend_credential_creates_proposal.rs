@@ -1,6 +1,7 @@
 use crate::helpers::*;
 use coconut_bandwidth::error::ContractError;
 use coconut_bandwidth_contract_common::{
+    expiry::deposit_expiry_epoch,
     msg::{
         ExecuteMsg as CoconutBandwidthExecuteMsg, InstantiateMsg as CoconutBandwidthInstantiateMsg,
     },
@@ -94,6 +95,7 @@ fn spend_credential_creates_proposal() {
             Coin::new(1, TEST_COIN_DENOM),
             String::from("blinded_serial_number"),
             String::from("gateway_cosmos_address"),
+            deposit_expiry_epoch(app.block_info().time.seconds()),
         ),
     };
     let res = app
@@ -137,6 +139,7 @@ fn spend_credential_creates_proposal() {
             Coin::new(1, TEST_COIN_DENOM),
             String::from("blinded_serial_number2"),
             String::from("gateway_cosmos_address"),
+            deposit_expiry_epoch(app.block_info().time.seconds()),
         ),
     };
     let res = app
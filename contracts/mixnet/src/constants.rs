@@ -38,13 +38,29 @@ pub const REWARDED_SET_MAX_RETRIEVAL_LIMIT: u32 = 1000;
 pub const FAMILIES_DEFAULT_RETRIEVAL_LIMIT: u32 = 10;
 pub const FAMILIES_MAX_RETRIEVAL_LIMIT: u32 = 20;
 
+pub const EVIDENCE_DEFAULT_RETRIEVAL_LIMIT: u32 = 50;
+pub const EVIDENCE_MAX_RETRIEVAL_LIMIT: u32 = 100;
+
+pub const MAX_GATEWAY_HOST_LENGTH: usize = 255;
+pub const MAX_GATEWAY_VERSION_LENGTH: usize = 32;
+
+// roughly a week worth of blocks, after which the unreviewed evidence no longer prevents the node from unbonding
+pub const PENDING_EVIDENCE_VALIDITY_BLOCKS: u64 = 100_800;
+
+pub const EXCLUDED_MIXNODES_DEFAULT_RETRIEVAL_LIMIT: u32 = 100;
+pub const EXCLUDED_MIXNODES_MAX_RETRIEVAL_LIMIT: u32 = 200;
+
 // storage keys
 pub(crate) const DELEGATION_PK_NAMESPACE: &str = "dl";
 pub(crate) const DELEGATION_OWNER_IDX_NAMESPACE: &str = "dlo";
 pub(crate) const DELEGATION_MIXNODE_IDX_NAMESPACE: &str = "dlm";
 
+pub(crate) const GATEWAY_DELEGATION_PK_NAMESPACE: &str = "gdl";
+pub(crate) const GATEWAY_DELEGATION_OWNER_IDX_NAMESPACE: &str = "gdlo";
+
 pub(crate) const GATEWAYS_PK_NAMESPACE: &str = "gt";
 pub(crate) const GATEWAYS_OWNER_IDX_NAMESPACE: &str = "gto";
+pub(crate) const GATEWAYS_PENDING_PLEDGE_CHANGES_NAMESPACE: &str = "gtpc";
 
 pub(crate) const REWARDED_SET_KEY: &str = "rs";
 pub(crate) const CURRENT_INTERVAL_KEY: &str = "ci";
@@ -57,6 +73,7 @@ pub(crate) const LAST_EPOCH_EVENT_ID_KEY: &str = "lee";
 pub(crate) const LAST_INTERVAL_EVENT_ID_KEY: &str = "lie";
 
 pub(crate) const CONTRACT_STATE_KEY: &str = "state";
+pub(crate) const TOPOLOGY_SIGNERS_NAMESPACE: &str = "tsg";
 
 pub(crate) const LAYER_DISTRIBUTION_KEY: &str = "layers";
 pub(crate) const NODE_ID_COUNTER_KEY: &str = "nic";
@@ -72,7 +89,15 @@ pub(crate) const UNBONDED_MIXNODES_IDENTITY_IDX_NAMESPACE: &str = "umi";
 pub(crate) const REWARDING_PARAMS_KEY: &str = "rparams";
 pub(crate) const PENDING_REWARD_POOL_KEY: &str = "prp";
 pub(crate) const MIXNODES_REWARDING_PK_NAMESPACE: &str = "mnr";
+pub(crate) const GATEWAYS_REWARDING_PK_NAMESPACE: &str = "gwr";
 
 pub(crate) const FAMILIES_INDEX_NAMESPACE: &str = "faml2";
 pub(crate) const FAMILIES_MAP_NAMESPACE: &str = "fam2";
 pub(crate) const MEMBERS_MAP_NAMESPACE: &str = "memb2";
+
+pub(crate) const PENALTY_MONITORS_NAMESPACE: &str = "pmn";
+pub(crate) const EVIDENCE_ID_COUNTER_KEY: &str = "evc";
+pub(crate) const EVIDENCE_NAMESPACE: &str = "evd";
+pub(crate) const PENDING_EVIDENCE_NAMESPACE: &str = "pevd";
+pub(crate) const SUBMITTED_EVIDENCE_NONCES_NAMESPACE: &str = "sevn";
+pub(crate) const EXCLUDED_MIXNODES_NAMESPACE: &str = "exm";
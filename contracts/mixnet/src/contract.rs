@@ -157,6 +157,21 @@ pub fn execute(
                 updated_parameters,
             )
         }
+        ExecuteMsg::AddTopologySigner { identity_key } => {
+            crate::mixnet_contract_settings::transactions::try_add_topology_signer(
+                deps,
+                env,
+                info,
+                identity_key,
+            )
+        }
+        ExecuteMsg::RemoveTopologySigner { identity_key } => {
+            crate::mixnet_contract_settings::transactions::try_remove_topology_signer(
+                deps,
+                info,
+                identity_key,
+            )
+        }
         ExecuteMsg::UpdateActiveSetSize {
             active_set_size,
             force_immediately,
@@ -291,6 +306,34 @@ pub fn execute(
         ExecuteMsg::UnbondGatewayOnBehalf { owner } => {
             crate::gateways::transactions::try_remove_gateway_on_behalf(deps, info, owner)
         }
+        ExecuteMsg::PledgeMoreGateway {} => {
+            crate::gateways::transactions::try_increase_gateway_pledge(deps, env, info)
+        }
+        ExecuteMsg::PledgeMoreGatewayOnBehalf { owner } => {
+            crate::gateways::transactions::try_increase_gateway_pledge_on_behalf(
+                deps, env, info, owner,
+            )
+        }
+        ExecuteMsg::DecreaseGatewayPledge { decrease_by } => {
+            crate::gateways::transactions::try_decrease_gateway_pledge(deps, env, info, decrease_by)
+        }
+        ExecuteMsg::DecreaseGatewayPledgeOnBehalf { owner, decrease_by } => {
+            crate::gateways::transactions::try_decrease_gateway_pledge_on_behalf(
+                deps,
+                env,
+                info,
+                decrease_by,
+                owner,
+            )
+        }
+        ExecuteMsg::UpdateGatewayConfig { new_config } => {
+            crate::gateways::transactions::try_update_gateway_config(deps, info, new_config)
+        }
+        ExecuteMsg::UpdateGatewayConfigOnBehalf { new_config, owner } => {
+            crate::gateways::transactions::try_update_gateway_config_on_behalf(
+                deps, info, new_config, owner,
+            )
+        }
 
         // delegation-related:
         ExecuteMsg::DelegateToMixnode { mix_id } => {
@@ -311,12 +354,51 @@ pub fn execute(
                 deps, env, info, mix_id, delegate,
             )
         }
+        ExecuteMsg::RedelegateFromMixnode { from, to, amount } => {
+            crate::delegations::transactions::try_redelegate_from_mixnode(
+                deps, env, info, from, to, amount,
+            )
+        }
+        ExecuteMsg::RedelegateFromMixnodeOnBehalf {
+            from,
+            to,
+            amount,
+            delegate,
+        } => crate::delegations::transactions::try_redelegate_from_mixnode_on_behalf(
+            deps, env, info, from, to, amount, delegate,
+        ),
+        ExecuteMsg::DelegateToGateway { gateway_identity } => {
+            crate::delegations::transactions::try_delegate_to_gateway(
+                deps,
+                env,
+                info,
+                gateway_identity,
+            )
+        }
+        ExecuteMsg::UndelegateFromGateway { gateway_identity } => {
+            crate::delegations::transactions::try_remove_delegation_from_gateway(
+                deps,
+                env,
+                info,
+                gateway_identity,
+            )
+        }
 
         // reward-related
         ExecuteMsg::RewardMixnode {
             mix_id,
             performance,
         } => crate::rewards::transactions::try_reward_mixnode(deps, env, info, mix_id, performance),
+        ExecuteMsg::RewardGateway {
+            gateway_identity,
+            performance,
+        } => crate::rewards::transactions::try_reward_gateway(
+            deps,
+            env,
+            info,
+            gateway_identity,
+            performance,
+        ),
 
         ExecuteMsg::WithdrawOperatorReward {} => {
             crate::rewards::transactions::try_withdraw_operator_reward(deps, info)
@@ -333,6 +415,37 @@ pub fn execute(
             )
         }
 
+        // penalty-related
+        ExecuteMsg::AddPenaltyMonitor { identity_key } => {
+            crate::penalties::transactions::try_add_penalty_monitor(deps, env, info, identity_key)
+        }
+        ExecuteMsg::RemovePenaltyMonitor { identity_key } => {
+            crate::penalties::transactions::try_remove_penalty_monitor(deps, info, identity_key)
+        }
+        ExecuteMsg::SubmitMisbehaviourEvidence {
+            evidence,
+            monitor_identity,
+            signature,
+        } => crate::penalties::transactions::try_submit_misbehaviour_evidence(
+            deps,
+            env,
+            evidence,
+            monitor_identity,
+            signature,
+        ),
+        ExecuteMsg::ApplyPenalty {
+            evidence_id,
+            penalty,
+        } => {
+            crate::penalties::transactions::try_apply_penalty(deps, env, info, evidence_id, penalty)
+        }
+        ExecuteMsg::DismissEvidence { evidence_id } => {
+            crate::penalties::transactions::try_dismiss_evidence(deps, env, info, evidence_id)
+        }
+        ExecuteMsg::ReleaseFromJail { mix_id } => {
+            crate::penalties::transactions::try_release_from_jail(deps, info, mix_id)
+        }
+
         // testing-only
         #[cfg(feature = "contract-testing")]
         ExecuteMsg::TestingResolveAllPendingEvents { limit } => {
@@ -378,6 +491,9 @@ pub fn query(
         QueryMsg::GetState {} => {
             to_binary(&crate::mixnet_contract_settings::queries::query_contract_state(deps)?)
         }
+        QueryMsg::GetTopologySigners {} => {
+            to_binary(&crate::mixnet_contract_settings::queries::query_topology_signers(deps)?)
+        }
         QueryMsg::GetRewardingParams {} => {
             to_binary(&crate::rewards::queries::query_rewarding_params(deps)?)
         }
@@ -480,6 +596,30 @@ pub fn query(
                 limit,
             )?,
         ),
+        QueryMsg::GetGatewayDelegations {
+            gateway_identity,
+            start_after,
+            limit,
+        } => to_binary(
+            &crate::delegations::queries::query_gateway_delegations_paged(
+                deps,
+                gateway_identity,
+                start_after,
+                limit,
+            )?,
+        ),
+        QueryMsg::GetDelegatorGatewayDelegations {
+            delegator,
+            start_after,
+            limit,
+        } => to_binary(
+            &crate::delegations::queries::query_delegator_gateway_delegations_paged(
+                deps,
+                delegator,
+                start_after,
+                limit,
+            )?,
+        ),
         QueryMsg::GetDelegationDetails {
             mix_id,
             delegator,
@@ -529,6 +669,25 @@ pub fn query(
                 estimated_performance,
             )?,
         ),
+        QueryMsg::GetGatewayRewardingDetails { gateway_identity } => to_binary(
+            &crate::rewards::queries::query_gateway_rewarding_details(deps, gateway_identity)?,
+        ),
+        QueryMsg::GetPendingGatewayOperatorReward { gateway_identity } => to_binary(
+            &crate::rewards::queries::query_pending_gateway_operator_reward(
+                deps,
+                gateway_identity,
+            )?,
+        ),
+        QueryMsg::GetPendingGatewayDelegatorReward {
+            address,
+            gateway_identity,
+        } => to_binary(
+            &crate::rewards::queries::query_pending_gateway_delegator_reward(
+                deps,
+                address,
+                gateway_identity,
+            )?,
+        ),
 
         // interval-related
         QueryMsg::GetPendingEpochEvents { limit, start_after } => {
@@ -547,6 +706,24 @@ pub fn query(
                 limit,
             )?,
         ),
+
+        // penalty-related
+        QueryMsg::GetPenaltyMonitors {} => {
+            to_binary(&crate::penalties::queries::query_penalty_monitors(deps)?)
+        }
+        QueryMsg::GetMisbehaviourEvidence { evidence_id } => to_binary(
+            &crate::penalties::queries::query_misbehaviour_evidence(deps, evidence_id)?,
+        ),
+        QueryMsg::GetMisbehaviourEvidencePaged { limit, start_after } => to_binary(
+            &crate::penalties::queries::query_misbehaviour_evidence_paged(
+                deps,
+                start_after,
+                limit,
+            )?,
+        ),
+        QueryMsg::GetExcludedMixnodes { limit, start_after } => to_binary(
+            &crate::penalties::queries::query_excluded_mixnodes_paged(deps, start_after, limit)?,
+        ),
     };
 
     Ok(query_res?)
@@ -568,6 +745,8 @@ pub fn migrate(
         mixnet_params_storage::CONTRACT_STATE.save(deps.storage, &current_state)?;
     }
 
+    crate::queued_migrations::initialise_gateway_rewarding(deps.storage)?;
+
     Ok(Default::default())
 }
 
@@ -576,7 +755,9 @@ mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::Decimal;
-    use mixnet_contract_common::reward_params::{IntervalRewardParams, RewardingParams};
+    use mixnet_contract_common::reward_params::{
+        GatewayRewardParams, IntervalRewardParams, RewardingParams,
+    };
     use mixnet_contract_common::{InitialRewardingParams, Percent};
     use std::time::Duration;
 
@@ -600,6 +781,9 @@ mod tests {
                 interval_pool_emission: Percent::from_percentage_value(1).unwrap(),
                 rewarded_set_size: 543,
                 active_set_size: 123,
+                gateway_interval_pool_emission: Percent::from_percentage_value(1).unwrap(),
+                nominal_gateways: 100,
+                gateway_profit_margin: Percent::from_percentage_value(10).unwrap(),
             },
         };
 
@@ -628,6 +812,11 @@ mod tests {
         let expected_epoch_reward_budget =
             Decimal::from_ratio(100_000_000_000_000u128, 1234u32) * Decimal::percent(1);
         let expected_stake_saturation_point = Decimal::from_ratio(123_456_000_000_000u128, 543u32);
+        let expected_gateway_epoch_reward_budget =
+            Decimal::from_ratio(100_000_000_000_000u128, 1234u32) * Decimal::percent(1)
+                / Decimal::from_atomics(100u32, 0).unwrap();
+        let expected_gateway_stake_saturation_point =
+            Decimal::from_ratio(123_456_000_000_000u128, 100u32);
 
         let expected_rewarding_params = RewardingParams {
             interval: IntervalRewardParams {
@@ -642,6 +831,13 @@ mod tests {
             },
             rewarded_set_size: 543,
             active_set_size: 123,
+            gateway: GatewayRewardParams {
+                interval_pool_emission: Percent::from_percentage_value(1).unwrap(),
+                nominal_gateways: 100,
+                profit_margin: Percent::from_percentage_value(10).unwrap(),
+                epoch_reward_budget: expected_gateway_epoch_reward_budget,
+                stake_saturation_point: expected_gateway_stake_saturation_point,
+            },
         };
 
         let state = mixnet_params_storage::CONTRACT_STATE
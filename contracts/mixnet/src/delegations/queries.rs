@@ -12,8 +12,9 @@ use cosmwasm_std::StdResult;
 use cw_storage_plus::Bound;
 use mixnet_contract_common::delegation::{MixNodeDelegationResponse, OwnerProxySubKey};
 use mixnet_contract_common::{
-    delegation, Delegation, MixId, PagedAllDelegationsResponse, PagedDelegatorDelegationsResponse,
-    PagedMixNodeDelegationsResponse,
+    delegation, Delegation, GatewayDelegation, IdentityKey, MixId, PagedAllDelegationsResponse,
+    PagedDelegatorDelegationsResponse, PagedDelegatorGatewayDelegationsResponse,
+    PagedGatewayDelegationsResponse, PagedMixNodeDelegationsResponse,
 };
 
 pub(crate) fn query_mixnode_delegations_paged(
@@ -82,6 +83,67 @@ pub(crate) fn query_delegator_delegations_paged(
     ))
 }
 
+pub(crate) fn query_gateway_delegations_paged(
+    deps: Deps<'_>,
+    gateway_identity: IdentityKey,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PagedGatewayDelegationsResponse> {
+    let limit = limit
+        .unwrap_or(DELEGATION_PAGE_DEFAULT_RETRIEVAL_LIMIT)
+        .min(DELEGATION_PAGE_MAX_RETRIEVAL_LIMIT) as usize;
+
+    let start = start_after
+        .map(|owner| deps.api.addr_validate(&owner))
+        .transpose()?
+        .map(Bound::exclusive);
+
+    let delegations = storage::gateway_delegations()
+        .prefix(gateway_identity)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|record| record.map(|r| r.1))
+        .collect::<StdResult<Vec<GatewayDelegation>>>()?;
+
+    let start_next_after = delegations.last().map(|del| del.owner.to_string());
+
+    Ok(PagedGatewayDelegationsResponse::new(
+        delegations,
+        start_next_after,
+    ))
+}
+
+pub(crate) fn query_delegator_gateway_delegations_paged(
+    deps: Deps<'_>,
+    delegation_owner: String,
+    start_after: Option<IdentityKey>,
+    limit: Option<u32>,
+) -> StdResult<PagedDelegatorGatewayDelegationsResponse> {
+    let validated_owner = deps.api.addr_validate(&delegation_owner)?;
+
+    let limit = limit
+        .unwrap_or(DELEGATION_PAGE_DEFAULT_RETRIEVAL_LIMIT)
+        .min(DELEGATION_PAGE_MAX_RETRIEVAL_LIMIT) as usize;
+
+    let start = start_after.map(|identity| Bound::exclusive((identity, validated_owner.clone())));
+
+    let delegations = storage::gateway_delegations()
+        .idx
+        .owner
+        .prefix(validated_owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|record| record.map(|r| r.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let start_next_after = delegations.last().map(|del| del.gateway_identity.clone());
+
+    Ok(PagedDelegatorGatewayDelegationsResponse::new(
+        delegations,
+        start_next_after,
+    ))
+}
+
 // queries for delegation value of given address for particular node
 pub(crate) fn query_mixnode_delegation(
     deps: Deps<'_>,
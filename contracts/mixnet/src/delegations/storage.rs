@@ -3,10 +3,11 @@
 
 use crate::constants::{
     DELEGATION_MIXNODE_IDX_NAMESPACE, DELEGATION_OWNER_IDX_NAMESPACE, DELEGATION_PK_NAMESPACE,
+    GATEWAY_DELEGATION_OWNER_IDX_NAMESPACE, GATEWAY_DELEGATION_PK_NAMESPACE,
 };
 use cw_storage_plus::{Index, IndexList, IndexedMap, MultiIndex};
 use mixnet_contract_common::delegation::OwnerProxySubKey;
-use mixnet_contract_common::{Addr, Delegation, MixId};
+use mixnet_contract_common::{Addr, Delegation, GatewayDelegation, IdentityKey, MixId};
 
 // It's a composite key on node's id and delegator address
 type PrimaryKey = (MixId, OwnerProxySubKey);
@@ -40,3 +41,30 @@ pub(crate) fn delegations<'a>() -> IndexedMap<'a, PrimaryKey, Delegation, Delega
 
     IndexedMap::new(DELEGATION_PK_NAMESPACE, indexes)
 }
+
+// It's a composite key on gateway's identity and delegator address
+pub(crate) type GatewayDelegationKey = (IdentityKey, Addr);
+
+pub(crate) struct GatewayDelegationIndex<'a> {
+    pub(crate) owner: MultiIndex<'a, Addr, GatewayDelegation, GatewayDelegationKey>,
+}
+
+impl<'a> IndexList<GatewayDelegation> for GatewayDelegationIndex<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<GatewayDelegation>> + '_> {
+        let v: Vec<&dyn Index<GatewayDelegation>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+pub(crate) fn gateway_delegations<'a>(
+) -> IndexedMap<'a, GatewayDelegationKey, GatewayDelegation, GatewayDelegationIndex<'a>> {
+    let indexes = GatewayDelegationIndex {
+        owner: MultiIndex::new(
+            |d| d.owner.clone(),
+            GATEWAY_DELEGATION_PK_NAMESPACE,
+            GATEWAY_DELEGATION_OWNER_IDX_NAMESPACE,
+        ),
+    };
+
+    IndexedMap::new(GATEWAY_DELEGATION_PK_NAMESPACE, indexes)
+}
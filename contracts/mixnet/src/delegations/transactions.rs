@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::storage;
+use crate::gateways::storage as gateways_storage;
 use crate::interval::storage as interval_storage;
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
 use crate::mixnodes::storage as mixnodes_storage;
@@ -9,10 +10,12 @@ use crate::support::helpers::validate_delegation_stake;
 use cosmwasm_std::{Addr, Coin, DepsMut, Env, MessageInfo, Response};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
-    new_pending_delegation_event, new_pending_undelegation_event,
+    new_pending_delegation_event, new_pending_gateway_delegation_event,
+    new_pending_gateway_undelegation_event, new_pending_redelegation_event,
+    new_pending_undelegation_event,
 };
 use mixnet_contract_common::pending_events::PendingEpochEventKind;
-use mixnet_contract_common::{Delegation, MixId};
+use mixnet_contract_common::{Delegation, IdentityKey, MixId};
 
 pub(crate) fn try_delegate_to_mixnode(
     deps: DepsMut<'_>,
@@ -127,6 +130,168 @@ pub(crate) fn _try_remove_delegation_from_mixnode(
     Ok(Response::new().add_event(cosmos_event))
 }
 
+pub(crate) fn try_redelegate_from_mixnode(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+) -> Result<Response, MixnetContractError> {
+    _try_redelegate_from_mixnode(deps, env, from_mix_id, to_mix_id, amount, info.sender, None)
+}
+
+pub(crate) fn try_redelegate_from_mixnode_on_behalf(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    delegate: String,
+) -> Result<Response, MixnetContractError> {
+    let delegate = deps.api.addr_validate(&delegate)?;
+    _try_redelegate_from_mixnode(
+        deps,
+        env,
+        from_mix_id,
+        to_mix_id,
+        amount,
+        delegate,
+        Some(info.sender),
+    )
+}
+
+pub(crate) fn _try_redelegate_from_mixnode(
+    deps: DepsMut<'_>,
+    env: Env,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    delegate: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    if from_mix_id == to_mix_id {
+        return Err(MixnetContractError::RedelegationToSameMixnode {
+            mix_id: from_mix_id,
+        });
+    }
+
+    // the moved amount is subject to the same constraints as a fresh delegation
+    let contract_state = mixnet_params_storage::CONTRACT_STATE.load(deps.storage)?;
+    let amount = validate_delegation_stake(
+        vec![amount],
+        contract_state.params.minimum_mixnode_delegation,
+        contract_state.rewarding_denom,
+    )?;
+
+    // see if the source delegation even exists
+    let storage_key = Delegation::generate_storage_key(from_mix_id, &delegate, proxy.as_ref());
+    if storage::delegations()
+        .may_load(deps.storage, storage_key)?
+        .is_none()
+    {
+        return Err(MixnetContractError::NoMixnodeDelegationFound {
+            mix_id: from_mix_id,
+            address: delegate.into_string(),
+            proxy: proxy.map(Addr::into_string),
+        });
+    }
+
+    // check if the target node actually exists and is still bonded
+    match mixnodes_storage::mixnode_bonds().may_load(deps.storage, to_mix_id)? {
+        None => {
+            return Err(MixnetContractError::MixNodeBondNotFound { mix_id: to_mix_id });
+        }
+        Some(bond) if bond.is_unbonding => {
+            return Err(MixnetContractError::MixnodeIsUnbonding { mix_id: to_mix_id })
+        }
+        _ => (),
+    }
+
+    // push the event onto the queue and wait for it to be picked up at the end of the epoch
+    let cosmos_event =
+        new_pending_redelegation_event(&delegate, &proxy, &amount, from_mix_id, to_mix_id);
+
+    let epoch_event = PendingEpochEventKind::Redelegate {
+        owner: delegate,
+        from_mix_id,
+        to_mix_id,
+        amount,
+        proxy,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
+pub(crate) fn try_delegate_to_gateway(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    gateway_identity: IdentityKey,
+) -> Result<Response, MixnetContractError> {
+    // check if the delegation contains any funds of the appropriate denomination
+    // (gateways share the minimum delegation with mixnodes)
+    let contract_state = mixnet_params_storage::CONTRACT_STATE.load(deps.storage)?;
+    let delegation = validate_delegation_stake(
+        info.funds,
+        contract_state.params.minimum_mixnode_delegation,
+        contract_state.rewarding_denom,
+    )?;
+
+    // check if the target gateway actually exists
+    if !gateways_storage::gateways().has(deps.storage, &gateway_identity) {
+        return Err(MixnetContractError::GatewayBondNotFound {
+            identity: gateway_identity,
+        });
+    }
+
+    // push the event onto the queue and wait for it to be picked up at the end of the epoch
+    let cosmos_event =
+        new_pending_gateway_delegation_event(&info.sender, &delegation, &gateway_identity);
+
+    let epoch_event = PendingEpochEventKind::DelegateToGateway {
+        owner: info.sender,
+        gateway_identity,
+        amount: delegation,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
+pub(crate) fn try_remove_delegation_from_gateway(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    gateway_identity: IdentityKey,
+) -> Result<Response, MixnetContractError> {
+    // see if the delegation even exists. note that we don't care whether the gateway
+    // is still bonded, the delegator should always be able to get their tokens back
+    let storage_key = (gateway_identity.clone(), info.sender.clone());
+    if storage::gateway_delegations()
+        .may_load(deps.storage, storage_key)?
+        .is_none()
+    {
+        return Err(MixnetContractError::NoGatewayDelegationFound {
+            identity: gateway_identity,
+            address: info.sender.into_string(),
+        });
+    }
+
+    // push the event onto the queue and wait for it to be picked up at the end of the epoch
+    let cosmos_event = new_pending_gateway_undelegation_event(&info.sender, &gateway_identity);
+
+    let epoch_event = PendingEpochEventKind::UndelegateFromGateway {
+        owner: info.sender,
+        gateway_identity,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -463,4 +628,352 @@ mod tests {
             assert!(res.is_ok());
         }
     }
+
+    #[cfg(test)]
+    mod redelegating_between_mixnodes {
+        use super::*;
+        use crate::support::tests::fixtures::TEST_COIN_DENOM;
+        use crate::support::tests::test_helpers::TestSetup;
+        use cosmwasm_std::testing::mock_info;
+        use cosmwasm_std::{coin, Decimal, Uint128};
+
+        #[test]
+        fn cannot_be_done_towards_the_same_mixnode() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "delegator";
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, mix_id);
+
+            let res = try_redelegate_from_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                mix_id,
+                mix_id,
+                coin(50_000_000, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::RedelegationToSameMixnode { mix_id })
+            )
+        }
+
+        #[test]
+        fn requires_existing_source_delegation() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "delegator";
+            let from = test.add_dummy_mixnode("mix-owner1", None);
+            let to = test.add_dummy_mixnode("mix-owner2", None);
+
+            let res = try_redelegate_from_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                from,
+                to,
+                coin(50_000_000, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoMixnodeDelegationFound {
+                    mix_id: from,
+                    address: owner.to_string(),
+                    proxy: None
+                })
+            )
+        }
+
+        #[test]
+        fn can_only_be_done_towards_fully_bonded_mixnode() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "delegator";
+            let from = test.add_dummy_mixnode("mix-owner1", None);
+            let to = test.add_dummy_mixnode("mix-owner2", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, from);
+
+            let res = try_redelegate_from_mixnode(
+                test.deps_mut(),
+                env.clone(),
+                mock_info(owner, &[]),
+                from,
+                42,
+                coin(50_000_000, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixNodeBondNotFound { mix_id: 42 })
+            );
+
+            test.start_unbonding_mixnode(to);
+            let res = try_redelegate_from_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                from,
+                to,
+                coin(50_000_000, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixnodeIsUnbonding { mix_id: to })
+            )
+        }
+
+        #[test]
+        fn moves_requested_amount_at_epoch_end() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "delegator";
+            let from = test.add_dummy_mixnode("mix-owner1", None);
+            let to = test.add_dummy_mixnode("mix-owner2", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, from);
+            test.add_immediate_delegation(owner, 20_000_000u32, to);
+
+            try_redelegate_from_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                from,
+                to,
+                coin(30_000_000, TEST_COIN_DENOM),
+            )
+            .unwrap();
+
+            // nothing changes until the epoch is over
+            assert_eq!(
+                test.delegation(from, owner, &None).amount.amount,
+                Uint128::new(100_000_000)
+            );
+
+            test.execute_all_pending_events();
+
+            let source = test.delegation(from, owner, &None);
+            let target = test.delegation(to, owner, &None);
+            assert_eq!(source.amount.amount, Uint128::new(70_000_000));
+            assert_eq!(target.amount.amount, Uint128::new(50_000_000));
+
+            assert_eq!(
+                test.mix_rewarding(from).delegates,
+                Decimal::from_atomics(70_000_000u32, 0).unwrap()
+            );
+            assert_eq!(
+                test.mix_rewarding(to).delegates,
+                Decimal::from_atomics(50_000_000u32, 0).unwrap()
+            );
+            assert_eq!(test.mix_rewarding(from).unique_delegations, 1);
+            assert_eq!(test.mix_rewarding(to).unique_delegations, 1);
+        }
+
+        #[test]
+        fn moves_everything_if_requested_amount_exceeds_the_delegation() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "delegator";
+            let from = test.add_dummy_mixnode("mix-owner1", None);
+            let to = test.add_dummy_mixnode("mix-owner2", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, from);
+
+            try_redelegate_from_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                from,
+                to,
+                coin(500_000_000, TEST_COIN_DENOM),
+            )
+            .unwrap();
+            test.execute_all_pending_events();
+
+            let source_key = Delegation::generate_storage_key(from, &Addr::unchecked(owner), None);
+            assert!(storage::delegations()
+                .may_load(test.deps().storage, source_key)
+                .unwrap()
+                .is_none());
+            assert_eq!(
+                test.delegation(to, owner, &None).amount.amount,
+                Uint128::new(100_000_000)
+            );
+            assert_eq!(test.mix_rewarding(from).delegates, Decimal::zero());
+            assert_eq!(test.mix_rewarding(from).unique_delegations, 0);
+        }
+
+        #[test]
+        fn leaves_source_untouched_if_target_unbonded_before_epoch_end() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "delegator";
+            let from = test.add_dummy_mixnode("mix-owner1", None);
+            let to = test.add_dummy_mixnode("mix-owner2", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, from);
+
+            try_redelegate_from_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                from,
+                to,
+                coin(30_000_000, TEST_COIN_DENOM),
+            )
+            .unwrap();
+            test.immediately_unbond_mixnode(to);
+            test.execute_all_pending_events();
+
+            assert_eq!(
+                test.delegation(from, owner, &None).amount.amount,
+                Uint128::new(100_000_000)
+            );
+            let target_key = Delegation::generate_storage_key(to, &Addr::unchecked(owner), None);
+            assert!(storage::delegations()
+                .may_load(test.deps().storage, target_key)
+                .unwrap()
+                .is_none());
+        }
+    }
+
+    #[cfg(test)]
+    mod gateway_delegations {
+        use super::*;
+        use crate::interval::pending_events;
+        use crate::support::tests::fixtures::{good_gateway_pledge, TEST_COIN_DENOM};
+        use crate::support::tests::test_helpers::{add_gateway, TestSetup};
+        use cosmwasm_std::testing::mock_info;
+        use cosmwasm_std::{coin, Addr, BankMsg, Uint128};
+
+        #[test]
+        fn can_only_be_done_towards_an_existing_gateway() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let sender = mock_info("delegator", &[coin(100_000_000, TEST_COIN_DENOM)]);
+
+            let res =
+                try_delegate_to_gateway(test.deps_mut(), env, sender, "non-existent".to_string());
+            assert_eq!(
+                res,
+                Err(MixnetContractError::GatewayBondNotFound {
+                    identity: "non-existent".to_string()
+                })
+            )
+        }
+
+        #[test]
+        fn is_added_and_removed_at_epoch_end() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let identity = add_gateway(
+                &mut test.rng,
+                test.deps.as_mut(),
+                env.clone(),
+                "gateway-owner",
+                good_gateway_pledge(),
+            );
+
+            let amount = coin(100_000_000, TEST_COIN_DENOM);
+            let sender = mock_info("delegator", &[amount.clone()]);
+            try_delegate_to_gateway(test.deps_mut(), env.clone(), sender, identity.clone())
+                .unwrap();
+
+            // nothing is stored until the epoch event gets executed
+            let key = (identity.clone(), Addr::unchecked("delegator"));
+            assert!(storage::gateway_delegations()
+                .may_load(test.deps().storage, key.clone())
+                .unwrap()
+                .is_none());
+
+            test.execute_all_pending_events();
+            let delegation = storage::gateway_delegations()
+                .load(test.deps().storage, key.clone())
+                .unwrap();
+            assert_eq!(delegation.amount, amount);
+
+            // a subsequent delegation is added on top of the existing one
+            let sender = mock_info("delegator", &[amount.clone()]);
+            try_delegate_to_gateway(test.deps_mut(), env.clone(), sender, identity.clone())
+                .unwrap();
+            test.execute_all_pending_events();
+            let delegation = storage::gateway_delegations()
+                .load(test.deps().storage, key.clone())
+                .unwrap();
+            assert_eq!(delegation.amount.amount, amount.amount * Uint128::new(2));
+
+            // undelegating from somebody else's delegation is not possible
+            let res = try_remove_delegation_from_gateway(
+                test.deps_mut(),
+                env.clone(),
+                mock_info("someone-else", &[]),
+                identity.clone(),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoGatewayDelegationFound {
+                    identity: identity.clone(),
+                    address: "someone-else".to_string(),
+                })
+            );
+
+            try_remove_delegation_from_gateway(
+                test.deps_mut(),
+                env,
+                mock_info("delegator", &[]),
+                identity,
+            )
+            .unwrap();
+            test.execute_all_pending_events();
+            assert!(storage::gateway_delegations()
+                .may_load(test.deps().storage, key)
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn is_returned_if_gateway_unbonded_before_epoch_end() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let identity = add_gateway(
+                &mut test.rng,
+                test.deps.as_mut(),
+                env.clone(),
+                "gateway-owner",
+                good_gateway_pledge(),
+            );
+
+            let amount = coin(100_000_000, TEST_COIN_DENOM);
+            let sender = mock_info("delegator", &[amount.clone()]);
+            try_delegate_to_gateway(test.deps_mut(), env.clone(), sender, identity.clone())
+                .unwrap();
+
+            crate::gateways::transactions::try_remove_gateway(
+                test.deps_mut(),
+                mock_info("gateway-owner", &[]),
+            )
+            .unwrap();
+
+            let res = pending_events::delegate_to_gateway(
+                test.deps_mut(),
+                &env,
+                env.block.height,
+                Addr::unchecked("delegator"),
+                identity.clone(),
+                amount.clone(),
+            )
+            .unwrap();
+            assert_eq!(
+                res.messages[0].msg,
+                BankMsg::Send {
+                    to_address: "delegator".to_string(),
+                    amount: vec![amount],
+                }
+                .into()
+            );
+            assert!(storage::gateway_delegations()
+                .may_load(
+                    test.deps().storage,
+                    (identity, Addr::unchecked("delegator"))
+                )
+                .unwrap()
+                .is_none());
+        }
+    }
 }
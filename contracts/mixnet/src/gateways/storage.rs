@@ -1,10 +1,18 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::constants::{GATEWAYS_OWNER_IDX_NAMESPACE, GATEWAYS_PK_NAMESPACE};
+use crate::constants::{
+    GATEWAYS_OWNER_IDX_NAMESPACE, GATEWAYS_PENDING_PLEDGE_CHANGES_NAMESPACE, GATEWAYS_PK_NAMESPACE,
+};
 use cosmwasm_std::Addr;
-use cw_storage_plus::{Index, IndexList, IndexedMap, UniqueIndex};
-use mixnet_contract_common::{GatewayBond, IdentityKeyRef};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Map, UniqueIndex};
+use mixnet_contract_common::{BlockHeight, GatewayBond, IdentityKeyRef};
+
+// gateways with a pledge change waiting to be resolved at the end of the current epoch
+// alongside the height at which the change was requested. Since gateways unbond immediately,
+// we must not allow them to leave before the change goes through.
+pub(crate) const PENDING_PLEDGE_CHANGES: Map<'_, IdentityKeyRef<'_>, BlockHeight> =
+    Map::new(GATEWAYS_PENDING_PLEDGE_CHANGES_NAMESPACE);
 
 pub(crate) struct GatewayBondIndex<'a> {
     pub(crate) owner: UniqueIndex<'a, Addr, GatewayBond>,
@@ -2,14 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::storage;
+use crate::constants::{MAX_GATEWAY_HOST_LENGTH, MAX_GATEWAY_VERSION_LENGTH};
+use crate::interval::storage as interval_storage;
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
+use crate::mixnet_contract_settings::storage::rewarding_denom;
+use crate::rewards::storage as rewards_storage;
 use crate::support::helpers::{
-    ensure_no_existing_bond, validate_node_identity_signature, validate_pledge,
+    ensure_no_existing_bond, ensure_proxy_match, validate_node_identity_signature, validate_pledge,
+};
+use cosmwasm_std::{
+    coin, wasm_execute, Addr, BankMsg, Coin, Decimal, DepsMut, Env, MessageInfo, Response, Storage,
 };
-use cosmwasm_std::{wasm_execute, Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response};
 use mixnet_contract_common::error::MixnetContractError;
-use mixnet_contract_common::events::{new_gateway_bonding_event, new_gateway_unbonding_event};
-use mixnet_contract_common::{Gateway, GatewayBond};
+use mixnet_contract_common::events::{
+    new_gateway_bonding_event, new_gateway_config_update_event, new_gateway_unbonding_event,
+    new_pending_gateway_pledge_decrease_event, new_pending_gateway_pledge_increase_event,
+};
+use mixnet_contract_common::pending_events::PendingEpochEventKind;
+use mixnet_contract_common::{Gateway, GatewayBond, GatewayConfigUpdate, GatewayRewarding};
 use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 
 pub fn try_add_gateway(
@@ -51,6 +61,50 @@ pub fn try_add_gateway_on_behalf(
     )
 }
 
+// the same rules apply to the details provided at bonding and to the subsequent config updates
+fn validate_gateway_details(
+    host: &str,
+    mix_port: u16,
+    clients_port: u16,
+    version: &str,
+) -> Result<(), MixnetContractError> {
+    if host.is_empty() || host.len() > MAX_GATEWAY_HOST_LENGTH {
+        return Err(MixnetContractError::InvalidGatewayConfig {
+            reason: format!(
+                "the host has to be between 1 and {} characters long",
+                MAX_GATEWAY_HOST_LENGTH
+            ),
+        });
+    }
+    if host.chars().any(char::is_whitespace) {
+        return Err(MixnetContractError::InvalidGatewayConfig {
+            reason: "the host must not contain any whitespace".into(),
+        });
+    }
+    if mix_port == 0 || clients_port == 0 {
+        return Err(MixnetContractError::InvalidGatewayConfig {
+            reason: "the ports must be non-zero".into(),
+        });
+    }
+    if mix_port == clients_port {
+        return Err(MixnetContractError::InvalidGatewayConfig {
+            reason: "the mix and clients ports must be different".into(),
+        });
+    }
+    if version.is_empty()
+        || version.len() > MAX_GATEWAY_VERSION_LENGTH
+        || version.chars().any(char::is_whitespace)
+    {
+        return Err(MixnetContractError::InvalidGatewayConfig {
+            reason: format!(
+                "the version has to be between 1 and {} characters long and contain no whitespace",
+                MAX_GATEWAY_VERSION_LENGTH
+            ),
+        });
+    }
+    Ok(())
+}
+
 pub(crate) fn _try_add_gateway(
     deps: DepsMut<'_>,
     env: Env,
@@ -64,6 +118,13 @@ pub(crate) fn _try_add_gateway(
     let minimum_pledge = mixnet_params_storage::minimum_gateway_pledge(deps.storage)?;
     let pledge = validate_pledge(pledge, minimum_pledge)?;
 
+    validate_gateway_details(
+        &gateway.host,
+        gateway.mix_port,
+        gateway.clients_port,
+        &gateway.version,
+    )?;
+
     // if the client has an active bonded mixnode or gateway, don't allow bonding
     ensure_no_existing_bond(deps.storage, &owner)?;
 
@@ -95,7 +156,19 @@ pub(crate) fn _try_add_gateway(
         proxy.clone(),
     );
 
+    // if the gateway has been bonded before and some of its delegators haven't yet left,
+    // we keep on using the same rewarding details so that their rewards would remain valid
+    let gateway_rewarding =
+        match rewards_storage::GATEWAY_REWARDING.may_load(deps.storage, bond.identity())? {
+            Some(mut existing) => {
+                existing.increase_operator_uint128(pledge.amount)?;
+                existing
+            }
+            None => GatewayRewarding::initialise_new(&pledge)?,
+        };
+
     storage::gateways().save(deps.storage, bond.identity(), &bond)?;
+    rewards_storage::GATEWAY_REWARDING.save(deps.storage, bond.identity(), &gateway_rewarding)?;
 
     Ok(Response::new().add_event(new_gateway_bonding_event(
         &owner,
@@ -146,21 +219,41 @@ pub(crate) fn _try_remove_gateway(
         });
     }
 
+    // the pending pledge change would have nothing to be applied to
+    ensure_no_pending_pledge_change(deps.storage, &gateway_bond)?;
+
+    // the operator gets back its pledge alongside all the rewards it has accumulated
+    let mut gateway_rewarding =
+        rewards_storage::GATEWAY_REWARDING.load(deps.storage, gateway_bond.identity())?;
+    let tokens = gateway_rewarding.operator_pledge_with_reward(&gateway_bond.pledge_amount.denom);
+
     // send bonded funds back to the bond owner
     let return_tokens = BankMsg::Send {
         to_address: proxy.as_ref().unwrap_or(&owner).to_string(),
-        amount: vec![gateway_bond.pledge_amount()],
+        amount: vec![tokens.clone()],
     };
 
     // remove the bond
     storage::gateways().remove(deps.storage, gateway_bond.identity())?;
 
+    // the rewarding details have to be kept around until all delegators have removed their stake
+    if gateway_rewarding.unique_delegations == 0 {
+        rewards_storage::GATEWAY_REWARDING.remove(deps.storage, gateway_bond.identity());
+    } else {
+        gateway_rewarding.operator = Decimal::zero();
+        rewards_storage::GATEWAY_REWARDING.save(
+            deps.storage,
+            gateway_bond.identity(),
+            &gateway_rewarding,
+        )?;
+    }
+
     let mut response = Response::new().add_message(return_tokens);
 
     if let Some(proxy) = &proxy {
         let msg = VestingContractExecuteMsg::TrackUnbondGateway {
             owner: owner.as_str().to_string(),
-            amount: gateway_bond.pledge_amount(),
+            amount: tokens.clone(),
         };
 
         let track_unbond_message = wasm_execute(proxy, &msg, vec![])?;
@@ -170,13 +263,219 @@ pub(crate) fn _try_remove_gateway(
     Ok(response.add_event(new_gateway_unbonding_event(
         &owner,
         &proxy,
-        &gateway_bond.pledge_amount,
+        &tokens,
         gateway_bond.identity(),
     )))
 }
 
+fn must_get_gateway_bond_by_owner(
+    storage: &dyn Storage,
+    owner: &Addr,
+) -> Result<GatewayBond, MixnetContractError> {
+    Ok(storage::gateways()
+        .idx
+        .owner
+        .item(storage, owner.clone())?
+        .ok_or(MixnetContractError::NoAssociatedGatewayBond {
+            owner: owner.clone(),
+        })?
+        .1)
+}
+
+fn ensure_no_pending_pledge_change(
+    storage: &dyn Storage,
+    bond: &GatewayBond,
+) -> Result<(), MixnetContractError> {
+    if storage::PENDING_PLEDGE_CHANGES.has(storage, bond.identity()) {
+        return Err(MixnetContractError::PendingGatewayPledgeChange {
+            identity: bond.identity().clone(),
+        });
+    }
+    Ok(())
+}
+
+pub fn try_increase_gateway_pledge(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, MixnetContractError> {
+    _try_increase_gateway_pledge(deps, env, info.funds, info.sender, None)
+}
+
+pub fn try_increase_gateway_pledge_on_behalf(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, MixnetContractError> {
+    let proxy = info.sender;
+    let owner = deps.api.addr_validate(&owner)?;
+    _try_increase_gateway_pledge(deps, env, info.funds, owner, Some(proxy))
+}
+
+pub(crate) fn _try_increase_gateway_pledge(
+    deps: DepsMut<'_>,
+    env: Env,
+    increase: Vec<Coin>,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let gateway_bond = must_get_gateway_bond_by_owner(deps.storage, &owner)?;
+
+    ensure_proxy_match(&proxy, &gateway_bond.proxy)?;
+    // only allow a single pledge change per epoch so that we'd know exactly when the gateway
+    // is free to unbond again
+    ensure_no_pending_pledge_change(deps.storage, &gateway_bond)?;
+
+    let rewarding_denom = rewarding_denom(deps.storage)?;
+    let pledge_increase = validate_pledge(increase, coin(1, rewarding_denom))?;
+
+    let cosmos_event =
+        new_pending_gateway_pledge_increase_event(gateway_bond.identity(), &pledge_increase);
+
+    // push the event to execute it at the end of the epoch
+    let epoch_event = PendingEpochEventKind::PledgeMoreGateway {
+        identity: gateway_bond.identity().clone(),
+        amount: pledge_increase,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+    storage::PENDING_PLEDGE_CHANGES.save(
+        deps.storage,
+        gateway_bond.identity(),
+        &env.block.height,
+    )?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
+pub fn try_decrease_gateway_pledge(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    decrease_by: Coin,
+) -> Result<Response, MixnetContractError> {
+    _try_decrease_gateway_pledge(deps, env, decrease_by, info.sender, None)
+}
+
+pub fn try_decrease_gateway_pledge_on_behalf(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    decrease_by: Coin,
+    owner: String,
+) -> Result<Response, MixnetContractError> {
+    let proxy = info.sender;
+    let owner = deps.api.addr_validate(&owner)?;
+    _try_decrease_gateway_pledge(deps, env, decrease_by, owner, Some(proxy))
+}
+
+pub(crate) fn _try_decrease_gateway_pledge(
+    deps: DepsMut<'_>,
+    env: Env,
+    decrease_by: Coin,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let gateway_bond = must_get_gateway_bond_by_owner(deps.storage, &owner)?;
+
+    ensure_proxy_match(&proxy, &gateway_bond.proxy)?;
+    ensure_no_pending_pledge_change(deps.storage, &gateway_bond)?;
+
+    let rewarding_denom = rewarding_denom(deps.storage)?;
+    let decrease_by = validate_pledge(vec![decrease_by], coin(1, rewarding_denom))?;
+
+    // the remaining pledge must still be above the minimum required for bonding
+    let minimum_pledge = mixnet_params_storage::minimum_gateway_pledge(deps.storage)?;
+    let current = &gateway_bond.pledge_amount;
+    if current.amount <= decrease_by.amount
+        || current.amount - decrease_by.amount < minimum_pledge.amount
+    {
+        return Err(MixnetContractError::InvalidGatewayPledgeReduction {
+            current: current.clone(),
+            decrease_by,
+            minimum: minimum_pledge,
+        });
+    }
+
+    let cosmos_event =
+        new_pending_gateway_pledge_decrease_event(gateway_bond.identity(), &decrease_by);
+
+    // push the event to execute it at the end of the epoch
+    let epoch_event = PendingEpochEventKind::DecreaseGatewayPledge {
+        identity: gateway_bond.identity().clone(),
+        decrease_by,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+    storage::PENDING_PLEDGE_CHANGES.save(
+        deps.storage,
+        gateway_bond.identity(),
+        &env.block.height,
+    )?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
+pub(crate) fn try_update_gateway_config(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    new_config: GatewayConfigUpdate,
+) -> Result<Response, MixnetContractError> {
+    let owner = info.sender;
+    _try_update_gateway_config(deps, new_config, owner, None)
+}
+
+pub(crate) fn try_update_gateway_config_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    new_config: GatewayConfigUpdate,
+    owner: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let proxy = info.sender;
+    _try_update_gateway_config(deps, new_config, owner, Some(proxy))
+}
+
+pub(crate) fn _try_update_gateway_config(
+    deps: DepsMut<'_>,
+    new_config: GatewayConfigUpdate,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = must_get_gateway_bond_by_owner(deps.storage, &owner)?;
+
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+    validate_gateway_details(
+        &new_config.host,
+        new_config.mix_port,
+        new_config.clients_port,
+        &new_config.version,
+    )?;
+
+    let cfg_update_event =
+        new_gateway_config_update_event(&owner, &proxy, existing_bond.identity(), &new_config);
+
+    // note: the identity and sphinx keys are deliberately not updatable so that the gateway
+    // would keep its history
+    let mut updated_bond = existing_bond.clone();
+    updated_bond.gateway.host = new_config.host;
+    updated_bond.gateway.mix_port = new_config.mix_port;
+    updated_bond.gateway.clients_port = new_config.clients_port;
+    updated_bond.gateway.location = new_config.location;
+    updated_bond.gateway.version = new_config.version;
+
+    storage::gateways().replace(
+        deps.storage,
+        existing_bond.identity(),
+        Some(&updated_bond),
+        Some(&existing_bond),
+    )?;
+
+    Ok(Response::new().add_event(cfg_update_event))
+}
+
 #[cfg(test)]
 pub mod tests {
+    use super::*;
     use crate::contract::execute;
     use crate::gateways::transactions::try_add_gateway;
     use crate::interval::pending_events;
@@ -367,4 +666,312 @@ pub mod tests {
         assert_eq!(1, gateway_bonds.len());
         assert_eq!(&Addr::unchecked("bob"), gateway_bonds[0].owner());
     }
+
+    #[test]
+    fn updating_gateway_config() {
+        let mut deps = test_helpers::init_contract();
+        let mut rng = test_helpers::test_rng();
+        let env = mock_env();
+
+        let update = GatewayConfigUpdate {
+            host: "1.1.1.1:1234".to_string(),
+            mix_port: 1234,
+            clients_port: 1235,
+            location: "Neptune".to_string(),
+            version: "v1.2.3".to_string(),
+        };
+
+        // there's no gateway bonded
+        let info = mock_info("gateway-owner", &[]);
+        let res = try_update_gateway_config(deps.as_mut(), info.clone(), update.clone());
+        assert_eq!(
+            Err(MixnetContractError::NoAssociatedGatewayBond {
+                owner: Addr::unchecked("gateway-owner")
+            }),
+            res
+        );
+
+        let identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env,
+            "gateway-owner",
+            fixtures::good_gateway_pledge(),
+        );
+
+        // the proxy has to match
+        let proxy_info = mock_info("proxy", &[]);
+        let res = try_update_gateway_config_on_behalf(
+            deps.as_mut(),
+            proxy_info,
+            update.clone(),
+            "gateway-owner".to_string(),
+        );
+        assert_eq!(
+            Err(MixnetContractError::ProxyMismatch {
+                existing: "None".to_string(),
+                incoming: "proxy".to_string()
+            }),
+            res
+        );
+
+        let res = try_update_gateway_config(deps.as_mut(), info, update.clone());
+        assert!(res.is_ok());
+
+        let bond = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(bond.gateway.host, update.host);
+        assert_eq!(bond.gateway.mix_port, update.mix_port);
+        assert_eq!(bond.gateway.clients_port, update.clients_port);
+        assert_eq!(bond.gateway.location, update.location);
+        assert_eq!(bond.gateway.version, update.version);
+        // and the identity is unchanged
+        assert_eq!(bond.gateway.identity_key, identity);
+    }
+
+    #[test]
+    fn gateway_config_update_is_validated_like_bonding() {
+        let mut deps = test_helpers::init_contract();
+        let mut rng = test_helpers::test_rng();
+        let env = mock_env();
+
+        let identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env,
+            "gateway-owner",
+            fixtures::good_gateway_pledge(),
+        );
+
+        let valid = GatewayConfigUpdate {
+            host: "1.1.1.1".to_string(),
+            mix_port: 1789,
+            clients_port: 9000,
+            location: "Neptune".to_string(),
+            version: "v1.2.3".to_string(),
+        };
+
+        let invalid_updates = vec![
+            GatewayConfigUpdate {
+                host: "".to_string(),
+                ..valid.clone()
+            },
+            GatewayConfigUpdate {
+                host: "a".repeat(MAX_GATEWAY_HOST_LENGTH + 1),
+                ..valid.clone()
+            },
+            GatewayConfigUpdate {
+                host: "1.1.1.1 2.2.2.2".to_string(),
+                ..valid.clone()
+            },
+            GatewayConfigUpdate {
+                mix_port: 0,
+                ..valid.clone()
+            },
+            GatewayConfigUpdate {
+                clients_port: 1789,
+                ..valid.clone()
+            },
+            GatewayConfigUpdate {
+                version: "".to_string(),
+                ..valid.clone()
+            },
+            GatewayConfigUpdate {
+                version: "v".repeat(MAX_GATEWAY_VERSION_LENGTH + 1),
+                ..valid.clone()
+            },
+        ];
+
+        let info = mock_info("gateway-owner", &[]);
+        for update in invalid_updates {
+            let res = try_update_gateway_config(deps.as_mut(), info.clone(), update);
+            assert!(matches!(
+                res,
+                Err(MixnetContractError::InvalidGatewayConfig { .. })
+            ));
+        }
+
+        // nothing got changed
+        let bond = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(bond.gateway.host, fixtures::gateway_fixture().host);
+
+        // and the same rules apply when bonding
+        let res = try_add_gateway(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("another-owner", &fixtures::good_gateway_pledge()),
+            Gateway {
+                mix_port: 0,
+                ..fixtures::gateway_fixture()
+            },
+            "signature".to_string(),
+        );
+        assert!(matches!(
+            res,
+            Err(MixnetContractError::InvalidGatewayConfig { .. })
+        ));
+
+        let res = try_update_gateway_config(deps.as_mut(), info, valid);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn increasing_gateway_pledge() {
+        let mut deps = test_helpers::init_contract();
+        let mut rng = test_helpers::test_rng();
+        let env = mock_env();
+
+        let identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env.clone(),
+            "gateway-owner",
+            fixtures::good_gateway_pledge(),
+        );
+
+        let increase = coin(1000, TEST_COIN_DENOM);
+        let info = mock_info("gateway-owner", &[increase.clone()]);
+        try_increase_gateway_pledge(deps.as_mut(), env.clone(), info.clone()).unwrap();
+
+        // the change is only queued up
+        let bond = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(bond.pledge_amount, fixtures::good_gateway_pledge()[0]);
+        assert!(storage::PENDING_PLEDGE_CHANGES.has(deps.as_ref().storage, &identity));
+
+        // only a single change can be pending at a time
+        let res = try_increase_gateway_pledge(deps.as_mut(), env.clone(), info);
+        assert_eq!(
+            Err(MixnetContractError::PendingGatewayPledgeChange {
+                identity: identity.clone()
+            }),
+            res
+        );
+
+        // and the gateway can't unbond while it's pending
+        let res = try_remove_gateway(deps.as_mut(), mock_info("gateway-owner", &[]));
+        assert_eq!(
+            Err(MixnetContractError::PendingGatewayPledgeChange {
+                identity: identity.clone()
+            }),
+            res
+        );
+
+        // once the epoch event is resolved, the pledge is updated
+        pending_events::increase_gateway_pledge(
+            deps.as_mut(),
+            env.block.height,
+            identity.clone(),
+            increase.clone(),
+        )
+        .unwrap();
+
+        let bond = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        let mut expected = fixtures::good_gateway_pledge()[0].clone();
+        expected.amount += increase.amount;
+        assert_eq!(bond.pledge_amount, expected);
+        assert!(!storage::PENDING_PLEDGE_CHANGES.has(deps.as_ref().storage, &identity));
+
+        // and the gateway is free to leave with the full pledge
+        let res = try_remove_gateway(deps.as_mut(), mock_info("gateway-owner", &[])).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "gateway-owner".to_string(),
+                amount: vec![expected],
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn decreasing_gateway_pledge() {
+        let mut deps = test_helpers::init_contract();
+        let mut rng = test_helpers::test_rng();
+        let env = mock_env();
+
+        let minimum_pledge = minimum_gateway_pledge(deps.as_ref().storage).unwrap();
+        let mut pledge = minimum_pledge.clone();
+        pledge.amount += Uint128::new(50_000_000);
+
+        let identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env.clone(),
+            "gateway-owner",
+            vec![pledge.clone()],
+        );
+        let info = mock_info("gateway-owner", &[]);
+
+        // the remaining pledge can't go below the minimum
+        let too_much = coin(50_000_001, TEST_COIN_DENOM);
+        let res =
+            try_decrease_gateway_pledge(deps.as_mut(), env.clone(), info.clone(), too_much.clone());
+        assert_eq!(
+            Err(MixnetContractError::InvalidGatewayPledgeReduction {
+                current: pledge.clone(),
+                decrease_by: too_much,
+                minimum: minimum_pledge.clone(),
+            }),
+            res
+        );
+
+        // nor can the whole pledge be withdrawn this way
+        let res =
+            try_decrease_gateway_pledge(deps.as_mut(), env.clone(), info.clone(), pledge.clone());
+        assert!(matches!(
+            res,
+            Err(MixnetContractError::InvalidGatewayPledgeReduction { .. })
+        ));
+
+        let decrease = coin(50_000_000, TEST_COIN_DENOM);
+        try_decrease_gateway_pledge(deps.as_mut(), env.clone(), info.clone(), decrease.clone())
+            .unwrap();
+
+        // the change is only queued up
+        let bond = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(bond.pledge_amount, pledge);
+        assert!(storage::PENDING_PLEDGE_CHANGES.has(deps.as_ref().storage, &identity));
+
+        // only a single change can be pending at a time
+        let res =
+            try_decrease_gateway_pledge(deps.as_mut(), env.clone(), info.clone(), decrease.clone());
+        assert_eq!(
+            Err(MixnetContractError::PendingGatewayPledgeChange {
+                identity: identity.clone()
+            }),
+            res
+        );
+
+        // once the epoch event is resolved, the pledge is updated and the tokens are returned
+        let res = pending_events::decrease_gateway_pledge(
+            deps.as_mut(),
+            env.block.height,
+            identity.clone(),
+            decrease.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "gateway-owner".to_string(),
+                amount: vec![decrease],
+            }
+            .into()
+        );
+
+        let bond = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(bond.pledge_amount, minimum_pledge);
+        assert!(!storage::PENDING_PLEDGE_CHANGES.has(deps.as_ref().storage, &identity));
+    }
 }
@@ -3,27 +3,37 @@
 
 use crate::delegations;
 use crate::delegations::storage as delegations_storage;
+use crate::gateways::storage as gateways_storage;
 use crate::interval::helpers::change_interval_config;
 use crate::interval::storage;
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
 use crate::mixnodes::helpers::{cleanup_post_unbond_mixnode_storage, get_mixnode_details_by_id};
 use crate::mixnodes::storage as mixnodes_storage;
+use crate::penalties::storage as penalties_storage;
 use crate::rewards::storage as rewards_storage;
 use crate::support::helpers::send_to_proxy_or_owner;
-use cosmwasm_std::{wasm_execute, Addr, Coin, DepsMut, Env, Response};
+use cosmwasm_std::{coin, wasm_execute, Addr, Coin, DepsMut, Env, Response};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
-    new_active_set_update_event, new_delegation_event, new_delegation_on_unbonded_node_event,
-    new_mixnode_cost_params_update_event, new_mixnode_unbonding_event, new_pledge_increase_event,
+    new_active_set_update_event, new_delegation_event, new_delegation_on_unbonded_gateway_event,
+    new_delegation_on_unbonded_node_event, new_gateway_delegation_event,
+    new_gateway_pledge_decrease_event, new_gateway_pledge_increase_event,
+    new_gateway_undelegation_event, new_mixnode_cost_params_update_event,
+    new_mixnode_unbonding_event, new_operator_pledge_slashing_event, new_pledge_increase_event,
+    new_redelegation_event, new_redelegation_to_unbonded_node_event,
     new_rewarding_params_update_event, new_undelegation_event,
 };
+use mixnet_contract_common::helpers::IntoBaseDecimal;
 use mixnet_contract_common::mixnode::MixNodeCostParams;
 use mixnet_contract_common::pending_events::{
     PendingEpochEventData, PendingEpochEventKind, PendingIntervalEventData,
     PendingIntervalEventKind,
 };
 use mixnet_contract_common::reward_params::IntervalRewardingParamsUpdate;
-use mixnet_contract_common::{BlockHeight, Delegation, MixId};
+use mixnet_contract_common::rewarding::helpers::truncate_reward;
+use mixnet_contract_common::{
+    BlockHeight, Delegation, GatewayDelegation, IdentityKey, MixId, Percent,
+};
 use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 
 pub(crate) trait ContractExecutableEvent {
@@ -187,6 +197,154 @@ pub(crate) fn undelegate(
     Ok(response)
 }
 
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn redelegate(
+    deps: DepsMut<'_>,
+    env: &Env,
+    created_at: BlockHeight,
+    owner: Addr,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    // see if the source delegation still exists (the user might have undelegated earlier in this epoch)
+    let source_key = Delegation::generate_storage_key(from_mix_id, &owner, proxy.as_ref());
+    let source_delegation =
+        match delegations_storage::delegations().may_load(deps.storage, source_key.clone())? {
+            None => return Ok(Response::default()),
+            Some(delegation) => delegation,
+        };
+
+    // check if the target node still exists. If it doesn't, we leave the source delegation untouched
+    // so that it'd keep on earning rewards
+    let target_details = match get_mixnode_details_by_id(deps.storage, to_mix_id)? {
+        Some(details)
+            if details.rewarding_details.still_bonded()
+                && !details.bond_information.is_unbonding =>
+        {
+            details
+        }
+        _ => {
+            return Ok(
+                Response::new().add_event(new_redelegation_to_unbonded_node_event(
+                    &owner,
+                    &proxy,
+                    from_mix_id,
+                    to_mix_id,
+                )),
+            )
+        }
+    };
+
+    let mut source_rewarding =
+        rewards_storage::MIXNODE_REWARDING.may_load(deps.storage, from_mix_id)?.ok_or(MixnetContractError::InconsistentState {
+            comment: "mixnode rewarding got removed from the storage whilst there's still an existing delegation"
+                .into(),
+        })?;
+
+    // completely remove the delegation from the source node, alongside all the rewards it has earned
+    let source_total = source_rewarding.undelegate(&source_delegation)?;
+
+    // if the requested amount is larger than whatever the delegation is currently worth
+    // (say the rewards got withdrawn in the meantime), just move everything
+    let moved_amount = if amount.amount >= source_total.amount {
+        source_total.clone()
+    } else {
+        amount
+    };
+    let remaining = source_total.amount - moved_amount.amount;
+
+    // and put back whatever is not being moved as a fresh delegation
+    let updated_source = if remaining.is_zero() {
+        None
+    } else {
+        source_rewarding.add_base_delegation(remaining)?;
+        Some(Delegation::new(
+            owner.clone(),
+            from_mix_id,
+            source_rewarding.total_unit_reward,
+            coin(remaining.u128(), &source_total.denom),
+            env.block.height,
+            proxy.clone(),
+        ))
+    };
+
+    delegations_storage::delegations().replace(
+        deps.storage,
+        source_key,
+        updated_source.as_ref(),
+        Some(&source_delegation),
+    )?;
+    rewards_storage::MIXNODE_REWARDING.save(deps.storage, from_mix_id, &source_rewarding)?;
+
+    // the target side is identical to a normal delegation, i.e. if there's an existing delegation,
+    // withdraw it alongside its rewards and create a new delegation with the sum of both
+    let mut target_rewarding = target_details.rewarding_details;
+    let mut stored_target_amount = moved_amount.clone();
+
+    let target_key = Delegation::generate_storage_key(to_mix_id, &owner, proxy.as_ref());
+    let old_target = if let Some(existing_delegation) =
+        delegations_storage::delegations().may_load(deps.storage, target_key.clone())?
+    {
+        let og_with_reward = target_rewarding.undelegate(&existing_delegation)?;
+        stored_target_amount.amount += og_with_reward.amount;
+        Some(existing_delegation)
+    } else {
+        None
+    };
+
+    target_rewarding.add_base_delegation(stored_target_amount.amount)?;
+
+    let cosmos_event = new_redelegation_event(
+        created_at,
+        &owner,
+        &proxy,
+        &moved_amount,
+        from_mix_id,
+        to_mix_id,
+        target_rewarding.total_unit_reward,
+    );
+
+    let target_delegation = Delegation::new(
+        owner.clone(),
+        to_mix_id,
+        target_rewarding.total_unit_reward,
+        stored_target_amount,
+        env.block.height,
+        proxy.clone(),
+    );
+
+    delegations_storage::delegations().replace(
+        deps.storage,
+        target_key,
+        Some(&target_delegation),
+        old_target.as_ref(),
+    )?;
+    rewards_storage::MIXNODE_REWARDING.save(deps.storage, to_mix_id, &target_rewarding)?;
+
+    let mut response = Response::new().add_event(cosmos_event);
+
+    if let Some(proxy) = &proxy {
+        // we can only attempt to send the message to the vesting contract if the proxy IS the vesting contract
+        // otherwise, we don't care
+        let vesting_contract = mixnet_params_storage::vesting_contract_address(deps.storage)?;
+        if proxy == &vesting_contract {
+            let msg = VestingContractExecuteMsg::TrackRedelegation {
+                owner: owner.into_string(),
+                from_mix_id,
+                to_mix_id,
+                amount: moved_amount,
+            };
+
+            let track_redelegate_message = wasm_execute(proxy, &msg, vec![])?;
+            response = response.add_message(track_redelegate_message);
+        }
+    }
+
+    Ok(response)
+}
+
 pub(crate) fn unbond_mixnode(
     deps: DepsMut<'_>,
     env: &Env,
@@ -218,6 +376,8 @@ pub(crate) fn unbond_mixnode(
     // remove the bond and if there are no delegations left, also the rewarding information
     // decrement the associated layer count
     cleanup_post_unbond_mixnode_storage(deps.storage, env, &node_details)?;
+    // mix ids are never reused, so there's no point in keeping the node's penalty state around
+    penalties_storage::EXCLUDED_MIXNODES.remove(deps.storage, mix_id);
 
     let mut response = Response::new()
         .add_message(return_tokens)
@@ -295,6 +455,260 @@ pub(crate) fn increase_pledge(
     Ok(Response::new().add_event(new_pledge_increase_event(created_at, mix_id, &increase)))
 }
 
+pub(crate) fn slash_operator_pledge(
+    deps: DepsMut<'_>,
+    created_at: BlockHeight,
+    mix_id: MixId,
+    fraction: Percent,
+) -> Result<Response, MixnetContractError> {
+    // the node can't request to unbond while there's pending evidence against it and the slashing
+    // event is always pushed before any subsequent unbonding. however, be defensive about it anyway
+    let mix_details = match get_mixnode_details_by_id(deps.storage, mix_id)? {
+        Some(details) if details.rewarding_details.still_bonded() => details,
+        _ => return Ok(Response::default()),
+    };
+
+    let mut updated_bond = mix_details.bond_information.clone();
+    let mut updated_rewarding = mix_details.rewarding_details;
+
+    // only the pledge itself is slashed: any pending operator rewards alongside all the delegations
+    // (and their rewards) remain untouched, so the unit reward of the node doesn't change
+    let pledge = &mix_details.bond_information.original_pledge;
+    let slashed = truncate_reward(
+        pledge.amount.into_base_decimal()? * fraction.value(),
+        &pledge.denom,
+    );
+
+    // make sure to decrease both values in order to preserve the `operator >= original_pledge` invariant
+    updated_bond.original_pledge.amount -= slashed.amount;
+    updated_rewarding.decrease_operator_decimal(slashed.amount.into_base_decimal()?)?;
+
+    mixnodes_storage::mixnode_bonds().replace(
+        deps.storage,
+        mix_id,
+        Some(&updated_bond),
+        Some(&mix_details.bond_information),
+    )?;
+    rewards_storage::MIXNODE_REWARDING.save(deps.storage, mix_id, &updated_rewarding)?;
+
+    // the slashed tokens never leave the contract, they just go back into the reward pool
+    rewards_storage::return_to_reward_pool(deps.storage, slashed.amount.into_base_decimal()?)?;
+
+    Ok(
+        Response::new().add_event(new_operator_pledge_slashing_event(
+            created_at, mix_id, &slashed,
+        )),
+    )
+}
+
+pub(crate) fn increase_gateway_pledge(
+    deps: DepsMut<'_>,
+    created_at: BlockHeight,
+    identity: IdentityKey,
+    increase: Coin,
+) -> Result<Response, MixnetContractError> {
+    // note: we have already validated the amount to know it has the correct denomination
+
+    // the target gateway MUST exist - we have checked it at the time of putting this event onto the queue
+    // and it can't unbond while there's a pending pledge change
+    let existing_bond = gateways_storage::gateways()
+        .may_load(deps.storage, &identity)?
+        .ok_or(MixnetContractError::InconsistentState {
+            comment:
+                "gateway getting processed to increase its pledge doesn't exist in the storage"
+                    .into(),
+        })?;
+
+    let mut gateway_rewarding = rewards_storage::GATEWAY_REWARDING.load(deps.storage, &identity)?;
+    gateway_rewarding.increase_operator_uint128(increase.amount)?;
+
+    let mut updated_bond = existing_bond.clone();
+    updated_bond.pledge_amount.amount += increase.amount;
+
+    gateways_storage::gateways().replace(
+        deps.storage,
+        &identity,
+        Some(&updated_bond),
+        Some(&existing_bond),
+    )?;
+    rewards_storage::GATEWAY_REWARDING.save(deps.storage, &identity, &gateway_rewarding)?;
+    gateways_storage::PENDING_PLEDGE_CHANGES.remove(deps.storage, &identity);
+
+    Ok(Response::new().add_event(new_gateway_pledge_increase_event(
+        created_at, &identity, &increase,
+    )))
+}
+
+pub(crate) fn decrease_gateway_pledge(
+    deps: DepsMut<'_>,
+    created_at: BlockHeight,
+    identity: IdentityKey,
+    decrease_by: Coin,
+) -> Result<Response, MixnetContractError> {
+    // the target gateway MUST exist - we have checked it at the time of putting this event onto the queue
+    // and it can't unbond while there's a pending pledge change
+    let existing_bond = gateways_storage::gateways()
+        .may_load(deps.storage, &identity)?
+        .ok_or(MixnetContractError::InconsistentState {
+            comment:
+                "gateway getting processed to decrease its pledge doesn't exist in the storage"
+                    .into(),
+        })?;
+
+    let mut gateway_rewarding = rewards_storage::GATEWAY_REWARDING.load(deps.storage, &identity)?;
+    gateway_rewarding.decrease_operator_uint128(decrease_by.amount)?;
+
+    let mut updated_bond = existing_bond.clone();
+    updated_bond.pledge_amount.amount -= decrease_by.amount;
+
+    gateways_storage::gateways().replace(
+        deps.storage,
+        &identity,
+        Some(&updated_bond),
+        Some(&existing_bond),
+    )?;
+    rewards_storage::GATEWAY_REWARDING.save(deps.storage, &identity, &gateway_rewarding)?;
+    gateways_storage::PENDING_PLEDGE_CHANGES.remove(deps.storage, &identity);
+
+    let proxy = &existing_bond.proxy;
+    let owner = &existing_bond.owner;
+    let return_tokens = send_to_proxy_or_owner(proxy, owner, vec![decrease_by.clone()]);
+
+    let cosmos_event = new_gateway_pledge_decrease_event(created_at, &identity, &decrease_by);
+    let mut response = Response::new()
+        .add_message(return_tokens)
+        .add_event(cosmos_event);
+
+    if let Some(proxy) = &proxy {
+        // we can only attempt to send the message to the vesting contract if the proxy IS the vesting contract
+        // otherwise, we don't care
+        let vesting_contract = mixnet_params_storage::vesting_contract_address(deps.storage)?;
+        if proxy == &vesting_contract {
+            let msg = VestingContractExecuteMsg::TrackDecreaseGatewayPledge {
+                owner: owner.clone().into_string(),
+                amount: decrease_by,
+            };
+
+            let track_decrease_message = wasm_execute(proxy, &msg, vec![])?;
+            response = response.add_message(track_decrease_message);
+        }
+    }
+
+    Ok(response)
+}
+
+pub(crate) fn delegate_to_gateway(
+    deps: DepsMut<'_>,
+    env: &Env,
+    created_at: BlockHeight,
+    owner: Addr,
+    gateway_identity: IdentityKey,
+    amount: Coin,
+) -> Result<Response, MixnetContractError> {
+    // check if the target gateway still exists (it might have unbonded between this event getting created
+    // and being executed). If not, just return the tokens back to the delegator
+    if !gateways_storage::gateways().has(deps.storage, &gateway_identity) {
+        let return_tokens = send_to_proxy_or_owner(&None, &owner, vec![amount]);
+        return Ok(Response::new().add_message(return_tokens).add_event(
+            new_delegation_on_unbonded_gateway_event(&owner, &gateway_identity),
+        ));
+    }
+
+    let cosmos_event = new_gateway_delegation_event(created_at, &owner, &amount, &gateway_identity);
+
+    let mut gateway_rewarding =
+        rewards_storage::GATEWAY_REWARDING.load(deps.storage, &gateway_identity)?;
+
+    // if there's an existing delegation, then withdraw the full reward and create a new delegation
+    // with the sum of both
+    let storage_key = (gateway_identity.clone(), owner.clone());
+    let old_delegation =
+        delegations_storage::gateway_delegations().may_load(deps.storage, storage_key.clone())?;
+    let mut stored_amount = amount;
+    if let Some(existing_delegation) = &old_delegation {
+        let existing_with_reward = gateway_rewarding.undelegate(existing_delegation)?;
+        stored_amount.amount += existing_with_reward.amount;
+    }
+
+    gateway_rewarding.add_base_delegation(stored_amount.amount)?;
+
+    let delegation = GatewayDelegation::new(
+        owner,
+        gateway_identity.clone(),
+        gateway_rewarding.full_reward_ratio(),
+        stored_amount,
+        env.block.height,
+    );
+
+    // save on reading since `.save()` would have attempted to read old data that we already have on hand
+    delegations_storage::gateway_delegations().replace(
+        deps.storage,
+        storage_key,
+        Some(&delegation),
+        old_delegation.as_ref(),
+    )?;
+    rewards_storage::GATEWAY_REWARDING.save(deps.storage, &gateway_identity, &gateway_rewarding)?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
+pub(crate) fn undelegate_from_gateway(
+    deps: DepsMut<'_>,
+    created_at: BlockHeight,
+    owner: Addr,
+    gateway_identity: IdentityKey,
+) -> Result<Response, MixnetContractError> {
+    // see if the delegation still exists (in case of impatient user who decided to send multiple
+    // undelegation requests in an epoch)
+    let storage_key = (gateway_identity.clone(), owner.clone());
+    let delegation = match delegations_storage::gateway_delegations()
+        .may_load(deps.storage, storage_key.clone())?
+    {
+        None => return Ok(Response::default()),
+        Some(delegation) => delegation,
+    };
+
+    // the delegator gets back its delegation alongside all the rewards it has accumulated
+    let tokens_to_return =
+        match rewards_storage::GATEWAY_REWARDING.may_load(deps.storage, &gateway_identity)? {
+            Some(mut gateway_rewarding) => {
+                let tokens = gateway_rewarding.undelegate(&delegation)?;
+
+                // if this was the last delegation towards an already unbonded gateway, purge its details
+                if gateway_rewarding.unique_delegations == 0
+                    && !gateways_storage::gateways().has(deps.storage, &gateway_identity)
+                {
+                    rewards_storage::GATEWAY_REWARDING.remove(deps.storage, &gateway_identity);
+                } else {
+                    rewards_storage::GATEWAY_REWARDING.save(
+                        deps.storage,
+                        &gateway_identity,
+                        &gateway_rewarding,
+                    )?;
+                }
+                tokens
+            }
+            None => delegation.amount.clone(),
+        };
+
+    delegations_storage::gateway_delegations().replace(
+        deps.storage,
+        storage_key,
+        None,
+        Some(&delegation),
+    )?;
+
+    let return_tokens = send_to_proxy_or_owner(&None, &owner, vec![tokens_to_return.clone()]);
+    Ok(Response::new()
+        .add_message(return_tokens)
+        .add_event(new_gateway_undelegation_event(
+            created_at,
+            &owner,
+            &tokens_to_return,
+            &gateway_identity,
+        )))
+}
+
 impl ContractExecutableEvent for PendingEpochEventData {
     fn execute(self, deps: DepsMut<'_>, env: &Env) -> Result<Response, MixnetContractError> {
         // note that the basic validation on all those events was already performed before
@@ -311,15 +725,50 @@ impl ContractExecutableEvent for PendingEpochEventData {
                 mix_id,
                 proxy,
             } => undelegate(deps, self.created_at, owner, mix_id, proxy),
+            PendingEpochEventKind::Redelegate {
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+                proxy,
+            } => redelegate(
+                deps,
+                env,
+                self.created_at,
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+                proxy,
+            ),
             PendingEpochEventKind::PledgeMore { mix_id, amount } => {
                 increase_pledge(deps, self.created_at, mix_id, amount)
             }
             PendingEpochEventKind::UnbondMixnode { mix_id } => {
                 unbond_mixnode(deps, env, self.created_at, mix_id)
             }
+            PendingEpochEventKind::SlashOperatorPledge { mix_id, fraction } => {
+                slash_operator_pledge(deps, self.created_at, mix_id, fraction)
+            }
             PendingEpochEventKind::UpdateActiveSetSize { new_size } => {
                 update_active_set_size(deps, self.created_at, new_size)
             }
+            PendingEpochEventKind::PledgeMoreGateway { identity, amount } => {
+                increase_gateway_pledge(deps, self.created_at, identity, amount)
+            }
+            PendingEpochEventKind::DecreaseGatewayPledge {
+                identity,
+                decrease_by,
+            } => decrease_gateway_pledge(deps, self.created_at, identity, decrease_by),
+            PendingEpochEventKind::DelegateToGateway {
+                owner,
+                gateway_identity,
+                amount,
+            } => delegate_to_gateway(deps, env, self.created_at, owner, gateway_identity, amount),
+            PendingEpochEventKind::UndelegateFromGateway {
+                owner,
+                gateway_identity,
+            } => undelegate_from_gateway(deps, self.created_at, owner, gateway_identity),
         }
     }
 }
@@ -1487,6 +1936,9 @@ mod tests {
             active_set_work_factor: None,
             interval_pool_emission: None,
             rewarded_set_size: None,
+            gateway_interval_pool_emission: None,
+            nominal_gateways: None,
+            gateway_profit_margin: None,
         };
 
         let res = update_rewarding_params(test.deps_mut(), 123, update);
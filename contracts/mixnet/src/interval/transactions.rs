@@ -6,6 +6,7 @@ use crate::interval::helpers::change_interval_config;
 use crate::interval::pending_events::ContractExecutableEvent;
 use crate::interval::storage::push_new_interval_event;
 use crate::mixnodes::transactions::update_mixnode_layer;
+use crate::penalties::storage as penalties_storage;
 use crate::rewards;
 use crate::rewards::storage as rewards_storage;
 use crate::support::helpers::{ensure_is_authorized, ensure_is_owner};
@@ -203,7 +204,7 @@ pub fn try_advance_epoch(
     mut deps: DepsMut<'_>,
     env: Env,
     info: MessageInfo,
-    layer_assignments: Vec<LayerAssignment>,
+    mut layer_assignments: Vec<LayerAssignment>,
     expected_active_set_size: u32,
 ) -> Result<Response, MixnetContractError> {
     // Only rewarding validator can attempt to advance epoch
@@ -248,6 +249,20 @@ pub fn try_advance_epoch(
     }
 
     let updated_interval = current_interval.advance_epoch();
+    let new_epoch_id = updated_interval.current_epoch_absolute_id();
+
+    // penalised nodes are not allowed in the rewarded set regardless of what the rewarding validator
+    // has decided, so drop them here (note that nobody takes their place, so the rewarded set,
+    // and possibly the active set, is going to be smaller for this epoch)
+    penalties_storage::remove_expired_exclusions(deps.storage, new_epoch_id)?;
+    let mut excluded = Vec::new();
+    for assignment in &layer_assignments {
+        if penalties_storage::is_excluded(deps.storage, assignment.mix_id(), new_epoch_id)? {
+            excluded.push(assignment.mix_id())
+        }
+    }
+    layer_assignments.retain(|a| !excluded.contains(&a.mix_id()));
+
     let num_nodes = layer_assignments.len();
 
     let new_rewarded_set = layer_assignments.iter().map(|l| l.mix_id()).collect();
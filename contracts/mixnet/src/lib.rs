@@ -12,6 +12,8 @@ mod gateways;
 mod interval;
 mod mixnet_contract_settings;
 mod mixnodes;
+mod penalties;
+mod queued_migrations;
 mod rewards;
 mod support;
 
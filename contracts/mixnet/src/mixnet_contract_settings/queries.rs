@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::storage;
-use cosmwasm_std::{Deps, StdResult};
-use mixnet_contract_common::{ContractBuildInformation, ContractState, ContractStateParams};
+use cosmwasm_std::{Deps, Order, StdResult};
+use mixnet_contract_common::{
+    ContractBuildInformation, ContractState, ContractStateParams, TopologySignersResponse,
+};
 
 pub(crate) fn query_contract_state(deps: Deps<'_>) -> StdResult<ContractState> {
     storage::CONTRACT_STATE.load(deps.storage)
@@ -21,6 +23,15 @@ pub(crate) fn query_rewarding_validator_address(deps: Deps<'_>) -> StdResult<Str
         .map(|settings| settings.rewarding_validator_address.to_string())
 }
 
+pub(crate) fn query_topology_signers(deps: Deps<'_>) -> StdResult<TopologySignersResponse> {
+    // there's only going to be a handful of signers, so there's no need for pagination
+    let signers = storage::TOPOLOGY_SIGNERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    Ok(TopologySignersResponse { signers })
+}
+
 pub(crate) fn query_contract_version() -> ContractBuildInformation {
     // as per docs
     // env! macro will expand to the value of the named environment variable at
@@ -1,15 +1,20 @@
 // Copyright 2021-2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::constants::CONTRACT_STATE_KEY;
+use crate::constants::{CONTRACT_STATE_KEY, TOPOLOGY_SIGNERS_NAMESPACE};
 use cosmwasm_std::{Addr, Storage};
 use cosmwasm_std::{Coin, StdResult};
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 use mixnet_contract_common::error::MixnetContractError;
-use mixnet_contract_common::ContractState;
+use mixnet_contract_common::{BlockHeight, ContractState, IdentityKeyRef};
 
 pub(crate) const CONTRACT_STATE: Item<'_, ContractState> = Item::new(CONTRACT_STATE_KEY);
 
+// identity keys of nym-apis trusted to sign topology documents alongside the height at which
+// they have been added
+pub(crate) const TOPOLOGY_SIGNERS: Map<'_, IdentityKeyRef<'_>, BlockHeight> =
+    Map::new(TOPOLOGY_SIGNERS_NAMESPACE);
+
 pub fn rewarding_validator_address(storage: &dyn Storage) -> Result<Addr, MixnetContractError> {
     Ok(CONTRACT_STATE
         .load(storage)
@@ -2,14 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::storage;
-use cosmwasm_std::DepsMut;
+use crate::support::helpers::decode_ed25519_identity_key;
 use cosmwasm_std::MessageInfo;
 use cosmwasm_std::Response;
+use cosmwasm_std::{DepsMut, Env};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
     new_rewarding_validator_address_update_event, new_settings_update_event,
+    new_topology_signer_addition_event, new_topology_signer_removal_event,
 };
-use mixnet_contract_common::ContractStateParams;
+use mixnet_contract_common::{ContractStateParams, IdentityKey};
 
 pub fn try_update_rewarding_validator_address(
     deps: DepsMut<'_>,
@@ -56,6 +58,46 @@ pub(crate) fn try_update_contract_settings(
     Ok(response)
 }
 
+pub(crate) fn try_add_topology_signer(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    identity_key: IdentityKey,
+) -> Result<Response, MixnetContractError> {
+    let state = storage::CONTRACT_STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(MixnetContractError::Unauthorized);
+    }
+
+    // make sure the key is at least well-formed so that clients wouldn't choke on it
+    decode_ed25519_identity_key(&identity_key)?;
+
+    if storage::TOPOLOGY_SIGNERS.has(deps.storage, &identity_key) {
+        return Err(MixnetContractError::DuplicateTopologySigner { identity_key });
+    }
+    storage::TOPOLOGY_SIGNERS.save(deps.storage, &identity_key, &env.block.height)?;
+
+    Ok(Response::new().add_event(new_topology_signer_addition_event(&identity_key)))
+}
+
+pub(crate) fn try_remove_topology_signer(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    identity_key: IdentityKey,
+) -> Result<Response, MixnetContractError> {
+    let state = storage::CONTRACT_STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(MixnetContractError::Unauthorized);
+    }
+
+    if !storage::TOPOLOGY_SIGNERS.has(deps.storage, &identity_key) {
+        return Err(MixnetContractError::NotATopologySigner { identity_key });
+    }
+    storage::TOPOLOGY_SIGNERS.remove(deps.storage, &identity_key);
+
+    Ok(Response::new().add_event(new_topology_signer_removal_event(&identity_key)))
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -64,7 +106,7 @@ pub mod tests {
     use crate::mixnet_contract_settings::storage::rewarding_denom;
     use crate::mixnet_contract_settings::transactions::try_update_contract_settings;
     use crate::support::tests::test_helpers;
-    use cosmwasm_std::testing::mock_info;
+    use cosmwasm_std::testing::{mock_env, mock_info};
     use cosmwasm_std::{Addr, Coin, Response, Uint128};
     use mixnet_contract_common::error::MixnetContractError;
     use mixnet_contract_common::ContractStateParams;
@@ -172,4 +214,67 @@ pub mod tests {
         // let res = try_update_contract_settings(deps.as_mut(), info, new_params);
         // assert_eq!(Err(MixnetContractError::ZeroActiveSet), res);
     }
+
+    #[test]
+    fn adding_and_removing_topology_signers() {
+        let mut deps = test_helpers::init_contract();
+        let env = mock_env();
+        let identity = "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7".to_string();
+
+        // only the owner can add signers
+        let info = mock_info("not-the-creator", &[]);
+        let res = try_add_topology_signer(deps.as_mut(), env.clone(), info, identity.clone());
+        assert_eq!(res, Err(MixnetContractError::Unauthorized));
+
+        // and the key has to be valid
+        let info = mock_info("creator", &[]);
+        let res = try_add_topology_signer(deps.as_mut(), env.clone(), info, "foomp".to_string());
+        assert!(matches!(
+            res,
+            Err(MixnetContractError::MalformedEd25519IdentityKey(..))
+        ));
+
+        let info = mock_info("creator", &[]);
+        let res = try_add_topology_signer(deps.as_mut(), env.clone(), info, identity.clone());
+        assert_eq!(
+            res,
+            Ok(Response::new().add_event(new_topology_signer_addition_event(&identity)))
+        );
+        assert_eq!(
+            storage::TOPOLOGY_SIGNERS
+                .load(deps.as_ref().storage, &identity)
+                .unwrap(),
+            env.block.height
+        );
+
+        let info = mock_info("creator", &[]);
+        let res = try_add_topology_signer(deps.as_mut(), env, info, identity.clone());
+        assert_eq!(
+            res,
+            Err(MixnetContractError::DuplicateTopologySigner {
+                identity_key: identity.clone()
+            })
+        );
+
+        let info = mock_info("not-the-creator", &[]);
+        let res = try_remove_topology_signer(deps.as_mut(), info, identity.clone());
+        assert_eq!(res, Err(MixnetContractError::Unauthorized));
+
+        let info = mock_info("creator", &[]);
+        let res = try_remove_topology_signer(deps.as_mut(), info, identity.clone());
+        assert_eq!(
+            res,
+            Ok(Response::new().add_event(new_topology_signer_removal_event(&identity)))
+        );
+        assert!(!storage::TOPOLOGY_SIGNERS.has(deps.as_ref().storage, &identity));
+
+        let info = mock_info("creator", &[]);
+        let res = try_remove_topology_signer(deps.as_mut(), info, identity.clone());
+        assert_eq!(
+            res,
+            Err(MixnetContractError::NotATopologySigner {
+                identity_key: identity
+            })
+        );
+    }
 }
@@ -9,6 +9,7 @@ use crate::mixnet_contract_settings::storage::rewarding_denom;
 use crate::mixnodes::helpers::{
     get_mixnode_details_by_owner, must_get_mixnode_bond_by_owner, save_new_mixnode,
 };
+use crate::penalties::storage as penalties_storage;
 use crate::support::helpers::{
     ensure_bonded, ensure_is_authorized, ensure_no_existing_bond, ensure_proxy_match,
     validate_node_identity_signature, validate_pledge,
@@ -235,6 +236,19 @@ pub(crate) fn _try_remove_mixnode(
     ensure_proxy_match(&proxy, &existing_bond.proxy)?;
     ensure_bonded(&existing_bond)?;
 
+    // don't let the operator run away with the pledge before the evidence against the node is reviewed
+    // (unless it hasn't been reviewed in time, in which case it no longer blocks the unbonding)
+    penalties_storage::expire_pending_evidence(
+        deps.storage,
+        existing_bond.mix_id,
+        env.block.height,
+    )?;
+    if penalties_storage::has_pending_evidence(deps.storage, existing_bond.mix_id)? {
+        return Err(MixnetContractError::MixnodeUnderInvestigation {
+            mix_id: existing_bond.mix_id,
+        });
+    }
+
     // set `is_unbonding` field
     let mut updated_bond = existing_bond.clone();
     updated_bond.is_unbonding = true;
@@ -0,0 +1,75 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::storage;
+use crate::constants::{
+    EVIDENCE_DEFAULT_RETRIEVAL_LIMIT, EVIDENCE_MAX_RETRIEVAL_LIMIT,
+    EXCLUDED_MIXNODES_DEFAULT_RETRIEVAL_LIMIT, EXCLUDED_MIXNODES_MAX_RETRIEVAL_LIMIT,
+};
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+use mixnet_contract_common::penalties::{
+    EvidenceId, EvidenceRecord, ExcludedMixnode, PagedEvidenceResponse,
+    PagedExcludedMixnodesResponse, PenaltyMonitorsResponse,
+};
+use mixnet_contract_common::MixId;
+
+pub(crate) fn query_penalty_monitors(deps: Deps<'_>) -> StdResult<PenaltyMonitorsResponse> {
+    // similarly to the topology signers, there's only going to be a handful of monitors
+    let monitors = storage::PENALTY_MONITORS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    Ok(PenaltyMonitorsResponse { monitors })
+}
+
+pub(crate) fn query_misbehaviour_evidence(
+    deps: Deps<'_>,
+    evidence_id: EvidenceId,
+) -> StdResult<Option<EvidenceRecord>> {
+    storage::EVIDENCE.may_load(deps.storage, evidence_id)
+}
+
+pub(crate) fn query_misbehaviour_evidence_paged(
+    deps: Deps<'_>,
+    start_after: Option<EvidenceId>,
+    limit: Option<u32>,
+) -> StdResult<PagedEvidenceResponse> {
+    let limit = limit
+        .unwrap_or(EVIDENCE_DEFAULT_RETRIEVAL_LIMIT)
+        .min(EVIDENCE_MAX_RETRIEVAL_LIMIT) as usize;
+
+    let start = start_after.map(Bound::exclusive);
+
+    let evidence = storage::EVIDENCE
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, record)| record))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let start_next_after = evidence.last().map(|record| record.id);
+
+    Ok(PagedEvidenceResponse::new(evidence, start_next_after))
+}
+
+pub(crate) fn query_excluded_mixnodes_paged(
+    deps: Deps<'_>,
+    start_after: Option<MixId>,
+    limit: Option<u32>,
+) -> StdResult<PagedExcludedMixnodesResponse> {
+    let limit = limit
+        .unwrap_or(EXCLUDED_MIXNODES_DEFAULT_RETRIEVAL_LIMIT)
+        .min(EXCLUDED_MIXNODES_MAX_RETRIEVAL_LIMIT) as usize;
+
+    let start = start_after.map(Bound::exclusive);
+
+    let nodes = storage::EXCLUDED_MIXNODES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(mix_id, exclusion)| ExcludedMixnode { mix_id, exclusion }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let start_next_after = nodes.last().map(|node| node.mix_id);
+
+    Ok(PagedExcludedMixnodesResponse::new(nodes, start_next_after))
+}
@@ -0,0 +1,146 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::constants::{
+    EVIDENCE_ID_COUNTER_KEY, EVIDENCE_NAMESPACE, EXCLUDED_MIXNODES_NAMESPACE,
+    PENALTY_MONITORS_NAMESPACE, PENDING_EVIDENCE_NAMESPACE, SUBMITTED_EVIDENCE_NONCES_NAMESPACE,
+};
+use cosmwasm_std::{Order, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+use mixnet_contract_common::penalties::{
+    EvidenceId, EvidenceRecord, EvidenceStatus, RewardedSetExclusion,
+};
+use mixnet_contract_common::{BlockHeight, EpochId, IdentityKeyRef, MixId};
+
+// identity keys of monitors trusted to sign misbehaviour evidence alongside the height at which
+// they have been added
+pub(crate) const PENALTY_MONITORS: Map<'_, IdentityKeyRef<'_>, BlockHeight> =
+    Map::new(PENALTY_MONITORS_NAMESPACE);
+
+pub(crate) const EVIDENCE_ID_COUNTER: Item<'_, EvidenceId> = Item::new(EVIDENCE_ID_COUNTER_KEY);
+pub(crate) const EVIDENCE: Map<'_, EvidenceId, EvidenceRecord> = Map::new(EVIDENCE_NAMESPACE);
+
+// evidence that has not yet been reviewed, grouped by the node it concerns
+pub(crate) const PENDING_EVIDENCE: Map<'_, (MixId, EvidenceId), ()> =
+    Map::new(PENDING_EVIDENCE_NAMESPACE);
+
+// nonces of all evidence ever submitted by given monitor alongside the id assigned to the evidence.
+// they are never removed so that resolved evidence could not be replayed
+pub(crate) const SUBMITTED_EVIDENCE_NONCES: Map<'_, (IdentityKeyRef<'_>, u64), EvidenceId> =
+    Map::new(SUBMITTED_EVIDENCE_NONCES_NAMESPACE);
+
+pub(crate) const EXCLUDED_MIXNODES: Map<'_, MixId, RewardedSetExclusion> =
+    Map::new(EXCLUDED_MIXNODES_NAMESPACE);
+
+pub(crate) fn next_evidence_id_counter(store: &mut dyn Storage) -> StdResult<EvidenceId> {
+    let id: EvidenceId = EVIDENCE_ID_COUNTER.may_load(store)?.unwrap_or_default() + 1;
+    EVIDENCE_ID_COUNTER.save(store, &id)?;
+    Ok(id)
+}
+
+pub(crate) fn pending_evidence_ids(
+    store: &dyn Storage,
+    mix_id: MixId,
+) -> StdResult<Vec<EvidenceId>> {
+    PENDING_EVIDENCE
+        .prefix(mix_id)
+        .keys(store, None, None, Order::Ascending)
+        .collect()
+}
+
+pub(crate) fn has_pending_evidence(store: &dyn Storage, mix_id: MixId) -> StdResult<bool> {
+    Ok(PENDING_EVIDENCE
+        .prefix(mix_id)
+        .keys(store, None, None, Order::Ascending)
+        .next()
+        .transpose()?
+        .is_some())
+}
+
+pub(crate) fn expire_pending_evidence(
+    store: &mut dyn Storage,
+    mix_id: MixId,
+    current_height: BlockHeight,
+) -> StdResult<()> {
+    for evidence_id in pending_evidence_ids(store, mix_id)? {
+        let mut record = EVIDENCE.load(store, evidence_id)?;
+        if record.is_expired(current_height) {
+            record.status = EvidenceStatus::Expired;
+            EVIDENCE.save(store, evidence_id, &record)?;
+            PENDING_EVIDENCE.remove(store, (mix_id, evidence_id));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn is_excluded(
+    store: &dyn Storage,
+    mix_id: MixId,
+    absolute_epoch_id: EpochId,
+) -> StdResult<bool> {
+    Ok(EXCLUDED_MIXNODES
+        .may_load(store, mix_id)?
+        .map(|exclusion| exclusion.is_active(absolute_epoch_id))
+        .unwrap_or_default())
+}
+
+pub(crate) fn remove_expired_exclusions(
+    store: &mut dyn Storage,
+    absolute_epoch_id: EpochId,
+) -> StdResult<()> {
+    let expired = EXCLUDED_MIXNODES
+        .range(store, None, None, Order::Ascending)
+        .filter_map(|res| match res {
+            Ok((mix_id, exclusion)) if !exclusion.is_active(absolute_epoch_id) => Some(Ok(mix_id)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for mix_id in expired {
+        EXCLUDED_MIXNODES.remove(store, mix_id)
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn next_evidence_id() {
+        let mut deps = mock_dependencies();
+
+        for i in 1u32..1000 {
+            assert_eq!(i, next_evidence_id_counter(deps.as_mut().storage).unwrap());
+        }
+    }
+
+    #[test]
+    fn checking_exclusion() {
+        let mut deps = mock_dependencies();
+        assert!(!is_excluded(deps.as_ref().storage, 1, 10).unwrap());
+
+        EXCLUDED_MIXNODES
+            .save(
+                deps.as_mut().storage,
+                1,
+                &RewardedSetExclusion::Until { epoch: 12 },
+            )
+            .unwrap();
+        EXCLUDED_MIXNODES
+            .save(
+                deps.as_mut().storage,
+                2,
+                &RewardedSetExclusion::Jailed { since_epoch: 5 },
+            )
+            .unwrap();
+
+        assert!(is_excluded(deps.as_ref().storage, 1, 10).unwrap());
+        assert!(is_excluded(deps.as_ref().storage, 1, 11).unwrap());
+        assert!(!is_excluded(deps.as_ref().storage, 1, 12).unwrap());
+        assert!(is_excluded(deps.as_ref().storage, 2, 1000).unwrap());
+        assert!(!is_excluded(deps.as_ref().storage, 3, 10).unwrap());
+    }
+}
@@ -0,0 +1,925 @@
+// Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::storage;
+use crate::constants::PENDING_EVIDENCE_VALIDITY_BLOCKS;
+use crate::interval::storage as interval_storage;
+use crate::mixnodes::storage as mixnodes_storage;
+use crate::support::helpers::{decode_ed25519_identity_key, ensure_is_owner, validate_signature};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use mixnet_contract_common::error::MixnetContractError;
+use mixnet_contract_common::events::{
+    new_evidence_dismissal_event, new_jail_release_event,
+    new_misbehaviour_evidence_submission_event, new_penalty_application_event,
+    new_penalty_monitor_addition_event, new_penalty_monitor_removal_event,
+};
+use mixnet_contract_common::penalties::{
+    EvidenceId, EvidenceRecord, EvidenceStatus, MisbehaviourEvidence, MisbehaviourKind, Penalty,
+    RewardedSetExclusion,
+};
+use mixnet_contract_common::pending_events::PendingEpochEventKind;
+use mixnet_contract_common::{EpochId, IdentityKey, MixId};
+
+pub(crate) fn try_add_penalty_monitor(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    identity_key: IdentityKey,
+) -> Result<Response, MixnetContractError> {
+    ensure_is_owner(info.sender, deps.storage)?;
+
+    // make sure the key is at least well-formed so that we could verify the signatures later on
+    decode_ed25519_identity_key(&identity_key)?;
+
+    if storage::PENALTY_MONITORS.has(deps.storage, &identity_key) {
+        return Err(MixnetContractError::DuplicatePenaltyMonitor { identity_key });
+    }
+    storage::PENALTY_MONITORS.save(deps.storage, &identity_key, &env.block.height)?;
+
+    Ok(Response::new().add_event(new_penalty_monitor_addition_event(&identity_key)))
+}
+
+pub(crate) fn try_remove_penalty_monitor(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    identity_key: IdentityKey,
+) -> Result<Response, MixnetContractError> {
+    ensure_is_owner(info.sender, deps.storage)?;
+
+    if !storage::PENALTY_MONITORS.has(deps.storage, &identity_key) {
+        return Err(MixnetContractError::NotAPenaltyMonitor { identity_key });
+    }
+    storage::PENALTY_MONITORS.remove(deps.storage, &identity_key);
+
+    Ok(Response::new().add_event(new_penalty_monitor_removal_event(&identity_key)))
+}
+
+fn validate_evidence_kind(
+    kind: &MisbehaviourKind,
+    current_epoch: EpochId,
+) -> Result<(), MixnetContractError> {
+    match kind {
+        MisbehaviourKind::PersistentZeroPerformance {
+            first_epoch,
+            last_epoch,
+            monitor_runs,
+        } => {
+            if first_epoch > last_epoch {
+                return Err(MixnetContractError::MalformedEvidence {
+                    reason: format!(
+                        "the first epoch ({}) is after the last epoch ({})",
+                        first_epoch, last_epoch
+                    ),
+                });
+            }
+            if *last_epoch > current_epoch {
+                return Err(MixnetContractError::MalformedEvidence {
+                    reason: format!(
+                        "the last epoch ({}) has not yet happened (current epoch: {})",
+                        last_epoch, current_epoch
+                    ),
+                });
+            }
+            if *monitor_runs == 0 {
+                return Err(MixnetContractError::MalformedEvidence {
+                    reason: "no monitor runs were performed".into(),
+                });
+            }
+        }
+        MisbehaviourKind::DuplicateIdentityKey { observed_host } => {
+            if observed_host.is_empty() {
+                return Err(MixnetContractError::MalformedEvidence {
+                    reason: "the observed host is empty".into(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn try_submit_misbehaviour_evidence(
+    deps: DepsMut<'_>,
+    env: Env,
+    evidence: MisbehaviourEvidence,
+    monitor_identity: IdentityKey,
+    signature: String,
+) -> Result<Response, MixnetContractError> {
+    if !storage::PENALTY_MONITORS.has(deps.storage, &monitor_identity) {
+        return Err(MixnetContractError::NotAPenaltyMonitor {
+            identity_key: monitor_identity,
+        });
+    }
+
+    validate_signature(
+        deps.as_ref(),
+        &evidence.signable_bytes()?,
+        &signature,
+        &monitor_identity,
+    )?;
+
+    // the accused node must still be around - there's no point in penalising nodes that are leaving anyway
+    let mix_id = evidence.mix_id;
+    let bond = match mixnodes_storage::mixnode_bonds().may_load(deps.storage, mix_id)? {
+        None => return Err(MixnetContractError::MixNodeBondNotFound { mix_id }),
+        Some(bond) if bond.is_unbonding => {
+            return Err(MixnetContractError::MixnodeIsUnbonding { mix_id })
+        }
+        Some(bond) => bond,
+    };
+
+    if bond.identity() != evidence.identity_key {
+        return Err(MixnetContractError::EvidenceIdentityMismatch {
+            mix_id,
+            provided: evidence.identity_key,
+            expected: bond.identity().to_string(),
+        });
+    }
+
+    let current_epoch =
+        interval_storage::current_interval(deps.storage)?.current_epoch_absolute_id();
+    validate_evidence_kind(&evidence.kind, current_epoch)?;
+
+    // don't let the same evidence be replayed, even after it has been resolved
+    let nonce_key = (monitor_identity.as_str(), evidence.nonce);
+    if storage::SUBMITTED_EVIDENCE_NONCES.has(deps.storage, nonce_key) {
+        return Err(MixnetContractError::DuplicateEvidence);
+    }
+
+    storage::expire_pending_evidence(deps.storage, mix_id, env.block.height)?;
+
+    let id = storage::next_evidence_id_counter(deps.storage)?;
+    storage::SUBMITTED_EVIDENCE_NONCES.save(deps.storage, nonce_key, &id)?;
+    let record = EvidenceRecord {
+        id,
+        evidence,
+        monitor: monitor_identity,
+        signature,
+        submitted_at: env.block.height,
+        expires_at: env.block.height + PENDING_EVIDENCE_VALIDITY_BLOCKS,
+        status: EvidenceStatus::Pending,
+    };
+    storage::EVIDENCE.save(deps.storage, id, &record)?;
+    storage::PENDING_EVIDENCE.save(deps.storage, (mix_id, id), &())?;
+
+    Ok(
+        Response::new().add_event(new_misbehaviour_evidence_submission_event(
+            id,
+            mix_id,
+            &record.monitor,
+        )),
+    )
+}
+
+fn load_pending_evidence(
+    deps: &DepsMut<'_>,
+    env: &Env,
+    evidence_id: EvidenceId,
+) -> Result<EvidenceRecord, MixnetContractError> {
+    let record = storage::EVIDENCE
+        .may_load(deps.storage, evidence_id)?
+        .ok_or(MixnetContractError::EvidenceNotFound { evidence_id })?;
+
+    if !record.status.is_pending() {
+        return Err(MixnetContractError::EvidenceAlreadyResolved { evidence_id });
+    }
+    if record.is_expired(env.block.height) {
+        return Err(MixnetContractError::EvidenceExpired { evidence_id });
+    }
+    Ok(record)
+}
+
+pub(crate) fn try_apply_penalty(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    evidence_id: EvidenceId,
+    penalty: Penalty,
+) -> Result<Response, MixnetContractError> {
+    // the owner is expected to be either the governance or a multisig so that no single party
+    // could arbitrarily penalise the nodes
+    ensure_is_owner(info.sender, deps.storage)?;
+
+    let mut record = load_pending_evidence(&deps, &env, evidence_id)?;
+    let mix_id = record.evidence.mix_id;
+
+    let current_epoch =
+        interval_storage::current_interval(deps.storage)?.current_epoch_absolute_id();
+    let existing_exclusion = storage::EXCLUDED_MIXNODES.may_load(deps.storage, mix_id)?;
+
+    match penalty {
+        Penalty::Jail => {
+            // if the node has already been jailed before, keep the original date
+            if !matches!(
+                existing_exclusion,
+                Some(RewardedSetExclusion::Jailed { .. })
+            ) {
+                storage::EXCLUDED_MIXNODES.save(
+                    deps.storage,
+                    mix_id,
+                    &RewardedSetExclusion::Jailed {
+                        since_epoch: current_epoch,
+                    },
+                )?;
+            }
+        }
+        Penalty::ExcludeFromRewardedSet { epochs } => {
+            if epochs == 0 {
+                return Err(MixnetContractError::ZeroEpochExclusion);
+            }
+            // the node forfeits rewards for the current epoch and is not allowed in the following `epochs` rewarded sets
+            let until = current_epoch + epochs + 1;
+            let updated = match existing_exclusion {
+                Some(RewardedSetExclusion::Jailed { .. }) => None,
+                Some(RewardedSetExclusion::Until { epoch }) if epoch >= until => None,
+                _ => Some(RewardedSetExclusion::Until { epoch: until }),
+            };
+            if let Some(exclusion) = updated {
+                storage::EXCLUDED_MIXNODES.save(deps.storage, mix_id, &exclusion)?;
+            }
+        }
+        Penalty::SlashPledge { fraction } => {
+            if fraction.is_zero() {
+                return Err(MixnetContractError::ZeroSlashingFraction);
+            }
+            // the actual slashing has to happen at the end of the epoch so that the stake used for
+            // the rewarding calculations would not change mid-epoch
+            let epoch_event = PendingEpochEventKind::SlashOperatorPledge { mix_id, fraction };
+            interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+        }
+    }
+
+    record.status = EvidenceStatus::Penalised { penalty };
+    storage::EVIDENCE.save(deps.storage, evidence_id, &record)?;
+    storage::PENDING_EVIDENCE.remove(deps.storage, (mix_id, evidence_id));
+
+    Ok(Response::new().add_event(new_penalty_application_event(evidence_id, mix_id, &penalty)))
+}
+
+pub(crate) fn try_dismiss_evidence(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    evidence_id: EvidenceId,
+) -> Result<Response, MixnetContractError> {
+    ensure_is_owner(info.sender, deps.storage)?;
+
+    let mut record = load_pending_evidence(&deps, &env, evidence_id)?;
+    let mix_id = record.evidence.mix_id;
+
+    record.status = EvidenceStatus::Dismissed;
+    storage::EVIDENCE.save(deps.storage, evidence_id, &record)?;
+    storage::PENDING_EVIDENCE.remove(deps.storage, (mix_id, evidence_id));
+
+    Ok(Response::new().add_event(new_evidence_dismissal_event(evidence_id, mix_id)))
+}
+
+pub(crate) fn try_release_from_jail(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    mix_id: MixId,
+) -> Result<Response, MixnetContractError> {
+    ensure_is_owner(info.sender, deps.storage)?;
+
+    match storage::EXCLUDED_MIXNODES.may_load(deps.storage, mix_id)? {
+        Some(RewardedSetExclusion::Jailed { .. }) => {
+            storage::EXCLUDED_MIXNODES.remove(deps.storage, mix_id)
+        }
+        _ => return Err(MixnetContractError::MixnodeNotJailed { mix_id }),
+    }
+
+    Ok(Response::new().add_event(new_jail_release_event(mix_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::support::tests::test_helpers::TestSetup;
+    use cosmwasm_std::testing::mock_info;
+    use crypto::asymmetric::identity;
+
+    #[cfg(test)]
+    mod managing_penalty_monitors {
+        use super::*;
+
+        #[test]
+        fn can_only_be_done_by_contract_owner() {
+            let mut test = TestSetup::new();
+            let monitor = identity::KeyPair::new(&mut test.rng)
+                .public_key()
+                .to_base58_string();
+
+            let env = test.env();
+            let not_owner = mock_info("not-owner", &[]);
+            let res = try_add_penalty_monitor(
+                test.deps_mut(),
+                env.clone(),
+                not_owner.clone(),
+                monitor.clone(),
+            );
+            assert_eq!(res, Err(MixnetContractError::Unauthorized));
+
+            let owner = test.owner();
+            let res = try_add_penalty_monitor(test.deps_mut(), env, owner.clone(), monitor.clone());
+            assert!(res.is_ok());
+
+            let res = try_remove_penalty_monitor(test.deps_mut(), not_owner, monitor.clone());
+            assert_eq!(res, Err(MixnetContractError::Unauthorized));
+
+            let res = try_remove_penalty_monitor(test.deps_mut(), owner, monitor);
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn requires_valid_and_unique_keys() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = test.owner();
+
+            let res = try_add_penalty_monitor(
+                test.deps_mut(),
+                env.clone(),
+                owner.clone(),
+                "definitely-not-a-key".to_string(),
+            );
+            assert!(res.is_err());
+
+            let monitor = identity::KeyPair::new(&mut test.rng)
+                .public_key()
+                .to_base58_string();
+            try_add_penalty_monitor(test.deps_mut(), env.clone(), owner.clone(), monitor.clone())
+                .unwrap();
+            assert!(storage::PENALTY_MONITORS.has(test.deps().storage, &monitor));
+
+            let res = try_add_penalty_monitor(test.deps_mut(), env, owner.clone(), monitor.clone());
+            assert_eq!(
+                res,
+                Err(MixnetContractError::DuplicatePenaltyMonitor {
+                    identity_key: monitor.clone()
+                })
+            );
+
+            try_remove_penalty_monitor(test.deps_mut(), owner.clone(), monitor.clone()).unwrap();
+            assert!(!storage::PENALTY_MONITORS.has(test.deps().storage, &monitor));
+
+            let res = try_remove_penalty_monitor(test.deps_mut(), owner, monitor.clone());
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NotAPenaltyMonitor {
+                    identity_key: monitor
+                })
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod submitting_evidence {
+        use super::*;
+
+        fn submit(
+            test: &mut TestSetup,
+            monitor: &identity::KeyPair,
+            evidence: MisbehaviourEvidence,
+        ) -> Result<Response, MixnetContractError> {
+            let signature = monitor
+                .private_key()
+                .sign(&evidence.signable_bytes().unwrap())
+                .to_base58_string();
+            let env = test.env();
+            try_submit_misbehaviour_evidence(
+                test.deps_mut(),
+                env,
+                evidence,
+                monitor.public_key().to_base58_string(),
+                signature,
+            )
+        }
+
+        #[test]
+        fn is_only_accepted_from_registered_monitors() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let evidence = test.dummy_evidence(mix_id);
+
+            let unknown = identity::KeyPair::new(&mut test.rng);
+            let res = submit(&mut test, &unknown, evidence.clone());
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NotAPenaltyMonitor {
+                    identity_key: unknown.public_key().to_base58_string()
+                })
+            );
+
+            let monitor = test.add_penalty_monitor();
+            assert!(submit(&mut test, &monitor, evidence).is_ok());
+        }
+
+        #[test]
+        fn requires_valid_monitor_signature() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let evidence = test.dummy_evidence(mix_id);
+            let monitor = test.add_penalty_monitor();
+            let other_key = identity::KeyPair::new(&mut test.rng);
+
+            // signed by somebody else
+            let bad_signature = other_key
+                .private_key()
+                .sign(&evidence.signable_bytes().unwrap())
+                .to_base58_string();
+            let env = test.env();
+            let res = try_submit_misbehaviour_evidence(
+                test.deps_mut(),
+                env.clone(),
+                evidence.clone(),
+                monitor.public_key().to_base58_string(),
+                bad_signature,
+            );
+            assert_eq!(res, Err(MixnetContractError::InvalidEd25519Signature));
+
+            // signed by the monitor, but over different content
+            let mut altered = evidence.clone();
+            altered.kind = MisbehaviourKind::DuplicateIdentityKey {
+                observed_host: "1.2.3.4".to_string(),
+            };
+            let altered_signature = monitor
+                .private_key()
+                .sign(&altered.signable_bytes().unwrap())
+                .to_base58_string();
+            let res = try_submit_misbehaviour_evidence(
+                test.deps_mut(),
+                env,
+                evidence,
+                monitor.public_key().to_base58_string(),
+                altered_signature,
+            );
+            assert_eq!(res, Err(MixnetContractError::InvalidEd25519Signature));
+        }
+
+        #[test]
+        fn requires_the_node_to_be_bonded_and_not_unbonding() {
+            let mut test = TestSetup::new();
+            let monitor = test.add_penalty_monitor();
+
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let evidence = test.dummy_evidence(mix_id);
+
+            let mut nonexistent = evidence.clone();
+            nonexistent.mix_id = 42;
+            let res = submit(&mut test, &monitor, nonexistent);
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixNodeBondNotFound { mix_id: 42 })
+            );
+
+            test.start_unbonding_mixnode(mix_id);
+            let res = submit(&mut test, &monitor, evidence.clone());
+            assert_eq!(res, Err(MixnetContractError::MixnodeIsUnbonding { mix_id }));
+
+            test.execute_all_pending_events();
+            let res = submit(&mut test, &monitor, evidence);
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixNodeBondNotFound { mix_id })
+            );
+        }
+
+        #[test]
+        fn requires_matching_identity_key() {
+            let mut test = TestSetup::new();
+            let monitor = test.add_penalty_monitor();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+
+            let mut evidence = test.dummy_evidence(mix_id);
+            let expected = evidence.identity_key.clone();
+            let provided = identity::KeyPair::new(&mut test.rng)
+                .public_key()
+                .to_base58_string();
+            evidence.identity_key = provided.clone();
+
+            let res = submit(&mut test, &monitor, evidence);
+            assert_eq!(
+                res,
+                Err(MixnetContractError::EvidenceIdentityMismatch {
+                    mix_id,
+                    provided,
+                    expected,
+                })
+            );
+        }
+
+        #[test]
+        fn rejects_malformed_evidence() {
+            let mut test = TestSetup::new();
+            let monitor = test.add_penalty_monitor();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            test.skip_to_next_epoch();
+            test.skip_to_next_epoch();
+            let current_epoch = test.current_interval().current_epoch_absolute_id();
+
+            let malformed_kinds = vec![
+                MisbehaviourKind::PersistentZeroPerformance {
+                    first_epoch: current_epoch,
+                    last_epoch: current_epoch - 1,
+                    monitor_runs: 10,
+                },
+                MisbehaviourKind::PersistentZeroPerformance {
+                    first_epoch: current_epoch,
+                    last_epoch: current_epoch + 1,
+                    monitor_runs: 10,
+                },
+                MisbehaviourKind::PersistentZeroPerformance {
+                    first_epoch: current_epoch - 1,
+                    last_epoch: current_epoch,
+                    monitor_runs: 0,
+                },
+                MisbehaviourKind::DuplicateIdentityKey {
+                    observed_host: "".to_string(),
+                },
+            ];
+
+            for kind in malformed_kinds {
+                let mut evidence = test.dummy_evidence(mix_id);
+                evidence.kind = kind;
+                let res = submit(&mut test, &monitor, evidence);
+                assert!(matches!(
+                    res,
+                    Err(MixnetContractError::MalformedEvidence { .. })
+                ));
+            }
+        }
+
+        #[test]
+        fn cannot_be_replayed() {
+            let mut test = TestSetup::new();
+            let monitor = test.add_penalty_monitor();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let evidence = test.dummy_evidence(mix_id);
+
+            let id = test.submit_evidence(&monitor, evidence.clone());
+            let res = submit(&mut test, &monitor, evidence.clone());
+            assert_eq!(res, Err(MixnetContractError::DuplicateEvidence));
+
+            // but another monitor can independently confirm it
+            let other_monitor = test.add_penalty_monitor();
+            let other_id = test.submit_evidence(&other_monitor, evidence.clone());
+            assert_ne!(id, other_id);
+
+            // not even once it's been resolved
+            let env = test.env();
+            let owner = test.owner();
+            try_dismiss_evidence(test.deps_mut(), env, owner, id).unwrap();
+            let res = submit(&mut test, &monitor, evidence.clone());
+            assert_eq!(res, Err(MixnetContractError::DuplicateEvidence));
+
+            // but it can be resubmitted under a fresh nonce
+            let mut resubmitted = evidence;
+            resubmitted.nonce += 1;
+            assert!(submit(&mut test, &monitor, resubmitted).is_ok());
+        }
+
+        #[test]
+        fn saves_the_record_as_pending() {
+            let mut test = TestSetup::new();
+            let monitor = test.add_penalty_monitor();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let evidence = test.dummy_evidence(mix_id);
+
+            assert!(!storage::has_pending_evidence(test.deps().storage, mix_id).unwrap());
+            let id = test.submit_evidence(&monitor, evidence.clone());
+
+            let record = storage::EVIDENCE.load(test.deps().storage, id).unwrap();
+            assert_eq!(record.id, id);
+            assert_eq!(record.evidence, evidence);
+            assert_eq!(record.monitor, monitor.public_key().to_base58_string());
+            assert_eq!(record.submitted_at, test.env().block.height);
+            assert_eq!(
+                record.expires_at,
+                test.env().block.height + PENDING_EVIDENCE_VALIDITY_BLOCKS
+            );
+            assert_eq!(record.status, EvidenceStatus::Pending);
+            assert!(storage::has_pending_evidence(test.deps().storage, mix_id).unwrap());
+        }
+    }
+
+    #[cfg(test)]
+    mod resolving_evidence {
+        use super::*;
+        use crate::interval::transactions::try_advance_epoch;
+        use crate::mixnodes::transactions::try_remove_mixnode;
+        use mixnet_contract_common::{Layer, LayerAssignment, Percent, RewardedSetNodeStatus};
+
+        #[test]
+        fn can_only_be_done_by_contract_owner() {
+            let mut test = TestSetup::new();
+            let monitor = test.add_penalty_monitor();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let evidence = test.dummy_evidence(mix_id);
+            let id = test.submit_evidence(&monitor, evidence);
+
+            let env = test.env();
+            let not_owner = mock_info("not-owner", &[]);
+            let res = try_apply_penalty(
+                test.deps_mut(),
+                env.clone(),
+                not_owner.clone(),
+                id,
+                Penalty::Jail,
+            );
+            assert_eq!(res, Err(MixnetContractError::Unauthorized));
+
+            let res = try_dismiss_evidence(test.deps_mut(), env.clone(), not_owner.clone(), id);
+            assert_eq!(res, Err(MixnetContractError::Unauthorized));
+
+            let res = try_release_from_jail(test.deps_mut(), not_owner, mix_id);
+            assert_eq!(res, Err(MixnetContractError::Unauthorized));
+
+            let owner = test.owner();
+            let res = try_apply_penalty(test.deps_mut(), env, owner, id, Penalty::Jail);
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn evidence_can_only_be_resolved_once() {
+            let mut test = TestSetup::new();
+            let monitor = test.add_penalty_monitor();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let evidence = test.dummy_evidence(mix_id);
+            let id = test.submit_evidence(&monitor, evidence);
+
+            let env = test.env();
+            let owner = test.owner();
+            let res = try_dismiss_evidence(test.deps_mut(), env.clone(), owner.clone(), 123);
+            assert_eq!(
+                res,
+                Err(MixnetContractError::EvidenceNotFound { evidence_id: 123 })
+            );
+
+            try_dismiss_evidence(test.deps_mut(), env.clone(), owner.clone(), id).unwrap();
+            let record = storage::EVIDENCE.load(test.deps().storage, id).unwrap();
+            assert_eq!(record.status, EvidenceStatus::Dismissed);
+            assert!(!storage::has_pending_evidence(test.deps().storage, mix_id).unwrap());
+
+            let res = try_dismiss_evidence(test.deps_mut(), env.clone(), owner.clone(), id);
+            assert_eq!(
+                res,
+                Err(MixnetContractError::EvidenceAlreadyResolved { evidence_id: id })
+            );
+            let res = try_apply_penalty(test.deps_mut(), env, owner, id, Penalty::Jail);
+            assert_eq!(
+                res,
+                Err(MixnetContractError::EvidenceAlreadyResolved { evidence_id: id })
+            );
+
+            // dismissed evidence has no effect on the node
+            assert!(storage::EXCLUDED_MIXNODES
+                .may_load(test.deps().storage, mix_id)
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn jailing_lasts_until_explicit_release() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let current_epoch = test.current_interval().current_epoch_absolute_id();
+
+            let owner = test.owner();
+            let res = try_release_from_jail(test.deps_mut(), owner.clone(), mix_id);
+            assert_eq!(res, Err(MixnetContractError::MixnodeNotJailed { mix_id }));
+
+            let id = test.penalise_mixnode(mix_id, Penalty::Jail);
+            let record = storage::EVIDENCE.load(test.deps().storage, id).unwrap();
+            assert_eq!(
+                record.status,
+                EvidenceStatus::Penalised {
+                    penalty: Penalty::Jail
+                }
+            );
+            assert_eq!(
+                storage::EXCLUDED_MIXNODES
+                    .load(test.deps().storage, mix_id)
+                    .unwrap(),
+                RewardedSetExclusion::Jailed {
+                    since_epoch: current_epoch
+                }
+            );
+
+            // jailing it again doesn't change the original epoch
+            test.skip_to_next_epoch();
+            test.penalise_mixnode(mix_id, Penalty::Jail);
+            // and neither does a temporary exclusion
+            test.penalise_mixnode(mix_id, Penalty::ExcludeFromRewardedSet { epochs: 5 });
+            assert_eq!(
+                storage::EXCLUDED_MIXNODES
+                    .load(test.deps().storage, mix_id)
+                    .unwrap(),
+                RewardedSetExclusion::Jailed {
+                    since_epoch: current_epoch
+                }
+            );
+
+            for _ in 0..100 {
+                test.skip_to_next_epoch();
+            }
+            let current_epoch = test.current_interval().current_epoch_absolute_id();
+            assert!(storage::is_excluded(test.deps().storage, mix_id, current_epoch).unwrap());
+
+            try_release_from_jail(test.deps_mut(), owner, mix_id).unwrap();
+            assert!(!storage::is_excluded(test.deps().storage, mix_id, current_epoch).unwrap());
+        }
+
+        #[test]
+        fn temporary_exclusion_includes_the_current_epoch() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let current_epoch = test.current_interval().current_epoch_absolute_id();
+
+            let env = test.env();
+            let owner = test.owner();
+            let monitor = test.add_penalty_monitor();
+            let evidence = test.dummy_evidence(mix_id);
+            let id = test.submit_evidence(&monitor, evidence);
+            let res = try_apply_penalty(
+                test.deps_mut(),
+                env,
+                owner,
+                id,
+                Penalty::ExcludeFromRewardedSet { epochs: 0 },
+            );
+            assert_eq!(res, Err(MixnetContractError::ZeroEpochExclusion));
+
+            test.penalise_mixnode(mix_id, Penalty::ExcludeFromRewardedSet { epochs: 3 });
+            let storage = test.deps().storage;
+            assert!(storage::is_excluded(storage, mix_id, current_epoch).unwrap());
+            assert!(storage::is_excluded(storage, mix_id, current_epoch + 3).unwrap());
+            assert!(!storage::is_excluded(storage, mix_id, current_epoch + 4).unwrap());
+
+            // shorter exclusion doesn't override the longer one
+            test.penalise_mixnode(mix_id, Penalty::ExcludeFromRewardedSet { epochs: 1 });
+            let storage = test.deps().storage;
+            assert!(storage::is_excluded(storage, mix_id, current_epoch + 3).unwrap());
+
+            // but a longer one extends it
+            test.penalise_mixnode(mix_id, Penalty::ExcludeFromRewardedSet { epochs: 10 });
+            let storage = test.deps().storage;
+            assert!(storage::is_excluded(storage, mix_id, current_epoch + 10).unwrap());
+            assert!(!storage::is_excluded(storage, mix_id, current_epoch + 11).unwrap());
+        }
+
+        #[test]
+        fn slashing_is_deferred_until_the_end_of_epoch() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+
+            let env = test.env();
+            let owner = test.owner();
+            let monitor = test.add_penalty_monitor();
+            let evidence = test.dummy_evidence(mix_id);
+            let id = test.submit_evidence(&monitor, evidence);
+            let res = try_apply_penalty(
+                test.deps_mut(),
+                env,
+                owner,
+                id,
+                Penalty::SlashPledge {
+                    fraction: Percent::zero(),
+                },
+            );
+            assert_eq!(res, Err(MixnetContractError::ZeroSlashingFraction));
+
+            let fraction = Percent::from_percentage_value(10).unwrap();
+            let before = test.mix_rewarding(mix_id);
+            test.penalise_mixnode(mix_id, Penalty::SlashPledge { fraction });
+
+            // nothing has changed yet
+            assert_eq!(before, test.mix_rewarding(mix_id));
+            let events = test.pending_epoch_events();
+            assert_eq!(events.len(), 1);
+            assert_eq!(
+                events[0].kind,
+                PendingEpochEventKind::SlashOperatorPledge { mix_id, fraction }
+            );
+            // and the node is not excluded from anything
+            assert!(storage::EXCLUDED_MIXNODES
+                .may_load(test.deps().storage, mix_id)
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn unbonding_is_blocked_while_evidence_is_pending() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let monitor = test.add_penalty_monitor();
+            let evidence = test.dummy_evidence(mix_id);
+            let id = test.submit_evidence(&monitor, evidence);
+
+            let env = test.env();
+            let sender = mock_info("mix-owner", &[]);
+            let res = try_remove_mixnode(test.deps_mut(), env.clone(), sender.clone());
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixnodeUnderInvestigation { mix_id })
+            );
+
+            let owner = test.owner();
+            try_dismiss_evidence(test.deps_mut(), env.clone(), owner, id).unwrap();
+            let res = try_remove_mixnode(test.deps_mut(), env, sender);
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn expired_evidence_does_not_block_unbonding() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let monitor = test.add_penalty_monitor();
+            let evidence = test.dummy_evidence(mix_id);
+            let id = test.submit_evidence(&monitor, evidence);
+
+            test.env.block.height += PENDING_EVIDENCE_VALIDITY_BLOCKS;
+
+            let env = test.env();
+            let owner = test.owner();
+            let res = try_apply_penalty(test.deps_mut(), env.clone(), owner, id, Penalty::Jail);
+            assert_eq!(
+                res,
+                Err(MixnetContractError::EvidenceExpired { evidence_id: id })
+            );
+
+            let sender = mock_info("mix-owner", &[]);
+            let res = try_remove_mixnode(test.deps_mut(), env, sender);
+            assert!(res.is_ok());
+
+            let record = storage::EVIDENCE.load(test.deps().storage, id).unwrap();
+            assert_eq!(record.status, EvidenceStatus::Expired);
+            assert!(!storage::has_pending_evidence(test.deps().storage, mix_id).unwrap());
+        }
+
+        #[test]
+        fn excluded_nodes_are_removed_from_the_rewarded_set() {
+            let mut test = TestSetup::new();
+            let mix1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix2 = test.add_dummy_mixnode("mix-owner2", None);
+            let mix3 = test.add_dummy_mixnode("mix-owner3", None);
+            let mix4 = test.add_dummy_mixnode("mix-owner4", None);
+            let active_set_size = test.rewarding_params().active_set_size;
+
+            test.penalise_mixnode(mix2, Penalty::Jail);
+            test.penalise_mixnode(mix4, Penalty::ExcludeFromRewardedSet { epochs: 1 });
+
+            let layer_assignments = vec![
+                LayerAssignment::new(mix1, Layer::One),
+                LayerAssignment::new(mix2, Layer::Two),
+                LayerAssignment::new(mix3, Layer::Three),
+                LayerAssignment::new(mix4, Layer::One),
+            ];
+
+            test.skip_to_current_epoch_end();
+            let env = test.env();
+            let sender = test.rewarding_validator();
+            try_advance_epoch(
+                test.deps_mut(),
+                env,
+                sender.clone(),
+                layer_assignments.clone(),
+                active_set_size,
+            )
+            .unwrap();
+
+            let rewarded_set = test.rewarded_set();
+            assert_eq!(
+                rewarded_set,
+                vec![
+                    (mix1, RewardedSetNodeStatus::Active),
+                    (mix3, RewardedSetNodeStatus::Active)
+                ]
+            );
+
+            // the temporary exclusion expires, but the jailed node stays out
+            test.skip_to_current_epoch_end();
+            let env = test.env();
+            try_advance_epoch(
+                test.deps_mut(),
+                env,
+                sender,
+                layer_assignments,
+                active_set_size,
+            )
+            .unwrap();
+
+            let rewarded_set = test.rewarded_set();
+            assert_eq!(
+                rewarded_set,
+                vec![
+                    (mix1, RewardedSetNodeStatus::Active),
+                    (mix3, RewardedSetNodeStatus::Active),
+                    (mix4, RewardedSetNodeStatus::Active)
+                ]
+            );
+            assert!(storage::EXCLUDED_MIXNODES
+                .may_load(test.deps().storage, mix4)
+                .unwrap()
+                .is_none());
+        }
+    }
+}
@@ -1,2 +1,44 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
+
+use crate::delegations::storage as delegations_storage;
+use crate::gateways::storage as gateways_storage;
+use crate::rewards::storage as rewards_storage;
+use cosmwasm_std::{Order, StdResult, Storage};
+use mixnet_contract_common::error::MixnetContractError;
+use mixnet_contract_common::{GatewayBond, GatewayDelegation, GatewayRewarding};
+
+// gateways bonded before the introduction of gateway rewarding don't have any rewarding details
+// associated with them. Create them using their current pledges and any existing delegations.
+pub(crate) fn initialise_gateway_rewarding(
+    storage: &mut dyn Storage,
+) -> Result<(), MixnetContractError> {
+    let bonds = gateways_storage::gateways()
+        .range(storage, None, None, Order::Ascending)
+        .map(|record| record.map(|(_, bond)| bond))
+        .collect::<StdResult<Vec<GatewayBond>>>()?;
+
+    for bond in bonds {
+        if rewards_storage::GATEWAY_REWARDING.has(storage, bond.identity()) {
+            continue;
+        }
+
+        let mut gateway_rewarding = GatewayRewarding::initialise_new(&bond.pledge_amount)?;
+
+        let delegations = delegations_storage::gateway_delegations()
+            .prefix(bond.identity().to_string())
+            .range(storage, None, None, Order::Ascending)
+            .map(|record| record.map(|(_, delegation)| delegation))
+            .collect::<StdResult<Vec<GatewayDelegation>>>()?;
+
+        // all existing delegations have the default (i.e. zero) cumulative reward ratio,
+        // which matches the ratio of the freshly initialised gateway
+        for delegation in delegations {
+            gateway_rewarding.add_base_delegation(delegation.amount.amount)?;
+        }
+
+        rewards_storage::GATEWAY_REWARDING.save(storage, bond.identity(), &gateway_rewarding)?;
+    }
+
+    Ok(())
+}
@@ -32,6 +32,11 @@ pub(crate) fn apply_reward_pool_changes(
     rewarding_params.interval.staking_supply = staking_supply;
     rewarding_params.interval.epoch_reward_budget = epoch_reward_budget;
     rewarding_params.interval.stake_saturation_point = stake_saturation_point;
+    rewarding_params.gateway.recompute(
+        reward_pool,
+        staking_supply,
+        interval.epochs_in_interval(),
+    )?;
 
     storage::PENDING_REWARD_POOL_CHANGE.save(store, &Default::default())?;
     storage::REWARDING_PARAMS.save(store, &rewarding_params)?;
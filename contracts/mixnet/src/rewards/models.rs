@@ -10,9 +10,7 @@ pub(crate) struct RewardPoolChange {
     /// upon the current interval finishing.
     pub removed: Decimal,
 
-    // this will be used once coconut credentials are in use;
     /// Indicates amount that shall get added to the reward pool and not touch the staking supply
-    /// upon the current interval finishing.
-    #[allow(unused)]
+    /// upon the current interval finishing, such as the slashed operator pledges.
     pub added: Decimal,
 }
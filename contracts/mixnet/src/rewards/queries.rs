@@ -3,6 +3,7 @@
 
 use super::storage;
 use crate::delegations::storage as delegations_storage;
+use crate::gateways::storage as gateways_storage;
 use crate::interval::storage as interval_storage;
 use crate::mixnodes;
 use crate::mixnodes::storage as mixnodes_storage;
@@ -14,7 +15,7 @@ use mixnet_contract_common::rewarding::helpers::truncate_reward;
 use mixnet_contract_common::rewarding::{
     EstimatedCurrentEpochRewardResponse, PendingRewardResponse,
 };
-use mixnet_contract_common::{Delegation, MixId};
+use mixnet_contract_common::{Delegation, GatewayRewardingDetailsResponse, IdentityKey, MixId};
 
 pub(crate) fn query_rewarding_params(deps: Deps<'_>) -> StdResult<RewardingParams> {
     storage::REWARDING_PARAMS.load(deps.storage)
@@ -91,6 +92,80 @@ pub fn query_pending_delegator_reward(
     })
 }
 
+pub(crate) fn query_gateway_rewarding_details(
+    deps: Deps<'_>,
+    gateway_identity: IdentityKey,
+) -> StdResult<GatewayRewardingDetailsResponse> {
+    let rewarding_details = storage::GATEWAY_REWARDING.may_load(deps.storage, &gateway_identity)?;
+    Ok(GatewayRewardingDetailsResponse {
+        identity: gateway_identity,
+        rewarding_details,
+    })
+}
+
+pub(crate) fn query_pending_gateway_operator_reward(
+    deps: Deps<'_>,
+    gateway_identity: IdentityKey,
+) -> StdResult<PendingRewardResponse> {
+    let gateway_bond =
+        match gateways_storage::gateways().may_load(deps.storage, &gateway_identity)? {
+            Some(gateway_bond) => gateway_bond,
+            None => return Ok(PendingRewardResponse::default()),
+        };
+    let gateway_rewarding =
+        match storage::GATEWAY_REWARDING.may_load(deps.storage, &gateway_identity)? {
+            Some(gateway_rewarding) => gateway_rewarding,
+            None => return Ok(PendingRewardResponse::default()),
+        };
+
+    let pledge = gateway_bond.pledge_amount;
+    let base_pledge = into_base_decimal(pledge.amount)?;
+    let amount_earned_detailed = if gateway_rewarding.operator > base_pledge {
+        gateway_rewarding.operator - base_pledge
+    } else {
+        Decimal::zero()
+    };
+
+    Ok(PendingRewardResponse {
+        amount_earned: Some(gateway_rewarding.pending_operator_reward(&pledge)),
+        amount_staked: Some(pledge),
+        amount_earned_detailed: Some(amount_earned_detailed),
+        mixnode_still_fully_bonded: true,
+    })
+}
+
+pub(crate) fn query_pending_gateway_delegator_reward(
+    deps: Deps<'_>,
+    owner: String,
+    gateway_identity: IdentityKey,
+) -> StdResult<PendingRewardResponse> {
+    let owner_address = deps.api.addr_validate(&owner)?;
+
+    let gateway_rewarding =
+        match storage::GATEWAY_REWARDING.may_load(deps.storage, &gateway_identity)? {
+            Some(gateway_rewarding) => gateway_rewarding,
+            None => return Ok(PendingRewardResponse::default()),
+        };
+
+    let storage_key = (gateway_identity, owner_address);
+    let delegation =
+        match delegations_storage::gateway_delegations().may_load(deps.storage, storage_key)? {
+            Some(delegation) => delegation,
+            None => return Ok(PendingRewardResponse::default()),
+        };
+
+    let detailed_reward = gateway_rewarding.determine_delegation_reward(&delegation)?;
+    let delegator_reward = gateway_rewarding.pending_delegator_reward(&delegation)?;
+    let is_bonded = gateways_storage::gateways().has(deps.storage, &delegation.gateway_identity);
+
+    Ok(PendingRewardResponse {
+        amount_staked: Some(delegation.amount),
+        amount_earned: Some(delegator_reward),
+        amount_earned_detailed: Some(detailed_reward),
+        mixnode_still_fully_bonded: is_bonded,
+    })
+}
+
 fn zero_reward(
     original_stake: Coin,
     current_value: Decimal,
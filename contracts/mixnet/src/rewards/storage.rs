@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::constants::{
-    MIXNODES_REWARDING_PK_NAMESPACE, PENDING_REWARD_POOL_KEY, REWARDING_PARAMS_KEY,
+    GATEWAYS_REWARDING_PK_NAMESPACE, MIXNODES_REWARDING_PK_NAMESPACE, PENDING_REWARD_POOL_KEY,
+    REWARDING_PARAMS_KEY,
 };
 use crate::rewards::models::RewardPoolChange;
 use cosmwasm_std::{Decimal, StdResult, Storage};
@@ -10,7 +11,7 @@ use cw_storage_plus::{Item, Map};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::mixnode::MixNodeRewarding;
 use mixnet_contract_common::reward_params::RewardingParams;
-use mixnet_contract_common::MixId;
+use mixnet_contract_common::{GatewayRewarding, IdentityKeyRef, MixId};
 
 // current parameters used for rewarding purposes
 pub(crate) const REWARDING_PARAMS: Item<'_, RewardingParams> = Item::new(REWARDING_PARAMS_KEY);
@@ -20,6 +21,10 @@ pub(crate) const PENDING_REWARD_POOL_CHANGE: Item<'_, RewardPoolChange> =
 pub const MIXNODE_REWARDING: Map<MixId, MixNodeRewarding> =
     Map::new(MIXNODES_REWARDING_PK_NAMESPACE);
 
+// gateways are not part of the rewarded set and thus they're keyed by their identities
+pub const GATEWAY_REWARDING: Map<'_, IdentityKeyRef<'_>, GatewayRewarding> =
+    Map::new(GATEWAYS_REWARDING_PK_NAMESPACE);
+
 pub fn reward_accounting(
     storage: &mut dyn Storage,
     amount: Decimal,
@@ -30,6 +35,17 @@ pub fn reward_accounting(
     Ok(PENDING_REWARD_POOL_CHANGE.save(storage, &pending_changes)?)
 }
 
+// used for returning slashed tokens back into the pool so that they'd get redistributed
+pub fn return_to_reward_pool(
+    storage: &mut dyn Storage,
+    amount: Decimal,
+) -> Result<(), MixnetContractError> {
+    let mut pending_changes = PENDING_REWARD_POOL_CHANGE.load(storage)?;
+    pending_changes.added += amount;
+
+    Ok(PENDING_REWARD_POOL_CHANGE.save(storage, &pending_changes)?)
+}
+
 pub(crate) fn initialise_storage(
     storage: &mut dyn Storage,
     reward_params: RewardingParams,
@@ -3,11 +3,13 @@
 
 use super::storage;
 use crate::delegations::storage as delegations_storage;
+use crate::gateways::storage as gateways_storage;
 use crate::interval::storage as interval_storage;
 use crate::interval::storage::{push_new_epoch_event, push_new_interval_event};
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
 use crate::mixnodes::helpers::get_mixnode_details_by_owner;
 use crate::mixnodes::storage as mixnodes_storage;
+use crate::penalties::storage as penalties_storage;
 use crate::rewards::helpers;
 use crate::support::helpers::{
     ensure_bonded, ensure_is_authorized, ensure_is_owner, ensure_proxy_match,
@@ -16,17 +18,18 @@ use crate::support::helpers::{
 use cosmwasm_std::{wasm_execute, Addr, DepsMut, Env, MessageInfo, Response};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
-    new_active_set_update_event, new_mix_rewarding_event,
+    new_active_set_update_event, new_excluded_mix_operator_rewarding_event,
+    new_gateway_rewarding_event, new_mix_rewarding_event, new_not_found_gateway_rewarding_event,
     new_not_found_mix_operator_rewarding_event, new_pending_active_set_update_event,
     new_pending_rewarding_params_update_event, new_rewarding_params_update_event,
     new_withdraw_delegator_reward_event, new_withdraw_operator_reward_event,
-    new_zero_uptime_mix_operator_rewarding_event,
+    new_zero_uptime_gateway_rewarding_event, new_zero_uptime_mix_operator_rewarding_event,
 };
 use mixnet_contract_common::pending_events::{PendingEpochEventKind, PendingIntervalEventKind};
 use mixnet_contract_common::reward_params::{
     IntervalRewardingParamsUpdate, NodeRewardParams, Performance,
 };
-use mixnet_contract_common::{Delegation, MixId};
+use mixnet_contract_common::{Delegation, IdentityKey, MixId};
 use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 
 pub(crate) fn try_reward_mixnode(
@@ -81,6 +84,15 @@ pub(crate) fn try_reward_mixnode(
             absolute_epoch_id,
         })?;
 
+    // the node got penalised during this epoch and thus it forfeits its rewards
+    if penalties_storage::is_excluded(deps.storage, mix_id, absolute_epoch_id)? {
+        mix_rewarding.last_rewarded_epoch = absolute_epoch_id;
+        storage::MIXNODE_REWARDING.save(deps.storage, mix_id, &mix_rewarding)?;
+        return Ok(
+            Response::new().add_event(new_excluded_mix_operator_rewarding_event(interval, mix_id))
+        );
+    }
+
     // no need to calculate anything as rewards are going to be 0 for everything
     // however, we still need to update last_rewarded_epoch field
     if node_performance.is_zero() {
@@ -118,6 +130,87 @@ pub(crate) fn try_reward_mixnode(
     )))
 }
 
+pub(crate) fn try_reward_gateway(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    gateway_identity: IdentityKey,
+    gateway_performance: Performance,
+) -> Result<Response, MixnetContractError> {
+    ensure_is_authorized(info.sender, deps.storage)?;
+
+    // see if the epoch has finished
+    let interval = interval_storage::current_interval(deps.storage)?;
+    if !interval.is_current_epoch_over(&env) {
+        return Err(MixnetContractError::EpochInProgress {
+            current_block_time: env.block.time.seconds(),
+            epoch_start: interval.current_epoch_start_unix_timestamp(),
+            epoch_end: interval.current_epoch_end_unix_timestamp(),
+        });
+    }
+
+    // similarly to mixnodes, the gateway might have unbonded before the rewards were distributed,
+    // in which case we don't want to fail the underlying transaction
+    let bonded = gateways_storage::gateways().has(deps.storage, &gateway_identity);
+    let mut gateway_rewarding = match storage::GATEWAY_REWARDING
+        .may_load(deps.storage, &gateway_identity)?
+    {
+        Some(gateway_rewarding) if bonded && gateway_rewarding.still_bonded() => gateway_rewarding,
+        _ => {
+            return Ok(
+                Response::new().add_event(new_not_found_gateway_rewarding_event(
+                    interval,
+                    &gateway_identity,
+                )),
+            );
+        }
+    };
+
+    let prior_delegates = gateway_rewarding.delegates;
+    let prior_unit_reward = gateway_rewarding.full_reward_ratio();
+
+    let absolute_epoch_id = interval.current_epoch_absolute_id();
+    if absolute_epoch_id == gateway_rewarding.last_rewarded_epoch {
+        return Err(MixnetContractError::GatewayAlreadyRewarded {
+            identity: gateway_identity,
+            absolute_epoch_id,
+        });
+    }
+
+    // no need to calculate anything as rewards are going to be 0 for everything
+    // however, we still need to update last_rewarded_epoch field
+    if gateway_performance.is_zero() {
+        gateway_rewarding.last_rewarded_epoch = absolute_epoch_id;
+        storage::GATEWAY_REWARDING.save(deps.storage, &gateway_identity, &gateway_rewarding)?;
+        return Ok(
+            Response::new().add_event(new_zero_uptime_gateway_rewarding_event(
+                interval,
+                &gateway_identity,
+            )),
+        );
+    }
+
+    let rewarding_params = storage::REWARDING_PARAMS.load(deps.storage)?;
+
+    // calculate each step separate for easier accounting
+    let node_reward = gateway_rewarding.node_reward(&rewarding_params.gateway, gateway_performance);
+    let reward_distribution =
+        gateway_rewarding.determine_reward_split(node_reward, &rewarding_params.gateway);
+    gateway_rewarding.distribute_rewards(reward_distribution, absolute_epoch_id);
+
+    // persist changes happened to the storage
+    storage::GATEWAY_REWARDING.save(deps.storage, &gateway_identity, &gateway_rewarding)?;
+    storage::reward_accounting(deps.storage, node_reward)?;
+
+    Ok(Response::new().add_event(new_gateway_rewarding_event(
+        interval,
+        &gateway_identity,
+        reward_distribution,
+        prior_delegates,
+        prior_unit_reward,
+    )))
+}
+
 pub(crate) fn try_withdraw_operator_reward(
     deps: DepsMut<'_>,
     info: MessageInfo,
@@ -1100,6 +1193,464 @@ pub mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod rewarding_penalised_mixnodes {
+        use super::*;
+        use crate::support::tests::test_helpers::{find_attribute, TestSetup};
+        use cosmwasm_std::{BankMsg, CosmosMsg, Decimal, Uint128};
+        use mixnet_contract_common::events::{
+            MixnetEventType, EXCLUDED_FROM_REWARDED_SET_VALUE, NO_REWARD_REASON_KEY,
+        };
+        use mixnet_contract_common::penalties::Penalty;
+        use mixnet_contract_common::rewarding::helpers::truncate_reward_amount;
+        use mixnet_contract_common::Percent;
+
+        #[test]
+        fn excluded_nodes_forfeit_their_rewards() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            test.add_immediate_delegation("delegator", Uint128::new(100_000_000), mix_id);
+
+            test.skip_to_next_epoch_end();
+            test.update_rewarded_set(vec![mix_id]);
+            let performance = test_helpers::performance(100.0);
+            let absolute_epoch_id = test.current_interval().current_epoch_absolute_id();
+
+            test.penalise_mixnode(mix_id, Penalty::ExcludeFromRewardedSet { epochs: 1 });
+            let before = test.mix_rewarding(mix_id);
+
+            let env = test.env();
+            let sender = test.rewarding_validator();
+            let res = try_reward_mixnode(
+                test.deps_mut(),
+                env.clone(),
+                sender.clone(),
+                mix_id,
+                performance,
+            )
+            .unwrap();
+            let reason = find_attribute(
+                Some(MixnetEventType::MixnodeRewarding.to_string()),
+                NO_REWARD_REASON_KEY,
+                &res,
+            );
+            assert_eq!(EXCLUDED_FROM_REWARDED_SET_VALUE, reason);
+
+            // nothing got distributed, but the epoch is still marked as rewarded
+            let after = test.mix_rewarding(mix_id);
+            assert_eq!(after.operator, before.operator);
+            assert_eq!(after.delegates, before.delegates);
+            assert_eq!(after.total_unit_reward, before.total_unit_reward);
+            assert_eq!(after.last_rewarded_epoch, absolute_epoch_id);
+
+            let res = try_reward_mixnode(test.deps_mut(), env, sender, mix_id, performance);
+            assert!(matches!(
+                res,
+                Err(MixnetContractError::MixnodeAlreadyRewarded { .. })
+            ));
+
+            // the following epoch is also covered by the exclusion...
+            test.skip_to_next_epoch_end();
+            let env = test.env();
+            let sender = test.rewarding_validator();
+            let res =
+                try_reward_mixnode(test.deps_mut(), env, sender, mix_id, performance).unwrap();
+            let reason = find_attribute(
+                Some(MixnetEventType::MixnodeRewarding.to_string()),
+                NO_REWARD_REASON_KEY,
+                &res,
+            );
+            assert_eq!(EXCLUDED_FROM_REWARDED_SET_VALUE, reason);
+
+            // ...but after that the node is back to normal
+            test.skip_to_next_epoch_end();
+            let dist = test.reward_with_distribution(mix_id, performance);
+            assert!(!dist.operator.is_zero());
+            assert!(!dist.delegates.is_zero());
+        }
+
+        #[test]
+        fn slashing_only_affects_the_operator_pledge() {
+            let mut test = TestSetup::new();
+            let pledge = Uint128::new(1_000_000_000);
+            let mix_id = test.add_dummy_mixnode("mix-owner", Some(pledge));
+            test.add_immediate_delegation("delegator", Uint128::new(500_000_000), mix_id);
+
+            test.skip_to_next_epoch_end();
+            test.update_rewarded_set(vec![mix_id]);
+            test.reward_with_distribution(mix_id, test_helpers::performance(100.0));
+
+            let operator_reward_before = test.pending_operator_reward(mix_id);
+            let delegator_reward_before = test.pending_delegator_reward("delegator", mix_id);
+            let rewarding_before = test.mix_rewarding(mix_id);
+            let pool_change_before = storage::PENDING_REWARD_POOL_CHANGE
+                .load(test.deps().storage)
+                .unwrap();
+
+            let fraction = Percent::from_percentage_value(10).unwrap();
+            test.penalise_mixnode(mix_id, Penalty::SlashPledge { fraction });
+            test.execute_all_pending_events();
+
+            let slashed = Decimal::from_atomics(100_000_000u128, 0).unwrap();
+            let bond = mixnodes_storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+            assert_eq!(bond.original_pledge.amount, Uint128::new(900_000_000));
+
+            let rewarding_after = test.mix_rewarding(mix_id);
+            assert_eq!(
+                rewarding_after.operator,
+                rewarding_before.operator - slashed
+            );
+            assert_eq!(rewarding_after.delegates, rewarding_before.delegates);
+            assert_eq!(
+                rewarding_after.total_unit_reward,
+                rewarding_before.total_unit_reward
+            );
+            assert_eq!(
+                rewarding_after.unique_delegations,
+                rewarding_before.unique_delegations
+            );
+
+            // the rewards themselves are not touched
+            assert_eq!(operator_reward_before, test.pending_operator_reward(mix_id));
+            assert_eq!(
+                delegator_reward_before,
+                test.pending_delegator_reward("delegator", mix_id)
+            );
+
+            // and the slashed tokens go back to the reward pool
+            let pool_change_after = storage::PENDING_REWARD_POOL_CHANGE
+                .load(test.deps().storage)
+                .unwrap();
+            assert_eq!(pool_change_after.added, pool_change_before.added + slashed);
+            assert_eq!(pool_change_after.removed, pool_change_before.removed);
+        }
+
+        #[test]
+        fn slashed_nodes_are_rewarded_based_on_the_reduced_stake() {
+            let mut test = TestSetup::new();
+            let pledge = Uint128::new(1_000_000_000);
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", Some(pledge));
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", Some(pledge));
+            test.add_immediate_delegation("delegator", Uint128::new(500_000_000), mix_id1);
+            test.add_immediate_delegation("delegator", Uint128::new(500_000_000), mix_id2);
+
+            test.skip_to_next_epoch_end();
+            test.update_rewarded_set(vec![mix_id1, mix_id2]);
+
+            let fraction = Percent::from_percentage_value(50).unwrap();
+            test.penalise_mixnode(mix_id1, Penalty::SlashPledge { fraction });
+            test.execute_all_pending_events();
+
+            let performance = test_helpers::performance(100.0);
+            let dist1 = test.reward_with_distribution(mix_id1, performance);
+            let dist2 = test.reward_with_distribution(mix_id2, performance);
+
+            assert!(!dist1.operator.is_zero());
+            assert!(dist1.operator < dist2.operator);
+            assert!(dist1.operator + dist1.delegates < dist2.operator + dist2.delegates);
+
+            // and withdrawing the operator reward only ever pays out the rewards
+            let reward = test.pending_operator_reward(mix_id1);
+            let res = try_withdraw_operator_reward(test.deps_mut(), mock_info("mix-owner1", &[]))
+                .unwrap();
+            let expected = truncate_reward_amount(reward);
+            assert!(matches!(
+                &res.messages[0].msg,
+                CosmosMsg::Bank(BankMsg::Send { amount, .. }) if amount[0].amount == expected
+            ));
+
+            let bond = mixnodes_storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id1)
+                .unwrap();
+            assert_eq!(bond.original_pledge.amount, Uint128::new(500_000_000));
+            assert_eq!(
+                test.mix_rewarding(mix_id1).operator,
+                Decimal::from_atomics(500_000_000u128, 0).unwrap()
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod gateway_rewarding {
+        use super::*;
+        use crate::delegations::transactions::{
+            try_delegate_to_gateway, try_remove_delegation_from_gateway,
+        };
+        use crate::gateways::transactions::try_remove_gateway;
+        use crate::support::tests::fixtures::{good_gateway_pledge, TEST_COIN_DENOM};
+        use crate::support::tests::test_helpers::{
+            add_gateway, find_attribute, get_bank_send_msg, TestSetup,
+        };
+        use cosmwasm_std::{coin, Decimal};
+        use mixnet_contract_common::events::{
+            MixnetEventType, BOND_NOT_FOUND_VALUE, NO_REWARD_REASON_KEY, ZERO_PERFORMANCE_VALUE,
+        };
+        use mixnet_contract_common::gateway::NEVER_REWARDED_EPOCH;
+        use mixnet_contract_common::rewarding::helpers::truncate_reward_amount;
+        use mixnet_contract_common::GatewayRewarding;
+
+        fn add_test_gateway(test: &mut TestSetup, owner: &str) -> IdentityKey {
+            let env = test.env();
+            add_gateway(
+                &mut test.rng,
+                test.deps.as_mut(),
+                env,
+                owner,
+                good_gateway_pledge(),
+            )
+        }
+
+        fn delegate_to_gateway(test: &mut TestSetup, delegator: &str, identity: &IdentityKey) {
+            let env = test.env();
+            let sender = mock_info(delegator, &[coin(100_000_000, TEST_COIN_DENOM)]);
+            try_delegate_to_gateway(test.deps_mut(), env, sender, identity.clone()).unwrap();
+            test.execute_all_pending_events();
+        }
+
+        fn gateway_rewarding(test: &TestSetup, identity: &IdentityKey) -> GatewayRewarding {
+            storage::GATEWAY_REWARDING
+                .load(test.deps().storage, identity)
+                .unwrap()
+        }
+
+        #[test]
+        fn can_only_be_done_by_the_rewarding_validator_after_epoch_end() {
+            let mut test = TestSetup::new();
+            let identity = add_test_gateway(&mut test, "gateway-owner");
+            let performance = test_helpers::performance(100.0);
+
+            let env = test.env();
+            let sender = test.rewarding_validator();
+            let res =
+                try_reward_gateway(test.deps_mut(), env, sender, identity.clone(), performance);
+            assert!(matches!(
+                res,
+                Err(MixnetContractError::EpochInProgress { .. })
+            ));
+
+            test.skip_to_next_epoch_end();
+            let env = test.env();
+            let res = try_reward_gateway(
+                test.deps_mut(),
+                env,
+                mock_info("random-guy", &[]),
+                identity,
+                performance,
+            );
+            assert_eq!(res, Err(MixnetContractError::Unauthorized));
+        }
+
+        #[test]
+        fn is_skipped_for_unknown_and_unbonded_gateways() {
+            let mut test = TestSetup::new();
+            let identity = add_test_gateway(&mut test, "gateway-owner");
+            try_remove_gateway(test.deps_mut(), mock_info("gateway-owner", &[])).unwrap();
+
+            test.skip_to_next_epoch_end();
+            let performance = test_helpers::performance(100.0);
+            for gateway in [identity, "non-existent".to_string()] {
+                let env = test.env();
+                let sender = test.rewarding_validator();
+                let res =
+                    try_reward_gateway(test.deps_mut(), env, sender, gateway, performance).unwrap();
+                let reason = find_attribute(
+                    Some(MixnetEventType::GatewayRewarding.to_string()),
+                    NO_REWARD_REASON_KEY,
+                    &res,
+                );
+                assert_eq!(BOND_NOT_FOUND_VALUE, reason);
+            }
+        }
+
+        #[test]
+        fn includes_gateways_bonded_during_the_epoch() {
+            let mut test = TestSetup::new();
+            test.skip_to_next_epoch_end();
+            let identity = add_test_gateway(&mut test, "gateway-owner");
+            assert_eq!(
+                gateway_rewarding(&test, &identity).last_rewarded_epoch,
+                NEVER_REWARDED_EPOCH
+            );
+
+            let env = test.env();
+            let sender = test.rewarding_validator();
+            try_reward_gateway(
+                test.deps_mut(),
+                env,
+                sender,
+                identity.clone(),
+                test_helpers::performance(100.0),
+            )
+            .unwrap();
+            assert_eq!(
+                gateway_rewarding(&test, &identity).last_rewarded_epoch,
+                test.current_interval().current_epoch_absolute_id()
+            );
+        }
+
+        #[test]
+        fn can_only_happen_once_per_epoch() {
+            let mut test = TestSetup::new();
+            let identity = add_test_gateway(&mut test, "gateway-owner");
+
+            test.skip_to_next_epoch_end();
+            let env = test.env();
+            let sender = test.rewarding_validator();
+            let zero = test_helpers::performance(0.0);
+            let before = gateway_rewarding(&test, &identity);
+            let res = try_reward_gateway(
+                test.deps_mut(),
+                env.clone(),
+                sender.clone(),
+                identity.clone(),
+                zero,
+            )
+            .unwrap();
+            let reason = find_attribute(
+                Some(MixnetEventType::GatewayRewarding.to_string()),
+                NO_REWARD_REASON_KEY,
+                &res,
+            );
+            assert_eq!(ZERO_PERFORMANCE_VALUE, reason);
+
+            // nothing got distributed, but the epoch is still marked as rewarded
+            let after = gateway_rewarding(&test, &identity);
+            assert_eq!(before.operator, after.operator);
+            assert_eq!(
+                test.current_interval().current_epoch_absolute_id(),
+                after.last_rewarded_epoch
+            );
+
+            let res = try_reward_gateway(
+                test.deps_mut(),
+                env,
+                sender,
+                identity,
+                test_helpers::performance(100.0),
+            );
+            assert!(matches!(
+                res,
+                Err(MixnetContractError::GatewayAlreadyRewarded { .. })
+            ));
+        }
+
+        #[test]
+        fn splits_reward_between_operator_and_delegators() {
+            let mut test = TestSetup::new();
+            let identity = add_test_gateway(&mut test, "gateway-owner");
+            delegate_to_gateway(&mut test, "delegator", &identity);
+
+            test.skip_to_next_epoch_end();
+            let env = test.env();
+            let sender = test.rewarding_validator();
+            let before = gateway_rewarding(&test, &identity);
+            try_reward_gateway(
+                test.deps_mut(),
+                env,
+                sender,
+                identity.clone(),
+                test_helpers::performance(100.0),
+            )
+            .unwrap();
+            let after = gateway_rewarding(&test, &identity);
+
+            let params = test.rewarding_params().gateway;
+            let expected = before.calculate_epoch_reward(&params, test_helpers::performance(100.0));
+            assert!(!expected.operator.is_zero());
+            assert!(!expected.delegates.is_zero());
+
+            // operator and delegator have staked the same amount, but the operator also takes the profit margin
+            assert!(expected.operator > expected.delegates);
+            assert_eq!(before.operator + expected.operator, after.operator);
+            assert_eq!(before.delegates + expected.delegates, after.delegates);
+
+            // the reward is removed from the pool at the end of the interval
+            let pool_change = storage::PENDING_REWARD_POOL_CHANGE
+                .load(test.deps().storage)
+                .unwrap();
+            assert_eq!(pool_change.removed, expected.operator + expected.delegates);
+        }
+
+        #[test]
+        fn accumulated_rewards_are_paid_out_on_unbonding_and_undelegating() {
+            let mut test = TestSetup::new();
+            let identity = add_test_gateway(&mut test, "gateway-owner");
+            delegate_to_gateway(&mut test, "delegator", &identity);
+
+            for _ in 0..5 {
+                test.skip_to_next_epoch_end();
+                let env = test.env();
+                let sender = test.rewarding_validator();
+                try_reward_gateway(
+                    test.deps_mut(),
+                    env,
+                    sender,
+                    identity.clone(),
+                    test_helpers::performance(90.0),
+                )
+                .unwrap();
+            }
+
+            let rewarding = gateway_rewarding(&test, &identity);
+            let pledge = good_gateway_pledge()[0].clone();
+            let operator_total = rewarding.operator_pledge_with_reward(TEST_COIN_DENOM);
+            assert!(operator_total.amount > pledge.amount);
+
+            let delegator_reward = rewarding
+                .determine_delegation_reward(
+                    &crate::delegations::storage::gateway_delegations()
+                        .load(
+                            test.deps().storage,
+                            (identity.clone(), Addr::unchecked("delegator")),
+                        )
+                        .unwrap(),
+                )
+                .unwrap();
+            assert!(!delegator_reward.is_zero());
+
+            let res = try_remove_gateway(test.deps_mut(), mock_info("gateway-owner", &[])).unwrap();
+            let (_, sent) = get_bank_send_msg(&res).unwrap();
+            assert_eq!(sent[0], operator_total);
+
+            // the details are kept around as there's still a delegation towards the gateway
+            let rewarding = gateway_rewarding(&test, &identity);
+            assert!(!rewarding.still_bonded());
+            assert_eq!(rewarding.operator, Decimal::zero());
+
+            let env = test.env();
+            try_remove_delegation_from_gateway(
+                test.deps_mut(),
+                env,
+                mock_info("delegator", &[]),
+                identity.clone(),
+            )
+            .unwrap();
+            let env = test.env();
+            let (res, _) = crate::interval::transactions::perform_pending_epoch_actions(
+                test.deps_mut(),
+                &env,
+                None,
+            )
+            .unwrap();
+            let (to, sent) = get_bank_send_msg(&res).unwrap();
+            assert_eq!(to, "delegator");
+            assert_eq!(
+                sent[0].amount,
+                truncate_reward_amount(
+                    delegator_reward + Decimal::from_atomics(100_000_000u128, 0).unwrap()
+                )
+            );
+
+            // and once the last delegation is gone, so are the rewarding details
+            assert!(storage::GATEWAY_REWARDING
+                .may_load(test.deps().storage, &identity)
+                .unwrap()
+                .is_none());
+        }
+    }
+
     #[cfg(test)]
     mod withdrawing_delegator_reward {
         use super::*;
@@ -1723,6 +2274,9 @@ pub mod tests {
                 active_set_work_factor: None,
                 interval_pool_emission: None,
                 rewarded_set_size: Some(123),
+                gateway_interval_pool_emission: None,
+                nominal_gateways: None,
+                gateway_profit_margin: None,
             };
 
             let env = test.env();
@@ -1756,6 +2310,9 @@ pub mod tests {
                 active_set_work_factor: None,
                 interval_pool_emission: None,
                 rewarded_set_size: None,
+                gateway_interval_pool_emission: None,
+                nominal_gateways: None,
+                gateway_profit_margin: None,
             };
 
             let env = test.env();
@@ -1776,6 +2333,9 @@ pub mod tests {
                 active_set_work_factor: None,
                 interval_pool_emission: None,
                 rewarded_set_size: Some(123),
+                gateway_interval_pool_emission: None,
+                nominal_gateways: None,
+                gateway_profit_margin: None,
             };
 
             let old = storage::REWARDING_PARAMS.load(test.deps().storage).unwrap();
@@ -1811,6 +2371,9 @@ pub mod tests {
                 active_set_work_factor: None,
                 interval_pool_emission: None,
                 rewarded_set_size: Some(123),
+                gateway_interval_pool_emission: None,
+                nominal_gateways: None,
+                gateway_profit_margin: None,
             };
 
             let old = storage::REWARDING_PARAMS.load(test.deps().storage).unwrap();
@@ -1836,6 +2399,9 @@ pub mod tests {
                 active_set_work_factor: None,
                 interval_pool_emission: None,
                 rewarded_set_size: Some(123),
+                gateway_interval_pool_emission: None,
+                nominal_gateways: None,
+                gateway_profit_margin: None,
             };
 
             let old = storage::REWARDING_PARAMS.load(test.deps().storage).unwrap();
@@ -1877,6 +2443,9 @@ pub mod tests {
                 active_set_work_factor: None,
                 interval_pool_emission: None,
                 rewarded_set_size: None,
+                gateway_interval_pool_emission: None,
+                nominal_gateways: None,
+                gateway_profit_margin: None,
             };
 
             let env = test.env();
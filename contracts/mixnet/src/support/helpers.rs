@@ -212,21 +212,14 @@ pub fn validate_family_signature(
     validate_signature(deps, family_member.as_bytes(), signature, family_head)
 }
 
-pub(crate) fn validate_signature(
-    deps: Deps<'_>,
-    signed_bytes: &[u8],
-    signature: &str,
+pub(crate) fn decode_ed25519_identity_key(
     identity: IdentityKeyRef<'_>,
-) -> Result<(), MixnetContractError> {
+) -> Result<[u8; 32], MixnetContractError> {
     let mut identity_bytes = [0u8; 32];
-    let mut signature_bytes = [0u8; 64];
 
     let identity_used_bytes = bs58::decode(identity)
         .into(&mut identity_bytes)
         .map_err(|err| MixnetContractError::MalformedEd25519IdentityKey(err.to_string()))?;
-    let signature_used_bytes = bs58::decode(signature)
-        .into(&mut signature_bytes)
-        .map_err(|err| MixnetContractError::MalformedEd25519Signature(err.to_string()))?;
 
     if identity_used_bytes != 32 {
         return Err(MixnetContractError::MalformedEd25519IdentityKey(
@@ -234,6 +227,22 @@ pub(crate) fn validate_signature(
         ));
     }
 
+    Ok(identity_bytes)
+}
+
+pub(crate) fn validate_signature(
+    deps: Deps<'_>,
+    signed_bytes: &[u8],
+    signature: &str,
+    identity: IdentityKeyRef<'_>,
+) -> Result<(), MixnetContractError> {
+    let mut signature_bytes = [0u8; 64];
+
+    let identity_bytes = decode_ed25519_identity_key(identity)?;
+    let signature_used_bytes = bs58::decode(signature)
+        .into(&mut signature_bytes)
+        .map_err(|err| MixnetContractError::MalformedEd25519Signature(err.to_string()))?;
+
     if signature_used_bytes != 64 {
         return Err(MixnetContractError::MalformedEd25519Signature(
             "Too few bytes provided for the signature".into(),
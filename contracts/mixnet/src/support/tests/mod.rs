@@ -28,6 +28,9 @@ pub mod test_helpers {
     use crate::mixnodes::transactions::{
         try_add_mixnode, try_add_mixnode_on_behalf, try_remove_mixnode,
     };
+    use crate::penalties::transactions::{
+        try_add_penalty_monitor, try_apply_penalty, try_submit_misbehaviour_evidence,
+    };
     use crate::rewards::queries::{
         query_pending_delegator_reward, query_pending_mixnode_operator_reward,
     };
@@ -48,9 +51,13 @@ pub mod test_helpers {
     use cosmwasm_std::{Env, Response, Timestamp, Uint128};
     use crypto::asymmetric::identity::KeyPair;
     use mixnet_contract_common::events::{
-        may_find_attribute, MixnetEventType, DELEGATES_REWARD_KEY, OPERATOR_REWARD_KEY,
+        may_find_attribute, MixnetEventType, DELEGATES_REWARD_KEY, EVIDENCE_ID_KEY,
+        OPERATOR_REWARD_KEY,
     };
     use mixnet_contract_common::mixnode::{MixNodeRewarding, UnbondedMixnode};
+    use mixnet_contract_common::penalties::{
+        EvidenceId, MisbehaviourEvidence, MisbehaviourKind, Penalty,
+    };
     use mixnet_contract_common::pending_events::{PendingEpochEventData, PendingIntervalEventData};
     use mixnet_contract_common::reward_params::{Performance, RewardingParams};
     use mixnet_contract_common::rewarding::simulator::simulated_node::SimulatedNode;
@@ -388,6 +395,79 @@ pub mod test_helpers {
                 .unwrap();
         }
 
+        pub fn add_penalty_monitor(&mut self) -> KeyPair {
+            let keypair = KeyPair::new(&mut self.rng);
+            let env = self.env();
+            let owner = self.owner();
+            try_add_penalty_monitor(
+                self.deps_mut(),
+                env,
+                owner,
+                keypair.public_key().to_base58_string(),
+            )
+            .unwrap();
+            keypair
+        }
+
+        pub fn dummy_evidence(&mut self, mix_id: MixId) -> MisbehaviourEvidence {
+            let identity_key = mixnodes_storage::mixnode_bonds()
+                .load(self.deps().storage, mix_id)
+                .unwrap()
+                .identity()
+                .to_string();
+            let current_epoch = self.current_interval().current_epoch_absolute_id();
+
+            MisbehaviourEvidence {
+                nonce: self.rng.next_u64(),
+                mix_id,
+                identity_key,
+                kind: MisbehaviourKind::PersistentZeroPerformance {
+                    first_epoch: current_epoch,
+                    last_epoch: current_epoch,
+                    monitor_runs: 42,
+                },
+            }
+        }
+
+        pub fn submit_evidence(
+            &mut self,
+            monitor: &KeyPair,
+            evidence: MisbehaviourEvidence,
+        ) -> EvidenceId {
+            let signature = monitor
+                .private_key()
+                .sign(&evidence.signable_bytes().unwrap())
+                .to_base58_string();
+
+            let env = self.env();
+            let res = try_submit_misbehaviour_evidence(
+                self.deps_mut(),
+                env,
+                evidence,
+                monitor.public_key().to_base58_string(),
+                signature,
+            )
+            .unwrap();
+            find_attribute(
+                Some(MixnetEventType::MisbehaviourEvidenceSubmission.to_string()),
+                EVIDENCE_ID_KEY,
+                &res,
+            )
+            .parse()
+            .unwrap()
+        }
+
+        pub fn penalise_mixnode(&mut self, mix_id: MixId, penalty: Penalty) -> EvidenceId {
+            let monitor = self.add_penalty_monitor();
+            let evidence = self.dummy_evidence(mix_id);
+            let evidence_id = self.submit_evidence(&monitor, evidence);
+
+            let env = self.env();
+            let owner = self.owner();
+            try_apply_penalty(self.deps_mut(), env, owner, evidence_id, penalty).unwrap();
+            evidence_id
+        }
+
         pub fn instantiate_simulator(&self, node: MixId) -> Simulator {
             simulator_from_single_node_state(self.deps(), node)
         }
@@ -810,6 +890,9 @@ pub mod test_helpers {
             interval_pool_emission: Percent::from_percentage_value(2).unwrap(),
             rewarded_set_size: 240,
             active_set_size: 100,
+            gateway_interval_pool_emission: Percent::from_percentage_value(1).unwrap(),
+            nominal_gateways: 50,
+            gateway_profit_margin: Percent::from_percentage_value(10).unwrap(),
         }
     }
 
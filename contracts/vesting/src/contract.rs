@@ -15,12 +15,12 @@ use cosmwasm_std::{
 };
 use cw_storage_plus::Bound;
 use mixnet_contract_common::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
-use mixnet_contract_common::{Gateway, MixId, MixNode};
+use mixnet_contract_common::{Gateway, GatewayConfigUpdate, MixId, MixNode};
 use vesting_contract_common::events::{
     new_ownership_transfer_event, new_periodic_vesting_account_event,
-    new_staking_address_update_event, new_track_gateway_unbond_event,
-    new_track_mixnode_unbond_event, new_track_reward_event, new_track_undelegation_event,
-    new_vested_coins_withdraw_event,
+    new_staking_address_update_event, new_track_gateway_decrease_pledge_event,
+    new_track_gateway_unbond_event, new_track_mixnode_unbond_event, new_track_redelegation_event,
+    new_track_reward_event, new_track_undelegation_event, new_vested_coins_withdraw_event,
 };
 use vesting_contract_common::messages::{
     ExecuteMsg, InitMsg, MigrateMsg, QueryMsg, VestingSpecification,
@@ -100,6 +100,9 @@ pub fn execute(
         ExecuteMsg::UndelegateFromMixnode { mix_id } => {
             try_undelegate_from_mixnode(mix_id, info, deps)
         }
+        ExecuteMsg::RedelegateFromMixnode { from, to, amount } => {
+            try_redelegate_from_mixnode(from, to, amount, info, deps)
+        }
         ExecuteMsg::CreateAccount {
             owner_address,
             staking_address,
@@ -122,6 +125,12 @@ pub fn execute(
             mix_id,
             amount,
         } => try_track_undelegation(&owner, mix_id, amount, info, deps),
+        ExecuteMsg::TrackRedelegation {
+            owner,
+            from_mix_id,
+            to_mix_id,
+            amount,
+        } => try_track_redelegation(&owner, from_mix_id, to_mix_id, amount, info, deps),
         ExecuteMsg::BondMixnode {
             mix_node,
             cost_params,
@@ -146,10 +155,22 @@ pub fn execute(
             owner_signature,
             amount,
         } => try_bond_gateway(gateway, owner_signature, amount, info, env, deps),
+        ExecuteMsg::PledgeMoreGateway { amount } => {
+            try_pledge_more_gateway(deps, env, info, amount)
+        }
+        ExecuteMsg::DecreaseGatewayPledge { amount } => {
+            try_decrease_gateway_pledge(amount, info, deps)
+        }
+        ExecuteMsg::UpdateGatewayConfig { new_config } => {
+            try_update_gateway_config(new_config, info, deps)
+        }
         ExecuteMsg::UnbondGateway {} => try_unbond_gateway(info, deps),
         ExecuteMsg::TrackUnbondGateway { owner, amount } => {
             try_track_unbond_gateway(&owner, amount, info, deps)
         }
+        ExecuteMsg::TrackDecreaseGatewayPledge { owner, amount } => {
+            try_track_decrease_gateway_pledge(&owner, amount, info, deps)
+        }
         ExecuteMsg::TransferOwnership { to_address } => {
             try_transfer_ownership(to_address, info, deps)
         }
@@ -340,6 +361,58 @@ pub fn try_bond_gateway(
     account.try_bond_gateway(gateway, owner_signature, pledge, &env, deps.storage)
 }
 
+/// Pledge more tokens towards a gateway bonded with vesting account, sends [mixnet_contract_common::ExecuteMsg::PledgeMoreGatewayOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_pledge_more_gateway(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    amount: Coin,
+) -> Result<Response, ContractError> {
+    let mix_denom = MIX_DENOM.load(deps.storage)?;
+    let additional_pledge = validate_funds(&[amount], mix_denom)?;
+
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_pledge_additional_gateway_tokens(additional_pledge, &env, deps.storage)
+}
+
+/// Decrease the pledge of a gateway bonded with vesting account, sends [mixnet_contract_common::ExecuteMsg::DecreaseGatewayPledgeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_decrease_gateway_pledge(
+    amount: Coin,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let mix_denom = MIX_DENOM.load(deps.storage)?;
+    let decrease_by = validate_funds(&[amount], mix_denom)?;
+
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_decrease_gateway_pledge(decrease_by, deps.storage)
+}
+
+/// Track gateway pledge decrease, invoked by the mixnet contract after the decrease got resolved, message contains the returned coins.
+pub fn try_track_decrease_gateway_pledge(
+    owner: &str,
+    amount: Coin,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    if info.sender != MIXNET_CONTRACT_ADDRESS.load(deps.storage)? {
+        return Err(ContractError::NotMixnetContract(info.sender));
+    }
+    let account = account_from_address(owner, deps.storage, deps.api)?;
+    account.try_track_decrease_gateway_pledge(amount, deps.storage)?;
+    Ok(Response::new().add_event(new_track_gateway_decrease_pledge_event()))
+}
+
+/// Update config for a gateway bonded with vesting account, sends [mixnet_contract_common::ExecuteMsg::UpdateGatewayConfigOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_update_gateway_config(
+    new_config: GatewayConfigUpdate,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_update_gateway_config(new_config, deps.storage)
+}
+
 /// Unbond a gateway, sends [mixnet_contract_common::ExecuteMsg::UnbondGatewayOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
 pub fn try_unbond_gateway(info: MessageInfo, deps: DepsMut<'_>) -> Result<Response, ContractError> {
     let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
@@ -450,6 +523,24 @@ fn try_track_undelegation(
     Ok(Response::new().add_event(new_track_undelegation_event()))
 }
 
+/// Track redelegation, invoked by the mixnet contract after the redelegation has been resolved at the end of the epoch.
+fn try_track_redelegation(
+    address: &str,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    if info.sender != MIXNET_CONTRACT_ADDRESS.load(deps.storage)? {
+        return Err(ContractError::NotMixnetContract(info.sender));
+    }
+    let account = account_from_address(address, deps.storage, deps.api)?;
+
+    account.track_redelegation(from_mix_id, to_mix_id, amount, deps.storage)?;
+    Ok(Response::new().add_event(new_track_redelegation_event()))
+}
+
 /// Delegate to mixnode, sends [mixnet_contract_common::ExecuteMsg::DelegateToMixnodeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS]..
 fn try_delegate_to_mixnode(
     mix_id: MixId,
@@ -496,6 +587,21 @@ fn try_undelegate_from_mixnode(
     account.try_undelegate_from_mixnode(mix_id, deps.storage)
 }
 
+/// Redelegates from one mixnode to another, sends [mixnet_contract_common::ExecuteMsg::RedelegateFromMixnodeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+fn try_redelegate_from_mixnode(
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let mix_denom = MIX_DENOM.load(deps.storage)?;
+    let amount = validate_funds(&[amount], mix_denom)?;
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+
+    account.try_redelegate_from_mixnode(from_mix_id, to_mix_id, amount, deps.storage)
+}
+
 /// Creates a new periodic vesting account, and deposits funds to vest into the contract.
 ///
 /// Callable by ADMIN only, see [instantiate].
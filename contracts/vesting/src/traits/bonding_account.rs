@@ -2,7 +2,7 @@ use crate::errors::ContractError;
 use cosmwasm_std::{Coin, Env, Response, Storage};
 use mixnet_contract_common::{
     mixnode::{MixNodeConfigUpdate, MixNodeCostParams},
-    Gateway, MixNode,
+    Gateway, GatewayConfigUpdate, MixNode,
 };
 
 pub trait MixnodeBondingAccount {
@@ -56,6 +56,25 @@ pub trait GatewayBondingAccount {
         storage: &mut dyn Storage,
     ) -> Result<Response, ContractError>;
 
+    fn try_pledge_additional_gateway_tokens(
+        &self,
+        additional_pledge: Coin,
+        env: &Env,
+        storage: &mut dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_decrease_gateway_pledge(
+        &self,
+        amount: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_track_decrease_gateway_pledge(
+        &self,
+        amount: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError>;
+
     fn try_unbond_gateway(&self, storage: &dyn Storage) -> Result<Response, ContractError>;
 
     fn try_track_unbond_gateway(
@@ -63,4 +82,10 @@ pub trait GatewayBondingAccount {
         amount: Coin,
         storage: &mut dyn Storage,
     ) -> Result<(), ContractError>;
+
+    fn try_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        storage: &mut dyn Storage,
+    ) -> Result<Response, ContractError>;
 }
@@ -23,6 +23,14 @@ pub trait DelegatingAccount {
         storage: &dyn Storage,
     ) -> Result<Response, ContractError>;
 
+    fn try_redelegate_from_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
     // track_delegation performs internal vesting accounting necessary when
     // delegating from a vesting account. It accepts the current block height, the
     // delegation amount and balance of all coins whose denomination exists in
@@ -44,4 +52,13 @@ pub trait DelegatingAccount {
         amount: Coin,
         storage: &mut dyn Storage,
     ) -> Result<(), ContractError>;
+    // track_redelegation moves the tracked delegations between mixnodes after the mixnet contract
+    // has resolved a redelegation. The balance is unaffected since no tokens left the mixnet contract.
+    fn track_redelegation(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError>;
 }
@@ -1,13 +1,13 @@
 use crate::errors::ContractError;
-use crate::storage::save_delegation;
 use crate::storage::MIXNET_CONTRACT_ADDRESS;
+use crate::storage::{remove_delegation, save_delegation, DELEGATIONS};
 use crate::traits::DelegatingAccount;
 use crate::traits::VestingAccount;
-use cosmwasm_std::{wasm_execute, Coin, Env, Response, Storage, Uint128};
+use cosmwasm_std::{wasm_execute, Coin, Env, Order, Response, StdResult, Storage, Uint128};
 use mixnet_contract_common::ExecuteMsg as MixnetExecuteMsg;
 use mixnet_contract_common::MixId;
 use vesting_contract_common::events::{
-    new_vesting_delegation_event, new_vesting_undelegation_event,
+    new_vesting_delegation_event, new_vesting_redelegation_event, new_vesting_undelegation_event,
 };
 
 use super::Account;
@@ -101,6 +101,35 @@ impl DelegatingAccount for Account {
             .add_event(new_vesting_undelegation_event()))
     }
 
+    fn try_redelegate_from_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if !self.any_delegation_for_mix(from_mix_id, storage) {
+            return Err(ContractError::NoSuchDelegation(
+                self.owner_address(),
+                from_mix_id,
+            ));
+        }
+
+        // note: no tokens are being sent as they never leave the mixnet contract
+        let msg = MixnetExecuteMsg::RedelegateFromMixnodeOnBehalf {
+            from: from_mix_id,
+            to: to_mix_id,
+            amount,
+            delegate: self.owner_address().into_string(),
+        };
+        let redelegate_from_mixnode =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(redelegate_from_mixnode)
+            .add_event(new_vesting_redelegation_event()))
+    }
+
     fn track_delegation(
         &self,
         block_timestamp_secs: u64,
@@ -130,4 +159,44 @@ impl DelegatingAccount for Account {
         self.save_balance(new_balance, storage)?;
         Ok(())
     }
+
+    fn track_redelegation(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError> {
+        // the moved amount might include rewards that were never tracked here, so we can't move
+        // more than the tracked delegations. Move the oldest entries first, keeping their timestamps
+        // so that the vesting/free split of the delegated coins stays unchanged.
+        let source_delegations = DELEGATIONS
+            .prefix((self.storage_key(), from_mix_id))
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut left_to_move = amount.amount;
+        for (block_timestamp_secs, tracked) in source_delegations {
+            if left_to_move.is_zero() {
+                break;
+            }
+            let moved = tracked.min(left_to_move);
+            left_to_move -= moved;
+
+            let source_key = (self.storage_key(), from_mix_id, block_timestamp_secs);
+            if moved == tracked {
+                remove_delegation(source_key, storage)?;
+            } else {
+                save_delegation(source_key, tracked - moved, storage)?;
+            }
+
+            let target_key = (self.storage_key(), to_mix_id, block_timestamp_secs);
+            let existing = DELEGATIONS
+                .may_load(storage, target_key)?
+                .unwrap_or_default();
+            save_delegation(target_key, existing + moved, storage)?;
+        }
+
+        Ok(())
+    }
 }
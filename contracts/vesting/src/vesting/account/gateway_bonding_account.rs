@@ -4,9 +4,11 @@ use crate::storage::MIXNET_CONTRACT_ADDRESS;
 use crate::traits::GatewayBondingAccount;
 use crate::traits::VestingAccount;
 use cosmwasm_std::{wasm_execute, Coin, Env, Response, Storage, Uint128};
-use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, Gateway};
+use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, Gateway, GatewayConfigUpdate};
 use vesting_contract_common::events::{
-    new_vesting_gateway_bonding_event, new_vesting_gateway_unbonding_event,
+    new_vesting_gateway_bonding_event, new_vesting_gateway_decrease_pledge_event,
+    new_vesting_gateway_pledge_more_event, new_vesting_gateway_unbonding_event,
+    new_vesting_update_gateway_config_event,
 };
 
 use super::Account;
@@ -66,6 +68,108 @@ impl GatewayBondingAccount for Account {
             .add_event(new_vesting_gateway_bonding_event()))
     }
 
+    fn try_pledge_additional_gateway_tokens(
+        &self,
+        additional_pledge: Coin,
+        env: &Env,
+        storage: &mut dyn Storage,
+    ) -> Result<Response, ContractError> {
+        let current_balance = self.load_balance(storage)?;
+        let total_pledged_after =
+            self.total_pledged_locked(storage, env)? + additional_pledge.amount;
+        let locked_pledge_cap = self.absolute_pledge_cap()?;
+
+        if locked_pledge_cap < total_pledged_after {
+            return Err(ContractError::LockedPledgeCapReached {
+                current: total_pledged_after,
+                cap: locked_pledge_cap,
+            });
+        }
+
+        if current_balance < additional_pledge.amount {
+            return Err(ContractError::InsufficientBalance(
+                self.owner_address().as_str().to_string(),
+                current_balance.u128(),
+            ));
+        }
+
+        let mut pledge_data = if let Some(pledge_data) = self.load_gateway_pledge(storage)? {
+            pledge_data
+        } else {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        };
+
+        pledge_data.amount.amount += additional_pledge.amount;
+        pledge_data.block_time = env.block.time;
+
+        let msg = MixnetExecuteMsg::PledgeMoreGatewayOnBehalf {
+            owner: self.owner_address().into_string(),
+        };
+
+        let new_balance = Uint128::new(current_balance.u128() - additional_pledge.amount.u128());
+
+        let pledge_more_msg = wasm_execute(
+            MIXNET_CONTRACT_ADDRESS.load(storage)?,
+            &msg,
+            vec![additional_pledge],
+        )?;
+
+        self.save_balance(new_balance, storage)?;
+        self.save_gateway_pledge(pledge_data, storage)?;
+
+        Ok(Response::new()
+            .add_message(pledge_more_msg)
+            .add_event(new_vesting_gateway_pledge_more_event()))
+    }
+
+    fn try_decrease_gateway_pledge(
+        &self,
+        amount: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if self.load_gateway_pledge(storage)?.is_none() {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        }
+
+        // the tokens are only returned (and tracked) once the decrease is resolved at the end of the epoch
+        let msg = MixnetExecuteMsg::DecreaseGatewayPledgeOnBehalf {
+            owner: self.owner_address().into_string(),
+            decrease_by: amount,
+        };
+
+        let decrease_pledge_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(decrease_pledge_msg)
+            .add_event(new_vesting_gateway_decrease_pledge_event()))
+    }
+
+    fn try_track_decrease_gateway_pledge(
+        &self,
+        amount: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError> {
+        let mut pledge_data = if let Some(pledge_data) = self.load_gateway_pledge(storage)? {
+            pledge_data
+        } else {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        };
+
+        let new_balance = Uint128::new(self.load_balance(storage)?.u128() + amount.amount.u128());
+        self.save_balance(new_balance, storage)?;
+
+        pledge_data.amount.amount = pledge_data.amount.amount.saturating_sub(amount.amount);
+        self.save_gateway_pledge(pledge_data, storage)?;
+        Ok(())
+    }
+
     fn try_unbond_gateway(&self, storage: &dyn Storage) -> Result<Response, ContractError> {
         let msg = MixnetExecuteMsg::UnbondGatewayOnBehalf {
             owner: self.owner_address().into_string(),
@@ -95,4 +199,22 @@ impl GatewayBondingAccount for Account {
         self.remove_gateway_pledge(storage)?;
         Ok(())
     }
+
+    fn try_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        storage: &mut dyn Storage,
+    ) -> Result<Response, ContractError> {
+        let msg = MixnetExecuteMsg::UpdateGatewayConfigOnBehalf {
+            new_config,
+            owner: self.owner_address().into_string(),
+        };
+
+        let update_gateway_config_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(update_gateway_config_msg)
+            .add_event(new_vesting_update_gateway_config_event()))
+    }
 }
@@ -583,6 +583,75 @@ mod tests {
         assert_eq!(Uint128::zero(), delegated_free.amount);
     }
 
+    #[test]
+    fn test_redelegations() {
+        let mut deps = init_contract();
+        let mut env = mock_env();
+
+        let msg = ExecuteMsg::CreateAccount {
+            owner_address: "owner".to_string(),
+            staking_address: None,
+            vesting_spec: None,
+            cap: Some(PledgeCap::Absolute(Uint128::from(100_000_000_000u128))),
+        };
+        let info = mock_info("admin", &coins(1_000_000_000_000, TEST_COIN_DENOM));
+        let _response = execute(deps.as_mut(), env.clone(), info, msg);
+        let account = load_account(&Addr::unchecked("owner"), &deps.storage)
+            .unwrap()
+            .unwrap();
+
+        let coin = |amount: u128| Coin {
+            amount: Uint128::new(amount),
+            denom: TEST_COIN_DENOM.to_string(),
+        };
+
+        // can't redelegate if there's nothing delegated
+        assert!(account
+            .try_redelegate_from_mixnode(1, 2, coin(1000), &deps.storage)
+            .is_err());
+
+        account
+            .try_delegate_to_mixnode(1, coin(10_000_000_000), &env, &mut deps.storage)
+            .unwrap();
+        env.block.time = env.block.time.plus_seconds(100);
+        account
+            .try_delegate_to_mixnode(1, coin(20_000_000_000), &env, &mut deps.storage)
+            .unwrap();
+        let balance = account.load_balance(&deps.storage).unwrap();
+
+        assert!(account
+            .try_redelegate_from_mixnode(1, 2, coin(15_000_000_000), &deps.storage)
+            .is_ok());
+
+        // the oldest delegations are moved first and the balance stays intact
+        account
+            .track_redelegation(1, 2, coin(15_000_000_000), &mut deps.storage)
+            .unwrap();
+        assert_eq!(
+            account.total_delegations_for_mix(1, &deps.storage).unwrap(),
+            Uint128::new(15_000_000_000)
+        );
+        assert_eq!(
+            account.total_delegations_for_mix(2, &deps.storage).unwrap(),
+            Uint128::new(15_000_000_000)
+        );
+        assert_eq!(
+            account.total_delegations(&deps.storage).unwrap(),
+            Uint128::new(30_000_000_000)
+        );
+        assert_eq!(balance, account.load_balance(&deps.storage).unwrap());
+
+        // moving more than tracked (i.e. including the rewards) moves everything that's left
+        account
+            .track_redelegation(1, 2, coin(50_000_000_000), &mut deps.storage)
+            .unwrap();
+        assert!(!account.any_delegation_for_mix(1, &deps.storage));
+        assert_eq!(
+            account.total_delegations_for_mix(2, &deps.storage).unwrap(),
+            Uint128::new(30_000_000_000)
+        );
+    }
+
     #[test]
     fn test_mixnode_bonds() {
         let mut deps = init_contract();
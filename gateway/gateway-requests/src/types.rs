@@ -21,7 +21,7 @@ use std::{
 use tungstenite::protocol::Message;
 
 #[cfg(feature = "coconut")]
-use coconut_interface::Credential;
+use coconut_interface::{Credential, TicketCredential};
 #[cfg(not(feature = "coconut"))]
 use credentials::token::bandwidth::TokenCredential;
 
@@ -140,6 +140,10 @@ pub enum ClientControlRequest {
         enc_credential: Vec<u8>,
         iv: Vec<u8>,
     },
+    BandwidthTicket {
+        enc_ticket: Vec<u8>,
+        iv: Vec<u8>,
+    },
     ClaimFreeTestnetBandwidth,
 }
 
@@ -183,6 +187,32 @@ impl ClientControlRequest {
             .map_err(|_| GatewayRequestsError::MalformedEncryption)
     }
 
+    #[cfg(feature = "coconut")]
+    pub fn new_enc_coconut_bandwidth_ticket(
+        ticket: &TicketCredential,
+        shared_key: &SharedKeys,
+        iv: IV,
+    ) -> Self {
+        let serialized_ticket = ticket.as_bytes();
+        let enc_ticket = shared_key.encrypt_and_tag(&serialized_ticket, Some(iv.inner()));
+
+        ClientControlRequest::BandwidthTicket {
+            enc_ticket,
+            iv: iv.to_bytes(),
+        }
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn try_from_enc_coconut_bandwidth_ticket(
+        enc_ticket: Vec<u8>,
+        shared_key: &SharedKeys,
+        iv: IV,
+    ) -> Result<TicketCredential, GatewayRequestsError> {
+        let ticket_bytes = shared_key.decrypt_tagged(&enc_ticket, Some(iv.inner()))?;
+        TicketCredential::from_bytes(&ticket_bytes)
+            .map_err(|_| GatewayRequestsError::MalformedEncryption)
+    }
+
     #[cfg(not(feature = "coconut"))]
     pub fn new_enc_token_bandwidth_credential(
         credential: &TokenCredential,
@@ -0,0 +1,14 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+CREATE TABLE spent_credentials
+(
+    blinded_serial_number_bs58 TEXT    NOT NULL PRIMARY KEY UNIQUE,
+    credential                 BLOB    NOT NULL,
+    proposal_id                INTEGER,
+    redeemed                   BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX `spent_credentials_redeemed_index` ON `spent_credentials` (`redeemed`);
@@ -0,0 +1,24 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- credentials spent at other gateways are synced from the chain without their content,
+-- so the `credential` column has to become nullable
+ALTER TABLE spent_credentials RENAME TO _spent_credentials_old;
+
+CREATE TABLE spent_credentials
+(
+    blinded_serial_number_bs58 TEXT    NOT NULL PRIMARY KEY UNIQUE,
+    credential                 BLOB,
+    proposal_id                INTEGER,
+    redeemed                   BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+INSERT INTO spent_credentials (blinded_serial_number_bs58, credential, proposal_id, redeemed)
+SELECT blinded_serial_number_bs58, credential, proposal_id, redeemed
+FROM _spent_credentials_old;
+
+DROP TABLE _spent_credentials_old;
+
+CREATE INDEX `spent_credentials_redeemed_index` ON `spent_credentials` (`redeemed`);
@@ -0,0 +1,13 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- individual tickets of divisible credentials. each one has its own serial number,
+-- so double spending is checked per ticket rather than per credential
+CREATE TABLE spent_tickets
+(
+    ticket_serial_number_bs58 TEXT    NOT NULL PRIMARY KEY UNIQUE,
+    ticket                    BLOB    NOT NULL,
+    redeemed                  BOOLEAN NOT NULL DEFAULT FALSE
+);
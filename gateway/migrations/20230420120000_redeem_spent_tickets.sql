@@ -0,0 +1,14 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- the tickets are redeemed on chain just like the whole credentials, so they have to share
+-- the serial numbers (synced from the chain) with them in order to be rejected at other gateways
+ALTER TABLE spent_credentials ADD COLUMN ticket BOOLEAN NOT NULL DEFAULT FALSE;
+
+INSERT OR IGNORE INTO spent_credentials (blinded_serial_number_bs58, credential, redeemed, ticket)
+SELECT ticket_serial_number_bs58, ticket, redeemed, TRUE
+FROM spent_tickets;
+
+DROP TABLE spent_tickets;
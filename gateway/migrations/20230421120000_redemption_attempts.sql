@@ -0,0 +1,8 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- credentials that keep failing to get redeemed are retried after the other ones
+-- and given up on once they reach the maximum number of attempts
+ALTER TABLE spent_credentials ADD COLUMN redemption_attempts INTEGER NOT NULL DEFAULT 0;
@@ -187,8 +187,12 @@ mod tests {
         };
 
         // The test is really if this instantiates with InMemStorage without panics
-        let _gateway =
-            Gateway::new_from_keys_and_storage(config, identity_keys, sphinx_keys, InMemStorage)
-                .await;
+        let _gateway = Gateway::new_from_keys_and_storage(
+            config,
+            identity_keys,
+            sphinx_keys,
+            InMemStorage::default(),
+        )
+        .await;
     }
 }
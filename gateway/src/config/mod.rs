@@ -29,6 +29,11 @@ const DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE: usize = 128;
 const DEFAULT_STORED_MESSAGE_FILENAME_LENGTH: u16 = 16;
 const DEFAULT_MESSAGE_RETRIEVAL_LIMIT: i64 = 100;
 
+const DEFAULT_CREDENTIAL_REDEMPTION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_CREDENTIAL_REDEMPTION_BATCH_SIZE: i64 = 32;
+const DEFAULT_CREDENTIAL_REDEMPTION_MAX_ATTEMPTS: i64 = 24;
+const DEFAULT_SPENT_CREDENTIALS_SYNC_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 pub fn missing_string_value() -> String {
     MISSING_VALUE.to_string()
 }
@@ -298,6 +303,26 @@ impl Config {
         self.debug.message_retrieval_limit
     }
 
+    #[cfg(feature = "coconut")]
+    pub fn get_credential_redemption_interval(&self) -> Duration {
+        self.debug.credential_redemption_interval
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_credential_redemption_batch_size(&self) -> i64 {
+        self.debug.credential_redemption_batch_size
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_credential_redemption_max_attempts(&self) -> i64 {
+        self.debug.credential_redemption_max_attempts
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_spent_credentials_sync_interval(&self) -> Duration {
+        self.debug.spent_credentials_sync_interval
+    }
+
     pub fn get_version(&self) -> &str {
         &self.gateway.version
     }
@@ -462,6 +487,21 @@ struct Debug {
     /// Number of messages from offline client that can be pulled at once from the storage.
     message_retrieval_limit: i64,
 
+    /// Delay between subsequent attempts of redeeming the locally spent credentials on chain.
+    #[serde(with = "humantime_serde")]
+    credential_redemption_interval: Duration,
+
+    /// Maximum number of spent credentials that are going to be redeemed in a single transaction.
+    credential_redemption_batch_size: i64,
+
+    /// Number of failed attempts of redeeming a spent credential after which it is given up on.
+    credential_redemption_max_attempts: i64,
+
+    /// Delay between subsequent synchronisations of the credentials spent on chain
+    /// (for example at other gateways) into the local storage.
+    #[serde(with = "humantime_serde")]
+    spent_credentials_sync_interval: Duration,
+
     /// Specifies whether the mixnode should be using the legacy framing for the sphinx packets.
     // it's set to true by default. The reason for that decision is to preserve compatibility with the
     // existing nodes whilst everyone else is upgrading and getting the code for handling the new field.
@@ -479,6 +519,10 @@ impl Default for Debug {
             maximum_connection_buffer_size: DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE,
             stored_messages_filename_length: DEFAULT_STORED_MESSAGE_FILENAME_LENGTH,
             message_retrieval_limit: DEFAULT_MESSAGE_RETRIEVAL_LIMIT,
+            credential_redemption_interval: DEFAULT_CREDENTIAL_REDEMPTION_INTERVAL,
+            credential_redemption_batch_size: DEFAULT_CREDENTIAL_REDEMPTION_BATCH_SIZE,
+            credential_redemption_max_attempts: DEFAULT_CREDENTIAL_REDEMPTION_MAX_ATTEMPTS,
+            spent_credentials_sync_interval: DEFAULT_SPENT_CREDENTIALS_SYNC_INTERVAL,
             // TODO: remember to change it in one of future releases!!
             use_legacy_framed_packet_version: true,
         }
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 #[cfg(feature = "coconut")]
-use coconut_interface::Credential;
+use coconut_interface::{Credential, TicketCredential};
+#[cfg(feature = "coconut")]
+use credentials::coconut::bandwidth::TICKET_INDEX_BITS;
 #[cfg(not(feature = "coconut"))]
 use credentials::token::bandwidth::TokenCredential;
 
@@ -27,6 +29,17 @@ impl From<Credential> for Bandwidth {
     }
 }
 
+#[cfg(feature = "coconut")]
+impl From<TicketCredential> for Bandwidth {
+    fn from(ticket: TicketCredential) -> Self {
+        let token_value = ticket.ticket_value(TICKET_INDEX_BITS);
+        let bandwidth_bytes = token_value * network_defaults::BYTES_PER_UTOKEN;
+        Bandwidth {
+            value: bandwidth_bytes,
+        }
+    }
+}
+
 #[cfg(not(feature = "coconut"))]
 impl From<TokenCredential> for Bandwidth {
     fn from(credential: TokenCredential) -> Self {
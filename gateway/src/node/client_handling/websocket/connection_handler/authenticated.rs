@@ -20,7 +20,13 @@ use tokio_tungstenite::tungstenite::protocol::Message;
 
 use crate::node::client_handling::bandwidth::Bandwidth;
 use crate::node::client_handling::FREE_TESTNET_BANDWIDTH_VALUE;
+#[cfg(feature = "coconut")]
+use coconut_bandwidth_contract_common::expiry::{effective_expiry_epoch, epoch_at, is_spendable};
 use gateway_requests::iv::IV;
+#[cfg(feature = "coconut")]
+use network_defaults::{DIVISIBLE_VOUCHER_INFO, VOUCHER_INFO};
+#[cfg(feature = "coconut")]
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Error)]
 pub(crate) enum RequestHandlingError {
@@ -63,6 +69,30 @@ pub(crate) enum RequestHandlingError {
     #[error("There was a problem with the proposal id: {reason}")]
     ProposalIdError { reason: String },
 
+    #[cfg(feature = "coconut")]
+    #[error("The provided bandwidth credential has already been spent")]
+    CredentialAlreadySpent,
+
+    #[cfg(feature = "coconut")]
+    #[error("The provided bandwidth ticket has already been spent")]
+    TicketAlreadySpent,
+
+    #[cfg(feature = "coconut")]
+    #[error("The provided bandwidth credential is divisible and can only be spent as individual tickets")]
+    DivisibleCredential,
+
+    #[cfg(feature = "coconut")]
+    #[error("The provided bandwidth ticket does not belong to a divisible credential")]
+    IndivisibleCredential,
+
+    #[cfg(feature = "coconut")]
+    #[error("The provided credential of type {voucher_info} is not a bandwidth credential")]
+    NotBandwidthCredential { voucher_info: String },
+
+    #[cfg(feature = "coconut")]
+    #[error("The provided bandwidth credential has expired at the end of epoch {expiry_epoch}")]
+    CredentialExpired { expiry_epoch: u64 },
+
     #[cfg(feature = "coconut")]
     #[error("Coconut interface error - {0}")]
     CoconutInterfaceError(#[from] coconut_interface::error::CoconutInterfaceError),
@@ -89,6 +119,22 @@ impl IntoWSMessage for Result<ServerResponse, RequestHandlingError> {
     }
 }
 
+/// Rejects credentials (and tickets) whose expiry epoch has already passed, as their redemption
+/// could no longer be guaranteed.
+#[cfg(feature = "coconut")]
+fn ensure_not_expired(expiry_epoch: u64) -> Result<(), RequestHandlingError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    if !is_spendable(expiry_epoch, epoch_at(now)) {
+        return Err(RequestHandlingError::CredentialExpired {
+            expiry_epoch: effective_expiry_epoch(expiry_epoch),
+        });
+    }
+    Ok(())
+}
+
 pub(crate) struct AuthenticatedHandler<R, S, St> {
     inner: FreshHandler<R, S, St>,
     client: ClientDetails,
@@ -207,21 +253,39 @@ where
             iv,
         )?;
 
-        if !credential.verify(
-            self.inner
-                .coconut_verifier
-                .as_ref()
-                .aggregated_verification_key(),
-        ) {
+        // otherwise the same voucher could be spent both as a whole and as tickets
+        if credential.voucher_info() == DIVISIBLE_VOUCHER_INFO {
+            return Err(RequestHandlingError::DivisibleCredential);
+        }
+        // credentials of other types, e.g. granting access to services, are not worth any bandwidth
+        if credential.voucher_info() != VOUCHER_INFO {
+            return Err(RequestHandlingError::NotBandwidthCredential {
+                voucher_info: credential.voucher_info().to_string(),
+            });
+        }
+
+        if !self
+            .inner
+            .coconut_verifier
+            .verify_credential(&credential)
+            .await
+        {
             return Err(RequestHandlingError::InvalidBandwidthCredential(
                 String::from("credential failed to verify on gateway"),
             ));
         }
+        ensure_not_expired(credential.expiry_epoch())?;
 
-        self.inner
-            .coconut_verifier
-            .release_funds(&credential)
+        // the credential is only redeemed on chain later on, in batches, by the `CredentialRedeemer`,
+        // so for now just make sure it hasn't been spent here before
+        let newly_spent = self
+            .inner
+            .storage
+            .insert_spent_credential(credential.blinded_serial_number(), credential.as_bytes())
             .await?;
+        if !newly_spent {
+            return Err(RequestHandlingError::CredentialAlreadySpent);
+        }
 
         let bandwidth = Bandwidth::from(credential);
         let bandwidth_value = bandwidth.value();
@@ -242,6 +306,65 @@ where
         Ok(ServerResponse::Bandwidth { available_total })
     }
 
+    #[cfg(feature = "coconut")]
+    /// Tries to handle the received bandwidth ticket, i.e. a single part of a divisible credential,
+    /// by checking its correctness and, if successful, increases client's bandwidth by the value
+    /// of that single ticket.
+    ///
+    /// # Arguments
+    ///
+    /// * `enc_ticket`: raw encrypted bandwidth ticket to verify.
+    /// * `iv`: fresh iv used for the ticket.
+    async fn handle_coconut_bandwidth_ticket(
+        &mut self,
+        enc_ticket: Vec<u8>,
+        iv: Vec<u8>,
+    ) -> Result<ServerResponse, RequestHandlingError> {
+        let iv = IV::try_from_bytes(&iv)?;
+        let ticket = ClientControlRequest::try_from_enc_coconut_bandwidth_ticket(
+            enc_ticket,
+            &self.client.shared_keys,
+            iv,
+        )?;
+
+        if ticket.voucher_info() != DIVISIBLE_VOUCHER_INFO {
+            return Err(RequestHandlingError::IndivisibleCredential);
+        }
+
+        if !self.inner.coconut_verifier.verify_ticket(&ticket).await {
+            return Err(RequestHandlingError::InvalidBandwidthCredential(
+                String::from("ticket failed to verify on gateway"),
+            ));
+        }
+        ensure_not_expired(ticket.expiry_epoch())?;
+
+        // every ticket has its own serial number, so the double spending check is done per ticket.
+        // just like the whole credentials, the tickets are redeemed on chain later on by the `CredentialRedeemer`
+        let newly_spent = self
+            .inner
+            .storage
+            .insert_spent_ticket(ticket.ticket_serial_number(), ticket.as_bytes())
+            .await?;
+        if !newly_spent {
+            return Err(RequestHandlingError::TicketAlreadySpent);
+        }
+
+        let bandwidth = Bandwidth::from(ticket);
+        let bandwidth_value = bandwidth.value();
+
+        if bandwidth_value > i64::MAX as u64 {
+            warn!("Somehow we received bandwidth value higher than 9223372036854775807. We don't really want to deal with this now");
+            return Err(RequestHandlingError::UnsupportedBandwidthValue(
+                bandwidth_value,
+            ));
+        }
+
+        self.increase_bandwidth(bandwidth_value as i64).await?;
+        let available_total = self.get_available_bandwidth().await?;
+
+        Ok(ServerResponse::Bandwidth { available_total })
+    }
+
     #[cfg(not(feature = "coconut"))]
     /// Tries to handle the received bandwidth request by checking correctness of the received data
     /// and if successful, increases client's bandwidth by an appropriate amount.
@@ -384,6 +507,11 @@ where
                     .handle_bandwidth(enc_credential, iv)
                     .await
                     .into_ws_message(),
+                #[cfg(feature = "coconut")]
+                ClientControlRequest::BandwidthTicket { enc_ticket, iv } => self
+                    .handle_coconut_bandwidth_ticket(enc_ticket, iv)
+                    .await
+                    .into_ws_message(),
                 ClientControlRequest::ClaimFreeTestnetBandwidth => self
                     .handle_claim_testnet_bandwidth()
                     .await
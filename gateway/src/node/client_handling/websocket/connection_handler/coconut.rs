@@ -1,30 +1,81 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
+use futures::channel::{mpsc, oneshot};
+use futures::StreamExt;
 use log::*;
 
-use coconut_interface::{Credential, VerificationKey};
+use coconut_interface::{Credential, TicketCredential, VerificationKey};
+use credentials::coconut::bandwidth::TICKET_INDEX_BITS;
+use nym_api_requests::coconut::{
+    BatchVerifyCredentialBody, VerifyCredentialBody, VerifyTicketBody,
+};
 use validator_client::{
     nymd::{
-        cosmwasm_client::logs::{find_attribute, BANDWIDTH_PROPOSAL_ID},
+        cosmwasm_client::logs::{find_attribute, Log, BANDWIDTH_PROPOSAL_ID},
         traits::{CoconutBandwidthSigningClient, MultisigQueryClient, MultisigSigningClient},
-        Coin, Fee, SigningNymdClient,
+        AccountId, Coin, Fee, ProposalStatus, SigningNymdClient,
     },
     Client, CoconutApiClient,
 };
 
 use super::authenticated::RequestHandlingError;
+use crate::node::storage::Storage;
 
 const ONE_HOUR_SEC: u64 = 3600;
 const MAX_FEEGRANT_UNYM: u128 = 10000;
+const MAX_VERIFICATION_BATCH_SIZE: usize = 64;
+
+type VerificationRequest = (Credential, oneshot::Sender<bool>);
+pub(crate) type VerificationRequestSender = mpsc::UnboundedSender<VerificationRequest>;
+type VerificationRequestReceiver = mpsc::UnboundedReceiver<VerificationRequest>;
+
+/// Credential spent at this gateway, either as a whole or as a single ticket of a divisible credential.
+pub(crate) enum SpentCredential {
+    Whole(Credential),
+    Ticket(TicketCredential),
+}
+
+impl SpentCredential {
+    /// Serial number the credential (or the ticket) is spent with on chain.
+    pub(crate) fn serial_number(&self) -> String {
+        match self {
+            SpentCredential::Whole(credential) => credential.blinded_serial_number(),
+            SpentCredential::Ticket(ticket) => ticket.ticket_serial_number(),
+        }
+    }
+
+    fn value(&self) -> u64 {
+        match self {
+            SpentCredential::Whole(credential) => credential.voucher_value(),
+            SpentCredential::Ticket(ticket) => ticket.ticket_value(TICKET_INDEX_BITS),
+        }
+    }
+
+    fn expiry_epoch(&self) -> u64 {
+        match self {
+            SpentCredential::Whole(credential) => credential.expiry_epoch(),
+            SpentCredential::Ticket(ticket) => ticket.expiry_epoch(),
+        }
+    }
+}
+
+/// Locally verified credential that is waiting to get its funds released on chain.
+struct PendingRedemption {
+    credential: SpentCredential,
+    proposal_id: Option<u64>,
+    attempts: i64,
+}
 
 pub(crate) struct CoconutVerifier {
     nym_api_clients: Vec<CoconutApiClient>,
     nymd_client: Client<SigningNymdClient>,
     mix_denom_base: String,
-    aggregated_verification_key: VerificationKey,
+    aggregated_verification_key: Arc<VerificationKey>,
+    verification_requests: VerificationRequestSender,
 }
 
 impl CoconutVerifier {
@@ -33,6 +84,7 @@ impl CoconutVerifier {
         nymd_client: Client<SigningNymdClient>,
         mix_denom_base: String,
         aggregated_verification_key: VerificationKey,
+        verification_requests: VerificationRequestSender,
     ) -> Result<Self, RequestHandlingError> {
         if api_clients.is_empty() {
             return Err(RequestHandlingError::NotEnoughNymAPIs {
@@ -44,33 +96,50 @@ impl CoconutVerifier {
             nym_api_clients: api_clients,
             nymd_client,
             mix_denom_base,
-            aggregated_verification_key,
+            aggregated_verification_key: Arc::new(aggregated_verification_key),
+            verification_requests,
         })
     }
 
-    pub fn aggregated_verification_key(&self) -> &VerificationKey {
-        &self.aggregated_verification_key
+    /// Verifies the ticket against the aggregated verification key. Tickets can't be verified
+    /// in batches, but the pairings are just as expensive, so they're computed on the blocking pool.
+    pub async fn verify_ticket(&self, ticket: &TicketCredential) -> bool {
+        let ticket = ticket.clone();
+        let verification_key = Arc::clone(&self.aggregated_verification_key);
+        match tokio::task::spawn_blocking(move || {
+            ticket.verify(&verification_key, TICKET_INDEX_BITS)
+        })
+        .await
+        {
+            Ok(verification_result) => verification_result,
+            Err(err) => {
+                error!("the ticket verification task has failed: {err}");
+                false
+            }
+        }
     }
 
-    pub async fn release_funds(&self, credential: &Credential) -> Result<(), RequestHandlingError> {
-        // Use a custom multiplier for revoke, as the default one (1.3)
-        // isn't enough
-        let revoke_fee = Some(Fee::Auto(Some(1.5)));
+    /// Verifies the credential against the aggregated verification key, together with any other
+    /// credentials received at about the same time, through the `CredentialBatchVerifier`.
+    pub async fn verify_credential(&self, credential: &Credential) -> bool {
+        let (result_sender, result_receiver) = oneshot::channel();
+        if self
+            .verification_requests
+            .unbounded_send((credential.clone(), result_sender))
+            .is_err()
+        {
+            warn!("the credential batch verifier has stopped. Verifying the credential directly");
+            return credential.verify(&self.aggregated_verification_key);
+        }
 
-        let res = self
-            .nymd_client
-            .nymd
-            .spend_credential(
-                Coin::new(
-                    credential.voucher_value().into(),
-                    self.mix_denom_base.clone(),
-                ),
-                credential.blinded_serial_number(),
-                self.nymd_client.nymd.address().to_string(),
-                None,
-            )
-            .await?;
-        let proposal_id = find_attribute(&res.logs, "wasm", BANDWIDTH_PROPOSAL_ID)
+        match result_receiver.await {
+            Ok(verification_result) => verification_result,
+            Err(_) => credential.verify(&self.aggregated_verification_key),
+        }
+    }
+
+    fn proposal_id_from_log(log: &Log) -> Result<u64, RequestHandlingError> {
+        find_attribute(std::slice::from_ref(log), "wasm", BANDWIDTH_PROPOSAL_ID)
             .ok_or(RequestHandlingError::ProposalIdError {
                 reason: String::from("proposal id not found"),
             })?
@@ -78,52 +147,629 @@ impl CoconutVerifier {
             .parse::<u64>()
             .map_err(|_| RequestHandlingError::ProposalIdError {
                 reason: String::from("proposal id could not be parsed to u64"),
-            })?;
+            })
+    }
 
+    /// Retrieves blinded serial numbers of the credentials that have been spent on chain after
+    /// the one with the provided spend id, along with the spend id to continue from next time.
+    pub async fn get_spent_credentials_after(
+        &self,
+        spend_id: Option<u64>,
+    ) -> Result<(Vec<String>, Option<u64>), RequestHandlingError> {
+        let (spent, last_spend_id) = self
+            .nymd_client
+            .get_nymd_spent_credentials_after(spend_id)
+            .await?;
+        let blinded_serial_numbers = spent
+            .into_iter()
+            .map(|spent| spent.blinded_serial_number().to_string())
+            .collect();
+        Ok((blinded_serial_numbers, last_spend_id))
+    }
+
+    /// Spends all the provided credentials in a single transaction and returns ids of the
+    /// proposals created for releasing their funds, in the same order as the credentials.
+    pub async fn spend_credentials(
+        &self,
+        credentials: &[&SpentCredential],
+    ) -> Result<Vec<u64>, RequestHandlingError> {
+        let to_spend = credentials
+            .iter()
+            .map(|credential| {
+                (
+                    Coin::new(credential.value().into(), self.mix_denom_base.clone()),
+                    credential.serial_number(),
+                    credential.expiry_epoch(),
+                )
+            })
+            .collect();
+
+        let res = self
+            .nymd_client
+            .nymd
+            .spend_credentials(to_spend, self.nymd_client.nymd.address().to_string(), None)
+            .await?;
+
+        (0..credentials.len())
+            .map(|msg_index| {
+                let log = res
+                    .logs
+                    .iter()
+                    .find(|log| log.msg_index == msg_index)
+                    .ok_or(RequestHandlingError::ProposalIdError {
+                        reason: format!("no logs found for message {msg_index}"),
+                    })?;
+                Self::proposal_id_from_log(log)
+            })
+            .collect()
+    }
+
+    /// Makes sure the proposal is to release the funds of the provided credential and returns
+    /// whether it has already been executed, e.g. by the previous attempt of releasing them.
+    async fn check_proposal(
+        &self,
+        credential: &SpentCredential,
+        proposal_id: u64,
+    ) -> Result<bool, RequestHandlingError> {
         let proposal = self.nymd_client.nymd.get_proposal(proposal_id).await?;
-        if !credential.has_blinded_serial_number(&proposal.description)? {
+        let matches_serial_number = match credential {
+            SpentCredential::Whole(credential) => {
+                credential.has_blinded_serial_number(&proposal.description)?
+            }
+            SpentCredential::Ticket(ticket) => {
+                ticket.ticket_serial_number() == proposal.description
+            }
+        };
+        if !matches_serial_number {
             return Err(RequestHandlingError::ProposalIdError {
                 reason: String::from("proposal has different serial number"),
             });
         }
+        Ok(proposal.status == ProposalStatus::Executed)
+    }
+
+    async fn request_vote(
+        &self,
+        client: &CoconutApiClient,
+        req: &VerifyCredentialBody,
+    ) -> Result<(), RequestHandlingError> {
+        let ret = client.api_client.verify_bandwidth_credential(req).await?;
+        if !ret.verification_result {
+            debug!("Validator {} didn't accept the credential. It will probably vote No on the spending proposal", client.api_client.nym_api_client.current_url());
+        }
+        Ok(())
+    }
+
+    async fn request_ticket_vote(
+        &self,
+        client: &CoconutApiClient,
+        req: &VerifyTicketBody,
+    ) -> Result<(), RequestHandlingError> {
+        let ret = client.api_client.verify_bandwidth_ticket(req).await?;
+        if !ret.verification_result {
+            debug!("Validator {} didn't accept the ticket. It will probably vote No on the spending proposal", client.api_client.nym_api_client.current_url());
+        }
+        Ok(())
+    }
+
+    // gets the nym-api to verify all the credentials at once, falling back to requesting
+    // the votes one by one if it doesn't support the batch verification yet
+    async fn request_votes(
+        &self,
+        client: &CoconutApiClient,
+        batch_req: &BatchVerifyCredentialBody,
+    ) -> Vec<Result<(), RequestHandlingError>> {
+        match client
+            .api_client
+            .batch_verify_bandwidth_credentials(batch_req)
+            .await
+        {
+            Ok(ret) => {
+                let rejected = ret
+                    .verification_results
+                    .iter()
+                    .filter(|verified| !**verified)
+                    .count();
+                if rejected > 0 {
+                    debug!("Validator {} didn't accept {rejected} credentials. It will probably vote No on their spending proposals", client.api_client.nym_api_client.current_url());
+                }
+                batch_req.credentials().iter().map(|_| Ok(())).collect()
+            }
+            Err(err) => {
+                debug!(
+                    "Validator {} failed to verify the credentials in a batch: {err}. Requesting the votes individually",
+                    client.api_client.nym_api_client.current_url()
+                );
+                let mut results = Vec::with_capacity(batch_req.credentials().len());
+                for req in batch_req.credentials() {
+                    results.push(self.request_vote(client, req).await);
+                }
+                results
+            }
+        }
+    }
+
+    /// Gets the nym-apis to vote on the spending proposals of all the provided credentials and
+    /// executes them afterwards. The fee allowances for the nym-apis are granted (and revoked)
+    /// once for the whole batch.
+    ///
+    /// Whole credentials are verified by the nym-apis in a single batch, while the tickets
+    /// are verified one by one. Proposals that have already been executed, e.g. by a previous
+    /// attempt that failed afterwards, are reported as released straight away.
+    ///
+    /// Returns the result of releasing the funds of each individual credential, in the same order.
+    pub async fn release_funds_batch(
+        &self,
+        credentials: &[(&SpentCredential, u64)],
+    ) -> Result<Vec<Result<(), RequestHandlingError>>, RequestHandlingError> {
+        let mut results = Vec::with_capacity(credentials.len());
+        let mut pending_execution = Vec::with_capacity(credentials.len());
+        let mut requested = Vec::with_capacity(credentials.len());
+        let mut requests = Vec::with_capacity(credentials.len());
+        let mut ticket_requests = Vec::new();
+        for (credential, proposal_id) in credentials {
+            let result = match self.check_proposal(credential, *proposal_id).await {
+                // there's nothing left to do for the proposals that have already been executed
+                Ok(true) => {
+                    results.push(Ok(()));
+                    continue;
+                }
+                Ok(false) => Ok(()),
+                Err(err) => Err(err),
+            };
+            if result.is_ok() {
+                pending_execution.push(results.len());
+                match credential {
+                    SpentCredential::Whole(credential) => {
+                        requested.push(results.len());
+                        requests.push(VerifyCredentialBody::new(
+                            credential.clone(),
+                            *proposal_id,
+                            self.nymd_client.nymd.address().clone(),
+                        ));
+                    }
+                    SpentCredential::Ticket(ticket) => ticket_requests.push((
+                        results.len(),
+                        VerifyTicketBody::new(
+                            ticket.clone(),
+                            *proposal_id,
+                            self.nymd_client.nymd.address().clone(),
+                        ),
+                    )),
+                }
+            }
+            results.push(result);
+        }
+        if pending_execution.is_empty() {
+            return Ok(results);
+        }
 
-        let req = nym_api_requests::coconut::VerifyCredentialBody::new(
-            credential.clone(),
-            proposal_id,
-            self.nymd_client.nymd.address().clone(),
-        );
+        let mut granted = Vec::with_capacity(self.nym_api_clients.len());
         for client in self.nym_api_clients.iter() {
-            self.nymd_client
-                .nymd
-                .grant_allowance(
-                    &client.cosmos_address,
-                    vec![Coin::new(MAX_FEEGRANT_UNYM, self.mix_denom_base.clone())],
-                    SystemTime::now().checked_add(Duration::from_secs(ONE_HOUR_SEC)),
-                    // It would be nice to be able to filter deeper, but for now only the msg type filter is avaialable
-                    vec![String::from("/cosmwasm.wasm.v1.MsgExecuteContract")],
-                    "Create allowance to vote the release of funds".to_string(),
-                    None,
-                )
-                .await?;
-            let ret = client.api_client.verify_bandwidth_credential(&req).await;
-            self.nymd_client
+            if let Err(err) = self.grant_allowance(&client.cosmos_address).await {
+                // don't leave behind the allowances that have already been granted
+                self.revoke_allowances(&granted).await;
+                return Err(err);
+            }
+            granted.push(&client.cosmos_address);
+        }
+
+        let batch_req = BatchVerifyCredentialBody::new(requests);
+        if !batch_req.credentials().is_empty() {
+            for client in self.nym_api_clients.iter() {
+                let votes = self.request_votes(client, &batch_req).await;
+                for (&idx, vote) in requested.iter().zip(votes) {
+                    if let Err(err) = vote {
+                        if results[idx].is_ok() {
+                            results[idx] = Err(err);
+                        }
+                    }
+                }
+            }
+        }
+        for (idx, req) in &ticket_requests {
+            for client in self.nym_api_clients.iter() {
+                if let Err(err) = self.request_ticket_vote(client, req).await {
+                    if results[*idx].is_ok() {
+                        results[*idx] = Err(err);
+                    }
+                }
+            }
+        }
+
+        for idx in pending_execution {
+            if results[idx].is_ok() {
+                let (_, proposal_id) = credentials[idx];
+                results[idx] = self
+                    .nymd_client
+                    .nymd
+                    .execute_proposal(proposal_id, None)
+                    .await
+                    .map(|_| ())
+                    .map_err(Into::into);
+            }
+        }
+
+        // the funds have been released (or not) by now, so failing to clean up shouldn't hide that
+        self.revoke_allowances(&granted).await;
+
+        Ok(results)
+    }
+
+    async fn grant_allowance(&self, grantee: &AccountId) -> Result<(), RequestHandlingError> {
+        self.nymd_client
+            .nymd
+            .grant_allowance(
+                grantee,
+                vec![Coin::new(MAX_FEEGRANT_UNYM, self.mix_denom_base.clone())],
+                SystemTime::now().checked_add(Duration::from_secs(ONE_HOUR_SEC)),
+                // It would be nice to be able to filter deeper, but for now only the msg type filter is avaialable
+                vec![String::from("/cosmwasm.wasm.v1.MsgExecuteContract")],
+                "Create allowance to vote the release of funds".to_string(),
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn revoke_allowances(&self, grantees: &[&AccountId]) {
+        // Use a custom multiplier for revoke, as the default one (1.3)
+        // isn't enough
+        let revoke_fee = Some(Fee::Auto(Some(1.5)));
+
+        for grantee in grantees {
+            if let Err(err) = self
+                .nymd_client
                 .nymd
                 .revoke_allowance(
-                    &client.cosmos_address,
+                    grantee,
                     "Cleanup the previous allowance for releasing funds".to_string(),
                     revoke_fee.clone(),
                 )
-                .await?;
-            if !ret?.verification_result {
-                debug!("Validator {} didn't accept the credential. It will probably vote No on the spending proposal", client.api_client.nym_api_client.current_url());
+                .await
+            {
+                warn!("failed to revoke the fee allowance of {grantee}: {err}");
+            }
+        }
+    }
+}
+
+/// Background task verifying the credentials received from the clients. All the requests that got
+/// queued up while the previous batch was being processed are verified together, sharing a single
+/// multi-pairing, so that a burst of clients doesn't pay for the full verification of each
+/// credential, while a lone request is not delayed at all.
+pub(crate) struct CredentialBatchVerifier {
+    aggregated_verification_key: Arc<VerificationKey>,
+    requests: VerificationRequestReceiver,
+    max_batch_size: usize,
+}
+
+impl CredentialBatchVerifier {
+    pub(crate) fn new(
+        aggregated_verification_key: VerificationKey,
+    ) -> (Self, VerificationRequestSender) {
+        let (sender, requests) = mpsc::unbounded();
+        (
+            CredentialBatchVerifier {
+                aggregated_verification_key: Arc::new(aggregated_verification_key),
+                requests,
+                max_batch_size: MAX_VERIFICATION_BATCH_SIZE,
+            },
+            sender,
+        )
+    }
+
+    // waits for the next request and takes whatever else is already queued, up to the max batch size
+    async fn next_batch(&mut self) -> Option<Vec<VerificationRequest>> {
+        let first = self.requests.next().await?;
+        let mut batch = vec![first];
+        while batch.len() < self.max_batch_size {
+            match self.requests.try_next() {
+                Ok(Some(request)) => batch.push(request),
+                _ => break,
             }
         }
+        Some(batch)
+    }
 
-        self.nymd_client
-            .nymd
-            .execute_proposal(proposal_id, None)
+    async fn verify_batch(&self, batch: Vec<VerificationRequest>) {
+        let (credentials, result_senders): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+        let verification_key = Arc::clone(&self.aggregated_verification_key);
+
+        // the pairings are expensive, so don't stall the runtime while computing them
+        let results = match tokio::task::spawn_blocking(move || {
+            Credential::batch_verify(&credentials, &verification_key)
+        })
+        .await
+        {
+            Ok(results) => results,
+            Err(err) => {
+                error!("the credential verification task has failed: {err}");
+                return;
+            }
+        };
+        if result_senders.len() > 1 {
+            debug!("Verified a batch of {} credentials", result_senders.len());
+        }
+
+        for (result_sender, verification_result) in result_senders.into_iter().zip(results) {
+            // the client might have disconnected in the meantime, in which case there's nobody to notify
+            let _ = result_sender.send(verification_result);
+        }
+    }
+
+    pub(crate) async fn run(&mut self) {
+        while let Some(batch) = self.next_batch().await {
+            self.verify_batch(batch).await
+        }
+    }
+
+    pub(crate) fn start(mut self) {
+        tokio::spawn(async move { self.run().await });
+    }
+}
+
+/// Background task periodically pulling the credentials spent on chain, for example at other
+/// gateways, into the local storage, so that any attempt of spending them again here could be
+/// rejected straight away. Only the credentials spent since the previous synchronisation are pulled.
+pub(crate) struct SpentCredentialsSynchronizer<St> {
+    verifier: Arc<CoconutVerifier>,
+    storage: St,
+    sync_interval: Duration,
+    last_spend_id: Option<u64>,
+}
+
+impl<St> SpentCredentialsSynchronizer<St>
+where
+    St: Storage + 'static,
+{
+    pub(crate) fn new(
+        verifier: Arc<CoconutVerifier>,
+        storage: St,
+        sync_interval: Duration,
+    ) -> Self {
+        SpentCredentialsSynchronizer {
+            verifier,
+            storage,
+            sync_interval,
+            last_spend_id: None,
+        }
+    }
+
+    async fn sync_spent_credentials(&mut self) -> Result<(), RequestHandlingError> {
+        let (spent, last_spend_id) = self
+            .verifier
+            .get_spent_credentials_after(self.last_spend_id)
             .await?;
+        debug!("Synchronising {} credentials spent on chain", spent.len());
+        self.storage.insert_remote_spent_credentials(spent).await?;
+        // only move the cursor once the credentials are safely stored
+        self.last_spend_id = last_spend_id;
+        Ok(())
+    }
+
+    pub(crate) async fn run(&mut self) {
+        let mut interval = tokio::time::interval(self.sync_interval);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.sync_spent_credentials().await {
+                warn!("failed to synchronise the spent credentials: {err}");
+            }
+        }
+    }
+
+    pub(crate) fn start(mut self) {
+        tokio::spawn(async move { self.run().await });
+    }
+}
+
+/// Background task periodically redeeming, in batches, the credentials that were spent
+/// (and verified locally) at this gateway. Credentials that failed to get redeemed are left
+/// in the storage and are retried in the subsequent runs, after the ones that haven't failed yet,
+/// until they reach the maximum number of attempts.
+pub(crate) struct CredentialRedeemer<St> {
+    verifier: Arc<CoconutVerifier>,
+    storage: St,
+    redemption_interval: Duration,
+    batch_size: i64,
+    max_attempts: i64,
+}
+
+impl<St> CredentialRedeemer<St>
+where
+    St: Storage + 'static,
+{
+    pub(crate) fn new(
+        verifier: Arc<CoconutVerifier>,
+        storage: St,
+        redemption_interval: Duration,
+        batch_size: i64,
+        max_attempts: i64,
+    ) -> Self {
+        CredentialRedeemer {
+            verifier,
+            storage,
+            redemption_interval,
+            batch_size,
+            max_attempts,
+        }
+    }
+
+    // a single invalid credential (say, one that has already been spent at a different gateway)
+    // would fail the whole batch transaction, so in that case fallback to spending them one by one
+    async fn spend_credentials(&self, credentials: &[&SpentCredential]) -> Vec<Option<u64>> {
+        match self.verifier.spend_credentials(credentials).await {
+            Ok(proposal_ids) => proposal_ids.into_iter().map(Some).collect(),
+            Err(err) if credentials.len() > 1 => {
+                warn!("failed to spend the batch of {} credentials: {err}. Going to spend them individually", credentials.len());
+                let mut proposal_ids = Vec::with_capacity(credentials.len());
+                for credential in credentials {
+                    match self.verifier.spend_credentials(&[*credential]).await {
+                        Ok(mut ids) => proposal_ids.push(ids.pop()),
+                        Err(err) => {
+                            warn!(
+                                "failed to spend credential {}: {err}",
+                                credential.serial_number()
+                            );
+                            proposal_ids.push(None)
+                        }
+                    }
+                }
+                proposal_ids
+            }
+            Err(err) => {
+                for credential in credentials {
+                    warn!(
+                        "failed to spend credential {}: {err}",
+                        credential.serial_number()
+                    );
+                }
+                vec![None; credentials.len()]
+            }
+        }
+    }
+
+    async fn record_failed_redemption(
+        &self,
+        blinded_serial_number: String,
+        previous_attempts: i64,
+    ) -> Result<(), RequestHandlingError> {
+        if previous_attempts + 1 >= self.max_attempts {
+            error!(
+                "giving up on redeeming credential {blinded_serial_number} after {} failed attempts",
+                previous_attempts + 1
+            );
+        }
+        self.storage
+            .record_failed_redemption(blinded_serial_number)
+            .await?;
+        Ok(())
+    }
+
+    async fn redeem_pending(&self) -> Result<(), RequestHandlingError> {
+        let stored = self
+            .storage
+            .get_unredeemed_credentials(self.batch_size, self.max_attempts)
+            .await?;
+        if stored.is_empty() {
+            return Ok(());
+        }
+
+        let mut pending = Vec::with_capacity(stored.len());
+        for stored_credential in stored {
+            let credential = if stored_credential.ticket {
+                TicketCredential::from_bytes(&stored_credential.credential)
+                    .map(SpentCredential::Ticket)
+            } else {
+                Credential::from_bytes(&stored_credential.credential).map(SpentCredential::Whole)
+            };
+            match credential {
+                Ok(credential) => pending.push(PendingRedemption {
+                    credential,
+                    proposal_id: stored_credential.proposal_id.map(|id| id as u64),
+                    attempts: stored_credential.redemption_attempts,
+                }),
+                Err(err) => {
+                    error!(
+                        "failed to recover stored credential {}: {err}",
+                        stored_credential.blinded_serial_number_bs58
+                    );
+                    self.record_failed_redemption(
+                        stored_credential.blinded_serial_number_bs58,
+                        stored_credential.redemption_attempts,
+                    )
+                    .await?;
+                }
+            }
+        }
+        info!("Attempting to redeem {} spent credentials", pending.len());
+
+        // spend the credentials that haven't yet been spent on chain in a single transaction
+        let unspent = pending
+            .iter()
+            .filter(|pending| pending.proposal_id.is_none())
+            .collect::<Vec<_>>();
+        if !unspent.is_empty() {
+            let credentials = unspent
+                .iter()
+                .map(|pending| &pending.credential)
+                .collect::<Vec<_>>();
+            let proposal_ids = self.spend_credentials(&credentials).await;
+            for (pending, proposal_id) in unspent.iter().zip(proposal_ids.iter()) {
+                match proposal_id {
+                    Some(proposal_id) => {
+                        self.storage
+                            .set_credential_proposal_id(
+                                pending.credential.serial_number(),
+                                *proposal_id as i64,
+                            )
+                            .await?
+                    }
+                    None => {
+                        self.record_failed_redemption(
+                            pending.credential.serial_number(),
+                            pending.attempts,
+                        )
+                        .await?
+                    }
+                }
+            }
+            let mut proposal_ids = proposal_ids.into_iter();
+            for pending in pending
+                .iter_mut()
+                .filter(|pending| pending.proposal_id.is_none())
+            {
+                pending.proposal_id = proposal_ids.next().flatten();
+            }
+        }
+
+        let to_release = pending
+            .iter()
+            .filter_map(|pending| {
+                pending
+                    .proposal_id
+                    .map(|proposal_id| (&pending.credential, proposal_id))
+            })
+            .collect::<Vec<_>>();
+        if to_release.is_empty() {
+            return Ok(());
+        }
+        let attempts = pending
+            .iter()
+            .filter(|pending| pending.proposal_id.is_some())
+            .map(|pending| pending.attempts);
+        let results = self.verifier.release_funds_batch(&to_release).await?;
+        for (((credential, proposal_id), result), attempts) in
+            to_release.into_iter().zip(results).zip(attempts)
+        {
+            match result {
+                Ok(_) => {
+                    self.storage
+                        .mark_credential_redeemed(credential.serial_number())
+                        .await?
+                }
+                Err(err) => {
+                    warn!("failed to release funds for proposal {proposal_id}: {err}");
+                    self.record_failed_redemption(credential.serial_number(), attempts)
+                        .await?
+                }
+            }
+        }
 
         Ok(())
     }
+
+    pub(crate) async fn run(&self) {
+        let mut interval = tokio::time::interval(self.redemption_interval);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.redeem_pending().await {
+                warn!("failed to redeem spent credentials: {err}. They will be retried later");
+            }
+        }
+    }
+
+    pub(crate) fn start(self) {
+        tokio::spawn(async move { self.run().await });
+    }
 }
@@ -23,7 +23,9 @@ use std::sync::Arc;
 
 use crate::config::persistence::pathfinder::GatewayPathfinder;
 #[cfg(feature = "coconut")]
-use crate::node::client_handling::websocket::connection_handler::coconut::CoconutVerifier;
+use crate::node::client_handling::websocket::connection_handler::coconut::{
+    CoconutVerifier, CredentialBatchVerifier, CredentialRedeemer, SpentCredentialsSynchronizer,
+};
 #[cfg(feature = "coconut")]
 use credentials::coconut::utils::obtain_aggregate_verification_key;
 #[cfg(feature = "coconut")]
@@ -306,16 +308,39 @@ where
             let validators_verification_key = obtain_aggregate_verification_key(&api_clients)
                 .await
                 .expect("failed to contact validators to obtain their verification keys");
-            CoconutVerifier::new(
+            let (batch_verifier, verification_requests) =
+                CredentialBatchVerifier::new(validators_verification_key.clone());
+            batch_verifier.start();
+            let coconut_verifier = CoconutVerifier::new(
                 api_clients,
                 nymd_client,
                 std::env::var(network_defaults::var_names::MIX_DENOM)
                     .expect("mix denom base not set"),
                 validators_verification_key,
+                verification_requests,
             )
-            .expect("Could not create coconut verifier")
+            .expect("Could not create coconut verifier");
+            Arc::new(coconut_verifier)
         };
 
+        #[cfg(feature = "coconut")]
+        CredentialRedeemer::new(
+            Arc::clone(&coconut_verifier),
+            self.storage.clone(),
+            self.config.get_credential_redemption_interval(),
+            self.config.get_credential_redemption_batch_size(),
+            self.config.get_credential_redemption_max_attempts(),
+        )
+        .start();
+
+        #[cfg(feature = "coconut")]
+        SpentCredentialsSynchronizer::new(
+            Arc::clone(&coconut_verifier),
+            self.storage.clone(),
+            self.config.get_spent_credentials_sync_interval(),
+        )
+        .start();
+
         let mix_forwarding_channel = self.start_packet_forwarder();
 
         let active_clients_store = ActiveClientsStore::new();
@@ -341,7 +366,7 @@ where
             mix_forwarding_channel,
             active_clients_store,
             #[cfg(feature = "coconut")]
-            Arc::new(coconut_verifier),
+            coconut_verifier,
         );
 
         info!("Finished nym gateway startup procedure - it should now be able to receive mix and client traffic!");
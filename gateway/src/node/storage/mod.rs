@@ -4,8 +4,9 @@
 use crate::node::storage::bandwidth::BandwidthManager;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::inboxes::InboxManager;
-use crate::node::storage::models::{PersistedSharedKeys, StoredMessage};
+use crate::node::storage::models::{PersistedSharedKeys, PersistedSpentCredential, StoredMessage};
 use crate::node::storage::shared_keys::SharedKeysManager;
+use crate::node::storage::spent_credentials::SpentCredentialsManager;
 use async_trait::async_trait;
 use gateway_requests::registration::handshake::SharedKeys;
 use log::{debug, error};
@@ -13,11 +14,19 @@ use nymsphinx::DestinationAddressBytes;
 use sqlx::ConnectOptions;
 use std::path::Path;
 
+#[cfg(test)]
+use std::collections::hash_map::Entry;
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+
 mod bandwidth;
 pub(crate) mod error;
 mod inboxes;
 mod models;
 mod shared_keys;
+mod spent_credentials;
 
 #[async_trait]
 pub(crate) trait Storage: Send + Sync {
@@ -134,6 +143,90 @@ pub(crate) trait Storage: Send + Sync {
         client_address: DestinationAddressBytes,
         amount: i64,
     ) -> Result<(), StorageError>;
+
+    /// Stores a locally verified credential so that it could be redeemed on chain at later time.
+    /// Returns `false` if a credential with the same blinded serial number has already been stored,
+    /// i.e. it has already been spent at this gateway.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number`: base58-encoded blinded serial number of the credential.
+    /// * `credential`: serialized credential.
+    async fn insert_spent_credential(
+        &self,
+        blinded_serial_number: String,
+        credential: Vec<u8>,
+    ) -> Result<bool, StorageError>;
+
+    /// Stores a locally verified ticket of a divisible credential so that it could be redeemed later on.
+    /// Returns `false` if a ticket with the same serial number has already been stored,
+    /// i.e. it has already been spent at this (or, once synced, any other) gateway.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticket_serial_number`: base58-encoded serial number of the ticket.
+    /// * `ticket`: serialized ticket.
+    async fn insert_spent_ticket(
+        &self,
+        ticket_serial_number: String,
+        ticket: Vec<u8>,
+    ) -> Result<bool, StorageError>;
+
+    /// Retrieves spent credentials and tickets that are yet to be redeemed on chain, starting with
+    /// the ones that have failed to get redeemed the least number of times.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit`: maximum number of credentials to retrieve.
+    /// * `max_attempts`: number of failed redemption attempts after which the credential is given up on.
+    async fn get_unredeemed_credentials(
+        &self,
+        limit: i64,
+        max_attempts: i64,
+    ) -> Result<Vec<PersistedSpentCredential>, StorageError>;
+
+    /// Stores blinded serial numbers of credentials that have already been spent on chain
+    /// (for example at other gateways) so that they could be rejected without any further checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_numbers`: base58-encoded blinded serial numbers of the spent credentials.
+    async fn insert_remote_spent_credentials(
+        &self,
+        blinded_serial_numbers: Vec<String>,
+    ) -> Result<(), StorageError>;
+
+    /// Associates the spent credential with the multisig proposal releasing its funds.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number`: base58-encoded blinded serial number of the credential.
+    /// * `proposal_id`: id of the proposal created when spending the credential on chain.
+    async fn set_credential_proposal_id(
+        &self,
+        blinded_serial_number: String,
+        proposal_id: i64,
+    ) -> Result<(), StorageError>;
+
+    /// Marks the spent credential as fully redeemed on chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number`: base58-encoded blinded serial number of the credential.
+    async fn mark_credential_redeemed(
+        &self,
+        blinded_serial_number: String,
+    ) -> Result<(), StorageError>;
+
+    /// Records a failed attempt of redeeming the spent credential on chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number`: base58-encoded blinded serial number of the credential.
+    async fn record_failed_redemption(
+        &self,
+        blinded_serial_number: String,
+    ) -> Result<(), StorageError>;
 }
 
 // note that clone here is fine as upon cloning the same underlying pool will be used
@@ -142,6 +235,7 @@ pub(crate) struct PersistentStorage {
     shared_key_manager: SharedKeysManager,
     inbox_manager: InboxManager,
     bandwidth_manager: BandwidthManager,
+    spent_credentials_manager: SpentCredentialsManager,
 }
 
 impl PersistentStorage {
@@ -187,7 +281,8 @@ impl PersistentStorage {
         Ok(PersistentStorage {
             shared_key_manager: SharedKeysManager::new(connection_pool.clone()),
             inbox_manager: InboxManager::new(connection_pool.clone(), message_retrieval_limit),
-            bandwidth_manager: BandwidthManager::new(connection_pool),
+            bandwidth_manager: BandwidthManager::new(connection_pool.clone()),
+            spent_credentials_manager: SpentCredentialsManager::new(connection_pool),
         })
     }
 }
@@ -304,18 +399,147 @@ impl Storage for PersistentStorage {
             .await?;
         Ok(())
     }
+
+    async fn insert_spent_credential(
+        &self,
+        blinded_serial_number: String,
+        credential: Vec<u8>,
+    ) -> Result<bool, StorageError> {
+        let inserted = self
+            .spent_credentials_manager
+            .insert_spent_credential(&blinded_serial_number, &credential)
+            .await?;
+        Ok(inserted)
+    }
+
+    async fn insert_spent_ticket(
+        &self,
+        ticket_serial_number: String,
+        ticket: Vec<u8>,
+    ) -> Result<bool, StorageError> {
+        let inserted = self
+            .spent_credentials_manager
+            .insert_spent_ticket(&ticket_serial_number, &ticket)
+            .await?;
+        Ok(inserted)
+    }
+
+    async fn get_unredeemed_credentials(
+        &self,
+        limit: i64,
+        max_attempts: i64,
+    ) -> Result<Vec<PersistedSpentCredential>, StorageError> {
+        let credentials = self
+            .spent_credentials_manager
+            .get_unredeemed_credentials(limit, max_attempts)
+            .await?;
+        Ok(credentials)
+    }
+
+    async fn insert_remote_spent_credentials(
+        &self,
+        blinded_serial_numbers: Vec<String>,
+    ) -> Result<(), StorageError> {
+        self.spent_credentials_manager
+            .insert_remote_spent_credentials(&blinded_serial_numbers)
+            .await?;
+        Ok(())
+    }
+
+    async fn set_credential_proposal_id(
+        &self,
+        blinded_serial_number: String,
+        proposal_id: i64,
+    ) -> Result<(), StorageError> {
+        self.spent_credentials_manager
+            .set_proposal_id(&blinded_serial_number, proposal_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn mark_credential_redeemed(
+        &self,
+        blinded_serial_number: String,
+    ) -> Result<(), StorageError> {
+        self.spent_credentials_manager
+            .mark_redeemed(&blinded_serial_number)
+            .await?;
+        Ok(())
+    }
+
+    async fn record_failed_redemption(
+        &self,
+        blinded_serial_number: String,
+    ) -> Result<(), StorageError> {
+        self.spent_credentials_manager
+            .increment_redemption_attempts(&blinded_serial_number)
+            .await?;
+        Ok(())
+    }
 }
 
 /// In-memory implementation of `Storage`. The intention is primarily in testing environments.
 #[cfg(test)]
-#[derive(Clone)]
-pub(crate) struct InMemStorage;
+#[derive(Clone, Default)]
+pub(crate) struct InMemStorage {
+    spent_credentials: Arc<Mutex<HashMap<String, InMemSpentCredential>>>,
+}
+
+/// Spent credential as kept by the `InMemStorage`, mirroring a row of the `spent_credentials` table.
+#[cfg(test)]
+struct InMemSpentCredential {
+    credential: Option<Vec<u8>>,
+    proposal_id: Option<i64>,
+    redeemed: bool,
+    ticket: bool,
+    redemption_attempts: i64,
+}
 
 #[cfg(test)]
 impl InMemStorage {
     #[allow(unused)]
     async fn init<P: AsRef<Path> + Send>() -> Result<Self, StorageError> {
-        todo!()
+        Ok(InMemStorage::default())
+    }
+
+    fn insert_local_spent_credential(
+        &self,
+        blinded_serial_number: String,
+        credential: Vec<u8>,
+        ticket: bool,
+    ) -> bool {
+        match self
+            .spent_credentials
+            .lock()
+            .unwrap()
+            .entry(blinded_serial_number)
+        {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(InMemSpentCredential {
+                    credential: Some(credential),
+                    proposal_id: None,
+                    redeemed: false,
+                    ticket,
+                    redemption_attempts: 0,
+                });
+                true
+            }
+        }
+    }
+
+    fn update_spent_credential<F>(&self, blinded_serial_number: &str, update: F)
+    where
+        F: FnOnce(&mut InMemSpentCredential),
+    {
+        if let Some(spent) = self
+            .spent_credentials
+            .lock()
+            .unwrap()
+            .get_mut(blinded_serial_number)
+        {
+            update(spent)
+        }
     }
 }
 
@@ -393,4 +617,151 @@ impl Storage for InMemStorage {
     ) -> Result<(), StorageError> {
         todo!()
     }
+
+    async fn insert_spent_credential(
+        &self,
+        blinded_serial_number: String,
+        credential: Vec<u8>,
+    ) -> Result<bool, StorageError> {
+        Ok(self.insert_local_spent_credential(blinded_serial_number, credential, false))
+    }
+
+    async fn insert_spent_ticket(
+        &self,
+        ticket_serial_number: String,
+        ticket: Vec<u8>,
+    ) -> Result<bool, StorageError> {
+        Ok(self.insert_local_spent_credential(ticket_serial_number, ticket, true))
+    }
+
+    async fn get_unredeemed_credentials(
+        &self,
+        limit: i64,
+        max_attempts: i64,
+    ) -> Result<Vec<PersistedSpentCredential>, StorageError> {
+        let mut unredeemed = self
+            .spent_credentials
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, spent)| !spent.redeemed && spent.redemption_attempts < max_attempts)
+            .filter_map(|(blinded_serial_number, spent)| {
+                spent
+                    .credential
+                    .clone()
+                    .map(|credential| PersistedSpentCredential {
+                        blinded_serial_number_bs58: blinded_serial_number.clone(),
+                        credential,
+                        proposal_id: spent.proposal_id,
+                        redeemed: spent.redeemed,
+                        ticket: spent.ticket,
+                        redemption_attempts: spent.redemption_attempts,
+                    })
+            })
+            .collect::<Vec<_>>();
+        unredeemed.sort_by_key(|spent| spent.redemption_attempts);
+        unredeemed.truncate(limit as usize);
+        Ok(unredeemed)
+    }
+
+    async fn insert_remote_spent_credentials(
+        &self,
+        blinded_serial_numbers: Vec<String>,
+    ) -> Result<(), StorageError> {
+        let mut spent_credentials = self.spent_credentials.lock().unwrap();
+        for blinded_serial_number in blinded_serial_numbers {
+            spent_credentials
+                .entry(blinded_serial_number)
+                .or_insert(InMemSpentCredential {
+                    credential: None,
+                    proposal_id: None,
+                    redeemed: true,
+                    ticket: false,
+                    redemption_attempts: 0,
+                });
+        }
+        Ok(())
+    }
+
+    async fn set_credential_proposal_id(
+        &self,
+        blinded_serial_number: String,
+        proposal_id: i64,
+    ) -> Result<(), StorageError> {
+        self.update_spent_credential(&blinded_serial_number, |spent| {
+            spent.proposal_id = Some(proposal_id)
+        });
+        Ok(())
+    }
+
+    async fn mark_credential_redeemed(
+        &self,
+        blinded_serial_number: String,
+    ) -> Result<(), StorageError> {
+        self.update_spent_credential(&blinded_serial_number, |spent| spent.redeemed = true);
+        Ok(())
+    }
+
+    async fn record_failed_redemption(
+        &self,
+        blinded_serial_number: String,
+    ) -> Result<(), StorageError> {
+        self.update_spent_credential(&blinded_serial_number, |spent| {
+            spent.redemption_attempts += 1
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn unredeemed_credentials_are_retrieved_by_redemption_attempts() {
+        let storage = InMemStorage::default();
+        for serial_number in ["a", "b", "c"] {
+            assert!(storage
+                .insert_spent_credential(serial_number.to_string(), vec![1, 2, 3])
+                .await
+                .unwrap());
+        }
+        assert!(!storage
+            .insert_spent_ticket("a".to_string(), vec![4, 5, 6])
+            .await
+            .unwrap());
+        storage
+            .insert_remote_spent_credentials(vec!["d".to_string()])
+            .await
+            .unwrap();
+
+        storage
+            .record_failed_redemption("a".to_string())
+            .await
+            .unwrap();
+        storage
+            .record_failed_redemption("a".to_string())
+            .await
+            .unwrap();
+        storage
+            .record_failed_redemption("b".to_string())
+            .await
+            .unwrap();
+        storage
+            .mark_credential_redeemed("c".to_string())
+            .await
+            .unwrap();
+
+        let unredeemed = storage.get_unredeemed_credentials(10, 3).await.unwrap();
+        let serial_numbers = unredeemed
+            .iter()
+            .map(|spent| spent.blinded_serial_number_bs58.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(serial_numbers, vec!["b", "a"]);
+
+        // credentials that reached the maximum number of attempts are given up on
+        let unredeemed = storage.get_unredeemed_credentials(10, 2).await.unwrap();
+        assert_eq!(unredeemed.len(), 1);
+        assert_eq!(unredeemed[0].blinded_serial_number_bs58, "b");
+    }
 }
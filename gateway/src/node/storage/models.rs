@@ -18,3 +18,16 @@ pub(crate) struct PersistedBandwidth {
     pub(crate) client_address_bs58: String,
     pub(crate) available: i64,
 }
+
+// the spent credentials are only ever redeemed by coconut-enabled gateways
+#[cfg_attr(not(feature = "coconut"), allow(dead_code))]
+pub(crate) struct PersistedSpentCredential {
+    pub(crate) blinded_serial_number_bs58: String,
+    pub(crate) credential: Vec<u8>,
+    pub(crate) proposal_id: Option<i64>,
+    #[allow(dead_code)]
+    pub(crate) redeemed: bool,
+    /// Whether the entry is a single ticket of a divisible credential rather than a whole credential.
+    pub(crate) ticket: bool,
+    pub(crate) redemption_attempts: i64,
+}
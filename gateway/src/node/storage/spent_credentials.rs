@@ -0,0 +1,184 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::storage::models::PersistedSpentCredential;
+
+#[derive(Clone)]
+pub(crate) struct SpentCredentialsManager {
+    connection_pool: sqlx::SqlitePool,
+}
+
+impl SpentCredentialsManager {
+    /// Creates new instance of the `SpentCredentialsManager` with the provided sqlite connection pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_pool`: database connection pool to use.
+    pub(crate) fn new(connection_pool: sqlx::SqlitePool) -> Self {
+        SpentCredentialsManager { connection_pool }
+    }
+
+    /// Inserts new spent credential into the storage, unless one with the same blinded serial
+    /// number already exists. Returns whether the credential has been inserted.
+    ///
+    /// Note that the check and the insertion happen within the same statement (and thus
+    /// transaction), so the same credential can't ever be accepted twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    /// * `credential`: serialized credential that is going to be redeemed on chain.
+    pub(crate) async fn insert_spent_credential(
+        &self,
+        blinded_serial_number_bs58: &str,
+        credential: &[u8],
+    ) -> Result<bool, sqlx::Error> {
+        let res = sqlx::query!(
+            r#"
+                INSERT OR IGNORE INTO spent_credentials(blinded_serial_number_bs58, credential, redeemed)
+                VALUES (?, ?, FALSE)
+            "#,
+            blinded_serial_number_bs58,
+            credential
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(res.rows_affected() == 1)
+    }
+
+    /// Inserts new spent ticket of a divisible credential into the storage, unless a ticket or
+    /// a credential with the same serial number already exists. Returns whether the ticket has been inserted.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticket_serial_number_bs58`: base58-encoded serial number of the ticket.
+    /// * `ticket`: serialized ticket.
+    pub(crate) async fn insert_spent_ticket(
+        &self,
+        ticket_serial_number_bs58: &str,
+        ticket: &[u8],
+    ) -> Result<bool, sqlx::Error> {
+        let res = sqlx::query!(
+            r#"
+                INSERT OR IGNORE INTO spent_credentials(blinded_serial_number_bs58, credential, redeemed, ticket)
+                VALUES (?, ?, FALSE, TRUE)
+            "#,
+            ticket_serial_number_bs58,
+            ticket
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(res.rows_affected() == 1)
+    }
+
+    /// Retrieves up to `limit` spent credentials (and tickets) that have not yet been redeemed on chain,
+    /// starting with the ones that have failed to get redeemed the least number of times.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit`: maximum number of credentials to retrieve.
+    /// * `max_attempts`: number of failed redemption attempts after which the credential is no longer retrieved.
+    pub(crate) async fn get_unredeemed_credentials(
+        &self,
+        limit: i64,
+        max_attempts: i64,
+    ) -> Result<Vec<PersistedSpentCredential>, sqlx::Error> {
+        sqlx::query_as!(
+            PersistedSpentCredential,
+            r#"
+                SELECT blinded_serial_number_bs58, credential as "credential!", proposal_id, redeemed as "redeemed: bool", ticket as "ticket: bool", redemption_attempts
+                FROM spent_credentials
+                WHERE redeemed = FALSE AND credential IS NOT NULL AND redemption_attempts < ?
+                ORDER BY redemption_attempts
+                LIMIT ?
+            "#,
+            max_attempts,
+            limit
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Inserts blinded serial numbers of credentials that have been spent elsewhere, i.e. at other
+    /// gateways, so that they would be rejected straight away if they were to be presented here.
+    /// Serial numbers that are already known are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_numbers_bs58`: base58-encoded blinded serial numbers of the credentials.
+    pub(crate) async fn insert_remote_spent_credentials(
+        &self,
+        blinded_serial_numbers_bs58: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+        for blinded_serial_number_bs58 in blinded_serial_numbers_bs58 {
+            sqlx::query!(
+                r#"
+                    INSERT OR IGNORE INTO spent_credentials(blinded_serial_number_bs58, redeemed)
+                    VALUES (?, TRUE)
+                "#,
+                blinded_serial_number_bs58
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Sets the id of the multisig proposal created for releasing the funds of the credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    /// * `proposal_id`: id of the associated proposal.
+    pub(crate) async fn set_proposal_id(
+        &self,
+        blinded_serial_number_bs58: &str,
+        proposal_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE spent_credentials SET proposal_id = ? WHERE blinded_serial_number_bs58 = ?",
+            proposal_id,
+            blinded_serial_number_bs58
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Increments the number of failed attempts of redeeming the credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    pub(crate) async fn increment_redemption_attempts(
+        &self,
+        blinded_serial_number_bs58: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE spent_credentials SET redemption_attempts = redemption_attempts + 1 WHERE blinded_serial_number_bs58 = ?",
+            blinded_serial_number_bs58
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Marks the credential as redeemed on chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `blinded_serial_number_bs58`: base58-encoded blinded serial number of the credential.
+    pub(crate) async fn mark_redeemed(
+        &self,
+        blinded_serial_number_bs58: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE spent_credentials SET redeemed = TRUE WHERE blinded_serial_number_bs58 = ?",
+            blinded_serial_number_bs58
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+CREATE TABLE mixnode_latency
+(
+    mixnode_details_id INTEGER NOT NULL,
+    latency_ms         REAL    NOT NULL,
+    jitter_ms          REAL    NOT NULL,
+    timestamp          INTEGER NOT NULL
+);
+
+CREATE TABLE gateway_latency
+(
+    gateway_details_id INTEGER NOT NULL,
+    latency_ms         REAL    NOT NULL,
+    jitter_ms          REAL    NOT NULL,
+    timestamp          INTEGER NOT NULL
+);
+
+CREATE INDEX `mixnode_latency_index` ON `mixnode_latency` (`mixnode_details_id`, `timestamp` desc);
+CREATE INDEX `gateway_latency_index` ON `gateway_latency` (`gateway_details_id`, `timestamp` desc);
@@ -0,0 +1,9 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- reliabilities of the client-facing and mix-facing sides of the gateways,
+-- NULL for the older entries and whenever the particular side could not have been tested
+ALTER TABLE gateway_status ADD COLUMN ingress_reliability INTEGER;
+ALTER TABLE gateway_status ADD COLUMN egress_reliability INTEGER;
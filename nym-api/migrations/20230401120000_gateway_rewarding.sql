@@ -0,0 +1,7 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- number of gateways that were sent rewarding messages alongside the mixnodes
+ALTER TABLE rewarding_report ADD COLUMN eligible_gateways INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,22 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- every request for issuing a partial coconut credential that got to check its deposit
+CREATE TABLE issuance_log
+(
+    id            INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    tx_hash       VARCHAR NOT NULL,
+    -- value and expiry epoch of the deposit, as claimed in the request (and verified if issued)
+    deposit_value INTEGER,
+    deposit_epoch INTEGER,
+    requester_ip  VARCHAR,
+    timestamp     INTEGER NOT NULL,
+    issued        BOOLEAN NOT NULL,
+    error         VARCHAR
+);
+
+-- a credential can only ever be issued once for the same deposit
+CREATE UNIQUE INDEX issuance_log_issued_deposit ON issuance_log (tx_hash) WHERE issued;
+CREATE INDEX issuance_log_deposit_epoch ON issuance_log (deposit_epoch);
@@ -6,7 +6,8 @@ use getset::{CopyGetters, Getters};
 use serde::{Deserialize, Serialize};
 
 use coconut_interface::{
-    error::CoconutInterfaceError, Attribute, Base58, BlindSignRequest, Credential, VerificationKey,
+    error::CoconutInterfaceError, Attribute, Base58, BlindSignRequest, Credential,
+    TicketCredential, VerificationKey,
 };
 
 #[derive(Serialize, Deserialize, Getters, CopyGetters)]
@@ -33,6 +34,56 @@ impl VerifyCredentialBody {
     }
 }
 
+/// Request for verifying a credential whose refund has been claimed by its owner.
+#[derive(Serialize, Deserialize, Getters, CopyGetters)]
+pub struct VerifyRefundBody {
+    #[getset(get = "pub")]
+    credential: Credential,
+    #[getset(get = "pub")]
+    proposal_id: u64,
+    #[getset(get = "pub")]
+    claimant_cosmos_addr: AccountId,
+}
+
+impl VerifyRefundBody {
+    pub fn new(
+        credential: Credential,
+        proposal_id: u64,
+        claimant_cosmos_addr: AccountId,
+    ) -> VerifyRefundBody {
+        VerifyRefundBody {
+            credential,
+            proposal_id,
+            claimant_cosmos_addr,
+        }
+    }
+}
+
+/// Request for verifying a single ticket of a divisible credential, spent at the gateway.
+#[derive(Serialize, Deserialize, Getters, CopyGetters)]
+pub struct VerifyTicketBody {
+    #[getset(get = "pub")]
+    ticket: TicketCredential,
+    #[getset(get = "pub")]
+    proposal_id: u64,
+    #[getset(get = "pub")]
+    gateway_cosmos_addr: AccountId,
+}
+
+impl VerifyTicketBody {
+    pub fn new(
+        ticket: TicketCredential,
+        proposal_id: u64,
+        gateway_cosmos_addr: AccountId,
+    ) -> VerifyTicketBody {
+        VerifyTicketBody {
+            ticket,
+            proposal_id,
+            gateway_cosmos_addr,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VerifyCredentialResponse {
     pub verification_result: bool,
@@ -46,6 +97,62 @@ impl VerifyCredentialResponse {
     }
 }
 
+#[derive(Serialize, Deserialize, Getters)]
+pub struct BatchVerifyCredentialBody {
+    #[getset(get = "pub")]
+    credentials: Vec<VerifyCredentialBody>,
+}
+
+impl BatchVerifyCredentialBody {
+    pub fn new(credentials: Vec<VerifyCredentialBody>) -> BatchVerifyCredentialBody {
+        BatchVerifyCredentialBody { credentials }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchVerifyCredentialResponse {
+    /// Verification result of each credential, in the order they were sent in.
+    pub verification_results: Vec<bool>,
+}
+
+impl BatchVerifyCredentialResponse {
+    pub fn new(verification_results: Vec<bool>) -> Self {
+        BatchVerifyCredentialResponse {
+            verification_results,
+        }
+    }
+}
+
+/// Credentials issued (and requests that failed) for the deposits made in a single epoch.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IssuanceTotals {
+    pub deposit_epoch: u64,
+    pub issued_credentials: u64,
+    pub issued_value: u64,
+    pub failed_requests: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssuanceTotalsResponse {
+    pub totals: Vec<IssuanceTotals>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssuanceLogEntry {
+    pub tx_hash: String,
+    pub deposit_value: Option<u64>,
+    pub requester_ip: Option<String>,
+    pub timestamp: i64,
+    pub issued: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssuanceLogResponse {
+    pub deposit_epoch: u64,
+    pub entries: Vec<IssuanceLogEntry>,
+}
+
 //  All strings are base58 encoded representations of structs
 #[derive(Clone, Serialize, Deserialize, Debug, Getters, CopyGetters)]
 pub struct BlindSignRequestBody {
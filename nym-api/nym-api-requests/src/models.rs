@@ -7,7 +7,8 @@ use mixnet_contract_common::mixnode::MixNodeDetails;
 use mixnet_contract_common::reward_params::{Performance, RewardingParams};
 use mixnet_contract_common::rewarding::RewardEstimate;
 use mixnet_contract_common::{
-    IdentityKey, Interval, MixId, MixNode, Percent, RewardedSetNodeStatus,
+    EpochId, GatewayBond, IdentityKey, Interval, MixId, MixNode, MixNodeBond, Percent,
+    RewardedSetNodeStatus,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -251,6 +252,10 @@ pub struct MixnodeStatusReportResponse {
     pub most_recent: Uptime,
     pub last_hour: Uptime,
     pub last_day: Uptime,
+    #[serde(default)]
+    pub last_hour_latency: Option<NodeLatencyResponse>,
+    #[serde(default)]
+    pub last_day_latency: Option<NodeLatencyResponse>,
 }
 
 #[derive(Clone, Serialize, Deserialize, schemars::JsonSchema)]
@@ -260,6 +265,43 @@ pub struct GatewayStatusReportResponse {
     pub most_recent: Uptime,
     pub last_hour: Uptime,
     pub last_day: Uptime,
+    #[serde(default)]
+    pub last_hour_latency: Option<NodeLatencyResponse>,
+    #[serde(default)]
+    pub last_day_latency: Option<NodeLatencyResponse>,
+
+    /// Reliability of the client-facing side of the gateway, i.e. of the packets sent through it
+    /// into the mixnet, within the last hour.
+    #[serde(default)]
+    pub last_hour_ingress: Option<Uptime>,
+
+    /// Reliability of the mix-facing side of the gateway, i.e. of the packets it delivered
+    /// from the mixnet to its clients, within the last hour.
+    #[serde(default)]
+    pub last_hour_egress: Option<Uptime>,
+
+    #[serde(default)]
+    pub last_day_ingress: Option<Uptime>,
+
+    #[serde(default)]
+    pub last_day_egress: Option<Uptime>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+pub struct NodeLatencyResponse {
+    /// Average latency, in milliseconds, attributed to the node by the network monitor.
+    pub latency_ms: f32,
+
+    /// Average jitter, in milliseconds, of the latency attributed to the node.
+    pub jitter_ms: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MixnodeLatencyResponse {
+    pub mix_id: MixId,
+    pub most_recent: Option<NodeLatencyResponse>,
+    pub last_hour: Option<NodeLatencyResponse>,
+    pub last_day: Option<NodeLatencyResponse>,
 }
 
 #[derive(Clone, Serialize, Deserialize, schemars::JsonSchema)]
@@ -282,3 +324,71 @@ pub struct GatewayUptimeHistoryResponse {
     pub owner: String,
     pub history: Vec<HistoricalUptimeResponse>,
 }
+
+/// Maximum interval between subsequent messages of the topology stream. If the topology
+/// doesn't change for that long, an empty diff is sent to let the subscribers know
+/// the stream is still alive.
+pub const TOPOLOGY_STREAM_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Message sent over the topology stream. The first message of every stream is always
+/// a snapshot of the current network topology (i.e. the active set and the gateways),
+/// which is followed by diffs created whenever the nym-api refreshes its cache
+/// (or empty ones, if nothing has changed for [`TOPOLOGY_STREAM_KEEPALIVE_INTERVAL`]).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TopologyUpdate {
+    Snapshot {
+        mixnodes: Vec<MixNodeDetails>,
+        gateways: Vec<GatewayBond>,
+    },
+    Diff(TopologyDiff),
+}
+
+/// Changes to the network topology since the previous update. Modified nodes are included
+/// in the added nodes and should replace the existing entries with the same `MixId`
+/// or gateway identity.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct TopologyDiff {
+    pub added_mixnodes: Vec<MixNodeDetails>,
+    pub removed_mixnodes: Vec<MixId>,
+    pub added_gateways: Vec<GatewayBond>,
+    pub removed_gateways: Vec<IdentityKey>,
+}
+
+impl TopologyDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_mixnodes.is_empty()
+            && self.removed_mixnodes.is_empty()
+            && self.added_gateways.is_empty()
+            && self.removed_gateways.is_empty()
+    }
+}
+
+/// Canonical description of the network topology for particular epoch as published by a nym-api.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct TopologyDocument {
+    pub epoch_id: EpochId,
+    /// Mixnodes in the rewarded set, ordered by their `MixId`.
+    pub mixnodes: Vec<RewardedMixnode>,
+    /// Bonded gateways, ordered by their identity keys.
+    pub gateways: Vec<GatewayBond>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct RewardedMixnode {
+    pub status: RewardedSetNodeStatus,
+    pub bond: MixNodeBond,
+}
+
+/// `TopologyDocument` signed with the identity key of the nym-api that has produced it.
+/// The document is kept in its serialized form so that the exact signed bytes could be
+/// redistributed and verified by anyone, independently of the original API.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SignedTopology {
+    /// Base58-encoded ed25519 identity key of the signer.
+    pub signer: IdentityKey,
+    /// Base58-encoded signature on the bytes of the `document`.
+    pub signature: String,
+    /// JSON-serialized `TopologyDocument`.
+    pub document: String,
+}
@@ -0,0 +1,114 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::coconut::error::{CoconutError, Result};
+use crate::coconut::State;
+use nym_api_requests::coconut::{
+    IssuanceLogEntry, IssuanceLogResponse, IssuanceTotals, IssuanceTotalsResponse,
+};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::serde::json::Json;
+use rocket::{Request, State as RocketState};
+
+/// Request guard of the endpoints only available to the operator of this nym-api,
+/// i.e. requests presenting the configured admin token as their bearer token.
+pub struct Admin;
+
+// compares the tokens without leaking the length of their common prefix through timing
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = CoconutError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let admin_token = request
+            .rocket()
+            .state::<State>()
+            .and_then(|state| state.admin_token.as_deref());
+        let provided_token = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|header| header.strip_prefix("Bearer "));
+
+        match (admin_token, provided_token) {
+            (Some(admin_token), Some(provided_token))
+                if tokens_match(admin_token, provided_token) =>
+            {
+                Outcome::Success(Admin)
+            }
+            _ => Outcome::Failure((Status::Unauthorized, CoconutError::Unauthorized)),
+        }
+    }
+}
+
+#[get("/issuance-totals?<from_epoch>&<to_epoch>")]
+pub async fn issuance_totals(
+    _admin: Admin,
+    from_epoch: Option<u64>,
+    to_epoch: Option<u64>,
+    state: &RocketState<State>,
+) -> Result<Json<IssuanceTotalsResponse>> {
+    let totals = state
+        .storage
+        .get_issuance_totals(from_epoch.unwrap_or_default(), to_epoch.unwrap_or(u64::MAX))
+        .await?
+        .into_iter()
+        .map(|totals| IssuanceTotals {
+            deposit_epoch: totals.deposit_epoch as u64,
+            issued_credentials: totals.issued_credentials as u64,
+            issued_value: totals.issued_value as u64,
+            failed_requests: totals.failed_requests as u64,
+        })
+        .collect();
+
+    Ok(Json(IssuanceTotalsResponse { totals }))
+}
+
+#[get("/issuance-log/<deposit_epoch>")]
+pub async fn issuance_log(
+    _admin: Admin,
+    deposit_epoch: u64,
+    state: &RocketState<State>,
+) -> Result<Json<IssuanceLogResponse>> {
+    let entries = state
+        .storage
+        .get_issuance_log(deposit_epoch)
+        .await?
+        .into_iter()
+        .map(|record| IssuanceLogEntry {
+            tx_hash: record.tx_hash,
+            deposit_value: record.deposit_value.map(|value| value as u64),
+            requester_ip: record.requester_ip,
+            timestamp: record.timestamp,
+            issued: record.issued,
+            error: record.error,
+        })
+        .collect();
+
+    Ok(Json(IssuanceLogResponse {
+        deposit_epoch,
+        entries,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admin_token_comparison() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secret", "secreT"));
+        assert!(!tokens_match("secret", "secret2"));
+        assert!(!tokens_match("secret", ""));
+    }
+}
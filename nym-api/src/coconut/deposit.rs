@@ -2,18 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use coconut_bandwidth_contract_common::events::{
-    DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_IDENTITY_KEY, DEPOSIT_INFO,
-    DEPOSIT_VALUE,
+    DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_EXPIRY_EPOCH, DEPOSIT_IDENTITY_KEY,
+    DEPOSIT_INFO, DEPOSIT_VALUE,
 };
-use credentials::coconut::bandwidth::BandwidthVoucher;
+use credentials::coconut::credential_type::{CredentialType, PublicAttributePolicy};
 use crypto::asymmetric::encryption;
 use crypto::asymmetric::identity::{self, Signature};
 use nym_api_requests::coconut::BlindSignRequestBody;
-use validator_client::nymd::TxResponse;
+use validator_client::nymd::{Tag, TxResponse};
 
 use super::error::{CoconutError, Result};
 
 pub async fn extract_encryption_key(
+    credential_type: &dyn CredentialType,
     blind_sign_request_body: &BlindSignRequestBody,
     tx: TxResponse,
 ) -> Result<encryption::PublicKey> {
@@ -21,7 +22,7 @@ pub async fn extract_encryption_key(
     let public_attributes = blind_sign_request_body.public_attributes();
     let public_attributes_plain = blind_sign_request_body.public_attributes_plain();
 
-    if !BandwidthVoucher::verify_against_plain(&public_attributes, public_attributes_plain) {
+    if !credential_type.verify_against_plain(&public_attributes, public_attributes_plain) {
         return Err(CoconutError::InconsistentPublicAttributes);
     }
 
@@ -40,63 +41,94 @@ pub async fn extract_encryption_key(
         .attributes
         .as_ref();
 
-    let deposit_value = attributes
+    for (spec, plain) in credential_type
+        .public_attributes()
         .iter()
-        .find(|tag| tag.key.as_ref() == DEPOSIT_VALUE)
-        .ok_or(CoconutError::DepositValueNotFound)?
-        .value
-        .as_ref();
-    let deposit_value_plain = public_attributes_plain.get(0).cloned().unwrap_or_default();
-    if deposit_value != deposit_value_plain {
-        return Err(CoconutError::DifferentPublicAttributes(
-            deposit_value.to_string(),
-            deposit_value_plain,
-        ));
+        .zip(public_attributes_plain)
+    {
+        check_public_attribute(credential_type, spec.policy, plain, attributes)?;
     }
 
-    let deposit_info = attributes
-        .iter()
-        .find(|tag| tag.key.as_ref() == DEPOSIT_INFO)
-        .ok_or(CoconutError::DepositInfoNotFound)?
-        .value
-        .as_ref();
-    let deposit_info_plain = public_attributes_plain.get(1).cloned().unwrap_or_default();
-    if deposit_info != deposit_info_plain {
-        return Err(CoconutError::DifferentPublicAttributes(
-            deposit_info.to_string(),
-            deposit_info_plain,
-        ));
+    let deposit_value = deposit_attribute(attributes, DEPOSIT_VALUE)?;
+    let accepts_value = deposit_value
+        .parse::<u64>()
+        .map(|value| credential_type.accepts_value(value))
+        .unwrap_or_default();
+    if !accepts_value {
+        return Err(CoconutError::UnsupportedDepositValue {
+            value: deposit_value.to_string(),
+            credential_type: credential_type.name().to_string(),
+        });
     }
 
-    let verification_key = identity::PublicKey::from_base58_string(
-        attributes
-            .iter()
-            .find(|tag| tag.key.as_ref() == DEPOSIT_IDENTITY_KEY)
-            .ok_or(CoconutError::DepositVerifKeyNotFound)?
-            .value
-            .as_ref(),
-    )?;
-
-    let encryption_key = encryption::PublicKey::from_base58_string(
-        attributes
-            .iter()
-            .find(|tag| tag.key.as_ref() == DEPOSIT_ENCRYPTION_KEY)
-            .ok_or(CoconutError::DepositEncrKeyNotFound)?
-            .value
-            .as_ref(),
-    )?;
+    let verification_key = identity::PublicKey::from_base58_string(deposit_attribute(
+        attributes,
+        DEPOSIT_IDENTITY_KEY,
+    )?)?;
+
+    let encryption_key = encryption::PublicKey::from_base58_string(deposit_attribute(
+        attributes,
+        DEPOSIT_ENCRYPTION_KEY,
+    )?)?;
 
     verification_key.verify(&message, &signature)?;
 
     Ok(encryption_key)
 }
 
+fn deposit_attribute<'a>(attributes: &'a [Tag], key: &str) -> Result<&'a str> {
+    attributes
+        .iter()
+        .find(|tag| tag.key.as_ref() == key)
+        .map(|tag| tag.value.as_ref())
+        .ok_or_else(|| match key {
+            DEPOSIT_VALUE => CoconutError::DepositValueNotFound,
+            DEPOSIT_INFO => CoconutError::DepositInfoNotFound,
+            DEPOSIT_EXPIRY_EPOCH => CoconutError::DepositExpiryEpochNotFound,
+            DEPOSIT_IDENTITY_KEY => CoconutError::DepositVerifKeyNotFound,
+            DEPOSIT_ENCRYPTION_KEY => CoconutError::DepositEncrKeyNotFound,
+            _ => CoconutError::DepositAttributeNotFound(key.to_string()),
+        })
+}
+
+fn check_public_attribute(
+    credential_type: &dyn CredentialType,
+    policy: PublicAttributePolicy,
+    plain: &str,
+    deposit_attributes: &[Tag],
+) -> Result<()> {
+    let expected = match policy {
+        PublicAttributePolicy::Deposit(key) => deposit_attribute(deposit_attributes, key)?,
+        PublicAttributePolicy::CredentialType => {
+            let deposit_info = deposit_attribute(deposit_attributes, DEPOSIT_INFO)?;
+            if deposit_info != credential_type.name() {
+                return Err(CoconutError::DifferentPublicAttributes(
+                    credential_type.name().to_string(),
+                    deposit_info.to_string(),
+                ));
+            }
+            credential_type.name()
+        }
+        PublicAttributePolicy::Unrestricted => return Ok(()),
+    };
+
+    if expected != plain {
+        return Err(CoconutError::DifferentPublicAttributes(
+            expected.to_string(),
+            plain.to_string(),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::coconut::tests::tx_entry_fixture;
-    use config::defaults::VOUCHER_INFO;
-    use nymcoconut::{prepare_blind_sign, BlindSignRequest, Parameters};
+    use config::defaults::{SERVICE_ACCESS_INFO, VOUCHER_INFO};
+    use credentials::coconut::bandwidth::BandwidthVoucher;
+    use credentials::coconut::credential_type::{Bandwidth, ServiceAccess};
+    use nymcoconut::{prepare_blind_sign, Parameters};
     use rand_07::rngs::OsRng;
     use std::str::FromStr;
     use validator_client::nymd::tx::Hash;
@@ -108,17 +140,17 @@ mod test {
             Hash::from_str("6B27412050B823E58BB38447D7870BBC8CBE3C51C905BEA89D459ACCDA80A00E")
                 .unwrap();
         let mut tx_entry = tx_entry_fixture(&tx_hash.to_string());
-        let params = Parameters::new(4).unwrap();
+        let params = Parameters::new(5).unwrap();
         let mut rng = OsRng;
+        let identity_keypair = identity::KeyPair::new(&mut rng);
         let voucher = BandwidthVoucher::new(
             &params,
             "1234".to_string(),
             VOUCHER_INFO.to_string(),
+            19000,
             tx_hash,
             identity::PrivateKey::from_base58_string(
-                identity::KeyPair::new(&mut rng)
-                    .private_key()
-                    .to_base58_string(),
+                identity_keypair.private_key().to_base58_string(),
             )
             .unwrap(),
             encryption::PrivateKey::from_bytes(
@@ -142,10 +174,11 @@ mod test {
             vec![
                 String::from("First wrong plain"),
                 String::from("Second wrong plain"),
+                String::from("Third wrong plain"),
             ],
-            4,
+            5,
         );
-        let err = extract_encryption_key(&req, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &req, tx_entry.clone())
             .await
             .unwrap_err();
         assert_eq!(
@@ -159,9 +192,9 @@ mod test {
             String::from("Invalid signature"),
             &voucher.get_public_attributes(),
             voucher.get_public_attributes_plain(),
-            4,
+            5,
         );
-        let err = extract_encryption_key(&req, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &req, tx_entry.clone())
             .await
             .unwrap_err();
 
@@ -178,14 +211,14 @@ mod test {
             signature.clone(),
             &voucher.get_public_attributes(),
             voucher.get_public_attributes_plain(),
-            4,
+            5,
         );
 
         tx_entry.tx_result.events.push(Event {
             type_str: format!("wasm-{}", DEPOSITED_FUNDS_EVENT_TYPE),
             attributes: vec![],
         });
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
         assert_eq!(
@@ -197,7 +230,7 @@ mod test {
             key: DEPOSIT_VALUE.parse().unwrap(),
             value: "10".parse().unwrap(),
         }];
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
         assert_eq!(
@@ -210,7 +243,7 @@ mod test {
             key: DEPOSIT_VALUE.parse().unwrap(),
             value: "1234".parse().unwrap(),
         }];
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
         assert_eq!(
@@ -228,14 +261,14 @@ mod test {
                 value: "bandwidth deposit info".parse().unwrap(),
             },
         ];
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
         assert_eq!(
             err.to_string(),
             CoconutError::DifferentPublicAttributes(
-                "bandwidth deposit info".to_string(),
                 VOUCHER_INFO.to_string(),
+                "bandwidth deposit info".to_string(),
             )
             .to_string(),
         );
@@ -250,7 +283,52 @@ mod test {
                 value: VOUCHER_INFO.parse().unwrap(),
             },
         ];
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            CoconutError::DepositExpiryEpochNotFound.to_string(),
+        );
+
+        tx_entry.tx_result.events.get_mut(0).unwrap().attributes = vec![
+            Tag {
+                key: DEPOSIT_VALUE.parse().unwrap(),
+                value: "1234".parse().unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_INFO.parse().unwrap(),
+                value: VOUCHER_INFO.parse().unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                value: "19001".parse().unwrap(),
+            },
+        ];
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            CoconutError::DifferentPublicAttributes("19001".to_string(), "19000".to_string())
+                .to_string(),
+        );
+
+        tx_entry.tx_result.events.get_mut(0).unwrap().attributes = vec![
+            Tag {
+                key: DEPOSIT_VALUE.parse().unwrap(),
+                value: "1234".parse().unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_INFO.parse().unwrap(),
+                value: VOUCHER_INFO.parse().unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                value: "19000".parse().unwrap(),
+            },
+        ];
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
         assert_eq!(
@@ -267,12 +345,16 @@ mod test {
                 key: DEPOSIT_INFO.parse().unwrap(),
                 value: VOUCHER_INFO.parse().unwrap(),
             },
+            Tag {
+                key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                value: "19000".parse().unwrap(),
+            },
             Tag {
                 key: DEPOSIT_IDENTITY_KEY.parse().unwrap(),
                 value: "verification key".parse().unwrap(),
             },
         ];
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
 
@@ -292,6 +374,10 @@ mod test {
                 key: DEPOSIT_INFO.parse().unwrap(),
                 value: VOUCHER_INFO.parse().unwrap(),
             },
+            Tag {
+                key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                value: "19000".parse().unwrap(),
+            },
             Tag {
                 key: DEPOSIT_IDENTITY_KEY.parse().unwrap(),
                 value: "2eSxwquNJb2nZTEW5p4rbqjHfBaz9UaNhjHHiexPN4He"
@@ -299,7 +385,7 @@ mod test {
                     .unwrap(),
             },
         ];
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
         assert_eq!(
@@ -316,6 +402,10 @@ mod test {
                 key: DEPOSIT_INFO.parse().unwrap(),
                 value: VOUCHER_INFO.parse().unwrap(),
             },
+            Tag {
+                key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                value: "19000".parse().unwrap(),
+            },
             Tag {
                 key: DEPOSIT_IDENTITY_KEY.parse().unwrap(),
                 value: "6EJGMdEq7t8Npz54uPkftGsdmj7DKntLVputAnDfVZB2"
@@ -327,7 +417,7 @@ mod test {
                 value: "encryption key".parse().unwrap(),
             },
         ];
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
 
@@ -348,6 +438,10 @@ mod test {
                 key: DEPOSIT_INFO.parse().unwrap(),
                 value: VOUCHER_INFO.parse().unwrap(),
             },
+            Tag {
+                key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                value: "19000".parse().unwrap(),
+            },
             Tag {
                 key: DEPOSIT_IDENTITY_KEY.parse().unwrap(),
                 value: "6EJGMdEq7t8Npz54uPkftGsdmj7DKntLVputAnDfVZB2"
@@ -359,7 +453,7 @@ mod test {
                 value: expected_encryption_key.parse().unwrap(),
             },
         ];
-        let err = extract_encryption_key(&correct_request, tx_entry.clone())
+        let err = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap_err();
         assert_eq!(
@@ -370,39 +464,15 @@ mod test {
             .to_string(),
         );
 
-        // hard-coded values, that generate a correct signature
-        let blind_sign_req = BlindSignRequest::from_bytes(&[
-            176, 113, 19, 237, 218, 252, 113, 20, 225, 238, 59, 88, 217, 45, 233, 178, 65, 28, 242,
-            0, 222, 48, 110, 216, 26, 111, 51, 235, 61, 74, 200, 15, 130, 245, 45, 170, 155, 190,
-            156, 77, 180, 142, 29, 63, 15, 224, 150, 31, 139, 24, 65, 175, 143, 153, 11, 203, 33,
-            16, 152, 22, 221, 203, 99, 233, 208, 142, 161, 194, 46, 227, 177, 96, 119, 30, 175, 69,
-            104, 14, 2, 191, 26, 94, 30, 165, 15, 28, 40, 176, 1, 78, 253, 79, 20, 137, 102, 74, 2,
-            0, 0, 0, 0, 0, 0, 0, 131, 133, 112, 115, 53, 98, 58, 166, 240, 70, 185, 210, 203, 12,
-            114, 66, 180, 38, 139, 12, 187, 45, 250, 201, 68, 102, 159, 172, 218, 124, 151, 23,
-            172, 18, 216, 122, 246, 7, 185, 76, 20, 167, 123, 122, 152, 241, 175, 226, 176, 8, 170,
-            70, 140, 252, 36, 130, 67, 204, 111, 116, 107, 92, 200, 77, 252, 31, 138, 18, 10, 215,
-            165, 243, 95, 199, 193, 61, 200, 187, 22, 198, 109, 213, 145, 71, 171, 132, 174, 68,
-            105, 248, 0, 115, 50, 55, 199, 84, 67, 16, 125, 216, 250, 154, 115, 174, 9, 206, 44,
-            88, 63, 163, 124, 10, 239, 64, 158, 191, 27, 169, 177, 194, 223, 142, 202, 206, 189,
-            122, 123, 91, 171, 15, 40, 192, 148, 75, 174, 24, 116, 229, 127, 170, 110, 183, 151, 2,
-            118, 168, 22, 113, 87, 237, 91, 228, 249, 120, 114, 255, 53, 175, 245, 39, 2, 0, 0, 0,
-            0, 0, 0, 0, 225, 45, 230, 25, 62, 202, 96, 166, 171, 241, 206, 137, 254, 51, 154, 255,
-            122, 130, 107, 54, 5, 206, 207, 120, 193, 214, 64, 10, 111, 195, 86, 55, 201, 36, 10,
-            18, 154, 158, 183, 87, 185, 59, 228, 89, 134, 193, 217, 188, 64, 164, 249, 21, 248, 20,
-            207, 58, 31, 10, 19, 176, 246, 150, 45, 48, 2, 0, 0, 0, 0, 0, 0, 0, 173, 60, 65, 209,
-            100, 114, 138, 186, 158, 150, 109, 230, 111, 86, 101, 72, 194, 237, 173, 195, 139, 175,
-            238, 25, 169, 18, 188, 75, 77, 54, 111, 20, 115, 235, 195, 2, 123, 133, 164, 81, 15,
-            45, 11, 84, 139, 38, 8, 224, 197, 181, 95, 147, 49, 77, 193, 207, 52, 141, 195, 195,
-            66, 137, 17, 32,
-        ])
-        .unwrap();
         let correct_request = BlindSignRequestBody::new(
-            &blind_sign_req,
-            "7C41AF8266D91DE55E1C8F4712E6A952A165ED3D8C27C7B00428CBD0DE00A52B".to_string(),
-            "gSFgpma5GAVMcsmZwKieqGNHNd3dPzcfa8eT2Qn2LoBccSeyiJdphREbNrkuh5XWxMe2hUsranaYzLro48L9Qhd".to_string(),
+            voucher.blind_sign_request(),
+            tx_hash.to_string(),
+            voucher
+                .sign(voucher.blind_sign_request())
+                .to_base58_string(),
             &voucher.get_public_attributes(),
             voucher.get_public_attributes_plain(),
-            4,
+            5,
         );
         tx_entry.tx_result.events.get_mut(0).unwrap().attributes = vec![
             Tag {
@@ -413,9 +483,15 @@ mod test {
                 key: DEPOSIT_INFO.parse().unwrap(),
                 value: VOUCHER_INFO.parse().unwrap(),
             },
+            Tag {
+                key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                value: "19000".parse().unwrap(),
+            },
             Tag {
                 key: DEPOSIT_IDENTITY_KEY.parse().unwrap(),
-                value: "64auwDkWan7R8yH1Mwe9dS4qXgrDBCUNDg3Q4KFnd2P5"
+                value: identity_keypair
+                    .public_key()
+                    .to_base58_string()
                     .parse()
                     .unwrap(),
             },
@@ -426,9 +502,100 @@ mod test {
                     .unwrap(),
             },
         ];
-        let encryption_key = extract_encryption_key(&correct_request, tx_entry.clone())
+        let encryption_key = extract_encryption_key(&Bandwidth, &correct_request, tx_entry.clone())
             .await
             .unwrap();
         assert_eq!(encryption_key.to_base58_string(), expected_encryption_key);
     }
+
+    #[tokio::test]
+    async fn deposit_has_to_be_made_for_the_credential_type() {
+        let tx_hash =
+            Hash::from_str("6B27412050B823E58BB38447D7870BBC8CBE3C51C905BEA89D459ACCDA80A00E")
+                .unwrap();
+        let mut tx_entry = tx_entry_fixture(&tx_hash.to_string());
+        let params = Parameters::new(5).unwrap();
+        let mut rng = OsRng;
+        let identity_keypair = identity::KeyPair::new(&mut rng);
+        let voucher = BandwidthVoucher::new(
+            &params,
+            "1234".to_string(),
+            SERVICE_ACCESS_INFO.to_string(),
+            19000,
+            tx_hash,
+            identity::PrivateKey::from_base58_string(
+                identity_keypair.private_key().to_base58_string(),
+            )
+            .unwrap(),
+            encryption::PrivateKey::from_bytes(
+                &encryption::KeyPair::new(&mut rng).private_key().to_bytes(),
+            )
+            .unwrap(),
+        );
+        let request = BlindSignRequestBody::new(
+            voucher.blind_sign_request(),
+            tx_hash.to_string(),
+            voucher
+                .sign(voucher.blind_sign_request())
+                .to_base58_string(),
+            &voucher.get_public_attributes(),
+            voucher.get_public_attributes_plain(),
+            5,
+        );
+        let deposit_attributes = |deposit_info: &str| {
+            vec![
+                Tag {
+                    key: DEPOSIT_VALUE.parse().unwrap(),
+                    value: "1234".parse().unwrap(),
+                },
+                Tag {
+                    key: DEPOSIT_INFO.parse().unwrap(),
+                    value: deposit_info.parse().unwrap(),
+                },
+                Tag {
+                    key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                    value: "19000".parse().unwrap(),
+                },
+                Tag {
+                    key: DEPOSIT_IDENTITY_KEY.parse().unwrap(),
+                    value: identity_keypair
+                        .public_key()
+                        .to_base58_string()
+                        .parse()
+                        .unwrap(),
+                },
+                Tag {
+                    key: DEPOSIT_ENCRYPTION_KEY.parse().unwrap(),
+                    value: "HxnTpWTkgigSTAysVKLE8pEiUULHdTT1BxFfzfJvQRi6"
+                        .parse()
+                        .unwrap(),
+                },
+            ]
+        };
+
+        // a bandwidth deposit can't be turned into a service access credential
+        tx_entry.tx_result.events.push(Event {
+            type_str: format!("wasm-{}", DEPOSITED_FUNDS_EVENT_TYPE),
+            attributes: deposit_attributes(VOUCHER_INFO),
+        });
+        let err = extract_encryption_key(&ServiceAccess, &request, tx_entry.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            CoconutError::DifferentPublicAttributes(
+                SERVICE_ACCESS_INFO.to_string(),
+                VOUCHER_INFO.to_string(),
+            )
+            .to_string(),
+        );
+
+        tx_entry.tx_result.events.get_mut(0).unwrap().attributes =
+            deposit_attributes(SERVICE_ACCESS_INFO);
+        assert!(
+            extract_encryption_key(&ServiceAccess, &request, tx_entry.clone())
+                .await
+                .is_ok()
+        );
+    }
 }
@@ -59,6 +59,18 @@ pub enum CoconutError {
     #[error("Could not find the encryption key in the event")]
     DepositEncrKeyNotFound,
 
+    #[error("Could not find the credential expiry epoch in the event")]
+    DepositExpiryEpochNotFound,
+
+    #[error("Could not find the {0} attribute in the event")]
+    DepositAttributeNotFound(String),
+
+    #[error("{credential_type} credentials can't be issued for a deposit of {value}")]
+    UnsupportedDepositValue {
+        value: String,
+        credential_type: String,
+    },
+
     #[error("Signature didn't verify correctly")]
     SignatureVerificationError(#[from] SignatureError),
 
@@ -73,6 +85,12 @@ pub enum CoconutError {
     #[error("No signature found")]
     NoSignature,
 
+    #[error("Unknown credential type: {0}")]
+    UnknownCredentialType(String),
+
+    #[error("Credentials of the requested type consist of {expected} attributes, the request is for {received}")]
+    InvalidAttributeCount { expected: u32, received: u32 },
+
     #[error("Error in coconut interface - {0}")]
     CoconutInterfaceError(#[from] coconut_interface::error::CoconutInterfaceError),
 
@@ -102,15 +120,27 @@ pub enum CoconutError {
 
     #[error("There was a problem with the proposal id: {reason}")]
     ProposalIdError { reason: String },
+
+    #[error("Too many credential issuance requests: {reason}")]
+    RateLimited { reason: String },
+
+    #[error("Missing or invalid admin token")]
+    Unauthorized,
 }
 
 impl<'r, 'o: 'r> Responder<'r, 'o> for CoconutError {
     fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
+        let status = match self {
+            CoconutError::RateLimited { .. } => Status::TooManyRequests,
+            CoconutError::Unauthorized => Status::Unauthorized,
+            CoconutError::UnknownCredentialType(_) => Status::NotFound,
+            _ => Status::BadRequest,
+        };
         let err_msg = self.to_string();
         Response::build()
             .header(ContentType::Plain)
             .sized_body(err_msg.len(), Cursor::new(err_msg))
-            .status(Status::BadRequest)
+            .status(status)
             .ok()
     }
 }
@@ -0,0 +1,272 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::coconut::error::{CoconutError, Result};
+use crate::coconut::State;
+use crate::config::Config;
+use crate::storage::models::IssuanceLogRecord;
+use coconut_bandwidth_contract_common::expiry::deposit_epoch;
+use nym_api_requests::coconut::{BlindSignRequestBody, BlindedSignatureResponse};
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+
+// upper bound on the number of distinct addresses (and deposits) tracked within a single window,
+// so that the counters couldn't grow without bounds
+const MAX_TRACKED_REQUESTERS: usize = 100_000;
+
+/// Limits of the credential issuance endpoints and the access to their reports.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IssuanceConfig {
+    /// Maximum number of requests accepted from a single IP address within the window. 0 disables the limit.
+    pub(crate) max_requests_per_ip: u32,
+
+    /// Maximum number of requests accepted for a single deposit within the window. 0 disables the limit.
+    pub(crate) max_requests_per_deposit: u32,
+
+    /// Duration of the window over which the requests are counted.
+    pub(crate) rate_limit_window: Duration,
+
+    /// Addresses of the reverse proxies whose `X-Real-IP` header is trusted.
+    pub(crate) trusted_proxies: Vec<IpAddr>,
+
+    /// Bearer token required by the admin endpoints. They're disabled if it's not set.
+    pub(crate) admin_token: Option<String>,
+}
+
+impl From<&Config> for IssuanceConfig {
+    fn from(config: &Config) -> Self {
+        IssuanceConfig {
+            max_requests_per_ip: config.get_max_issuance_requests_per_ip(),
+            max_requests_per_deposit: config.get_max_issuance_requests_per_deposit(),
+            rate_limit_window: config.get_issuance_rate_limit_window(),
+            trusted_proxies: config.get_issuance_trusted_proxies(),
+            admin_token: config.get_admin_token(),
+        }
+    }
+}
+
+struct RequestCounts {
+    window_start: Instant,
+    per_ip: HashMap<IpAddr, u32>,
+    per_deposit: HashMap<String, u32>,
+}
+
+impl RequestCounts {
+    fn new() -> Self {
+        RequestCounts {
+            window_start: Instant::now(),
+            per_ip: HashMap::new(),
+            per_deposit: HashMap::new(),
+        }
+    }
+}
+
+/// Fixed window limiter of the credential issuance requests coming from the same IP address
+/// or concerning the same deposit. All the counters are reset at the end of each window.
+pub(crate) struct IssuanceRateLimiter {
+    max_requests_per_ip: u32,
+    max_requests_per_deposit: u32,
+    window: Duration,
+    trusted_proxies: Vec<IpAddr>,
+    counts: Mutex<RequestCounts>,
+}
+
+impl IssuanceRateLimiter {
+    pub(crate) fn new(config: &IssuanceConfig) -> Self {
+        IssuanceRateLimiter {
+            max_requests_per_ip: config.max_requests_per_ip,
+            max_requests_per_deposit: config.max_requests_per_deposit,
+            window: config.rate_limit_window,
+            trusted_proxies: config.trusted_proxies.clone(),
+            counts: Mutex::new(RequestCounts::new()),
+        }
+    }
+
+    /// Determines the address the request is attributed to. The address claimed in the `X-Real-IP`
+    /// header is only used if the request has been forwarded by one of the trusted proxies,
+    /// as otherwise anyone could claim an arbitrary address.
+    pub(crate) fn requester_ip(
+        &self,
+        peer_ip: Option<IpAddr>,
+        real_ip: Option<IpAddr>,
+    ) -> Option<IpAddr> {
+        match peer_ip {
+            Some(peer_ip) if self.trusted_proxies.contains(&peer_ip) => real_ip.or(Some(peer_ip)),
+            _ => peer_ip,
+        }
+    }
+
+    /// Counts the request towards the limits of its origin and of its deposit,
+    /// rejecting it if either of them has already been exhausted in the current window.
+    pub(crate) fn check(&self, requester_ip: Option<IpAddr>, tx_hash: &str) -> Result<()> {
+        let mut counts = self
+            .counts
+            .lock()
+            .expect("issuance rate limiter mutex got poisoned");
+        if counts.window_start.elapsed() >= self.window {
+            *counts = RequestCounts::new();
+        }
+
+        if self.max_requests_per_ip != 0 {
+            if let Some(ip) = requester_ip {
+                if counts.per_ip.len() >= MAX_TRACKED_REQUESTERS && !counts.per_ip.contains_key(&ip)
+                {
+                    return Err(CoconutError::RateLimited {
+                        reason: String::from("too many requesters in the current window"),
+                    });
+                }
+                let ip_requests = counts.per_ip.entry(ip).or_default();
+                if *ip_requests >= self.max_requests_per_ip {
+                    return Err(CoconutError::RateLimited {
+                        reason: format!("too many requests from {ip}"),
+                    });
+                }
+                *ip_requests += 1;
+            }
+        }
+
+        if self.max_requests_per_deposit != 0 {
+            if counts.per_deposit.len() >= MAX_TRACKED_REQUESTERS
+                && !counts.per_deposit.contains_key(tx_hash)
+            {
+                return Err(CoconutError::RateLimited {
+                    reason: String::from("too many deposits in the current window"),
+                });
+            }
+            let deposit_requests = counts.per_deposit.entry(tx_hash.to_string()).or_default();
+            if *deposit_requests >= self.max_requests_per_deposit {
+                return Err(CoconutError::RateLimited {
+                    reason: format!("too many requests for deposit {tx_hash}"),
+                });
+            }
+            *deposit_requests += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Request guard providing the address the request is attributed to by the `IssuanceRateLimiter`.
+pub(crate) struct RequesterIp(pub(crate) Option<IpAddr>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequesterIp {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let peer_ip = request.remote().map(|address| address.ip());
+        let requester_ip = match request.rocket().state::<State>() {
+            Some(state) => state.rate_limiter.requester_ip(peer_ip, request.real_ip()),
+            None => peer_ip,
+        };
+        Outcome::Success(RequesterIp(requester_ip))
+    }
+}
+
+/// Creates the issuance log entry of the request. The deposit value and epoch are the ones
+/// claimed in the request, which have been checked against the deposit if the issuance succeeded.
+pub(crate) fn issuance_record(
+    blind_sign_request_body: &BlindSignRequestBody,
+    requester_ip: Option<IpAddr>,
+    result: &Result<BlindedSignatureResponse>,
+) -> IssuanceLogRecord {
+    let public_attributes_plain = blind_sign_request_body.public_attributes_plain();
+    IssuanceLogRecord {
+        tx_hash: blind_sign_request_body.tx_hash().clone(),
+        deposit_value: public_attributes_plain
+            .get(0)
+            .and_then(|value| value.parse().ok()),
+        deposit_epoch: public_attributes_plain
+            .get(2)
+            .and_then(|expiry_epoch| expiry_epoch.parse().ok())
+            .map(|expiry_epoch| deposit_epoch(expiry_epoch) as i64),
+        requester_ip: requester_ip.map(|ip| ip.to_string()),
+        timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+        issued: result.is_ok(),
+        error: result.as_ref().err().map(|err| err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(max_requests_per_ip: u32, max_requests_per_deposit: u32) -> IssuanceRateLimiter {
+        IssuanceRateLimiter::new(&IssuanceConfig {
+            max_requests_per_ip,
+            max_requests_per_deposit,
+            rate_limit_window: Duration::from_secs(60),
+            trusted_proxies: vec!["10.0.0.1".parse().unwrap()],
+            admin_token: None,
+        })
+    }
+
+    #[test]
+    fn limits_requests_per_ip() {
+        let limiter = limiter(2, 0);
+        let ip = "1.2.3.4".parse().unwrap();
+        let other_ip = "5.6.7.8".parse().unwrap();
+
+        assert!(limiter.check(Some(ip), "deposit1").is_ok());
+        assert!(limiter.check(Some(ip), "deposit2").is_ok());
+        assert!(limiter.check(Some(ip), "deposit3").is_err());
+        assert!(limiter.check(Some(other_ip), "deposit3").is_ok());
+        assert!(limiter.check(None, "deposit3").is_ok());
+    }
+
+    #[test]
+    fn limits_requests_per_deposit() {
+        let limiter = limiter(0, 1);
+        let ip = "1.2.3.4".parse().unwrap();
+        let other_ip = "5.6.7.8".parse().unwrap();
+
+        assert!(limiter.check(Some(ip), "deposit1").is_ok());
+        assert!(limiter.check(Some(other_ip), "deposit1").is_err());
+        assert!(limiter.check(Some(other_ip), "deposit2").is_ok());
+    }
+
+    #[test]
+    fn counts_are_reset_after_the_window() {
+        let limiter = IssuanceRateLimiter::new(&IssuanceConfig {
+            max_requests_per_ip: 0,
+            max_requests_per_deposit: 1,
+            rate_limit_window: Duration::ZERO,
+            trusted_proxies: Vec::new(),
+            admin_token: None,
+        });
+
+        assert!(limiter.check(None, "deposit1").is_ok());
+        assert!(limiter.check(None, "deposit1").is_ok());
+    }
+
+    #[test]
+    fn real_ip_is_only_trusted_from_trusted_proxies() {
+        let limiter = limiter(0, 0);
+        let proxy = "10.0.0.1".parse().unwrap();
+        let peer = "1.2.3.4".parse().unwrap();
+        let claimed = "5.6.7.8".parse().unwrap();
+
+        assert_eq!(limiter.requester_ip(Some(peer), Some(claimed)), Some(peer));
+        assert_eq!(
+            limiter.requester_ip(Some(proxy), Some(claimed)),
+            Some(claimed)
+        );
+        assert_eq!(limiter.requester_ip(Some(proxy), None), Some(proxy));
+        assert_eq!(limiter.requester_ip(None, Some(claimed)), None);
+    }
+
+    #[test]
+    fn number_of_tracked_deposits_is_bounded() {
+        let limiter = limiter(0, 1);
+        for n in 0..MAX_TRACKED_REQUESTERS {
+            assert!(limiter.check(None, &format!("deposit{n}")).is_ok());
+        }
+        assert!(limiter.check(None, "another deposit").is_err());
+    }
+}
@@ -1,11 +1,13 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+mod admin;
 pub(crate) mod client;
 pub(crate) mod comm;
 mod deposit;
 pub(crate) mod dkg;
 pub(crate) mod error;
+pub(crate) mod issuance;
 pub(crate) mod keypair;
 #[cfg(test)]
 pub(crate) mod tests;
@@ -13,34 +15,45 @@ pub(crate) mod tests;
 use crate::coconut::client::Client as LocalClient;
 use crate::coconut::deposit::extract_encryption_key;
 use crate::coconut::error::{CoconutError, Result};
+use crate::coconut::issuance::{issuance_record, IssuanceConfig, IssuanceRateLimiter, RequesterIp};
 use crate::NymApiStorage;
 
+use coconut_bandwidth_contract_common::expiry::effective_expiry_epoch;
+use coconut_bandwidth_contract_common::refund::refund_from_cosmos_msgs;
 use coconut_bandwidth_contract_common::spend_credential::{
-    funds_from_cosmos_msgs, SpendCredentialStatus,
+    funds_from_cosmos_msgs, SpendCredential, SpendCredentialStatus,
 };
 use coconut_interface::KeyPair as CoconutKeyPair;
 use coconut_interface::{
-    Attribute, BlindSignRequest, BlindedSignature, Parameters, VerificationKey,
+    Attribute, BlindSignRequest, BlindedSignature, Credential, Parameters, VerificationKey,
 };
-use config::defaults::NYM_API_VERSION;
+use config::defaults::{DIVISIBLE_VOUCHER_INFO, NYM_API_VERSION, VOUCHER_INFO};
+use credentials::coconut::bandwidth::TICKET_INDEX_BITS;
+use credentials::coconut::credential_type::{credential_type, Bandwidth, CredentialType};
 use credentials::coconut::params::{
     NymApiCredentialEncryptionAlgorithm, NymApiCredentialHkdfAlgorithm,
 };
 use crypto::asymmetric::encryption;
 use crypto::shared_key::new_ephemeral_shared_key;
 use crypto::symmetric::stream_cipher;
+use cw3::ProposalResponse;
 use keypair::KeyPair;
 use nym_api_requests::coconut::{
-    BlindSignRequestBody, BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse,
+    BatchVerifyCredentialBody, BatchVerifyCredentialResponse, BlindSignRequestBody,
+    BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse, VerifyRefundBody,
+    VerifyTicketBody,
 };
-use validator_client::nym_api::routes::{BANDWIDTH, COCONUT_ROUTES};
-use validator_client::nymd::{Coin, Fee};
+use validator_client::nym_api::routes::{
+    BANDWIDTH, COCONUT_ADMIN, COCONUT_CREDENTIALS, COCONUT_ROUTES,
+};
+use validator_client::nymd::{AccountId, Coin, Fee};
 
 use getset::{CopyGetters, Getters};
 use rand_07::rngs::OsRng;
 use rocket::fairing::AdHoc;
 use rocket::serde::json::Json;
 use rocket::State as RocketState;
+use std::net::IpAddr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -53,6 +66,8 @@ pub struct State {
     comm_channel: Arc<dyn APICommunicationChannel + Send + Sync>,
     storage: NymApiStorage,
     rng: Arc<Mutex<OsRng>>,
+    rate_limiter: IssuanceRateLimiter,
+    admin_token: Option<String>,
 }
 
 impl State {
@@ -62,6 +77,7 @@ impl State {
         key_pair: KeyPair,
         comm_channel: D,
         storage: NymApiStorage,
+        issuance_config: IssuanceConfig,
     ) -> Self
     where
         C: LocalClient + Send + Sync + 'static,
@@ -70,6 +86,7 @@ impl State {
         let client = Arc::new(client);
         let comm_channel = Arc::new(comm_channel);
         let rng = Arc::new(Mutex::new(OsRng));
+        let rate_limiter = IssuanceRateLimiter::new(&issuance_config);
         Self {
             client,
             mix_denom,
@@ -77,6 +94,8 @@ impl State {
             comm_channel,
             storage,
             rng,
+            rate_limiter,
+            admin_token: issuance_config.admin_token,
         }
     }
 
@@ -170,22 +189,46 @@ impl InternalSignRequest {
         key_pair: KeyPair,
         comm_channel: D,
         storage: NymApiStorage,
+        issuance_config: IssuanceConfig,
     ) -> AdHoc
     where
         C: LocalClient + Send + Sync + 'static,
         D: APICommunicationChannel + Send + Sync + 'static,
     {
-        let state = State::new(client, mix_denom, key_pair, comm_channel, storage);
+        let state = State::new(
+            client,
+            mix_denom,
+            key_pair,
+            comm_channel,
+            storage,
+            issuance_config,
+        );
         AdHoc::on_ignite("Internal Sign Request Stage", |rocket| async {
-            rocket.manage(state).mount(
-                // this format! is so ugly...
-                format!("/{}/{}/{}", NYM_API_VERSION, COCONUT_ROUTES, BANDWIDTH),
-                routes![
-                    post_blind_sign,
-                    post_partial_bandwidth_credential,
-                    verify_bandwidth_credential
-                ],
-            )
+            rocket
+                .manage(state)
+                .mount(
+                    // this format! is so ugly...
+                    format!("/{}/{}/{}", NYM_API_VERSION, COCONUT_ROUTES, BANDWIDTH),
+                    routes![
+                        post_blind_sign,
+                        post_partial_bandwidth_credential,
+                        verify_bandwidth_credential,
+                        batch_verify_bandwidth_credentials,
+                        verify_bandwidth_ticket,
+                        verify_refund
+                    ],
+                )
+                .mount(
+                    format!(
+                        "/{}/{}/{}",
+                        NYM_API_VERSION, COCONUT_ROUTES, COCONUT_CREDENTIALS
+                    ),
+                    routes![post_blind_sign_credential],
+                )
+                .mount(
+                    format!("/{}/{}/{}", NYM_API_VERSION, COCONUT_ROUTES, COCONUT_ADMIN),
+                    routes![admin::issuance_totals, admin::issuance_log],
+                )
         })
     }
 }
@@ -204,20 +247,101 @@ fn blind_sign(request: InternalSignRequest, key_pair: &CoconutKeyPair) -> Result
 //  Until we have serialization and deserialization traits we'll be using a crutch
 pub async fn post_blind_sign(
     blind_sign_request_body: Json<BlindSignRequestBody>,
+    requester_ip: RequesterIp,
     state: &RocketState<State>,
 ) -> Result<Json<BlindedSignatureResponse>> {
     debug!("{:?}", blind_sign_request_body);
+    process_blind_sign_request(&Bandwidth, &blind_sign_request_body, requester_ip.0, state)
+        .await
+        .map(Json)
+}
+
+#[post("/<type_name>/blind-sign", data = "<blind_sign_request_body>")]
+pub async fn post_blind_sign_credential(
+    type_name: &str,
+    blind_sign_request_body: Json<BlindSignRequestBody>,
+    requester_ip: RequesterIp,
+    state: &RocketState<State>,
+) -> Result<Json<BlindedSignatureResponse>> {
+    debug!("{}: {:?}", type_name, blind_sign_request_body);
+    let credential_type = credential_type(type_name)
+        .ok_or_else(|| CoconutError::UnknownCredentialType(type_name.to_string()))?;
+    process_blind_sign_request(
+        credential_type,
+        &blind_sign_request_body,
+        requester_ip.0,
+        state,
+    )
+    .await
+    .map(Json)
+}
+
+async fn process_blind_sign_request(
+    credential_type: &dyn CredentialType,
+    blind_sign_request_body: &BlindSignRequestBody,
+    requester_ip: Option<IpAddr>,
+    state: &State,
+) -> Result<BlindedSignatureResponse> {
+    state
+        .rate_limiter
+        .check(requester_ip, blind_sign_request_body.tx_hash())?;
     if let Some(response) = state
         .signed_before(blind_sign_request_body.tx_hash())
         .await?
     {
-        return Ok(Json(response));
+        return Ok(response);
+    }
+
+    // requests that don't even match their deposit are not recorded, so that anyone couldn't
+    // grow the issuance log at will
+    let encryption_key =
+        match check_issuance_request(credential_type, blind_sign_request_body, state).await {
+            Ok(encryption_key) => encryption_key,
+            Err(err) => {
+                debug!(
+                    "rejected the issuance request for deposit {}: {err}",
+                    blind_sign_request_body.tx_hash()
+                );
+                return Err(err);
+            }
+        };
+
+    let result = issue_partial_credential(blind_sign_request_body, &encryption_key, state).await;
+    let record = issuance_record(blind_sign_request_body, requester_ip, &result);
+    if let Err(err) = state.storage.insert_issuance_log_record(&record).await {
+        error!(
+            "failed to record the issuance attempt for deposit {}: {err}",
+            record.tx_hash
+        );
+    }
+
+    result
+}
+
+// Makes sure the request matches its deposit and returns the encryption key of the depositor.
+async fn check_issuance_request(
+    credential_type: &dyn CredentialType,
+    blind_sign_request_body: &BlindSignRequestBody,
+    state: &State,
+) -> Result<encryption::PublicKey> {
+    if *blind_sign_request_body.total_params() != credential_type.total_attributes() {
+        return Err(CoconutError::InvalidAttributeCount {
+            expected: credential_type.total_attributes(),
+            received: *blind_sign_request_body.total_params(),
+        });
     }
     let tx = state
         .client
         .get_tx(blind_sign_request_body.tx_hash())
         .await?;
-    let encryption_key = extract_encryption_key(&blind_sign_request_body, tx).await?;
+    extract_encryption_key(credential_type, blind_sign_request_body, tx).await
+}
+
+async fn issue_partial_credential(
+    blind_sign_request_body: &BlindSignRequestBody,
+    encryption_key: &encryption::PublicKey,
+    state: &State,
+) -> Result<BlindedSignatureResponse> {
     let internal_request = InternalSignRequest::new(
         *blind_sign_request_body.total_params(),
         blind_sign_request_body.public_attributes(),
@@ -229,22 +353,22 @@ pub async fn post_blind_sign(
         return Err(CoconutError::KeyPairNotDerivedYet);
     };
 
-    let response = state
+    state
         .encrypt_and_store(
             blind_sign_request_body.tx_hash(),
-            &encryption_key,
+            encryption_key,
             &blinded_signature,
         )
-        .await?;
-
-    Ok(Json(response))
+        .await
 }
 
 #[post("/partial-bandwidth-credential", data = "<tx_hash>")]
 pub async fn post_partial_bandwidth_credential(
     tx_hash: Json<String>,
+    requester_ip: RequesterIp,
     state: &RocketState<State>,
 ) -> Result<Json<BlindedSignatureResponse>> {
+    state.rate_limiter.check(requester_ip.0, &tx_hash)?;
     let v = state
         .signed_before(&tx_hash)
         .await?
@@ -252,53 +376,164 @@ pub async fn post_partial_bandwidth_credential(
     Ok(Json(v))
 }
 
-#[post("/verify-bandwidth-credential", data = "<verify_credential_body>")]
-pub async fn verify_bandwidth_credential(
-    verify_credential_body: Json<VerifyCredentialBody>,
-    state: &RocketState<State>,
-) -> Result<Json<VerifyCredentialResponse>> {
-    let proposal_id = *verify_credential_body.proposal_id();
-    let proposal = state.client.get_proposal(proposal_id).await?;
+// Makes sure the proposal is a pending release of the funds of the provided credential and returns
+// whether the funds and the expiry recorded on chain are the ones the credential has been issued with.
+// Note that it does not verify the credential itself.
+async fn check_release_proposal(
+    state: &State,
+    verify_credential_body: &VerifyCredentialBody,
+) -> Result<bool> {
+    let credential = verify_credential_body.credential();
+    let proposal = state
+        .client
+        .get_proposal(*verify_credential_body.proposal_id())
+        .await?;
     // Proposal description is the blinded serial number
-    if !verify_credential_body
-        .credential()
-        .has_blinded_serial_number(&proposal.description)?
-    {
+    if !credential.has_blinded_serial_number(&proposal.description)? {
         return Err(CoconutError::IncorrectProposal {
             reason: String::from("incorrect blinded serial number in description"),
         });
     }
-    let proposed_release_funds =
-        funds_from_cosmos_msgs(proposal.msgs).ok_or(CoconutError::IncorrectProposal {
-            reason: String::from("action is not to release funds"),
+    let matches_credential = check_pending_release(
+        state,
+        proposal,
+        credential.blinded_serial_number(),
+        credential.voucher_value(),
+        credential.expiry_epoch(),
+    )
+    .await?;
+
+    // only the bandwidth vouchers are spent whole, in particular the divisible ones have to be spent as tickets
+    Ok(matches_credential && credential.voucher_info() == VOUCHER_INFO)
+}
+
+// Same as `check_release_proposal`, but for a single ticket of a divisible credential.
+async fn check_ticket_release_proposal(
+    state: &State,
+    verify_ticket_body: &VerifyTicketBody,
+) -> Result<bool> {
+    let ticket = verify_ticket_body.ticket();
+    let proposal = state
+        .client
+        .get_proposal(*verify_ticket_body.proposal_id())
+        .await?;
+    // Proposal description is the serial number of the ticket
+    if proposal.description != ticket.ticket_serial_number() {
+        return Err(CoconutError::IncorrectProposal {
+            reason: String::from("incorrect ticket serial number in description"),
+        });
+    }
+    let matches_ticket = check_pending_release(
+        state,
+        proposal,
+        ticket.ticket_serial_number(),
+        ticket.ticket_value(TICKET_INDEX_BITS),
+        ticket.expiry_epoch(),
+    )
+    .await?;
+
+    Ok(matches_ticket && ticket.voucher_info() == DIVISIBLE_VOUCHER_INFO)
+}
+
+// Makes sure the proposal is a pending refund of the funds of the provided credential to the claimant
+// and returns whether the credential has been issued with the recorded funds and expiry.
+// Note that it does not verify the credential itself.
+async fn check_refund_proposal(
+    state: &State,
+    verify_refund_body: &VerifyRefundBody,
+) -> Result<bool> {
+    let credential = verify_refund_body.credential();
+    let proposal = state
+        .client
+        .get_proposal(*verify_refund_body.proposal_id())
+        .await?;
+    // Proposal description is the blinded serial number
+    if !credential.has_blinded_serial_number(&proposal.description)? {
+        return Err(CoconutError::IncorrectProposal {
+            reason: String::from("incorrect blinded serial number in description"),
+        });
+    }
+    let (proposed_refund_funds, recipient) =
+        refund_from_cosmos_msgs(proposal.msgs).ok_or(CoconutError::IncorrectProposal {
+            reason: String::from("action is not to refund funds"),
         })?;
-    // Credential has not been spent before, and is on its way of being spent
-    let credential_status = state
+    let claimant = verify_refund_body.claimant_cosmos_addr().to_string();
+    if recipient != claimant {
+        return Err(CoconutError::IncorrectProposal {
+            reason: String::from("funds are not refunded to the claimant"),
+        });
+    }
+    // the contract puts the refunded credentials into the spent set, so this also guarantees that
+    // the credential has never been spent at a gateway, nor refunded before
+    let spent_credential =
+        pending_spent_credential(state, credential.blinded_serial_number()).await?;
+    if spent_credential.gateway_cosmos_address().as_str() != claimant {
+        return Err(CoconutError::IncorrectProposal {
+            reason: String::from("refund has been claimed by someone else"),
+        });
+    }
+
+    // the contract only enforces the refund window of the expiry declared by the claimant
+    let matches_credential = spent_credential.expiry_epoch()
+        == effective_expiry_epoch(credential.expiry_epoch())
+        && Coin::from(proposed_refund_funds)
+            == Coin::new(credential.voucher_value() as u128, state.mix_denom.clone());
+
+    // tickets of the divisible vouchers are accounted for separately, so they can't be refunded whole
+    Ok(matches_credential && credential.voucher_info() == VOUCHER_INFO)
+}
+
+// Returns the spent credential with the provided serial number, as long as it is on its way of being spent.
+async fn pending_spent_credential(state: &State, serial_number: String) -> Result<SpendCredential> {
+    let spent_credential = state
         .client
-        .get_spent_credential(verify_credential_body.credential().blinded_serial_number())
+        .get_spent_credential(serial_number)
         .await?
         .spend_credential
         .ok_or(CoconutError::InvalidCredentialStatus {
             status: String::from("Inexistent"),
-        })?
-        .status();
+        })?;
+    let credential_status = spent_credential.status();
     if credential_status != SpendCredentialStatus::InProgress {
         return Err(CoconutError::InvalidCredentialStatus {
             status: format!("{:?}", credential_status),
         });
     }
-    let verification_key = state.verification_key().await?;
-    let mut vote_yes = verify_credential_body
-        .credential()
-        .verify(&verification_key);
-
-    vote_yes &= Coin::from(proposed_release_funds)
-        == Coin::new(
-            verify_credential_body.credential().voucher_value() as u128,
-            state.mix_denom.clone(),
-        );
+    Ok(spent_credential)
+}
 
-    // Vote yes or no on the proposal based on the verification result
+// Checks that the proposal is to release the provided value and that the credential (or ticket)
+// with the provided serial number is on its way of being spent with the expected expiry.
+async fn check_pending_release(
+    state: &State,
+    proposal: ProposalResponse,
+    serial_number: String,
+    value: u64,
+    expiry_epoch: u64,
+) -> Result<bool> {
+    let proposed_release_funds =
+        funds_from_cosmos_msgs(proposal.msgs).ok_or(CoconutError::IncorrectProposal {
+            reason: String::from("action is not to release funds"),
+        })?;
+    // Credential has not been spent before, and is on its way of being spent
+    let spent_credential = pending_spent_credential(state, serial_number).await?;
+
+    // the contract only enforces the redemption deadline of the expiry declared by the gateway,
+    // which has to be the one the credential has actually been issued with
+    let matches_credential =
+        spent_credential.expiry_epoch() == effective_expiry_epoch(expiry_epoch);
+
+    Ok(matches_credential
+        && Coin::from(proposed_release_funds) == Coin::new(value as u128, state.mix_denom.clone()))
+}
+
+// Vote yes or no on the proposal based on the verification result, with the fees paid by the granter
+async fn vote_on_release_proposal(
+    state: &State,
+    proposal_id: u64,
+    gateway_cosmos_addr: &AccountId,
+    vote_yes: bool,
+) -> Result<()> {
     state
         .client
         .vote_proposal(
@@ -307,10 +542,144 @@ pub async fn verify_bandwidth_credential(
             Some(Fee::new_payer_granter_auto(
                 None,
                 None,
-                Some(verify_credential_body.gateway_cosmos_addr().to_owned()),
+                Some(gateway_cosmos_addr.to_owned()),
             )),
         )
         .await?;
+    Ok(())
+}
+
+#[post("/verify-bandwidth-credential", data = "<verify_credential_body>")]
+pub async fn verify_bandwidth_credential(
+    verify_credential_body: Json<VerifyCredentialBody>,
+    state: &RocketState<State>,
+) -> Result<Json<VerifyCredentialResponse>> {
+    let matches_credential = check_release_proposal(state, &verify_credential_body).await?;
+    let verification_key = state.verification_key().await?;
+    let vote_yes = matches_credential
+        && verify_credential_body
+            .credential()
+            .verify(&verification_key);
+
+    vote_on_release_proposal(
+        state,
+        *verify_credential_body.proposal_id(),
+        verify_credential_body.gateway_cosmos_addr(),
+        vote_yes,
+    )
+    .await?;
+
+    Ok(Json(VerifyCredentialResponse::new(vote_yes)))
+}
+
+#[post("/verify-bandwidth-ticket", data = "<verify_ticket_body>")]
+pub async fn verify_bandwidth_ticket(
+    verify_ticket_body: Json<VerifyTicketBody>,
+    state: &RocketState<State>,
+) -> Result<Json<VerifyCredentialResponse>> {
+    let matches_ticket = check_ticket_release_proposal(state, &verify_ticket_body).await?;
+    let verification_key = state.verification_key().await?;
+    let vote_yes = matches_ticket
+        && verify_ticket_body
+            .ticket()
+            .verify(&verification_key, TICKET_INDEX_BITS);
+
+    vote_on_release_proposal(
+        state,
+        *verify_ticket_body.proposal_id(),
+        verify_ticket_body.gateway_cosmos_addr(),
+        vote_yes,
+    )
+    .await?;
+
+    Ok(Json(VerifyCredentialResponse::new(vote_yes)))
+}
+
+#[post("/verify-refund", data = "<verify_refund_body>")]
+pub async fn verify_refund(
+    verify_refund_body: Json<VerifyRefundBody>,
+    state: &RocketState<State>,
+) -> Result<Json<VerifyCredentialResponse>> {
+    let matches_credential = check_refund_proposal(state, &verify_refund_body).await?;
+    let verification_key = state.verification_key().await?;
+    let vote_yes = matches_credential && verify_refund_body.credential().verify(&verification_key);
+
+    // as with the gateways, the claimant grants the allowance for the fees of the vote
+    vote_on_release_proposal(
+        state,
+        *verify_refund_body.proposal_id(),
+        verify_refund_body.claimant_cosmos_addr(),
+        vote_yes,
+    )
+    .await?;
 
     Ok(Json(VerifyCredentialResponse::new(vote_yes)))
 }
+
+#[post(
+    "/batch-verify-bandwidth-credentials",
+    data = "<batch_verify_credential_body>"
+)]
+pub async fn batch_verify_bandwidth_credentials(
+    batch_verify_credential_body: Json<BatchVerifyCredentialBody>,
+    state: &RocketState<State>,
+) -> Result<Json<BatchVerifyCredentialResponse>> {
+    let requests = batch_verify_credential_body.credentials();
+    let verification_key = state.verification_key().await?;
+
+    // a proposal that can't be voted on is reported as failed without affecting the rest of the batch
+    let mut proposal_checks = Vec::with_capacity(requests.len());
+    for request in requests {
+        let check = match check_release_proposal(state, request).await {
+            Ok(matches_credential) => Some(matches_credential),
+            Err(err) => {
+                warn!(
+                    "could not verify the release proposal {}: {err}",
+                    request.proposal_id()
+                );
+                None
+            }
+        };
+        proposal_checks.push(check);
+    }
+
+    let credentials = requests
+        .iter()
+        .zip(proposal_checks.iter())
+        .filter(|(_, check)| check.is_some())
+        .map(|(request, _)| request.credential().clone())
+        .collect::<Vec<_>>();
+    let mut verified = Credential::batch_verify(&credentials, &verification_key).into_iter();
+
+    let mut verification_results = Vec::with_capacity(requests.len());
+    for (request, check) in requests.iter().zip(proposal_checks) {
+        let matches_credential = match check {
+            Some(matches_credential) => matches_credential,
+            None => {
+                verification_results.push(false);
+                continue;
+            }
+        };
+        let vote_yes = verified.next().unwrap_or_default() && matches_credential;
+        if let Err(err) = vote_on_release_proposal(
+            state,
+            *request.proposal_id(),
+            request.gateway_cosmos_addr(),
+            vote_yes,
+        )
+        .await
+        {
+            warn!(
+                "failed to vote on the release proposal {}: {err}",
+                request.proposal_id()
+            );
+            verification_results.push(false);
+        } else {
+            verification_results.push(vote_yes);
+        }
+    }
+
+    Ok(Json(BatchVerifyCredentialResponse::new(
+        verification_results,
+    )))
+}
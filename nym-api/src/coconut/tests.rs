@@ -4,35 +4,41 @@
 use super::InternalSignRequest;
 use crate::coconut::error::{CoconutError, Result};
 use coconut_bandwidth_contract_common::events::{
-    DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_IDENTITY_KEY, DEPOSIT_INFO,
-    DEPOSIT_VALUE,
+    DEPOSITED_FUNDS_EVENT_TYPE, DEPOSIT_ENCRYPTION_KEY, DEPOSIT_EXPIRY_EPOCH, DEPOSIT_IDENTITY_KEY,
+    DEPOSIT_INFO, DEPOSIT_VALUE,
 };
+use coconut_bandwidth_contract_common::expiry::deposit_epoch;
 use coconut_bandwidth_contract_common::spend_credential::{
     SpendCredential, SpendCredentialResponse,
 };
-use coconut_interface::{hash_to_scalar, Credential, VerificationKey};
-use config::defaults::VOUCHER_INFO;
+use coconut_interface::{hash_to_scalar, Credential, TicketCredential, VerificationKey};
+use config::defaults::{DIVISIBLE_VOUCHER_INFO, SERVICE_ACCESS_INFO, VOUCHER_INFO};
 use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, WasmMsg};
-use credentials::coconut::bandwidth::BandwidthVoucher;
+use credentials::coconut::bandwidth::{BandwidthVoucher, TICKET_INDEX_BITS};
 use credentials::coconut::params::{
     NymApiCredentialEncryptionAlgorithm, NymApiCredentialHkdfAlgorithm,
 };
 use crypto::shared_key::recompute_shared_key;
 use crypto::symmetric::stream_cipher;
 use nym_api_requests::coconut::{
-    BlindSignRequestBody, BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse,
+    BatchVerifyCredentialBody, BatchVerifyCredentialResponse, BlindSignRequestBody,
+    BlindedSignatureResponse, IssuanceLogResponse, IssuanceTotals, IssuanceTotalsResponse,
+    VerifyCredentialBody, VerifyCredentialResponse, VerifyRefundBody, VerifyTicketBody,
 };
 use nymcoconut::tests::helpers::theta_from_keys_and_attributes;
 use nymcoconut::{
-    prepare_blind_sign, ttp_keygen, Base58, BlindSignRequest, BlindedSignature, Parameters,
+    blind_sign, prepare_blind_sign, prove_ticket, ttp_keygen, Base58, BlindedSignature, Parameters,
 };
 use validator_client::nym_api::routes::{
-    API_VERSION, BANDWIDTH, COCONUT_BLIND_SIGN, COCONUT_PARTIAL_BANDWIDTH_CREDENTIAL,
-    COCONUT_ROUTES, COCONUT_VERIFY_BANDWIDTH_CREDENTIAL,
+    API_VERSION, BANDWIDTH, COCONUT_ADMIN, COCONUT_BATCH_VERIFY_BANDWIDTH_CREDENTIALS,
+    COCONUT_BLIND_SIGN, COCONUT_CREDENTIALS, COCONUT_ISSUANCE_LOG, COCONUT_ISSUANCE_TOTALS,
+    COCONUT_PARTIAL_BANDWIDTH_CREDENTIAL, COCONUT_ROUTES, COCONUT_VERIFY_BANDWIDTH_CREDENTIAL,
+    COCONUT_VERIFY_BANDWIDTH_TICKET, COCONUT_VERIFY_REFUND,
 };
 use validator_client::nymd::Coin;
 use validator_client::nymd::{tx::Hash, AccountId, DeliverTx, Event, Fee, Tag, TxResponse};
 
+use crate::coconut::issuance::IssuanceConfig;
 use crate::coconut::State;
 use crate::NymApiStorage;
 use async_trait::async_trait;
@@ -48,11 +54,12 @@ use cw3::ProposalResponse;
 use dkg::Threshold;
 use rand_07::rngs::OsRng;
 use rand_07::Rng;
-use rocket::http::Status;
+use rocket::http::{Header, Status};
 use rocket::local::asynchronous::Client;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use validator_client::nymd::cosmwasm_client::logs::Log;
 use validator_client::nymd::cosmwasm_client::types::ExecuteResult;
 
@@ -432,12 +439,13 @@ async fn signed_before() {
         "2DHbEZ6pzToGpsAXJrqJi7Wj1pAXeT18283q2YEEyNH5gTymwRozWBdja6SMAVt1dyYmUnM4ZNhsJ4wxZyGh4Z6J",
     );
 
-    let params = Parameters::new(4).unwrap();
+    let params = Parameters::new(5).unwrap();
     let mut rng = OsRng;
     let voucher = BandwidthVoucher::new(
         &params,
         "1234".to_string(),
         VOUCHER_INFO.to_string(),
+        19000,
         tx_hash,
         identity::PrivateKey::from_base58_string(
             identity::KeyPair::new(&mut rng)
@@ -479,6 +487,7 @@ async fn signed_before() {
         staged_key_pair,
         comm_channel,
         storage.clone(),
+        IssuanceConfig::default(),
     ));
     let client = Client::tracked(rocket)
         .await
@@ -490,7 +499,7 @@ async fn signed_before() {
         signature.clone(),
         &voucher.get_public_attributes(),
         voucher.get_public_attributes_plain(),
-        4,
+        5,
     );
 
     let encrypted_signature = vec![1, 2, 3, 4];
@@ -546,6 +555,7 @@ async fn state_functions() {
         staged_key_pair,
         comm_channel,
         storage.clone(),
+        IssuanceConfig::default(),
     );
 
     let tx_hash = String::from("6B27412050B823E58BB38447D7870BBC8CBE3C51C905BEA89D459ACCDA80A00E");
@@ -647,19 +657,17 @@ async fn blind_sign_correct() {
     let tx_hash =
         Hash::from_str("7C41AF8266D91DE55E1C8F4712E6A952A165ED3D8C27C7B00428CBD0DE00A52B").unwrap();
 
-    let params = Parameters::new(4).unwrap();
+    let params = Parameters::new(5).unwrap();
     let mut rng = OsRng;
+    let identity_keypair = identity::KeyPair::new(&mut rng);
     let voucher = BandwidthVoucher::new(
         &params,
         "1234".to_string(),
         VOUCHER_INFO.to_string(),
+        19000,
         tx_hash,
-        identity::PrivateKey::from_base58_string(
-            identity::KeyPair::new(&mut rng)
-                .private_key()
-                .to_base58_string(),
-        )
-        .unwrap(),
+        identity::PrivateKey::from_base58_string(identity_keypair.private_key().to_base58_string())
+            .unwrap(),
         encryption::PrivateKey::from_bytes(
             &encryption::KeyPair::new(&mut rng).private_key().to_bytes(),
         )
@@ -686,9 +694,15 @@ async fn blind_sign_correct() {
             key: DEPOSIT_INFO.parse().unwrap(),
             value: VOUCHER_INFO.parse().unwrap(),
         },
+        Tag {
+            key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+            value: "19000".parse().unwrap(),
+        },
         Tag {
             key: DEPOSIT_IDENTITY_KEY.parse().unwrap(),
-            value: "64auwDkWan7R8yH1Mwe9dS4qXgrDBCUNDg3Q4KFnd2P5"
+            value: identity_keypair
+                .public_key()
+                .to_base58_string()
                 .parse()
                 .unwrap(),
         },
@@ -716,44 +730,27 @@ async fn blind_sign_correct() {
         staged_key_pair,
         comm_channel,
         storage.clone(),
+        IssuanceConfig {
+            max_requests_per_ip: 0,
+            max_requests_per_deposit: 2,
+            rate_limit_window: Duration::from_secs(60),
+            trusted_proxies: Vec::new(),
+            admin_token: Some(String::from("admin-token")),
+        },
     ));
     let client = Client::tracked(rocket)
         .await
         .expect("valid rocket instance");
 
-    // hard-coded values, that generate a correct signature
-    let blind_sign_req = BlindSignRequest::from_bytes(&[
-        176, 113, 19, 237, 218, 252, 113, 20, 225, 238, 59, 88, 217, 45, 233, 178, 65, 28, 242, 0,
-        222, 48, 110, 216, 26, 111, 51, 235, 61, 74, 200, 15, 130, 245, 45, 170, 155, 190, 156, 77,
-        180, 142, 29, 63, 15, 224, 150, 31, 139, 24, 65, 175, 143, 153, 11, 203, 33, 16, 152, 22,
-        221, 203, 99, 233, 208, 142, 161, 194, 46, 227, 177, 96, 119, 30, 175, 69, 104, 14, 2, 191,
-        26, 94, 30, 165, 15, 28, 40, 176, 1, 78, 253, 79, 20, 137, 102, 74, 2, 0, 0, 0, 0, 0, 0, 0,
-        131, 133, 112, 115, 53, 98, 58, 166, 240, 70, 185, 210, 203, 12, 114, 66, 180, 38, 139, 12,
-        187, 45, 250, 201, 68, 102, 159, 172, 218, 124, 151, 23, 172, 18, 216, 122, 246, 7, 185,
-        76, 20, 167, 123, 122, 152, 241, 175, 226, 176, 8, 170, 70, 140, 252, 36, 130, 67, 204,
-        111, 116, 107, 92, 200, 77, 252, 31, 138, 18, 10, 215, 165, 243, 95, 199, 193, 61, 200,
-        187, 22, 198, 109, 213, 145, 71, 171, 132, 174, 68, 105, 248, 0, 115, 50, 55, 199, 84, 67,
-        16, 125, 216, 250, 154, 115, 174, 9, 206, 44, 88, 63, 163, 124, 10, 239, 64, 158, 191, 27,
-        169, 177, 194, 223, 142, 202, 206, 189, 122, 123, 91, 171, 15, 40, 192, 148, 75, 174, 24,
-        116, 229, 127, 170, 110, 183, 151, 2, 118, 168, 22, 113, 87, 237, 91, 228, 249, 120, 114,
-        255, 53, 175, 245, 39, 2, 0, 0, 0, 0, 0, 0, 0, 225, 45, 230, 25, 62, 202, 96, 166, 171,
-        241, 206, 137, 254, 51, 154, 255, 122, 130, 107, 54, 5, 206, 207, 120, 193, 214, 64, 10,
-        111, 195, 86, 55, 201, 36, 10, 18, 154, 158, 183, 87, 185, 59, 228, 89, 134, 193, 217, 188,
-        64, 164, 249, 21, 248, 20, 207, 58, 31, 10, 19, 176, 246, 150, 45, 48, 2, 0, 0, 0, 0, 0, 0,
-        0, 173, 60, 65, 209, 100, 114, 138, 186, 158, 150, 109, 230, 111, 86, 101, 72, 194, 237,
-        173, 195, 139, 175, 238, 25, 169, 18, 188, 75, 77, 54, 111, 20, 115, 235, 195, 2, 123, 133,
-        164, 81, 15, 45, 11, 84, 139, 38, 8, 224, 197, 181, 95, 147, 49, 77, 193, 207, 52, 141,
-        195, 195, 66, 137, 17, 32,
-    ])
-    .unwrap();
     let request_body = BlindSignRequestBody::new(
-        &blind_sign_req,
+        voucher.blind_sign_request(),
         tx_hash.to_string(),
-        "gSFgpma5GAVMcsmZwKieqGNHNd3dPzcfa8eT2Qn2LoBccSeyiJdphREbNrkuh5XWxMe2hUsranaYzLro48L9Qhd"
-            .to_string(),
+        voucher
+            .sign(voucher.blind_sign_request())
+            .to_base58_string(),
         &voucher.get_public_attributes(),
         voucher.get_public_attributes_plain(),
-        4,
+        5,
     );
 
     let response = client
@@ -770,6 +767,214 @@ async fn blind_sign_correct() {
     let blinded_signature_response =
         serde_json::from_str::<BlindedSignatureResponse>(&response.into_string().await.unwrap());
     assert!(blinded_signature_response.is_ok());
+
+    // Requesting the credential again just returns the same signature, up to the limit
+    let response = client
+        .post(format!(
+            "/{}/{}/{}/{}",
+            API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_BLIND_SIGN
+        ))
+        .json(&request_body)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let response = client
+        .post(format!(
+            "/{}/{}/{}/{}",
+            API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_BLIND_SIGN
+        ))
+        .json(&request_body)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::TooManyRequests);
+
+    // The issuance reports are only available with the admin token
+    let totals_route = format!(
+        "/{}/{}/{}/{}",
+        API_VERSION, COCONUT_ROUTES, COCONUT_ADMIN, COCONUT_ISSUANCE_TOTALS
+    );
+    let response = client.get(totals_route.clone()).dispatch().await;
+    assert_eq!(response.status(), Status::Unauthorized);
+    let response = client
+        .get(totals_route.clone())
+        .header(Header::new("Authorization", "Bearer wrong-token"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .get(totals_route)
+        .header(Header::new("Authorization", "Bearer admin-token"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let totals_response =
+        serde_json::from_str::<IssuanceTotalsResponse>(&response.into_string().await.unwrap())
+            .unwrap();
+    assert_eq!(
+        totals_response.totals,
+        vec![IssuanceTotals {
+            deposit_epoch: deposit_epoch(19000),
+            issued_credentials: 1,
+            issued_value: 1234,
+            failed_requests: 0,
+        }]
+    );
+
+    let response = client
+        .get(format!(
+            "/{}/{}/{}/{}/{}",
+            API_VERSION,
+            COCONUT_ROUTES,
+            COCONUT_ADMIN,
+            COCONUT_ISSUANCE_LOG,
+            deposit_epoch(19000)
+        ))
+        .header(Header::new("Authorization", "Bearer admin-token"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let log_response =
+        serde_json::from_str::<IssuanceLogResponse>(&response.into_string().await.unwrap())
+            .unwrap();
+    assert_eq!(log_response.entries.len(), 1);
+    assert_eq!(log_response.entries[0].tx_hash, tx_hash.to_string());
+    assert!(log_response.entries[0].issued);
+}
+
+#[tokio::test]
+async fn blind_sign_credential_type() {
+    let tx_hash =
+        Hash::from_str("2B7E1A3C9F0D4E5A6B7C8D9E0F1A2B3C4D5E6F708192A3B4C5D6E7F8091A2B3C").unwrap();
+
+    let params = Parameters::new(5).unwrap();
+    let mut rng = OsRng;
+    let identity_keypair = identity::KeyPair::new(&mut rng);
+    let voucher = BandwidthVoucher::new(
+        &params,
+        "1234".to_string(),
+        SERVICE_ACCESS_INFO.to_string(),
+        19000,
+        tx_hash,
+        identity::PrivateKey::from_base58_string(identity_keypair.private_key().to_base58_string())
+            .unwrap(),
+        encryption::PrivateKey::from_bytes(
+            &encryption::KeyPair::new(&mut rng).private_key().to_bytes(),
+        )
+        .unwrap(),
+    );
+
+    let key_pair = ttp_keygen(&params, 1, 1).unwrap().remove(0);
+    let mut db_dir = std::env::temp_dir();
+    db_dir.push(&key_pair.verification_key().to_bs58()[..8]);
+    let storage = NymApiStorage::init(db_dir).await.unwrap();
+    let tx_db = Arc::new(RwLock::new(HashMap::new()));
+
+    let mut tx_entry = tx_entry_fixture(&tx_hash.to_string());
+    tx_entry.tx_result.events.push(Event {
+        type_str: format!("wasm-{}", DEPOSITED_FUNDS_EVENT_TYPE),
+        attributes: vec![
+            Tag {
+                key: DEPOSIT_VALUE.parse().unwrap(),
+                value: "1234".parse().unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_INFO.parse().unwrap(),
+                value: SERVICE_ACCESS_INFO.parse().unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_EXPIRY_EPOCH.parse().unwrap(),
+                value: "19000".parse().unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_IDENTITY_KEY.parse().unwrap(),
+                value: identity_keypair
+                    .public_key()
+                    .to_base58_string()
+                    .parse()
+                    .unwrap(),
+            },
+            Tag {
+                key: DEPOSIT_ENCRYPTION_KEY.parse().unwrap(),
+                value: "HxnTpWTkgigSTAysVKLE8pEiUULHdTT1BxFfzfJvQRi6"
+                    .parse()
+                    .unwrap(),
+            },
+        ],
+    });
+    tx_db
+        .write()
+        .unwrap()
+        .insert(tx_hash.to_string(), tx_entry.clone());
+    let nymd_client =
+        DummyClient::new(AccountId::from_str(TEST_REWARDING_VALIDATOR_ADDRESS).unwrap())
+            .with_tx_db(&tx_db);
+    let comm_channel = DummyCommunicationChannel::new(key_pair.verification_key());
+    let staged_key_pair = crate::coconut::KeyPair::new();
+    staged_key_pair.set(key_pair).await;
+
+    let rocket = rocket::build().attach(InternalSignRequest::stage(
+        nymd_client,
+        TEST_COIN_DENOM.to_string(),
+        staged_key_pair,
+        comm_channel,
+        storage.clone(),
+        IssuanceConfig::default(),
+    ));
+    let client = Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance");
+
+    let request_body = |total_params| {
+        BlindSignRequestBody::new(
+            voucher.blind_sign_request(),
+            tx_hash.to_string(),
+            voucher
+                .sign(voucher.blind_sign_request())
+                .to_base58_string(),
+            &voucher.get_public_attributes(),
+            voucher.get_public_attributes_plain(),
+            total_params,
+        )
+    };
+    let route = |type_name: &str| {
+        format!(
+            "/{}/{}/{}/{}/{}",
+            API_VERSION, COCONUT_ROUTES, COCONUT_CREDENTIALS, type_name, COCONUT_BLIND_SIGN
+        )
+    };
+
+    let response = client
+        .post(route("PremiumVoucher"))
+        .json(&request_body(5))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = client
+        .post(route(SERVICE_ACCESS_INFO))
+        .json(&request_body(4))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        CoconutError::InvalidAttributeCount {
+            expected: 5,
+            received: 4
+        }
+        .to_string()
+    );
+
+    let response = client
+        .post(route(SERVICE_ACCESS_INFO))
+        .json(&request_body(5))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let blinded_signature_response =
+        serde_json::from_str::<BlindedSignatureResponse>(&response.into_string().await.unwrap());
+    assert!(blinded_signature_response.is_ok());
 }
 
 #[tokio::test]
@@ -793,6 +998,7 @@ async fn signature_test() {
         staged_key_pair,
         comm_channel,
         storage.clone(),
+        IssuanceConfig::default(),
     ));
     let client = Client::tracked(rocket)
         .await
@@ -852,13 +1058,15 @@ async fn verification_of_bandwidth_credential() {
         .with_proposal_db(&proposal_db)
         .with_spent_credential_db(&spent_credential_db);
     let mut db_dir = std::env::temp_dir();
-    let params = Parameters::new(4).unwrap();
+    let params = Parameters::new(5).unwrap();
     let mut key_pairs = ttp_keygen(&params, 1, 1).unwrap();
     let voucher_value = 1234u64;
-    let voucher_info = "voucher info";
+    let voucher_info = VOUCHER_INFO;
+    let expiry_epoch = 19000u64;
     let public_attributes = vec![
         hash_to_scalar(voucher_value.to_string()),
         hash_to_scalar(voucher_info),
+        hash_to_scalar(expiry_epoch.to_string()),
     ];
     let indices: Vec<u64> = key_pairs
         .iter()
@@ -879,13 +1087,20 @@ async fn verification_of_bandwidth_credential() {
         staged_key_pair,
         comm_channel.clone(),
         storage1.clone(),
+        IssuanceConfig::default(),
     ));
 
     let client = Client::tracked(rocket)
         .await
         .expect("valid rocket instance");
 
-    let credential = Credential::new(4, theta.clone(), voucher_value, voucher_info.to_string());
+    let credential = Credential::new(
+        5,
+        theta.clone(),
+        voucher_value,
+        voucher_info.to_string(),
+        expiry_epoch,
+    );
     let proposal_id = 42;
     // The address is not used, so we can use a duplicate
     let gateway_cosmos_addr = validator_address.clone();
@@ -1026,16 +1241,18 @@ async fn verification_of_bandwidth_credential() {
         funds.clone().into(),
         credential.blinded_serial_number(),
         Addr::unchecked("unimportant"),
+        expiry_epoch,
     );
     spent_credential_db.write().unwrap().insert(
         credential.blinded_serial_number(),
         SpendCredentialResponse::new(Some(spent_credential.clone())),
     );
     let bad_credential = Credential::new(
-        4,
+        5,
         theta.clone(),
         voucher_value,
         String::from("bad voucher info"),
+        expiry_epoch,
     );
     let bad_req =
         VerifyCredentialBody::new(bad_credential, proposal_id, gateway_cosmos_addr.clone());
@@ -1062,6 +1279,44 @@ async fn verification_of_bandwidth_credential() {
             .status
     );
 
+    // Test the endpoint with a spent credential recorded with a different expiry than the credential's
+    let wrong_expiry_credential = SpendCredential::new(
+        funds.clone().into(),
+        credential.blinded_serial_number(),
+        Addr::unchecked("unimportant"),
+        expiry_epoch + 1,
+    );
+    spent_credential_db.write().unwrap().insert(
+        credential.blinded_serial_number(),
+        SpendCredentialResponse::new(Some(wrong_expiry_credential)),
+    );
+    let response = client
+        .post(format!(
+            "/{}/{}/{}/{}",
+            API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_VERIFY_BANDWIDTH_CREDENTIAL
+        ))
+        .json(&req)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let verify_credential_response =
+        serde_json::from_str::<VerifyCredentialResponse>(&response.into_string().await.unwrap())
+            .unwrap();
+    assert!(!verify_credential_response.verification_result);
+    assert_eq!(
+        cw3::Status::Rejected,
+        proposal_db
+            .read()
+            .unwrap()
+            .get(&proposal_id)
+            .unwrap()
+            .status
+    );
+    spent_credential_db.write().unwrap().insert(
+        credential.blinded_serial_number(),
+        SpendCredentialResponse::new(Some(spent_credential.clone())),
+    );
+
     // Test the endpoint with a proposal that has a different value for the funds to be released
     // then what's in the credential
     let funds = Coin::new((voucher_value + 10) as u128, TEST_COIN_DENOM);
@@ -1139,6 +1394,45 @@ async fn verification_of_bandwidth_credential() {
             .status
     );
 
+    // Test the batch endpoint with a valid credential, an invalid one and a missing proposal
+    proposal_db
+        .write()
+        .unwrap()
+        .insert(proposal_id, proposal.clone());
+    let missing_proposal_req =
+        VerifyCredentialBody::new(credential.clone(), proposal_id + 1, gateway_cosmos_addr);
+    let batch_req = BatchVerifyCredentialBody::new(vec![
+        VerifyCredentialBody::new(credential.clone(), proposal_id, validator_address.clone()),
+        bad_req,
+        missing_proposal_req,
+    ]);
+    let response = client
+        .post(format!(
+            "/{}/{}/{}/{}",
+            API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_BATCH_VERIFY_BANDWIDTH_CREDENTIALS
+        ))
+        .json(&batch_req)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let batch_verify_response = serde_json::from_str::<BatchVerifyCredentialResponse>(
+        &response.into_string().await.unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        batch_verify_response.verification_results,
+        vec![true, false, false]
+    );
+    assert_eq!(
+        cw3::Status::Passed,
+        proposal_db
+            .read()
+            .unwrap()
+            .get(&proposal_id)
+            .unwrap()
+            .status
+    );
+
     // Test the endpoint with the credential marked as Spent in the Coconut Bandwidth Contract
     spent_credential.mark_as_spent();
     spent_credential_db.write().unwrap().insert(
@@ -1162,3 +1456,368 @@ async fn verification_of_bandwidth_credential() {
         .to_string()
     );
 }
+
+#[tokio::test]
+async fn verification_of_bandwidth_ticket() {
+    let validator_address = AccountId::from_str(TEST_REWARDING_VALIDATOR_ADDRESS).unwrap();
+    let proposal_db = Arc::new(RwLock::new(HashMap::new()));
+    let spent_credential_db = Arc::new(RwLock::new(HashMap::new()));
+    let nymd_client = DummyClient::new(validator_address.clone())
+        .with_proposal_db(&proposal_db)
+        .with_spent_credential_db(&spent_credential_db);
+    let mut db_dir = std::env::temp_dir();
+    let params = Parameters::new(5).unwrap();
+    let key_pair = ttp_keygen(&params, 1, 1).unwrap().remove(0);
+    let verification_key = key_pair.verification_key();
+    let voucher_value = 25600u64;
+    let expiry_epoch = 19000u64;
+
+    let issue_ticket = |voucher_info: &str| {
+        let serial_number = params.random_scalar();
+        let binding_number = params.random_scalar();
+        let private_attributes = vec![serial_number, binding_number];
+        let public_attributes = vec![
+            hash_to_scalar(voucher_value.to_string()),
+            hash_to_scalar(voucher_info),
+            hash_to_scalar(expiry_epoch.to_string()),
+        ];
+        let (commitments_openings, blind_sign_request) =
+            prepare_blind_sign(&params, &private_attributes, &public_attributes).unwrap();
+        let signature = blind_sign(
+            &params,
+            &key_pair.secret_key(),
+            &blind_sign_request,
+            &public_attributes,
+        )
+        .unwrap()
+        .unblind(
+            &params,
+            &verification_key,
+            &private_attributes,
+            &public_attributes,
+            &blind_sign_request.get_commitment_hash(),
+            &commitments_openings,
+        )
+        .unwrap();
+        let theta = prove_ticket(
+            &params,
+            &verification_key,
+            &signature,
+            serial_number,
+            binding_number,
+            3,
+            TICKET_INDEX_BITS,
+        )
+        .unwrap();
+        TicketCredential::new(
+            5,
+            theta,
+            voucher_value,
+            voucher_info.to_string(),
+            expiry_epoch,
+        )
+    };
+    let ticket = issue_ticket(DIVISIBLE_VOUCHER_INFO);
+    let indivisible_ticket = issue_ticket(VOUCHER_INFO);
+
+    db_dir.push(&verification_key.to_bs58()[..8]);
+    let storage = NymApiStorage::init(db_dir).await.unwrap();
+    let comm_channel = DummyCommunicationChannel::new(verification_key.clone());
+    let staged_key_pair = crate::coconut::KeyPair::new();
+    staged_key_pair.set(key_pair).await;
+    let rocket = rocket::build().attach(InternalSignRequest::stage(
+        nymd_client.clone(),
+        TEST_COIN_DENOM.to_string(),
+        staged_key_pair,
+        comm_channel,
+        storage,
+        IssuanceConfig::default(),
+    ));
+    let client = Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance");
+
+    // the proposals only ever release the value of a single ticket
+    let ticket_value = voucher_value >> TICKET_INDEX_BITS;
+    let funds = Coin::new(ticket_value as u128, TEST_COIN_DENOM);
+    let msg = coconut_bandwidth_contract_common::msg::ExecuteMsg::ReleaseFunds {
+        funds: funds.clone().into(),
+    };
+    let proposal_for = |id: u64, ticket: &TicketCredential| ProposalResponse {
+        id,
+        title: String::new(),
+        description: ticket.ticket_serial_number(),
+        msgs: vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::new(),
+            msg: to_binary(&msg).unwrap(),
+            funds: vec![],
+        })],
+        status: cw3::Status::Open,
+        expires: cw_utils::Expiration::Never {},
+        threshold: cw_utils::ThresholdResponse::AbsolutePercentage {
+            percentage: Decimal::from_ratio(2u32, 3u32),
+            total_weight: 100,
+        },
+    };
+    for (proposal_id, ticket) in [(1, &ticket), (2, &indivisible_ticket)] {
+        proposal_db
+            .write()
+            .unwrap()
+            .insert(proposal_id, proposal_for(proposal_id, ticket));
+        spent_credential_db.write().unwrap().insert(
+            ticket.ticket_serial_number(),
+            SpendCredentialResponse::new(Some(SpendCredential::new(
+                funds.clone().into(),
+                ticket.ticket_serial_number(),
+                Addr::unchecked("unimportant"),
+                expiry_epoch,
+            ))),
+        );
+    }
+
+    let verify = |req: VerifyTicketBody| {
+        let client = &client;
+        async move {
+            client
+                .post(format!(
+                    "/{}/{}/{}/{}",
+                    API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_VERIFY_BANDWIDTH_TICKET
+                ))
+                .json(&req)
+                .dispatch()
+                .await
+        }
+    };
+
+    // the proposal has to be about that very ticket
+    let response = verify(VerifyTicketBody::new(
+        indivisible_ticket.clone(),
+        1,
+        validator_address.clone(),
+    ))
+    .await;
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        CoconutError::IncorrectProposal {
+            reason: "incorrect ticket serial number in description".to_string()
+        }
+        .to_string()
+    );
+
+    // tickets of credentials that were not issued as divisible are rejected
+    let response = verify(VerifyTicketBody::new(
+        indivisible_ticket,
+        2,
+        validator_address.clone(),
+    ))
+    .await;
+    assert_eq!(response.status(), Status::Ok);
+    let verify_response =
+        serde_json::from_str::<VerifyCredentialResponse>(&response.into_string().await.unwrap())
+            .unwrap();
+    assert!(!verify_response.verification_result);
+    assert_eq!(
+        cw3::Status::Rejected,
+        proposal_db.read().unwrap().get(&2).unwrap().status
+    );
+
+    let response = verify(VerifyTicketBody::new(ticket, 1, validator_address)).await;
+    assert_eq!(response.status(), Status::Ok);
+    let verify_response =
+        serde_json::from_str::<VerifyCredentialResponse>(&response.into_string().await.unwrap())
+            .unwrap();
+    assert!(verify_response.verification_result);
+    assert_eq!(
+        cw3::Status::Passed,
+        proposal_db.read().unwrap().get(&1).unwrap().status
+    );
+}
+
+#[tokio::test]
+async fn verification_of_refund() {
+    let claimant_address = AccountId::from_str(TEST_REWARDING_VALIDATOR_ADDRESS).unwrap();
+    let proposal_db = Arc::new(RwLock::new(HashMap::new()));
+    let spent_credential_db = Arc::new(RwLock::new(HashMap::new()));
+    let nymd_client = DummyClient::new(claimant_address.clone())
+        .with_proposal_db(&proposal_db)
+        .with_spent_credential_db(&spent_credential_db);
+    let mut db_dir = std::env::temp_dir();
+    let params = Parameters::new(5).unwrap();
+    let mut key_pairs = ttp_keygen(&params, 1, 1).unwrap();
+    let voucher_value = 1234u64;
+    let expiry_epoch = 19000u64;
+    let public_attributes = vec![
+        hash_to_scalar(voucher_value.to_string()),
+        hash_to_scalar(VOUCHER_INFO),
+        hash_to_scalar(expiry_epoch.to_string()),
+    ];
+    let theta =
+        theta_from_keys_and_attributes(&params, &key_pairs, &[1], &public_attributes).unwrap();
+    let key_pair = key_pairs.remove(0);
+    db_dir.push(&key_pair.verification_key().to_bs58()[..8]);
+    let storage = NymApiStorage::init(db_dir).await.unwrap();
+    let comm_channel = DummyCommunicationChannel::new(key_pair.verification_key());
+    let staged_key_pair = crate::coconut::KeyPair::new();
+    staged_key_pair.set(key_pair).await;
+    let rocket = rocket::build().attach(InternalSignRequest::stage(
+        nymd_client,
+        TEST_COIN_DENOM.to_string(),
+        staged_key_pair,
+        comm_channel,
+        storage,
+        IssuanceConfig::default(),
+    ));
+    let client = Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance");
+
+    let credential = Credential::new(
+        5,
+        theta,
+        voucher_value,
+        VOUCHER_INFO.to_string(),
+        expiry_epoch,
+    );
+    let proposal_id = 42;
+    let req = VerifyRefundBody::new(credential.clone(), proposal_id, claimant_address.clone());
+    let funds = Coin::new(voucher_value as u128, TEST_COIN_DENOM);
+    let proposal_with =
+        |msg: coconut_bandwidth_contract_common::msg::ExecuteMsg| ProposalResponse {
+            id: proposal_id,
+            title: String::new(),
+            description: credential.blinded_serial_number(),
+            msgs: vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::new(),
+                msg: to_binary(&msg).unwrap(),
+                funds: vec![],
+            })],
+            status: cw3::Status::Open,
+            expires: cw_utils::Expiration::Never {},
+            threshold: cw_utils::ThresholdResponse::AbsolutePercentage {
+                percentage: Decimal::from_ratio(2u32, 3u32),
+                total_weight: 100,
+            },
+        };
+    let refund_to = |recipient: &str| {
+        proposal_with(
+            coconut_bandwidth_contract_common::msg::ExecuteMsg::RefundFunds {
+                funds: funds.clone().into(),
+                recipient: recipient.to_string(),
+            },
+        )
+    };
+    let claimed_by = |address: &str| {
+        SpendCredentialResponse::new(Some(SpendCredential::new(
+            funds.clone().into(),
+            credential.blinded_serial_number(),
+            Addr::unchecked(address),
+            expiry_epoch,
+        )))
+    };
+    let post_request = || {
+        client
+            .post(format!(
+                "/{}/{}/{}/{}",
+                API_VERSION, COCONUT_ROUTES, BANDWIDTH, COCONUT_VERIFY_REFUND
+            ))
+            .json(&req)
+            .dispatch()
+    };
+
+    // Release of the funds to a gateway can't be passed off as a refund
+    proposal_db.write().unwrap().insert(
+        proposal_id,
+        proposal_with(
+            coconut_bandwidth_contract_common::msg::ExecuteMsg::ReleaseFunds {
+                funds: funds.clone().into(),
+            },
+        ),
+    );
+    let response = post_request().await;
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        CoconutError::IncorrectProposal {
+            reason: "action is not to refund funds".to_string()
+        }
+        .to_string()
+    );
+
+    // The funds have to go to the claimant
+    proposal_db
+        .write()
+        .unwrap()
+        .insert(proposal_id, refund_to("someone else"));
+    let response = post_request().await;
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        CoconutError::IncorrectProposal {
+            reason: "funds are not refunded to the claimant".to_string()
+        }
+        .to_string()
+    );
+
+    // The refund has to be claimed in the contract by the claimant as well
+    proposal_db
+        .write()
+        .unwrap()
+        .insert(proposal_id, refund_to(claimant_address.as_ref()));
+    spent_credential_db.write().unwrap().insert(
+        credential.blinded_serial_number(),
+        claimed_by("someone else"),
+    );
+    let response = post_request().await;
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        CoconutError::IncorrectProposal {
+            reason: "refund has been claimed by someone else".to_string()
+        }
+        .to_string()
+    );
+
+    // Test the endpoint with every dependency met
+    spent_credential_db.write().unwrap().insert(
+        credential.blinded_serial_number(),
+        claimed_by(claimant_address.as_ref()),
+    );
+    let response = post_request().await;
+    assert_eq!(response.status(), Status::Ok);
+    let verify_refund_response =
+        serde_json::from_str::<VerifyCredentialResponse>(&response.into_string().await.unwrap())
+            .unwrap();
+    assert!(verify_refund_response.verification_result);
+    assert_eq!(
+        cw3::Status::Passed,
+        proposal_db
+            .read()
+            .unwrap()
+            .get(&proposal_id)
+            .unwrap()
+            .status
+    );
+
+    // A credential spent at a gateway can't be refunded
+    let mut spent_credential = SpendCredential::new(
+        funds.clone().into(),
+        credential.blinded_serial_number(),
+        Addr::unchecked(claimant_address.as_ref()),
+        expiry_epoch,
+    );
+    spent_credential.mark_as_spent();
+    spent_credential_db.write().unwrap().insert(
+        credential.blinded_serial_number(),
+        SpendCredentialResponse::new(Some(spent_credential)),
+    );
+    let response = post_request().await;
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        CoconutError::InvalidCredentialStatus {
+            status: "Spent".to_string()
+        }
+        .to_string()
+    );
+}
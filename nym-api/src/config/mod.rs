@@ -6,6 +6,7 @@ use config::defaults::mainnet::MIXNET_CONTRACT_ADDRESS;
 use config::defaults::DEFAULT_NYM_API_PORT;
 use config::NymConfig;
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 use url::Url;
@@ -16,15 +17,18 @@ pub const DEFAULT_LOCAL_VALIDATOR: &str = "http://localhost:26657";
 
 pub const DEFAULT_DKG_CONTRACT_POLLING_RATE: Duration = Duration::from_secs(10);
 
+const DEFAULT_MAX_ISSUANCE_REQUESTS_PER_IP: u32 = 60;
+const DEFAULT_MAX_ISSUANCE_REQUESTS_PER_DEPOSIT: u32 = 10;
+const DEFAULT_ISSUANCE_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
 const DEFAULT_GATEWAY_SENDING_RATE: usize = 200;
 const DEFAULT_MAX_CONCURRENT_GATEWAY_CLIENTS: usize = 50;
 const DEFAULT_PACKET_DELIVERY_TIMEOUT: Duration = Duration::from_secs(20);
 const DEFAULT_MONITOR_RUN_INTERVAL: Duration = Duration::from_secs(15 * 60);
 const DEFAULT_GATEWAY_PING_INTERVAL: Duration = Duration::from_secs(60);
-// Set this to a high value for now, so that we don't risk sporadic timeouts that might cause
-// bought bandwidth tokens to not have time to be spent; Once we remove the gateway from the
-// bandwidth bridging protocol, we can come back to a smaller timeout value
-const DEFAULT_GATEWAY_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+// gateways no longer redeem the bandwidth credentials on chain while the client is waiting,
+// so there's no need to wait for minutes for the response
+const DEFAULT_GATEWAY_RESPONSE_TIMEOUT: Duration = Duration::from_millis(1_500);
 const DEFAULT_GATEWAY_CONNECTION_TIMEOUT: Duration = Duration::from_millis(2_500);
 
 const DEFAULT_TEST_ROUTES: usize = 3;
@@ -105,6 +109,25 @@ pub struct Base {
 
     /// Mnemonic used for rewarding and/or multisig operations
     mnemonic: String,
+
+    /// Path to file containing private identity key used for signing the published topology.
+    private_identity_key_file: PathBuf,
+
+    /// Path to file containing public identity key used for signing the published topology.
+    public_identity_key_file: PathBuf,
+}
+
+impl Base {
+    pub const PRIVATE_IDENTITY_KEY_FILE: &'static str = "private_identity.pem";
+    pub const PUBLIC_IDENTITY_KEY_FILE: &'static str = "public_identity.pem";
+
+    fn default_private_identity_key_file() -> PathBuf {
+        Config::default_data_directory(None).join(Self::PRIVATE_IDENTITY_KEY_FILE)
+    }
+
+    fn default_public_identity_key_file() -> PathBuf {
+        Config::default_data_directory(None).join(Self::PUBLIC_IDENTITY_KEY_FILE)
+    }
 }
 
 impl Default for Base {
@@ -122,6 +145,8 @@ impl Default for Base {
             announce_address: default_announce_address,
             mixnet_contract_address: MIXNET_CONTRACT_ADDRESS.to_string(),
             mnemonic: "exact antique hybrid width raise anchor puzzle degree fee quit long crack net vague hip despair write put useless civil mechanic broom music day".to_string(),
+            private_identity_key_file: Base::default_private_identity_key_file(),
+            public_identity_key_file: Base::default_public_identity_key_file(),
         }
     }
 }
@@ -300,6 +325,26 @@ pub struct CoconutSigner {
 
     /// Duration of the interval for polling the dkg contract.
     dkg_contract_polling_rate: Duration,
+
+    /// Maximum number of credential issuance requests accepted from a single IP address
+    /// within the rate limiting window. 0 disables the limit.
+    max_issuance_requests_per_ip: u32,
+
+    /// Maximum number of credential issuance requests accepted for a single deposit
+    /// within the rate limiting window. 0 disables the limit.
+    max_issuance_requests_per_deposit: u32,
+
+    /// Duration of the window over which the credential issuance requests are counted.
+    #[serde(with = "humantime_serde")]
+    issuance_rate_limit_window: Duration,
+
+    /// Addresses of the reverse proxies in front of this nym-api. Only the requests coming from them
+    /// are attributed to the address from their `X-Real-IP` header when limiting the issuance requests.
+    issuance_trusted_proxies: Vec<IpAddr>,
+
+    /// Bearer token required by the admin endpoints reporting the credential issuance.
+    /// The endpoints are disabled if it's empty.
+    admin_token: String,
 }
 
 impl CoconutSigner {
@@ -340,6 +385,11 @@ impl Default for CoconutSigner {
             decryption_key_path: CoconutSigner::default_dkg_decryption_key_path(),
             public_key_with_proof_path: CoconutSigner::default_dkg_public_key_with_proof_path(),
             dkg_contract_polling_rate: DEFAULT_DKG_CONTRACT_POLLING_RATE,
+            max_issuance_requests_per_ip: DEFAULT_MAX_ISSUANCE_REQUESTS_PER_IP,
+            max_issuance_requests_per_deposit: DEFAULT_MAX_ISSUANCE_REQUESTS_PER_DEPOSIT,
+            issuance_rate_limit_window: DEFAULT_ISSUANCE_RATE_LIMIT_WINDOW,
+            issuance_trusted_proxies: Vec::new(),
+            admin_token: String::new(),
         }
     }
 }
@@ -351,6 +401,10 @@ impl Config {
 
     pub fn with_id(mut self, id: &str) -> Self {
         self.base.id = id.to_string();
+        self.base.private_identity_key_file =
+            Config::default_data_directory(Some(id)).join(Base::PRIVATE_IDENTITY_KEY_FILE);
+        self.base.public_identity_key_file =
+            Config::default_data_directory(Some(id)).join(Base::PUBLIC_IDENTITY_KEY_FILE);
         self.node_status_api.database_path =
             Config::default_data_directory(Some(id)).join(NodeStatusAPI::DB_FILE);
         self.network_monitor.credentials_database_path =
@@ -517,6 +571,14 @@ impl Config {
         self.topology_cacher.caching_interval
     }
 
+    pub fn get_private_identity_key_file(&self) -> PathBuf {
+        self.base.private_identity_key_file.clone()
+    }
+
+    pub fn get_public_identity_key_file(&self) -> PathBuf {
+        self.base.public_identity_key_file.clone()
+    }
+
     pub fn get_node_status_api_database_path(&self) -> PathBuf {
         self.node_status_api.database_path.clone()
     }
@@ -551,6 +613,35 @@ impl Config {
         self.coconut_signer.dkg_contract_polling_rate
     }
 
+    #[cfg(feature = "coconut")]
+    pub fn get_max_issuance_requests_per_ip(&self) -> u32 {
+        self.coconut_signer.max_issuance_requests_per_ip
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_max_issuance_requests_per_deposit(&self) -> u32 {
+        self.coconut_signer.max_issuance_requests_per_deposit
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_issuance_rate_limit_window(&self) -> Duration {
+        self.coconut_signer.issuance_rate_limit_window
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_issuance_trusted_proxies(&self) -> Vec<IpAddr> {
+        self.coconut_signer.issuance_trusted_proxies.clone()
+    }
+
+    #[cfg(feature = "coconut")]
+    pub fn get_admin_token(&self) -> Option<String> {
+        if self.coconut_signer.admin_token.is_empty() {
+            None
+        } else {
+            Some(self.coconut_signer.admin_token.clone())
+        }
+    }
+
     // TODO: Remove if still unused
     #[allow(dead_code)]
     pub fn get_minimum_interval_monitor_threshold(&self) -> u8 {
@@ -27,6 +27,12 @@ mixnet_contract_address = '{{ base.mixnet_contract_address }}'
 # Mnemonic used for rewarding and validator interaction
 mnemonic = '{{ base.mnemonic }}'
 
+# Path to file containing private identity key used for signing the published topology.
+private_identity_key_file = '{{ base.private_identity_key_file }}'
+
+# Path to file containing public identity key used for signing the published topology.
+public_identity_key_file = '{{ base.public_identity_key_file }}'
+
 ##### network monitor config options #####
 
 [network_monitor]
@@ -110,5 +116,28 @@ decryption_key_path = '{{ coconut_signer.decryption_key_path }}'
 # Path to the dkg dealer public key with proof
 public_key_with_proof_path = '{{ coconut_signer.public_key_with_proof_path }}'
 
+# Maximum number of credential issuance requests accepted from a single IP address
+# within the rate limiting window. 0 disables the limit.
+max_issuance_requests_per_ip = {{ coconut_signer.max_issuance_requests_per_ip }}
+
+# Maximum number of credential issuance requests accepted for a single deposit
+# within the rate limiting window. 0 disables the limit.
+max_issuance_requests_per_deposit = {{ coconut_signer.max_issuance_requests_per_deposit }}
+
+# Duration of the window over which the credential issuance requests are counted.
+issuance_rate_limit_window = '{{ coconut_signer.issuance_rate_limit_window }}'
+
+# Addresses of the reverse proxies in front of this nym-api. Only the requests coming from them
+# are attributed to the address from their `X-Real-IP` header when limiting the issuance requests.
+issuance_trusted_proxies = [
+    {{#each coconut_signer.issuance_trusted_proxies }}
+        '{{this}}',
+    {{/each}}
+]
+
+# Bearer token required by the admin endpoints reporting the credential issuance.
+# The endpoints are disabled if it's empty.
+admin_token = '{{ coconut_signer.admin_token }}'
+
 "#
 }
@@ -9,7 +9,7 @@ use mixnet_contract_common::{
     mixnode::MixNodeDetails, reward_params::RewardingParams, GatewayBond, IdentityKey, Interval,
     MixId, MixNodeBond, RewardedSetNodeStatus,
 };
-use nym_api_requests::models::MixnodeStatus;
+use nym_api_requests::models::{MixnodeStatus, TopologyDiff, TopologyUpdate};
 use okapi::openapi3::OpenApi;
 use rocket::fairing::AdHoc;
 use rocket::Route;
@@ -22,11 +22,13 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use task::TaskClient;
-use tokio::sync::{watch, RwLock};
+use tokio::sync::{broadcast, watch, RwLock};
 use tokio::time;
 use validator_client::nymd::CosmWasmClient;
 
 pub(crate) mod routes;
+pub(crate) mod signed_topology;
+mod topology_stream;
 
 // The cache can emit notifications to listeners about the current state
 #[derive(Debug, PartialEq, Eq)]
@@ -48,6 +50,9 @@ pub struct ValidatorCacheRefresher<C> {
 pub struct ValidatorCache {
     initialised: Arc<AtomicBool>,
     inner: Arc<RwLock<ValidatorCacheInner>>,
+
+    // changes to the network topology pushed to all clients subscribed to the topology stream
+    topology_updates: broadcast::Sender<TopologyDiff>,
 }
 
 struct ValidatorCacheInner {
@@ -243,15 +248,19 @@ pub(crate) fn validator_cache_routes(settings: &OpenApiSettings) -> (Vec<Route>,
         routes::get_blacklisted_mixnodes,
         routes::get_blacklisted_gateways,
         routes::get_interval_reward_params,
-        routes::get_current_epoch
+        routes::get_current_epoch,
+        routes::stream_topology_updates,
+        routes::get_signed_topology
     ]
 }
 
 impl ValidatorCache {
     fn new() -> Self {
+        let (topology_updates, _) = broadcast::channel(topology_stream::TOPOLOGY_UPDATES_BUFFER);
         ValidatorCache {
             initialised: Arc::new(AtomicBool::new(false)),
             inner: Arc::new(RwLock::new(ValidatorCacheInner::new())),
+            topology_updates,
         }
     }
 
@@ -272,6 +281,9 @@ impl ValidatorCache {
         current_interval: Interval,
         mix_to_family: Vec<(IdentityKey, FamilyHead)>,
     ) {
+        let old_active_set = self.active_set().await.into_inner();
+        let old_gateways = self.gateways().await;
+
         match time::timeout(Duration::from_millis(100), self.inner.write()).await {
             Ok(mut cache) => {
                 cache.mixnodes.update(mixnodes);
@@ -284,8 +296,56 @@ impl ValidatorCache {
             }
             Err(e) => {
                 error!("{}", e);
+                return;
             }
         }
+
+        self.publish_topology_diff(old_active_set, old_gateways)
+            .await
+    }
+
+    async fn publish_topology_diff(
+        &self,
+        old_active_set: Vec<MixNodeDetails>,
+        old_gateways: Vec<GatewayBond>,
+    ) {
+        // nobody is listening, so there's no point in computing the diff
+        if self.topology_updates.receiver_count() == 0 {
+            return;
+        }
+
+        let diff = topology_stream::topology_diff(
+            &old_active_set,
+            &self.active_set().await,
+            &old_gateways,
+            &self.gateways().await,
+        );
+        if diff.is_empty() {
+            return;
+        }
+
+        debug!(
+            "publishing topology diff: {} mixnodes added, {} mixnodes removed, {} gateways added, {} gateways removed",
+            diff.added_mixnodes.len(),
+            diff.removed_mixnodes.len(),
+            diff.added_gateways.len(),
+            diff.removed_gateways.len()
+        );
+        // the only possible error is the lack of receivers, which is fine
+        let _ = self.topology_updates.send(diff);
+    }
+
+    pub(crate) fn subscribe_to_topology_updates(&self) -> broadcast::Receiver<TopologyDiff> {
+        self.topology_updates.subscribe()
+    }
+
+    /// Returns the current network topology as served by the `/mixnodes/active`
+    /// and `/gateways` endpoints.
+    pub(crate) async fn topology_snapshot(&self) -> TopologyUpdate {
+        TopologyUpdate::Snapshot {
+            mixnodes: self.active_set().await.into_inner(),
+            gateways: self.gateways().await,
+        }
     }
 
     pub async fn mixnodes_blacklist(&self) -> Option<Cache<HashSet<MixId>>> {
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    contract_cache::ValidatorCache,
+    contract_cache::{signed_topology::TopologySigner, ValidatorCache},
     node_status_api::{
         helpers::{_get_active_set_detailed, _get_mixnodes_detailed, _get_rewarded_set_detailed},
         NodeStatusCache,
@@ -11,9 +11,14 @@ use crate::{
 use mixnet_contract_common::{
     mixnode::MixNodeDetails, reward_params::RewardingParams, GatewayBond, Interval, MixId,
 };
-use nym_api_requests::models::MixNodeBondAnnotated;
+use nym_api_requests::models::{
+    MixNodeBondAnnotated, SignedTopology, TopologyDiff, TopologyUpdate,
+    TOPOLOGY_STREAM_KEEPALIVE_INTERVAL,
+};
 
-use rocket::{serde::json::Json, State};
+use rocket::response::stream::{Event, EventStream};
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::{serde::json::Json, Shutdown, State};
 use rocket_okapi::openapi;
 use std::collections::HashSet;
 
@@ -115,3 +120,50 @@ pub async fn get_interval_reward_params(
 pub async fn get_current_epoch(cache: &State<ValidatorCache>) -> Json<Option<Interval>> {
     Json(cache.current_interval().await.value)
 }
+
+// Server-sent events aren't really expressible in the openapi spec, hence the skip.
+// The stream starts with a snapshot of the current topology followed by the diffs
+// published whenever the cache gets refreshed.
+#[openapi(skip)]
+#[get("/topology/stream")]
+pub async fn stream_topology_updates(
+    cache: &State<ValidatorCache>,
+    mut shutdown: Shutdown,
+) -> EventStream![] {
+    let cache = cache.inner().clone();
+
+    // subscribe before creating the snapshot so that no change could be missed. At worst, some of
+    // them are going to be sent twice, which is harmless as applying a diff is idempotent.
+    let mut updates = cache.subscribe_to_topology_updates();
+    EventStream! {
+        yield Event::json(&cache.topology_snapshot().await);
+        loop {
+            let update = rocket::tokio::select! {
+                update = updates.recv() => match update {
+                    Ok(diff) => TopologyUpdate::Diff(diff),
+                    Err(RecvError::Closed) => break,
+                    // we've missed some diffs, so the subscriber has to start over
+                    Err(RecvError::Lagged(_)) => cache.topology_snapshot().await,
+                },
+                // an empty diff doesn't change anything, but it lets the subscribers know
+                // the stream is still alive
+                _ = rocket::tokio::time::sleep(TOPOLOGY_STREAM_KEEPALIVE_INTERVAL) => {
+                    TopologyUpdate::Diff(TopologyDiff::default())
+                },
+                _ = &mut shutdown => break,
+            };
+            yield Event::json(&update);
+        }
+    }
+}
+
+// The returned document is signed with the identity key of this nym-api, so it can be safely
+// cached and redistributed by third parties.
+#[openapi(tag = "contract-cache")]
+#[get("/topology/signed")]
+pub async fn get_signed_topology(
+    cache: &State<ValidatorCache>,
+    signer: &State<TopologySigner>,
+) -> Option<Json<SignedTopology>> {
+    signer.signed_topology(cache).await.map(Json)
+}
@@ -0,0 +1,172 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::contract_cache::ValidatorCache;
+use crypto::asymmetric::identity;
+use mixnet_contract_common::{
+    mixnode::MixNodeDetails, EpochId, GatewayBond, MixId, RewardedSetNodeStatus,
+};
+use nym_api_requests::models::{RewardedMixnode, SignedTopology, TopologyDocument};
+use rocket::fairing::AdHoc;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Produces topology documents signed with the identity key of this nym-api.
+/// A document is created only once per epoch, so any changes to the bonded gateways
+/// that happened mid-epoch are only reflected in the document of the following epoch.
+#[derive(Clone)]
+pub(crate) struct TopologySigner {
+    identity_keypair: Arc<identity::KeyPair>,
+    latest: Arc<RwLock<Option<(EpochId, SignedTopology)>>>,
+}
+
+impl TopologySigner {
+    pub(crate) fn new(identity_keypair: identity::KeyPair) -> Self {
+        TopologySigner {
+            identity_keypair: Arc::new(identity_keypair),
+            latest: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub(crate) fn stage(identity_keypair: identity::KeyPair) -> AdHoc {
+        AdHoc::on_ignite("Topology Signer Stage", |rocket| async move {
+            rocket.manage(Self::new(identity_keypair))
+        })
+    }
+
+    /// Returns the signed topology document for the current epoch or `None` if the cache
+    /// has not been fully populated yet, so that an empty document wouldn't be served
+    /// (and cached) for the remainder of the epoch.
+    pub(crate) async fn signed_topology(&self, cache: &ValidatorCache) -> Option<SignedTopology> {
+        if !cache.initialised() {
+            return None;
+        }
+
+        let epoch_id = cache
+            .current_interval()
+            .await
+            .into_inner()?
+            .current_epoch_absolute_id();
+
+        if let Some((signed_epoch, signed)) = &*self.latest.read().await {
+            if *signed_epoch == epoch_id {
+                return Some(signed.clone());
+            }
+        }
+
+        let document = topology_document(
+            epoch_id,
+            &cache.rewarded_set().await,
+            &cache.active_set().await,
+            &cache.gateways().await,
+        );
+        let signed = sign_topology_document(&self.identity_keypair, &document);
+        *self.latest.write().await = Some((epoch_id, signed.clone()));
+
+        Some(signed)
+    }
+}
+
+/// Creates the canonical topology document, i.e. one with all the nodes sorted, so that
+/// different nym-apis with identical view of the network produce identical documents.
+pub(crate) fn topology_document(
+    epoch_id: EpochId,
+    rewarded_set: &[MixNodeDetails],
+    active_set: &[MixNodeDetails],
+    gateways: &[GatewayBond],
+) -> TopologyDocument {
+    let active_set: HashSet<MixId> = active_set.iter().map(|details| details.mix_id()).collect();
+
+    let mut mixnodes = rewarded_set
+        .iter()
+        .map(|details| RewardedMixnode {
+            status: if active_set.contains(&details.mix_id()) {
+                RewardedSetNodeStatus::Active
+            } else {
+                RewardedSetNodeStatus::Standby
+            },
+            bond: details.bond_information.clone(),
+        })
+        .collect::<Vec<_>>();
+    mixnodes.sort_by_key(|node| node.bond.mix_id);
+
+    let mut gateways = gateways.to_vec();
+    gateways.sort_by(|a, b| a.identity().cmp(b.identity()));
+
+    TopologyDocument {
+        epoch_id,
+        mixnodes,
+        gateways,
+    }
+}
+
+pub(crate) fn sign_topology_document(
+    identity_keypair: &identity::KeyPair,
+    document: &TopologyDocument,
+) -> SignedTopology {
+    // serializing a struct with no maps inside of it can't possibly fail
+    let document = serde_json::to_string(document).expect("failed to serialize topology document");
+    SignedTopology {
+        signer: identity_keypair.public_key().to_base58_string(),
+        signature: identity_keypair.private_key().sign_text(&document),
+        document,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract_cache::topology_stream::tests::{gateway, mixnode};
+
+    #[test]
+    fn topology_document_is_canonical() {
+        let rewarded_set = vec![mixnode(3, "3.3.3.3"), mixnode(1, "1.1.1.1")];
+        let active_set = vec![mixnode(1, "1.1.1.1")];
+        let gateways = vec![gateway("bbb", "4.4.4.4"), gateway("aaa", "5.5.5.5")];
+
+        let document = topology_document(42, &rewarded_set, &active_set, &gateways);
+        assert_eq!(document.epoch_id, 42);
+        assert_eq!(
+            document
+                .mixnodes
+                .iter()
+                .map(|node| (node.bond.mix_id, node.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, RewardedSetNodeStatus::Active),
+                (3, RewardedSetNodeStatus::Standby)
+            ]
+        );
+        assert_eq!(document.gateways[0].identity(), "aaa");
+
+        let reordered = topology_document(
+            42,
+            &[mixnode(1, "1.1.1.1"), mixnode(3, "3.3.3.3")],
+            &active_set,
+            &[gateway("aaa", "5.5.5.5"), gateway("bbb", "4.4.4.4")],
+        );
+        assert_eq!(document, reordered);
+    }
+
+    #[test]
+    fn signed_document_can_be_verified() {
+        let keypair = identity::KeyPair::new(&mut rand_07::rngs::OsRng);
+        let document = topology_document(
+            1,
+            &[mixnode(1, "1.1.1.1")],
+            &[],
+            &[gateway("aaa", "5.5.5.5")],
+        );
+        let signed = sign_topology_document(&keypair, &document);
+
+        let signer = identity::PublicKey::from_base58_string(&signed.signer).unwrap();
+        let signature = identity::Signature::from_base58_string(&signed.signature).unwrap();
+        assert!(signer
+            .verify(signed.document.as_bytes(), &signature)
+            .is_ok());
+
+        let parsed: TopologyDocument = serde_json::from_str(&signed.document).unwrap();
+        assert_eq!(parsed, document);
+    }
+}
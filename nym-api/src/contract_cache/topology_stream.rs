@@ -0,0 +1,153 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use mixnet_contract_common::{mixnode::MixNodeDetails, GatewayBond, IdentityKey, MixId};
+use nym_api_requests::models::TopologyDiff;
+use std::collections::HashMap;
+
+// the number of diffs that might be buffered for each subscriber before it has to be sent
+// a fresh snapshot instead. With diffs being created every cache refresh, that's plenty.
+pub(crate) const TOPOLOGY_UPDATES_BUFFER: usize = 16;
+
+/// Determines the changes between two versions of the network topology, i.e. the active set
+/// and the gateways.
+pub(crate) fn topology_diff(
+    old_mixnodes: &[MixNodeDetails],
+    new_mixnodes: &[MixNodeDetails],
+    old_gateways: &[GatewayBond],
+    new_gateways: &[GatewayBond],
+) -> TopologyDiff {
+    let old_mixnodes: HashMap<MixId, &MixNodeDetails> = old_mixnodes
+        .iter()
+        .map(|details| (details.mix_id(), details))
+        .collect();
+    let new_mixnodes: HashMap<MixId, &MixNodeDetails> = new_mixnodes
+        .iter()
+        .map(|details| (details.mix_id(), details))
+        .collect();
+    let old_gateways: HashMap<&IdentityKey, &GatewayBond> = old_gateways
+        .iter()
+        .map(|bond| (bond.identity(), bond))
+        .collect();
+    let new_gateways: HashMap<&IdentityKey, &GatewayBond> = new_gateways
+        .iter()
+        .map(|bond| (bond.identity(), bond))
+        .collect();
+
+    // rewarding details of the mixnodes change every epoch, but they are irrelevant for routing,
+    // so a mixnode is only re-sent if its bond information has changed
+    TopologyDiff {
+        added_mixnodes: new_mixnodes
+            .iter()
+            .filter(|(mix_id, details)| {
+                old_mixnodes.get(mix_id).map(|old| &old.bond_information)
+                    != Some(&details.bond_information)
+            })
+            .map(|(_, details)| (*details).clone())
+            .collect(),
+        removed_mixnodes: old_mixnodes
+            .keys()
+            .filter(|mix_id| !new_mixnodes.contains_key(mix_id))
+            .copied()
+            .collect(),
+        added_gateways: new_gateways
+            .iter()
+            .filter(|(identity, bond)| old_gateways.get(*identity) != Some(bond))
+            .map(|(_, bond)| (*bond).clone())
+            .collect(),
+        removed_gateways: old_gateways
+            .keys()
+            .filter(|identity| !new_gateways.contains_key(*identity))
+            .map(|identity| (*identity).clone())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, Addr};
+    use mixnet_contract_common::mixnode::{MixNodeCostParams, MixNodeRewarding};
+    use mixnet_contract_common::{Gateway, Layer, MixNode, MixNodeBond, Percent};
+
+    pub(crate) fn mixnode(mix_id: MixId, host: &str) -> MixNodeDetails {
+        let pledge = coin(100_000_000, "unym");
+        let mix_node = MixNode {
+            host: host.to_string(),
+            mix_port: 1789,
+            verloc_port: 1790,
+            http_api_port: 8000,
+            sphinx_key: format!("sphinx{mix_id}"),
+            identity_key: format!("identity{mix_id}"),
+            version: "1.1.0".to_string(),
+        };
+        let cost_params = MixNodeCostParams {
+            profit_margin_percent: Percent::from_percentage_value(10).unwrap(),
+            interval_operating_cost: coin(40_000_000, "unym"),
+        };
+
+        MixNodeDetails::new(
+            MixNodeBond::new(
+                mix_id,
+                Addr::unchecked("owner"),
+                pledge.clone(),
+                Layer::One,
+                mix_node,
+                None,
+                123,
+            ),
+            MixNodeRewarding::initialise_new(cost_params, &pledge, 1).unwrap(),
+        )
+    }
+
+    pub(crate) fn gateway(identity: &str, host: &str) -> GatewayBond {
+        GatewayBond::new(
+            coin(100_000_000, "unym"),
+            Addr::unchecked("owner"),
+            123,
+            Gateway {
+                host: host.to_string(),
+                mix_port: 1789,
+                clients_port: 9000,
+                location: "Neverland".to_string(),
+                sphinx_key: format!("sphinx{identity}"),
+                identity_key: identity.to_string(),
+                version: "1.1.0".to_string(),
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn identical_topologies_produce_empty_diff() {
+        let mixnodes = vec![mixnode(1, "1.1.1.1"), mixnode(2, "2.2.2.2")];
+        let gateways = vec![gateway("gateway1", "3.3.3.3")];
+
+        assert!(topology_diff(&mixnodes, &mixnodes, &gateways, &gateways).is_empty())
+    }
+
+    #[test]
+    fn changes_are_included_in_diff() {
+        let old_mixnodes = vec![mixnode(1, "1.1.1.1"), mixnode(2, "2.2.2.2")];
+        let old_gateways = vec![
+            gateway("gateway1", "3.3.3.3"),
+            gateway("gateway2", "4.4.4.4"),
+        ];
+
+        let mut rewarded = mixnode(1, "1.1.1.1");
+        rewarded.rewarding_details.last_rewarded_epoch = 2;
+        let new_mixnodes = vec![rewarded, mixnode(3, "5.5.5.5")];
+        let new_gateways = vec![
+            gateway("gateway1", "6.6.6.6"),
+            gateway("gateway2", "4.4.4.4"),
+        ];
+
+        let diff = topology_diff(&old_mixnodes, &new_mixnodes, &old_gateways, &new_gateways);
+
+        // change to the rewarding details alone doesn't affect the topology
+        assert_eq!(diff.added_mixnodes, vec![mixnode(3, "5.5.5.5")]);
+        assert_eq!(diff.removed_mixnodes, vec![2]);
+        assert_eq!(diff.added_gateways, vec![gateway("gateway1", "6.6.6.6")]);
+        assert!(diff.removed_gateways.is_empty());
+    }
+}
@@ -33,6 +33,7 @@ pub(crate) mod error;
 mod helpers;
 
 use crate::epoch_operations::helpers::stake_to_f64;
+use crate::node_status_api::models::Uptime;
 use crate::node_status_api::ONE_DAY;
 use error::RewardingError;
 use task::TaskClient;
@@ -53,6 +54,22 @@ impl From<MixnodeToReward> for ExecuteMsg {
     }
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct GatewayToReward {
+    pub(crate) identity: IdentityKey,
+
+    pub(crate) performance: Performance,
+}
+
+impl From<GatewayToReward> for ExecuteMsg {
+    fn from(gateway_reward: GatewayToReward) -> Self {
+        ExecuteMsg::RewardGateway {
+            gateway_identity: gateway_reward.identity,
+            performance: gateway_reward.performance,
+        }
+    }
+}
+
 pub struct RewardedSetUpdater {
     nymd_client: Client<SigningNymdClient>,
     validator_cache: ValidatorCache,
@@ -167,19 +184,24 @@ impl RewardedSetUpdater {
         current_interval: Interval,
     ) -> Result<(), RewardingError> {
         let to_reward = self.nodes_to_reward(current_interval).await;
+        let gateways_to_reward = self.gateways_to_reward(current_interval).await;
 
         if let Some(existing_report) = self
             .storage
             .get_rewarding_report(current_interval.current_epoch_absolute_id())
             .await?
         {
-            warn!("We have already rewarded mixnodes for this rewarding epoch ({}). {} nodes should have gotten rewards", existing_report.absolute_epoch_id, existing_report.eligible_mixnodes);
+            warn!("We have already rewarded mixnodes for this rewarding epoch ({}). {} nodes and {} gateways should have gotten rewards", existing_report.absolute_epoch_id, existing_report.eligible_mixnodes, existing_report.eligible_gateways);
             return Ok(());
         }
 
-        if to_reward.is_empty() {
+        if to_reward.is_empty() && gateways_to_reward.is_empty() {
             info!("There are no nodes to reward in this epoch");
-        } else if let Err(err) = self.nymd_client.send_rewarding_messages(&to_reward).await {
+        } else if let Err(err) = self
+            .nymd_client
+            .send_rewarding_messages(&to_reward, &gateways_to_reward)
+            .await
+        {
             error!(
                 "failed to perform mixnode rewarding for epoch {}! Error encountered: {}",
                 current_interval.current_epoch_absolute_id(),
@@ -188,11 +210,16 @@ impl RewardedSetUpdater {
             return Err(err.into());
         }
 
-        log::info!("rewarded {} mixnodes...", to_reward.len());
+        log::info!(
+            "rewarded {} mixnodes and {} gateways...",
+            to_reward.len(),
+            gateways_to_reward.len()
+        );
 
         let rewarding_report = RewardingReport {
             absolute_epoch_id: current_interval.current_epoch_absolute_id(),
             eligible_mixnodes: to_reward.len() as u32,
+            eligible_gateways: gateways_to_reward.len() as u32,
         };
 
         self.storage
@@ -238,6 +265,57 @@ impl RewardedSetUpdater {
         eligible_nodes
     }
 
+    // failing to determine the gateway rewards must never prevent the mixnodes from getting theirs,
+    // so in that case the gateways are just not rewarded in this epoch
+    async fn gateways_to_reward(&self, interval: Interval) -> Vec<GatewayToReward> {
+        // don't bother sending any transactions if gateways wouldn't have received anything anyway
+        match self.nymd_client.get_current_rewarding_parameters().await {
+            Ok(rewarding_parameters) if !rewarding_parameters.gateway.rewarding_enabled() => {
+                return Vec::new();
+            }
+            Ok(_) => (),
+            Err(err) => {
+                warn!("failed to obtain the current rewarding parameters - {err}. Gateways are not going to be rewarded in this epoch");
+                return Vec::new();
+            }
+        }
+
+        let reliabilities = match self
+            .storage
+            .get_all_avg_gateway_reliability_in_last_24hr(
+                interval.current_epoch_end_unix_timestamp(),
+            )
+            .await
+        {
+            Ok(reliabilities) => reliabilities
+                .into_iter()
+                .map(|reliability| (reliability.identity().to_owned(), reliability.value()))
+                .collect::<HashMap<_, _>>(),
+            Err(err) => {
+                warn!("failed to obtain the gateway reliabilities - {err}. Gateways are not going to be rewarded in this epoch");
+                return Vec::new();
+            }
+        };
+
+        // unlike mixnodes, all bonded gateways are eligible for rewards,
+        // including the blacklisted ones (their performance is going to be low anyway)
+        self.validator_cache
+            .gateways_all()
+            .await
+            .into_iter()
+            .map(|bond| {
+                let reliability = reliabilities
+                    .get(bond.identity())
+                    .copied()
+                    .unwrap_or_default();
+                GatewayToReward {
+                    identity: bond.identity().to_owned(),
+                    performance: Uptime::new(reliability).into(),
+                }
+            })
+            .collect()
+    }
+
     async fn update_rewarded_set_and_advance_epoch(
         &self,
         all_mixnodes: &[MixNodeDetails],
@@ -5,6 +5,7 @@
 extern crate rocket;
 
 use crate::config::Config;
+use crate::contract_cache::signed_topology::TopologySigner;
 use crate::contract_cache::ValidatorCacheRefresher;
 use crate::network_monitor::NetworkMonitorBuilder;
 use crate::node_status_api::uptime_updater::HistoricalUptimeUpdater;
@@ -17,6 +18,7 @@ use ::config::NymConfig;
 use anyhow::Result;
 use clap::{crate_version, App, Arg, ArgMatches};
 use contract_cache::ValidatorCache;
+use crypto::asymmetric::identity;
 use log::{info, warn};
 use node_status_api::NodeStatusCache;
 use okapi::openapi3::OpenApi;
@@ -42,6 +44,7 @@ use crate::epoch_operations::RewardedSetUpdater;
 use coconut::{
     comm::QueryCommunicationChannel,
     dkg::controller::{init_keypair, DkgController},
+    issuance::IssuanceConfig,
     InternalSignRequest,
 };
 use logging::setup_logging;
@@ -392,6 +395,23 @@ fn setup_network_monitor<'a>(
     ))
 }
 
+fn load_or_generate_identity_keypair(config: &Config) -> Result<identity::KeyPair> {
+    let private_key_file = config.get_private_identity_key_file();
+    let public_key_file = config.get_public_identity_key_file();
+    // nym-apis initialised before the introduction of signed topology don't have any identity keys
+    let already_exist = private_key_file.exists() && public_key_file.exists();
+    let paths = pemstore::KeyPairPath::new(private_key_file, public_key_file);
+
+    if already_exist {
+        return Ok(pemstore::load_keypair(&paths)?);
+    }
+
+    info!("Generating new identity keys of this nym-api");
+    let keypair = identity::KeyPair::new(&mut rand_07::rngs::OsRng);
+    pemstore::store_keypair(&keypair, &paths)?;
+    Ok(keypair)
+}
+
 // TODO: Remove if still unused
 #[allow(dead_code)]
 fn expected_monitor_test_runs(config: &Config, interval_length: Duration) -> usize {
@@ -407,6 +427,7 @@ async fn setup_rocket(
     _mix_denom: String,
     liftoff_notify: Arc<Notify>,
     _nymd_client: Client<SigningNymdClient>,
+    identity_keypair: identity::KeyPair,
     #[cfg(feature = "coconut")] coconut_keypair: coconut::keypair::KeyPair,
 ) -> Result<Rocket<Ignite>> {
     let openapi_settings = rocket_okapi::settings::OpenApiSettings::default();
@@ -428,6 +449,7 @@ async fn setup_rocket(
         .attach(setup_cors()?)
         .attach(setup_liftoff_notify(liftoff_notify))
         .attach(ValidatorCache::stage())
+        .attach(TopologySigner::stage(identity_keypair))
         .attach(NodeStatusCache::stage());
 
     // This is not a very nice approach. A lazy value would be more suitable, but that's still
@@ -446,6 +468,7 @@ async fn setup_rocket(
             coconut_keypair,
             QueryCommunicationChannel::new(_nymd_client),
             storage.clone().unwrap(),
+            IssuanceConfig::from(config),
         ))
     } else {
         rocket
@@ -517,6 +540,12 @@ async fn run_nym_api(matches: ArgMatches) -> Result<()> {
         init_keypair(&config)?;
     }
 
+    let identity_keypair = load_or_generate_identity_keypair(&config)?;
+    info!(
+        "Topology documents are going to be signed with identity key {}",
+        identity_keypair.public_key().to_base58_string()
+    );
+
     // if we just wanted to write data to the config, exit
     if matches.is_present(WRITE_CONFIG_ARG) {
         return Ok(());
@@ -538,6 +567,7 @@ async fn run_nym_api(matches: ArgMatches) -> Result<()> {
         mix_denom,
         Arc::clone(&liftoff_notify),
         signing_nymd_client.clone(),
+        identity_keypair,
         #[cfg(feature = "coconut")]
         coconut_keypair.clone(),
     )
@@ -1,8 +1,9 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use nymsphinx::forwarding::packet::MixPacket;
 use nymsphinx::message::NymMessage;
+use nymsphinx::params::PacketSize;
+use nymsphinx::preparer::PreparedFragment;
 use nymsphinx::{
     acknowledgements::AckKey, addressing::clients::Recipient, preparer::MessagePreparer,
 };
@@ -37,7 +38,7 @@ impl Chunker {
         message: Vec<u8>,
         topology: &NymTopology,
         packet_sender: Recipient,
-    ) -> Vec<MixPacket> {
+    ) -> Vec<PreparedFragment> {
         // I really dislike how we have to overwrite the parameter of the `MessagePreparer` on each run
         // but without some significant API changes in the `MessagePreparer` this was the easiest
         // way to being able to have variable sender address.
@@ -50,23 +51,31 @@ impl Chunker {
         message: Vec<u8>,
         topology: &NymTopology,
         packet_sender: Recipient,
-    ) -> Vec<MixPacket> {
+    ) -> Vec<PreparedFragment> {
         let ack_key: AckKey = AckKey::new(&mut self.rng);
 
-        let split_message = self
-            .message_preparer
-            .pad_and_split_message(NymMessage::new_plain(message));
+        let split_message = self.message_preparer.pad_and_split_message(
+            NymMessage::new_plain(message),
+            PacketSize::default(),
+            None,
+        );
 
-        let mut mix_packets = Vec::with_capacity(split_message.len());
+        let mut prepared_fragments = Vec::with_capacity(split_message.len());
         for message_chunk in split_message {
             // don't bother with acks etc. for time being
             let prepared_fragment = self
                 .message_preparer
-                .prepare_chunk_for_sending(message_chunk, topology, &ack_key, &packet_sender)
+                .prepare_chunk_for_sending(
+                    message_chunk,
+                    topology,
+                    &ack_key,
+                    &packet_sender,
+                    PacketSize::default(),
+                )
                 .unwrap();
 
-            mix_packets.push(prepared_fragment.mix_packet);
+            prepared_fragments.push(prepared_fragment);
         }
-        mix_packets
+        prepared_fragments
     }
 }
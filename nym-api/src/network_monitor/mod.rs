@@ -11,6 +11,7 @@ use validator_client::nymd::SigningNymdClient;
 
 use crate::config::Config;
 use crate::contract_cache::ValidatorCache;
+use crate::network_monitor::monitor::latency::PacketTimings;
 use crate::network_monitor::monitor::preparer::PacketPreparer;
 use crate::network_monitor::monitor::processor::{
     ReceivedProcessor, ReceivedProcessorReceiver, ReceivedProcessorSender,
@@ -66,6 +67,8 @@ impl<'a> NetworkMonitorBuilder<'a> {
         let identity_keypair = Arc::new(identity::KeyPair::new(&mut rng));
         let encryption_keypair = Arc::new(encryption::KeyPair::new(&mut rng));
 
+        let packet_timings = PacketTimings::new();
+
         let (gateway_status_update_sender, gateway_status_update_receiver) = mpsc::unbounded();
         let (received_processor_sender_channel, received_processor_receiver_channel) =
             mpsc::unbounded();
@@ -105,11 +108,13 @@ impl<'a> NetworkMonitorBuilder<'a> {
             self.config.get_gateway_sending_rate(),
             bandwidth_controller,
             self.config.get_disabled_credentials_mode(),
+            packet_timings.clone(),
         );
 
         let received_processor = new_received_processor(
             received_processor_receiver_channel,
             Arc::clone(&encryption_keypair),
+            packet_timings.clone(),
         );
         let summary_producer = new_summary_producer(self.config.get_per_node_test_packets());
         let packet_receiver = new_packet_receiver(
@@ -124,6 +129,7 @@ impl<'a> NetworkMonitorBuilder<'a> {
             received_processor,
             summary_producer,
             self.node_status_storage,
+            packet_timings,
         );
 
         NetworkMonitorRunnables {
@@ -175,6 +181,7 @@ fn new_packet_sender(
     max_sending_rate: usize,
     bandwidth_controller: BandwidthController<PersistentStorage>,
     disabled_credentials_mode: bool,
+    packet_timings: PacketTimings,
 ) -> PacketSender {
     PacketSender::new(
         gateways_status_updater,
@@ -185,14 +192,16 @@ fn new_packet_sender(
         max_sending_rate,
         bandwidth_controller,
         disabled_credentials_mode,
+        packet_timings,
     )
 }
 
 fn new_received_processor(
     packets_receiver: ReceivedProcessorReceiver,
     client_encryption_keypair: Arc<encryption::KeyPair>,
+    packet_timings: PacketTimings,
 ) -> ReceivedProcessor {
-    ReceivedProcessor::new(packets_receiver, client_encryption_keypair)
+    ReceivedProcessor::new(packets_receiver, client_encryption_keypair, packet_timings)
 }
 
 fn new_summary_producer(per_node_test_packets: usize) -> SummaryProducer {
@@ -0,0 +1,238 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::network_monitor::test_packet::{NodeType, TestPacket};
+use mixnet_contract_common::MixId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// number of nodes on each test route whose latency contributes to the round-trip time,
+// i.e. three mixnodes and the gateway
+const ROUTE_NODES: f64 = 4.0;
+
+/// Latency attributed to a single node during a network monitor test run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LatencyMeasurement {
+    /// Estimated latency, in milliseconds, introduced by the node.
+    pub(crate) latency_ms: f32,
+
+    /// Standard deviation, in milliseconds, of the latency introduced by the node.
+    pub(crate) jitter_ms: f32,
+}
+
+#[derive(Default)]
+struct PacketTimingsInner {
+    /// Instants at which the test packets would have been received if the network introduced
+    /// no latency, i.e. the sending time plus the delays deliberately put inside the packets.
+    expected_arrivals: HashMap<u64, Instant>,
+
+    /// Round-trip times of the received test packets, excluding the deliberate mix delays.
+    round_trip_times: HashMap<u64, Duration>,
+}
+
+/// Keeps track of sending and receiving times of the sequenced test packets. It is shared between
+/// the packet sender and the received packets processor.
+#[derive(Clone, Default)]
+pub(crate) struct PacketTimings {
+    inner: Arc<Mutex<PacketTimingsInner>>,
+}
+
+impl PacketTimings {
+    pub(crate) fn new() -> Self {
+        Default::default()
+    }
+
+    /// Records the sending time of the packets given as pairs of their sequence numbers
+    /// and the total delays they are going to experience on the mixnodes.
+    pub(crate) fn record_sent(
+        &self,
+        packets: impl IntoIterator<Item = (u64, Duration)>,
+        sent_at: Instant,
+    ) {
+        let mut inner = self.inner.lock().expect("packet timings lock got poisoned");
+        for (sequence, mix_delay) in packets {
+            // packets that are not sequenced are not used for latency measurements
+            if sequence != 0 {
+                inner
+                    .expected_arrivals
+                    .insert(sequence, sent_at + mix_delay);
+            }
+        }
+    }
+
+    pub(crate) fn record_received(&self, sequence: u64, received_at: Instant) {
+        let mut inner = self.inner.lock().expect("packet timings lock got poisoned");
+        if let Some(expected_arrival) = inner.expected_arrivals.get(&sequence).copied() {
+            inner.round_trip_times.insert(
+                sequence,
+                received_at.saturating_duration_since(expected_arrival),
+            );
+        }
+    }
+
+    /// Returns round-trip times of all received test packets and clears the timings
+    /// so that they would not leak into the following test run.
+    pub(crate) fn take_round_trip_times(&self) -> HashMap<u64, Duration> {
+        let mut inner = self.inner.lock().expect("packet timings lock got poisoned");
+        inner.expected_arrivals.clear();
+        std::mem::take(&mut inner.round_trip_times)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct NodeLatencies {
+    pub(crate) mixnodes: HashMap<MixId, LatencyMeasurement>,
+    pub(crate) gateways: HashMap<String, LatencyMeasurement>,
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Attributes the measured round-trip times to the individual tested nodes.
+///
+/// Each test packet goes through a fixed test route with a single node substituted for the one
+/// being tested. The baseline of each route is the median round-trip time of all packets sent
+/// through it, which is evenly split between the nodes on the route. Whatever the packets of
+/// the tested node took on top of (or below) that baseline is attributed to the tested node itself.
+pub(crate) fn estimate_node_latencies(
+    received_packets: &[TestPacket],
+    round_trip_times: &HashMap<u64, Duration>,
+) -> NodeLatencies {
+    let samples = received_packets
+        .iter()
+        .filter_map(|packet| {
+            round_trip_times
+                .get(&packet.sequence)
+                .map(|rtt| (packet, rtt.as_secs_f64() * 1000.0))
+        })
+        .collect::<Vec<_>>();
+
+    let mut route_samples: HashMap<u64, Vec<f64>> = HashMap::new();
+    for (packet, rtt) in &samples {
+        route_samples.entry(packet.route_id).or_default().push(*rtt);
+    }
+    let route_baselines = route_samples
+        .into_iter()
+        .map(|(route_id, mut rtts)| (route_id, median(&mut rtts)))
+        .collect::<HashMap<_, _>>();
+
+    let mut mixnode_samples: HashMap<MixId, Vec<(f64, f64)>> = HashMap::new();
+    let mut gateway_samples: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+    for (packet, rtt) in samples {
+        // we've just computed baselines of all the routes
+        let baseline = route_baselines[&packet.route_id];
+        let sample = (baseline / ROUTE_NODES, rtt - baseline);
+        match packet.node_type {
+            NodeType::Mixnode(mix_id) => mixnode_samples.entry(mix_id).or_default().push(sample),
+            NodeType::Gateway => gateway_samples
+                .entry(packet.pub_key.to_base58_string())
+                .or_default()
+                .push(sample),
+        }
+    }
+
+    NodeLatencies {
+        mixnodes: mixnode_samples
+            .into_iter()
+            .map(|(mix_id, samples)| (mix_id, measurement_from_samples(&samples)))
+            .collect(),
+        gateways: gateway_samples
+            .into_iter()
+            .map(|(identity, samples)| (identity, measurement_from_samples(&samples)))
+            .collect(),
+    }
+}
+
+// each sample consists of the share of the route baseline and the excess latency of the packet
+fn measurement_from_samples(samples: &[(f64, f64)]) -> LatencyMeasurement {
+    let count = samples.len() as f64;
+    let share = samples.iter().map(|(share, _)| share).sum::<f64>() / count;
+    let mean_excess = samples.iter().map(|(_, excess)| excess).sum::<f64>() / count;
+    let variance = samples
+        .iter()
+        .map(|(_, excess)| (excess - mean_excess).powi(2))
+        .sum::<f64>()
+        / count;
+
+    LatencyMeasurement {
+        latency_ms: (share + mean_excess).max(0.0) as f32,
+        jitter_ms: variance.sqrt() as f32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::asymmetric::identity;
+
+    fn received(
+        key: &identity::PublicKey,
+        node_type: NodeType,
+        route_id: u64,
+        sequence: u64,
+    ) -> TestPacket {
+        TestPacket::new(*key, "owner".to_string(), route_id, 1, node_type).with_sequence(sequence)
+    }
+
+    #[test]
+    fn slow_nodes_get_higher_latency_attributed() {
+        let key = *identity::KeyPair::new(&mut rand_07::thread_rng()).public_key();
+        let mut packets = Vec::new();
+        let mut round_trip_times = HashMap::new();
+
+        let mut sequence = 0;
+        for route_id in [1, 2] {
+            // route 2 is overall slower than route 1
+            let route_rtt = if route_id == 1 { 400 } else { 800 };
+            for (node, extra) in [(1, 0), (2, 0), (3, 0), (4, 100)] {
+                for jitter in [0, 20] {
+                    sequence += 1;
+                    packets.push(received(&key, NodeType::Mixnode(node), route_id, sequence));
+                    round_trip_times
+                        .insert(sequence, Duration::from_millis(route_rtt + extra + jitter));
+                }
+            }
+        }
+        // packet we have never sent (or its timing got lost) is not taken into consideration
+        packets.push(received(&key, NodeType::Gateway, 1, sequence + 1));
+
+        let latencies = estimate_node_latencies(&packets, &round_trip_times);
+        assert!(latencies.gateways.is_empty());
+        assert_eq!(latencies.mixnodes.len(), 4);
+
+        let regular = latencies.mixnodes[&1];
+        let slow = latencies.mixnodes[&4];
+        assert!((slow.latency_ms - regular.latency_ms - 100.0).abs() < 0.001);
+        assert!((regular.jitter_ms - 10.0).abs() < 0.001);
+        // baselines of 420ms and 820ms evenly split between 4 nodes
+        // with the node being on average 10ms faster than the median
+        assert!((regular.latency_ms - 145.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn deliberate_mix_delays_are_excluded_from_round_trip_times() {
+        let timings = PacketTimings::new();
+        let sent_at = Instant::now();
+        let packets = [
+            (1, Duration::from_millis(300)),
+            // route testing packets are not sequenced
+            (0, Duration::from_millis(300)),
+        ];
+        timings.record_sent(packets, sent_at);
+        timings.record_received(1, sent_at + Duration::from_millis(350));
+        timings.record_received(0, sent_at + Duration::from_millis(350));
+
+        let round_trip_times = timings.take_round_trip_times();
+        assert_eq!(round_trip_times.len(), 1);
+        assert_eq!(round_trip_times[&1], Duration::from_millis(50));
+        assert!(timings.take_round_trip_times().is_empty());
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Config;
+use crate::network_monitor::monitor::latency::PacketTimings;
 use crate::network_monitor::monitor::preparer::PacketPreparer;
 use crate::network_monitor::monitor::processor::ReceivedProcessor;
 use crate::network_monitor::monitor::sender::PacketSender;
@@ -17,6 +18,7 @@ use tokio::time::{sleep, Duration, Instant};
 
 pub(crate) mod gateway_clients_cache;
 pub(crate) mod gateways_pinger;
+pub(crate) mod latency;
 pub(crate) mod preparer;
 pub(crate) mod processor;
 pub(crate) mod receiver;
@@ -30,6 +32,7 @@ pub(super) struct Monitor {
     received_processor: ReceivedProcessor,
     summary_producer: SummaryProducer,
     node_status_storage: NymApiStorage,
+    packet_timings: PacketTimings,
     run_interval: Duration,
     gateway_ping_interval: Duration,
     packet_delivery_timeout: Duration,
@@ -53,6 +56,7 @@ impl Monitor {
         received_processor: ReceivedProcessor,
         summary_producer: SummaryProducer,
         node_status_storage: NymApiStorage,
+        packet_timings: PacketTimings,
     ) -> Self {
         Monitor {
             test_nonce: 1,
@@ -61,6 +65,7 @@ impl Monitor {
             received_processor,
             summary_producer,
             node_status_storage,
+            packet_timings,
             run_interval: config.get_network_monitor_run_interval(),
             gateway_ping_interval: config.get_gateway_ping_interval(),
             packet_delivery_timeout: config.get_packet_delivery_timeout(),
@@ -250,9 +255,19 @@ impl Monitor {
             .await;
 
         info!("Sending packets to all gateways...");
-        self.packet_sender
+        let unreachable_gateways = self
+            .packet_sender
             .send_packets(prepared_packets.packets)
-            .await;
+            .await
+            .into_iter()
+            .map(|gateway| gateway.to_base58_string())
+            .collect::<HashSet<_>>();
+        if !unreachable_gateways.is_empty() {
+            info!(
+                "Failed to connect to {} gateways, their egress will not be measured",
+                unreachable_gateways.len()
+            );
+        }
 
         info!(
             "Sending is over, waiting for {:?} before checking what we received",
@@ -263,6 +278,7 @@ impl Monitor {
         sleep(self.packet_delivery_timeout).await;
 
         let received = self.received_processor.return_received().await;
+        let round_trip_times = self.packet_timings.take_round_trip_times();
         let total_received = received.len();
         info!("Test routes: {:?}", routes);
         info!("Received {}/{} packets", total_received, total_sent);
@@ -271,8 +287,10 @@ impl Monitor {
             prepared_packets.tested_mixnodes,
             prepared_packets.tested_gateways,
             received,
+            &round_trip_times,
             prepared_packets.invalid_mixnodes,
             prepared_packets.invalid_gateways,
+            &unreachable_gateways,
             routes,
         );
 
@@ -3,14 +3,13 @@
 
 use crate::contract_cache::ValidatorCache;
 use crate::network_monitor::chunker::Chunker;
-use crate::network_monitor::monitor::sender::GatewayPackets;
-use crate::network_monitor::test_packet::{NodeType, TestPacket};
+use crate::network_monitor::monitor::sender::{GatewayPackets, TimedMixPacket};
+use crate::network_monitor::test_packet::{GatewayPath, NodeType, TestPacket};
 use crate::network_monitor::test_route::TestRoute;
 use crypto::asymmetric::{encryption, identity};
 use log::info;
 use mixnet_contract_common::{Addr, GatewayBond, Layer, MixId, MixNodeBond};
 use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::forwarding::packet::MixPacket;
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng};
 use std::collections::{HashMap, HashSet};
@@ -170,24 +169,29 @@ impl PacketPreparer {
         packet: &TestPacket,
         topology: &NymTopology,
         packet_recipient: Recipient,
-    ) -> MixPacket {
+    ) -> TimedMixPacket {
         // this should be done only once. We can't really do it at construction time
         // as there's no sane Default for Recipient
         if self.chunker.is_none() {
             self.chunker = Some(Chunker::new(packet_recipient));
         }
-        let mut mix_packets = self.chunker.as_mut().unwrap().prepare_packets_from(
+        let mut prepared_fragments = self.chunker.as_mut().unwrap().prepare_packets_from(
             packet.to_bytes(),
             topology,
             packet_recipient,
         );
         assert_eq!(
-            mix_packets.len(),
+            prepared_fragments.len(),
             1,
             "Our test packets data is longer than a single sphinx packet!"
         );
 
-        mix_packets.pop().unwrap()
+        let prepared_fragment = prepared_fragments.pop().unwrap();
+        TimedMixPacket::new(
+            packet.sequence,
+            prepared_fragment.forward_delay.to_duration(),
+            prepared_fragment.mix_packet,
+        )
     }
 
     pub(crate) async fn wait_for_validator_cache_initial_values(&self, minimum_full_routes: usize) {
@@ -442,12 +446,17 @@ impl PacketPreparer {
         let tested_mixnodes = mixnodes.iter().map(|node| node.into()).collect::<Vec<_>>();
         let tested_gateways = gateways.iter().map(|node| node.into()).collect::<Vec<_>>();
 
+        // each gateway is tested twice, once for each of its sides
         let packets_to_create = (test_routes.len() * self.per_node_test_packets)
-            * (tested_mixnodes.len() + tested_gateways.len());
+            * (tested_mixnodes.len() + 2 * tested_gateways.len());
         info!("Need to create {} mix packets", packets_to_create);
 
         let mut all_gateway_packets = HashMap::new();
 
+        // sequence numbers of the packets used for latency measurements. 0 is reserved for
+        // packets that are not measured, like the ones used for testing the route viability
+        let mut sequence = 0;
+
         // for each test route...
         for test_route in test_routes {
             let recipient = self.create_packet_sender(test_route.gateway());
@@ -464,6 +473,8 @@ impl PacketPreparer {
                 let topology = test_route.substitute_mix(mixnode);
                 // produce n mix packets
                 for _ in 0..self.per_node_test_packets {
+                    sequence += 1;
+                    let test_packet = test_packet.clone().with_sequence(sequence);
                     let mix_packet = self.wrap_test_packet(&test_packet, &topology, recipient);
                     mix_packets.push(mix_packet);
                 }
@@ -478,27 +489,61 @@ impl PacketPreparer {
 
             // and for each gateway...
             for gateway in &gateways {
-                let mut gateway_mix_packets = Vec::new();
-                let test_packet = TestPacket::from_gateway(gateway, test_route.id(), test_nonce);
-                let gateway_identity = gateway.identity_key;
-                let gateway_address = gateway.clients_address();
-                let gateway_owner = gateway.owner.clone();
-                let recipient = self.create_packet_sender(gateway);
-                let topology = test_route.substitute_gateway(gateway);
+                // test the client-facing side of the gateway: the packets enter the mixnet through
+                // the tested gateway and leave it through the core gateway of the route
+                let mut ingress_mix_packets = Vec::new();
+                let test_packet = TestPacket::from_gateway(
+                    gateway,
+                    test_route.id(),
+                    test_nonce,
+                    GatewayPath::Ingress,
+                );
                 // produce n mix packets
                 for _ in 0..self.per_node_test_packets {
-                    let mix_packet = self.wrap_test_packet(&test_packet, &topology, recipient);
-                    gateway_mix_packets.push(mix_packet);
+                    sequence += 1;
+                    let test_packet = test_packet.clone().with_sequence(sequence);
+                    let mix_packet =
+                        self.wrap_test_packet(&test_packet, test_route.topology(), recipient);
+                    ingress_mix_packets.push(mix_packet);
                 }
 
                 // and push it into existing struct (if it's a "core" gateway being tested against another route)
                 // or create a new one
+                let gateway_identity = gateway.identity_key;
+                let gateway_address = gateway.clients_address();
+                let gateway_owner = gateway.owner.clone();
                 let gateway_packets = all_gateway_packets
                     .entry(gateway_identity.to_bytes())
                     .or_insert_with(|| {
                         GatewayPackets::empty(gateway_address, gateway_identity, gateway_owner)
                     });
-                gateway_packets.push_packets(gateway_mix_packets);
+                gateway_packets.push_packets(ingress_mix_packets);
+
+                // test the mix-facing side of the gateway: the packets enter the mixnet through
+                // the core gateway of the route and are delivered to us by the tested gateway
+                let mut egress_mix_packets = Vec::new();
+                let test_packet = TestPacket::from_gateway(
+                    gateway,
+                    test_route.id(),
+                    test_nonce,
+                    GatewayPath::Egress,
+                );
+                let egress_recipient = self.create_packet_sender(gateway);
+                let topology = test_route.substitute_gateway(gateway);
+                // produce n mix packets
+                for _ in 0..self.per_node_test_packets {
+                    sequence += 1;
+                    let test_packet = test_packet.clone().with_sequence(sequence);
+                    let mix_packet =
+                        self.wrap_test_packet(&test_packet, &topology, egress_recipient);
+                    egress_mix_packets.push(mix_packet);
+                }
+
+                // the core gateway entry must already exist as we've just sent the mixnode packets through it
+                all_gateway_packets
+                    .get_mut(&test_route.gateway_identity().to_bytes())
+                    .expect("the core gateway packets have not been created")
+                    .push_packets(egress_mix_packets);
             }
         }
 
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::network_monitor::gateways_reader::GatewayMessages;
+use crate::network_monitor::monitor::latency::PacketTimings;
 use crate::network_monitor::test_packet::{TestPacket, TestPacketError};
 use crate::network_monitor::ROUTE_TESTING_TEST_NONCE;
 use crypto::asymmetric::encryption;
@@ -12,6 +13,7 @@ use log::warn;
 use nymsphinx::receiver::{MessageReceiver, MessageRecoveryError};
 use std::mem;
 use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
 
 pub(crate) type ReceivedProcessorSender = mpsc::UnboundedSender<GatewayMessages>;
@@ -59,10 +61,15 @@ struct ReceivedProcessorInner {
 
     /// Vector containing all received (and decrypted) packets in the current test run.
     received_packets: Vec<TestPacket>,
+
+    /// Timings of the test packets used for determining their round-trip times.
+    packet_timings: PacketTimings,
 }
 
 impl ReceivedProcessorInner {
     fn on_message(&mut self, mut message: Vec<u8>) -> Result<(), ProcessingError> {
+        let received_at = Instant::now();
+
         // if the nonce is none it means the packet was received during the 'waiting' for the
         // next test run
         if self.test_nonce.is_none() {
@@ -90,6 +97,8 @@ impl ReceivedProcessorInner {
             });
         }
 
+        self.packet_timings
+            .record_received(test_packet.sequence, received_at);
         self.received_packets.push(test_packet);
 
         Ok(())
@@ -110,6 +119,7 @@ impl ReceivedProcessor {
     pub(crate) fn new(
         packets_receiver: ReceivedProcessorReceiver,
         client_encryption_keypair: Arc<encryption::KeyPair>,
+        packet_timings: PacketTimings,
     ) -> Self {
         let inner: Arc<Mutex<ReceivedProcessorInner>> =
             Arc::new(Mutex::new(ReceivedProcessorInner {
@@ -118,6 +128,7 @@ impl ReceivedProcessor {
                 client_encryption_keypair,
                 message_receiver: MessageReceiver::new(),
                 received_packets: Vec::new(),
+                packet_timings,
             }));
 
         ReceivedProcessor {
@@ -5,6 +5,7 @@ use crate::network_monitor::monitor::gateway_clients_cache::{
     ActiveGatewayClients, GatewayClientHandle,
 };
 use crate::network_monitor::monitor::gateways_pinger::GatewayPinger;
+use crate::network_monitor::monitor::latency::PacketTimings;
 use crate::network_monitor::monitor::receiver::{GatewayClientUpdate, GatewayClientUpdateSender};
 use config::defaults::REMAINING_BANDWIDTH_THRESHOLD;
 use credential_storage::PersistentStorage;
@@ -18,18 +19,40 @@ use gateway_client::{AcknowledgementReceiver, GatewayClient, MixnetMessageReceiv
 use log::{debug, info, trace, warn};
 use nymsphinx::forwarding::packet::MixPacket;
 use pin_project::pin_project;
+use std::collections::HashSet;
 use std::mem;
 use std::num::NonZeroUsize;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Poll;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use task::TaskClient;
 
 use gateway_client::bandwidth::BandwidthController;
 
 const TIME_CHUNK_SIZE: Duration = Duration::from_millis(50);
 
+/// Mix packet alongside the information required for measuring its round-trip time.
+pub(crate) struct TimedMixPacket {
+    /// Sequence number of the test packet contained inside the mix packet.
+    pub(crate) sequence: u64,
+
+    /// Total delay the packet is going to experience on the mixnodes on its way.
+    pub(crate) mix_delay: Duration,
+
+    pub(crate) packet: MixPacket,
+}
+
+impl TimedMixPacket {
+    pub(crate) fn new(sequence: u64, mix_delay: Duration, packet: MixPacket) -> Self {
+        TimedMixPacket {
+            sequence,
+            mix_delay,
+            packet,
+        }
+    }
+}
+
 pub(crate) struct GatewayPackets {
     /// Network address of the target gateway if wanted to be accessed by the client.
     /// It is a websocket address.
@@ -42,7 +65,7 @@ pub(crate) struct GatewayPackets {
     pub(crate) gateway_owner: String,
 
     /// All the packets that are going to get sent to the gateway.
-    pub(crate) packets: Vec<MixPacket>,
+    pub(crate) packets: Vec<TimedMixPacket>,
 }
 
 impl GatewayPackets {
@@ -50,7 +73,7 @@ impl GatewayPackets {
         clients_address: String,
         pub_key: identity::PublicKey,
         gateway_owner: String,
-        packets: Vec<MixPacket>,
+        packets: Vec<TimedMixPacket>,
     ) -> Self {
         GatewayPackets {
             clients_address,
@@ -73,7 +96,7 @@ impl GatewayPackets {
         }
     }
 
-    pub(super) fn push_packets(&mut self, mut packets: Vec<MixPacket>) {
+    pub(super) fn push_packets(&mut self, mut packets: Vec<TimedMixPacket>) {
         if self.packets.is_empty() {
             self.packets = packets
         } else if self.packets.len() > packets.len() {
@@ -135,6 +158,7 @@ pub(crate) struct PacketSender {
     gateway_connection_timeout: Duration,
     max_concurrent_clients: usize,
     max_sending_rate: usize,
+    packet_timings: PacketTimings,
 }
 
 impl PacketSender {
@@ -150,6 +174,7 @@ impl PacketSender {
         max_sending_rate: usize,
         bandwidth_controller: BandwidthController<PersistentStorage>,
         disabled_credentials_mode: bool,
+        packet_timings: PacketTimings,
     ) -> Self {
         PacketSender {
             active_gateway_clients: ActiveGatewayClients::new(),
@@ -163,6 +188,7 @@ impl PacketSender {
             gateway_connection_timeout,
             max_concurrent_clients,
             max_sending_rate,
+            packet_timings,
         }
     }
 
@@ -217,10 +243,24 @@ impl PacketSender {
         )
     }
 
+    fn record_sending_time(packets: &[TimedMixPacket], packet_timings: &PacketTimings) {
+        packet_timings.record_sent(
+            packets
+                .iter()
+                .map(|packet| (packet.sequence, packet.mix_delay)),
+            Instant::now(),
+        )
+    }
+
+    fn strip_timings(packets: Vec<TimedMixPacket>) -> Vec<MixPacket> {
+        packets.into_iter().map(|packet| packet.packet).collect()
+    }
+
     async fn attempt_to_send_packets(
         client: &mut GatewayClient,
-        mut mix_packets: Vec<MixPacket>,
+        mut mix_packets: Vec<TimedMixPacket>,
         max_sending_rate: usize,
+        packet_timings: &PacketTimings,
     ) -> Result<(), GatewayClientError> {
         let gateway_id = client.gateway_identity().to_base58_string();
         info!(
@@ -231,7 +271,10 @@ impl PacketSender {
 
         if mix_packets.len() <= max_sending_rate {
             debug!("Everything is going to get sent as one.");
-            client.batch_send_mix_packets(mix_packets).await?;
+            Self::record_sending_time(&mix_packets, packet_timings);
+            client
+                .batch_send_mix_packets(Self::strip_timings(mix_packets))
+                .await?;
         } else {
             let packets_per_time_chunk =
                 (max_sending_rate as f64 * TIME_CHUNK_SIZE.as_secs_f64()) as usize;
@@ -243,7 +286,10 @@ impl PacketSender {
                 max_sending_rate, total_expected_time, gateway_id
             );
 
-            fn split_off_vec(vec: &mut Vec<MixPacket>, at: usize) -> Option<Vec<MixPacket>> {
+            fn split_off_vec(
+                vec: &mut Vec<TimedMixPacket>,
+                at: usize,
+            ) -> Option<Vec<TimedMixPacket>> {
                 if vec.is_empty() {
                     None
                 } else {
@@ -260,10 +306,15 @@ impl PacketSender {
             while let Some(retained) = split_off_vec(&mut mix_packets, packets_per_time_chunk) {
                 trace!("Sending {} packets...", mix_packets.len());
 
+                Self::record_sending_time(&mix_packets, packet_timings);
                 if mix_packets.len() == 1 {
-                    client.send_mix_packet(mix_packets.pop().unwrap()).await?;
+                    client
+                        .send_mix_packet(mix_packets.pop().unwrap().packet)
+                        .await?;
                 } else {
-                    client.batch_send_mix_packets(mix_packets).await?;
+                    client
+                        .batch_send_mix_packets(Self::strip_timings(mix_packets))
+                        .await?;
                 }
 
                 tokio::time::sleep(TIME_CHUNK_SIZE).await;
@@ -347,6 +398,7 @@ impl PacketSender {
         fresh_gateway_client_data: Arc<FreshGatewayClientData>,
         client: Option<GatewayClientHandle>,
         max_sending_rate: usize,
+        packet_timings: PacketTimings,
     ) -> Option<GatewayClientHandle> {
         let existing_client = client.is_some();
 
@@ -395,7 +447,12 @@ impl PacketSender {
 
         match tokio::time::timeout(
             timeout,
-            Self::attempt_to_send_packets(unwrapped_client, packets.packets, max_sending_rate),
+            Self::attempt_to_send_packets(
+                unwrapped_client,
+                packets.packets,
+                max_sending_rate,
+                &packet_timings,
+            ),
         )
         .await
         {
@@ -458,7 +515,17 @@ impl PacketSender {
         }
     }
 
-    pub(super) async fn send_packets(&mut self, packets: Vec<GatewayPackets>) {
+    /// Sends all the packets through their respective gateways and returns identities of
+    /// the gateways we failed to send the packets through.
+    pub(super) async fn send_packets(
+        &mut self,
+        packets: Vec<GatewayPackets>,
+    ) -> Vec<identity::PublicKey> {
+        let gateways = packets
+            .iter()
+            .map(|packets| packets.pub_key)
+            .collect::<Vec<_>>();
+
         // we know that each of the elements in the packets array will only ever access a single,
         // unique element from the existing clients
 
@@ -469,6 +536,7 @@ impl PacketSender {
             None
         };
         let max_sending_rate = self.max_sending_rate;
+        let packet_timings = &self.packet_timings;
 
         let guard = self.active_gateway_clients.lock().await;
         // this clippy warning is a false positive as we cannot get rid of the collect by moving
@@ -506,6 +574,7 @@ impl PacketSender {
                     fresh_data,
                     client,
                     max_sending_rate,
+                    packet_timings.clone(),
                 )
                 .await
             },
@@ -513,9 +582,19 @@ impl PacketSender {
         .await
         .into_iter()
         .flatten()
-        .collect();
+        .collect::<Vec<_>>();
+
+        let reached = used_clients
+            .iter()
+            .map(|handle| handle.raw_identity())
+            .collect::<HashSet<_>>();
 
         self.merge_client_handles(used_clients).await;
+
+        gateways
+            .into_iter()
+            .filter(|gateway| !reached.contains(&gateway.to_bytes()))
+            .collect()
     }
 }
 
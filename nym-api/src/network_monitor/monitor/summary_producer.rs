@@ -1,12 +1,15 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::network_monitor::monitor::latency::{self, LatencyMeasurement};
 use crate::network_monitor::monitor::preparer::{InvalidNode, TestedNode};
-use crate::network_monitor::test_packet::{NodeType, TestPacket};
+use crate::network_monitor::test_packet::{GatewayPath, NodeType, TestPacket};
 use crate::network_monitor::test_route::TestRoute;
+use log::warn;
 use mixnet_contract_common::MixId;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 const INVALID_MIX_ID: u32 = u32::MAX;
 
@@ -16,6 +19,24 @@ const FINE_THRESHOLD: u8 = 80; // 80 - 95
 const POOR_THRESHOLD: u8 = 60; // 60 - 80
 const UNRELIABLE_THRESHOLD: u8 = 1; // 1 - 60
 
+// number of received test packets of a gateway on each of its sides
+struct GatewayPathCounts {
+    // invalid gateways (e.g. with malformed keys) are not actually tested
+    tested: bool,
+    ingress: usize,
+    egress: usize,
+}
+
+impl Default for GatewayPathCounts {
+    fn default() -> Self {
+        GatewayPathCounts {
+            tested: true,
+            ingress: 0,
+            egress: 0,
+        }
+    }
+}
+
 // I didn't have time to implement it for this PR, however, an idea for the future is as follows:
 // After testing network against N routes, if any one of them is worse than ALLOWED_RELIABILITY_DEVIATION
 // from the average result, remove this data and recalculate scores.
@@ -27,15 +48,23 @@ pub(crate) struct MixnodeResult {
     pub(crate) identity: String,
     pub(crate) owner: String,
     pub(crate) reliability: u8,
+    pub(crate) latency: Option<LatencyMeasurement>,
 }
 
 impl MixnodeResult {
-    pub(crate) fn new(mix_id: MixId, identity: String, owner: String, reliability: u8) -> Self {
+    pub(crate) fn new(
+        mix_id: MixId,
+        identity: String,
+        owner: String,
+        reliability: u8,
+        latency: Option<LatencyMeasurement>,
+    ) -> Self {
         MixnodeResult {
             mix_id,
             identity,
             owner,
             reliability,
+            latency,
         }
     }
 }
@@ -45,16 +74,44 @@ pub(crate) struct GatewayResult {
     pub(crate) identity: String,
     pub(crate) owner: String,
     pub(crate) reliability: u8,
+    pub(crate) latency: Option<LatencyMeasurement>,
+
+    /// Reliability of the client-facing side of the gateway, i.e. of the packets entering
+    /// the mixnet through it.
+    pub(crate) ingress_reliability: Option<u8>,
+
+    /// Reliability of the mix-facing side of the gateway, i.e. of the packets it delivered
+    /// to its client. It is not known if we failed to connect to the gateway.
+    pub(crate) egress_reliability: Option<u8>,
 }
 
 impl GatewayResult {
-    pub(crate) fn new(identity: String, owner: String, reliability: u8) -> Self {
+    pub(crate) fn new(
+        identity: String,
+        owner: String,
+        reliability: u8,
+        latency: Option<LatencyMeasurement>,
+    ) -> Self {
         GatewayResult {
             identity,
             owner,
             reliability,
+            latency,
+            ingress_reliability: None,
+            egress_reliability: None,
         }
     }
+
+    #[must_use]
+    pub(crate) fn with_path_reliabilities(
+        mut self,
+        ingress_reliability: Option<u8>,
+        egress_reliability: Option<u8>,
+    ) -> Self {
+        self.ingress_reliability = ingress_reliability;
+        self.egress_reliability = egress_reliability;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -77,6 +134,9 @@ pub(crate) struct TestReport {
 
     pub(crate) route_results: Vec<RouteResult>,
 
+    pub(crate) average_mixnode_latency: Option<f32>,
+    pub(crate) average_gateway_latency: Option<f32>,
+
     pub(crate) exceptional_mixnodes: usize,
     pub(crate) exceptional_gateways: usize,
 
@@ -151,6 +211,12 @@ impl TestReport {
             total_sent,
             total_received,
             route_results: route_results.to_vec(),
+            average_mixnode_latency: average_latency(
+                mixnode_results.iter().filter_map(|result| result.latency),
+            ),
+            average_gateway_latency: average_latency(
+                gateway_results.iter().filter_map(|result| result.latency),
+            ),
             exceptional_mixnodes,
             exceptional_gateways,
             fine_mixnodes,
@@ -165,6 +231,17 @@ impl TestReport {
     }
 }
 
+fn average_latency(measurements: impl Iterator<Item = LatencyMeasurement>) -> Option<f32> {
+    let (count, sum) = measurements.fold((0, 0.0), |(count, sum), measurement| {
+        (count + 1, sum + measurement.latency_ms)
+    });
+    if count == 0 {
+        None
+    } else {
+        Some(sum / count as f32)
+    }
+}
+
 impl Display for TestReport {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Mix Network Test Report")?;
@@ -183,6 +260,13 @@ impl Display for TestReport {
             )?;
         }
 
+        if let Some(latency) = self.average_mixnode_latency {
+            writeln!(f, "Average mixnode latency: {latency:.2}ms")?;
+        }
+        if let Some(latency) = self.average_gateway_latency {
+            writeln!(f, "Average gateway latency: {latency:.2}ms")?;
+        }
+
         writeln!(
             f,
             "Exceptional mixnodes (reliability >= {}): {}",
@@ -284,18 +368,23 @@ impl SummaryProducer {
         tested_mixnodes: Vec<TestedNode>,
         tested_gateways: Vec<TestedNode>,
         received_packets: Vec<TestPacket>,
+        round_trip_times: &HashMap<u64, Duration>,
         invalid_mixnodes: Vec<InvalidNode>,
         invalid_gateways: Vec<InvalidNode>,
+        unreachable_gateways: &HashSet<String>,
         test_routes: &[TestRoute],
     ) -> TestSummary {
+        let mut latencies = latency::estimate_node_latencies(&received_packets, round_trip_times);
+
         let mut raw_mixnode_results = HashMap::new();
-        let mut raw_gateway_results = HashMap::new();
+        let mut raw_gateway_results: HashMap<_, GatewayPathCounts> = HashMap::new();
 
         let mut raw_route_results = HashMap::new();
 
         // we expect each route to receive this many packets in the ideal world
+        // (each gateway is tested on both its ingress and egress side)
         let per_route_expected =
-            (tested_mixnodes.len() + tested_gateways.len()) * self.per_node_test_packets;
+            (tested_mixnodes.len() + 2 * tested_gateways.len()) * self.per_node_test_packets;
         let per_node_expected = test_routes.len() * self.per_node_test_packets;
 
         // TODO: whenever somebody feels like it, this should really get refactored.
@@ -312,7 +401,10 @@ impl SummaryProducer {
         }
 
         for tested_gateway in tested_gateways {
-            raw_gateway_results.insert((tested_gateway.identity, tested_gateway.owner), 0);
+            raw_gateway_results.insert(
+                (tested_gateway.identity, tested_gateway.owner),
+                GatewayPathCounts::default(),
+            );
         }
 
         for invalid_mixnode in invalid_mixnodes {
@@ -327,7 +419,13 @@ impl SummaryProducer {
         }
 
         for invalid_gateway in invalid_gateways {
-            raw_gateway_results.insert((invalid_gateway.identity(), invalid_gateway.owner()), 0);
+            raw_gateway_results.insert(
+                (invalid_gateway.identity(), invalid_gateway.owner()),
+                GatewayPathCounts {
+                    tested: false,
+                    ..Default::default()
+                },
+            );
         }
 
         for test_route in test_routes {
@@ -344,9 +442,15 @@ impl SummaryProducer {
                         .or_default() += 1usize;
                 }
                 NodeType::Gateway => {
-                    *raw_gateway_results
+                    let counts = raw_gateway_results
                         .entry((pub_key, received.owner))
-                        .or_default() += 1usize;
+                        .or_default();
+                    match received.gateway_path {
+                        Some(GatewayPath::Ingress) => counts.ingress += 1,
+                        Some(GatewayPath::Egress) => counts.egress += 1,
+                        // we always specify the path of the gateway test packets
+                        None => warn!("received gateway test packet without the tested path"),
+                    }
                 }
             }
 
@@ -358,16 +462,32 @@ impl SummaryProducer {
             .map(|((mix_id, identity_key, owner), received)| {
                 let reliability =
                     (received as f32 / per_node_expected as f32 * 100.0).round() as u8;
-                MixnodeResult::new(mix_id, identity_key, owner, reliability)
+                let latency = latencies.mixnodes.remove(&mix_id);
+                MixnodeResult::new(mix_id, identity_key, owner, reliability, latency)
             })
             .collect();
 
         let gateway_results = raw_gateway_results
             .into_iter()
-            .map(|((identity_key, owner), received)| {
-                let reliability =
-                    (received as f32 / per_node_expected as f32 * 100.0).round() as u8;
-                GatewayResult::new(identity_key, owner, reliability)
+            .map(|((identity_key, owner), counts)| {
+                let reliability = ((counts.ingress + counts.egress) as f32
+                    / (2 * per_node_expected) as f32
+                    * 100.0)
+                    .round() as u8;
+                let path_reliability = |received: usize| {
+                    (received as f32 / per_node_expected as f32 * 100.0).round() as u8
+                };
+
+                let ingress_reliability = counts.tested.then(|| path_reliability(counts.ingress));
+                // if we couldn't connect to the gateway, we have no idea whether it would have
+                // delivered the packets to us
+                let egress_reliability = (counts.tested
+                    && !unreachable_gateways.contains(&identity_key))
+                .then(|| path_reliability(counts.egress));
+
+                let latency = latencies.gateways.remove(&identity_key);
+                GatewayResult::new(identity_key, owner, reliability, latency)
+                    .with_path_reliabilities(ingress_reliability, egress_reliability)
             })
             .collect();
 
@@ -16,6 +16,41 @@ use topology::{gateway, mix};
 const MIXNODE_TYPE: u8 = 0;
 const GATEWAY_TYPE: u8 = 1;
 
+const NO_GATEWAY_PATH: u8 = 0;
+const GATEWAY_INGRESS_PATH: u8 = 1;
+const GATEWAY_EGRESS_PATH: u8 = 2;
+
+/// Side of the tested gateway exercised by the test packet.
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub(crate) enum GatewayPath {
+    /// The client-facing side, i.e. authentication, bandwidth handling and accepting packets
+    /// from the clients over the websocket. The packets leave the mixnet through a core gateway.
+    Ingress,
+
+    /// The mix-facing side, i.e. receiving packets from the last mix layer and delivering (or storing)
+    /// them for the clients. The packets enter the mixnet through a core gateway.
+    Egress,
+}
+
+impl GatewayPath {
+    fn to_byte(path: Option<GatewayPath>) -> u8 {
+        match path {
+            None => NO_GATEWAY_PATH,
+            Some(GatewayPath::Ingress) => GATEWAY_INGRESS_PATH,
+            Some(GatewayPath::Egress) => GATEWAY_EGRESS_PATH,
+        }
+    }
+
+    fn try_from_byte(b: u8) -> Result<Option<GatewayPath>, TestPacketError> {
+        match b {
+            NO_GATEWAY_PATH => Ok(None),
+            GATEWAY_INGRESS_PATH => Ok(Some(GatewayPath::Ingress)),
+            GATEWAY_EGRESS_PATH => Ok(Some(GatewayPath::Egress)),
+            _ => Err(TestPacketError::InvalidGatewayPath),
+        }
+    }
+}
+
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
 pub(crate) enum NodeType {
@@ -83,6 +118,9 @@ pub(crate) enum TestPacketError {
     #[error("the received packet did not contain a valid node type")]
     InvalidNodeType,
 
+    #[error("the received packet did not contain a valid gateway path")]
+    InvalidGatewayPath,
+
     #[error("the received node identity key was malformed - {0}")]
     InvalidNodeKey(#[from] Ed25519RecoveryError),
 
@@ -94,6 +132,11 @@ pub(crate) enum TestPacketError {
 pub(crate) struct TestPacket {
     pub(crate) route_id: u64,
     pub(crate) test_nonce: u64,
+    /// Sequence number of the packet within the test run used for matching it against its
+    /// sending time. Packets not used for latency measurements have it set to 0.
+    pub(crate) sequence: u64,
+    /// Side of the gateway being tested. Only set for the gateway test packets.
+    pub(crate) gateway_path: Option<GatewayPath>,
     pub(crate) pub_key: identity::PublicKey,
     pub(crate) owner: String,
     pub(crate) node_type: NodeType,
@@ -103,11 +146,12 @@ impl Display for TestPacket {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "TestPacket {{ pub_key: {}, owner: {}, route: {} test nonce: {} }}",
+            "TestPacket {{ pub_key: {}, owner: {}, route: {} test nonce: {} sequence: {} }}",
             self.pub_key.to_base58_string(),
             self.owner,
             self.route_id,
-            self.test_nonce
+            self.test_nonce,
+            self.sequence
         )
     }
 }
@@ -116,6 +160,8 @@ impl Hash for TestPacket {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.route_id.hash(state);
         self.test_nonce.hash(state);
+        self.sequence.hash(state);
+        self.gateway_path.hash(state);
         self.pub_key.to_bytes().hash(state);
         self.owner.hash(state);
         self.node_type.hash(state);
@@ -126,6 +172,8 @@ impl PartialEq for TestPacket {
     fn eq(&self, other: &Self) -> bool {
         self.route_id == other.route_id
             && self.test_nonce == other.test_nonce
+            && self.sequence == other.sequence
+            && self.gateway_path == other.gateway_path
             && self.pub_key.to_bytes() == other.pub_key.to_bytes()
             && self.owner == other.owner
             && self.node_type == other.node_type
@@ -139,16 +187,25 @@ impl TestPacket {
             owner: mix.owner.clone(),
             route_id,
             test_nonce,
+            sequence: 0,
+            gateway_path: None,
             node_type: NodeType::Mixnode(mix.mix_id),
         }
     }
 
-    pub(crate) fn from_gateway(gateway: &gateway::Node, route_id: u64, test_nonce: u64) -> Self {
+    pub(crate) fn from_gateway(
+        gateway: &gateway::Node,
+        route_id: u64,
+        test_nonce: u64,
+        path: GatewayPath,
+    ) -> Self {
         TestPacket {
             pub_key: gateway.identity_key,
             owner: gateway.owner.clone(),
             route_id,
             test_nonce,
+            sequence: 0,
+            gateway_path: Some(path),
             node_type: NodeType::Gateway,
         }
     }
@@ -163,12 +220,20 @@ impl TestPacket {
         TestPacket {
             route_id,
             test_nonce,
+            sequence: 0,
+            gateway_path: None,
             pub_key,
             owner,
             node_type,
         }
     }
 
+    #[must_use]
+    pub(crate) fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
     pub(crate) fn test_nonce(&self) -> u64 {
         self.test_nonce
     }
@@ -176,6 +241,8 @@ impl TestPacket {
     pub(crate) fn to_bytes(&self) -> Vec<u8> {
         IntoIterator::into_iter(self.route_id.to_be_bytes())
             .chain(IntoIterator::into_iter(self.test_nonce.to_be_bytes()))
+            .chain(IntoIterator::into_iter(self.sequence.to_be_bytes()))
+            .chain(std::iter::once(GatewayPath::to_byte(self.gateway_path)))
             .chain(self.node_type.into_bytes().iter().cloned())
             .chain(self.pub_key.to_bytes().iter().cloned())
             .chain(self.owner.as_bytes().iter().cloned())
@@ -183,31 +250,37 @@ impl TestPacket {
     }
 
     pub(crate) fn try_from_bytes(b: &[u8]) -> Result<Self, TestPacketError> {
-        // route id + test nonce size
+        // route id + test nonce + sequence size
         let n = mem::size_of::<u64>();
+        // all of the above alongside the gateway path
+        let h = 3 * n + 1;
 
-        if b.len() < 2 * n + 1 + identity::PUBLIC_KEY_LENGTH {
+        if b.len() < h + 1 + identity::PUBLIC_KEY_LENGTH {
             return Err(TestPacketError::IncompletePacket {
                 received: b.len(),
-                min_expected: 2 * n + 1 + identity::PUBLIC_KEY_LENGTH,
+                min_expected: h + 1 + identity::PUBLIC_KEY_LENGTH,
             });
         }
 
         // those unwraps can't fail as we've already checked for the size
         let route_id = u64::from_be_bytes(b[0..n].try_into().unwrap());
         let test_nonce = u64::from_be_bytes(b[n..2 * n].try_into().unwrap());
-        let node_type = NodeType::try_from_bytes(&b[2 * n..])?;
+        let sequence = u64::from_be_bytes(b[2 * n..3 * n].try_into().unwrap());
+        let gateway_path = GatewayPath::try_from_byte(b[3 * n])?;
+        let node_type = NodeType::try_from_bytes(&b[h..])?;
         let type_size = node_type.size();
 
         let pub_key = identity::PublicKey::from_bytes(
-            &b[2 * n + type_size..2 * n + type_size + identity::PUBLIC_KEY_LENGTH],
+            &b[h + type_size..h + type_size + identity::PUBLIC_KEY_LENGTH],
         )?;
-        let owner = std::str::from_utf8(&b[2 * n + type_size + identity::PUBLIC_KEY_LENGTH..])?;
+        let owner = std::str::from_utf8(&b[h + type_size + identity::PUBLIC_KEY_LENGTH..])?;
 
         Ok(TestPacket {
             route_id,
             node_type,
             test_nonce,
+            sequence,
+            gateway_path,
             pub_key,
             owner: owner.to_owned(),
         })
@@ -239,19 +312,21 @@ mod tests {
             42,
             123,
             NodeType::Mixnode(1234),
-        );
+        )
+        .with_sequence(7);
 
         let bytes = mix_packet.to_bytes();
         let recovered = TestPacket::try_from_bytes(&bytes).unwrap();
         assert_eq!(mix_packet, recovered);
 
-        let gateway_packet = TestPacket::new(
+        let mut gateway_packet = TestPacket::new(
             *dummy_keypair.public_key(),
             owner,
             42,
             123,
             NodeType::Gateway,
         );
+        gateway_packet.gateway_path = Some(GatewayPath::Egress);
 
         let bytes = gateway_packet.to_bytes();
         let recovered = TestPacket::try_from_bytes(&bytes).unwrap();
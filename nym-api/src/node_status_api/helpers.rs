@@ -10,9 +10,9 @@ use mixnet_contract_common::reward_params::Performance;
 use mixnet_contract_common::{Interval, MixId, RewardedSetNodeStatus};
 use nym_api_requests::models::{
     AllInclusionProbabilitiesResponse, ComputeRewardEstParam, InclusionProbabilityResponse,
-    MixNodeBondAnnotated, MixnodeCoreStatusResponse, MixnodeStatusReportResponse,
-    MixnodeStatusResponse, MixnodeUptimeHistoryResponse, RewardEstimationResponse,
-    StakeSaturationResponse, UptimeResponse,
+    MixNodeBondAnnotated, MixnodeCoreStatusResponse, MixnodeLatencyResponse,
+    MixnodeStatusReportResponse, MixnodeStatusResponse, MixnodeUptimeHistoryResponse,
+    RewardEstimationResponse, StakeSaturationResponse, UptimeResponse,
 };
 use rocket::http::Status;
 use rocket::State;
@@ -30,6 +30,17 @@ pub(crate) async fn _mixnode_report(
         .map_err(|err| ErrorResponse::new(err.to_string(), Status::NotFound))
 }
 
+pub(crate) async fn _mixnode_latency(
+    storage: &NymApiStorage,
+    mix_id: MixId,
+) -> Result<MixnodeLatencyResponse, ErrorResponse> {
+    storage
+        .construct_mixnode_latency_report(mix_id)
+        .await
+        .map(MixnodeLatencyResponse::from)
+        .map_err(|err| ErrorResponse::new(err.to_string(), Status::NotFound))
+}
+
 pub(crate) async fn _mixnode_uptime_history(
     storage: &NymApiStorage,
     mix_id: MixId,
@@ -29,6 +29,7 @@ pub(crate) fn node_status_routes(
             routes::gateway_uptime_history,
             routes::gateway_core_status_count,
             routes::mixnode_report,
+            routes::mixnode_latency,
             routes::mixnode_uptime_history,
             routes::mixnode_core_status_count,
             routes::get_mixnode_status,
@@ -1,13 +1,14 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::node_status_api::utils::NodeUptimes;
+use crate::node_status_api::utils::{GatewayPathUptimes, NodeLatencies, NodeUptimes};
 use crate::storage::models::NodeStatus;
 use mixnet_contract_common::reward_params::Performance;
 use mixnet_contract_common::{IdentityKey, MixId};
 use nym_api_requests::models::{
     GatewayStatusReportResponse, GatewayUptimeHistoryResponse, HistoricalUptimeResponse,
-    MixnodeStatusReportResponse, MixnodeUptimeHistoryResponse, RequestError,
+    MixnodeLatencyResponse, MixnodeStatusReportResponse, MixnodeUptimeHistoryResponse,
+    NodeLatencyResponse, RequestError,
 };
 use okapi::openapi3::{Responses, SchemaObject};
 use rocket::http::Status;
@@ -126,6 +127,42 @@ impl From<Uptime> for Performance {
     }
 }
 
+// average latency, in milliseconds, and its jitter attributed to a node by the network monitor
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+pub struct Latency {
+    pub(crate) latency_ms: f32,
+    pub(crate) jitter_ms: f32,
+}
+
+impl Latency {
+    pub fn from_measurements(measurements: impl Iterator<Item = (f64, f64)>) -> Option<Self> {
+        let (count, latency_sum, jitter_sum) = measurements.fold(
+            (0usize, 0f64, 0f64),
+            |(count, latency_sum, jitter_sum), (latency, jitter)| {
+                (count + 1, latency_sum + latency, jitter_sum + jitter)
+            },
+        );
+
+        if count == 0 {
+            return None;
+        }
+
+        Some(Latency {
+            latency_ms: (latency_sum / count as f64) as f32,
+            jitter_ms: (jitter_sum / count as f64) as f32,
+        })
+    }
+}
+
+impl From<Latency> for NodeLatencyResponse {
+    fn from(latency: Latency) -> Self {
+        NodeLatencyResponse {
+            latency_ms: latency.latency_ms,
+            jitter_ms: latency.jitter_ms,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct MixnodeStatusReport {
     pub(crate) mix_id: MixId,
@@ -136,6 +173,9 @@ pub struct MixnodeStatusReport {
 
     pub(crate) last_hour: Uptime,
     pub(crate) last_day: Uptime,
+
+    pub(crate) last_hour_latency: Option<Latency>,
+    pub(crate) last_day_latency: Option<Latency>,
 }
 
 impl MixnodeStatusReport {
@@ -162,8 +202,17 @@ impl MixnodeStatusReport {
             most_recent: node_uptimes.most_recent,
             last_hour: node_uptimes.last_hour,
             last_day: node_uptimes.last_day,
+            last_hour_latency: None,
+            last_day_latency: None,
         }
     }
+
+    #[must_use]
+    pub(crate) fn with_latencies(mut self, latencies: &NodeLatencies) -> Self {
+        self.last_hour_latency = latencies.last_hour;
+        self.last_day_latency = latencies.last_day;
+        self
+    }
 }
 
 impl From<MixnodeStatusReport> for MixnodeStatusReportResponse {
@@ -175,6 +224,8 @@ impl From<MixnodeStatusReport> for MixnodeStatusReportResponse {
             most_recent: status.most_recent.0,
             last_hour: status.last_hour.0,
             last_day: status.last_day.0,
+            last_hour_latency: status.last_hour_latency.map(Into::into),
+            last_day_latency: status.last_day_latency.map(Into::into),
         }
     }
 }
@@ -188,6 +239,14 @@ pub struct GatewayStatusReport {
 
     pub(crate) last_hour: Uptime,
     pub(crate) last_day: Uptime,
+
+    pub(crate) last_hour_latency: Option<Latency>,
+    pub(crate) last_day_latency: Option<Latency>,
+
+    pub(crate) last_hour_ingress: Option<Uptime>,
+    pub(crate) last_hour_egress: Option<Uptime>,
+    pub(crate) last_day_ingress: Option<Uptime>,
+    pub(crate) last_day_egress: Option<Uptime>,
 }
 
 impl GatewayStatusReport {
@@ -212,8 +271,30 @@ impl GatewayStatusReport {
             most_recent: node_uptimes.most_recent,
             last_hour: node_uptimes.last_hour,
             last_day: node_uptimes.last_day,
+            last_hour_latency: None,
+            last_day_latency: None,
+            last_hour_ingress: None,
+            last_hour_egress: None,
+            last_day_ingress: None,
+            last_day_egress: None,
         }
     }
+
+    #[must_use]
+    pub(crate) fn with_latencies(mut self, latencies: &NodeLatencies) -> Self {
+        self.last_hour_latency = latencies.last_hour;
+        self.last_day_latency = latencies.last_day;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_path_uptimes(mut self, path_uptimes: &GatewayPathUptimes) -> Self {
+        self.last_hour_ingress = path_uptimes.last_hour_ingress;
+        self.last_hour_egress = path_uptimes.last_hour_egress;
+        self.last_day_ingress = path_uptimes.last_day_ingress;
+        self.last_day_egress = path_uptimes.last_day_egress;
+        self
+    }
 }
 
 impl From<GatewayStatusReport> for GatewayStatusReportResponse {
@@ -224,6 +305,28 @@ impl From<GatewayStatusReport> for GatewayStatusReportResponse {
             most_recent: status.most_recent.0,
             last_hour: status.last_hour.0,
             last_day: status.last_day.0,
+            last_hour_latency: status.last_hour_latency.map(Into::into),
+            last_day_latency: status.last_day_latency.map(Into::into),
+            last_hour_ingress: status.last_hour_ingress.map(|uptime| uptime.0),
+            last_hour_egress: status.last_hour_egress.map(|uptime| uptime.0),
+            last_day_ingress: status.last_day_ingress.map(|uptime| uptime.0),
+            last_day_egress: status.last_day_egress.map(|uptime| uptime.0),
+        }
+    }
+}
+
+pub struct MixnodeLatencyReport {
+    pub(crate) mix_id: MixId,
+    pub(crate) latencies: NodeLatencies,
+}
+
+impl From<MixnodeLatencyReport> for MixnodeLatencyResponse {
+    fn from(report: MixnodeLatencyReport) -> Self {
+        MixnodeLatencyResponse {
+            mix_id: report.mix_id,
+            most_recent: report.latencies.most_recent.map(Into::into),
+            last_hour: report.latencies.last_hour.map(Into::into),
+            last_day: report.latencies.last_day.map(Into::into),
         }
     }
 }
@@ -380,6 +483,9 @@ pub enum NymApiStorageError {
     #[error("Could not find status report associated with gateway {identity}")]
     GatewayReportNotFound { identity: IdentityKey },
 
+    #[error("could not find latency measurements associated with mixnode {mix_id}")]
+    MixnodeLatencyNotFound { mix_id: MixId },
+
     #[error("could not find uptime history associated with mixnode {mix_id}")]
     MixnodeUptimeHistoryNotFound { mix_id: MixId },
 
@@ -7,7 +7,7 @@ use crate::node_status_api::helpers::{
     _get_mixnode_inclusion_probabilities, _get_mixnode_inclusion_probability,
     _get_mixnode_reward_estimation, _get_mixnode_stake_saturation, _get_mixnode_status,
     _get_mixnodes_detailed, _get_rewarded_set_detailed, _mixnode_core_status_count,
-    _mixnode_report, _mixnode_uptime_history,
+    _mixnode_latency, _mixnode_report, _mixnode_uptime_history,
 };
 use crate::node_status_api::models::ErrorResponse;
 use crate::storage::NymApiStorage;
@@ -16,9 +16,9 @@ use mixnet_contract_common::MixId;
 use nym_api_requests::models::{
     AllInclusionProbabilitiesResponse, ComputeRewardEstParam, GatewayCoreStatusResponse,
     GatewayStatusReportResponse, GatewayUptimeHistoryResponse, InclusionProbabilityResponse,
-    MixNodeBondAnnotated, MixnodeCoreStatusResponse, MixnodeStatusReportResponse,
-    MixnodeStatusResponse, MixnodeUptimeHistoryResponse, RewardEstimationResponse,
-    StakeSaturationResponse, UptimeResponse,
+    MixNodeBondAnnotated, MixnodeCoreStatusResponse, MixnodeLatencyResponse,
+    MixnodeStatusReportResponse, MixnodeStatusResponse, MixnodeUptimeHistoryResponse,
+    RewardEstimationResponse, StakeSaturationResponse, UptimeResponse,
 };
 use rocket::http::Status;
 use rocket::serde::json::Json;
@@ -80,6 +80,15 @@ pub(crate) async fn mixnode_report(
     Ok(Json(_mixnode_report(storage, mix_id).await?))
 }
 
+#[openapi(tag = "status")]
+#[get("/mixnode/<mix_id>/latency")]
+pub(crate) async fn mixnode_latency(
+    storage: &State<NymApiStorage>,
+    mix_id: MixId,
+) -> Result<Json<MixnodeLatencyResponse>, ErrorResponse> {
+    Ok(Json(_mixnode_latency(storage, mix_id).await?))
+}
+
 #[openapi(tag = "status")]
 #[get("/mixnode/<mix_id>/history")]
 pub(crate) async fn mixnode_uptime_history(
@@ -1,9 +1,9 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::node_status_api::models::Uptime;
+use crate::node_status_api::models::{Latency, Uptime};
 use crate::node_status_api::{FIFTEEN_MINUTES, ONE_HOUR};
-use crate::storage::models::NodeStatus;
+use crate::storage::models::{GatewayPathStatus, NodeLatency, NodeStatus};
 use log::warn;
 use mixnet_contract_common::MixId;
 use std::convert::TryInto;
@@ -116,3 +116,107 @@ impl NodeUptimes {
         }
     }
 }
+
+// A helper intermediate struct to remove duplicate code for construction of mixnode and gateway
+// latency reports
+pub(crate) struct NodeLatencies {
+    pub(crate) most_recent: Option<Latency>,
+
+    pub(crate) last_hour: Option<Latency>,
+    pub(crate) last_day: Option<Latency>,
+}
+
+impl NodeLatencies {
+    pub(crate) fn calculate_from_last_day_measurements(
+        report_time: OffsetDateTime,
+        last_day: &[NodeLatency],
+    ) -> Self {
+        let hour_ago = (report_time - ONE_HOUR).unix_timestamp();
+        let fifteen_minutes_ago = (report_time - FIFTEEN_MINUTES).unix_timestamp();
+
+        // unlike the uptime, the latency is not averaged over the expected number of test runs
+        // as a node not being measured in a particular run doesn't make it any faster or slower
+        let last_day_latency = Latency::from_measurements(
+            last_day
+                .iter()
+                .map(|measurement| (measurement.latency_ms(), measurement.jitter_ms())),
+        );
+
+        let last_hour_latency = Latency::from_measurements(
+            last_day
+                .iter()
+                .filter(|measurement| measurement.timestamp() >= hour_ago)
+                .map(|measurement| (measurement.latency_ms(), measurement.jitter_ms())),
+        );
+
+        // make sure the most recent measurement is within last 15min
+        let most_recent = last_day
+            .iter()
+            .max_by_key(|measurement| measurement.timestamp())
+            .filter(|measurement| measurement.timestamp() >= fifteen_minutes_ago)
+            .and_then(|measurement| {
+                Latency::from_measurements(std::iter::once((
+                    measurement.latency_ms(),
+                    measurement.jitter_ms(),
+                )))
+            });
+
+        NodeLatencies {
+            most_recent,
+            last_hour: last_hour_latency,
+            last_day: last_day_latency,
+        }
+    }
+}
+
+// A temporary helper struct used to produce reliabilities of the separate sides of a gateway.
+// Unlike the overall uptime, they're averaged only over the test runs in which
+// the particular side has actually been tested.
+#[derive(Debug, Default)]
+pub(crate) struct GatewayPathUptimes {
+    pub(crate) last_hour_ingress: Option<Uptime>,
+    pub(crate) last_hour_egress: Option<Uptime>,
+    pub(crate) last_day_ingress: Option<Uptime>,
+    pub(crate) last_day_egress: Option<Uptime>,
+}
+
+impl GatewayPathUptimes {
+    pub(crate) fn calculate_from_last_day_statuses(
+        report_time: OffsetDateTime,
+        last_day: &[GatewayPathStatus],
+    ) -> Self {
+        let hour_ago = (report_time - ONE_HOUR).unix_timestamp();
+        let last_hour = last_day
+            .iter()
+            .filter(|status| status.timestamp() >= hour_ago)
+            .collect::<Vec<_>>();
+
+        GatewayPathUptimes {
+            last_hour_ingress: average_uptime(last_hour.iter().map(|s| s.ingress_reliability)),
+            last_hour_egress: average_uptime(last_hour.iter().map(|s| s.egress_reliability)),
+            last_day_ingress: average_uptime(last_day.iter().map(|s| s.ingress_reliability)),
+            last_day_egress: average_uptime(last_day.iter().map(|s| s.egress_reliability)),
+        }
+    }
+}
+
+// averages the available reliabilities, returns `None` if there are none
+fn average_uptime(reliabilities: impl Iterator<Item = Option<u8>>) -> Option<Uptime> {
+    let (sum, count) = reliabilities
+        .flatten()
+        .fold((0f32, 0usize), |(sum, count), reliability| {
+            (sum + reliability as f32, count + 1)
+        });
+
+    if count == 0 {
+        return None;
+    }
+
+    match Uptime::from_uptime_sum(sum, count) {
+        Ok(uptime) => Some(uptime),
+        Err(err) => {
+            warn!("failed to calculate gateway path uptime - {}", err);
+            None
+        }
+    }
+}
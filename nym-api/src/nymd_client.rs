@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Config;
-use crate::epoch_operations::MixnodeToReward;
+use crate::epoch_operations::{GatewayToReward, MixnodeToReward};
 use config::defaults::{NymNetworkDetails, DEFAULT_NYM_API_PORT};
 use mixnet_contract_common::families::{Family, FamilyHead};
 use mixnet_contract_common::mixnode::MixNodeDetails;
@@ -213,6 +213,7 @@ impl<C> Client<C> {
     pub(crate) async fn send_rewarding_messages(
         &self,
         nodes: &[MixnodeToReward],
+        gateways: &[GatewayToReward],
     ) -> Result<(), ValidatorClientError>
     where
         C: SigningCosmWasmClient + Sync + Send,
@@ -222,6 +223,7 @@ impl<C> Client<C> {
         #[allow(unused_variables)]
         fn generate_reward_messages(
             eligible_mixnodes: &[MixnodeToReward],
+            eligible_gateways: &[GatewayToReward],
         ) -> Vec<(ExecuteMsg, Vec<Coin>)> {
             cfg_if::cfg_if! {
                 if #[cfg(feature = "no-reward")] {
@@ -229,7 +231,8 @@ impl<C> Client<C> {
                 } else {
                     eligible_mixnodes
                         .iter()
-                    .map(|node| (*node).into())
+                        .map(|node| (*node).into())
+                        .chain(eligible_gateways.iter().map(|gateway| gateway.clone().into()))
                         .zip(std::iter::repeat(Vec::new()))
                         .collect()
                 }
@@ -238,7 +241,7 @@ impl<C> Client<C> {
 
         let contract = self.0.read().await.get_mixnet_contract_address();
 
-        let msgs = generate_reward_messages(nodes);
+        let msgs = generate_reward_messages(nodes, gateways);
 
         self.0
             .write()
@@ -248,7 +251,11 @@ impl<C> Client<C> {
                 &contract,
                 msgs,
                 Default::default(),
-                format!("rewarding {} mixnodes", nodes.len()),
+                format!(
+                    "rewarding {} mixnodes and {} gateways",
+                    nodes.len(),
+                    gateways.len()
+                ),
             )
             .await?;
         Ok(())
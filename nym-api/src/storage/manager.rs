@@ -4,8 +4,11 @@ use crate::network_monitor::monitor::summary_producer::{GatewayResult, MixnodeRe
 use crate::node_status_api::models::{HistoricalUptime, Uptime};
 use crate::node_status_api::utils::{ActiveGatewayStatuses, ActiveMixnodeStatuses};
 use crate::storage::models::{
-    ActiveGateway, ActiveMixnode, NodeStatus, RewardingReport, TestingRoute,
+    ActiveGateway, ActiveMixnode, GatewayPathStatus, NodeLatency, NodeStatus, RewardingReport,
+    TestingRoute,
 };
+#[cfg(feature = "coconut")]
+use crate::storage::models::{EpochIssuanceTotals, IssuanceLogRecord};
 use mixnet_contract_common::{EpochId, IdentityKey, MixId};
 use std::convert::TryFrom;
 
@@ -284,6 +287,90 @@ impl StorageManager {
         .await
     }
 
+    /// Gets all latency measurements for mixnode with particular id that were inserted
+    /// into the database after the specified unix timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `mix_id`: mix-id (as assigned by the smart contract) of the mixnode.
+    /// * `timestamp`: unix timestamp of the lower bound of the selection.
+    pub(super) async fn get_mixnode_latencies_since(
+        &self,
+        mix_id: MixId,
+        timestamp: i64,
+    ) -> Result<Vec<NodeLatency>, sqlx::Error> {
+        sqlx::query_as!(
+            NodeLatency,
+            r#"
+                SELECT timestamp, latency_ms, jitter_ms
+                    FROM mixnode_latency
+                    JOIN mixnode_details
+                    ON mixnode_latency.mixnode_details_id = mixnode_details.id
+                    WHERE mixnode_details.mix_id=? AND mixnode_latency.timestamp > ?;
+            "#,
+            mix_id,
+            timestamp,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Gets all latency measurements for gateway with particular identity that were inserted
+    /// into the database after the specified unix timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity`: identity (base58-encoded public key) of the gateway.
+    /// * `timestamp`: unix timestamp of the lower bound of the selection.
+    pub(super) async fn get_gateway_latencies_since(
+        &self,
+        identity: &str,
+        timestamp: i64,
+    ) -> Result<Vec<NodeLatency>, sqlx::Error> {
+        sqlx::query_as!(
+            NodeLatency,
+            r#"
+                SELECT timestamp, latency_ms, jitter_ms
+                    FROM gateway_latency
+                    JOIN gateway_details
+                    ON gateway_latency.gateway_details_id = gateway_details.id
+                    WHERE gateway_details.identity=? AND gateway_latency.timestamp > ?;
+            "#,
+            identity,
+            timestamp,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Gets all reliability statuses of both sides of the gateway with the provided identity
+    /// that were inserted into the database after the specified unix timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity`: identity (base58-encoded public key) of the gateway.
+    /// * `timestamp`: unix timestamp of the lower bound of the selection.
+    pub(super) async fn get_gateway_path_statuses_since(
+        &self,
+        identity: &str,
+        timestamp: i64,
+    ) -> Result<Vec<GatewayPathStatus>, sqlx::Error> {
+        sqlx::query_as!(
+            GatewayPathStatus,
+            r#"
+                SELECT timestamp, ingress_reliability as "ingress_reliability: u8", egress_reliability as "egress_reliability: u8"
+                    FROM gateway_status
+                    JOIN gateway_details
+                    ON gateway_status.gateway_details_id = gateway_details.id
+                    WHERE gateway_details.identity=? AND gateway_status.timestamp > ?;
+            "#,
+            identity,
+            timestamp,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
     /// Gets the historical daily uptime associated with the particular mixnode
     ///
     /// # Arguments
@@ -476,6 +563,21 @@ impl StorageManager {
                 )
                 .execute(&mut tx)
                 .await?;
+
+            // and the latency, if we managed to measure it
+            if let Some(latency) = mixnode_result.latency {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO mixnode_latency (mixnode_details_id, latency_ms, jitter_ms, timestamp) VALUES (?, ?, ?, ?);
+                    "#,
+                    mixnode_id,
+                    latency.latency_ms,
+                    latency.jitter_ms,
+                    timestamp
+                )
+                .execute(&mut tx)
+                .await?;
+            }
         }
 
         // finally commit the transaction
@@ -517,14 +619,31 @@ impl StorageManager {
             // insert the actual status
             sqlx::query!(
                     r#"
-                        INSERT INTO gateway_status (gateway_details_id, reliability, timestamp) VALUES (?, ?, ?);
+                        INSERT INTO gateway_status (gateway_details_id, reliability, ingress_reliability, egress_reliability, timestamp) VALUES (?, ?, ?, ?, ?);
                     "#,
                     gateway_id,
                     gateway_result.reliability,
+                    gateway_result.ingress_reliability,
+                    gateway_result.egress_reliability,
+                    timestamp
+                )
+                .execute(&mut tx)
+                .await?;
+
+            // and the latency, if we managed to measure it
+            if let Some(latency) = gateway_result.latency {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO gateway_latency (gateway_details_id, latency_ms, jitter_ms, timestamp) VALUES (?, ?, ?, ?);
+                    "#,
+                    gateway_id,
+                    latency.latency_ms,
+                    latency.jitter_ms,
                     timestamp
                 )
                 .execute(&mut tx)
                 .await?;
+            }
         }
 
         // finally commit the transaction
@@ -728,7 +847,7 @@ impl StorageManager {
         Ok(count)
     }
 
-    /// Removes all statuses for all mixnodes that are older than the
+    /// Removes all statuses and latency measurements for all mixnodes that are older than the
     /// provided timestamp. This method is indirectly called at every reward cycle.
     ///
     /// # Arguments
@@ -741,10 +860,13 @@ impl StorageManager {
         sqlx::query!("DELETE FROM mixnode_status WHERE timestamp < ?", timestamp)
             .execute(&self.connection_pool)
             .await?;
+        sqlx::query!("DELETE FROM mixnode_latency WHERE timestamp < ?", timestamp)
+            .execute(&self.connection_pool)
+            .await?;
         Ok(())
     }
 
-    /// Removes all statuses for all gateways that are older than the
+    /// Removes all statuses and latency measurements for all gateways that are older than the
     /// provided timestamp. This method is indirectly called at every reward cycle.
     ///
     /// # Arguments
@@ -757,6 +879,9 @@ impl StorageManager {
         sqlx::query!("DELETE FROM gateway_status WHERE timestamp < ?", timestamp)
             .execute(&self.connection_pool)
             .await?;
+        sqlx::query!("DELETE FROM gateway_latency WHERE timestamp < ?", timestamp)
+            .execute(&self.connection_pool)
+            .await?;
         Ok(())
     }
 
@@ -854,11 +979,12 @@ impl StorageManager {
         sqlx::query!(
             r#"
                 INSERT INTO rewarding_report
-                (absolute_epoch_id, eligible_mixnodes)
-                VALUES (?, ?);
+                (absolute_epoch_id, eligible_mixnodes, eligible_gateways)
+                VALUES (?, ?, ?);
             "#,
             report.absolute_epoch_id,
             report.eligible_mixnodes,
+            report.eligible_gateways,
         )
         .execute(&self.connection_pool)
         .await?;
@@ -874,7 +1000,8 @@ impl StorageManager {
             r#"
                 SELECT 
                     absolute_epoch_id as "absolute_epoch_id: u32",
-                    eligible_mixnodes as "eligible_mixnodes: u32"
+                    eligible_mixnodes as "eligible_mixnodes: u32",
+                    eligible_gateways as "eligible_gateways: u32"
                 FROM rewarding_report 
                 WHERE absolute_epoch_id = ?
             "#,
@@ -993,4 +1120,91 @@ impl StorageManager {
 
         Ok(blinded_signature_response)
     }
+
+    /// Records an attempt of issuing a partial credential for a deposit. If a credential has
+    /// already been issued for the same deposit, another successful issuance is not recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `record`: the issuance attempt to record.
+    #[cfg(feature = "coconut")]
+    pub(super) async fn insert_issuance_log_record(
+        &self,
+        record: &IssuanceLogRecord,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+                INSERT OR IGNORE INTO issuance_log
+                (tx_hash, deposit_value, deposit_epoch, requester_ip, timestamp, issued, error)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            record.tx_hash,
+            record.deposit_value,
+            record.deposit_epoch,
+            record.requester_ip,
+            record.timestamp,
+            record.issued,
+            record.error,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Gets all the issuance attempts for deposits made in the provided epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `deposit_epoch`: expiry epoch during which the deposits were made.
+    #[cfg(feature = "coconut")]
+    pub(super) async fn get_issuance_log(
+        &self,
+        deposit_epoch: i64,
+    ) -> Result<Vec<IssuanceLogRecord>, sqlx::Error> {
+        sqlx::query_as!(
+            IssuanceLogRecord,
+            r#"
+                SELECT tx_hash, deposit_value, deposit_epoch, requester_ip, timestamp,
+                    issued as "issued: bool", error
+                    FROM issuance_log
+                    WHERE deposit_epoch = ?
+                    ORDER BY id;
+            "#,
+            deposit_epoch,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Gets the issued credentials and their values, alongside the failed issuance attempts,
+    /// aggregated for each deposit epoch in the provided (inclusive) range.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_epoch`: first deposit epoch of the selection.
+    /// * `to_epoch`: last deposit epoch of the selection.
+    #[cfg(feature = "coconut")]
+    pub(super) async fn get_issuance_totals(
+        &self,
+        from_epoch: i64,
+        to_epoch: i64,
+    ) -> Result<Vec<EpochIssuanceTotals>, sqlx::Error> {
+        sqlx::query_as!(
+            EpochIssuanceTotals,
+            r#"
+                SELECT deposit_epoch as "deposit_epoch!: i64",
+                    COALESCE(SUM(CASE WHEN issued THEN 1 ELSE 0 END), 0) as "issued_credentials!: i64",
+                    COALESCE(SUM(CASE WHEN issued THEN deposit_value ELSE 0 END), 0) as "issued_value!: i64",
+                    COALESCE(SUM(CASE WHEN issued THEN 0 ELSE 1 END), 0) as "failed_requests!: i64"
+                    FROM issuance_log
+                    WHERE deposit_epoch >= ? AND deposit_epoch <= ?
+                    GROUP BY deposit_epoch
+                    ORDER BY deposit_epoch;
+            "#,
+            from_epoch,
+            to_epoch,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
 }
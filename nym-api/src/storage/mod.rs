@@ -4,11 +4,14 @@
 use crate::network_monitor::monitor::summary_producer::{GatewayResult, MixnodeResult};
 use crate::network_monitor::test_route::TestRoute;
 use crate::node_status_api::models::{
-    GatewayStatusReport, GatewayUptimeHistory, MixnodeStatusReport, MixnodeUptimeHistory,
-    NymApiStorageError, Uptime,
+    GatewayStatusReport, GatewayUptimeHistory, MixnodeLatencyReport, MixnodeStatusReport,
+    MixnodeUptimeHistory, NymApiStorageError, Uptime,
 };
+use crate::node_status_api::utils::{GatewayPathUptimes, NodeLatencies};
 use crate::node_status_api::{ONE_DAY, ONE_HOUR};
 use crate::storage::manager::StorageManager;
+#[cfg(feature = "coconut")]
+use crate::storage::models::{EpochIssuanceTotals, IssuanceLogRecord};
 use crate::storage::models::{NodeStatus, RewardingReport, TestingRoute};
 use mixnet_contract_common::{EpochId, MixId};
 use rocket::fairing::AdHoc;
@@ -192,6 +195,11 @@ impl NymApiStorage {
                 "The node doesn't have an owner even though we have status information on it!",
             );
 
+        let latencies = self
+            .manager
+            .get_mixnode_latencies_since(mix_id, day_ago)
+            .await?;
+
         Ok(MixnodeStatusReport::construct_from_last_day_reports(
             now,
             mix_id,
@@ -200,7 +208,38 @@ impl NymApiStorage {
             statuses,
             last_hour_runs_count,
             last_day_runs_count,
-        ))
+        )
+        .with_latencies(&NodeLatencies::calculate_from_last_day_measurements(
+            now, &latencies,
+        )))
+    }
+
+    /// Tries to construct a latency report for mixnode with the specified mix_id.
+    ///
+    /// # Arguments
+    ///
+    /// * `mix_id`: mix-id (as assigned by the smart contract) of the mixnode.
+    pub(crate) async fn construct_mixnode_latency_report(
+        &self,
+        mix_id: MixId,
+    ) -> Result<MixnodeLatencyReport, NymApiStorageError> {
+        let now = OffsetDateTime::now_utc();
+        let day_ago = (now - ONE_DAY).unix_timestamp();
+
+        let latencies = self
+            .manager
+            .get_mixnode_latencies_since(mix_id, day_ago)
+            .await?;
+
+        // if we have no measurements, the node doesn't exist (or monitor is down), but either way, we can't make a report
+        if latencies.is_empty() {
+            return Err(NymApiStorageError::MixnodeLatencyNotFound { mix_id });
+        }
+
+        Ok(MixnodeLatencyReport {
+            mix_id,
+            latencies: NodeLatencies::calculate_from_last_day_measurements(now, &latencies),
+        })
     }
 
     pub(crate) async fn construct_gateway_report(
@@ -232,6 +271,16 @@ impl NymApiStorage {
             "The gateway doesn't have an owner even though we have status information on it!",
         );
 
+        let latencies = self
+            .manager
+            .get_gateway_latencies_since(identity, day_ago)
+            .await?;
+
+        let path_statuses = self
+            .manager
+            .get_gateway_path_statuses_since(identity, day_ago)
+            .await?;
+
         Ok(GatewayStatusReport::construct_from_last_day_reports(
             now,
             identity.to_owned(),
@@ -239,7 +288,14 @@ impl NymApiStorage {
             statuses,
             last_hour_runs_count,
             last_day_runs_count,
+        )
+        .with_latencies(&NodeLatencies::calculate_from_last_day_measurements(
+            now, &latencies,
         ))
+        .with_path_uptimes(&GatewayPathUptimes::calculate_from_last_day_statuses(
+            now,
+            &path_statuses,
+        )))
     }
 
     pub(crate) async fn get_mixnode_uptime_history(
@@ -718,4 +774,41 @@ impl NymApiStorage {
             .await
             .map_err(|err| err.into())
     }
+
+    #[cfg(feature = "coconut")]
+    pub(crate) async fn insert_issuance_log_record(
+        &self,
+        record: &IssuanceLogRecord,
+    ) -> Result<(), NymApiStorageError> {
+        self.manager
+            .insert_issuance_log_record(record)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    #[cfg(feature = "coconut")]
+    pub(crate) async fn get_issuance_log(
+        &self,
+        deposit_epoch: u64,
+    ) -> Result<Vec<IssuanceLogRecord>, NymApiStorageError> {
+        self.manager
+            .get_issuance_log(i64::try_from(deposit_epoch).unwrap_or(i64::MAX))
+            .await
+            .map_err(|err| err.into())
+    }
+
+    #[cfg(feature = "coconut")]
+    pub(crate) async fn get_issuance_totals(
+        &self,
+        from_epoch: u64,
+        to_epoch: u64,
+    ) -> Result<Vec<EpochIssuanceTotals>, NymApiStorageError> {
+        self.manager
+            .get_issuance_totals(
+                i64::try_from(from_epoch).unwrap_or(i64::MAX),
+                i64::try_from(to_epoch).unwrap_or(i64::MAX),
+            )
+            .await
+            .map_err(|err| err.into())
+    }
 }
@@ -19,6 +19,40 @@ impl NodeStatus {
     }
 }
 
+// Internally used struct to catch reliabilities of both sides of given gateway from the database
+pub(crate) struct GatewayPathStatus {
+    pub timestamp: Option<i64>,
+    pub ingress_reliability: Option<u8>,
+    pub egress_reliability: Option<u8>,
+}
+
+impl GatewayPathStatus {
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp.unwrap_or_default()
+    }
+}
+
+// Internally used struct to catch latency measurements of given mixnode/gateway from the database
+pub(crate) struct NodeLatency {
+    pub timestamp: Option<i64>,
+    pub latency_ms: Option<f64>,
+    pub jitter_ms: Option<f64>,
+}
+
+impl NodeLatency {
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp.unwrap_or_default()
+    }
+
+    pub fn latency_ms(&self) -> f64 {
+        self.latency_ms.unwrap_or_default()
+    }
+
+    pub fn jitter_ms(&self) -> f64 {
+        self.jitter_ms.unwrap_or_default()
+    }
+}
+
 // Internally used structs to catch results from the database to find active mixnodes
 pub(crate) struct ActiveMixnode {
     pub(crate) id: i64,
@@ -46,4 +80,27 @@ pub(crate) struct RewardingReport {
     pub(crate) absolute_epoch_id: u32,
 
     pub(crate) eligible_mixnodes: u32,
+
+    pub(crate) eligible_gateways: u32,
+}
+
+// Internally used struct to catch a single entry of the credential issuance log
+#[cfg(feature = "coconut")]
+pub(crate) struct IssuanceLogRecord {
+    pub(crate) tx_hash: String,
+    pub(crate) deposit_value: Option<i64>,
+    pub(crate) deposit_epoch: Option<i64>,
+    pub(crate) requester_ip: Option<String>,
+    pub(crate) timestamp: i64,
+    pub(crate) issued: bool,
+    pub(crate) error: Option<String>,
+}
+
+// Internally used struct to catch the issuance log aggregated over a single deposit epoch
+#[cfg(feature = "coconut")]
+pub(crate) struct EpochIssuanceTotals {
+    pub(crate) deposit_epoch: i64,
+    pub(crate) issued_credentials: i64,
+    pub(crate) issued_value: i64,
+    pub(crate) failed_requests: i64,
 }
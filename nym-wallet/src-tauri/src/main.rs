@@ -73,6 +73,7 @@ fn main() {
             mixnet::delegate::get_all_mix_delegations,
             mixnet::delegate::undelegate_from_mixnode,
             mixnet::delegate::undelegate_all_from_mixnode,
+            mixnet::delegate::redelegate_from_mixnode,
             mixnet::families::create_family,
             mixnet::families::join_family,
             mixnet::families::leave_family,
@@ -121,6 +122,7 @@ fn main() {
             vesting::bond::withdraw_vested_coins,
             vesting::delegate::vesting_delegate_to_mixnode,
             vesting::delegate::vesting_undelegate_from_mixnode,
+            vesting::delegate::vesting_redelegate_from_mixnode,
             vesting::queries::delegated_free,
             vesting::queries::delegated_vesting,
             vesting::queries::get_account_info,
@@ -118,6 +118,38 @@ pub async fn undelegate_from_mixnode(
     )?)
 }
 
+#[tauri::command]
+pub async fn redelegate_from_mixnode(
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: DecCoin,
+    fee: Option<Fee>,
+    state: tauri::State<'_, WalletState>,
+) -> Result<TransactionExecuteResult, BackendError> {
+    let guard = state.read().await;
+    let redelegation_base = guard.attempt_convert_to_base_coin(amount.clone())?;
+    let fee_amount = guard.convert_tx_fee(fee.as_ref());
+
+    log::info!(
+        ">>> Redelegate between mixnodes: from_mix_id = {}, to_mix_id = {}, display_amount = {}, base_amount = {}, fee = {:?}",
+        from_mix_id,
+        to_mix_id,
+        amount,
+        redelegation_base,
+        fee,
+    );
+    let res = guard
+        .current_client()?
+        .nymd
+        .redelegate_from_mixnode(from_mix_id, to_mix_id, redelegation_base, fee)
+        .await?;
+    log::info!("<<< tx hash = {}", res.transaction_hash);
+    log::trace!("<<< {:?}", res);
+    Ok(TransactionExecuteResult::from_execute_result(
+        res, fee_amount,
+    )?)
+}
+
 #[tauri::command]
 pub async fn undelegate_all_from_mixnode(
     mix_id: MixId,
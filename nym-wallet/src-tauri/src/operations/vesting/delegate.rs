@@ -62,3 +62,34 @@ pub async fn vesting_undelegate_from_mixnode(
         res, fee_amount,
     )?)
 }
+
+#[tauri::command]
+pub async fn vesting_redelegate_from_mixnode(
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: DecCoin,
+    fee: Option<Fee>,
+    state: tauri::State<'_, WalletState>,
+) -> Result<TransactionExecuteResult, BackendError> {
+    let guard = state.read().await;
+    let redelegation = guard.attempt_convert_to_base_coin(amount.clone())?;
+    let fee_amount = guard.convert_tx_fee(fee.as_ref());
+    log::info!(
+        ">>> Redelegate between mixnodes with locked tokens: from_mix_id = {}, to_mix_id = {}, amount_display = {}, amount_base = {}, fee = {:?}",
+        from_mix_id,
+        to_mix_id,
+        amount,
+        redelegation,
+        fee,
+    );
+    let res = guard
+        .current_client()?
+        .nymd
+        .vesting_redelegate_from_mixnode(from_mix_id, to_mix_id, redelegation, fee)
+        .await?;
+    log::info!("<<< tx hash = {}", res.transaction_hash);
+    log::trace!("<<< {:?}", res);
+    Ok(TransactionExecuteResult::from_execute_result(
+        res, fee_amount,
+    )?)
+}
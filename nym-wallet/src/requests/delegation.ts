@@ -16,6 +16,9 @@ export const getDelegationSummary = async () => invokeWrapper<DelegationsSummary
 export const undelegateFromMixnode = async (mixId: number, fee?: Fee) =>
   invokeWrapper<TransactionExecuteResult>('undelegate_from_mixnode', { mixId, fee });
 
+export const redelegateFromMixnode = async (fromMixId: number, toMixId: number, amount: DecCoin, fee?: Fee) =>
+  invokeWrapper<TransactionExecuteResult>('redelegate_from_mixnode', { fromMixId, toMixId, amount, fee });
+
 export const undelegateAllFromMixnode = async (
   mixId: number,
   usesVestingContractTokens: boolean,
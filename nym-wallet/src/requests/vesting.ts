@@ -75,6 +75,9 @@ export const vestingDelegateToMixnode = async (mixId: number, amount: DecCoin, f
 export const vestingUndelegateFromMixnode = async (mixId: number) =>
   invokeWrapper<TransactionExecuteResult>('vesting_undelegate_from_mixnode', { mixId });
 
+export const vestingRedelegateFromMixnode = async (fromMixId: number, toMixId: number, amount: DecCoin, fee?: Fee) =>
+  invokeWrapper<TransactionExecuteResult>('vesting_redelegate_from_mixnode', { fromMixId, toMixId, amount, fee });
+
 export const getVestingAccountInfo = async (address: string) =>
   invokeWrapper<VestingAccountInfo>('get_account_info', { address });
 
@@ -83,6 +83,8 @@ impl ReturnAddress {
                 recipient: *recipient,
                 message,
                 connection_id: Some(connection_id),
+                compress: false,
+                erasure_coding: false,
             },
             ReturnAddress::Anonymous(sender_tag) => ClientRequest::Reply {
                 message,
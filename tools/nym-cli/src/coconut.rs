@@ -0,0 +1,68 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::bail;
+use credential_storage::PersistentStorage;
+use nym_cli_commands::coconut::{Coconut, CoconutCommands};
+use std::path::Path;
+
+async fn open_credential_storage(
+    client_home_directory: &Path,
+    passphrase_file: Option<&Path>,
+) -> anyhow::Result<PersistentStorage> {
+    let db_path = client_home_directory
+        .join(config::DATA_DIR)
+        .join(config::DB_FILE_NAME);
+    if !db_path.exists() {
+        bail!(
+            "there is no credential storage at {} - is the client home directory correct?",
+            db_path.display()
+        )
+    }
+    let storage = match credential_storage::read_passphrase(passphrase_file)? {
+        Some(passphrase) => {
+            PersistentStorage::init_with_passphrase(db_path, passphrase.as_bytes()).await?
+        }
+        None => PersistentStorage::init(db_path).await?,
+    };
+    Ok(storage)
+}
+
+pub(crate) async fn execute(coconut: Coconut) -> anyhow::Result<()> {
+    match coconut.command {
+        Some(CoconutCommands::List(args)) => {
+            let storage = open_credential_storage(
+                &args.client_home_directory,
+                args.passphrase_file.as_deref(),
+            )
+            .await?;
+            nym_cli_commands::coconut::list::list_credentials(args, &storage).await
+        }
+        Some(CoconutCommands::Balance(args)) => {
+            let storage = open_credential_storage(
+                &args.client_home_directory,
+                args.passphrase_file.as_deref(),
+            )
+            .await?;
+            nym_cli_commands::coconut::balance::show_available_bandwidth(args, &storage).await
+        }
+        Some(CoconutCommands::Export(args)) => {
+            let storage = open_credential_storage(
+                &args.client_home_directory,
+                args.passphrase_file.as_deref(),
+            )
+            .await?;
+            nym_cli_commands::coconut::export::export_credentials(args, &storage).await
+        }
+        Some(CoconutCommands::Import(args)) => {
+            let storage = open_credential_storage(
+                &args.client_home_directory,
+                args.passphrase_file.as_deref(),
+            )
+            .await?;
+            nym_cli_commands::coconut::import::import_credentials(args, &storage).await
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
@@ -7,6 +7,7 @@ use logging::setup_logging;
 use nym_cli_commands::context::{get_network_details, ClientArgs};
 use validator_client::nymd::AccountId;
 
+mod coconut;
 mod completion;
 mod validator;
 
@@ -70,6 +71,8 @@ pub(crate) enum Commands {
     VestingSchedule(nym_cli_commands::validator::vesting::VestingSchedule),
     /// Manage your mixnet infrastructure, delegate stake or query the directory
     Mixnet(nym_cli_commands::validator::mixnet::Mixnet),
+    /// Manage the bandwidth credentials stored by your clients
+    Coconut(nym_cli_commands::coconut::Coconut),
     /// Generates shell completion
     GenerateFig,
 }
@@ -113,6 +116,7 @@ async fn execute(cli: Cli) -> anyhow::Result<()> {
         Commands::Mixnet(mixnet) => {
             validator::mixnet::execute(args, mixnet, &network_details).await?
         }
+        Commands::Coconut(coconut) => coconut::execute(coconut).await?,
         Commands::GenerateFig => {
             let mut cmd = Cli::command();
             completion::print_fig(&mut cmd);
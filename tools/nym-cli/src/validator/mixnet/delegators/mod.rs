@@ -23,6 +23,18 @@ pub(crate) async fn execute(
         nym_cli_commands::validator::mixnet::delegators::MixnetDelegatorsCommands::Undelegate(args) => {
             nym_cli_commands::validator::mixnet::delegators::undelegate_from_mixnode::undelegate_from_mixnode(args, create_signing_client(global_args, network_details)?).await
         }
+        nym_cli_commands::validator::mixnet::delegators::MixnetDelegatorsCommands::Redelegate(args) => {
+            nym_cli_commands::validator::mixnet::delegators::redelegate_from_mixnode::redelegate_from_mixnode(args, create_signing_client(global_args, network_details)?).await
+        }
+        nym_cli_commands::validator::mixnet::delegators::MixnetDelegatorsCommands::RedelegateVesting(args) => {
+            nym_cli_commands::validator::mixnet::delegators::vesting_redelegate_from_mixnode::vesting_redelegate_from_mixnode(args, create_signing_client(global_args, network_details)?).await
+        }
+        nym_cli_commands::validator::mixnet::delegators::MixnetDelegatorsCommands::DelegateToGateway(args) => {
+            nym_cli_commands::validator::mixnet::delegators::delegate_to_gateway::delegate_to_gateway(args, create_signing_client(global_args, network_details)?).await
+        }
+        nym_cli_commands::validator::mixnet::delegators::MixnetDelegatorsCommands::UndelegateFromGateway(args) => {
+            nym_cli_commands::validator::mixnet::delegators::undelegate_from_gateway::undelegate_from_gateway(args, create_signing_client(global_args, network_details)?).await
+        }
         nym_cli_commands::validator::mixnet::delegators::MixnetDelegatorsCommands::List(args) => {
             nym_cli_commands::validator::mixnet::delegators::query_for_delegations::execute(args, create_signing_client_with_nym_api(global_args, network_details)?).await
         }
@@ -0,0 +1,7 @@
+export interface GatewayRewardParams {
+  interval_pool_emission: string;
+  nominal_gateways: number;
+  profit_margin: string;
+  epoch_reward_budget: string;
+  stake_saturation_point: string;
+}
@@ -5,4 +5,7 @@ export interface IntervalRewardingParamsUpdate {
   active_set_work_factor: string | null;
   interval_pool_emission: string | null;
   rewarded_set_size: number | null;
+  gateway_interval_pool_emission: string | null;
+  nominal_gateways: number | null;
+  gateway_profit_margin: string | null;
 }
@@ -20,6 +20,7 @@ export * from './GasInfo';
 export * from './Gateway';
 export * from './GatewayBond';
 export * from './GatewayCoreStatusResponse';
+export * from './GatewayRewardParams';
 export * from './InclusionProbabilityResponse';
 export * from './IntervalRewardingParamsUpdate';
 export * from './IntervalRewardParams';